@@ -336,34 +336,7 @@ fn build_operator(
     scheme: opendal::Scheme,
     map: HashMap<String, String>,
 ) -> Result<opendal::Operator, opendal::Error> {
-    use opendal::services::*;
-
-    let op = match scheme {
-        opendal::Scheme::Azblob => opendal::Operator::from_map::<Azblob>(map).unwrap().finish(),
-        opendal::Scheme::Azdfs => opendal::Operator::from_map::<Azdfs>(map).unwrap().finish(),
-        opendal::Scheme::Fs => opendal::Operator::from_map::<Fs>(map).unwrap().finish(),
-        opendal::Scheme::Gcs => opendal::Operator::from_map::<Gcs>(map).unwrap().finish(),
-        opendal::Scheme::Ghac => opendal::Operator::from_map::<Ghac>(map).unwrap().finish(),
-        opendal::Scheme::Http => opendal::Operator::from_map::<Http>(map).unwrap().finish(),
-        opendal::Scheme::Ipmfs => opendal::Operator::from_map::<Ipmfs>(map).unwrap().finish(),
-        opendal::Scheme::Memory => opendal::Operator::from_map::<Memory>(map).unwrap().finish(),
-        opendal::Scheme::Obs => opendal::Operator::from_map::<Obs>(map).unwrap().finish(),
-        opendal::Scheme::Oss => opendal::Operator::from_map::<Oss>(map).unwrap().finish(),
-        opendal::Scheme::S3 => opendal::Operator::from_map::<S3>(map).unwrap().finish(),
-        opendal::Scheme::Webdav => opendal::Operator::from_map::<Webdav>(map).unwrap().finish(),
-        opendal::Scheme::Webhdfs => opendal::Operator::from_map::<Webhdfs>(map)
-            .unwrap()
-            .finish(),
-
-        _ => {
-            return Err(opendal::Error::new(
-                opendal::ErrorKind::Unexpected,
-                "Scheme not supported",
-            ));
-        }
-    };
-
-    Ok(op)
+    opendal::Operator::via_map(scheme, map)
 }
 
 fn convert_map(env: &mut JNIEnv, params: &JObject) -> HashMap<String, String> {
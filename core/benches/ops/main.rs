@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+mod copy;
 mod read;
 mod utils;
 mod write;
@@ -22,5 +23,5 @@ mod write;
 use criterion::criterion_group;
 use criterion::criterion_main;
 
-criterion_group!(benches, read::bench, write::bench);
+criterion_group!(benches, copy::bench, read::bench, write::bench);
 criterion_main!(benches);
@@ -0,0 +1,168 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use futures::TryStreamExt;
+
+use super::*;
+use crate::ops::OpList;
+
+/// Cases for services that meet the following capability:
+///
+/// - read
+/// - write
+/// - list
+pub(super) fn cases() -> Vec<Case> {
+    cases!(
+        test_list_dir,
+        test_list_empty_dir,
+        test_list_non_exist_dir,
+        test_list_nested_dir,
+        test_list_dir_with_file_path,
+        test_list_with_start_after,
+    )
+}
+
+/// List all paths under dir.
+async fn list_paths(op: &Operator, dir: &str) -> Result<Vec<String>> {
+    op.list(dir)
+        .await?
+        .map_ok(|de| de.path().to_string())
+        .try_collect()
+        .await
+}
+
+/// List dir should return newly created file.
+async fn test_list_dir(op: Operator) -> Result<()> {
+    let parent = gen_path();
+    let path = format!("{parent}/{}", gen_path());
+    let content = gen_bytes();
+
+    op.write(&path, content.clone()).await?;
+
+    let mut lister = op.list(&format!("{parent}/")).await?;
+    let mut found = false;
+    while let Some(de) = lister.try_next().await? {
+        if de.path() == path {
+            let meta = op.stat(de.path()).await?;
+            ensure_eq!(meta.mode(), EntryMode::FILE, "mode of listed file");
+            ensure_eq!(
+                meta.content_length(),
+                content.len() as u64,
+                "content length of listed file"
+            );
+
+            found = true
+        }
+    }
+    ensure!(found, "file {path} should be found in list");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// List empty dir should return nothing.
+async fn test_list_empty_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let dir = format!("{}/", gen_path());
+
+    op.create_dir(&dir).await?;
+
+    let paths = list_paths(&op, &dir).await?;
+    ensure!(paths.is_empty(), "empty dir listed {paths:?}");
+
+    op.delete(&dir).await?;
+    Ok(())
+}
+
+/// List non exist dir should return nothing.
+async fn test_list_non_exist_dir(op: Operator) -> Result<()> {
+    let dir = format!("{}/", gen_path());
+
+    let paths = list_paths(&op, &dir).await?;
+    ensure!(paths.is_empty(), "non exist dir listed {paths:?}");
+
+    Ok(())
+}
+
+/// List dir should return its direct children only, including dirs.
+async fn test_list_nested_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let dir = format!("{}/{}/", gen_path(), gen_path());
+    let file_path = format!("{dir}{}", gen_path());
+    let dir_path = format!("{dir}{}/", gen_path());
+    let nested_path = format!("{dir_path}{}", gen_path());
+
+    op.create_dir(&dir).await?;
+    op.write(&file_path, gen_bytes()).await?;
+    op.create_dir(&dir_path).await?;
+    op.write(&nested_path, gen_bytes()).await?;
+
+    let mut paths = list_paths(&op, &dir).await?;
+    paths.sort_unstable();
+    let mut expected = vec![file_path.clone(), dir_path.clone()];
+    expected.sort_unstable();
+    ensure_eq!(paths, expected, "listed paths");
+
+    let meta = op.stat(&dir_path).await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of listed dir");
+
+    op.remove_all(&dir).await?;
+    Ok(())
+}
+
+/// List with file path should return NotADirectory.
+async fn test_list_dir_with_file_path(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    ensure_err!(op.list(&path).await, ErrorKind::NotADirectory);
+
+    Ok(())
+}
+
+/// List with start after should list after the given path in
+/// lexicographical order.
+async fn test_list_with_start_after(op: Operator) -> Result<()> {
+    if !op.info().capability().list_with_start_after {
+        return Ok(());
+    }
+
+    let dir = format!("{}/", gen_path());
+    let given: Vec<String> = (0..6)
+        .map(|i| format!("{dir}file-{i}-{}", gen_path()))
+        .collect();
+    for path in &given {
+        op.write(path, gen_bytes()).await?;
+    }
+
+    let mut lister = op
+        .list_with(&dir, OpList::new().with_start_after(&given[2]))
+        .await?;
+    let mut paths = vec![];
+    while let Some(de) = lister.try_next().await? {
+        paths.push(de.path().to_string());
+    }
+    ensure_eq!(paths, given[3..], "paths listed after start_after");
+
+    op.remove_all(&dir).await?;
+    Ok(())
+}
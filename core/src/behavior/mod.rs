@@ -0,0 +1,269 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Behavior tests that check whether an operator behaves as OpenDAL
+//! documented.
+//!
+//! Authors of custom services can run them against their own
+//! [`Accessor`](crate::raw::Accessor) to make sure it works the same as
+//! services shipped with OpenDAL. Cases are picked by the capabilities
+//! advertised in [`Operator::info`], for example:
+//!
+//! - `read` && `write`: write, read, stat and delete round trips, missing
+//!   paths return [`ErrorKind::NotFound`] and delete is idempotent.
+//! - `read` && !`write`: stat and read against missing paths.
+//! - `list`: newly written files and dirs are listed, missing dirs list
+//!   nothing and `start_after` lists in lexicographical order.
+//! - `copy` and `rename`: content is kept and invalid sources or targets
+//!   are rejected.
+//!
+//! Files will be written under random paths and removed after every case,
+//! please run them against an empty root that is not used by others.
+//!
+//! This module is enabled by the `tests` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use anyhow::Result;
+//! use opendal::behavior;
+//! use opendal::services::Memory;
+//! use opendal::Operator;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     // Replace with the builder of the service to test.
+//!     let op = Operator::new(Memory::default())?.finish();
+//!
+//!     behavior::run(op).await?;
+//!     Ok(())
+//! }
+//! ```
+
+mod copy;
+mod list;
+mod read;
+mod rename;
+mod write;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use log::debug;
+use log::info;
+use log::warn;
+use rand::prelude::*;
+
+use crate::*;
+
+/// A behavior test case with its name.
+type Case = (&'static str, fn(Operator) -> BoxFuture<'static, Result<()>>);
+
+/// Build cases from async functions that take an [`Operator`].
+macro_rules! cases {
+    ($($case:ident),* $(,)?) => {
+        vec![$((
+            stringify!($case),
+            (|op| Box::pin($case(op))) as fn(Operator) -> BoxFuture<'static, Result<()>>,
+        )),*]
+    };
+}
+use cases;
+
+/// Return an [`ErrorKind::Unexpected`] error if the condition is false.
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return Err(Error::new(ErrorKind::Unexpected, &format!($($arg)+)));
+        }
+    };
+}
+use ensure;
+
+/// Return an [`ErrorKind::Unexpected`] error if the two values differ.
+macro_rules! ensure_eq {
+    ($left:expr, $right:expr, $what:expr) => {{
+        let (left, right) = (&$left, &$right);
+        ensure!(
+            left == right,
+            "{} mismatch, expect: {:?}, actual: {:?}",
+            $what,
+            right,
+            left
+        );
+    }};
+}
+use ensure_eq;
+
+/// Return an [`ErrorKind::Unexpected`] error if the result is not an
+/// error of given kind.
+macro_rules! ensure_err {
+    ($res:expr, $kind:expr) => {
+        match $res {
+            Ok(_) => {
+                return Err(Error::new(
+                    ErrorKind::Unexpected,
+                    &format!("expect error {}, but succeeded", $kind),
+                ))
+            }
+            Err(err) => ensure_eq!(err.kind(), $kind, "error kind"),
+        }
+    };
+}
+use ensure_err;
+
+/// Run all behavior tests supported by the capabilities of `op`.
+///
+/// All cases will be run even if some of them failed, and the failed
+/// cases will be returned together as one error with their names and
+/// errors in its context.
+pub async fn run(op: Operator) -> Result<()> {
+    let cap = op.info().capability();
+
+    let mut cases = Vec::new();
+    if cap.read && cap.write {
+        cases.extend(write::cases());
+    } else if cap.read {
+        cases.extend(read::cases());
+    }
+    if cap.read && cap.write && cap.list {
+        cases.extend(list::cases());
+    }
+    if cap.read && cap.write && cap.copy {
+        cases.extend(copy::cases());
+    }
+    if cap.read && cap.write && cap.rename {
+        cases.extend(rename::cases());
+    }
+
+    run_cases(op, cases).await
+}
+
+/// Run given cases one by one and collect all failures.
+async fn run_cases(op: Operator, cases: Vec<Case>) -> Result<()> {
+    let total = cases.len();
+    info!(
+        "behavior tests of service {} started: {} cases",
+        op.info().scheme(),
+        total
+    );
+
+    let mut failed = Vec::new();
+    for (name, case) in cases {
+        debug!("behavior test {name} started");
+        if let Err(err) = case(op.clone()).await {
+            warn!("behavior test {name} failed: {err}");
+            failed.push((name, err));
+        }
+    }
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    let mut err = Error::new(
+        ErrorKind::Unexpected,
+        &format!("{} of {} behavior tests failed", failed.len(), total),
+    )
+    .with_context("service", op.info().scheme());
+    for (name, e) in failed {
+        err = err.with_context("case", format!("{name}: {e}"));
+    }
+    Err(err)
+}
+
+/// Generate a random path under root.
+fn gen_path() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Generate random content in size of `[1, 64KiB]`.
+fn gen_bytes() -> Bytes {
+    let mut rng = thread_rng();
+
+    let mut content = vec![0; rng.gen_range(1..=64 * 1024)];
+    rng.fill_bytes(&mut content);
+    Bytes::from(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services;
+
+    #[tokio::test]
+    async fn test_run_memory() -> Result<()> {
+        let op = Operator::new(services::Memory::default())?.finish();
+
+        run(op).await
+    }
+
+    #[cfg(feature = "services-fs")]
+    #[tokio::test]
+    async fn test_run_fs() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", uuid::Uuid::new_v4()));
+
+        let mut builder = services::Fs::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        run(op).await?;
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_cases_collect_failures() -> Result<()> {
+        async fn pass(_: Operator) -> Result<()> {
+            Ok(())
+        }
+        async fn fail_first(_: Operator) -> Result<()> {
+            ensure!(false, "first failure");
+            Ok(())
+        }
+        async fn fail_second(_: Operator) -> Result<()> {
+            ensure!(false, "second failure");
+            Ok(())
+        }
+
+        let op = Operator::new(services::Memory::default())?.finish();
+        let err = run_cases(op, cases!(fail_first, pass, fail_second))
+            .await
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("2 of 3 behavior tests failed"), "{msg}");
+        assert!(msg.contains("fail_first: Unexpected"), "{msg}");
+        assert!(msg.contains("first failure"), "{msg}");
+        assert!(msg.contains("second failure"), "{msg}");
+        assert!(!msg.contains("pass:"), "{msg}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ensure_eq() -> Result<()> {
+        let err = async {
+            ensure_eq!(1, 2, "number");
+            Ok(())
+        }
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.to_string().contains("expect: 2, actual: 1"));
+
+        Ok(())
+    }
+}
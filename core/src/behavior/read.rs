@@ -0,0 +1,55 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+
+/// Cases for services that meet the following capability:
+///
+/// - read
+/// - !write
+pub(super) fn cases() -> Vec<Case> {
+    cases!(test_stat_root, test_stat_not_exist, test_read_not_exist)
+}
+
+/// Root should be able to stat and returns DIR.
+pub(super) async fn test_stat_root(op: Operator) -> Result<()> {
+    let meta = op.stat("").await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of root");
+
+    let meta = op.stat("/").await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of root");
+
+    Ok(())
+}
+
+/// Stat not exist file should return NotFound.
+pub(super) async fn test_stat_not_exist(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    ensure_err!(op.stat(&path).await, ErrorKind::NotFound);
+
+    Ok(())
+}
+
+/// Read not exist file should return NotFound.
+pub(super) async fn test_read_not_exist(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    ensure_err!(op.read(&path).await, ErrorKind::NotFound);
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+
+/// Cases for services that meet the following capability:
+///
+/// - read
+/// - write
+/// - rename
+pub(super) fn cases() -> Vec<Case> {
+    cases!(
+        test_rename,
+        test_rename_non_existing_source,
+        test_rename_source_dir,
+        test_rename_target_dir,
+        test_rename_self,
+        test_rename_nested,
+        test_rename_overwrite,
+    )
+}
+
+/// Rename a file, the source should be removed and the content kept.
+async fn test_rename(op: Operator) -> Result<()> {
+    let source_path = gen_path();
+    let source_content = gen_bytes();
+
+    op.write(&source_path, source_content.clone()).await?;
+
+    let target_path = gen_path();
+
+    op.rename(&source_path, &target_path).await?;
+
+    ensure_err!(op.stat(&source_path).await, ErrorKind::NotFound);
+    let target_content = op.read(&target_path).await?;
+    ensure_eq!(target_content, source_content, "content of renamed file");
+
+    op.delete(&source_path).await?;
+    op.delete(&target_path).await?;
+    Ok(())
+}
+
+/// Rename a nonexistent source should return NotFound.
+async fn test_rename_non_existing_source(op: Operator) -> Result<()> {
+    let source_path = gen_path();
+    let target_path = gen_path();
+
+    ensure_err!(
+        op.rename(&source_path, &target_path).await,
+        ErrorKind::NotFound
+    );
+
+    Ok(())
+}
+
+/// Rename a dir as source should return IsADirectory.
+async fn test_rename_source_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let source_path = format!("{}/", gen_path());
+    let target_path = gen_path();
+
+    op.create_dir(&source_path).await?;
+
+    ensure_err!(
+        op.rename(&source_path, &target_path).await,
+        ErrorKind::IsADirectory
+    );
+
+    op.delete(&source_path).await?;
+    Ok(())
+}
+
+/// Rename to a dir should return IsADirectory.
+async fn test_rename_target_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let source_path = gen_path();
+    let target_path = format!("{}/", gen_path());
+
+    op.write(&source_path, gen_bytes()).await?;
+    op.create_dir(&target_path).await?;
+
+    ensure_err!(
+        op.rename(&source_path, &target_path).await,
+        ErrorKind::IsADirectory
+    );
+
+    op.delete(&source_path).await?;
+    op.delete(&target_path).await?;
+    Ok(())
+}
+
+/// Rename a file to self should return IsSameFile.
+async fn test_rename_self(op: Operator) -> Result<()> {
+    let source_path = gen_path();
+
+    op.write(&source_path, gen_bytes()).await?;
+
+    ensure_err!(
+        op.rename(&source_path, &source_path).await,
+        ErrorKind::IsSameFile
+    );
+
+    op.delete(&source_path).await?;
+    Ok(())
+}
+
+/// Rename to a nested path, parent path should be created successfully.
+async fn test_rename_nested(op: Operator) -> Result<()> {
+    let source_path = gen_path();
+    let source_content = gen_bytes();
+
+    op.write(&source_path, source_content.clone()).await?;
+
+    let dir = gen_path();
+    let target_path = format!("{dir}/{}/{}", gen_path(), gen_path());
+
+    op.rename(&source_path, &target_path).await?;
+
+    ensure_err!(op.stat(&source_path).await, ErrorKind::NotFound);
+    let target_content = op.read(&target_path).await?;
+    ensure_eq!(target_content, source_content, "content of renamed file");
+
+    op.delete(&source_path).await?;
+    op.remove_all(&format!("{dir}/")).await?;
+    Ok(())
+}
+
+/// Rename to an existing path should overwrite it.
+async fn test_rename_overwrite(op: Operator) -> Result<()> {
+    let source_path = gen_path();
+    let source_content = gen_bytes();
+    let target_path = gen_path();
+
+    op.write(&source_path, source_content.clone()).await?;
+    op.write(&target_path, gen_bytes()).await?;
+
+    op.rename(&source_path, &target_path).await?;
+
+    ensure_err!(op.stat(&source_path).await, ErrorKind::NotFound);
+    let target_content = op.read(&target_path).await?;
+    ensure_eq!(target_content, source_content, "content of renamed file");
+
+    op.delete(&source_path).await?;
+    op.delete(&target_path).await?;
+    Ok(())
+}
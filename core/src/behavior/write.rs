@@ -0,0 +1,275 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::read::*;
+use super::*;
+
+/// Cases for services that meet the following capability:
+///
+/// - read
+/// - write
+pub(super) fn cases() -> Vec<Case> {
+    cases!(
+        test_stat_root,
+        test_stat_not_exist,
+        test_read_not_exist,
+        test_create_dir,
+        test_create_dir_existing,
+        test_write,
+        test_write_overwrite,
+        test_write_with_dir_path,
+        test_writer_write,
+        test_stat,
+        test_stat_dir,
+        test_read_full,
+        test_read_range,
+        test_read_with_dir_path,
+        test_delete,
+        test_delete_not_existing,
+        test_delete_empty_dir,
+    )
+}
+
+/// Create dir with dir path should succeed.
+async fn test_create_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let path = format!("{}/", gen_path());
+
+    op.create_dir(&path).await?;
+
+    let meta = op.stat(&path).await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of created dir");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Create dir on existing dir should succeed.
+async fn test_create_dir_existing(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let path = format!("{}/", gen_path());
+
+    op.create_dir(&path).await?;
+    op.create_dir(&path).await?;
+
+    let meta = op.stat(&path).await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of created dir");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Write a single file and test with stat.
+async fn test_write(op: Operator) -> Result<()> {
+    let path = gen_path();
+    let content = gen_bytes();
+
+    op.write(&path, content.clone()).await?;
+
+    let meta = op.stat(&path).await?;
+    ensure_eq!(
+        meta.content_length(),
+        content.len() as u64,
+        "content length of written file"
+    );
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Write an existing file should overwrite it.
+async fn test_write_overwrite(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    op.write(&path, gen_bytes()).await?;
+    let content = gen_bytes();
+    op.write(&path, content.clone()).await?;
+
+    let bs = op.read(&path).await?;
+    ensure_eq!(bs, content, "content of overwritten file");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Write file with dir path should return IsADirectory.
+async fn test_write_with_dir_path(op: Operator) -> Result<()> {
+    let path = format!("{}/", gen_path());
+
+    ensure_err!(op.write(&path, gen_bytes()).await, ErrorKind::IsADirectory);
+
+    Ok(())
+}
+
+/// Write a file in multiple parts via writer should succeed.
+async fn test_writer_write(op: Operator) -> Result<()> {
+    if !op.info().capability().write_without_content_length {
+        return Ok(());
+    }
+
+    let path = gen_path();
+    let (first, second) = (gen_bytes(), gen_bytes());
+
+    let mut w = op.writer(&path).await?;
+    w.write(first.clone()).await?;
+    w.write(second.clone()).await?;
+    w.close().await?;
+
+    let bs = op.read(&path).await?;
+    ensure_eq!(bs, [first, second].concat(), "content written via writer");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Stat existing file should return metadata.
+async fn test_stat(op: Operator) -> Result<()> {
+    let path = gen_path();
+    let content = gen_bytes();
+
+    op.write(&path, content.clone()).await?;
+
+    let meta = op.stat(&path).await?;
+    ensure_eq!(meta.mode(), EntryMode::FILE, "mode of file");
+    ensure_eq!(
+        meta.content_length(),
+        content.len() as u64,
+        "content length of file"
+    );
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Stat existing dir should return DIR.
+async fn test_stat_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let path = format!("{}/", gen_path());
+
+    op.create_dir(&path).await?;
+
+    let meta = op.stat(&path).await?;
+    ensure_eq!(meta.mode(), EntryMode::DIR, "mode of dir");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Read full content should match.
+async fn test_read_full(op: Operator) -> Result<()> {
+    let path = gen_path();
+    let content = gen_bytes();
+
+    op.write(&path, content.clone()).await?;
+
+    let bs = op.read(&path).await?;
+    ensure_eq!(bs.len(), content.len(), "read size");
+    ensure!(bs == content, "read content mismatch");
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Read range content should match.
+async fn test_read_range(op: Operator) -> Result<()> {
+    if !op.info().capability().read_with_range {
+        return Ok(());
+    }
+
+    let path = gen_path();
+    let content = gen_bytes();
+    let size = content.len() as u64;
+    let offset = thread_rng().gen_range(0..size);
+    let length = thread_rng().gen_range(1..=size - offset);
+
+    op.write(&path, content.clone()).await?;
+
+    let bs = op.range_read(&path, offset..offset + length).await?;
+    ensure_eq!(bs.len() as u64, length, "range read size");
+    ensure!(
+        bs == content[offset as usize..(offset + length) as usize],
+        "range read content mismatch"
+    );
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Read with dir path should return IsADirectory.
+async fn test_read_with_dir_path(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let path = format!("{}/", gen_path());
+
+    op.create_dir(&path).await?;
+
+    ensure_err!(op.read(&path).await, ErrorKind::IsADirectory);
+
+    op.delete(&path).await?;
+    Ok(())
+}
+
+/// Delete existing file should succeed.
+async fn test_delete(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    op.write(&path, gen_bytes()).await?;
+
+    op.delete(&path).await?;
+
+    ensure!(!op.is_exist(&path).await?, "deleted file still exists");
+    Ok(())
+}
+
+/// Delete not existing file should also succeed, so delete is idempotent.
+async fn test_delete_not_existing(op: Operator) -> Result<()> {
+    let path = gen_path();
+
+    op.delete(&path).await?;
+
+    op.write(&path, gen_bytes()).await?;
+    op.delete(&path).await?;
+    op.delete(&path).await?;
+
+    Ok(())
+}
+
+/// Delete empty dir should succeed.
+async fn test_delete_empty_dir(op: Operator) -> Result<()> {
+    if !op.info().capability().create_dir {
+        return Ok(());
+    }
+
+    let path = format!("{}/", gen_path());
+
+    op.create_dir(&path).await?;
+
+    op.delete(&path).await?;
+
+    Ok(())
+}
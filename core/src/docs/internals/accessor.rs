@@ -247,6 +247,7 @@
 //! impl Builder for DuckBuilder {
 //!     const SCHEME: Scheme = Scheme::Duck;
 //!     type Accessor = DuckBackend;
+//!     const CONFIG_KEYS: &'static [&'static str] = &["root"];
 //!
 //!     fn from_map(map: HashMap<String, String>) -> Self {
 //!         let mut builder = DuckBuilder::default();
@@ -16,12 +16,15 @@
 // under the License.
 
 use std::io;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::FutureExt;
 use rand::prelude::*;
 use rand::rngs::StdRng;
 
@@ -41,10 +44,27 @@ use crate::*;
 /// For example: If we specify an error rate of 0.5, there is a 50% chance
 /// of an EOF error for every read operation.
 ///
+/// ChaosLayer can also:
+///
+/// - delay operations by a fixed or random duration via
+///   [`ChaosLayer::with_delay`] and [`ChaosLayer::with_random_delay`].
+/// - fail operations with the given [`ErrorKind`] via
+///   [`ChaosLayer::with_operation_error`].
+/// - be reproducible by a seed via [`ChaosLayer::with_seed`].
+///
 /// # Note
 ///
-/// For now, ChaosLayer only injects read operations. More operations may
-/// be added in the future.
+/// - Errors are injected into reading body only by the error ratio passed
+///   to [`ChaosLayer::new`], it works like dropping connections while
+///   reading.
+/// - Delays and operation errors are injected into `create_dir`, `read`,
+///   `write`, `copy`, `rename`, `stat`, `delete`, `list`, `batch` and their
+///   blocking versions. Other operations are passed through.
+/// - All injected errors are temporary, so they will be retried by
+///   [`RetryLayer`](crate::layers::RetryLayer).
+/// - With the same seed, operations issued in the same order will get the
+///   same chaos. Operations issued concurrently could take random numbers
+///   in different orders.
 ///
 /// # Examples
 ///
@@ -60,9 +80,34 @@ use crate::*;
 ///     .layer(ChaosLayer::new(0.1))
 ///     .finish();
 /// ```
+///
+/// Fail 10% operations with `Unexpected` after 10ms to 100ms delay:
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use opendal::layers::ChaosLayer;
+/// use opendal::services;
+/// use opendal::ErrorKind;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(
+///         ChaosLayer::new(0.0)
+///             .with_seed(42)
+///             .with_random_delay(Duration::from_millis(10)..=Duration::from_millis(100))
+///             .with_operation_error(0.1, ErrorKind::Unexpected),
+///     )
+///     .finish();
+/// ```
 #[derive(Debug, Clone)]
 pub struct ChaosLayer {
     error_ratio: f64,
+    operation_error_ratio: f64,
+    operation_error_kind: ErrorKind,
+    delay: Option<RangeInclusive<Duration>>,
+    seed: Option<u64>,
 }
 
 impl ChaosLayer {
@@ -76,7 +121,48 @@ impl ChaosLayer {
             (0.0..=1.0).contains(&error_ratio),
             "error_ratio must between 0.0 and 1.0"
         );
-        Self { error_ratio }
+        Self {
+            error_ratio,
+            operation_error_ratio: 0.0,
+            operation_error_kind: ErrorKind::Unexpected,
+            delay: None,
+            seed: None,
+        }
+    }
+
+    /// Set the seed of chaos, so that the chaos can be reproduced.
+    ///
+    /// Chaos will be seeded from entropy if not set.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Delay every operation by the given duration.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay..=delay);
+        self
+    }
+
+    /// Delay every operation by a random duration in the given range.
+    pub fn with_random_delay(mut self, delay: RangeInclusive<Duration>) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Fail operations with `kind` by the given error ratio.
+    ///
+    /// # Panics
+    ///
+    /// Input error_ratio must in [0.0..=1.0]
+    pub fn with_operation_error(mut self, error_ratio: f64, kind: ErrorKind) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&error_ratio),
+            "error_ratio must between 0.0 and 1.0"
+        );
+        self.operation_error_ratio = error_ratio;
+        self.operation_error_kind = kind;
+        self
     }
 }
 
@@ -84,10 +170,18 @@ impl<A: Accessor> Layer<A> for ChaosLayer {
     type LayeredAccessor = ChaosAccessor<A>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
         ChaosAccessor {
             inner,
-            rng: StdRng::from_entropy(),
+            rng: Arc::new(Mutex::new(rng)),
             error_ratio: self.error_ratio,
+            operation_error_ratio: self.operation_error_ratio,
+            operation_error_kind: self.operation_error_kind,
+            delay: self.delay.clone(),
         }
     }
 }
@@ -95,9 +189,61 @@ impl<A: Accessor> Layer<A> for ChaosLayer {
 #[derive(Debug)]
 pub struct ChaosAccessor<A> {
     inner: A,
-    rng: StdRng,
+    rng: Arc<Mutex<StdRng>>,
 
     error_ratio: f64,
+    operation_error_ratio: f64,
+    operation_error_kind: ErrorKind,
+    delay: Option<RangeInclusive<Duration>>,
+}
+
+impl<A> ChaosAccessor<A> {
+    /// Roll the dice for given operation, returns the delay to wait and
+    /// whether this operation should fail.
+    fn roll(&self, op: Operation) -> (Option<Duration>, Result<()>) {
+        let mut rng = self.rng.lock().expect("lock must be valid");
+
+        let delay = self.delay.clone().map(|v| {
+            if v.start() >= v.end() {
+                *v.start()
+            } else {
+                rng.gen_range(v)
+            }
+        });
+
+        let res = if rng.gen_bool(self.operation_error_ratio) {
+            Err(Error::new(self.operation_error_kind, "I am your chaos!")
+                .with_operation(op)
+                .set_temporary())
+        } else {
+            Ok(())
+        };
+
+        (delay, res)
+    }
+
+    async fn inject(&self, op: Operation) -> Result<()> {
+        let (delay, res) = self.roll(op);
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        res
+    }
+
+    fn blocking_inject(&self, op: Operation) -> Result<()> {
+        let (delay, res) = self.roll(op);
+        if let Some(delay) = delay {
+            std::thread::sleep(delay);
+        }
+        res
+    }
+
+    /// Every reader will take its own rng seeded by ours, so that reading
+    /// different files won't affect each other.
+    fn new_reader<R>(&self, r: R) -> ChaosReader<R> {
+        let seed = self.rng.lock().expect("lock must be valid").gen();
+        ChaosReader::new(r, StdRng::seed_from_u64(seed), self.error_ratio)
+    }
 }
 
 #[async_trait]
@@ -114,32 +260,93 @@ impl<A: Accessor> LayeredAccessor for ChaosAccessor<A> {
         &self.inner
     }
 
+    async fn create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
+        self.inject(Operation::CreateDir).await?;
+        self.inner.create_dir(path, args).await
+    }
+
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inject(Operation::Read).await?;
         self.inner
             .read(path, args)
-            .map(|v| v.map(|(rp, r)| (rp, ChaosReader::new(r, self.rng.clone(), self.error_ratio))))
             .await
+            .map(|(rp, r)| (rp, self.new_reader(r)))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inject(Operation::Write).await?;
+        self.inner.write(path, args).await
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inject(Operation::Copy).await?;
+        self.inner.copy(from, to, args).await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inject(Operation::Rename).await?;
+        self.inner.rename(from, to, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inject(Operation::Stat).await?;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.inject(Operation::Delete).await?;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inject(Operation::List).await?;
+        self.inner.list(path, args).await
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.inject(Operation::Batch).await?;
+        self.inner.batch(args).await
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
+        self.blocking_inject(Operation::BlockingCreateDir)?;
+        self.inner.blocking_create_dir(path, args)
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.blocking_inject(Operation::BlockingRead)?;
         self.inner
             .blocking_read(path, args)
-            .map(|(rp, r)| (rp, ChaosReader::new(r, self.rng.clone(), self.error_ratio)))
-    }
-
-    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        self.inner.write(path, args).await
+            .map(|(rp, r)| (rp, self.new_reader(r)))
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.blocking_inject(Operation::BlockingWrite)?;
         self.inner.blocking_write(path, args)
     }
 
-    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        self.inner.list(path, args).await
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.blocking_inject(Operation::BlockingCopy)?;
+        self.inner.blocking_copy(from, to, args)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.blocking_inject(Operation::BlockingRename)?;
+        self.inner.blocking_rename(from, to, args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.blocking_inject(Operation::BlockingStat)?;
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.blocking_inject(Operation::BlockingDelete)?;
+        self.inner.blocking_delete(path, args)
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.blocking_inject(Operation::BlockingList)?;
         self.inner.blocking_list(path, args)
     }
 }
@@ -226,3 +433,48 @@ impl<R: oio::BlockingRead> oio::BlockingRead for ChaosReader<R> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::Memory;
+
+    async fn stat_results(seed: u64) -> Vec<ErrorKind> {
+        let op = Operator::new(Memory::default())
+            .expect("must init")
+            .layer(
+                ChaosLayer::new(0.0)
+                    .with_seed(seed)
+                    .with_operation_error(0.5, ErrorKind::RateLimited),
+            )
+            .finish();
+
+        let mut kinds = Vec::new();
+        for _ in 0..64 {
+            let err = op.stat("not_exist").await.expect_err("stat must fail");
+            kinds.push(err.kind());
+        }
+        kinds
+    }
+
+    #[tokio::test]
+    async fn test_operation_error_with_seed() {
+        let kinds = stat_results(42).await;
+        assert!(kinds.contains(&ErrorKind::RateLimited));
+        assert!(kinds.contains(&ErrorKind::NotFound));
+
+        assert_eq!(kinds, stat_results(42).await);
+    }
+
+    #[tokio::test]
+    async fn test_delay() {
+        let op = Operator::new(Memory::default())
+            .expect("must init")
+            .layer(ChaosLayer::new(0.0).with_delay(Duration::from_millis(50)))
+            .finish();
+
+        let now = std::time::Instant::now();
+        let _ = op.stat("not_exist").await;
+        assert!(now.elapsed() >= Duration::from_millis(50));
+    }
+}
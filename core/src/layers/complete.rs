@@ -157,6 +157,19 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
         let streamable = capability.read_can_next;
 
         let range = args.range();
+        // Following reads of range reader must visit the same snapshot
+        // or generation.
+        let (mut op, mut op_stat) = match args.snapshot() {
+            Some(v) => (
+                OpRead::new().with_snapshot(v),
+                OpStat::new().with_snapshot(v),
+            ),
+            None => (OpRead::new(), OpStat::new()),
+        };
+        if let Some(v) = args.generation() {
+            op = op.with_generation(v);
+            op_stat = op_stat.with_generation(v);
+        }
         let (rp, r) = self.inner.read(path, args).await?;
         let content_length = rp.metadata().content_length();
 
@@ -171,10 +184,18 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
                     (Some(offset), _) => (offset, content_length),
                     (None, None) => (0, content_length),
                     (None, Some(size)) => {
-                        // TODO: we can read content range to calculate
-                        // the total content length.
-                        let om = self.inner.stat(path, OpStat::new()).await?.into_metadata();
-                        let total_size = om.content_length();
+                        // Reuse the total size in content range if the
+                        // service has resolved the suffix range already.
+                        let total_size = match rp.metadata().content_range().and_then(|v| v.size())
+                        {
+                            Some(v) => v,
+                            None => self
+                                .inner
+                                .stat(path, op_stat)
+                                .await?
+                                .into_metadata()
+                                .content_length(),
+                        };
                         let (offset, size) = if size > total_size {
                             (0, total_size)
                         } else {
@@ -184,7 +205,7 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
                         (offset, size)
                     }
                 };
-                let r = oio::into_reader::by_range(self.inner.clone(), path, r, offset, size);
+                let r = oio::into_reader::by_range(self.inner.clone(), path, op, r, offset, size);
 
                 if streamable {
                     Ok((rp, CompleteReader::NeedSeekable(r)))
@@ -220,6 +241,192 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
         }
     }
 
+    fn check_write_append(&self, args: &OpWrite) -> Result<()> {
+        if args.append() && !self.meta.capability().write_with_append {
+            return Err(
+                Error::new(ErrorKind::Unsupported, "write with append is not supported")
+                    .with_context("service", self.meta.scheme())
+                    .with_operation("write"),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn check_write_resume(&self, args: &OpWrite) -> Result<()> {
+        if args.resume().is_some() && !self.meta.capability().write_can_resume {
+            return Err(
+                Error::new(ErrorKind::Unsupported, "write with resume is not supported")
+                    .with_context("service", self.meta.scheme())
+                    .with_operation("write"),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn check_write_if_none_match(&self, args: &OpWrite) -> Result<()> {
+        if args.if_none_match().is_some() && !self.meta.capability().write_with_if_none_match {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "write with if none match is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("write"));
+        }
+
+        Ok(())
+    }
+
+    fn check_write_storage_class(&self, args: &OpWrite) -> Result<()> {
+        if args.storage_class().is_some() && !self.meta.capability().write_with_storage_class {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "write with storage class is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("write"));
+        }
+
+        Ok(())
+    }
+
+    fn check_write_expires(&self, args: &OpWrite) -> Result<()> {
+        if args.expires().is_some() && !self.meta.capability().write_with_expires {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "write with expires is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("write"));
+        }
+
+        Ok(())
+    }
+
+    fn check_write_object_lock(&self, args: &OpWrite) -> Result<()> {
+        if (args.object_lock_retention().is_some() || args.object_lock_legal_hold().is_some())
+            && !self.meta.capability().write_with_object_lock
+        {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "write with object lock is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("write"));
+        }
+
+        Ok(())
+    }
+
+    fn check_write_if_generation_match(&self, args: &OpWrite) -> Result<()> {
+        if (args.if_generation_match().is_some() || args.if_metageneration_match().is_some())
+            && !self.meta.capability().write_with_if_generation_match
+        {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "write with if generation match is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("write"));
+        }
+
+        Ok(())
+    }
+
+    fn check_read_generation(&self, args: &OpRead) -> Result<()> {
+        if args.generation().is_some() && !self.meta.capability().read_with_generation {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "read with generation is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("read"));
+        }
+
+        Ok(())
+    }
+
+    fn check_stat_generation(&self, args: &OpStat) -> Result<()> {
+        if args.generation().is_some() && !self.meta.capability().stat_with_generation {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "stat with generation is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("stat"));
+        }
+
+        Ok(())
+    }
+
+    fn check_delete_if_generation_match(&self, args: &OpDelete) -> Result<()> {
+        if (args.if_generation_match().is_some() || args.if_metageneration_match().is_some())
+            && !self.meta.capability().delete_with_if_generation_match
+        {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "delete with if generation match is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("delete"));
+        }
+
+        Ok(())
+    }
+
+    fn check_read_snapshot(&self, args: &OpRead) -> Result<()> {
+        if args.snapshot().is_some() && !self.meta.capability().read_with_snapshot {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "read with snapshot is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("read"));
+        }
+
+        Ok(())
+    }
+
+    fn check_stat_snapshot(&self, args: &OpStat) -> Result<()> {
+        if args.snapshot().is_some() && !self.meta.capability().stat_with_snapshot {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "stat with snapshot is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("stat"));
+        }
+
+        Ok(())
+    }
+
+    fn check_delete_snapshots(&self, args: &OpDelete) -> Result<()> {
+        if args.delete_snapshots().is_some() && !self.meta.capability().delete_with_snapshots {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "delete with snapshots is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("delete"));
+        }
+
+        Ok(())
+    }
+
+    fn check_list_snapshots(&self, args: &OpList) -> Result<()> {
+        if args.include_snapshots() && !self.meta.capability().list_with_snapshots {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "list with snapshots is not supported",
+            )
+            .with_context("service", self.meta.scheme())
+            .with_operation("list"));
+        }
+
+        Ok(())
+    }
+
     async fn complete_list(
         &self,
         path: &str,
@@ -233,6 +440,7 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
                     .with_operation("list"),
             );
         }
+        self.check_list_snapshots(&args)?;
 
         let delimiter = args.delimiter();
 
@@ -282,6 +490,7 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
                     .with_operation("list"),
             );
         }
+        self.check_list_snapshots(&args)?;
 
         let delimiter = args.delimiter();
 
@@ -335,14 +544,23 @@ impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.check_read_snapshot(&args)?;
+        self.check_read_generation(&args)?;
+
         self.complete_reader(path, args).await
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.check_read_snapshot(&args)?;
+        self.check_read_generation(&args)?;
+
         self.complete_blocking_reader(path, args)
     }
 
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.check_stat_snapshot(&args)?;
+        self.check_stat_generation(&args)?;
+
         self.inner.stat(path, args).await.map(|v| {
             v.map_metadata(|m| {
                 let bit = m.bit();
@@ -352,6 +570,9 @@ impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     }
 
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.check_stat_snapshot(&args)?;
+        self.check_stat_generation(&args)?;
+
         self.inner.blocking_stat(path, args).map(|v| {
             v.map_metadata(|m| {
                 let bit = m.bit();
@@ -361,6 +582,14 @@ impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.check_write_append(&args)?;
+        self.check_write_resume(&args)?;
+        self.check_write_if_none_match(&args)?;
+        self.check_write_storage_class(&args)?;
+        self.check_write_expires(&args)?;
+        self.check_write_if_generation_match(&args)?;
+        self.check_write_object_lock(&args)?;
+
         let size = args.content_length();
         self.inner
             .write(path, args)
@@ -369,12 +598,34 @@ impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.check_write_append(&args)?;
+        self.check_write_resume(&args)?;
+        self.check_write_if_none_match(&args)?;
+        self.check_write_storage_class(&args)?;
+        self.check_write_expires(&args)?;
+        self.check_write_if_generation_match(&args)?;
+        self.check_write_object_lock(&args)?;
+
         let size = args.content_length();
         self.inner
             .blocking_write(path, args)
             .map(|(rp, w)| (rp, CompleteWriter::new(w, size)))
     }
 
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.check_delete_snapshots(&args)?;
+        self.check_delete_if_generation_match(&args)?;
+
+        self.inner.delete(path, args).await
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.check_delete_snapshots(&args)?;
+        self.check_delete_if_generation_match(&args)?;
+
+        self.inner.blocking_delete(path, args)
+    }
+
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         self.complete_list(path, args).await
     }
@@ -594,6 +845,18 @@ where
 
         Ok(())
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        let w = self.inner.as_mut().ok_or_else(|| {
+            Error::new(ErrorKind::Unexpected, "writer has been closed or aborted")
+        })?;
+
+        w.flush().await
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.as_ref().and_then(|w| w.resume())
+    }
 }
 
 impl<W> oio::BlockingWrite for CompleteWriter<W>
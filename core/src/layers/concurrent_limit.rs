@@ -297,6 +297,14 @@ impl<R: oio::Write> oio::Write for ConcurrentLimitWrapper<R> {
     async fn close(&mut self) -> Result<()> {
         self.inner.close().await
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for ConcurrentLimitWrapper<R> {
@@ -213,6 +213,66 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
         })
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        self.inner.restore(path, args).await.map_err(|err| {
+            err.with_operation(Operation::Restore)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        self.inner.compose(path, args).await.map_err(|err| {
+            err.with_operation(Operation::Compose)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        self.inner.get_acl(path, args).await.map_err(|err| {
+            err.with_operation(Operation::GetAcl)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        self.inner.create_symlink(path, args).await.map_err(|err| {
+            err.with_operation(Operation::CreateSymlink)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        self.inner.truncate(path, args).await.map_err(|err| {
+            err.with_operation(Operation::Truncate)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        self.inner.set_permissions(path, args).await.map_err(|err| {
+            err.with_operation(Operation::SetPermissions)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        self.inner.set_owner(path, args).await.map_err(|err| {
+            err.with_operation(Operation::SetOwner)
+                .with_context("service", self.meta.scheme())
+                .with_context("path", path)
+        })
+    }
+
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
         self.inner
             .batch(args)
@@ -427,6 +487,18 @@ impl<T: oio::Write> oio::Write for ErrorContextWrapper<T> {
                 .with_context("path", &self.path)
         })
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await.map_err(|err| {
+            err.with_operation(WriteOperation::Flush)
+                .with_context("service", self.scheme)
+                .with_context("path", &self.path)
+        })
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<T: oio::BlockingWrite> oio::BlockingWrite for ErrorContextWrapper<T> {
@@ -1357,6 +1357,40 @@ impl<W: oio::Write> oio::Write for LoggingWriter<W> {
             }
         }
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        match self.inner.flush().await {
+            Ok(_) => {
+                trace!(
+                    target: LOGGING_TARGET,
+                    "service={} operation={} path={} written={} -> data flushed",
+                    self.scheme,
+                    WriteOperation::Flush,
+                    self.path,
+                    self.written,
+                );
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(lvl) = self.failure_level {
+                    log!(
+                        target: LOGGING_TARGET,
+                        lvl,
+                        "service={} operation={} path={} written={} -> data flush failed: {err:?}",
+                        self.scheme,
+                        WriteOperation::Flush,
+                        self.path,
+                        self.written,
+                    )
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<W: oio::BlockingWrite> oio::BlockingWrite for LoggingWriter<W> {
@@ -0,0 +1,367 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+type MapPathFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Transform paths between operator and underlying storage services.
+///
+/// # Notes
+///
+/// `forward` maps the path passed by users to the key stored in services,
+/// and `reverse` maps keys returned by services (like listing results)
+/// back. They must be the inverse of each other.
+///
+/// Paths passed to both functions are relative to the root of operator,
+/// like `dir/file` or `dir/`, while the root itself is `/`. Trailing `/`
+/// of dirs must be kept after transformation.
+///
+/// All paths are transformed, including the targets of copy, rename and
+/// symlinks, so presigned requests will carry the transformed path too.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::MapPathLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(MapPathLayer::new(
+///         |p| format!("data/{}", p.trim_start_matches('/')),
+///         |p| p.trim_start_matches("data/").to_string(),
+///     ))
+///     .finish();
+/// ```
+#[derive(Clone)]
+pub struct MapPathLayer {
+    forward: MapPathFn,
+    reverse: MapPathFn,
+}
+
+impl MapPathLayer {
+    /// Create a new MapPathLayer with given forward and reverse functions.
+    pub fn new(
+        forward: impl Fn(&str) -> String + Send + Sync + 'static,
+        reverse: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            forward: Arc::new(forward),
+            reverse: Arc::new(reverse),
+        }
+    }
+}
+
+impl<A: Accessor> Layer<A> for MapPathLayer {
+    type LayeredAccessor = MapPathAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        MapPathAccessor {
+            inner,
+            forward: self.forward.clone(),
+            reverse: self.reverse.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MapPathAccessor<A: Accessor> {
+    inner: A,
+    forward: MapPathFn,
+    reverse: MapPathFn,
+}
+
+impl<A: Accessor> Debug for MapPathAccessor<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapPathAccessor")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Accessor> MapPathAccessor<A> {
+    /// Map the symlink target returned by services back.
+    fn reverse_metadata(&self, meta: Metadata) -> Metadata {
+        if !meta.bit().contains(Metakey::SymlinkTarget) {
+            return meta;
+        }
+
+        match meta.symlink_target() {
+            Some(target) => {
+                let target = (self.reverse)(target);
+                meta.with_symlink_target(target)
+            }
+            None => meta,
+        }
+    }
+
+    fn forward_batch(&self, args: OpBatch) -> OpBatch {
+        OpBatch::new(
+            args.into_operation()
+                .into_iter()
+                .map(|(path, op)| ((self.forward)(&path), op))
+                .collect(),
+        )
+    }
+
+    fn reverse_batch(&self, rp: RpBatch) -> RpBatch {
+        RpBatch::new(
+            rp.into_results()
+                .into_iter()
+                .map(|(path, res)| ((self.reverse)(&path), res))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for MapPathAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = MapPathPager<A::Pager>;
+    type BlockingPager = MapPathPager<A::BlockingPager>;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
+        self.inner.create_dir(&(self.forward)(path), args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(&(self.forward)(path), args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(&(self.forward)(path), args).await
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inner
+            .copy(&(self.forward)(from), &(self.forward)(to), args)
+            .await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inner
+            .rename(&(self.forward)(from), &(self.forward)(to), args)
+            .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner
+            .stat(&(self.forward)(path), args)
+            .await
+            .map(|rp| rp.map_metadata(|m| self.reverse_metadata(m)))
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.inner.delete(&(self.forward)(path), args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner
+            .list(&(self.forward)(path), args)
+            .await
+            .map(|(rp, p)| (rp, MapPathPager::new(p, self.reverse.clone())))
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.inner
+            .batch(self.forward_batch(args))
+            .await
+            .map(|rp| self.reverse_batch(rp))
+    }
+
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        self.inner.restore(&(self.forward)(path), args).await
+    }
+
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        let args = args.map_sources(|v| (self.forward)(v));
+        self.inner.compose(&(self.forward)(path), args).await
+    }
+
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        self.inner.get_acl(&(self.forward)(path), args).await
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        let args = OpCreateSymlink::new(&(self.forward)(args.target()));
+        self.inner.create_symlink(&(self.forward)(path), args).await
+    }
+
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        self.inner.truncate(&(self.forward)(path), args).await
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        self.inner
+            .set_permissions(&(self.forward)(path), args)
+            .await
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        self.inner.set_owner(&(self.forward)(path), args).await
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.inner.presign(&(self.forward)(path), args).await
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
+        self.inner.blocking_create_dir(&(self.forward)(path), args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(&(self.forward)(path), args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(&(self.forward)(path), args)
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inner
+            .blocking_copy(&(self.forward)(from), &(self.forward)(to), args)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inner
+            .blocking_rename(&(self.forward)(from), &(self.forward)(to), args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner
+            .blocking_stat(&(self.forward)(path), args)
+            .map(|rp| rp.map_metadata(|m| self.reverse_metadata(m)))
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.inner.blocking_delete(&(self.forward)(path), args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner
+            .blocking_list(&(self.forward)(path), args)
+            .map(|(rp, p)| (rp, MapPathPager::new(p, self.reverse.clone())))
+    }
+}
+
+pub struct MapPathPager<P> {
+    inner: P,
+    reverse: MapPathFn,
+}
+
+impl<P> MapPathPager<P> {
+    fn new(inner: P, reverse: MapPathFn) -> Self {
+        Self { inner, reverse }
+    }
+
+    fn reverse_entries(&self, entries: Option<Vec<oio::Entry>>) -> Option<Vec<oio::Entry>> {
+        entries.map(|mut entries| {
+            for de in entries.iter_mut() {
+                let path = (self.reverse)(de.path());
+                de.set_path(&path);
+            }
+            entries
+        })
+    }
+}
+
+#[async_trait]
+impl<P: oio::Page> oio::Page for MapPathPager<P> {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let entries = self.inner.next().await?;
+        Ok(self.reverse_entries(entries))
+    }
+}
+
+impl<P: oio::BlockingPage> oio::BlockingPage for MapPathPager<P> {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let entries = self.inner.next()?;
+        Ok(self.reverse_entries(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::Memory;
+
+    fn prefix_layer() -> MapPathLayer {
+        MapPathLayer::new(
+            |p| {
+                if p == "/" {
+                    "data/".to_string()
+                } else {
+                    format!("data/{p}")
+                }
+            },
+            |p| match p.strip_prefix("data/") {
+                Some("") => "/".to_string(),
+                Some(v) => v.to_string(),
+                None => p.to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_map_path() -> Result<()> {
+        let raw = Operator::new(Memory::default())?.finish();
+        let op = raw.clone().layer(prefix_layer());
+
+        op.write("dir/file", "Hello, World!").await?;
+        assert_eq!(raw.read("data/dir/file").await?, b"Hello, World!");
+        assert_eq!(op.read("dir/file").await?, b"Hello, World!");
+        assert_eq!(op.stat("dir/file").await?.content_length(), 13);
+
+        let entries: Vec<_> = op.scan("/").await?.try_collect().await?;
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert!(paths.contains(&"dir/file"), "got {paths:?}");
+
+        let entries: Vec<_> = op.list("dir/").await?.try_collect().await?;
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert_eq!(paths, ["dir/file"]);
+
+        op.delete("dir/file").await?;
+        assert!(!raw.is_exist("data/dir/file").await?);
+        Ok(())
+    }
+}
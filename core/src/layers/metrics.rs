@@ -873,6 +873,17 @@ impl<R: oio::Write> oio::Write for MetricWrapper<R> {
             err
         })
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await.map_err(|err| {
+            self.handle.increment_errors_total(self.op, err.kind());
+            err
+        })
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for MetricWrapper<R> {
@@ -338,6 +338,20 @@ impl<R: oio::Write> oio::Write for MinitraceWrapper<R> {
             ))
             .await
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner
+            .flush()
+            .in_span(Span::enter_with_parent(
+                WriteOperation::Flush.into_static(),
+                &self.span,
+            ))
+            .await
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for MinitraceWrapper<R> {
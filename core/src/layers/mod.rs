@@ -26,6 +26,12 @@ pub use immutable_index::ImmutableIndexLayer;
 mod logging;
 pub use logging::LoggingLayer;
 
+mod map_path;
+pub use map_path::MapPathLayer;
+
+mod overlay;
+pub use overlay::OverlayLayer;
+
 #[cfg(feature = "layers-chaos")]
 mod chaos;
 #[cfg(feature = "layers-chaos")]
@@ -41,6 +47,11 @@ mod prometheus;
 #[cfg(feature = "layers-prometheus")]
 pub use self::prometheus::PrometheusLayer;
 
+#[cfg(feature = "layers-moka-cache")]
+mod moka_cache;
+#[cfg(feature = "layers-moka-cache")]
+pub use self::moka_cache::MokaCacheLayer;
+
 mod retry;
 pub use self::retry::RetryLayer;
 
@@ -0,0 +1,431 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use bytes::BytesMut;
+use moka::future::Cache;
+
+use crate::ops::*;
+use crate::raw::oio::ReadExt;
+use crate::raw::*;
+use crate::*;
+
+/// Cache the content of small objects in memory with [moka](https://github.com/moka-rs/moka).
+///
+/// # Notes
+///
+/// - Entries are keyed by path and etag, so objects without etag will
+///   never be cached. Every read will issue a `stat` to fetch the latest
+///   etag, stale entries will be evicted by moka eventually.
+/// - Only reads with nothing but a range will be cached. Reads with other
+///   args like conditions, generation, snapshot or response overrides
+///   will be passed to underlying service directly.
+/// - Entries are weighed by their byte length, so `max_capacity` is the
+///   total bytes that this cache can hold.
+/// - Objects with `Cache-Control: no-store` will not be cached.
+/// - Only async read will be cached, blocking read will be passed to
+///   underlying service directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::MokaCacheLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(
+///         MokaCacheLayer::new(64 * 1024 * 1024).with_time_to_live(Duration::from_secs(60)),
+///     )
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MokaCacheLayer {
+    max_capacity: u64,
+    time_to_live: Option<Duration>,
+}
+
+impl MokaCacheLayer {
+    /// Create a new moka cache layer which holds at most `max_capacity`
+    /// bytes of content.
+    pub fn new(max_capacity: u64) -> Self {
+        Self {
+            max_capacity,
+            time_to_live: None,
+        }
+    }
+
+    /// Set the time to live of cached entries.
+    pub fn with_time_to_live(mut self, ttl: Duration) -> Self {
+        self.time_to_live = Some(ttl);
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for MokaCacheLayer {
+    type LayeredAccessor = MokaCacheAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let mut builder = Cache::builder()
+            .max_capacity(self.max_capacity)
+            // Use content's bytes as capacity weigher.
+            .weigher(|k: &CacheKey, v: &Bytes| {
+                (k.0.len() + k.1.len() + v.len())
+                    .try_into()
+                    .unwrap_or(u32::MAX)
+            });
+        if let Some(v) = self.time_to_live {
+            builder = builder.time_to_live(v);
+        }
+
+        MokaCacheAccessor {
+            inner,
+            cache: builder.build(),
+            max_capacity: self.max_capacity,
+        }
+    }
+}
+
+/// CacheKey is the `(path, etag)` pair of cached content.
+type CacheKey = (String, String);
+
+pub struct MokaCacheAccessor<A: Accessor> {
+    inner: A,
+    cache: Cache<CacheKey, Bytes>,
+    max_capacity: u64,
+}
+
+impl<A: Accessor> Debug for MokaCacheAccessor<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MokaCacheAccessor")
+            .field("inner", &self.inner)
+            .field("max_capacity", &self.max_capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Accessor> MokaCacheAccessor<A> {
+    /// Check if the object described by this metadata can be cached.
+    fn cacheable(&self, meta: &Metadata) -> bool {
+        if meta.etag().is_none() {
+            return false;
+        }
+        if meta.content_length() > self.max_capacity {
+            return false;
+        }
+        match meta.cache_control() {
+            Some(v) => !v
+                .split(',')
+                .any(|d| d.trim().eq_ignore_ascii_case("no-store")),
+            None => true,
+        }
+    }
+
+    async fn read_all(&self, path: &str) -> Result<Bytes> {
+        let (_, mut r) = self.inner.read(path, OpRead::new()).await?;
+
+        let mut buf = BytesMut::new();
+        while let Some(bs) = r.next().await {
+            buf.extend_from_slice(&bs?);
+        }
+        Ok(buf.freeze())
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for MokaCacheAccessor<A> {
+    type Inner = A;
+    type Reader = MokaCacheReader<A::Reader>;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        // Cached content is the current object, reads with other args must
+        // be handled by underlying services.
+        if !args.is_range_only() {
+            let (rp, r) = self.inner.read(path, args).await?;
+            return Ok((rp, MokaCacheReader::Inner(r)));
+        }
+
+        let meta = self.inner.stat(path, OpStat::new()).await?.into_metadata();
+        if !meta.mode().is_file() || !self.cacheable(&meta) {
+            let (rp, r) = self.inner.read(path, args).await?;
+            return Ok((rp, MokaCacheReader::Inner(r)));
+        }
+
+        let key = (
+            path.to_string(),
+            meta.etag().expect("etag must be valid").to_string(),
+        );
+        let bs = match self.cache.get(&key) {
+            Some(bs) => bs,
+            None => {
+                let bs = self.read_all(path).await?;
+                self.cache.insert(key, bs.clone()).await;
+                bs
+            }
+        };
+
+        let range = args.range().resolve(bs.len() as u64)?;
+        let bs = bs.slice(range.start as usize..range.end as usize);
+
+        Ok((
+            RpRead::new(bs.len() as u64),
+            MokaCacheReader::Cached(oio::Cursor::from(bs)),
+        ))
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, args).await
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+}
+
+/// MokaCacheReader will read from cached content or underlying reader.
+pub enum MokaCacheReader<R> {
+    /// Content is served from cache.
+    Cached(oio::Cursor),
+    /// Content is served from underlying reader.
+    Inner(R),
+}
+
+impl<R: oio::Read> oio::Read for MokaCacheReader<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        match self {
+            MokaCacheReader::Cached(r) => r.poll_read(cx, buf),
+            MokaCacheReader::Inner(r) => r.poll_read(cx, buf),
+        }
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        match self {
+            MokaCacheReader::Cached(r) => r.poll_seek(cx, pos),
+            MokaCacheReader::Inner(r) => r.poll_seek(cx, pos),
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        match self {
+            MokaCacheReader::Cached(r) => r.poll_next(cx),
+            MokaCacheReader::Inner(r) => r.poll_next(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::services::Memory;
+
+    /// MockService returns the same content and counts requests.
+    #[derive(Debug, Default)]
+    struct MockService {
+        etag: Option<String>,
+        stat: Arc<AtomicUsize>,
+        read: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        type Reader = oio::Cursor;
+        type BlockingReader = ();
+        type Writer = ();
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capability(Capability {
+                stat: true,
+                read: true,
+                ..Default::default()
+            });
+            am
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<RpStat> {
+            self.stat.fetch_add(1, Ordering::Relaxed);
+            let mut meta = Metadata::new(EntryMode::FILE)
+                .with_bit(Metakey::Complete)
+                .with_content_length(13);
+            if let Some(etag) = &self.etag {
+                meta.set_etag(etag);
+            }
+            Ok(RpStat::new(meta))
+        }
+
+        async fn read(&self, _: &str, _: OpRead) -> Result<(RpRead, Self::Reader)> {
+            self.read.fetch_add(1, Ordering::Relaxed);
+            Ok((
+                RpRead::new(13),
+                oio::Cursor::from(Bytes::from_static(b"Hello, World!")),
+            ))
+        }
+    }
+
+    async fn read_to_end(acc: &impl Accessor, path: &str, args: OpRead) -> Result<Bytes> {
+        let (_, mut r) = acc.read(path, args).await?;
+        let mut buf = BytesMut::new();
+        while let Some(bs) = r.next().await {
+            buf.extend_from_slice(&bs?);
+        }
+        Ok(buf.freeze())
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit() -> Result<()> {
+        let srv = MockService {
+            etag: Some("\"abc\"".to_string()),
+            ..Default::default()
+        };
+        let (stat, read) = (srv.stat.clone(), srv.read.clone());
+        let acc = MokaCacheLayer::new(1024).layer(srv);
+
+        assert_eq!(
+            read_to_end(&acc, "test", OpRead::new()).await?,
+            "Hello, World!"
+        );
+        assert_eq!(read.load(Ordering::Relaxed), 1);
+
+        // Both full and range reads should be served from cache.
+        assert_eq!(
+            read_to_end(&acc, "test", OpRead::new()).await?,
+            "Hello, World!"
+        );
+        let args = OpRead::new().with_range(BytesRange::new(Some(7), None));
+        assert_eq!(read_to_end(&acc, "test", args).await?, "World!");
+        assert_eq!(read.load(Ordering::Relaxed), 1);
+        assert_eq!(stat.load(Ordering::Relaxed), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_without_etag() -> Result<()> {
+        // Memory doesn't return etag, so content should never be cached.
+        let op = Operator::new(Memory::default())?
+            .layer(MokaCacheLayer::new(1024))
+            .finish();
+
+        op.write("test", "Hello, World!").await?;
+        assert_eq!(op.read("test").await?, b"Hello, World!");
+        assert_eq!(op.range_read("test", 7..).await?, b"World!");
+
+        op.write("test", "Hello, OpenDAL!").await?;
+        assert_eq!(op.read("test").await?, b"Hello, OpenDAL!");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stat_without_etag() -> Result<()> {
+        let srv = MockService::default();
+        let (stat, read) = (srv.stat.clone(), srv.read.clone());
+        let acc = MokaCacheLayer::new(1024).layer(srv);
+
+        for _ in 0..3 {
+            assert_eq!(
+                read_to_end(&acc, "test", OpRead::new()).await?,
+                "Hello, World!"
+            );
+        }
+        // Whether to cache is decided by every object itself.
+        assert_eq!(stat.load(Ordering::Relaxed), 3);
+        assert_eq!(read.load(Ordering::Relaxed), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bypass_with_args() -> Result<()> {
+        let srv = MockService {
+            etag: Some("\"abc\"".to_string()),
+            ..Default::default()
+        };
+        let (stat, read) = (srv.stat.clone(), srv.read.clone());
+        let acc = MokaCacheLayer::new(1024).layer(srv);
+
+        read_to_end(&acc, "test", OpRead::new()).await?;
+        assert_eq!(read.load(Ordering::Relaxed), 1);
+
+        for args in [
+            OpRead::new().with_if_none_match("\"abc\""),
+            OpRead::new().with_generation(1),
+            OpRead::new().with_snapshot("2023-05-01T00:00:00Z"),
+            OpRead::new().with_override_cache_control("no-cache"),
+            OpRead::new().with_override_content_disposition("attachment"),
+            OpRead::new().with_follow_symlink(false),
+        ] {
+            read_to_end(&acc, "test", args).await?;
+        }
+        assert_eq!(read.load(Ordering::Relaxed), 7);
+        assert_eq!(stat.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cacheable() {
+        let acc = MokaCacheLayer::new(16).layer(());
+
+        let meta = Metadata::new(EntryMode::FILE)
+            .with_bit(Metakey::Complete)
+            .with_content_length(8);
+        assert!(!acc.cacheable(&meta), "etag is required");
+
+        let meta = meta.with_etag("abc".to_string());
+        assert!(acc.cacheable(&meta));
+        assert!(!acc.cacheable(&meta.clone().with_content_length(32)));
+        assert!(acc.cacheable(&meta.clone().with_cache_control("max-age=60".to_string())));
+        assert!(!acc.cacheable(&meta.with_cache_control("private, No-Store".to_string())));
+    }
+}
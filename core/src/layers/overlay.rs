@@ -0,0 +1,478 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io;
+use std::task::Context;
+use std::task::Poll;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Overlay the underlying operator on top of fallback operators.
+///
+/// This layer is designed for gradual migrations: new data is written to
+/// the new service while old data can still be read from the old ones,
+/// which is transparent to application code.
+///
+/// # Behavior
+///
+/// - `read` and `stat` try the underlying operator first, then the
+///   fallbacks in order. The first result that is not
+///   [`ErrorKind::NotFound`] is returned, so errors like
+///   [`ErrorKind::PermissionDenied`] won't be hidden by fallbacks. If all
+///   of them return `NotFound`, the error of the underlying operator is
+///   returned.
+/// - `list` merges entries from all operators. Entries are deduplicated by
+///   path and the one from the earlier operator wins. `NotFound` returned
+///   while listing is treated as an empty dir.
+/// - All other operations, including `write` and `delete`, only go to the
+///   underlying operator.
+///
+/// # Notes
+///
+/// - Paths deleted from the underlying operator are still visible if
+///   fallbacks have them.
+/// - `list` keeps all returned paths in memory for deduplication.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::OverlayLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let old = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .finish();
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(OverlayLayer::new(old))
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OverlayLayer {
+    fallbacks: Vec<FusedAccessor>,
+}
+
+impl OverlayLayer {
+    /// Create a new overlay layer which falls back to `fallback`.
+    pub fn new(fallback: Operator) -> Self {
+        Self {
+            fallbacks: vec![fallback.into_inner()],
+        }
+    }
+
+    /// Add another fallback which will be tried after existing ones.
+    pub fn with_fallback(mut self, fallback: Operator) -> Self {
+        self.fallbacks.push(fallback.into_inner());
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for OverlayLayer {
+    type LayeredAccessor = OverlayAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        OverlayAccessor {
+            inner,
+            fallbacks: self.fallbacks.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OverlayAccessor<A: Accessor> {
+    inner: A,
+    fallbacks: Vec<FusedAccessor>,
+}
+
+/// Convert `NotFound` into `None` so that the next operator can be tried.
+fn not_found_as_none<T>(res: Result<T>) -> Result<Option<T>> {
+    match res {
+        Ok(v) => Ok(Some(v)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for OverlayAccessor<A> {
+    type Inner = A;
+    type Reader = OverlayReader<A::Reader, oio::Reader>;
+    type BlockingReader = OverlayReader<A::BlockingReader, oio::BlockingReader>;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = OverlayPager<A::Pager, oio::Pager>;
+    type BlockingPager = OverlayPager<A::BlockingPager, oio::BlockingPager>;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let err = match self.inner.read(path, args.clone()).await {
+            Ok((rp, r)) => return Ok((rp, OverlayReader::Primary(r))),
+            Err(err) if err.kind() == ErrorKind::NotFound => err,
+            Err(err) => return Err(err),
+        };
+
+        for fallback in &self.fallbacks {
+            if let Some((rp, r)) = not_found_as_none(fallback.read(path, args.clone()).await)? {
+                return Ok((rp, OverlayReader::Fallback(r)));
+            }
+        }
+        Err(err)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        let err = match self.inner.blocking_read(path, args.clone()) {
+            Ok((rp, r)) => return Ok((rp, OverlayReader::Primary(r))),
+            Err(err) if err.kind() == ErrorKind::NotFound => err,
+            Err(err) => return Err(err),
+        };
+
+        for fallback in &self.fallbacks {
+            if let Some((rp, r)) = not_found_as_none(fallback.blocking_read(path, args.clone()))? {
+                return Ok((rp, OverlayReader::Fallback(r)));
+            }
+        }
+        Err(err)
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, args).await
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let err = match self.inner.stat(path, args.clone()).await {
+            Ok(rp) => return Ok(rp),
+            Err(err) if err.kind() == ErrorKind::NotFound => err,
+            Err(err) => return Err(err),
+        };
+
+        for fallback in &self.fallbacks {
+            if let Some(rp) = not_found_as_none(fallback.stat(path, args.clone()).await)? {
+                return Ok(rp);
+            }
+        }
+        Err(err)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let err = match self.inner.blocking_stat(path, args.clone()) {
+            Ok(rp) => return Ok(rp),
+            Err(err) if err.kind() == ErrorKind::NotFound => err,
+            Err(err) => return Err(err),
+        };
+
+        for fallback in &self.fallbacks {
+            if let Some(rp) = not_found_as_none(fallback.blocking_stat(path, args.clone()))? {
+                return Ok(rp);
+            }
+        }
+        Err(err)
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let primary = not_found_as_none(self.inner.list(path, args.clone()).await)?;
+
+        let mut fallbacks = VecDeque::with_capacity(self.fallbacks.len());
+        for fallback in &self.fallbacks {
+            if let Some((_, p)) = not_found_as_none(fallback.list(path, args.clone()).await)? {
+                fallbacks.push_back(p);
+            }
+        }
+
+        let (rp, primary) = match primary {
+            Some((rp, p)) => (rp, Some(p)),
+            None => (RpList::default(), None),
+        };
+        Ok((rp, OverlayPager::new(primary, fallbacks)))
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        let primary = not_found_as_none(self.inner.blocking_list(path, args.clone()))?;
+
+        let mut fallbacks = VecDeque::with_capacity(self.fallbacks.len());
+        for fallback in &self.fallbacks {
+            if let Some((_, p)) = not_found_as_none(fallback.blocking_list(path, args.clone()))? {
+                fallbacks.push_back(p);
+            }
+        }
+
+        let (rp, primary) = match primary {
+            Some((rp, p)) => (rp, Some(p)),
+            None => (RpList::default(), None),
+        };
+        Ok((rp, OverlayPager::new(primary, fallbacks)))
+    }
+}
+
+/// OverlayReader reads from the operator that returned it.
+pub enum OverlayReader<R, F> {
+    /// Content is served by the underlying operator.
+    Primary(R),
+    /// Content is served by a fallback operator.
+    Fallback(F),
+}
+
+impl<R: oio::Read, F: oio::Read> oio::Read for OverlayReader<R, F> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        match self {
+            OverlayReader::Primary(r) => r.poll_read(cx, buf),
+            OverlayReader::Fallback(r) => r.poll_read(cx, buf),
+        }
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        match self {
+            OverlayReader::Primary(r) => r.poll_seek(cx, pos),
+            OverlayReader::Fallback(r) => r.poll_seek(cx, pos),
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        match self {
+            OverlayReader::Primary(r) => r.poll_next(cx),
+            OverlayReader::Fallback(r) => r.poll_next(cx),
+        }
+    }
+}
+
+impl<R: oio::BlockingRead, F: oio::BlockingRead> oio::BlockingRead for OverlayReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            OverlayReader::Primary(r) => r.read(buf),
+            OverlayReader::Fallback(r) => r.read(buf),
+        }
+    }
+
+    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
+        match self {
+            OverlayReader::Primary(r) => r.seek(pos),
+            OverlayReader::Fallback(r) => r.seek(pos),
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<Bytes>> {
+        match self {
+            OverlayReader::Primary(r) => r.next(),
+            OverlayReader::Fallback(r) => r.next(),
+        }
+    }
+}
+
+/// OverlayPager returns all entries of the underlying operator first,
+/// then entries of fallbacks that haven't been returned.
+pub struct OverlayPager<P, F> {
+    primary: Option<P>,
+    fallbacks: VecDeque<F>,
+    /// Paths that have been returned.
+    seen: HashSet<String>,
+}
+
+impl<P, F> OverlayPager<P, F> {
+    fn new(primary: Option<P>, fallbacks: VecDeque<F>) -> Self {
+        Self {
+            primary,
+            fallbacks,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Remove entries that have been returned and record the rest.
+    fn dedup(&mut self, entries: Vec<oio::Entry>) -> Vec<oio::Entry> {
+        entries
+            .into_iter()
+            .filter(|e| self.seen.insert(e.path().to_string()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<P: oio::Page, F: oio::Page> oio::Page for OverlayPager<P, F> {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        loop {
+            let entries = if let Some(p) = &mut self.primary {
+                match p.next().await? {
+                    Some(entries) => entries,
+                    None => {
+                        self.primary = None;
+                        continue;
+                    }
+                }
+            } else if let Some(p) = self.fallbacks.front_mut() {
+                match p.next().await? {
+                    Some(entries) => entries,
+                    None => {
+                        self.fallbacks.pop_front();
+                        continue;
+                    }
+                }
+            } else {
+                return Ok(None);
+            };
+
+            let entries = self.dedup(entries);
+            if !entries.is_empty() {
+                return Ok(Some(entries));
+            }
+        }
+    }
+}
+
+impl<P: oio::BlockingPage, F: oio::BlockingPage> oio::BlockingPage for OverlayPager<P, F> {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        loop {
+            let entries = if let Some(p) = &mut self.primary {
+                match p.next()? {
+                    Some(entries) => entries,
+                    None => {
+                        self.primary = None;
+                        continue;
+                    }
+                }
+            } else if let Some(p) = self.fallbacks.front_mut() {
+                match p.next()? {
+                    Some(entries) => entries,
+                    None => {
+                        self.fallbacks.pop_front();
+                        continue;
+                    }
+                }
+            } else {
+                return Ok(None);
+            };
+
+            let entries = self.dedup(entries);
+            if !entries.is_empty() {
+                return Ok(Some(entries));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::Memory;
+
+    /// MockService denies all stat requests.
+    #[derive(Debug)]
+    struct MockService;
+
+    #[async_trait]
+    impl Accessor for MockService {
+        type Reader = ();
+        type BlockingReader = ();
+        type Writer = ();
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capability(Capability {
+                stat: true,
+                ..Default::default()
+            });
+            am
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<RpStat> {
+            Err(Error::new(ErrorKind::PermissionDenied, "stat is denied"))
+        }
+    }
+
+    async fn memory_with(paths: &[(&str, &str)]) -> Result<Operator> {
+        let op = Operator::new(Memory::default())?.finish();
+        for (path, content) in paths {
+            op.write(path, content.to_string()).await?;
+        }
+        Ok(op)
+    }
+
+    #[tokio::test]
+    async fn test_overlay() -> Result<()> {
+        let old = memory_with(&[("dir/a", "old a"), ("dir/b", "old b")]).await?;
+        let older = memory_with(&[("dir/c", "older c"), ("dir/d", "older d")]).await?;
+        let new = memory_with(&[("dir/b", "new b"), ("dir/c", "new c")]).await?;
+        let op = new
+            .clone()
+            .layer(OverlayLayer::new(old.clone()).with_fallback(older));
+
+        assert_eq!(op.read("dir/a").await?, b"old a");
+        assert_eq!(op.read("dir/b").await?, b"new b");
+        assert_eq!(op.blocking().read("dir/c")?, b"new c");
+        assert_eq!(op.stat("dir/d").await?.content_length(), 7);
+        assert_eq!(
+            op.stat("dir/e").await.unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+
+        let mut paths: Vec<_> = op
+            .list("dir/")
+            .await?
+            .map_ok(|e| e.path().to_string())
+            .try_collect()
+            .await?;
+        paths.sort();
+        assert_eq!(paths, ["dir/a", "dir/b", "dir/c", "dir/d"]);
+        let paths: Vec<_> = op
+            .blocking()
+            .list("dir/")?
+            .map(|e| e.map(|e| e.path().to_string()))
+            .collect::<Result<_>>()?;
+        assert_eq!(paths.len(), 4);
+
+        // Writes and deletes only go to the underlying operator.
+        op.write("dir/a", "new a").await?;
+        assert_eq!(new.read("dir/a").await?, b"new a");
+        assert_eq!(old.read("dir/a").await?, b"old a");
+        op.delete("dir/a").await?;
+        assert_eq!(op.read("dir/a").await?, b"old a");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_overlay_error_precedence() -> Result<()> {
+        let old = memory_with(&[("a", "old a")]).await?;
+        let op = OperatorBuilder::new(MockService)
+            .layer(OverlayLayer::new(old))
+            .finish();
+
+        // Errors other than NotFound must not be hidden by fallbacks.
+        assert_eq!(
+            op.stat("a").await.unwrap_err().kind(),
+            ErrorKind::PermissionDenied
+        );
+        Ok(())
+    }
+}
@@ -693,6 +693,17 @@ impl<R: oio::Write> oio::Write for PrometheusMetricWrapper<R> {
             err
         })
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await.map_err(|err| {
+            self.stats.increment_errors_total(self.op, err.kind());
+            err
+        })
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for PrometheusMetricWrapper<R> {
@@ -19,6 +19,8 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::io;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::task::ready;
 use std::task::Context;
 use std::task::Poll;
@@ -41,6 +43,9 @@ use crate::raw::oio::WriteOperation;
 use crate::raw::*;
 use crate::*;
 
+/// The default cap of `Retry-After` honored by [`RetryLayer`].
+const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
 /// Add retry for temporary failed operations.
 ///
 /// # Notes
@@ -49,7 +54,29 @@ use crate::*;
 /// returns true. If operation still failed, this layer will set error to
 /// `Persistent` which means error has been retried.
 ///
-/// `write` and `blocking_write` don't support retry so far, visit [this issue](https://github.com/apache/incubator-opendal/issues/1223) for more details.
+/// `write` and `blocking_write` will re-send the same in-memory [`Bytes`]
+/// for every attempt, both for whole object writes and for every chunk of
+/// streaming writes. Writers must be safe to re-enter: a failed `write`
+/// means the given bytes have not been accepted. Visit [this issue](https://github.com/apache/incubator-opendal/issues/1223) for more details.
+///
+/// If the failed operation returns an error with [`Error::retry_after`]
+/// (for example, parsed from the `Retry-After` header of a `503` response),
+/// the next retry will wait at least that long even if the computed backoff
+/// is shorter. The honored delay is capped to 60s by default, use
+/// [`RetryLayer::with_max_retry_after`] to change it.
+///
+/// # Logging
+///
+/// Every retry will be logged at `WARN` level under target
+/// `opendal::service` with the operation, the path, the attempt number,
+/// the delay and the error that triggered it, for example:
+///
+/// ```text
+/// operation=stat path=abc -> retry attempt=1 after 1s: error_kind=Unexpected error=...
+/// ```
+///
+/// An operation that still fails after all retries will be logged as
+/// `retry gave up after N attempts` with the last error.
 ///
 /// # Examples
 ///
@@ -65,8 +92,20 @@ use crate::*;
 ///     .layer(RetryLayer::new())
 ///     .finish();
 /// ```
-#[derive(Default, Clone)]
-pub struct RetryLayer(ExponentialBuilder);
+#[derive(Clone)]
+pub struct RetryLayer {
+    backoff: ExponentialBuilder,
+    max_retry_after: Duration,
+}
+
+impl Default for RetryLayer {
+    fn default() -> Self {
+        Self {
+            backoff: ExponentialBuilder::default(),
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+        }
+    }
+}
 
 impl RetryLayer {
     /// Create a new retry layer.
@@ -92,7 +131,7 @@ impl RetryLayer {
     /// If jitter is enabled, ExponentialBackoff will add a random jitter in `[0, min_delay)
     /// to current delay.
     pub fn with_jitter(mut self) -> Self {
-        self.0 = self.0.with_jitter();
+        self.backoff = self.backoff.with_jitter();
         self
     }
 
@@ -102,13 +141,13 @@ impl RetryLayer {
     ///
     /// This function will panic if input factor smaller than `1.0`.
     pub fn with_factor(mut self, factor: f32) -> Self {
-        self.0 = self.0.with_factor(factor);
+        self.backoff = self.backoff.with_factor(factor);
         self
     }
 
     /// Set min_delay of current backoff.
     pub fn with_min_delay(mut self, min_delay: Duration) -> Self {
-        self.0 = self.0.with_min_delay(min_delay);
+        self.backoff = self.backoff.with_min_delay(min_delay);
         self
     }
 
@@ -116,7 +155,7 @@ impl RetryLayer {
     ///
     /// Delay will not increasing if current delay is larger than max_delay.
     pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
-        self.0 = self.0.with_max_delay(max_delay);
+        self.backoff = self.backoff.with_max_delay(max_delay);
         self
     }
 
@@ -124,7 +163,17 @@ impl RetryLayer {
     ///
     /// Backoff will return `None` if max times is reaching.
     pub fn with_max_times(mut self, max_times: usize) -> Self {
-        self.0 = self.0.with_max_times(max_times);
+        self.backoff = self.backoff.with_max_times(max_times);
+        self
+    }
+
+    /// Set max_retry_after of current backoff.
+    ///
+    /// `Retry-After` returned by services will be capped to max_retry_after,
+    /// default to 60s. Set it to zero to ignore `Retry-After` and use the
+    /// computed backoff only.
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
         self
     }
 }
@@ -135,15 +184,141 @@ impl<A: Accessor> Layer<A> for RetryLayer {
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
         RetryAccessor {
             inner,
-            builder: self.0.clone(),
+            builder: RetryBuilder {
+                inner: self.backoff.clone(),
+                max_retry_after: self.max_retry_after,
+                operation: "",
+                path: Arc::from(""),
+                state: Arc::default(),
+            },
         }
     }
 }
 
+/// RetryBuilder builds backoffs that wait at least the `Retry-After` of
+/// the last retryable error, and log every retry of the operation.
+#[derive(Debug, Clone)]
+struct RetryBuilder {
+    inner: ExponentialBuilder,
+    max_retry_after: Duration,
+
+    /// Operation and path of the retry loop, only used in logs.
+    operation: &'static str,
+    path: Arc<str>,
+    state: Arc<Mutex<RetryState>>,
+}
+
+/// RetryState is shared between a retry loop and its backoff.
+#[derive(Debug, Default)]
+struct RetryState {
+    /// `Retry-After` of the last retryable error, will be taken by the next delay.
+    retry_after: Option<Duration>,
+    /// The last error formatted for logs.
+    error: String,
+}
+
+impl RetryBuilder {
+    /// Create a new builder for `operation` on `path` that doesn't share
+    /// state with others.
+    ///
+    /// Every retry loop should hold its own builder so that concurrent
+    /// operations won't affect each other.
+    fn fresh(&self, operation: impl Into<&'static str>, path: &str) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            max_retry_after: self.max_retry_after,
+            operation: operation.into(),
+            path: Arc::from(path),
+            state: Arc::default(),
+        }
+    }
+
+    /// Set the operation of the following retry loops.
+    ///
+    /// Wrappers run different operations with the same builder.
+    fn set_operation(&mut self, operation: impl Into<&'static str>) {
+        self.operation = operation.into();
+    }
+
+    /// Record the `Retry-After` and the error of this result so that the
+    /// next delay will be at least as long as it.
+    ///
+    /// backon only accepts `fn` as retryable checker and notifier, so we
+    /// have to observe the result before it returns to backon.
+    fn observe<T>(&self, res: Result<T>) -> Result<T> {
+        if let Err(err) = &res {
+            let retry_after = err.retry_after().map(|v| v.min(self.max_retry_after));
+
+            let mut state = self.state.lock().expect("lock must succeed");
+            state.retry_after = retry_after;
+            state.error = format!("error_kind={} error={:?}", err.kind(), err);
+        }
+
+        res
+    }
+}
+
+impl BackoffBuilder for RetryBuilder {
+    type Backoff = RetryBackoff;
+
+    fn build(&self) -> Self::Backoff {
+        RetryBackoff {
+            inner: self.inner.build(),
+            operation: self.operation,
+            path: self.path.clone(),
+            state: self.state.clone(),
+            attempt: 0,
+        }
+    }
+}
+
+/// RetryBackoff will be asked for the next delay only if the last attempt
+/// failed with a temporary error, so it logs every retry and the final
+/// give up.
+#[derive(Debug)]
+struct RetryBackoff {
+    inner: ExponentialBackoff,
+    operation: &'static str,
+    path: Arc<str>,
+    state: Arc<Mutex<RetryState>>,
+    /// Number of retries that have been made.
+    attempt: usize,
+}
+
+impl Iterator for RetryBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut state = self.state.lock().expect("lock must succeed");
+
+        let dur = match self.inner.next() {
+            Some(dur) => dur,
+            None => {
+                warn!(
+                    target: "opendal::service",
+                    "operation={} path={} -> retry gave up after {} attempts: {}",
+                    self.operation, self.path, self.attempt + 1, state.error);
+                return None;
+            }
+        };
+        let dur = match state.retry_after.take() {
+            Some(retry_after) => dur.max(retry_after),
+            None => dur,
+        };
+
+        self.attempt += 1;
+        warn!(
+            target: "opendal::service",
+            "operation={} path={} -> retry attempt={} after {}s: {}",
+            self.operation, self.path, self.attempt, dur.as_secs_f64(), state.error);
+        Some(dur)
+    }
+}
+
 #[derive(Clone)]
 pub struct RetryAccessor<A: Accessor> {
     inner: A,
-    builder: ExponentialBuilder,
+    builder: RetryBuilder,
 }
 
 impl<A: Accessor> Debug for RetryAccessor<A> {
@@ -169,241 +344,234 @@ impl<A: Accessor> LayeredAccessor for RetryAccessor<A> {
     }
 
     async fn create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
-        { || self.inner.create_dir(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::CreateDir, dur.as_secs_f64(), err)
-            })
-            .map(|v| v.map_err(|e| e.set_persistent()))
-            .await
+        let backoff = self.builder.fresh(Operation::CreateDir, path);
+        {
+            || {
+                self.inner
+                    .create_dir(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| v.map_err(|e| e.set_persistent()))
+        .await
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        { || self.inner.read(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Read, dur.as_secs_f64(), err)
-            })
-            .map(|v| {
-                v.map(|(rp, r)| (rp, RetryWrapper::new(r, path, self.builder.clone())))
-                    .map_err(|e| e.set_persistent())
+        let backoff = self.builder.fresh(Operation::Read, path);
+        {
+            || {
+                self.inner
+                    .read(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| {
+            v.map(|(rp, r)| {
+                (
+                    rp,
+                    RetryWrapper::new(r, self.builder.fresh(ReadOperation::Read, path)),
+                )
             })
-            .await
+            .map_err(|e| e.set_persistent())
+        })
+        .await
     }
 
     /// Return `Interrupted` Error even after retry.
     ///
     /// Allowing users to retry the write request from upper logic.
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        { || self.inner.write(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Write, dur.as_secs_f64(), err)
-            })
-            .map(|v| {
-                v.map(|(rp, r)| (rp, RetryWrapper::new(r, path, self.builder.clone())))
-                    .map_err(|e| e.set_persistent())
+        let backoff = self.builder.fresh(Operation::Write, path);
+        {
+            || {
+                self.inner
+                    .write(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| {
+            v.map(|(rp, r)| {
+                (
+                    rp,
+                    RetryWrapper::new(r, self.builder.fresh(WriteOperation::Write, path)),
+                )
             })
-            .await
+            .map_err(|e| e.set_persistent())
+        })
+        .await
     }
 
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        { || self.inner.stat(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Stat, dur.as_secs_f64(), err)
-            })
-            .map(|v| v.map_err(|e| e.set_persistent()))
-            .await
+        let backoff = self.builder.fresh(Operation::Stat, path);
+        {
+            || {
+                self.inner
+                    .stat(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| v.map_err(|e| e.set_persistent()))
+        .await
     }
 
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        { || self.inner.delete(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Delete, dur.as_secs_f64(), err)
-            })
-            .map(|v| v.map_err(|e| e.set_persistent()))
-            .await
+        let backoff = self.builder.fresh(Operation::Delete, path);
+        {
+            || {
+                self.inner
+                    .delete(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| v.map_err(|e| e.set_persistent()))
+        .await
     }
 
     async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
-        { || self.inner.copy(from, to, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Copy, dur.as_secs_f64(), err)
-            })
-            .map(|v| v.map_err(|e| e.set_persistent()))
-            .await
+        let backoff = self.builder.fresh(Operation::Copy, from);
+        {
+            || {
+                self.inner
+                    .copy(from, to, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| v.map_err(|e| e.set_persistent()))
+        .await
     }
 
     async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
-        { || self.inner.rename(from, to, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::Rename, dur.as_secs_f64(), err)
-            })
-            .map(|v| v.map_err(|e| e.set_persistent()))
-            .await
+        let backoff = self.builder.fresh(Operation::Rename, from);
+        {
+            || {
+                self.inner
+                    .rename(from, to, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| v.map_err(|e| e.set_persistent()))
+        .await
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        { || self.inner.list(path, args.clone()) }
-            .retry(&self.builder)
-            .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::List, dur.as_secs_f64(), err)
-            })
-            .map(|v| {
-                v.map(|(l, p)| {
-                    let pager = RetryWrapper::new(p, path, self.builder.clone());
-                    (l, pager)
-                })
-                .map_err(|e| e.set_persistent())
+        let backoff = self.builder.fresh(Operation::List, path);
+        {
+            || {
+                self.inner
+                    .list(path, args.clone())
+                    .map(|v| backoff.observe(v))
+            }
+        }
+        .retry(&backoff)
+        .when(|e| e.is_temporary())
+        .map(|v| {
+            v.map(|(l, p)| {
+                let pager = RetryWrapper::new(p, self.builder.fresh(PageOperation::Next, path));
+                (l, pager)
             })
-            .await
+            .map_err(|e| e.set_persistent())
+        })
+        .await
     }
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        let backoff = self.builder.fresh(Operation::Batch, "");
         {
             || async {
-                let rp = self.inner.batch(args.clone()).await?;
+                let rp = backoff.observe(self.inner.batch(args.clone()).await)?;
                 let mut nrp = Vec::with_capacity(rp.results().len());
                 for (path, result) in rp.into_results() {
-                    let result = result?;
+                    let result = backoff.observe(result)?;
                     nrp.push((path, Ok(result)))
                 }
                 Ok(RpBatch::new(nrp))
             }
         }
-        .retry(&self.builder)
+        .retry(&backoff)
         .when(|e: &Error| e.is_temporary())
-        .notify(|err, dur| {
-            warn!(
-                target: "opendal::service",
-                "operation={} -> retry after {}s: error={:?}",
-                Operation::Batch, dur.as_secs_f64(), err)
-        })
         .await
         .map_err(|e| e.set_persistent())
     }
 
     fn blocking_create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
-        { || self.inner.blocking_create_dir(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingCreateDir, path);
+        { || backoff.observe(self.inner.blocking_create_dir(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingCreateDir, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
-        { || self.inner.blocking_read(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingRead, path);
+        { || backoff.observe(self.inner.blocking_read(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingRead, dur.as_secs_f64(), err)
-            })
             .call()
-            .map(|(rp, r)| (rp, RetryWrapper::new(r, path, self.builder.clone())))
+            .map(|(rp, r)| {
+                (
+                    rp,
+                    RetryWrapper::new(r, self.builder.fresh(ReadOperation::BlockingRead, path)),
+                )
+            })
             .map_err(|e| e.set_persistent())
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        { || self.inner.blocking_write(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingWrite, path);
+        { || backoff.observe(self.inner.blocking_write(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingWrite, dur.as_secs_f64(), err)
-            })
             .call()
-            .map(|(rp, r)| (rp, RetryWrapper::new(r, path, self.builder.clone())))
+            .map(|(rp, r)| {
+                (
+                    rp,
+                    RetryWrapper::new(r, self.builder.fresh(WriteOperation::BlockingWrite, path)),
+                )
+            })
             .map_err(|e| e.set_persistent())
     }
 
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        { || self.inner.blocking_stat(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingStat, path);
+        { || backoff.observe(self.inner.blocking_stat(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingStat, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        { || self.inner.blocking_delete(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingDelete, path);
+        { || backoff.observe(self.inner.blocking_delete(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingDelete, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
-        { || self.inner.blocking_list(path, args.clone()) }
-            .retry(&self.builder)
+        let backoff = self.builder.fresh(Operation::BlockingList, path);
+        { || backoff.observe(self.inner.blocking_list(path, args.clone())) }
+            .retry(&backoff)
             .when(|e| e.is_temporary())
-            .notify(|err, dur| {
-                warn!(
-                    target: "opendal::service",
-                    "operation={} -> retry after {}s: error={:?}",
-                    Operation::BlockingList, dur.as_secs_f64(), err)
-            })
             .call()
             .map(|(rp, p)| {
-                let p = RetryWrapper::new(p, path, self.builder.clone());
+                let p = RetryWrapper::new(p, self.builder.fresh(PageOperation::BlockingNext, path));
                 (rp, p)
             })
             .map_err(|e| e.set_persistent())
@@ -412,18 +580,16 @@ impl<A: Accessor> LayeredAccessor for RetryAccessor<A> {
 
 pub struct RetryWrapper<R> {
     inner: R,
-    path: String,
-    builder: ExponentialBuilder,
-    current_backoff: Option<ExponentialBackoff>,
+    builder: RetryBuilder,
+    current_backoff: Option<RetryBackoff>,
     sleep: Option<Pin<Box<tokio::time::Sleep>>>,
 }
 
 impl<R> RetryWrapper<R> {
-    fn new(inner: R, path: &str, backoff: ExponentialBuilder) -> Self {
+    fn new(inner: R, builder: RetryBuilder) -> Self {
         Self {
             inner,
-            path: path.to_string(),
-            builder: backoff,
+            builder,
             current_backoff: None,
             sleep: None,
         }
@@ -437,7 +603,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
             self.sleep = None;
         }
 
-        match ready!(self.inner.poll_read(cx, buf)) {
+        match self.builder.observe(ready!(self.inner.poll_read(cx, buf))) {
             Ok(v) => {
                 self.current_backoff = None;
                 Poll::Ready(Ok(v))
@@ -450,6 +616,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                 let backoff = match self.current_backoff.as_mut() {
                     Some(backoff) => backoff,
                     None => {
+                        self.builder.set_operation(ReadOperation::Read);
                         self.current_backoff = Some(self.builder.build());
                         self.current_backoff.as_mut().unwrap()
                     }
@@ -461,10 +628,6 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                         Poll::Ready(Err(err))
                     }
                     Some(dur) => {
-                        warn!(
-                            target: "opendal::service",
-                            "operation={} path={} -> retry after {}s: error={:?}",
-                            ReadOperation::Read, self.path, dur.as_secs_f64(), err);
                         self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
                         self.poll_read(cx, buf)
                     }
@@ -479,7 +642,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
             self.sleep = None;
         }
 
-        match ready!(self.inner.poll_seek(cx, pos)) {
+        match self.builder.observe(ready!(self.inner.poll_seek(cx, pos))) {
             Ok(v) => {
                 self.current_backoff = None;
                 Poll::Ready(Ok(v))
@@ -492,6 +655,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                 let backoff = match self.current_backoff.as_mut() {
                     Some(backoff) => backoff,
                     None => {
+                        self.builder.set_operation(ReadOperation::Seek);
                         self.current_backoff = Some(self.builder.build());
                         self.current_backoff.as_mut().unwrap()
                     }
@@ -503,10 +667,6 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                         Poll::Ready(Err(err))
                     }
                     Some(dur) => {
-                        warn!(
-                            target: "opendal::service",
-                            "operation={} path={} -> retry after {}s: error={:?}",
-                             ReadOperation::Seek, self.path, dur.as_secs_f64(), err);
                         self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
                         self.poll_seek(cx, pos)
                     }
@@ -521,7 +681,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
             self.sleep = None;
         }
 
-        match ready!(self.inner.poll_next(cx)) {
+        match ready!(self.inner.poll_next(cx)).map(|v| self.builder.observe(v)) {
             None => {
                 self.current_backoff = None;
                 Poll::Ready(None)
@@ -538,6 +698,7 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                 let backoff = match self.current_backoff.as_mut() {
                     Some(backoff) => backoff,
                     None => {
+                        self.builder.set_operation(ReadOperation::Next);
                         self.current_backoff = Some(self.builder.build());
                         self.current_backoff.as_mut().unwrap()
                     }
@@ -549,10 +710,6 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
                         Poll::Ready(Some(Err(err)))
                     }
                     Some(dur) => {
-                        warn!(
-                            target: "opendal::service",
-                            "operation={} path={} -> retry after {}s: error={:?}",
-                            ReadOperation::Next, self.path, dur.as_secs_f64(), err);
                         self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
                         self.poll_next(cx)
                     }
@@ -564,43 +721,28 @@ impl<R: oio::Read> oio::Read for RetryWrapper<R> {
 
 impl<R: oio::BlockingRead> oio::BlockingRead for RetryWrapper<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        { || self.inner.read(buf) }
+        self.builder.set_operation(ReadOperation::BlockingRead);
+        { || self.builder.observe(self.inner.read(buf)) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-                ReadOperation::BlockingRead, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
-        { || self.inner.seek(pos) }
+        self.builder.set_operation(ReadOperation::BlockingSeek);
+        { || self.builder.observe(self.inner.seek(pos)) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-                ReadOperation::BlockingSeek, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn next(&mut self) -> Option<Result<Bytes>> {
-        { || self.inner.next().transpose() }
+        self.builder.set_operation(ReadOperation::BlockingNext);
+        { || self.builder.observe(self.inner.next().transpose()) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-                ReadOperation::BlockingNext, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
             .transpose()
@@ -610,18 +752,16 @@ impl<R: oio::BlockingRead> oio::BlockingRead for RetryWrapper<R> {
 #[async_trait]
 impl<R: oio::Write> oio::Write for RetryWrapper<R> {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.builder.set_operation(WriteOperation::Write);
         let mut backoff = self.builder.build();
 
         loop {
-            match self.inner.write(bs.clone()).await {
+            match self.builder.observe(self.inner.write(bs.clone()).await) {
                 Ok(v) => return Ok(v),
                 Err(e) if !e.is_temporary() => return Err(e),
                 Err(e) => match backoff.next() {
                     None => return Err(e),
                     Some(dur) => {
-                        warn!(target: "opendal::service",
-                              "operation={} path={} -> pager retry after {}s: error={:?}",
-                              WriteOperation::Write, self.path, dur.as_secs_f64(), e);
                         tokio::time::sleep(dur).await;
                         continue;
                     }
@@ -631,18 +771,16 @@ impl<R: oio::Write> oio::Write for RetryWrapper<R> {
     }
 
     async fn abort(&mut self) -> Result<()> {
+        self.builder.set_operation(WriteOperation::Abort);
         let mut backoff = self.builder.build();
 
         loop {
-            match self.inner.abort().await {
+            match self.builder.observe(self.inner.abort().await) {
                 Ok(v) => return Ok(v),
                 Err(e) if !e.is_temporary() => return Err(e),
                 Err(e) => match backoff.next() {
                     None => return Err(e),
                     Some(dur) => {
-                        warn!(target: "opendal::service",
-                              "operation={} path={} -> pager retry after {}s: error={:?}",
-                              WriteOperation::Abort, self.path, dur.as_secs_f64(), e);
                         tokio::time::sleep(dur).await;
                         continue;
                     }
@@ -652,18 +790,35 @@ impl<R: oio::Write> oio::Write for RetryWrapper<R> {
     }
 
     async fn close(&mut self) -> Result<()> {
+        self.builder.set_operation(WriteOperation::Close);
+        let mut backoff = self.builder.build();
+
+        loop {
+            match self.builder.observe(self.inner.close().await) {
+                Ok(v) => return Ok(v),
+                Err(e) if !e.is_temporary() => return Err(e),
+                Err(e) => match backoff.next() {
+                    None => return Err(e),
+                    Some(dur) => {
+                        tokio::time::sleep(dur).await;
+                        continue;
+                    }
+                },
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.builder.set_operation(WriteOperation::Flush);
         let mut backoff = self.builder.build();
 
         loop {
-            match self.inner.close().await {
+            match self.builder.observe(self.inner.flush().await) {
                 Ok(v) => return Ok(v),
                 Err(e) if !e.is_temporary() => return Err(e),
                 Err(e) => match backoff.next() {
                     None => return Err(e),
                     Some(dur) => {
-                        warn!(target: "opendal::service",
-                              "operation={} path={} -> pager retry after {}s: error={:?}",
-                              WriteOperation::Close, self.path, dur.as_secs_f64(), e);
                         tokio::time::sleep(dur).await;
                         continue;
                     }
@@ -671,33 +826,27 @@ impl<R: oio::Write> oio::Write for RetryWrapper<R> {
             }
         }
     }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.inner.resume()
+    }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for RetryWrapper<R> {
     fn write(&mut self, bs: Bytes) -> Result<()> {
-        { || self.inner.write(bs.clone()) }
+        self.builder.set_operation(WriteOperation::BlockingWrite);
+        { || self.builder.observe(self.inner.write(bs.clone())) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-                WriteOperation::BlockingWrite, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
 
     fn close(&mut self) -> Result<()> {
-        { || self.inner.close() }
+        self.builder.set_operation(WriteOperation::BlockingClose);
+        { || self.builder.observe(self.inner.close()) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-               WriteOperation::BlockingClose, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
@@ -706,18 +855,16 @@ impl<R: oio::BlockingWrite> oio::BlockingWrite for RetryWrapper<R> {
 #[async_trait]
 impl<P: oio::Page> oio::Page for RetryWrapper<P> {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        self.builder.set_operation(PageOperation::Next);
         let mut backoff = self.builder.build();
 
         loop {
-            match self.inner.next().await {
+            match self.builder.observe(self.inner.next().await) {
                 Ok(v) => return Ok(v),
                 Err(e) if !e.is_temporary() => return Err(e),
                 Err(e) => match backoff.next() {
                     None => return Err(e),
                     Some(dur) => {
-                        warn!(target: "opendal::service",
-                              "operation={} path={} -> pager retry after {}s: error={:?}",
-                              PageOperation::Next, self.path, dur.as_secs_f64(), e);
                         tokio::time::sleep(dur).await;
                         continue;
                     }
@@ -729,15 +876,10 @@ impl<P: oio::Page> oio::Page for RetryWrapper<P> {
 
 impl<P: oio::BlockingPage> oio::BlockingPage for RetryWrapper<P> {
     fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        { || self.inner.next() }
+        self.builder.set_operation(PageOperation::BlockingNext);
+        { || self.builder.observe(self.inner.next()) }
             .retry(&self.builder)
             .when(|e| e.is_temporary())
-            .notify(move |err, dur| {
-                warn!(
-                target: "opendal::service",
-                "operation={} -> pager retry after {}s: error={:?}",
-                PageOperation::BlockingNext, dur.as_secs_f64(), err)
-            })
             .call()
             .map_err(|e| e.set_persistent())
     }
@@ -767,6 +909,7 @@ mod tests {
     impl Builder for MockBuilder {
         const SCHEME: Scheme = Scheme::Custom("mock");
         type Accessor = MockService;
+        const CONFIG_KEYS: &'static [&'static str] = &[];
 
         fn from_map(_: HashMap<String, String>) -> Self {
             Self::default()
@@ -788,7 +931,7 @@ mod tests {
     impl Accessor for MockService {
         type Reader = MockReader;
         type BlockingReader = ();
-        type Writer = ();
+        type Writer = MockWriter;
         type BlockingWriter = ();
         type Pager = MockPager;
         type BlockingPager = ();
@@ -796,6 +939,9 @@ mod tests {
         fn info(&self) -> AccessorInfo {
             let mut am = AccessorInfo::default();
             am.set_capability(Capability {
+                stat: true,
+                write: true,
+                write_without_content_length: true,
                 list: true,
                 list_with_delimiter_slash: true,
                 list_without_delimiter: true,
@@ -816,6 +962,27 @@ mod tests {
             ))
         }
 
+        async fn write(&self, _: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+            Ok((
+                RpWrite::new(),
+                MockWriter {
+                    attempt: self.attempt.clone(),
+                },
+            ))
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<RpStat> {
+            let mut attempt = self.attempt.lock().unwrap();
+            *attempt += 1;
+
+            match *attempt {
+                1 => Err(Error::new(ErrorKind::RateLimited, "slow down")
+                    .set_temporary()
+                    .with_retry_after(Duration::from_secs(1))),
+                _ => Ok(RpStat::new(Metadata::new(EntryMode::FILE))),
+            }
+        }
+
         async fn list(&self, _: &str, _: OpList) -> Result<(RpList, Self::Pager)> {
             let pager = MockPager::default();
             Ok((RpList::default(), pager))
@@ -934,6 +1101,35 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Clone, Default)]
+    struct MockWriter {
+        attempt: Arc<Mutex<usize>>,
+    }
+
+    #[async_trait]
+    impl oio::Write for MockWriter {
+        async fn write(&mut self, _: Bytes) -> Result<()> {
+            let mut attempt = self.attempt.lock().unwrap();
+            *attempt += 1;
+
+            match *attempt {
+                1 => Err(
+                    Error::new(ErrorKind::Unexpected, "retryable_error from writer")
+                        .set_temporary(),
+                ),
+                _ => Ok(()),
+            }
+        }
+
+        async fn abort(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
     #[derive(Debug, Clone, Default)]
     struct MockPager {
         attempt: usize,
@@ -996,6 +1192,40 @@ mod tests {
         assert_eq!(*builder.attempt.lock().unwrap(), 5);
     }
 
+    #[tokio::test]
+    async fn test_retry_write() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let builder = MockBuilder::default();
+        let op = Operator::new(builder.clone())
+            .unwrap()
+            .layer(RetryLayer::new())
+            .finish();
+
+        op.write("retryable_error", "Hello, World!")
+            .await
+            .expect("write must succeed");
+        assert_eq!(*builder.attempt.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_write_chunks() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let builder = MockBuilder::default();
+        let op = Operator::new(builder.clone())
+            .unwrap()
+            .layer(RetryLayer::new())
+            .finish();
+
+        let mut w = op.writer("retryable_error").await.unwrap();
+        // The first chunk fails once and will be retried.
+        w.write("Hello, ").await.expect("write must succeed");
+        w.write("World!").await.expect("write must succeed");
+        w.close().await.expect("close must succeed");
+        assert_eq!(*builder.attempt.lock().unwrap(), 3);
+    }
+
     #[tokio::test]
     async fn test_retry_list() {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
@@ -1044,4 +1274,84 @@ mod tests {
         op.remove(paths).await.expect("batch must succeed");
         assert_eq!(*builder.attempt.lock().unwrap(), 5);
     }
+
+    #[tokio::test]
+    async fn test_retry_after() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let builder = MockBuilder::default();
+        let op = Operator::new(builder.clone())
+            .unwrap()
+            .layer(RetryLayer::new().with_min_delay(Duration::from_millis(10)))
+            .finish();
+
+        let now = std::time::Instant::now();
+        op.stat("retry_after").await.expect("stat must succeed");
+        assert!(now.elapsed() >= Duration::from_secs(1));
+        assert_eq!(*builder.attempt.lock().unwrap(), 2);
+
+        let builder = MockBuilder::default();
+        let op = Operator::new(builder.clone())
+            .unwrap()
+            .layer(
+                RetryLayer::new()
+                    .with_min_delay(Duration::from_millis(10))
+                    .with_max_retry_after(Duration::from_millis(10)),
+            )
+            .finish();
+
+        let now = std::time::Instant::now();
+        op.stat("retry_after").await.expect("stat must succeed");
+        assert!(now.elapsed() < Duration::from_secs(1));
+        assert_eq!(*builder.attempt.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let builder = RetryLayer::new()
+            .with_min_delay(Duration::from_millis(1))
+            .with_max_times(2)
+            .with_max_retry_after(Duration::from_secs(1))
+            .layer(MockService::default())
+            .builder
+            .fresh(Operation::Stat, "path/to/file");
+
+        let mut backoff = builder.build();
+        assert_eq!(backoff.operation, "stat");
+        assert_eq!(&*backoff.path, "path/to/file");
+
+        let _ = builder.observe::<()>(Err(Error::new(ErrorKind::Unexpected, "retryable")
+            .set_temporary()
+            .with_retry_after(Duration::from_secs(10))));
+        assert!(builder
+            .state
+            .lock()
+            .unwrap()
+            .error
+            .starts_with("error_kind=Unexpected"));
+        assert_eq!(backoff.next(), Some(Duration::from_secs(1)));
+        assert_eq!(backoff.attempt, 1);
+
+        assert!(backoff.next().is_some());
+        assert_eq!(backoff.attempt, 2);
+
+        // Gave up after max times.
+        assert_eq!(backoff.next(), None);
+        assert_eq!(backoff.attempt, 2);
+    }
+
+    #[test]
+    fn test_retry_after_default_cap() {
+        let builder = RetryLayer::new()
+            .with_min_delay(Duration::from_millis(1))
+            .layer(MockService::default())
+            .builder
+            .fresh(Operation::Stat, "path/to/file");
+
+        let mut backoff = builder.build();
+        let _ = builder.observe::<()>(Err(Error::new(ErrorKind::RateLimited, "slow down")
+            .set_temporary()
+            .with_retry_after(Duration::from_secs(3600))));
+        assert_eq!(backoff.next(), Some(DEFAULT_MAX_RETRY_AFTER));
+    }
 }
@@ -70,6 +70,8 @@ mod types;
 pub use types::*;
 
 // Public modules, they will be accessed like `opendal::layers::Xxxx`
+#[cfg(any(test, feature = "tests"))]
+pub mod behavior;
 #[cfg(feature = "docs")]
 pub mod docs;
 pub mod layers;
@@ -89,8 +91,8 @@ mod tests {
     #[test]
     fn assert_size() {
         assert_eq!(24, size_of::<Operator>());
-        assert_eq!(240, size_of::<Entry>());
-        assert_eq!(216, size_of::<Metadata>());
+        assert_eq!(272, size_of::<Entry>());
+        assert_eq!(248, size_of::<Metadata>());
         assert_eq!(1, size_of::<EntryMode>());
         assert_eq!(24, size_of::<Scheme>());
     }
@@ -247,6 +247,134 @@ pub trait Accessor: Send + Sync + Debug + Unpin + 'static {
         ))
     }
 
+    /// Invoke the `restore` operation on the specified path.
+    ///
+    /// Require [`Capability::restore`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check mode.
+    /// - Restore SHOULD succeed if the object is being restored or has been restored.
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `compose` operation to concatenate sources in
+    /// [`OpCompose`] into the specified path.
+    ///
+    /// Require [`Capability::compose`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path and sources MUST be file paths, DON'T NEED to check mode.
+    /// - Services SHOULD handle their limits on the count of sources.
+    /// - Sources SHOULD be deleted after composed if `delete_sources` is set.
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `get_acl` operation on the specified path.
+    ///
+    /// Require [`Capability::get_acl`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check mode.
+    /// - Services SHOULD fill all ACL parts they know in [`Acl`].
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `create_symlink` operation on the specified path.
+    ///
+    /// Require [`Capability::create_symlink`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check mode.
+    /// - Target is relative to the root of accessor, DON'T NEED to check
+    ///   whether it exists.
+    /// - Existing entry at path SHOULD be overwritten.
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `truncate` operation on the specified path.
+    ///
+    /// Require [`Capability::truncate`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check mode.
+    /// - Size MUST NOT be larger than the current size of file.
+    /// - Truncated content SHOULD NOT be visible after returning.
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `set_permissions` operation on the specified path.
+    ///
+    /// Require [`Capability::set_permissions`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path could be file path or dir path.
+    /// - Permission bits are set as is without being masked by umask.
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
+    /// Invoke the `set_owner` operation on the specified path.
+    ///
+    /// Require [`Capability::set_owner`]
+    ///
+    /// # Behavior
+    ///
+    /// - Input path could be file path or dir path.
+    /// - Owner or group that is not set MUST be kept unchanged.
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        let (_, _) = (path, args);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "operation is not supported",
+        ))
+    }
+
     /// Invoke the `blocking_create` operation on the specified path.
     ///
     /// This operation is the blocking version of [`Accessor::create_dir`]
@@ -432,6 +560,38 @@ impl<T: Accessor + ?Sized> Accessor for Arc<T> {
         self.as_ref().batch(args).await
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        self.as_ref().restore(path, args).await
+    }
+
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        self.as_ref().compose(path, args).await
+    }
+
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        self.as_ref().get_acl(path, args).await
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        self.as_ref().create_symlink(path, args).await
+    }
+
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        self.as_ref().truncate(path, args).await
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        self.as_ref().set_permissions(path, args).await
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        self.as_ref().set_owner(path, args).await
+    }
+
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         self.as_ref().presign(path, args).await
     }
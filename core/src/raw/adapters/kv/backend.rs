@@ -79,6 +79,7 @@ impl<S: Adapter> Accessor for Backend<S> {
         }
 
         if cap.write {
+            cap.write_without_content_length = true;
             cap.create_dir = true;
             cap.delete = true;
         }
@@ -111,10 +112,8 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
-
-        let length = bs.len();
-        Ok((RpRead::new(length as u64), oio::Cursor::from(bs)))
+        let (rp, bs) = self.read_range(bs, args.range())?;
+        Ok((rp, oio::Cursor::from(bs)))
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
@@ -125,31 +124,17 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
-        Ok((RpRead::new(bs.len() as u64), oio::Cursor::from(bs)))
+        let (rp, bs) = self.read_range(bs, args.range())?;
+        Ok((rp, oio::Cursor::from(bs)))
     }
 
-    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        if args.content_length().is_none() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "write without content length is not supported",
-            ));
-        }
-
+    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         let p = build_abs_path(&self.root, path);
 
         Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p)))
     }
 
-    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        if args.content_length().is_none() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "write without content length is not supported",
-            ));
-        }
-
+    fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
         let p = build_abs_path(&self.root, path);
 
         Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p)))
@@ -236,19 +221,12 @@ impl<S> Backend<S>
 where
     S: Adapter,
 {
-    fn apply_range(&self, mut bs: Vec<u8>, br: BytesRange) -> Vec<u8> {
-        match (br.offset(), br.size()) {
-            (Some(offset), Some(size)) => {
-                let mut bs = bs.split_off(offset as usize);
-                if (size as usize) < bs.len() {
-                    let _ = bs.split_off(size as usize);
-                }
-                bs
-            }
-            (Some(offset), None) => bs.split_off(offset as usize),
-            (None, Some(size)) => bs.split_off(bs.len() - size as usize),
-            (None, None) => bs,
-        }
+    fn read_range(&self, mut bs: Vec<u8>, br: BytesRange) -> Result<(RpRead, Vec<u8>)> {
+        let total_size = bs.len() as u64;
+        let range = br.resolve(total_size)?;
+        bs.truncate(range.end as usize);
+        let bs = bs.split_off(range.start as usize);
+        Ok((RpRead::from_range(range, total_size), bs))
     }
 }
 
@@ -302,8 +280,10 @@ pub struct KvWriter<S> {
     kv: Arc<S>,
     path: String,
 
+    /// All written bytes will be buffered and set into kv while closing.
+    ///
     /// TODO: if kv supports append, we can use them directly.
-    buf: Option<Vec<u8>>,
+    buf: Vec<u8>,
 }
 
 impl<S> KvWriter<S> {
@@ -311,31 +291,29 @@ impl<S> KvWriter<S> {
         KvWriter {
             kv,
             path,
-            buf: None,
+            buf: Vec::new(),
         }
     }
 }
 
 #[async_trait]
 impl<S: Adapter> oio::Write for KvWriter<S> {
-    // TODO: we need to support append in the future.
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.buf = Some(bs.into());
+        self.buf.extend_from_slice(&bs);
 
         Ok(())
     }
 
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        // Nothing has been written into kv before close.
+        self.buf.clear();
+
+        Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
-        if let Some(buf) = self.buf.as_deref() {
-            self.kv.set(&self.path, buf).await?;
-        }
+        self.kv.set(&self.path, &self.buf).await?;
+        self.buf.clear();
 
         Ok(())
     }
@@ -343,15 +321,14 @@ impl<S: Adapter> oio::Write for KvWriter<S> {
 
 impl<S: Adapter> oio::BlockingWrite for KvWriter<S> {
     fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.buf = Some(bs.into());
+        self.buf.extend_from_slice(&bs);
 
         Ok(())
     }
 
     fn close(&mut self) -> Result<()> {
-        if let Some(buf) = self.buf.as_deref() {
-            self.kv.blocking_set(&self.path, buf)?;
-        }
+        self.kv.blocking_set(&self.path, &self.buf)?;
+        self.buf.clear();
 
         Ok(())
     }
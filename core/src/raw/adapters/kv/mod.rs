@@ -18,6 +18,86 @@
 //! Providing Key Value Adapter for OpenDAL.
 //!
 //! Any services that implement `Adapter` can be used an OpenDAL Service.
+//!
+//! # Examples
+//!
+//! Users can expose their own key value storage via implementing
+//! [`Adapter`] and building an operator from [`Backend`]:
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use std::sync::Mutex;
+//!
+//! use async_trait::async_trait;
+//! use futures::TryStreamExt;
+//! use opendal::raw::adapters::kv;
+//! use opendal::Capability;
+//! use opendal::OperatorBuilder;
+//! use opendal::Result;
+//! use opendal::Scheme;
+//!
+//! #[derive(Debug, Default)]
+//! struct MyKv {
+//!     inner: Mutex<HashMap<String, Vec<u8>>>,
+//! }
+//!
+//! #[async_trait]
+//! impl kv::Adapter for MyKv {
+//!     fn metadata(&self) -> kv::Metadata {
+//!         kv::Metadata::new(
+//!             Scheme::Custom("mykv"),
+//!             "mykv",
+//!             Capability {
+//!                 read: true,
+//!                 write: true,
+//!                 list: true,
+//!                 ..Default::default()
+//!             },
+//!         )
+//!     }
+//!
+//!     async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+//!         Ok(self.inner.lock().unwrap().get(path).cloned())
+//!     }
+//!
+//!     async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+//!         self.inner
+//!             .lock()
+//!             .unwrap()
+//!             .insert(path.to_string(), value.to_vec());
+//!         Ok(())
+//!     }
+//!
+//!     async fn delete(&self, path: &str) -> Result<()> {
+//!         self.inner.lock().unwrap().remove(path);
+//!         Ok(())
+//!     }
+//!
+//!     async fn scan(&self, path: &str) -> Result<Vec<String>> {
+//!         let inner = self.inner.lock().unwrap();
+//!         Ok(inner.keys().filter(|k| k.starts_with(path)).cloned().collect())
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! let op = OperatorBuilder::new(kv::Backend::new(MyKv::default())).finish();
+//!
+//! let mut w = op.writer("dir/file").await?;
+//! w.write("Hello, ").await?;
+//! w.write("World!").await?;
+//! w.close().await?;
+//!
+//! assert_eq!(op.read("dir/file").await?, b"Hello, World!");
+//! assert_eq!(op.stat("dir/file").await?.content_length(), 13);
+//!
+//! let entries: Vec<_> = op.list("dir/").await?.try_collect().await?;
+//! assert_eq!(entries[0].path(), "dir/file");
+//! op.delete("dir/file").await?;
+//! assert!(!op.is_exist("dir/file").await?);
+//! # Ok(())
+//! # }
+//! ```
 
 mod api;
 pub use api::Adapter;
@@ -117,10 +117,8 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
-
-        let length = bs.len();
-        Ok((RpRead::new(length as u64), oio::Cursor::from(bs)))
+        let (rp, bs) = self.read_range(bs, args.range())?;
+        Ok((rp, oio::Cursor::from(bs)))
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
@@ -132,8 +130,8 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
-        Ok((RpRead::new(bs.len() as u64), oio::Cursor::from(bs)))
+        let (rp, bs) = self.read_range(bs, args.range())?;
+        Ok((rp, oio::Cursor::from(bs)))
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
@@ -225,19 +223,11 @@ impl<S> Backend<S>
 where
     S: Adapter,
 {
-    fn apply_range(&self, mut bs: Bytes, br: BytesRange) -> Bytes {
-        match (br.offset(), br.size()) {
-            (Some(offset), Some(size)) => {
-                let mut bs = bs.split_off(offset as usize);
-                if (size as usize) < bs.len() {
-                    let _ = bs.split_off(size as usize);
-                }
-                bs
-            }
-            (Some(offset), None) => bs.split_off(offset as usize),
-            (None, Some(size)) => bs.split_off(bs.len() - size as usize),
-            (None, None) => bs,
-        }
+    fn read_range(&self, bs: Bytes, br: BytesRange) -> Result<(RpRead, Bytes)> {
+        let total_size = bs.len() as u64;
+        let range = br.resolve(total_size)?;
+        let bs = bs.slice(range.start as usize..range.end as usize);
+        Ok((RpRead::from_range(range, total_size), bs))
     }
 }
 
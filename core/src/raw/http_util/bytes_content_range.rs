@@ -113,11 +113,14 @@ impl BytesContentRange {
     }
 
     /// Calculate bytes content range from size and specified range.
+    ///
+    /// Ranges beyond the end of content will be clamped like
+    /// [`BytesRange::resolve`].
     pub fn from_bytes_range(total_size: u64, range: BytesRange) -> Self {
         let (start, end) = match (range.offset(), range.size()) {
-            (Some(offset), Some(size)) => (offset, offset + size - 1),
+            (Some(offset), Some(size)) => (offset, offset.saturating_add(size).min(total_size) - 1),
             (Some(offset), None) => (offset, total_size - 1),
-            (None, Some(size)) => (total_size - size, total_size - 1),
+            (None, Some(size)) => (total_size.saturating_sub(size), total_size - 1),
             (None, None) => (0, total_size - 1),
         };
 
@@ -268,6 +271,22 @@ mod tests {
                     .with_size(4096)
                     .with_range(1024, 1024),
             ),
+            (
+                "size beyond end",
+                BytesRange::new(Some(1024), Some(4096)),
+                2048,
+                BytesContentRange::default()
+                    .with_size(2048)
+                    .with_range(1024, 2047),
+            ),
+            (
+                "suffix larger than size",
+                BytesRange::new(None, Some(4096)),
+                2048,
+                BytesContentRange::default()
+                    .with_size(2048)
+                    .with_range(0, 2047),
+            ),
         ];
 
         for (name, input, input_size, expected) in cases {
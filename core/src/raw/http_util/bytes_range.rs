@@ -19,6 +19,7 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::ops::Bound;
+use std::ops::Range;
 use std::ops::RangeBounds;
 use std::str::FromStr;
 
@@ -113,24 +114,50 @@ impl BytesRange {
         )
     }
 
-    /// apply_on_bytes will apply range on bytes.
-    pub fn apply_on_bytes(&self, mut bs: Bytes) -> Bytes {
+    /// Resolve this range against content of `total_size` bytes.
+    ///
+    /// The returned range is always within `0..total_size`. We follow the
+    /// policy of [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-14.1.2):
+    ///
+    /// - Ranges that extend beyond the end of content will be clamped:
+    ///   `0..1024` on a 100 bytes file resolves to `0..100`.
+    /// - Suffix ranges longer than the content cover the whole content:
+    ///   `..1024` on a 100 bytes file resolves to `0..100`.
+    /// - Ranges that start at or beyond the end of content are not
+    ///   satisfiable, and [`ErrorKind::RangeNotSatisfied`] will be returned.
+    ///   The only exception is ranges starting at `0`, which always resolve
+    ///   to `0..0` for empty content so that reading the head of an empty
+    ///   file returns empty content instead of an error.
+    pub fn resolve(&self, total_size: u64) -> Result<Range<u64>> {
+        if self.is_full() {
+            return Ok(0..total_size);
+        }
+
         match (self.0, self.1) {
-            (None, None) => bs,
-            (None, Some(size)) => {
-                if size as usize >= bs.len() {
-                    return bs;
-                }
-                bs.split_off(bs.len() - size as usize)
-            }
-            (Some(offset), None) => bs.split_off(offset as usize),
-            (Some(offset), Some(size)) => {
-                let mut bs = bs.split_off(offset as usize);
-                if (size as usize) < bs.len() {
-                    let _ = bs.split_off(size as usize);
-                }
-                bs
-            }
+            (None, Some(size)) => Ok(total_size.saturating_sub(size)..total_size),
+            (Some(offset), _) if offset > 0 && offset >= total_size => Err(Error::new(
+                ErrorKind::RangeNotSatisfied,
+                "range is not satisfiable",
+            )
+            .with_operation("BytesRange::resolve")
+            .with_context("range", self.to_string())
+            .with_context("size", total_size.to_string())),
+            (Some(offset), Some(size)) => Ok(offset..offset.saturating_add(size).min(total_size)),
+            (offset, None) => Ok(offset.unwrap_or_default()..total_size),
+        }
+    }
+
+    /// apply_on_bytes will apply range on bytes.
+    ///
+    /// The range will be clamped to the length of bytes, and empty bytes
+    /// will be returned if the range starts beyond the end. Use
+    /// [`BytesRange::resolve`] first if unsatisfiable ranges should be
+    /// reported as errors.
+    pub fn apply_on_bytes(&self, bs: Bytes) -> Bytes {
+        let len = bs.len() as u64;
+        match self.resolve(len) {
+            Ok(range) => bs.slice(range.start as usize..range.end as usize),
+            Err(_) => Bytes::new(),
         }
     }
 }
@@ -314,6 +341,7 @@ mod tests {
                 "ello, World!",
             ),
             ("with_empty_offset", (Some(0), Some(100)), "Hello, World!"),
+            ("with_larger_offset", (Some(100), Some(1)), ""),
         ];
 
         for (name, input, expected) in cases {
@@ -325,4 +353,75 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_bytes_range_resolve() {
+        let cases = vec![
+            ("full", BytesRange::new(None, None), 50, Some(0..50)),
+            (
+                "full on empty",
+                BytesRange::new(Some(0), None),
+                0,
+                Some(0..0),
+            ),
+            (
+                "head on empty",
+                BytesRange::new(Some(0), Some(10)),
+                0,
+                Some(0..0),
+            ),
+            (
+                "suffix on empty",
+                BytesRange::new(None, Some(10)),
+                0,
+                Some(0..0),
+            ),
+            ("beyond empty", BytesRange::new(Some(1), None), 0, None),
+            (
+                "within",
+                BytesRange::new(Some(10), Some(20)),
+                50,
+                Some(10..30),
+            ),
+            (
+                "clamped",
+                BytesRange::new(Some(10), Some(100)),
+                50,
+                Some(10..50),
+            ),
+            ("open", BytesRange::new(Some(10), None), 50, Some(10..50)),
+            ("suffix", BytesRange::new(None, Some(20)), 50, Some(30..50)),
+            (
+                "long suffix",
+                BytesRange::new(None, Some(100)),
+                50,
+                Some(0..50),
+            ),
+            (
+                "over range",
+                BytesRange::new(Some(100), Some(101)),
+                50,
+                None,
+            ),
+            ("at end", BytesRange::new(Some(50), None), 50, None),
+            (
+                "huge size",
+                BytesRange::new(Some(10), Some(u64::MAX)),
+                50,
+                Some(10..50),
+            ),
+        ];
+
+        for (name, input, size, expected) in cases {
+            let actual = input.resolve(size);
+            match expected {
+                Some(expected) => assert_eq!(expected, actual.unwrap(), "{name}"),
+                None => assert_eq!(
+                    ErrorKind::RangeNotSatisfied,
+                    actual.unwrap_err().kind(),
+                    "{name}"
+                ),
+            }
+        }
+    }
 }
@@ -19,6 +19,7 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::mem;
 use std::str::FromStr;
+use std::time::Duration;
 
 use futures::TryStreamExt;
 use http::Request;
@@ -29,6 +30,7 @@ use reqwest::Url;
 use super::body::IncomingAsyncBody;
 use super::parse_content_length;
 use super::AsyncBody;
+use super::HttpRecorder;
 use crate::Error;
 use crate::ErrorKind;
 use crate::Result;
@@ -37,6 +39,7 @@ use crate::Result;
 #[derive(Clone)]
 pub struct HttpClient {
     client: reqwest::Client,
+    recorder: Option<HttpRecorder>,
 }
 
 /// We don't want users to know details about our clients.
@@ -70,9 +73,25 @@ impl HttpClient {
             client: builder.build().map_err(|err| {
                 Error::new(ErrorKind::Unexpected, "async client build failed").set_source(err)
             })?,
+            recorder: None,
         })
     }
 
+    /// Create a [`HttpClientBuilder`] to build http client with tuned
+    /// connection settings.
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder::default()
+    }
+
+    /// Enable dry run mode with given recorder.
+    ///
+    /// All requests will be recorded by the recorder instead of being
+    /// sent, and `send` will return an error directly.
+    pub fn with_recorder(mut self, recorder: HttpRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Get the async client from http client.
     pub fn client(&self) -> reqwest::Client {
         self.client.clone()
@@ -80,6 +99,16 @@ impl HttpClient {
 
     /// Send a request in async way.
     pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&req);
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                "request is recorded but not sent in dry run mode",
+            )
+            .with_operation("http_util::Client::send_async")
+            .with_context("url", req.uri().to_string()));
+        }
+
         let url = req.uri().to_string();
         let is_head = req.method() == http::Method::HEAD;
 
@@ -113,10 +142,15 @@ impl HttpClient {
                 err.is_status()
             );
 
+            let network_error = parse_network_error(&err);
+
             let mut oerr = Error::new(ErrorKind::Unexpected, "send async request")
                 .with_operation("http_util::Client::send_async")
-                .with_context("url", &url)
-                .set_source(err);
+                .with_context("url", &url);
+            if let Some(cause) = network_error {
+                oerr = oerr.with_network_error(cause);
+            }
+            let mut oerr = oerr.set_source(err);
             if is_temporary {
                 oerr = oerr.set_temporary();
             }
@@ -154,3 +188,193 @@ impl HttpClient {
         Ok(resp)
     }
 }
+
+/// Parse the cause of transport level error, returns `None` if the error
+/// is not caused by network.
+///
+/// - `connect`: failed to establish the connection, including DNS
+///   failure, connection refused, TLS handshake failure and connect
+///   timeout. The request never reached the service.
+/// - `timeout`: the request timed out after the connection has been
+///   established, the request may or may not have reached the service.
+fn parse_network_error(err: &reqwest::Error) -> Option<&'static str> {
+    if err.is_connect() {
+        Some("connect")
+    } else if err.is_timeout() {
+        Some("timeout")
+    } else {
+        None
+    }
+}
+
+/// The max connect timeout that we allow.
+const MAX_CONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+/// The max pool idle timeout that we allow.
+const MAX_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// HttpClientBuilder is used to build [`HttpClient`] with tuned connection
+/// settings, which is useful for services in different regions that have
+/// very different latency profiles.
+///
+/// Those settings are independent of the operation-level timeout provided
+/// by `TimeoutLayer`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::raw::HttpClient;
+/// use opendal::services::S3;
+/// use opendal::Operator;
+///
+/// fn main() -> Result<()> {
+///     let client = HttpClient::builder()
+///         .with_connect_timeout(Duration::from_secs(3))
+///         .with_pool_idle_timeout(Duration::from_secs(60))
+///         .with_pool_max_idle_per_host(16)
+///         .build()?;
+///
+///     let mut builder = S3::default();
+///     builder.bucket("test");
+///     builder.region("us-east-1");
+///     builder.http_client(client);
+///
+///     let _ = Operator::new(builder)?.finish();
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct HttpClientBuilder {
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+}
+
+impl HttpClientBuilder {
+    /// Set the timeout for establishing tcp connections.
+    ///
+    /// The timeout must be larger than zero and no more than 5 minutes.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for idle connections to be kept alive in the pool.
+    ///
+    /// The timeout must be larger than zero and no more than 1 hour.
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the max idle connections to be kept in the pool per host.
+    ///
+    /// Setting to `0` will disable the connection pool.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Build the http client.
+    pub fn build(self) -> Result<HttpClient> {
+        let mut builder = reqwest::ClientBuilder::new();
+
+        if let Some(timeout) = self.connect_timeout {
+            if timeout.is_zero() || timeout > MAX_CONNECT_TIMEOUT {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "connect timeout must be larger than zero and no more than 5 minutes",
+                )
+                .with_operation("http_util::HttpClientBuilder::build")
+                .with_context("connect_timeout", format!("{timeout:?}")));
+            }
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(timeout) = self.pool_idle_timeout {
+            if timeout.is_zero() || timeout > MAX_POOL_IDLE_TIMEOUT {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "pool idle timeout must be larger than zero and no more than 1 hour",
+                )
+                .with_operation("http_util::HttpClientBuilder::build")
+                .with_context("pool_idle_timeout", format!("{timeout:?}")));
+            }
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+
+        HttpClient::build(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send_connection_refused() {
+        let client = HttpClient::new().expect("must succeed");
+
+        // Nothing should be listening on port 1.
+        let req = Request::get("http://127.0.0.1:1/")
+            .body(AsyncBody::Empty)
+            .expect("must succeed");
+        let err = match client.send(req).await {
+            Ok(_) => panic!("send must fail"),
+            Err(err) => err,
+        };
+
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert_eq!(err.network_error(), Some("connect"));
+        assert!(err.is_temporary());
+    }
+
+    #[test]
+    fn test_http_client_builder() {
+        let cases = vec![
+            ("default", HttpClient::builder(), true),
+            (
+                "valid settings",
+                HttpClient::builder()
+                    .with_connect_timeout(Duration::from_secs(3))
+                    .with_pool_idle_timeout(Duration::from_secs(60))
+                    .with_pool_max_idle_per_host(0),
+                true,
+            ),
+            (
+                "zero connect timeout",
+                HttpClient::builder().with_connect_timeout(Duration::ZERO),
+                false,
+            ),
+            (
+                "absurd connect timeout",
+                HttpClient::builder().with_connect_timeout(Duration::from_secs(3600)),
+                false,
+            ),
+            (
+                "zero pool idle timeout",
+                HttpClient::builder().with_pool_idle_timeout(Duration::ZERO),
+                false,
+            ),
+            (
+                "absurd pool idle timeout",
+                HttpClient::builder().with_pool_idle_timeout(Duration::from_secs(86400)),
+                false,
+            ),
+        ];
+
+        for (name, builder, ok) in cases {
+            let res = builder.build();
+            assert_eq!(res.is_ok(), ok, "{name}");
+            if let Err(err) = res {
+                assert_eq!(err.kind(), ErrorKind::ConfigInvalid, "{name}");
+            }
+        }
+    }
+}
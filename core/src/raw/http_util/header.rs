@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::time::Duration;
+
 use base64::engine::general_purpose;
 use base64::Engine;
 use chrono::DateTime;
@@ -22,12 +24,15 @@ use chrono::Utc;
 use http::header::HeaderName;
 use http::header::CACHE_CONTROL;
 use http::header::CONTENT_DISPOSITION;
+use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_RANGE;
 use http::header::CONTENT_TYPE;
 use http::header::ETAG;
+use http::header::EXPIRES;
 use http::header::LAST_MODIFIED;
 use http::header::LOCATION;
+use http::header::RETRY_AFTER;
 use http::HeaderMap;
 use http::HeaderValue;
 use md5::Digest;
@@ -131,6 +136,21 @@ pub fn parse_content_type(headers: &HeaderMap) -> Result<Option<&str>> {
     }
 }
 
+/// Parse content encoding from header map.
+pub fn parse_content_encoding(headers: &HeaderMap) -> Result<Option<&str>> {
+    match headers.get(CONTENT_ENCODING) {
+        None => Ok(None),
+        Some(v) => Ok(Some(v.to_str().map_err(|e| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header value is not valid utf-8 string",
+            )
+            .with_operation("http_util::parse_content_encoding")
+            .set_source(e)
+        })?)),
+    }
+}
+
 /// Parse content range from header map.
 pub fn parse_content_range(headers: &HeaderMap) -> Result<Option<BytesContentRange>> {
     match headers.get(CONTENT_RANGE) {
@@ -169,6 +189,58 @@ pub fn parse_last_modified(headers: &HeaderMap) -> Result<Option<DateTime<Utc>>>
     }
 }
 
+/// Parse expires from header map.
+///
+/// # Note
+///
+/// Invalid values like `0` are allowed by HTTP which means already
+/// expired, we will treat them as not set.
+pub fn parse_expires(headers: &HeaderMap) -> Result<Option<DateTime<Utc>>> {
+    match headers.get(EXPIRES) {
+        None => Ok(None),
+        Some(v) => {
+            let v = v.to_str().map_err(|e| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "header value is not valid utf-8 string",
+                )
+                .with_operation("http_util::parse_expires")
+                .set_source(e)
+            })?;
+
+            Ok(parse_datetime_from_rfc2822(v).ok())
+        }
+    }
+}
+
+/// Parse retry after from header map.
+///
+/// Both delay seconds like `120` and http dates like
+/// `Wed, 21 Oct 2015 07:28:00 GMT` are supported. Dates in the past will
+/// be returned as zero, and invalid values will be treated as not set.
+pub fn parse_retry_after(headers: &HeaderMap) -> Result<Option<Duration>> {
+    match headers.get(RETRY_AFTER) {
+        None => Ok(None),
+        Some(v) => {
+            let v = v.to_str().map_err(|e| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "header value is not valid utf-8 string",
+                )
+                .with_operation("http_util::parse_retry_after")
+                .set_source(e)
+            })?;
+
+            if let Ok(secs) = v.trim().parse::<u64>() {
+                return Ok(Some(Duration::from_secs(secs)));
+            }
+            Ok(parse_datetime_from_rfc2822(v)
+                .ok()
+                .map(|t| (t - Utc::now()).to_std().unwrap_or_default()))
+        }
+    }
+}
+
 /// Parse etag from header map.
 pub fn parse_etag(headers: &HeaderMap) -> Result<Option<&str>> {
     match headers.get(ETAG) {
@@ -184,6 +256,22 @@ pub fn parse_etag(headers: &HeaderMap) -> Result<Option<&str>> {
     }
 }
 
+/// Parse header value of given name to str.
+pub fn parse_header_to_str<'a>(headers: &'a HeaderMap, name: &str) -> Result<Option<&'a str>> {
+    match headers.get(name) {
+        None => Ok(None),
+        Some(v) => Ok(Some(v.to_str().map_err(|e| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header value has to be valid utf-8 string",
+            )
+            .with_operation("http_util::parse_header_to_str")
+            .with_context("header_name", name)
+            .set_source(e)
+        })?)),
+    }
+}
+
 /// Parse Content-Disposition for header map
 pub fn parse_content_disposition(headers: &HeaderMap) -> Result<Option<&str>> {
     match headers.get(CONTENT_DISPOSITION) {
@@ -230,6 +318,10 @@ pub fn parse_into_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata>
         m.set_content_range(v);
     }
 
+    if let Some(v) = parse_content_encoding(headers)? {
+        m.set_content_encoding(v);
+    }
+
     if let Some(v) = parse_etag(headers)? {
         m.set_etag(v);
     }
@@ -242,10 +334,16 @@ pub fn parse_into_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata>
         m.set_last_modified(v);
     }
 
+    if let Some(v) = parse_expires(headers)? {
+        m.set_expires(v);
+    }
+
     if let Some(v) = parse_content_disposition(headers)? {
         m.set_content_disposition(v);
     }
 
+    m.set_is_dir_marker(is_dir_marker(path, m.content_length_raw()));
+
     Ok(m)
 }
 
@@ -307,6 +405,70 @@ pub fn build_header_value(v: &str) -> Result<HeaderValue> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_expires() {
+        let cases = vec![
+            ("valid", "Wed, 21 Oct 2015 07:28:00 GMT", Some(1445412480)),
+            ("already expired", "0", None),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut headers = HeaderMap::new();
+            headers.insert(EXPIRES, HeaderValue::from_static(input));
+
+            let actual = parse_expires(&headers).expect("must succeed");
+            assert_eq!(actual.map(|v| v.timestamp()), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_parse_into_metadata_dir_marker() {
+        let cases = vec![
+            ("dir marker", "abc/", "0", true),
+            ("dir with content", "abc/", "1", false),
+            ("empty file", "abc", "0", false),
+        ];
+
+        for (name, path, content_length, expected) in cases {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_LENGTH, HeaderValue::from_static(content_length));
+
+            let meta = parse_into_metadata(path, &headers).expect("must succeed");
+            assert_eq!(meta.is_dir_marker(), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let future = (Utc::now() + chrono::Duration::seconds(120))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let cases = vec![
+            ("delay seconds", "3".to_string(), Some(3..=3)),
+            ("future date", future, Some(118..=120)),
+            (
+                "past date",
+                "Wed, 21 Oct 2015 07:28:00 GMT".to_string(),
+                Some(0..=0),
+            ),
+            ("invalid", "soon".to_string(), None),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, HeaderValue::from_str(&input).unwrap());
+
+            let actual = parse_retry_after(&headers).expect("must succeed");
+            match expected {
+                Some(range) => {
+                    let secs = actual.expect("must be set").as_secs();
+                    assert!(range.contains(&secs), "{name}: {secs}");
+                }
+                None => assert_eq!(actual, None, "{name}"),
+            }
+        }
+    }
+
     /// Test cases is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_DeleteObjects.html
     #[test]
     fn test_format_content_md5() {
@@ -24,6 +24,11 @@
 
 mod client;
 pub use client::HttpClient;
+pub use client::HttpClientBuilder;
+
+mod recorder;
+pub use recorder::HttpRecorder;
+pub use recorder::RecordedRequest;
 
 mod body;
 pub use body::AsyncBody;
@@ -35,16 +40,21 @@ pub use header::format_authorization_by_basic;
 pub use header::format_authorization_by_bearer;
 pub use header::format_content_md5;
 pub use header::parse_content_disposition;
+pub use header::parse_content_encoding;
 pub use header::parse_content_length;
 pub use header::parse_content_md5;
 pub use header::parse_content_range;
 pub use header::parse_content_type;
 pub use header::parse_etag;
+pub use header::parse_expires;
+pub use header::parse_header_to_str;
 pub use header::parse_into_metadata;
 pub use header::parse_last_modified;
 pub use header::parse_location;
+pub use header::parse_retry_after;
 
 mod uri;
+pub use uri::normalize_endpoint;
 pub use uri::percent_encode_path;
 
 mod error;
@@ -0,0 +1,290 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::mem;
+use std::sync::Arc;
+
+use http::header::AUTHORIZATION;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Method;
+use http::Request;
+use http::Uri;
+use parking_lot::Mutex;
+
+use super::AsyncBody;
+
+/// Placeholder of masked secrets.
+const MASK: &str = "******";
+
+/// Headers that carry secrets but are not marked as sensitive by services.
+const SECRET_HEADERS: &[&str] = &[
+    "cookie",
+    "proxy-authorization",
+    "x-amz-security-token",
+    "x-amz-server-side-encryption-customer-key",
+    "x-amz-copy-source-server-side-encryption-customer-key",
+    "x-oss-security-token",
+    "x-oss-server-side-encryption-customer-key",
+    "x-obs-security-token",
+    "x-ms-encryption-key",
+    "x-amz-sso_bearer_token",
+];
+
+/// Query parameters that carry secrets in presigned urls.
+const SECRET_QUERIES: &[&str] = &[
+    "x-amz-signature",
+    "x-amz-security-token",
+    "x-goog-signature",
+    "signature",
+    "sig",
+    "security-token",
+];
+
+/// HttpRecorder records the requests built by services instead of
+/// sending them.
+///
+/// Set a recorder via [`HttpClient::with_recorder`][super::HttpClient::with_recorder]
+/// to enable dry run mode: every request will be recorded with secrets
+/// masked, and an error will be returned before touching the network.
+///
+/// # Examples
+///
+/// ```
+/// # use anyhow::Result;
+/// use opendal::raw::HttpClient;
+/// use opendal::raw::HttpRecorder;
+/// use opendal::services::S3;
+/// use opendal::Operator;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let recorder = HttpRecorder::new();
+///
+/// let mut builder = S3::default();
+/// builder.bucket("test");
+/// builder.region("us-east-1");
+/// builder.access_key_id("access_key_id");
+/// builder.secret_access_key("secret_access_key");
+/// builder.http_client(HttpClient::new()?.with_recorder(recorder.clone()));
+///
+/// let op = Operator::new(builder)?.finish();
+/// // The request will not be sent.
+/// assert!(op.stat("path/to/file").await.is_err());
+/// assert_eq!(recorder.requests().len(), 1);
+///
+/// for req in recorder.requests() {
+///     println!("{} {} {:?}", req.method(), req.uri(), req.headers());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HttpRecorder {
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl HttpRecorder {
+    /// Create a new recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the given request with secrets masked.
+    pub fn record(&self, req: &Request<AsyncBody>) {
+        let content_length = match req.body() {
+            AsyncBody::Empty => 0,
+            AsyncBody::Bytes(bs) => bs.len() as u64,
+        };
+
+        self.requests.lock().push(RecordedRequest {
+            method: req.method().clone(),
+            uri: mask_uri(req.uri()),
+            headers: mask_headers(req.headers()),
+            content_length,
+        });
+    }
+
+    /// Get all recorded requests.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().clone()
+    }
+
+    /// Take all recorded requests and clear the recorder.
+    pub fn take(&self) -> Vec<RecordedRequest> {
+        mem::take(&mut *self.requests.lock())
+    }
+}
+
+/// RecordedRequest is the metadata of a request recorded by [`HttpRecorder`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    method: Method,
+    uri: String,
+    headers: HeaderMap,
+    content_length: u64,
+}
+
+impl RecordedRequest {
+    /// Method of this request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Uri of this request, secrets in query are masked.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Headers of this request, secrets are masked.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Length of the request body.
+    pub fn content_length(&self) -> u64 {
+        self.content_length
+    }
+}
+
+fn mask_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut masked = HeaderMap::with_capacity(headers.len());
+
+    for (k, v) in headers {
+        let v = if k == AUTHORIZATION {
+            mask_authorization(v)
+        } else if v.is_sensitive() || SECRET_HEADERS.contains(&k.as_str()) {
+            HeaderValue::from_static(MASK)
+        } else {
+            v.clone()
+        };
+        masked.append(k.clone(), v);
+    }
+
+    masked
+}
+
+/// Mask the secret part of authorization header.
+///
+/// - Signature of SigV4 style authorization is masked so that other parts
+///   like `SignedHeaders` are kept for debugging.
+/// - Other authorizations will only keep their scheme like `Basic`.
+fn mask_authorization(v: &HeaderValue) -> HeaderValue {
+    let s = match v.to_str() {
+        Ok(s) => s,
+        Err(_) => return HeaderValue::from_static(MASK),
+    };
+
+    let masked = if let Some(idx) = s.find("Signature=") {
+        let (prefix, rest) = s.split_at(idx + "Signature=".len());
+        let suffix = rest.find(',').map(|v| &rest[v..]).unwrap_or_default();
+        format!("{prefix}{MASK}{suffix}")
+    } else {
+        match s.split_once(' ') {
+            Some((scheme, _)) => format!("{scheme} {MASK}"),
+            None => MASK.to_string(),
+        }
+    };
+
+    HeaderValue::from_str(&masked).unwrap_or_else(|_| HeaderValue::from_static(MASK))
+}
+
+fn mask_uri(uri: &Uri) -> String {
+    let s = uri.to_string();
+    let (base, query) = match s.split_once('?') {
+        Some(v) => v,
+        None => return s,
+    };
+
+    let query = query
+        .split('&')
+        .map(|kv| match kv.split_once('=') {
+            Some((k, _)) if SECRET_QUERIES.contains(&k.to_lowercase().as_str()) => {
+                format!("{k}={MASK}")
+            }
+            _ => kv.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{base}?{query}")
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let recorder = HttpRecorder::new();
+
+        let mut sse_key = HeaderValue::from_static("customer-key");
+        sse_key.set_sensitive(true);
+        let req = Request::put("https://test.s3.amazonaws.com/abc?X-Amz-Signature=abcdef&partNumber=1")
+            .header(
+                AUTHORIZATION,
+                "AWS4-HMAC-SHA256 Credential=ak/20230501/us-east-1/s3/aws4_request, SignedHeaders=host;x-amz-date, Signature=abcdef",
+            )
+            .header("x-amz-security-token", "token")
+            .header("x-amz-server-side-encryption-customer-algorithm", "AES256")
+            .header("x-test-sse-key", sse_key)
+            .body(AsyncBody::Bytes(Bytes::from("hello")))
+            .expect("must succeed");
+        recorder.record(&req);
+
+        let reqs = recorder.take();
+        assert_eq!(reqs.len(), 1);
+        assert!(recorder.requests().is_empty());
+
+        let req = &reqs[0];
+        assert_eq!(req.method(), Method::PUT);
+        assert_eq!(
+            req.uri(),
+            "https://test.s3.amazonaws.com/abc?X-Amz-Signature=******&partNumber=1"
+        );
+        assert_eq!(req.content_length(), 5);
+
+        let headers = req.headers();
+        assert_eq!(
+            headers[AUTHORIZATION],
+            "AWS4-HMAC-SHA256 Credential=ak/20230501/us-east-1/s3/aws4_request, SignedHeaders=host;x-amz-date, Signature=******"
+        );
+        assert_eq!(headers["x-amz-security-token"], MASK);
+        assert_eq!(
+            headers["x-amz-server-side-encryption-customer-algorithm"],
+            "AES256"
+        );
+        assert_eq!(headers["x-test-sse-key"], MASK);
+    }
+
+    #[test]
+    fn test_mask_authorization() {
+        let cases = vec![
+            ("Basic dXNlcjpwYXNz", "Basic ******"),
+            ("Bearer token", "Bearer ******"),
+            ("token", "******"),
+            ("OSS ak:signature", "OSS ******"),
+        ];
+
+        for (input, expected) in cases {
+            let actual = mask_authorization(&HeaderValue::from_static(input));
+            assert_eq!(actual, expected, "{input}");
+        }
+    }
+}
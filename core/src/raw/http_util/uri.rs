@@ -15,13 +15,19 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use http::Uri;
 use percent_encoding::utf8_percent_encode;
 use percent_encoding::AsciiSet;
 use percent_encoding::NON_ALPHANUMERIC;
 
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+
 /// PATH_ENCODE_SET is the encode set for http url path.
 ///
-/// This set follows [encodeURIComponent](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/encodeURIComponent) which will encode all non-ASCII characters except `A-Z a-z 0-9 - _ . ! ~ * ' ( )`
+/// This set follows the unreserved characters of [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-2.3)
+/// which will encode all characters except `A-Z a-z 0-9 - _ . ~`
 ///
 /// There is a special case for `/` in path: we will allow `/` in path as
 /// required by storage services like s3.
@@ -30,23 +36,78 @@ static PATH_ENCODE_SET: AsciiSet = NON_ALPHANUMERIC
     .remove(b'-')
     .remove(b'_')
     .remove(b'.')
-    .remove(b'!')
-    .remove(b'~')
-    .remove(b'*')
-    .remove(b'\'')
-    .remove(b'(')
-    .remove(b')');
+    .remove(b'~');
 
 /// percent_encode_path will do percent encoding for http encode path.
 ///
-/// Follows [encodeURIComponent](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/encodeURIComponent) which will encode all non-ASCII characters except `A-Z a-z 0-9 - _ . ! ~ * ' ( )`
+/// Follows the unreserved characters of [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-2.3)
+/// which will encode all characters except `A-Z a-z 0-9 - _ . ~`
 ///
 /// There is a special case for `/` in path: we will allow `/` in path as
 /// required by storage services like s3.
+///
+/// The output is the same as the canonical URI of [AWS SigV4](https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html),
+/// so the path we send is exactly the path we sign. Please always use
+/// this function to build paths of requests instead of encoding by hand.
 pub fn percent_encode_path(path: &str) -> String {
     utf8_percent_encode(path, &PATH_ENCODE_SET).to_string()
 }
 
+/// normalize_endpoint will normalize the endpoint input by users.
+///
+/// - Leading and trailing whitespaces and trailing `/` will be trimmed.
+/// - `https://` will be prepended if endpoint doesn't have a scheme.
+/// - Only `http` and `https` are allowed, and the host must not be empty.
+/// - Query and fragment are not allowed.
+///
+/// Returns `ConfigInvalid` error if endpoint is invalid, callers should add
+/// the service context.
+pub fn normalize_endpoint(endpoint: &str) -> Result<String> {
+    let trimmed = endpoint.trim();
+    let input = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{trimmed}")
+    };
+
+    let uri = input.parse::<Uri>().map_err(|err| {
+        Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
+            .with_context("endpoint", endpoint)
+            .set_source(err)
+    })?;
+
+    let scheme = match uri.scheme_str() {
+        Some(v @ ("http" | "https")) => v,
+        _ => {
+            return Err(
+                Error::new(ErrorKind::ConfigInvalid, "endpoint protocol is invalid")
+                    .with_context("endpoint", endpoint),
+            )
+        }
+    };
+    let authority = match uri.authority() {
+        Some(v) if !v.host().is_empty() => v,
+        _ => {
+            return Err(
+                Error::new(ErrorKind::ConfigInvalid, "endpoint host is empty")
+                    .with_context("endpoint", endpoint),
+            )
+        }
+    };
+    if uri.query().is_some() || input.contains('#') {
+        return Err(Error::new(
+            ErrorKind::ConfigInvalid,
+            "endpoint must not contain query or fragment",
+        )
+        .with_context("endpoint", endpoint));
+    }
+
+    Ok(format!(
+        "{scheme}://{authority}{}",
+        uri.path().trim_end_matches('/')
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,7 +120,8 @@ mod tests {
                 ";,/?:@&=+$",
                 "%3B%2C/%3F%3A%40%26%3D%2B%24",
             ),
-            ("Unescaped Characters", "-_.!~*'()", "-_.!~*'()"),
+            ("Unescaped Characters", "-_.~", "-_.~"),
+            ("Sub-delims Characters", "!*'()", "%21%2A%27%28%29"),
             ("Number Sign", "#", "%23"),
             (
                 "Alphanumeric Characters + Space",
@@ -71,6 +133,11 @@ mod tests {
                 "你好，世界！❤",
                 "%E4%BD%A0%E5%A5%BD%EF%BC%8C%E4%B8%96%E7%95%8C%EF%BC%81%E2%9D%A4",
             ),
+            (
+                "Tricky Key",
+                "a b/c+d#e/ünïcödé.txt",
+                "a%20b/c%2Bd%23e/%C3%BCn%C3%AFc%C3%B6d%C3%A9.txt",
+            ),
         ];
 
         for (name, input, expected) in cases {
@@ -79,4 +146,65 @@ mod tests {
             assert_eq!(actual, expected, "{name}");
         }
     }
+
+    #[test]
+    fn test_normalize_endpoint() {
+        let cases = vec![
+            ("schemeless", "s3.amazonaws.com", "https://s3.amazonaws.com"),
+            ("http", "http://127.0.0.1", "http://127.0.0.1"),
+            (
+                "https",
+                "https://s3.amazonaws.com",
+                "https://s3.amazonaws.com",
+            ),
+            (
+                "with port",
+                "http://127.0.0.1:9000",
+                "http://127.0.0.1:9000",
+            ),
+            (
+                "schemeless with port",
+                "127.0.0.1:9000",
+                "https://127.0.0.1:9000",
+            ),
+            (
+                "trailing slash",
+                "http://127.0.0.1:9000/",
+                "http://127.0.0.1:9000",
+            ),
+            (
+                "with path",
+                "http://127.0.0.1:10000/devstoreaccount1/",
+                "http://127.0.0.1:10000/devstoreaccount1",
+            ),
+            (
+                "whitespaces",
+                " https://s3.amazonaws.com ",
+                "https://s3.amazonaws.com",
+            ),
+        ];
+
+        for (name, input, expected) in cases {
+            let actual = normalize_endpoint(input).expect("must succeed");
+
+            assert_eq!(actual, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_endpoint_invalid() {
+        let cases = vec![
+            ("empty", ""),
+            ("invalid scheme", "ftp://s3.amazonaws.com"),
+            ("empty host", "https://"),
+            ("invalid char", "https://s3 amazonaws.com"),
+            ("with query", "https://s3.amazonaws.com?a=b"),
+        ];
+
+        for (name, input) in cases {
+            let err = normalize_endpoint(input).expect_err("must fail");
+
+            assert_eq!(err.kind(), ErrorKind::ConfigInvalid, "{name}");
+        }
+    }
 }
@@ -173,6 +173,38 @@ pub trait LayeredAccessor: Send + Sync + Debug + Unpin + 'static {
         self.inner().batch(args).await
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        self.inner().restore(path, args).await
+    }
+
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        self.inner().compose(path, args).await
+    }
+
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        self.inner().get_acl(path, args).await
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        self.inner().create_symlink(path, args).await
+    }
+
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        self.inner().truncate(path, args).await
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        self.inner().set_permissions(path, args).await
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        self.inner().set_owner(path, args).await
+    }
+
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         self.inner().presign(path, args).await
     }
@@ -253,6 +285,38 @@ impl<L: LayeredAccessor> Accessor for L {
         (self as &L).batch(args).await
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        (self as &L).restore(path, args).await
+    }
+
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        (self as &L).compose(path, args).await
+    }
+
+    async fn get_acl(&self, path: &str, args: OpGetAcl) -> Result<RpGetAcl> {
+        (self as &L).get_acl(path, args).await
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        (self as &L).create_symlink(path, args).await
+    }
+
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        (self as &L).truncate(path, args).await
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        (self as &L).set_permissions(path, args).await
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        (self as &L).set_owner(path, args).await
+    }
+
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         (self as &L).presign(path, args).await
     }
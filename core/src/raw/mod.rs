@@ -43,6 +43,7 @@ pub use path::build_rel_path;
 pub use path::build_rooted_abs_path;
 pub use path::get_basename;
 pub use path::get_parent;
+pub use path::is_dir_marker;
 pub use path::normalize_path;
 pub use path::normalize_root;
 pub use path::validate_path;
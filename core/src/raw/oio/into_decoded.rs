@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::io;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+
+use async_compression::futures::bufread::GzipDecoder;
+use async_compression::futures::bufread::ZlibDecoder;
+use bytes::Bytes;
+use futures::io::BufReader;
+use futures::AsyncRead;
+use tokio::io::ReadBuf;
+
+use crate::ops::OpRead;
+use crate::raw::*;
+use crate::*;
+
+/// The default capacity of the chunk returned by `poll_next`.
+const DEFAULT_DECODED_CHUNK_SIZE: usize = 256 * 1024;
+
+/// ContentEncoding is the content encoding that [`into_decoded_reader`]
+/// is able to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// `gzip` or `x-gzip`
+    Gzip,
+    /// `deflate`, which is the zlib format defined by RFC 1950.
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Parse from the value of `Content-Encoding` header.
+    ///
+    /// Returns `None` if the encoding is not recognized, including
+    /// `identity` and multiple encodings like `gzip, br`.
+    pub fn parse(v: &str) -> Option<Self> {
+        match v.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(ContentEncoding::Gzip),
+            "deflate" => Some(ContentEncoding::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// into_decoded_reader is used to decode the content of [`oio::Reader`]
+/// by given content encoding.
+///
+/// The returning reader doesn't support seek.
+pub fn into_decoded_reader(r: oio::Reader, encoding: ContentEncoding) -> DecodedReader {
+    let r = BufReader::new(r);
+    let decoder = match encoding {
+        ContentEncoding::Gzip => Decoder::Gzip(GzipDecoder::new(r)),
+        ContentEncoding::Deflate => Decoder::Deflate(ZlibDecoder::new(r)),
+    };
+
+    DecodedReader {
+        decoder,
+        buf: Vec::with_capacity(DEFAULT_DECODED_CHUNK_SIZE),
+    }
+}
+
+/// Decode the content of reader if `OpRead::with_content_encoding_decode`
+/// is set and the content encoding of `RpRead` is recognized.
+///
+/// The content encoding and content length will be cleared from the
+/// metadata of returning `RpRead` after decoded.
+pub(crate) fn decode_by_content_encoding(
+    args: &OpRead,
+    mut rp: RpRead,
+    r: oio::Reader,
+) -> (RpRead, oio::Reader) {
+    if !args.content_encoding_decode() || !rp.metadata().bit().contains(Metakey::ContentEncoding) {
+        return (rp, r);
+    }
+
+    match rp
+        .metadata()
+        .content_encoding()
+        .and_then(ContentEncoding::parse)
+    {
+        Some(encoding) => {
+            rp.metadata_mut().clear_content_encoding();
+            (rp, Box::new(into_decoded_reader(r, encoding)))
+        }
+        None => (rp, r),
+    }
+}
+
+enum Decoder {
+    Gzip(GzipDecoder<BufReader<oio::Reader>>),
+    Deflate(ZlibDecoder<BufReader<oio::Reader>>),
+}
+
+impl Decoder {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let res = match self {
+            Decoder::Gzip(d) => ready!(Pin::new(d).poll_read(cx, buf)),
+            Decoder::Deflate(d) => ready!(Pin::new(d).poll_read(cx, buf)),
+        };
+
+        Poll::Ready(res.map_err(parse_decode_error))
+    }
+}
+
+/// Reader that decodes content from the underlying reader.
+pub struct DecodedReader {
+    decoder: Decoder,
+    buf: Vec<u8>,
+}
+
+impl oio::Read for DecodedReader {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.decoder.poll_read(cx, buf)
+    }
+
+    fn poll_seek(&mut self, _: &mut Context<'_>, _: SeekFrom) -> Poll<Result<u64>> {
+        Poll::Ready(Err(Error::new(
+            ErrorKind::Unsupported,
+            "seek is not supported on decoded content",
+        )
+        .with_operation(oio::ReadOperation::Seek)))
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        let dst = self.buf.spare_capacity_mut();
+        let mut buf = ReadBuf::uninit(dst);
+        unsafe { buf.assume_init(DEFAULT_DECODED_CHUNK_SIZE) };
+
+        match ready!(self.decoder.poll_read(cx, buf.initialized_mut())) {
+            Err(err) => Poll::Ready(Some(Err(err))),
+            Ok(0) => Poll::Ready(None),
+            Ok(n) => {
+                buf.set_filled(n);
+                Poll::Ready(Some(Ok(Bytes::from(buf.filled().to_vec()))))
+            }
+        }
+    }
+}
+
+/// Errors returned by the underlying reader are wrapped into `io::Error`,
+/// we should unwrap them to keep the original error kind.
+fn parse_decode_error(err: io::Error) -> Error {
+    if err.get_ref().map_or(false, |e| e.is::<Error>()) {
+        if let Some(Ok(err)) = err.into_inner().map(|e| e.downcast::<Error>()) {
+            return *err;
+        }
+        unreachable!("error has been checked to be opendal error")
+    }
+
+    Error::new(ErrorKind::Unexpected, "decode content failed")
+        .with_operation(oio::ReadOperation::Read)
+        .set_source(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use rand::prelude::*;
+
+    use super::*;
+
+    fn gen_content() -> Vec<u8> {
+        let mut rng = ThreadRng::default();
+        // Generate size between 1B..4MB.
+        let size = rng.gen_range(1..4 * 1024 * 1024);
+        let mut content = vec![0; size];
+        rng.fill_bytes(&mut content);
+        content
+    }
+
+    #[test]
+    fn test_parse_content_encoding() {
+        let cases = vec![
+            ("gzip", Some(ContentEncoding::Gzip)),
+            ("x-gzip", Some(ContentEncoding::Gzip)),
+            (" GZIP ", Some(ContentEncoding::Gzip)),
+            ("deflate", Some(ContentEncoding::Deflate)),
+            ("identity", None),
+            ("br", None),
+            ("gzip, br", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(ContentEncoding::parse(input), expected, "{input}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_gzip() {
+        use oio::ReadExt;
+
+        let content = gen_content();
+        let mut e = GzEncoder::new(Vec::new(), Compression::default());
+        e.write_all(&content).expect("write must success");
+        let encoded = e.finish().expect("finish must success");
+
+        let r = Box::new(oio::Cursor::from(encoded)) as oio::Reader;
+        let mut r = into_decoded_reader(r, ContentEncoding::Gzip);
+
+        let mut bs = Vec::new();
+        while let Some(b) = r.next().await {
+            bs.extend_from_slice(&b.expect("read must success"));
+        }
+        assert_eq!(bs, content)
+    }
+
+    #[tokio::test]
+    async fn test_decode_deflate() {
+        use futures::AsyncReadExt;
+
+        let content = gen_content();
+        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+        e.write_all(&content).expect("write must success");
+        let encoded = e.finish().expect("finish must success");
+
+        let r = Box::new(oio::Cursor::from(encoded)) as oio::Reader;
+        let mut r = Box::new(into_decoded_reader(r, ContentEncoding::Deflate)) as oio::Reader;
+
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.expect("read must success");
+        assert_eq!(bs, content)
+    }
+
+    #[test]
+    fn test_decode_by_content_encoding() {
+        let meta = Metadata::new(EntryMode::FILE)
+            .with_content_length(1024)
+            .with_content_encoding("gzip".to_string());
+        let r = Box::new(()) as oio::Reader;
+
+        let (rp, _) =
+            decode_by_content_encoding(&OpRead::new(), RpRead::with_metadata(meta.clone()), r);
+        assert_eq!(rp.metadata().content_encoding(), Some("gzip"));
+        assert_eq!(rp.metadata().content_length(), 1024);
+
+        let r = Box::new(()) as oio::Reader;
+        let (rp, _) = decode_by_content_encoding(
+            &OpRead::new().with_content_encoding_decode(),
+            RpRead::with_metadata(meta),
+            r,
+        );
+        assert!(!rp.metadata().bit().contains(Metakey::ContentEncoding));
+        assert!(!rp.metadata().bit().contains(Metakey::ContentLength));
+    }
+
+    #[tokio::test]
+    async fn test_decode_invalid_content() {
+        use oio::ReadExt;
+
+        let r = Box::new(oio::Cursor::from(b"not gzip".to_vec())) as oio::Reader;
+        let mut r = into_decoded_reader(r, ContentEncoding::Gzip);
+
+        let mut buf = vec![0; 16];
+        let err = r.read(&mut buf).await.expect_err("read must fail");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+    }
+}
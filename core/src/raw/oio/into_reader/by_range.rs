@@ -37,9 +37,13 @@ use crate::*;
 ///
 /// This operation is not zero cost. If the accessor already returns a
 /// seekable reader, please don't use this.
+///
+/// `op` is used as the base args of following reads, its range will be
+/// replaced while seeking.
 pub fn by_range<A: Accessor>(
     acc: Arc<A>,
     path: &str,
+    op: OpRead,
     reader: A::Reader,
     offset: u64,
     size: u64,
@@ -47,6 +51,7 @@ pub fn by_range<A: Accessor>(
     RangeReader {
         acc,
         path: path.to_string(),
+        op,
         offset,
         size,
         cur: 0,
@@ -60,6 +65,7 @@ pub fn by_range<A: Accessor>(
 pub struct RangeReader<A: Accessor> {
     acc: Arc<A>,
     path: String,
+    op: OpRead,
 
     offset: u64,
     size: u64,
@@ -89,7 +95,7 @@ impl<A: Accessor> RangeReader<A> {
     fn read_future(&self) -> BoxFuture<'static, Result<(RpRead, A::Reader)>> {
         let acc = self.acc.clone();
         let path = self.path.clone();
-        let op = OpRead::default().with_range(BytesRange::new(
+        let op = self.op.clone().with_range(BytesRange::new(
             Some(self.offset + self.cur),
             Some(self.size - self.cur),
         ));
@@ -387,7 +393,8 @@ mod tests {
         let r = MockReader {
             inner: futures::io::Cursor::new(bs.to_vec()),
         };
-        let mut r = Box::new(by_range(acc, "x", r, 0, bs.len() as u64)) as oio::Reader;
+        let mut r =
+            Box::new(by_range(acc, "x", OpRead::new(), r, 0, bs.len() as u64)) as oio::Reader;
 
         let mut buf = Vec::new();
         r.read_to_end(&mut buf).await?;
@@ -421,7 +428,7 @@ mod tests {
         let r = MockReader {
             inner: futures::io::Cursor::new(bs[4096..4096 + 4096].to_vec()),
         };
-        let mut r = Box::new(by_range(acc, "x", r, 4096, 4096)) as oio::Reader;
+        let mut r = Box::new(by_range(acc, "x", OpRead::new(), r, 4096, 4096)) as oio::Reader;
 
         let mut buf = Vec::new();
         r.read_to_end(&mut buf).await?;
@@ -49,6 +49,28 @@ mod into_streamable;
 pub use into_streamable::into_streamable_reader;
 pub use into_streamable::IntoStreamableReader;
 
+#[cfg(feature = "content-decoding")]
+mod into_decoded;
+#[cfg(feature = "content-decoding")]
+pub(crate) use into_decoded::decode_by_content_encoding;
+#[cfg(feature = "content-decoding")]
+pub use into_decoded::into_decoded_reader;
+#[cfg(feature = "content-decoding")]
+pub use into_decoded::ContentEncoding;
+#[cfg(feature = "content-decoding")]
+pub use into_decoded::DecodedReader;
+
+/// Content can't be decoded without `content-decoding` feature, return the
+/// reader as is.
+#[cfg(not(feature = "content-decoding"))]
+pub(crate) fn decode_by_content_encoding(
+    _: &crate::ops::OpRead,
+    rp: crate::raw::RpRead,
+    r: Reader,
+) -> (crate::raw::RpRead, Reader) {
+    (rp, r)
+}
+
 mod entry;
 pub use entry::Entry;
 
@@ -63,6 +85,10 @@ mod to_flat_pager;
 pub use to_flat_pager::to_flat_pager;
 pub use to_flat_pager::ToFlatPager;
 
+mod to_filtered_pager;
+pub use to_filtered_pager::to_filtered_pager;
+pub use to_filtered_pager::ToFilteredPager;
+
 mod to_hierarchy_pager;
 pub use to_hierarchy_pager::to_hierarchy_pager;
 pub use to_hierarchy_pager::ToHierarchyPager;
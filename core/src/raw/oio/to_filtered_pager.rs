@@ -0,0 +1,172 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::ops::OpList;
+use crate::raw::*;
+use crate::*;
+
+/// to_filtered_pager is used to filter entries of a pager by the
+/// predicates in [`OpList`].
+pub fn to_filtered_pager<P>(pager: P, args: &OpList) -> ToFilteredPager<P> {
+    ToFilteredPager {
+        pager,
+        modified_after: args.modified_after(),
+        size_range: args.size_range(),
+    }
+}
+
+/// ToFilteredPager will drop entries that don't match the predicates by
+/// the metadata returned along with list response, no extra `stat` will
+/// be sent.
+///
+/// # Notes
+///
+/// - Dirs are always returned so that callers can go on walking them.
+/// - Entries whose metadata doesn't carry the needed fields will be
+///   returned as is, since we can't decide whether they match.
+/// - ToFilteredPager filter entries after fetch entries. So it's possible
+///   to return an empty vec. It doesn't mean the all pages have been
+///   returned.
+pub struct ToFilteredPager<P> {
+    pager: P,
+    modified_after: Option<DateTime<Utc>>,
+    size_range: Option<(u64, u64)>,
+}
+
+impl<P> ToFilteredPager<P> {
+    fn is_matched(&self, e: &oio::Entry) -> bool {
+        let meta = e.metadata();
+        if meta.is_dir() {
+            return true;
+        }
+
+        if let Some(after) = self.modified_after {
+            let has_last_modified = meta.bit().contains(Metakey::LastModified)
+                || meta.bit().contains(Metakey::Complete);
+            if has_last_modified {
+                if let Some(v) = meta.last_modified() {
+                    if v <= after {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        if let Some((min, max)) = self.size_range {
+            if let Some(v) = meta.content_length_raw() {
+                if v < min || v > max {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn filter_entries(&self, mut entries: Vec<oio::Entry>) -> Vec<oio::Entry> {
+        entries.retain(|e| self.is_matched(e));
+        entries
+    }
+}
+
+#[async_trait]
+impl<P: oio::Page> oio::Page for ToFilteredPager<P> {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let entries = match self.pager.next().await? {
+            Some(entries) => entries,
+            None => return Ok(None),
+        };
+
+        Ok(Some(self.filter_entries(entries)))
+    }
+}
+
+impl<P: oio::BlockingPage> oio::BlockingPage for ToFilteredPager<P> {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let entries = match self.pager.next()? {
+            Some(entries) => entries,
+            None => return Ok(None),
+        };
+
+        Ok(Some(self.filter_entries(entries)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    struct MockPager {
+        pages: Vec<Vec<oio::Entry>>,
+    }
+
+    impl oio::BlockingPage for MockPager {
+        fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+            if self.pages.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(self.pages.remove(0)))
+        }
+    }
+
+    fn file(path: &str, size: Option<u64>, modified: Option<i64>) -> oio::Entry {
+        let mut meta = Metadata::new(EntryMode::FILE);
+        if let Some(size) = size {
+            meta.set_content_length(size);
+        }
+        if let Some(ts) = modified {
+            meta.set_last_modified(Utc.timestamp_opt(ts, 0).unwrap());
+        }
+        oio::Entry::new(path, meta)
+    }
+
+    #[test]
+    fn test_to_filtered_pager() {
+        use oio::BlockingPage;
+
+        let pager = MockPager {
+            pages: vec![
+                vec![
+                    oio::Entry::new("dir/", Metadata::new(EntryMode::DIR)),
+                    file("old", Some(10), Some(100)),
+                    file("new", Some(10), Some(300)),
+                ],
+                vec![
+                    file("small", Some(1), Some(300)),
+                    file("large", Some(1000), Some(300)),
+                    file("unknown", None, None),
+                ],
+            ],
+        };
+        let op = OpList::new()
+            .with_modified_after(Utc.timestamp_opt(200, 0).unwrap())
+            .with_size_range(5, 100);
+        let mut pager = to_filtered_pager(pager, &op);
+
+        let mut paths = vec![];
+        while let Some(entries) = pager.next().expect("next must succeed") {
+            paths.extend(entries.into_iter().map(|e| e.path().to_string()));
+        }
+        assert_eq!(paths, vec!["dir/", "new", "unknown"]);
+    }
+}
@@ -33,6 +33,8 @@ pub enum WriteOperation {
     Abort,
     /// Operation for [`Write::close`]
     Close,
+    /// Operation for [`Write::flush`]
+    Flush,
     /// Operation for [`BlockingWrite::write`]
     BlockingWrite,
     /// Operation for [`BlockingWrite::close`]
@@ -60,6 +62,7 @@ impl From<WriteOperation> for &'static str {
             Write => "Writer::write",
             Abort => "Writer::abort",
             Close => "Writer::close",
+            Flush => "Writer::flush",
             BlockingWrite => "BlockingWriter::write",
             BlockingClose => "BlockingWriter::close",
         }
@@ -98,6 +101,24 @@ pub trait Write: Unpin + Send + Sync {
 
     /// Close the writer and make sure all data has been flushed.
     async fn close(&mut self) -> Result<()>;
+
+    /// Flush written data into durable storage without closing the writer.
+    ///
+    /// Most services only persist data in `close`, so it's a no-op by
+    /// default. Wrappers must forward it to the inner writer.
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The state to resume this write later via
+    /// [`OpWrite::with_resume`](crate::ops::OpWrite::with_resume),
+    /// which is the session url and the committed bytes.
+    ///
+    /// Only services with `write_can_resume` return it, and it's `None` by
+    /// default. Wrappers must forward it to the inner writer.
+    fn resume(&self) -> Option<(&str, u64)> {
+        None
+    }
 }
 
 #[async_trait]
@@ -138,6 +159,14 @@ impl<T: Write + ?Sized> Write for Box<T> {
     async fn close(&mut self) -> Result<()> {
         (**self).close().await
     }
+
+    async fn flush(&mut self) -> Result<()> {
+        (**self).flush().await
+    }
+
+    fn resume(&self) -> Option<(&str, u64)> {
+        (**self).resume()
+    }
 }
 
 /// BlockingWriter is a type erased [`BlockingWrite`]
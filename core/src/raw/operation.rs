@@ -47,6 +47,20 @@ pub enum Operation {
     Batch,
     /// Operation for [`crate::raw::Accessor::presign`]
     Presign,
+    /// Operation for [`crate::raw::Accessor::restore`]
+    Restore,
+    /// Operation for [`crate::raw::Accessor::compose`]
+    Compose,
+    /// Operation for [`crate::raw::Accessor::get_acl`]
+    GetAcl,
+    /// Operation for [`crate::raw::Accessor::create_symlink`]
+    CreateSymlink,
+    /// Operation for [`crate::raw::Accessor::truncate`]
+    Truncate,
+    /// Operation for [`crate::raw::Accessor::set_permissions`]
+    SetPermissions,
+    /// Operation for [`crate::raw::Accessor::set_owner`]
+    SetOwner,
     /// Operation for [`crate::raw::Accessor::blocking_create_dir`]
     BlockingCreateDir,
     /// Operation for [`crate::raw::Accessor::blocking_read`]
@@ -95,6 +109,13 @@ impl From<Operation> for &'static str {
             Operation::Scan => "scan",
             Operation::Presign => "presign",
             Operation::Batch => "batch",
+            Operation::Restore => "restore",
+            Operation::Compose => "compose",
+            Operation::GetAcl => "get_acl",
+            Operation::CreateSymlink => "create_symlink",
+            Operation::Truncate => "truncate",
+            Operation::SetPermissions => "set_permissions",
+            Operation::SetOwner => "set_owner",
             Operation::BlockingCreateDir => "blocking_create_dir",
             Operation::BlockingRead => "blocking_read",
             Operation::BlockingWrite => "blocking_write",
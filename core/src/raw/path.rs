@@ -222,6 +222,13 @@ pub fn validate_path(path: &str, mode: EntryMode) -> bool {
     }
 }
 
+/// Check if the object at given path with given content length is a
+/// directory marker, see
+/// [`Metadata::is_dir_marker`](crate::Metadata::is_dir_marker) for details.
+pub fn is_dir_marker(path: &str, content_length: Option<u64>) -> bool {
+    path.ends_with('/') && content_length == Some(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +385,19 @@ mod tests {
             assert_eq!(actual, expect, "{name}")
         }
     }
+
+    #[test]
+    fn test_is_dir_marker() {
+        let cases = vec![
+            ("empty dir", "abc/", Some(0), true),
+            ("non-empty dir", "abc/", Some(1), false),
+            ("dir without length", "abc/", None, false),
+            ("empty file", "abc", Some(0), false),
+        ];
+
+        for (name, path, content_length, expect) in cases {
+            let actual = is_dir_marker(path, content_length);
+            assert_eq!(actual, expect, "{name}")
+        }
+    }
 }
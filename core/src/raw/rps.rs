@@ -15,8 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::ops::Range;
+
 use http::Request;
 
+use crate::raw::BytesContentRange;
 use crate::*;
 
 /// Reply for `create_dir` operation
@@ -31,6 +34,48 @@ pub struct RpDelete {}
 #[derive(Debug, Clone, Default)]
 pub struct RpList {}
 
+/// Reply for `create_symlink` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpCreateSymlink {}
+
+/// Reply for `truncate` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpTruncate {}
+
+/// Reply for `set_permissions` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpSetPermissions {}
+
+/// Reply for `set_owner` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpSetOwner {}
+
+/// Reply for `restore` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpRestore {}
+
+/// Reply for `compose` operation.
+#[derive(Debug, Clone, Default)]
+pub struct RpCompose {}
+
+/// Reply for `get_acl` operation.
+#[derive(Debug, Clone)]
+pub struct RpGetAcl {
+    acl: Acl,
+}
+
+impl RpGetAcl {
+    /// Create a new reply for `get_acl`.
+    pub fn new(acl: Acl) -> Self {
+        RpGetAcl { acl }
+    }
+
+    /// Consume RpGetAcl to get the inner acl.
+    pub fn into_acl(self) -> Acl {
+        self.acl
+    }
+}
+
 /// Reply for `presign` operation.
 #[derive(Debug, Clone)]
 pub struct RpPresign {
@@ -110,6 +155,21 @@ impl RpRead {
         }
     }
 
+    /// Create a new reply for reading `range` of content which has
+    /// `total_size` bytes in all, content range will be set accordingly.
+    pub fn from_range(range: Range<u64>, total_size: u64) -> Self {
+        let mut rp = RpRead::new(range.end - range.start);
+        // Content range can't represent empty ranges.
+        if range.start < range.end {
+            rp.meta.set_content_range(
+                BytesContentRange::default()
+                    .with_range(range.start, range.end - 1)
+                    .with_size(total_size),
+            );
+        }
+        rp
+    }
+
     /// Create reply read with existing metadata.
     pub fn with_metadata(meta: Metadata) -> Self {
         RpRead { meta }
@@ -120,6 +180,12 @@ impl RpRead {
         &self.meta
     }
 
+    /// Get a mutable ref of metadata.
+    #[cfg(feature = "content-decoding")]
+    pub(crate) fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.meta
+    }
+
     /// Consume reply to get the meta.
     pub fn into_metadata(self) -> Metadata {
         self.meta
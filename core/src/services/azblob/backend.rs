@@ -16,19 +16,30 @@
 // under the License.
 
 use std::collections::HashMap;
+use std::env;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use http::header::CONTENT_TYPE;
 use http::StatusCode;
 use log::debug;
+use log::warn;
 use reqsign::AzureStorageConfig;
 use reqsign::AzureStorageLoader;
 use reqsign::AzureStorageSigner;
+use tokio::sync::Mutex;
 
+use super::batch::is_batch_unsupported;
 use super::batch::parse_batch_delete_response;
+use super::core::check_access_tier;
+use super::core::constants;
+use super::credential::TokenLoader;
+use super::credential::TokenSource;
+use super::credential::DEFAULT_AUTHORITY_HOST;
 use super::error::parse_error;
 use super::pager::AzblobPager;
 use super::writer::AzblobWriter;
@@ -50,6 +61,13 @@ const KNOWN_AZBLOB_ENDPOINT_SUFFIX: &[&str] = &[
 
 const AZBLOB_BATCH_LIMIT: usize = 256;
 
+/// The default block size of staged upload.
+const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
+/// The max size of a block staged by Put Block.
+const MAX_BLOCK_SIZE: usize = 4000 * 1024 * 1024;
+/// The max size of a blob written by a single Put Blob.
+const MAX_PUT_BLOB_SIZE: usize = 5000 * 1024 * 1024;
+
 /// Azure Storage Blob services support.
 ///
 /// # Capabilities
@@ -68,6 +86,11 @@ const AZBLOB_BATCH_LIMIT: usize = 256;
 /// - [x] presign
 /// - [ ] blocking
 ///
+/// Presign generates a service sas signed by `account_key`, or a user
+/// delegation sas while using managed identity or workload identity, or
+/// appends the configured `sas_token` as is. The sas is scoped to the
+/// blob and expires as `OpPresign` requires.
+///
 /// # Configuration
 ///
 /// - `root`: Set the work dir for backend.
@@ -75,9 +98,37 @@ const AZBLOB_BATCH_LIMIT: usize = 256;
 /// - `endpoint`: Set the endpoint for backend.
 /// - `account_name`: Set the account_name for backend.
 /// - `account_key`: Set the account_key for backend.
+/// - `sas_token`: Set the sas_token for backend.
+/// - `client_id`: Set the client id of managed identity or workload identity.
+/// - `tenant_id`: Set the tenant id of workload identity.
+/// - `federated_token_file`: Set the federated token file of workload identity.
+/// - `authority_host`: Set the authority host of workload identity.
+/// - `enable_managed_identity`: Load bearer token via managed identity.
+/// - `write_min_size`: Set the block size of staged upload.
+/// - `write_multipart_threshold`: Set the max size that will be written in a single Put Blob.
+/// - `write_max_concurrent_parts`: Set the max number of blocks uploaded concurrently.
+/// - `default_access_tier`: Set the access tier for new blobs, like `Cool`.
+/// - `enable_write_with_if_none_match`: Send `If-None-Match` on writes to custom endpoints.
 ///
 /// Refer to public API docs for more information.
 ///
+/// # Authentication
+///
+/// Azblob supports the following auth modes, which are mutually
+/// exclusive. `build` will return [`ErrorKind::ConfigInvalid`] if more
+/// than one of them are configured:
+///
+/// - `account_key`: sign requests with shared key.
+/// - `sas_token`: append the sas token to requests without signing.
+/// - `federated_token_file`: exchange the federated token of workload
+///   identity for bearer tokens.
+/// - `enable_managed_identity`: load bearer tokens from azure instance
+///   metadata service.
+///
+/// Bearer tokens are refreshed before they expire. If none of them is
+/// configured, workload identity will be loaded from env, otherwise
+/// requests are sent anonymously.
+///
 /// # Example
 ///
 /// This example works on [Azurite](https://github.com/Azure/Azurite) for local developments.
@@ -138,6 +189,16 @@ pub struct AzblobBuilder {
     account_name: Option<String>,
     account_key: Option<String>,
     sas_token: Option<String>,
+    client_id: Option<String>,
+    tenant_id: Option<String>,
+    federated_token_file: Option<String>,
+    authority_host: Option<String>,
+    enable_managed_identity: bool,
+    write_min_size: Option<usize>,
+    write_multipart_threshold: Option<usize>,
+    write_max_concurrent_parts: Option<usize>,
+    default_access_tier: Option<String>,
+    enable_write_with_if_none_match: bool,
     http_client: Option<HttpClient>,
 }
 
@@ -188,6 +249,9 @@ impl AzblobBuilder {
     ///
     /// - Azblob: `https://accountname.blob.core.windows.net`
     /// - Azurite: `http://127.0.0.1:10000/devstoreaccount1`
+    ///
+    /// If user inputs endpoint without scheme, we will prepend "https://"
+    /// before it.
     pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
         if !endpoint.is_empty() {
             // Trim trailing `/` so that we can accept `http://127.0.0.1:9000/`
@@ -221,14 +285,219 @@ impl AzblobBuilder {
         self
     }
 
+    /// Set client id of managed identity or workload identity.
+    ///
+    /// - For managed identity, it's required to choose user-assigned identity.
+    /// - For workload identity, we will try to load it from env
+    ///   `AZURE_CLIENT_ID` if not set.
+    pub fn client_id(&mut self, client_id: &str) -> &mut Self {
+        if !client_id.is_empty() {
+            self.client_id = Some(client_id.to_string());
+        }
+
+        self
+    }
+
+    /// Set tenant id of workload identity.
+    ///
+    /// We will try to load it from env `AZURE_TENANT_ID` if not set.
+    pub fn tenant_id(&mut self, tenant_id: &str) -> &mut Self {
+        if !tenant_id.is_empty() {
+            self.tenant_id = Some(tenant_id.to_string());
+        }
+
+        self
+    }
+
+    /// Set the path of federated token file of workload identity.
+    ///
+    /// We will try to load it from env `AZURE_FEDERATED_TOKEN_FILE` if not set.
+    ///
+    /// With account key and sas token not set, azblob will exchange the
+    /// federated token for a bearer token and send requests with it.
+    ///
+    /// See [Azure AD workload identity](https://learn.microsoft.com/en-us/azure/aks/workload-identity-overview)
+    /// for more info.
+    pub fn federated_token_file(&mut self, path: &str) -> &mut Self {
+        if !path.is_empty() {
+            self.federated_token_file = Some(path.to_string());
+        }
+
+        self
+    }
+
+    /// Set authority host of workload identity.
+    ///
+    /// We will try to load it from env `AZURE_AUTHORITY_HOST` if not set,
+    /// and default to `https://login.microsoftonline.com`.
+    pub fn authority_host(&mut self, authority_host: &str) -> &mut Self {
+        if !authority_host.is_empty() {
+            self.authority_host = Some(authority_host.to_string());
+        }
+
+        self
+    }
+
+    /// Load bearer token via managed identity from azure instance metadata
+    /// service.
+    ///
+    /// Managed identity can't be used together with account key, sas
+    /// token or federated token file, and will take precedence over
+    /// workload identity loaded from env.
+    ///
+    /// See [Managed identities for Azure resources](https://learn.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/overview)
+    /// for more info.
+    pub fn enable_managed_identity(&mut self) -> &mut Self {
+        self.enable_managed_identity = true;
+
+        self
+    }
+
+    /// Enable conditional write with `If-None-Match` for custom endpoints.
+    ///
+    /// It's always enabled for Azure Storage endpoints. Services that emulate
+    /// azblob may ignore this header and overwrite the existing blob, so it's
+    /// disabled by default for custom endpoints.
+    pub fn enable_write_with_if_none_match(&mut self) -> &mut Self {
+        self.enable_write_with_if_none_match = true;
+
+        self
+    }
+
+    /// Build the bearer token source if account key and sas token are not
+    /// set.
+    fn token_source(&self) -> Option<TokenSource> {
+        if self.account_key.is_some() || self.sas_token.is_some() {
+            return None;
+        }
+
+        let from_env = |v: &Option<String>, key: &str| {
+            v.clone()
+                .or_else(|| env::var(key).ok().filter(|v| !v.is_empty()))
+        };
+
+        if self.enable_managed_identity {
+            return Some(TokenSource::managed_identity(self.client_id.clone()));
+        }
+
+        let federated_token_file =
+            from_env(&self.federated_token_file, "AZURE_FEDERATED_TOKEN_FILE");
+        let tenant_id = from_env(&self.tenant_id, "AZURE_TENANT_ID");
+        let client_id = from_env(&self.client_id, "AZURE_CLIENT_ID");
+        if let (Some(federated_token_file), Some(tenant_id), Some(client_id)) =
+            (federated_token_file, tenant_id, client_id)
+        {
+            let authority_host = from_env(&self.authority_host, "AZURE_AUTHORITY_HOST")
+                .unwrap_or_else(|| DEFAULT_AUTHORITY_HOST.to_string());
+            return Some(TokenSource::WorkloadIdentity {
+                authority_host,
+                tenant_id,
+                client_id,
+                federated_token_file,
+            });
+        }
+
+        None
+    }
+
+    /// Make sure at most one auth mode is configured.
+    fn check_auth_mode(&self) -> Result<()> {
+        let modes: Vec<&str> = [
+            ("account_key", self.account_key.is_some()),
+            ("sas_token", self.sas_token.is_some()),
+            ("federated_token_file", self.federated_token_file.is_some()),
+            ("enable_managed_identity", self.enable_managed_identity),
+        ]
+        .into_iter()
+        .filter(|(_, configured)| *configured)
+        .map(|(mode, _)| mode)
+        .collect();
+
+        if modes.len() > 1 {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "auth modes are mutually exclusive",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Azblob)
+            .with_context("modes", modes.join(",")));
+        }
+
+        Ok(())
+    }
+
+    /// Set the minimum size of unsized write, a.k.a., the block size of
+    /// staged upload via Put Block and Put Block List.
+    ///
+    /// If the content length is known while writing, the block size will be
+    /// increased automatically to make sure the blob can be uploaded within
+    /// 50,000 blocks.
+    ///
+    /// Default to 8 MiB, and it should not be larger than 4000 MiB.
+    #[doc(alias = "block_size")]
+    pub fn write_min_size(&mut self, write_min_size: usize) -> &mut Self {
+        self.write_min_size = Some(write_min_size);
+
+        self
+    }
+
+    /// Set the multipart threshold of write, blobs smaller than or equal
+    /// to this size will be written in a single Put Blob request.
+    ///
+    /// Default to the same value of `write_min_size`, and it should not be
+    /// larger than 5000 MiB.
+    pub fn write_multipart_threshold(&mut self, threshold: usize) -> &mut Self {
+        self.write_multipart_threshold = Some(threshold);
+
+        self
+    }
+
+    /// Set the max number of blocks that can be uploaded concurrently
+    /// while doing staged upload.
+    ///
+    /// Default to 1 which means blocks will be uploaded one by one.
+    ///
+    /// # Notes
+    ///
+    /// Blocks are sent in the background while writing, so a failed write
+    /// could not be re-entered if this value is larger than 1.
+    pub fn write_max_concurrent_parts(&mut self, n: usize) -> &mut Self {
+        self.write_max_concurrent_parts = Some(n);
+
+        self
+    }
+
+    /// Set the default access tier for new blobs, like `Hot`, `Cool`,
+    /// `Cold` or `Archive`.
+    ///
+    /// Writes with [`OpWrite::with_storage_class`] override it. The
+    /// account default access tier will be used if not set.
+    ///
+    /// Append blobs don't support access tier, so it's ignored while
+    /// writing in append mode.
+    pub fn default_access_tier(&mut self, tier: &str) -> &mut Self {
+        if !tier.is_empty() {
+            self.default_access_tier = Some(tier.to_string())
+        }
+
+        self
+    }
+
     /// Set sas_token of this backend.
     ///
     /// - If sas_token is set, we will take user's input first.
     /// - If not, we will try to load it from environment.
     ///
+    /// - The leading `?` copied from the sas url will be trimmed.
+    ///
+    /// With sas token set, all requests will carry the sas token in query
+    /// instead of being signed, and presign will return urls with the sas
+    /// token as is.
+    ///
     /// See [Grant limited access to Azure Storage resources using shared access signatures (SAS)](https://learn.microsoft.com/en-us/azure/storage/common/storage-sas-overview)
     /// for more info.
     pub fn sas_token(&mut self, sas_token: &str) -> &mut Self {
+        let sas_token = sas_token.trim_start_matches('?');
         if !sas_token.is_empty() {
             self.sas_token = Some(sas_token.to_string());
         }
@@ -335,6 +604,24 @@ impl AzblobBuilder {
 impl Builder for AzblobBuilder {
     const SCHEME: Scheme = Scheme::Azblob;
     type Accessor = AzblobBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "container",
+        "endpoint",
+        "account_name",
+        "account_key",
+        "sas_token",
+        "client_id",
+        "tenant_id",
+        "federated_token_file",
+        "authority_host",
+        "enable_managed_identity",
+        "write_min_size",
+        "write_multipart_threshold",
+        "write_max_concurrent_parts",
+        "default_access_tier",
+        "enable_write_with_if_none_match",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = AzblobBuilder::default();
@@ -345,6 +632,29 @@ impl Builder for AzblobBuilder {
         map.get("account_name").map(|v| builder.account_name(v));
         map.get("account_key").map(|v| builder.account_key(v));
         map.get("sas_token").map(|v| builder.sas_token(v));
+        map.get("client_id").map(|v| builder.client_id(v));
+        map.get("tenant_id").map(|v| builder.tenant_id(v));
+        map.get("federated_token_file")
+            .map(|v| builder.federated_token_file(v));
+        map.get("authority_host").map(|v| builder.authority_host(v));
+        map.get("enable_managed_identity")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_managed_identity());
+        map.get("write_min_size")
+            .map(|v| v.parse::<usize>().map(|v| builder.write_min_size(v)));
+        map.get("write_multipart_threshold").map(|v| {
+            v.parse::<usize>()
+                .map(|v| builder.write_multipart_threshold(v))
+        });
+        map.get("write_max_concurrent_parts").map(|v| {
+            v.parse::<usize>()
+                .map(|v| builder.write_max_concurrent_parts(v))
+        });
+        map.get("default_access_tier")
+            .map(|v| builder.default_access_tier(v));
+        map.get("enable_write_with_if_none_match")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_write_with_if_none_match());
 
         builder
     }
@@ -365,13 +675,18 @@ impl Builder for AzblobBuilder {
         debug!("backend use container {}", &container);
 
         let endpoint = match &self.endpoint {
-            Some(endpoint) => Ok(endpoint.clone()),
+            Some(endpoint) => normalize_endpoint(endpoint).map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::Azblob)
+            }),
             None => Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")
                 .with_operation("Builder::build")
                 .with_context("service", Scheme::Azblob)),
         }?;
         debug!("backend use endpoint {}", &container);
 
+        self.check_auth_mode()?;
+
         let client = if let Some(client) = self.http_client.take() {
             client
         } else {
@@ -381,33 +696,88 @@ impl Builder for AzblobBuilder {
             })?
         };
 
+        let account_name = self
+            .account_name
+            .clone()
+            .or_else(|| infer_storage_name_from_endpoint(endpoint.as_str()));
+
         let config_loader = AzureStorageConfig {
-            account_name: self
-                .account_name
-                .clone()
-                .or_else(|| infer_storage_name_from_endpoint(endpoint.as_str())),
+            account_name: account_name.clone(),
             account_key: self.account_key.clone(),
             sas_token: self.sas_token.clone(),
         };
 
         let cred_loader = AzureStorageLoader::new(config_loader);
 
+        let token_loader = self
+            .token_source()
+            .map(|source| TokenLoader::new(source, client.clone()));
+        // Presign is supported by sas token, service sas signed by account
+        // key or user delegation sas signed via bearer token.
+        let can_presign =
+            self.sas_token.is_some() || self.account_key.is_some() || token_loader.is_some();
+
         let signer = AzureStorageSigner::new();
         let batch_signer = AzureStorageSigner::new().omit_service_version();
 
+        let write_min_size = self.write_min_size.unwrap_or(DEFAULT_WRITE_MIN_SIZE);
+        if !(1..=MAX_BLOCK_SIZE).contains(&write_min_size) {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "The write minimum buffer size is misconfigured",
+            )
+            .with_context("service", Scheme::Azblob)
+            .with_context("write_min_size", write_min_size.to_string()));
+        }
+        let write_multipart_threshold = self.write_multipart_threshold.unwrap_or(write_min_size);
+        if write_multipart_threshold > MAX_PUT_BLOB_SIZE {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "The write multipart threshold is misconfigured",
+            )
+            .with_context("service", Scheme::Azblob)
+            .with_context(
+                "write_multipart_threshold",
+                write_multipart_threshold.to_string(),
+            ));
+        }
+        let write_max_concurrent_parts = self.write_max_concurrent_parts.unwrap_or(1);
+        if write_max_concurrent_parts == 0 {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "The write max concurrent parts is misconfigured",
+            )
+            .with_context("service", Scheme::Azblob)
+            .with_context("write_max_concurrent_parts", "0"));
+        }
+
+        let write_with_if_none_match = self.enable_write_with_if_none_match
+            || infer_storage_name_from_endpoint(&endpoint).is_some();
+
         debug!("backend build finished: {:?}", &self);
         Ok(AzblobBackend {
             core: Arc::new(AzblobCore {
                 root,
                 endpoint,
                 container: self.container.clone(),
+                account_name,
 
                 client,
                 loader: cred_loader,
                 signer,
                 batch_signer,
+                token_loader,
+                user_delegation_key: Mutex::new(None),
+
+                write_min_size,
+                write_multipart_threshold,
+                write_max_concurrent_parts,
+                default_access_tier: self.default_access_tier.clone(),
+                write_with_if_none_match,
+
+                batch_disabled: AtomicBool::new(false),
             }),
-            has_sas_token: self.sas_token.is_some(),
+            can_presign,
         })
     }
 }
@@ -440,7 +810,29 @@ fn infer_storage_name_from_endpoint(endpoint: &str) -> Option<String> {
 #[derive(Debug, Clone)]
 pub struct AzblobBackend {
     core: Arc<AzblobCore>,
-    has_sas_token: bool,
+    can_presign: bool,
+}
+
+impl AzblobBackend {
+    /// Delete given blobs one by one for endpoints that don't support blob batch.
+    async fn batch_delete_sequentially(&self, paths: Vec<String>) -> Result<RpBatch> {
+        let mut results = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let resp = self.core.azblob_delete_blob(&path, None).await?;
+
+            let rp = match resp.status() {
+                StatusCode::ACCEPTED | StatusCode::NOT_FOUND => {
+                    resp.into_body().consume().await?;
+                    Ok(RpDelete::default().into())
+                }
+                _ => Err(parse_error(resp).await?),
+            };
+            results.push((path, rp));
+        }
+
+        Ok(RpBatch::new(results))
+    }
 }
 
 #[async_trait]
@@ -461,6 +853,7 @@ impl Accessor for AzblobBackend {
                 stat: true,
                 stat_with_if_match: true,
                 stat_with_if_none_match: true,
+                stat_with_snapshot: true,
 
                 read: true,
                 read_can_next: true,
@@ -468,23 +861,30 @@ impl Accessor for AzblobBackend {
                 read_with_if_match: true,
                 read_with_if_none_match: true,
                 read_with_override_content_disposition: true,
+                read_with_snapshot: true,
 
                 write: true,
+                write_without_content_length: true,
                 write_with_cache_control: true,
+                write_with_append: true,
+                write_with_if_none_match: self.core.write_with_if_none_match,
                 write_with_content_type: true,
+                write_with_storage_class: true,
 
                 delete: true,
+                delete_with_snapshots: true,
                 create_dir: true,
                 copy: true,
 
                 list: true,
                 list_with_delimiter_slash: true,
                 list_without_delimiter: true,
+                list_with_snapshots: true,
 
-                presign: self.has_sas_token,
-                presign_stat: self.has_sas_token,
-                presign_read: self.has_sas_token,
-                presign_write: self.has_sas_token,
+                presign: self.can_presign,
+                presign_stat: self.can_presign,
+                presign_read: self.can_presign,
+                presign_write: self.can_presign,
 
                 batch: true,
                 batch_delete: true,
@@ -499,7 +899,7 @@ impl Accessor for AzblobBackend {
     async fn create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
         let mut req =
             self.core
-                .azblob_put_blob_request(path, Some(0), None, None, AsyncBody::Empty)?;
+                .azblob_put_blob_request(path, Some(0), None, None, None, AsyncBody::Empty)?;
 
         self.core.sign(&mut req).await?;
 
@@ -525,6 +925,7 @@ impl Accessor for AzblobBackend {
                 args.if_none_match(),
                 args.if_match(),
                 args.override_content_disposition(),
+                args.snapshot(),
             )
             .await?;
 
@@ -541,11 +942,8 @@ impl Accessor for AzblobBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        if args.content_length().is_none() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "write without content length is not supported",
-            ));
+        if let Some(access_tier) = args.storage_class() {
+            check_access_tier(access_tier)?;
         }
 
         Ok((
@@ -576,13 +974,38 @@ impl Accessor for AzblobBackend {
 
         let resp = self
             .core
-            .azblob_get_blob_properties(path, args.if_none_match(), args.if_match())
+            .azblob_get_blob_properties(
+                path,
+                args.if_none_match(),
+                args.if_match(),
+                args.snapshot(),
+            )
             .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            StatusCode::OK => {
+                let mut meta = parse_into_metadata(path, resp.headers())?;
+                if let Some(v) = parse_header_to_str(resp.headers(), constants::X_MS_ACCESS_TIER)? {
+                    meta.set_storage_class(v);
+                }
+                // Archive status is returned while the blob is being
+                // rehydrated, like `rehydrate-pending-to-hot`.
+                if let Some(v) =
+                    parse_header_to_str(resp.headers(), constants::X_MS_ARCHIVE_STATUS)?
+                {
+                    meta.set_restore_status(v);
+                }
+                // Only append blobs carry committed block count.
+                if let Some(v) =
+                    parse_header_to_str(resp.headers(), constants::X_MS_BLOB_COMMITTED_BLOCK_COUNT)?
+                        .and_then(|v| v.parse().ok())
+                {
+                    meta.set_parts_count(v);
+                }
+                Ok(RpStat::new(meta))
+            }
             StatusCode::NOT_FOUND if path.ends_with('/') => {
                 Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
             }
@@ -590,8 +1013,11 @@ impl Accessor for AzblobBackend {
         }
     }
 
-    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
-        let resp = self.core.azblob_delete_blob(path).await?;
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let resp = self
+            .core
+            .azblob_delete_blob(path, args.delete_snapshots())
+            .await?;
 
         let status = resp.status();
 
@@ -607,31 +1033,63 @@ impl Accessor for AzblobBackend {
             path.to_string(),
             args.delimiter().to_string(),
             args.limit(),
+            args.include_snapshots(),
         );
 
         Ok((RpList::default(), op))
     }
 
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
-        let mut req = match args.operation() {
-            PresignOperation::Stat(v) => {
-                self.core
-                    .azblob_head_blob_request(path, v.if_none_match(), v.if_match())?
-            }
-            PresignOperation::Read(v) => self.core.azblob_get_blob_request(
-                path,
-                v.range(),
-                v.if_none_match(),
-                v.if_match(),
+        let (mut req, permissions, content_disposition, snapshot) = match args.operation() {
+            PresignOperation::Stat(v) => (
+                self.core.azblob_head_blob_request(
+                    path,
+                    v.if_none_match(),
+                    v.if_match(),
+                    v.snapshot(),
+                )?,
+                "r",
+                None,
+                v.snapshot(),
+            ),
+            PresignOperation::Read(v) => (
+                self.core.azblob_get_blob_request(
+                    path,
+                    v.range(),
+                    v.if_none_match(),
+                    v.if_match(),
+                    v.override_content_disposition(),
+                    v.snapshot(),
+                )?,
+                "r",
                 v.override_content_disposition(),
-            )?,
-            PresignOperation::Write(_) => {
-                self.core
-                    .azblob_put_blob_request(path, None, None, None, AsyncBody::Empty)?
-            }
+                v.snapshot(),
+            ),
+            PresignOperation::Write(_) => (
+                self.core.azblob_put_blob_request(
+                    path,
+                    None,
+                    None,
+                    None,
+                    None,
+                    AsyncBody::Empty,
+                )?,
+                "cw",
+                None,
+                None,
+            ),
         };
 
-        self.core.sign_query(&mut req).await?;
+        self.core
+            .sign_query(
+                &mut req,
+                path,
+                permissions,
+                args.expire(),
+                content_disposition,
+                snapshot,
+            )
+            .await?;
 
         let (parts, _) = req.into_parts();
 
@@ -652,9 +1110,23 @@ impl Accessor for AzblobBackend {
             ));
         }
 
+        if self.core.batch_disabled.load(Ordering::Relaxed) {
+            return self.batch_delete_sequentially(paths).await;
+        }
+
         // construct and complete batch request
         let resp = self.core.azblob_batch_delete(&paths).await?;
 
+        if is_batch_unsupported(resp.status(), resp.headers()) {
+            warn!(
+                "azblob endpoint {} doesn't support blob batch, fallback to delete blobs one by one",
+                self.core.endpoint
+            );
+            resp.into_body().consume().await?;
+            self.core.batch_disabled.store(true, Ordering::Relaxed);
+            return self.batch_delete_sequentially(paths).await;
+        }
+
         // check response status
         if resp.status() != StatusCode::ACCEPTED {
             return Err(parse_error(resp).await?);
@@ -702,9 +1174,14 @@ impl Accessor for AzblobBackend {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::AzblobBuilder;
+    use super::TokenSource;
+    use super::DEFAULT_AUTHORITY_HOST;
     use crate::services::azblob::backend::infer_storage_name_from_endpoint;
     use crate::Builder;
+    use crate::ErrorKind;
 
     #[test]
     fn test_infer_storage_name_from_endpoint() {
@@ -720,6 +1197,29 @@ mod tests {
         assert_eq!(storage_name, Some("account".to_string()));
     }
 
+    #[test]
+    fn test_write_with_if_none_match() {
+        let cases = vec![
+            ("https://account.blob.core.windows.net", false, true),
+            ("http://127.0.0.1:10000/devstoreaccount1", false, false),
+            ("http://127.0.0.1:10000/devstoreaccount1", true, true),
+        ];
+
+        for (endpoint, enable, expected) in cases {
+            let mut builder = AzblobBuilder::default();
+            builder.endpoint(endpoint);
+            builder.container("container");
+            builder.account_name("account");
+            builder.account_key("account-key");
+            if enable {
+                builder.enable_write_with_if_none_match();
+            }
+            let azblob = builder.build().expect("build azblob should be succeeded.");
+
+            assert_eq!(azblob.core.write_with_if_none_match, expected, "{endpoint}");
+        }
+    }
+
     #[test]
     fn test_builder_from_endpoint_and_key_infer_account_name() {
         let mut azblob_builder = AzblobBuilder::default();
@@ -768,8 +1268,7 @@ mod tests {
         azblob_builder.endpoint("https://storagesample.blob.core.usgovcloudapi.net");
         azblob_builder.container("container");
         azblob_builder.account_name("storagesample");
-        azblob_builder.account_key("account-key");
-        azblob_builder.sas_token("sas");
+        azblob_builder.sas_token("?sas");
         let azblob = azblob_builder
             .build()
             .expect("build azblob should be succeeded.");
@@ -781,11 +1280,6 @@ mod tests {
 
         assert_eq!(azblob.core.container, "container".to_string());
 
-        assert_eq!(
-            azblob_builder.account_key.unwrap(),
-            "account-key".to_string()
-        );
-
         assert_eq!(azblob_builder.sas_token.unwrap(), "sas".to_string());
     }
 
@@ -866,4 +1360,116 @@ SharedAccessSignature=sv=2021-01-01&ss=b&srt=c&sp=rwdlaciytfx&se=2022-01-01T11:0
         assert_eq!(builder.account_name, None);
         assert_eq!(builder.account_key, None);
     }
+
+    #[test]
+    fn test_build_normalize_endpoint() {
+        let cases = vec![
+            (
+                "schemeless",
+                "storagesample.blob.core.windows.net",
+                "https://storagesample.blob.core.windows.net",
+            ),
+            (
+                "http",
+                "http://storagesample.blob.core.windows.net",
+                "http://storagesample.blob.core.windows.net",
+            ),
+            (
+                "https",
+                "https://storagesample.blob.core.windows.net",
+                "https://storagesample.blob.core.windows.net",
+            ),
+            (
+                "with port",
+                "http://127.0.0.1:10000/devstoreaccount1",
+                "http://127.0.0.1:10000/devstoreaccount1",
+            ),
+            (
+                "trailing slash",
+                "https://storagesample.blob.core.windows.net/",
+                "https://storagesample.blob.core.windows.net",
+            ),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut builder = AzblobBuilder::default();
+            builder.endpoint(input).container("container");
+            let azblob = builder.build().expect("build must succeed");
+
+            assert_eq!(azblob.core.endpoint, expected, "{name}");
+        }
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint("ftp://storagesample.blob.core.windows.net")
+            .container("container");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_token_source() {
+        let mut builder = AzblobBuilder::default();
+        builder.client_id("client");
+        builder.tenant_id("tenant");
+        builder.federated_token_file("/var/run/secrets/azure/tokens/azure-identity-token");
+        assert_eq!(
+            builder.token_source(),
+            Some(TokenSource::WorkloadIdentity {
+                authority_host: DEFAULT_AUTHORITY_HOST.to_string(),
+                tenant_id: "tenant".to_string(),
+                client_id: "client".to_string(),
+                federated_token_file: "/var/run/secrets/azure/tokens/azure-identity-token"
+                    .to_string(),
+            })
+        );
+
+        // Managed identity takes precedence over workload identity in env.
+        let mut builder = AzblobBuilder::default();
+        builder.enable_managed_identity();
+        builder.client_id("client");
+        assert_eq!(
+            builder.token_source(),
+            Some(TokenSource::managed_identity(Some("client".to_string())))
+        );
+
+        // Account key takes precedence over bearer token.
+        builder.account_key("account-key");
+        assert_eq!(builder.token_source(), None);
+    }
+
+    #[test]
+    fn test_auth_mode_mutually_exclusive() {
+        let cases = vec![
+            ("account_key", "sas_token"),
+            ("account_key", "enable_managed_identity"),
+            ("sas_token", "enable_managed_identity"),
+            ("sas_token", "federated_token_file"),
+            ("federated_token_file", "enable_managed_identity"),
+        ];
+
+        for (left, right) in cases {
+            let mut builder = AzblobBuilder::from_map(HashMap::from([
+                ("container".to_string(), "container".to_string()),
+                (
+                    "endpoint".to_string(),
+                    "https://storagesample.blob.core.windows.net".to_string(),
+                ),
+                (left.to_string(), "true".to_string()),
+                (right.to_string(), "true".to_string()),
+            ]));
+
+            let err = builder.build().expect_err("build must fail");
+            assert_eq!(err.kind(), ErrorKind::ConfigInvalid, "{left} and {right}");
+            assert!(err.to_string().contains(&format!("{left},{right}")));
+        }
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .container("container")
+            .endpoint("https://storagesample.blob.core.windows.net")
+            .enable_managed_identity()
+            .client_id("client");
+        builder.build().expect("build must succeed");
+    }
 }
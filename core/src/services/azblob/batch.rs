@@ -15,12 +15,27 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use http::HeaderMap;
 use http::StatusCode;
 
 use super::error::parse_http_error;
 use crate::raw::*;
 use crate::*;
 
+/// Returns whether the endpoint doesn't support blob batch.
+///
+/// Emulators like Azurite return `501 Not Implemented` for blob batch.
+pub(super) fn is_batch_unsupported(status: StatusCode, headers: &HeaderMap) -> bool {
+    if status == StatusCode::NOT_IMPLEMENTED {
+        return true;
+    }
+
+    matches!(
+        parse_header_to_str(headers, "x-ms-error-code"),
+        Ok(Some("NotImplemented" | "FeatureNotSupported"))
+    )
+}
+
 pub(super) fn parse_batch_delete_response(
     boundary: &str,
     body: String,
@@ -91,6 +106,19 @@ pub(super) fn parse_batch_delete_response(
 mod test {
     use super::*;
 
+    #[test]
+    fn test_is_batch_unsupported() {
+        let mut headers = HeaderMap::new();
+        assert!(is_batch_unsupported(StatusCode::NOT_IMPLEMENTED, &headers));
+        assert!(!is_batch_unsupported(StatusCode::BAD_REQUEST, &headers));
+
+        headers.insert("x-ms-error-code", "FeatureNotSupported".parse().unwrap());
+        assert!(is_batch_unsupported(StatusCode::BAD_REQUEST, &headers));
+
+        headers.insert("x-ms-error-code", "AuthenticationFailed".parse().unwrap());
+        assert!(!is_batch_unsupported(StatusCode::FORBIDDEN, &headers));
+    }
+
     #[test]
     fn test_break_down_batch() {
         // the last item in batch is a mocked response.
@@ -19,8 +19,15 @@ use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::Buf;
+use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Utc;
 use http::header::HeaderName;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
@@ -28,28 +35,67 @@ use http::header::IF_MATCH;
 use http::header::IF_NONE_MATCH;
 use http::Request;
 use http::Response;
+use http::StatusCode;
 use reqsign::AzureStorageCredential;
 use reqsign::AzureStorageLoader;
 use reqsign::AzureStorageSigner;
-
+use serde::Serialize;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::credential::TokenLoader;
+use super::error::parse_error;
+use super::sas::apply_sas_token;
+use super::sas::format_sas_datetime;
+use super::sas::BlobSas;
+use super::sas::UserDelegationKey;
+use crate::ops::DeleteSnapshots;
 use crate::raw::*;
 use crate::*;
 
-mod constants {
+/// User delegation key is valid for at most 7 days.
+const MAX_USER_DELEGATION_KEY_DAYS: i64 = 7;
+
+pub mod constants {
     pub const X_MS_BLOB_TYPE: &str = "x-ms-blob-type";
     pub const X_MS_COPY_SOURCE: &str = "x-ms-copy-source";
     pub const X_MS_BLOB_CACHE_CONTROL: &str = "x-ms-blob-cache-control";
+    pub const X_MS_BLOB_CONTENT_TYPE: &str = "x-ms-blob-content-type";
+    pub const X_MS_BLOB_COMMITTED_BLOCK_COUNT: &str = "x-ms-blob-committed-block-count";
+    pub const X_MS_ACCESS_TIER: &str = "x-ms-access-tier";
+    pub const X_MS_ARCHIVE_STATUS: &str = "x-ms-archive-status";
+    pub const X_MS_DELETE_SNAPSHOTS: &str = "x-ms-delete-snapshots";
 }
 
 pub struct AzblobCore {
     pub container: String,
     pub root: String,
     pub endpoint: String,
+    /// Account name is required to sign sas for presign.
+    pub account_name: Option<String>,
 
     pub client: HttpClient,
     pub loader: AzureStorageLoader,
     pub signer: AzureStorageSigner,
     pub batch_signer: AzureStorageSigner,
+    /// Loader of bearer token, requests will be signed with bearer token
+    /// instead of credential loaded by `loader` if it's set.
+    pub token_loader: Option<TokenLoader>,
+    /// Cached key to sign user delegation sas while using bearer token.
+    pub user_delegation_key: Mutex<Option<UserDelegationKey>>,
+
+    pub write_min_size: usize,
+    pub write_multipart_threshold: usize,
+    pub write_max_concurrent_parts: usize,
+    /// Access tier of new blobs like `Cool`, used while writing without
+    /// storage class.
+    pub default_access_tier: Option<String>,
+    /// Whether the endpoint honors `If-None-Match` while writing.
+    pub write_with_if_none_match: bool,
+
+    /// Set while the endpoint doesn't support blob batch, for example
+    /// Azurite, so that we can skip it and delete blobs one by one.
+    pub batch_disabled: AtomicBool,
 }
 
 impl Debug for AzblobCore {
@@ -80,20 +126,118 @@ impl AzblobCore {
         }
     }
 
-    pub async fn sign_query<T>(&self, req: &mut Request<T>) -> Result<()> {
-        let cred = self.load_credential().await?;
+    /// Sign request with sas in query.
+    ///
+    /// - Service sas will be generated if account key is configured.
+    /// - User delegation sas will be generated if bearer token is used.
+    /// - Configured sas token will be appended as is.
+    pub async fn sign_query<T>(
+        &self,
+        req: &mut Request<T>,
+        path: &str,
+        permissions: &str,
+        expire: Duration,
+        content_disposition: Option<&str>,
+        snapshot: Option<&str>,
+    ) -> Result<()> {
+        let p = build_abs_path(&self.root, path);
+        let expiry = Utc::now()
+            + chrono::Duration::from_std(expire).map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "presign expire is invalid").set_source(err)
+            })?;
+
+        let account_name = || {
+            self.account_name.as_deref().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "account name is required to presign",
+                )
+                .with_context("service", Scheme::Azblob)
+            })
+        };
+
+        if self.token_loader.is_some() {
+            let key = self.load_user_delegation_key(expiry).await?;
+            let sas = BlobSas {
+                account_name: account_name()?,
+                container: &self.container,
+                blob: &p,
+                permissions,
+                expiry,
+                content_disposition,
+                snapshot,
+            };
+            return apply_sas_token(req, &sas.user_delegation_token(&key)?);
+        }
 
-        self.signer
-            .sign_query(req, Duration::from_secs(3600), &cred)
-            .map_err(new_request_sign_error)
+        match self.load_credential().await? {
+            AzureStorageCredential::SharedKey(ak, sk) => {
+                let sas = BlobSas {
+                    account_name: &ak,
+                    container: &self.container,
+                    blob: &p,
+                    permissions,
+                    expiry,
+                    content_disposition,
+                    snapshot,
+                };
+                apply_sas_token(req, &sas.service_token(&sk)?)
+            }
+            cred => self
+                .signer
+                .sign_query(req, expire, &cred)
+                .map_err(new_request_sign_error),
+        }
+    }
+
+    /// Load user delegation key which is valid until given expiry.
+    async fn load_user_delegation_key(&self, expiry: DateTime<Utc>) -> Result<UserDelegationKey> {
+        let mut key = self.user_delegation_key.lock().await;
+        if let Some(v) = key.as_ref().filter(|v| v.is_valid_at(expiry)) {
+            return Ok(v.clone());
+        }
+
+        let now = Utc::now();
+        if expiry > now + chrono::Duration::days(MAX_USER_DELEGATION_KEY_DAYS) {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "user delegation sas can't be valid for more than 7 days",
+            )
+            .with_context("service", Scheme::Azblob));
+        }
+        // Request key with a longer lifetime to reuse it across presigns.
+        let key_expiry = (now + chrono::Duration::days(1)).max(expiry);
+
+        let mut req = self.azblob_get_user_delegation_key_request(now, key_expiry)?;
+        self.sign(&mut req).await?;
+
+        let resp = self.send(req).await?;
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        let loaded: UserDelegationKey =
+            quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+
+        *key = Some(loaded.clone());
+        Ok(loaded)
     }
 
     pub async fn sign<T>(&self, req: &mut Request<T>) -> Result<()> {
+        if let Some(token_loader) = &self.token_loader {
+            return token_loader.sign(req, false).await;
+        }
+
         let cred = self.load_credential().await?;
         self.signer.sign(req, &cred).map_err(new_request_sign_error)
     }
 
     async fn batch_sign<T>(&self, req: &mut Request<T>) -> Result<()> {
+        if let Some(token_loader) = &self.token_loader {
+            return token_loader.sign(req, true).await;
+        }
+
         let cred = self.load_credential().await?;
         self.batch_signer
             .sign(req, &cred)
@@ -107,6 +251,19 @@ impl AzblobCore {
 }
 
 impl AzblobCore {
+    /// Insert `x-ms-access-tier`, the given access tier overrides the
+    /// default one.
+    fn insert_access_tier_header(
+        &self,
+        req: http::request::Builder,
+        access_tier: Option<&str>,
+    ) -> http::request::Builder {
+        match access_tier.or(self.default_access_tier.as_deref()) {
+            Some(v) => req.header(constants::X_MS_ACCESS_TIER, v),
+            None => req,
+        }
+    }
+
     pub fn azblob_get_blob_request(
         &self,
         path: &str,
@@ -114,6 +271,7 @@ impl AzblobCore {
         if_none_match: Option<&str>,
         if_match: Option<&str>,
         override_content_disposition: Option<&str>,
+        snapshot: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
@@ -125,6 +283,9 @@ impl AzblobCore {
         );
 
         let mut query_args = Vec::new();
+        if let Some(snapshot) = snapshot {
+            query_args.push(format!("snapshot={}", percent_encode_path(snapshot)))
+        }
         if let Some(override_content_disposition) = override_content_disposition {
             query_args.push(format!(
                 "rscd={}",
@@ -174,6 +335,7 @@ impl AzblobCore {
         if_none_match: Option<&str>,
         if_match: Option<&str>,
         override_content_disposition: Option<&str>,
+        snapshot: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let mut req = self.azblob_get_blob_request(
             path,
@@ -181,6 +343,7 @@ impl AzblobCore {
             if_none_match,
             if_match,
             override_content_disposition,
+            snapshot,
         )?;
 
         self.sign(&mut req).await?;
@@ -194,6 +357,7 @@ impl AzblobCore {
         size: Option<usize>,
         content_type: Option<&str>,
         cache_control: Option<&str>,
+        access_tier: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -221,6 +385,7 @@ impl AzblobCore {
             HeaderName::from_static(constants::X_MS_BLOB_TYPE),
             "BlockBlob",
         );
+        req = self.insert_access_tier_header(req, access_tier);
 
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
@@ -228,20 +393,223 @@ impl AzblobCore {
         Ok(req)
     }
 
+    /// Get a key to sign user delegation sas.
+    ///
+    /// Reference: [Get User Delegation Key](https://learn.microsoft.com/en-us/rest/api/storageservices/get-user-delegation-key)
+    pub fn azblob_get_user_delegation_key_request(
+        &self,
+        start: DateTime<Utc>,
+        expiry: DateTime<Utc>,
+    ) -> Result<Request<AsyncBody>> {
+        let url = format!("{}/?restype=service&comp=userdelegationkey", self.endpoint);
+
+        let content = quick_xml::se::to_string(&KeyInfo {
+            start: format_sas_datetime(start),
+            expiry: format_sas_datetime(expiry),
+        })
+        .map_err(new_xml_deserialize_error)?;
+
+        let req = Request::post(&url)
+            .header(CONTENT_LENGTH, content.len())
+            .header(CONTENT_TYPE, "application/xml");
+
+        let req = req
+            .body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Create an empty append blob, the content will be appended by
+    /// Append Block later.
+    ///
+    /// The request carries `If-None-Match: *` so that existing blobs
+    /// will be kept untouched.
+    ///
+    /// Reference: [Put Blob](https://learn.microsoft.com/en-us/rest/api/storageservices/put-blob)
+    pub fn azblob_init_append_blob_request(
+        &self,
+        path: &str,
+        content_type: Option<&str>,
+        cache_control: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let mut req = Request::put(&url);
+        if let Some(cache_control) = cache_control {
+            req = req.header(constants::X_MS_BLOB_CACHE_CONTROL, cache_control);
+        }
+        if let Some(ty) = content_type {
+            req = req.header(constants::X_MS_BLOB_CONTENT_TYPE, ty)
+        }
+
+        req = req
+            .header(CONTENT_LENGTH, 0)
+            .header(IF_NONE_MATCH, "*")
+            .header(
+                HeaderName::from_static(constants::X_MS_BLOB_TYPE),
+                "AppendBlob",
+            );
+
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Append a block to the end of an append blob.
+    ///
+    /// Reference: [Append Block](https://learn.microsoft.com/en-us/rest/api/storageservices/append-block)
+    pub fn azblob_append_block_request(
+        &self,
+        path: &str,
+        size: u64,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}?comp=appendblock",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let req = Request::put(&url).header(CONTENT_LENGTH, size);
+
+        let req = req.body(body).map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Stage a block to be committed as part of the blob later.
+    ///
+    /// Reference: [Put Block](https://learn.microsoft.com/en-us/rest/api/storageservices/put-block)
+    pub fn azblob_put_block_request(
+        &self,
+        path: &str,
+        block_id: &str,
+        size: Option<u64>,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}?comp=block&blockid={}",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p),
+            percent_encode_path(block_id)
+        );
+
+        let mut req = Request::put(&url);
+        if let Some(size) = size {
+            req = req.header(CONTENT_LENGTH, size)
+        }
+
+        // Set body
+        let req = req.body(body).map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Commit staged blocks as the content of blob.
+    ///
+    /// Reference: [Put Block List](https://learn.microsoft.com/en-us/rest/api/storageservices/put-block-list)
+    pub fn azblob_put_block_list_request(
+        &self,
+        path: &str,
+        block_ids: &[String],
+        content_type: Option<&str>,
+        cache_control: Option<&str>,
+        access_tier: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}?comp=blocklist",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let mut req = Request::put(&url);
+        if let Some(cache_control) = cache_control {
+            req = req.header(constants::X_MS_BLOB_CACHE_CONTROL, cache_control);
+        }
+        if let Some(ty) = content_type {
+            req = req.header(constants::X_MS_BLOB_CONTENT_TYPE, ty)
+        }
+        if let Some(if_none_match) = if_none_match {
+            req = req.header(IF_NONE_MATCH, if_none_match);
+        }
+        req = self.insert_access_tier_header(req, access_tier);
+
+        let content = quick_xml::se::to_string(&PutBlockListRequest {
+            latest: block_ids.to_vec(),
+        })
+        .map_err(new_xml_deserialize_error)?;
+        // Make sure content length has been set to avoid put with chunked encoding.
+        let req = req.header(CONTENT_LENGTH, content.len());
+        let req = req.header(CONTENT_TYPE, "application/xml");
+
+        let req = req
+            .body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    pub async fn azblob_put_block_list(
+        &self,
+        path: &str,
+        block_ids: &[String],
+        content_type: Option<&str>,
+        cache_control: Option<&str>,
+        access_tier: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.azblob_put_block_list_request(
+            path,
+            block_ids,
+            content_type,
+            cache_control,
+            access_tier,
+            if_none_match,
+        )?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
     pub fn azblob_head_blob_request(
         &self,
         path: &str,
         if_none_match: Option<&str>,
         if_match: Option<&str>,
+        snapshot: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/{}/{}",
             self.endpoint,
             self.container,
             percent_encode_path(&p)
         );
+        if let Some(snapshot) = snapshot {
+            write!(url, "?snapshot={}", percent_encode_path(snapshot))
+                .expect("write into string must succeed");
+        }
 
         let mut req = Request::head(&url);
 
@@ -265,14 +633,19 @@ impl AzblobCore {
         path: &str,
         if_none_match: Option<&str>,
         if_match: Option<&str>,
+        snapshot: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.azblob_head_blob_request(path, if_none_match, if_match)?;
+        let mut req = self.azblob_head_blob_request(path, if_none_match, if_match, snapshot)?;
 
         self.sign(&mut req).await?;
         self.send(req).await
     }
 
-    pub fn azblob_delete_blob_request(&self, path: &str) -> Result<Request<AsyncBody>> {
+    pub fn azblob_delete_blob_request(
+        &self,
+        path: &str,
+        delete_snapshots: Option<DeleteSnapshots>,
+    ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
         let url = format!(
@@ -282,15 +655,28 @@ impl AzblobCore {
             percent_encode_path(&p)
         );
 
-        let req = Request::delete(&url);
+        let mut req = Request::delete(&url);
+
+        // Blob with snapshots can't be deleted without this header.
+        if let Some(v) = delete_snapshots {
+            let v = match v {
+                DeleteSnapshots::Include => "include",
+                DeleteSnapshots::Only => "only",
+            };
+            req = req.header(constants::X_MS_DELETE_SNAPSHOTS, v);
+        }
 
         req.header(CONTENT_LENGTH, 0)
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)
     }
 
-    pub async fn azblob_delete_blob(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.azblob_delete_blob_request(path)?;
+    pub async fn azblob_delete_blob(
+        &self,
+        path: &str,
+        delete_snapshots: Option<DeleteSnapshots>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.azblob_delete_blob_request(path, delete_snapshots)?;
 
         self.sign(&mut req).await?;
         self.send(req).await
@@ -333,6 +719,7 @@ impl AzblobCore {
         next_marker: &str,
         delimiter: &str,
         limit: Option<usize>,
+        include_snapshots: bool,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
@@ -353,6 +740,9 @@ impl AzblobCore {
         if !next_marker.is_empty() {
             write!(url, "&marker={next_marker}").expect("write into string must succeed");
         }
+        if include_snapshots {
+            write!(url, "&include=snapshots").expect("write into string must succeed");
+        }
 
         let mut req = Request::get(&url)
             .body(AsyncBody::Empty)
@@ -374,7 +764,7 @@ impl AzblobCore {
         let mut multipart = Multipart::new();
 
         for (idx, path) in paths.iter().enumerate() {
-            let mut req = self.azblob_delete_blob_request(path)?;
+            let mut req = self.azblob_delete_blob_request(path, None)?;
 
             self.batch_sign(&mut req).await?;
             multipart = multipart.part(
@@ -389,3 +779,70 @@ impl AzblobCore {
         self.send(req).await
     }
 }
+
+/// Access tiers that can be set on block blobs.
+///
+/// Reference: <https://learn.microsoft.com/en-us/rest/api/storageservices/set-blob-tier>
+const ACCESS_TIERS: [&str; 4] = ["Hot", "Cool", "Cold", "Archive"];
+
+/// Check if given access tier is supported by azblob.
+pub fn check_access_tier(access_tier: &str) -> Result<()> {
+    if ACCESS_TIERS.contains(&access_tier) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "access tier is not supported")
+            .with_context("service", Scheme::Azblob)
+            .with_context("access_tier", access_tier),
+    )
+}
+
+/// Generate a new block id for Put Block.
+///
+/// Block ids must be base64 strings with the same length within a blob,
+/// we use uuid here to avoid conflicts with blocks staged by other writers.
+pub fn new_block_id() -> String {
+    BASE64_STANDARD.encode(Uuid::new_v4().as_simple().to_string())
+}
+
+/// Request of Put Block List.
+#[derive(Default, Debug, Serialize)]
+#[serde(default, rename = "BlockList", rename_all = "PascalCase")]
+pub struct PutBlockListRequest {
+    pub latest: Vec<String>,
+}
+
+/// Request of Get User Delegation Key.
+#[derive(Default, Debug, Serialize)]
+#[serde(default, rename = "KeyInfo", rename_all = "PascalCase")]
+pub struct KeyInfo {
+    pub start: String,
+    pub expiry: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_block_id() {
+        let id = new_block_id();
+        assert_eq!(id.len(), new_block_id().len());
+        assert_ne!(id, new_block_id());
+        assert!(BASE64_STANDARD.decode(&id).is_ok());
+    }
+
+    #[test]
+    fn test_serialize_put_block_list_request() {
+        let req = PutBlockListRequest {
+            latest: vec!["AAAAAA==".to_string(), "AQAAAA==".to_string()],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+        assert_eq!(
+            actual,
+            "<BlockList><Latest>AAAAAA==</Latest><Latest>AQAAAA==</Latest></BlockList>"
+        );
+    }
+}
@@ -0,0 +1,464 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fs;
+
+use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_TYPE;
+use http::HeaderValue;
+use http::Request;
+use log::debug;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::raw::*;
+use crate::*;
+
+/// The endpoint of azure instance metadata service.
+const IMDS_ENDPOINT: &str = "http://169.254.169.254";
+/// The default authority host of azure active directory.
+pub const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+/// The resource of azure storage that tokens are requested for.
+const STORAGE_RESOURCE: &str = "https://storage.azure.com/";
+/// The service version sent along with bearer tokens, which must be
+/// `2017-11-09` or later. Keep the same with shared key signing.
+const AZURE_VERSION: &str = "2019-12-12";
+
+const X_MS_DATE: &str = "x-ms-date";
+const X_MS_VERSION: &str = "x-ms-version";
+
+/// Tokens will be refreshed 5 minutes before they expire.
+const TOKEN_REFRESH_BUFFER_MINUTES: i64 = 5;
+
+/// TokenSource is the source to load oauth bearer token from.
+#[derive(Clone, PartialEq, Eq)]
+pub enum TokenSource {
+    /// Managed identity via azure instance metadata service.
+    ///
+    /// `client_id` is required for user-assigned identities.
+    ///
+    /// Reference: <https://learn.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/how-to-use-vm-token>
+    ManagedIdentity {
+        endpoint: String,
+        client_id: Option<String>,
+    },
+    /// Workload identity via federated token exchange.
+    ///
+    /// Reference: <https://learn.microsoft.com/en-us/azure/aks/workload-identity-overview>
+    WorkloadIdentity {
+        authority_host: String,
+        tenant_id: String,
+        client_id: String,
+        federated_token_file: String,
+    },
+}
+
+impl Debug for TokenSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenSource::ManagedIdentity { client_id, .. } => f
+                .debug_struct("ManagedIdentity")
+                .field("client_id", client_id)
+                .finish_non_exhaustive(),
+            TokenSource::WorkloadIdentity {
+                tenant_id,
+                client_id,
+                federated_token_file,
+                ..
+            } => f
+                .debug_struct("WorkloadIdentity")
+                .field("tenant_id", tenant_id)
+                .field("client_id", client_id)
+                .field("federated_token_file", federated_token_file)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl TokenSource {
+    pub fn managed_identity(client_id: Option<String>) -> Self {
+        TokenSource::ManagedIdentity {
+            endpoint: IMDS_ENDPOINT.to_string(),
+            client_id,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Token {
+    access_token: String,
+    expires_on: DateTime<Utc>,
+}
+
+impl Token {
+    fn is_valid(&self) -> bool {
+        self.expires_on - Duration::minutes(TOKEN_REFRESH_BUFFER_MINUTES) > Utc::now()
+    }
+}
+
+/// TokenLoader loads oauth bearer token for azblob and signs requests
+/// with it.
+///
+/// Tokens are cached until they are about to expire. The cache is guarded
+/// by an async mutex, so concurrent requests that find the token expired
+/// will wait for the same refresh instead of requesting at the same time.
+pub struct TokenLoader {
+    source: TokenSource,
+    client: HttpClient,
+    token: Mutex<Option<Token>>,
+}
+
+impl Debug for TokenLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenLoader")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenLoader {
+    pub fn new(source: TokenSource, client: HttpClient) -> Self {
+        Self {
+            source,
+            client,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Sign request with bearer token.
+    ///
+    /// `x-ms-version` should be omitted for sub requests of batch.
+    pub async fn sign<T>(&self, req: &mut Request<T>, omit_service_version: bool) -> Result<()> {
+        let token = self.load().await?;
+
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .map_err(|err| new_request_sign_error(err.into()))?;
+        value.set_sensitive(true);
+
+        let headers = req.headers_mut();
+        headers.insert(AUTHORIZATION, value);
+        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        headers.insert(
+            X_MS_DATE,
+            HeaderValue::from_str(&date).map_err(|err| new_request_sign_error(err.into()))?,
+        );
+        if !omit_service_version {
+            headers.insert(X_MS_VERSION, HeaderValue::from_static(AZURE_VERSION));
+        }
+
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(v) = token.as_ref().filter(|v| v.is_valid()) {
+            return Ok(v.access_token.clone());
+        }
+
+        let loaded = match &self.source {
+            TokenSource::ManagedIdentity {
+                endpoint,
+                client_id,
+            } => self.load_via_imds(endpoint, client_id.as_deref()).await,
+            TokenSource::WorkloadIdentity {
+                authority_host,
+                tenant_id,
+                client_id,
+                federated_token_file,
+            } => {
+                self.load_via_federated_token(
+                    authority_host,
+                    tenant_id,
+                    client_id,
+                    federated_token_file,
+                )
+                .await
+            }
+        }
+        .map_err(|err| {
+            err.with_operation("TokenLoader::load")
+                .with_context("service", Scheme::Azblob)
+                .with_context("source", format!("{:?}", self.source))
+        })?;
+        debug!(
+            "azblob bearer token loaded, expires at {}",
+            loaded.expires_on
+        );
+
+        let access_token = loaded.access_token.clone();
+        *token = Some(loaded);
+        Ok(access_token)
+    }
+
+    async fn load_via_imds(&self, endpoint: &str, client_id: Option<&str>) -> Result<Token> {
+        let mut url = format!(
+            "{}/metadata/identity/oauth2/token?api-version=2018-02-01&resource={}",
+            endpoint.trim_end_matches('/'),
+            percent_encode_path(STORAGE_RESOURCE)
+        );
+        if let Some(client_id) = client_id {
+            url.push_str(&format!("&client_id={}", percent_encode_path(client_id)));
+        }
+
+        let req = Request::get(&url)
+            .header("Metadata", "true")
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp: ImdsTokenResponse = self.send(req).await?;
+        let expires_on = resp.expires_on.parse::<i64>().map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "parse expires_on of token failed")
+                .with_context("expires_on", &resp.expires_on)
+                .set_source(err)
+        })?;
+        let expires_on = parse_datetime_from_from_timestamp_millis(expires_on * 1000)?;
+
+        Ok(Token {
+            access_token: resp.access_token,
+            expires_on,
+        })
+    }
+
+    async fn load_via_federated_token(
+        &self,
+        authority_host: &str,
+        tenant_id: &str,
+        client_id: &str,
+        federated_token_file: &str,
+    ) -> Result<Token> {
+        // The token file will be rotated by kubernetes, so read it every time.
+        let assertion = fs::read_to_string(federated_token_file).map_err(|err| {
+            Error::new(ErrorKind::ConfigInvalid, "read federated token file failed")
+                .with_context("federated_token_file", federated_token_file)
+                .set_source(err)
+        })?;
+
+        let url = format!(
+            "{}/{}/oauth2/v2.0/token",
+            authority_host.trim_end_matches('/'),
+            tenant_id
+        );
+        let body = format!(
+            "client_assertion_type={}&client_assertion={}&client_id={}&grant_type=client_credentials&scope={}",
+            percent_encode_path("urn:ietf:params:oauth:client-assertion-type:jwt-bearer"),
+            percent_encode_path(assertion.trim()),
+            percent_encode_path(client_id),
+            percent_encode_path(&format!("{STORAGE_RESOURCE}.default")),
+        );
+
+        let req = Request::post(&url)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(CONTENT_LENGTH, body.len())
+            .body(AsyncBody::Bytes(Bytes::from(body)))
+            .map_err(new_request_build_error)?;
+
+        let resp: FederatedTokenResponse = self.send(req).await?;
+
+        Ok(Token {
+            access_token: resp.access_token,
+            expires_on: Utc::now() + Duration::seconds(resp.expires_in),
+        })
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(&self, req: Request<AsyncBody>) -> Result<T> {
+        let resp = self.client.send(req).await?;
+        let status = resp.status();
+        let bs = resp.into_body().bytes().await?;
+
+        if !status.is_success() {
+            let mut err = Error::new(ErrorKind::PermissionDenied, "request bearer token failed")
+                .with_context("status", status.as_str())
+                .with_context("response", String::from_utf8_lossy(&bs));
+            if status.is_server_error() || status.as_u16() == 429 {
+                err = err.set_temporary();
+            }
+            return Err(err);
+        }
+
+        serde_json::from_slice(&bs).map_err(new_json_deserialize_error)
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ImdsTokenResponse {
+    access_token: String,
+    /// Seconds since epoch in string.
+    expires_on: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FederatedTokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use wiremock::matchers::body_string_contains;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_managed_identity() {
+        let server = MockServer::start().await;
+        let expires_on = (Utc::now() + Duration::hours(24)).timestamp();
+        Mock::given(method("GET"))
+            .and(path("/metadata/identity/oauth2/token"))
+            .and(header("Metadata", "true"))
+            .and(query_param("resource", STORAGE_RESOURCE))
+            .and(query_param("client_id", "test-client"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{
+  "access_token": "imds-token",
+  "expires_in": "86399",
+  "expires_on": "{expires_on}",
+  "resource": "https://storage.azure.com/",
+  "token_type": "Bearer"
+}}"#
+            )))
+            // Token should be cached across concurrent requests.
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let loader = Arc::new(TokenLoader::new(
+            TokenSource::ManagedIdentity {
+                endpoint: server.uri(),
+                client_id: Some("test-client".to_string()),
+            },
+            HttpClient::new().expect("must succeed"),
+        ));
+
+        let tasks = (0..8).map(|_| {
+            let loader = loader.clone();
+            tokio::spawn(async move {
+                let mut req = Request::get("https://account.blob.core.windows.net/container/path")
+                    .body(())
+                    .expect("must succeed");
+                loader.sign(&mut req, false).await.map(|_| req)
+            })
+        });
+        for task in tasks {
+            let req = task.await.expect("must succeed").expect("must succeed");
+            assert_eq!(
+                req.headers().get(AUTHORIZATION).expect("must exist"),
+                "Bearer imds-token"
+            );
+            assert_eq!(
+                req.headers().get(X_MS_VERSION).expect("must exist"),
+                AZURE_VERSION
+            );
+            assert!(req.headers().contains_key(X_MS_DATE));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/test-tenant/oauth2/v2.0/token"))
+            .and(body_string_contains("client_assertion=federated-token"))
+            .and(body_string_contains("client_id=test-client"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"token_type":"Bearer","expires_in":3599,"access_token":"aad-token"}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let mut file = create_temp_file("federated-token");
+        file.1
+            .write_all(b"federated-token\n")
+            .expect("must succeed");
+
+        let loader = TokenLoader::new(
+            TokenSource::WorkloadIdentity {
+                authority_host: server.uri(),
+                tenant_id: "test-tenant".to_string(),
+                client_id: "test-client".to_string(),
+                federated_token_file: file.0.clone(),
+            },
+            HttpClient::new().expect("must succeed"),
+        );
+
+        let mut req = Request::post("https://account.blob.core.windows.net/container?comp=batch")
+            .body(())
+            .expect("must succeed");
+        loader.sign(&mut req, true).await.expect("must succeed");
+        assert_eq!(
+            req.headers().get(AUTHORIZATION).expect("must exist"),
+            "Bearer aad-token"
+        );
+        assert!(!req.headers().contains_key(X_MS_VERSION));
+
+        fs::remove_file(&file.0).expect("must succeed");
+    }
+
+    #[tokio::test]
+    async fn test_token_request_failed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(
+                r#"{"error":"invalid_request","error_description":"Identity not found"}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let loader = TokenLoader::new(
+            TokenSource::ManagedIdentity {
+                endpoint: server.uri(),
+                client_id: None,
+            },
+            HttpClient::new().expect("must succeed"),
+        );
+
+        let mut req = Request::get("https://account.blob.core.windows.net/container/path")
+            .body(())
+            .expect("must succeed");
+        let err = loader
+            .sign(&mut req, false)
+            .await
+            .expect_err("sign must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(!err.is_temporary());
+        assert!(err.to_string().contains("Identity not found"));
+    }
+
+    fn create_temp_file(name: &str) -> (String, fs::File) {
+        let path = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4()));
+        let file = fs::File::create(&path).expect("must succeed");
+        (path.to_string_lossy().to_string(), file)
+    }
+}
@@ -28,6 +28,8 @@ use crate::Error;
 use crate::ErrorKind;
 use crate::Result;
 
+const X_MS_ERROR_CODE: &str = "x-ms-error-code";
+
 /// AzblobError is the error returned by azure blob service.
 #[derive(Default, Deserialize)]
 #[serde(default, rename_all = "PascalCase")]
@@ -37,6 +39,7 @@ struct AzblobError {
     query_parameter_name: String,
     query_parameter_value: String,
     reason: String,
+    authentication_error_detail: String,
 }
 
 impl Debug for AzblobError {
@@ -55,6 +58,12 @@ impl Debug for AzblobError {
         if !self.reason.is_empty() {
             de.field("reason", &self.reason);
         }
+        if !self.authentication_error_detail.is_empty() {
+            de.field(
+                "authentication_error_detail",
+                &self.authentication_error_detail.replace('\n', " "),
+            );
+        }
 
         de.finish()
     }
@@ -70,7 +79,7 @@ pub fn parse_http_error(status: StatusCode, body: &str) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
     let message = match de::from_str::<AzblobError>(body) {
-        Ok(err) => format!("{err:?}"),
+        Ok(err) => format_error_message(&err),
         Err(_) => body.to_string(),
     };
     let mut err = Error::new(kind, &message).with_context("response", body.to_string());
@@ -87,12 +96,29 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
     let bs = body.bytes().await?;
 
+    let code = parse_header_to_str(&parts.headers, X_MS_ERROR_CODE)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
     let (kind, retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
+        // Concurrent appends to the same append blob could fail with
+        // `AppendPositionConditionNotMet`, it's Ok to retry the append.
+        StatusCode::PRECONDITION_FAILED if code == "AppendPositionConditionNotMet" => {
+            (ErrorKind::ConditionNotMatch, true)
+        }
+        StatusCode::PRECONDITION_FAILED => (ErrorKind::ConditionNotMatch, false),
+        StatusCode::NOT_MODIFIED => (ErrorKind::NotModified, false),
+        // Azblob returns `409 BlobAlreadyExists` instead of `412` while
+        // writing with `If-None-Match: *` to an existing blob.
+        StatusCode::CONFLICT if code == "BlobAlreadyExists" => {
             (ErrorKind::ConditionNotMatch, false)
         }
+        // Blobs in archive tier must be rehydrated before reading.
+        StatusCode::CONFLICT if code == "BlobArchived" => (ErrorKind::Archived, false),
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -101,22 +127,18 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     };
 
     let mut message = match de::from_reader::<_, AzblobError>(bs.clone().reader()) {
-        Ok(azblob_err) => format!("{azblob_err:?}"),
+        Ok(azblob_err) => format_error_message(&azblob_err),
         Err(_) => String::from_utf8_lossy(&bs).into_owned(),
     };
     // If there is no body here, fill with error code.
-    if message.is_empty() {
-        if let Some(v) = parts.headers.get("x-ms-error-code") {
-            if let Ok(code) = v.to_str() {
-                message = format!(
-                    "{:?}",
-                    AzblobError {
-                        code: code.to_string(),
-                        ..Default::default()
-                    }
-                )
+    if message.is_empty() && !code.is_empty() {
+        message = format!(
+            "{:?}",
+            AzblobError {
+                code: code.to_string(),
+                ..Default::default()
             }
-        }
+        )
     }
 
     let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
@@ -128,6 +150,32 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     Ok(err)
 }
 
+/// The message of errors caused by expired sas token.
+pub const SAS_TOKEN_EXPIRED_ERROR_MESSAGE: &str =
+    "sas token is expired, please renew it and build a new operator";
+
+/// Give users a clear hint while the sas token is expired instead of a
+/// generic permission denied.
+fn format_error_message(err: &AzblobError) -> String {
+    if is_sas_token_expired_error(&err.code, &err.authentication_error_detail) {
+        format!("{SAS_TOKEN_EXPIRED_ERROR_MESSAGE}: {err:?}")
+    } else {
+        format!("{err:?}")
+    }
+}
+
+/// Returns whether this error is caused by expired sas token.
+///
+/// Azblob returns `AuthenticationFailed` with detail like `Signed expiry time
+/// [..] must be after signed start time [..]` or `Signature not valid in the
+/// specified time frame: Start [..] - Expiry [..] - Current [..]` for sas
+/// tokens out of their valid time range.
+pub fn is_sas_token_expired_error(code: &str, detail: &str) -> bool {
+    code == "AuthenticationFailed"
+        && (detail.contains("Signed expiry time")
+            || detail.contains("Signature not valid in the specified time frame"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +229,118 @@ mod tests {
         );
         assert_eq!(out.reason, "invalid receipt format");
     }
+
+    #[tokio::test]
+    async fn test_parse_sas_token_expired_error() {
+        let bs = bytes::Bytes::from(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Error>
+  <Code>AuthenticationFailed</Code>
+  <Message>Server failed to authenticate the request. Make sure the value of Authorization header is formed correctly including the signature.
+RequestId:a2ab2e32-f01e-0040-5ac4-7e7fd6000000
+Time:2023-05-16T08:32:21.6624932Z</Message>
+  <AuthenticationErrorDetail>Signature not valid in the specified time frame: Start [Sat, 01 Jan 2022 03:00:14 GMT] - Expiry [Sat, 01 Jan 2022 11:00:14 GMT] - Current [Tue, 16 May 2023 08:32:21 GMT]</AuthenticationErrorDetail>
+</Error>"#,
+        );
+        let resp = Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::iter(vec![Ok(bs)])),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains(SAS_TOKEN_EXPIRED_ERROR_MESSAGE));
+    }
+
+    #[tokio::test]
+    async fn test_parse_blob_already_exists_error() {
+        let resp = Response::builder()
+            .status(StatusCode::CONFLICT)
+            .header("x-ms-error-code", "BlobAlreadyExists")
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::empty()),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::ConditionNotMatch);
+
+        let resp = Response::builder()
+            .status(StatusCode::CONFLICT)
+            .header("x-ms-error-code", "LeaseIdMissing")
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::empty()),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+    }
+
+    #[tokio::test]
+    async fn test_parse_blob_archived_error() {
+        let resp = Response::builder()
+            .status(StatusCode::CONFLICT)
+            .header("x-ms-error-code", "BlobArchived")
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::empty()),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::Archived);
+        assert!(!err.is_temporary());
+    }
+
+    #[tokio::test]
+    async fn test_parse_append_position_condition_not_met_error() {
+        let resp = Response::builder()
+            .status(StatusCode::PRECONDITION_FAILED)
+            .header("x-ms-error-code", "AppendPositionConditionNotMet")
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::empty()),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::ConditionNotMatch);
+        assert!(err.is_temporary());
+    }
+
+    #[test]
+    fn test_is_sas_token_expired_error() {
+        let cases = vec![
+            (
+                "AuthenticationFailed",
+                "Signed expiry time [Sat, 01 Jan 2022 11:00:14 GMT] must be after signed start time [Sun, 02 Jan 2022 03:00:14 GMT]",
+                true,
+            ),
+            (
+                "AuthenticationFailed",
+                "Signature not valid in the specified time frame: Start [Sat, 01 Jan 2022 03:00:14 GMT] - Expiry [Sat, 01 Jan 2022 11:00:14 GMT] - Current [Tue, 16 May 2023 08:32:21 GMT]",
+                true,
+            ),
+            (
+                "AuthenticationFailed",
+                "Signature did not match. String to sign used was r",
+                false,
+            ),
+            ("AuthorizationPermissionMismatch", "", false),
+        ];
+
+        for (code, detail, expected) in cases {
+            assert_eq!(
+                is_sas_token_expired_error(code, detail),
+                expected,
+                "{detail}"
+            );
+        }
+    }
 }
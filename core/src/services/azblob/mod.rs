@@ -20,6 +20,8 @@ pub use backend::AzblobBuilder as Azblob;
 
 mod batch;
 mod core;
+mod credential;
 mod error;
 mod pager;
+mod sas;
 mod writer;
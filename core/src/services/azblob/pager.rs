@@ -33,6 +33,7 @@ pub struct AzblobPager {
     path: String,
     delimiter: String,
     limit: Option<usize>,
+    include_snapshots: bool,
 
     next_marker: String,
     done: bool,
@@ -44,12 +45,14 @@ impl AzblobPager {
         path: String,
         delimiter: String,
         limit: Option<usize>,
+        include_snapshots: bool,
     ) -> Self {
         Self {
             core,
             path,
             delimiter,
             limit,
+            include_snapshots,
 
             next_marker: "".to_string(),
             done: false,
@@ -66,7 +69,13 @@ impl oio::Page for AzblobPager {
 
         let resp = self
             .core
-            .azblob_list_blobs(&self.path, &self.next_marker, &self.delimiter, self.limit)
+            .azblob_list_blobs(
+                &self.path,
+                &self.next_marker,
+                &self.delimiter,
+                self.limit,
+                self.include_snapshots,
+            )
             .await?;
 
         if resp.status() != http::StatusCode::OK {
@@ -107,7 +116,7 @@ impl oio::Page for AzblobPager {
                 continue;
             }
 
-            let meta = Metadata::new(EntryMode::FILE)
+            let mut meta = Metadata::new(EntryMode::FILE)
                 // Keep fit with ETag header.
                 .with_etag(format!("\"{}\"", object.properties.etag.as_str()))
                 .with_content_length(object.properties.content_length)
@@ -116,6 +125,10 @@ impl oio::Page for AzblobPager {
                 .with_last_modified(parse_datetime_from_rfc2822(
                     object.properties.last_modified.as_str(),
                 )?);
+            // Only returned while listing with `include=snapshots`.
+            if let Some(v) = object.snapshot.as_deref() {
+                meta.set_snapshot(v);
+            }
 
             let de = oio::Entry::new(&build_rel_path(&self.core.root, &object.name), meta);
 
@@ -152,6 +165,7 @@ struct BlobPrefix {
 struct Blob {
     properties: Properties,
     name: String,
+    snapshot: Option<String>,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -331,6 +345,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_snapshots() {
+        let bs = Bytes::from(
+            r#"
+            <?xml version="1.0" encoding="utf-8"?>
+            <EnumerationResults ServiceEndpoint="https://test.blob.core.windows.net/" ContainerName="myazurebucket">
+                <Blobs>
+                    <Blob>
+                        <Name>dir1/file</Name>
+                        <Snapshot>2023-04-30T08:12:33.1234567Z</Snapshot>
+                        <Properties>
+                            <Last-Modified>Sun, 30 Apr 2023 08:12:33 GMT</Last-Modified>
+                            <Etag>0x8DA0A64D66790C3</Etag>
+                            <Content-Length>3</Content-Length>
+                        </Properties>
+                    </Blob>
+                    <Blob>
+                        <Name>dir1/file</Name>
+                        <Properties>
+                            <Last-Modified>Mon, 01 May 2023 08:12:33 GMT</Last-Modified>
+                            <Etag>0x8DA112702D88FE4</Etag>
+                            <Content-Length>4</Content-Length>
+                        </Properties>
+                    </Blob>
+                </Blobs>
+                <NextMarker />
+            </EnumerationResults>"#,
+        );
+        let out: Output = de::from_reader(bs.reader()).expect("must success");
+
+        assert_eq!(
+            out.blobs
+                .blob
+                .iter()
+                .map(|v| v.snapshot.clone())
+                .collect::<Vec<_>>(),
+            [Some("2023-04-30T08:12:33.1234567Z".to_string()), None]
+        );
+    }
+
     /// This case is copied from real environment for testing
     /// quick-xml overlapped-lists features. By default, quick-xml
     /// can't deserialize content with overlapped-lists.
@@ -0,0 +1,385 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use chrono::DateTime;
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
+use http::Request;
+use http::Uri;
+use percent_encoding::utf8_percent_encode;
+use percent_encoding::AsciiSet;
+use percent_encoding::NON_ALPHANUMERIC;
+use serde::Deserialize;
+use sha2::Sha256;
+
+use crate::raw::*;
+use crate::*;
+
+/// The signed version of generated sas.
+///
+/// Reference: <https://learn.microsoft.com/en-us/rest/api/storageservices/create-service-sas#version-2020-12-06-and-later>
+pub const SAS_VERSION: &str = "2020-12-06";
+
+const QUERY_ENCODE_SET: AsciiSet = NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The key returned by Get User Delegation Key, which is used to sign user
+/// delegation sas.
+///
+/// Reference: <https://learn.microsoft.com/en-us/rest/api/storageservices/get-user-delegation-key>
+#[derive(Default, Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct UserDelegationKey {
+    pub signed_oid: String,
+    pub signed_tid: String,
+    pub signed_start: String,
+    pub signed_expiry: String,
+    pub signed_service: String,
+    pub signed_version: String,
+    pub value: String,
+}
+
+impl UserDelegationKey {
+    /// Returns whether this key is still valid at given time.
+    pub fn is_valid_at(&self, t: DateTime<Utc>) -> bool {
+        parse_datetime_from_rfc3339(&self.signed_expiry)
+            .map(|expiry| expiry > t)
+            .unwrap_or_default()
+    }
+}
+
+/// Shared access signature of a blob.
+pub struct BlobSas<'a> {
+    pub account_name: &'a str,
+    pub container: &'a str,
+    /// Absolute path of the blob without leading `/`.
+    pub blob: &'a str,
+    /// Signed permissions like `r` or `cw`.
+    pub permissions: &'a str,
+    pub expiry: DateTime<Utc>,
+    /// The content disposition to override, it must be the same with
+    /// `rscd` in the request query.
+    pub content_disposition: Option<&'a str>,
+    /// The snapshot to access, it must be the same with `snapshot` in
+    /// the request query.
+    pub snapshot: Option<&'a str>,
+}
+
+impl BlobSas<'_> {
+    fn canonicalized_resource(&self) -> String {
+        format!(
+            "/blob/{}/{}/{}",
+            self.account_name, self.container, self.blob
+        )
+    }
+
+    fn signed_expiry(&self) -> String {
+        format_sas_datetime(self.expiry)
+    }
+
+    fn signed_resource(&self) -> &'static str {
+        if self.snapshot.is_some() {
+            "bs"
+        } else {
+            "b"
+        }
+    }
+
+    /// Build string to sign of service sas.
+    ///
+    /// Reference: <https://learn.microsoft.com/en-us/rest/api/storageservices/create-service-sas#version-2020-12-06-and-later>
+    fn service_string_to_sign(&self) -> String {
+        [
+            self.permissions,
+            // signedStart
+            "",
+            &self.signed_expiry(),
+            &self.canonicalized_resource(),
+            // signedIdentifier
+            "",
+            // signedIP
+            "",
+            // signedProtocol
+            "",
+            SAS_VERSION,
+            self.signed_resource(),
+            self.snapshot.unwrap_or_default(),
+            // signedEncryptionScope
+            "",
+            // rscc
+            "",
+            self.content_disposition.unwrap_or_default(),
+            // rsce
+            "",
+            // rscl
+            "",
+            // rsct
+            "",
+        ]
+        .join("\n")
+    }
+
+    /// Build string to sign of user delegation sas.
+    ///
+    /// Reference: <https://learn.microsoft.com/en-us/rest/api/storageservices/create-user-delegation-sas#version-2020-12-06-and-later>
+    fn user_delegation_string_to_sign(&self, key: &UserDelegationKey) -> String {
+        [
+            self.permissions,
+            // signedStart
+            "",
+            &self.signed_expiry(),
+            &self.canonicalized_resource(),
+            &key.signed_oid,
+            &key.signed_tid,
+            &key.signed_start,
+            &key.signed_expiry,
+            &key.signed_service,
+            &key.signed_version,
+            // signedAuthorizedUserObjectId
+            "",
+            // signedUnauthorizedUserObjectId
+            "",
+            // signedCorrelationId
+            "",
+            // signedIP
+            "",
+            // signedProtocol
+            "",
+            SAS_VERSION,
+            self.signed_resource(),
+            self.snapshot.unwrap_or_default(),
+            // signedEncryptionScope
+            "",
+            // rscc
+            "",
+            self.content_disposition.unwrap_or_default(),
+            // rsce
+            "",
+            // rscl
+            "",
+            // rsct
+            "",
+        ]
+        .join("\n")
+    }
+
+    /// Build the query pairs of service sas signed by account key.
+    pub fn service_token(&self, account_key: &str) -> Result<Vec<(&'static str, String)>> {
+        let sig = sign(account_key, &self.service_string_to_sign())?;
+
+        Ok(vec![
+            ("sv", SAS_VERSION.to_string()),
+            ("se", self.signed_expiry()),
+            ("sr", self.signed_resource().to_string()),
+            ("sp", self.permissions.to_string()),
+            ("sig", sig),
+        ])
+    }
+
+    /// Build the query pairs of user delegation sas signed by given key.
+    pub fn user_delegation_token(
+        &self,
+        key: &UserDelegationKey,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let sig = sign(&key.value, &self.user_delegation_string_to_sign(key))?;
+
+        Ok(vec![
+            ("sv", SAS_VERSION.to_string()),
+            ("se", self.signed_expiry()),
+            ("sr", self.signed_resource().to_string()),
+            ("sp", self.permissions.to_string()),
+            ("skoid", key.signed_oid.clone()),
+            ("sktid", key.signed_tid.clone()),
+            ("skt", key.signed_start.clone()),
+            ("ske", key.signed_expiry.clone()),
+            ("sks", key.signed_service.clone()),
+            ("skv", key.signed_version.clone()),
+            ("sig", sig),
+        ])
+    }
+}
+
+/// Append sas token into the query of given request.
+pub fn apply_sas_token<T>(req: &mut Request<T>, token: &[(&str, String)]) -> Result<()> {
+    let token = token
+        .iter()
+        .map(|(k, v)| format!("{k}={}", utf8_percent_encode(v, &QUERY_ENCODE_SET)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let uri = req.uri().to_string();
+    let uri = match req.uri().query() {
+        Some(_) => format!("{uri}&{token}"),
+        None => format!("{uri}?{token}"),
+    };
+
+    *req.uri_mut() = uri.parse::<Uri>().map_err(|err| {
+        Error::new(ErrorKind::Unexpected, "sas signed uri is invalid").set_source(err)
+    })?;
+    Ok(())
+}
+
+/// Format datetime in ISO 8601 UTC format like `2023-05-01T00:00:00Z`.
+pub fn format_sas_datetime(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn sign(key: &str, string_to_sign: &str) -> Result<String> {
+    let key = BASE64_STANDARD.decode(key).map_err(|err| {
+        Error::new(
+            ErrorKind::ConfigInvalid,
+            "sas signing key is not valid base64",
+        )
+        .set_source(err)
+    })?;
+
+    let mut h = Hmac::<Sha256>::new_from_slice(&key).expect("hmac accepts key of any size");
+    h.update(string_to_sign.as_bytes());
+    Ok(BASE64_STANDARD.encode(h.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn test_sas(
+        permissions: &'static str,
+        content_disposition: Option<&'static str>,
+    ) -> BlobSas<'static> {
+        BlobSas {
+            account_name: "myaccount",
+            container: "mycontainer",
+            blob: "dir/file.txt",
+            permissions,
+            expiry: Utc.with_ymd_and_hms(2023, 5, 1, 0, 0, 0).unwrap(),
+            content_disposition,
+            snapshot: None,
+        }
+    }
+
+    fn test_user_delegation_key() -> UserDelegationKey {
+        UserDelegationKey {
+            signed_oid: "11111111-1111-1111-1111-111111111111".to_string(),
+            signed_tid: "22222222-2222-2222-2222-222222222222".to_string(),
+            signed_start: "2023-04-30T00:00:00Z".to_string(),
+            signed_expiry: "2023-05-02T00:00:00Z".to_string(),
+            signed_service: "b".to_string(),
+            signed_version: "2020-12-06".to_string(),
+            value: "YWNjb3VudF9rZXkK".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_service_sas() {
+        let sas = test_sas("r", Some(r#"attachment; filename="a.txt""#));
+
+        assert_eq!(
+            sas.service_string_to_sign(),
+            "r\n\n2023-05-01T00:00:00Z\n/blob/myaccount/mycontainer/dir/file.txt\n\n\n\n2020-12-06\nb\n\n\n\nattachment; filename=\"a.txt\"\n\n\n"
+        );
+
+        let token = sas.service_token("YWNjb3VudF9rZXkK").expect("must succeed");
+        assert_eq!(
+            token,
+            vec![
+                ("sv", "2020-12-06".to_string()),
+                ("se", "2023-05-01T00:00:00Z".to_string()),
+                ("sr", "b".to_string()),
+                ("sp", "r".to_string()),
+                (
+                    "sig",
+                    "BbHd7SaJTbd3q5HPwZl5IHf/Yz8/kA1+S0vFQkbWewc=".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_delegation_sas() {
+        let sas = test_sas("cw", None);
+        let key = test_user_delegation_key();
+
+        assert_eq!(
+            sas.user_delegation_string_to_sign(&key),
+            "cw\n\n2023-05-01T00:00:00Z\n/blob/myaccount/mycontainer/dir/file.txt\n11111111-1111-1111-1111-111111111111\n22222222-2222-2222-2222-222222222222\n2023-04-30T00:00:00Z\n2023-05-02T00:00:00Z\nb\n2020-12-06\n\n\n\n\n\n2020-12-06\nb\n\n\n\n\n\n\n"
+        );
+
+        let token = sas.user_delegation_token(&key).expect("must succeed");
+        assert_eq!(
+            token.last(),
+            Some(&(
+                "sig",
+                "NaGKMIyLs7tnwJKHv/vHhe+Mme6Uis4HPXKoPEv2ihQ=".to_string()
+            ))
+        );
+        assert!(token.contains(&("skoid", key.signed_oid.clone())));
+    }
+
+    #[test]
+    fn test_snapshot_sas() {
+        let sas = BlobSas {
+            snapshot: Some("2023-04-30T08:12:33.1234567Z"),
+            ..test_sas("r", None)
+        };
+
+        assert_eq!(
+            sas.service_string_to_sign(),
+            "r\n\n2023-05-01T00:00:00Z\n/blob/myaccount/mycontainer/dir/file.txt\n\n\n\n2020-12-06\nbs\n2023-04-30T08:12:33.1234567Z\n\n\n\n\n\n"
+        );
+
+        let token = sas.service_token("YWNjb3VudF9rZXkK").expect("must succeed");
+        assert!(token.contains(&("sr", "bs".to_string())));
+    }
+
+    #[test]
+    fn test_user_delegation_key_is_valid_at() {
+        let key = test_user_delegation_key();
+
+        assert!(key.is_valid_at(Utc.with_ymd_and_hms(2023, 5, 1, 0, 0, 0).unwrap()));
+        assert!(!key.is_valid_at(Utc.with_ymd_and_hms(2023, 5, 3, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_apply_sas_token() {
+        let mut req =
+            Request::get("https://myaccount.blob.core.windows.net/mycontainer/file?rscd=inline")
+                .body(())
+                .expect("must succeed");
+
+        apply_sas_token(
+            &mut req,
+            &[
+                ("sv", "2020-12-06".to_string()),
+                ("sig", "a+b/c=".to_string()),
+            ],
+        )
+        .expect("must succeed");
+
+        assert_eq!(
+            req.uri().to_string(),
+            "https://myaccount.blob.core.windows.net/mycontainer/file?rscd=inline&sv=2020-12-06&sig=a%2Bb%2Fc%3D"
+        );
+    }
+}
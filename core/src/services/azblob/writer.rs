@@ -14,43 +14,111 @@
 // KIND, either express or implied.  See the License for the
 // specific language governing permissions and limitations
 // under the License.
-
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::stream::FuturesOrdered;
+use futures::StreamExt;
+use http::header::IF_NONE_MATCH;
 use http::StatusCode;
+use log::debug;
 
+use super::core::constants;
+use super::core::new_block_id;
 use super::core::AzblobCore;
 use super::error::parse_error;
 use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
+/// Azure Blob allows at most 50,000 committed blocks in a block blob or
+/// an append blob.
+const MAX_BLOCKS: u64 = 50_000;
+/// Azure Blob allows at most 4 MiB in a single Append Block request.
+const MAX_APPEND_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
 pub struct AzblobWriter {
     core: Arc<AzblobCore>,
 
     op: OpWrite,
     path: String,
+    /// Whether blocks have been staged for this blob.
+    staged: bool,
+
+    /// Ids of all blocks in order, including in-flight and failed ones.
+    block_ids: Vec<String>,
+    buffer: oio::VectorCursor,
+    buffer_size: usize,
+
+    /// Blocks that are uploading concurrently.
+    futures: FuturesOrdered<BoxFuture<'static, Result<()>>>,
+    /// Ids and bytes of blocks that are uploading concurrently, in the same
+    /// order of `futures`.
+    ///
+    /// Bytes are kept until the block is staged so that failed blocks can
+    /// be retried.
+    inflight: VecDeque<(String, Bytes)>,
+    /// Blocks that failed to stage, they must be staged again with the same
+    /// block id before committing the block list.
+    failed: VecDeque<(String, Bytes)>,
+
+    /// Whether the append blob has been created, only used in append mode.
+    appendable: bool,
+    /// Committed block count of the append blob returned by the last
+    /// Append Block, only used in append mode.
+    committed_blocks: u64,
 }
 
+/// # Safety
+///
+/// AzblobWriter will only be accessed by `&mut Self`
+unsafe impl Sync for AzblobWriter {}
+
 impl AzblobWriter {
     pub fn new(core: Arc<AzblobCore>, op: OpWrite, path: String) -> Self {
-        AzblobWriter { core, op, path }
+        let buffer_size = block_size(core.write_min_size, op.content_length());
+        AzblobWriter {
+            core,
+            op,
+            path,
+
+            staged: false,
+            block_ids: vec![],
+            buffer: oio::VectorCursor::new(),
+            buffer_size,
+            futures: FuturesOrdered::new(),
+            inflight: VecDeque::new(),
+            failed: VecDeque::new(),
+
+            appendable: false,
+            committed_blocks: 0,
+        }
     }
-}
 
-#[async_trait]
-impl oio::Write for AzblobWriter {
-    async fn write(&mut self, bs: Bytes) -> Result<()> {
+    async fn write_oneshot(&self, bs: Bytes) -> Result<()> {
         let mut req = self.core.azblob_put_blob_request(
             &self.path,
             Some(bs.len()),
             self.op.content_type(),
             self.op.cache_control(),
+            self.op.storage_class(),
             AsyncBody::Bytes(bs),
         )?;
 
+        if let Some(if_none_match) = self.op.if_none_match() {
+            req.headers_mut().insert(
+                IF_NONE_MATCH,
+                if_none_match.parse().map_err(|err| {
+                    Error::new(ErrorKind::Unexpected, "header value is invalid")
+                        .with_operation("AzblobWriter::write_oneshot")
+                        .set_source(err)
+                })?,
+            );
+        }
+
         self.core.sign(&mut req).await?;
 
         let resp = self.core.send(req).await?;
@@ -66,11 +134,458 @@ impl oio::Write for AzblobWriter {
         }
     }
 
+    /// Build a future to stage given bytes as the block of `block_id`.
+    fn write_block(&self, block_id: String, bs: Bytes) -> BoxFuture<'static, Result<()>> {
+        let core = self.core.clone();
+        let path = self.path.clone();
+
+        Box::pin(async move {
+            let mut req = core.azblob_put_block_request(
+                &path,
+                &block_id,
+                Some(bs.len() as u64),
+                AsyncBody::Bytes(bs),
+            )?;
+
+            core.sign(&mut req).await?;
+
+            let resp = core.send(req).await?;
+
+            let status = resp.status();
+
+            match status {
+                StatusCode::CREATED | StatusCode::OK => {
+                    resp.into_body().consume().await?;
+                    Ok(())
+                }
+                _ => Err(parse_error(resp).await?),
+            }
+        })
+    }
+
+    /// Create the append blob if it doesn't exist.
+    async fn init_append_blob(&mut self) -> Result<()> {
+        if self.appendable {
+            return Ok(());
+        }
+
+        let mut req = self.core.azblob_init_append_blob_request(
+            &self.path,
+            self.op.content_type(),
+            self.op.cache_control(),
+        )?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+            }
+            _ => {
+                let err = parse_error(resp).await?;
+                // The blob already exists, append to it directly.
+                if err.kind() != ErrorKind::ConditionNotMatch {
+                    return Err(err);
+                }
+            }
+        }
+
+        self.appendable = true;
+        Ok(())
+    }
+
+    /// Append given bytes to the end of append blob.
+    ///
+    /// Bytes larger than [`MAX_APPEND_BLOCK_SIZE`] will be split into
+    /// multiple blocks. If one of them fails, blocks before it have been
+    /// committed already.
+    async fn append(&mut self, mut bs: Bytes) -> Result<()> {
+        self.init_append_blob().await?;
+
+        while !bs.is_empty() {
+            if self.committed_blocks >= MAX_BLOCKS {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "append blob exceeds the max blocks of azblob",
+                )
+                .with_operation(oio::WriteOperation::Write)
+                .with_context("max_blocks", MAX_BLOCKS.to_string()));
+            }
+
+            let block = bs.split_to(bs.len().min(MAX_APPEND_BLOCK_SIZE));
+
+            let mut req = self.core.azblob_append_block_request(
+                &self.path,
+                block.len() as u64,
+                AsyncBody::Bytes(block),
+            )?;
+
+            self.core.sign(&mut req).await?;
+
+            let resp = self.core.send(req).await?;
+
+            let status = resp.status();
+
+            match status {
+                StatusCode::CREATED | StatusCode::OK => {
+                    if let Some(v) = parse_header_to_str(
+                        resp.headers(),
+                        constants::X_MS_BLOB_COMMITTED_BLOCK_COUNT,
+                    )?
+                    .and_then(|v| v.parse().ok())
+                    {
+                        self.committed_blocks = v;
+                    }
+                    resp.into_body().consume().await?;
+                }
+                _ => return Err(parse_error(resp).await?),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the oldest in-flight block.
+    ///
+    /// The failed block will be kept and retried by [`Self::retry_failed_blocks`].
+    async fn wait_oldest_block(&mut self) -> Result<()> {
+        let res = self.futures.next().await.expect("block future must exist");
+        let block = self.inflight.pop_front().expect("block must be in-flight");
+
+        if let Err(e) = res {
+            self.failed.push_back(block);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Wait for all concurrent uploading blocks to finish.
+    async fn wait_blocks(&mut self) -> Result<()> {
+        let mut res = Ok(());
+        while !self.futures.is_empty() {
+            if let Err(e) = self.wait_oldest_block().await {
+                res = res.and(Err(e));
+            }
+        }
+        res
+    }
+
+    /// Stage failed blocks again with the same block id.
+    async fn retry_failed_blocks(&mut self) -> Result<()> {
+        while let Some((block_id, bs)) = self.failed.front().cloned() {
+            self.write_block(block_id, bs).await?;
+            self.failed.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Returns whether we should wait for the oldest in-flight block before
+    /// accepting more data.
+    fn is_full(&self) -> bool {
+        if self.futures.is_empty() {
+            return false;
+        }
+        if self.futures.len() >= self.core.write_max_concurrent_parts {
+            return true;
+        }
+
+        match self.op.buffer_limit() {
+            Some(limit) => {
+                self.inflight.iter().map(|(_, bs)| bs.len()).sum::<usize>() + self.buffer.len()
+                    > limit
+            }
+            None => false,
+        }
+    }
+}
+
+/// Calculate the block size of staged upload.
+///
+/// If the content length is known, the block size will be increased to make
+/// sure the blob can be uploaded within [`MAX_BLOCKS`] blocks.
+fn block_size(min_size: usize, content_length: Option<u64>) -> usize {
+    match content_length {
+        Some(size) => {
+            let size = ((size + MAX_BLOCKS - 1) / MAX_BLOCKS) as usize;
+            size.max(min_size)
+        }
+        None => min_size,
+    }
+}
+
+#[async_trait]
+impl oio::Write for AzblobWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        if self.op.append() {
+            return self.append(bs).await;
+        }
+
+        if !self.staged {
+            let content_length = self.op.content_length();
+            // Write the whole blob in one request.
+            if self.buffer.is_empty() && content_length.unwrap_or_default() == bs.len() as u64 {
+                return self.write_oneshot(bs).await;
+            }
+
+            self.buffer.push(bs);
+
+            let threshold = self.core.write_multipart_threshold as u64;
+            match content_length {
+                // The blob is small enough, wait for all data to be
+                // buffered and write them in one request.
+                Some(size) if size <= threshold => {
+                    if (self.buffer.len() as u64) < size {
+                        return Ok(());
+                    }
+
+                    return match self.write_oneshot(self.buffer.peak_all()).await {
+                        Ok(_) => {
+                            self.buffer.clear();
+                            Ok(())
+                        }
+                        Err(e) => {
+                            self.buffer.pop();
+                            Err(e)
+                        }
+                    };
+                }
+                // The size is unknown, keep buffering until threshold or
+                // buffer limit is reached.
+                None if self.buffer.len() as u64
+                    <= self
+                        .op
+                        .buffer_limit()
+                        .map_or(threshold, |v| threshold.min(v as u64)) =>
+                {
+                    return Ok(())
+                }
+                _ => {}
+            }
+
+            // Staged blocks don't need to be initiated, they will be
+            // committed by Put Block List while closing.
+            self.staged = true;
+        } else {
+            // Ignore empty bytes
+            if bs.is_empty() {
+                return Ok(());
+            }
+
+            // Retry failed blocks and wait for the oldest block if there are
+            // too many blocks or bytes in flight before accepting given bs,
+            // so that write is re-enter safe.
+            self.retry_failed_blocks().await?;
+            while self.is_full() {
+                self.wait_oldest_block().await?;
+            }
+
+            self.buffer.push(bs);
+        }
+
+        // Return directly if the buffer is not full
+        if self.buffer.len() <= self.buffer_size {
+            return Ok(());
+        }
+
+        if self.block_ids.len() >= MAX_BLOCKS as usize {
+            self.buffer.pop();
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "blob exceeds the max blocks of azblob, please increase write_min_size",
+            )
+            .with_operation(oio::WriteOperation::Write)
+            .with_context("max_blocks", MAX_BLOCKS.to_string()));
+        }
+
+        let bs = self.buffer.peak_at_least(self.buffer_size);
+        let size = bs.len();
+
+        let block_id = new_block_id();
+
+        if self.core.write_max_concurrent_parts > 1 {
+            self.buffer.take(size);
+            self.futures
+                .push_back(self.write_block(block_id.clone(), bs.clone()));
+            self.inflight.push_back((block_id.clone(), bs));
+            // Keep the position of this block in the block list.
+            self.block_ids.push(block_id);
+            return Ok(());
+        }
+
+        match self.write_block(block_id.clone(), bs).await {
+            Ok(()) => {
+                self.buffer.take(size);
+                self.block_ids.push(block_id);
+                Ok(())
+            }
+            Err(e) => {
+                // If the upload fails, we should pop the given bs to make sure
+                // write is re-enter safe.
+                self.buffer.pop();
+                Err(e)
+            }
+        }
+    }
+
+    /// Staged blocks that are never committed will be garbage collected by
+    /// azblob after a week, so we only need to drop them here.
     async fn abort(&mut self) -> Result<()> {
+        self.futures = FuturesOrdered::new();
+        self.inflight.clear();
+        self.failed.clear();
+        self.block_ids.clear();
+        self.buffer.clear();
         Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
-        Ok(())
+        // Make sure the append blob is created even if nothing is written.
+        if self.op.append() {
+            return self.init_append_blob().await;
+        }
+
+        if !self.staged {
+            // Data that smaller than multipart threshold is still buffered.
+            if !self.buffer.is_empty() {
+                self.write_oneshot(self.buffer.peak_all()).await?;
+                self.buffer.clear();
+            }
+            return Ok(());
+        }
+
+        // Failed blocks will be retried below, the block list must never be
+        // committed without them.
+        if let Err(e) = self.wait_blocks().await {
+            debug!("azblob writer block upload failed, retrying: {e:?}");
+        }
+        self.retry_failed_blocks().await?;
+
+        // Make sure internal buffer has been flushed.
+        if !self.buffer.is_empty() {
+            let bs = self.buffer.peak_exact(self.buffer.len());
+
+            let block_id = new_block_id();
+            self.write_block(block_id.clone(), bs).await?;
+            self.buffer.clear();
+            self.block_ids.push(block_id);
+        }
+
+        let resp = self
+            .core
+            .azblob_put_block_list(
+                &self.path,
+                &self.block_ids,
+                self.op.content_type(),
+                self.op.cache_control(),
+                self.op.storage_class(),
+                self.op.if_none_match(),
+            )
+            .await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::method;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::super::backend::AzblobBuilder;
+    use super::*;
+
+    #[test]
+    fn test_block_size() {
+        let min_size = 8 * 1024 * 1024;
+
+        assert_eq!(block_size(min_size, None), min_size);
+        assert_eq!(block_size(min_size, Some(1024)), min_size);
+        // 1 TiB blob requires larger blocks to stay under 50,000 blocks.
+        assert_eq!(
+            block_size(min_size, Some(1024 * 1024 * 1024 * 1024)),
+            21_990_233
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_retry_failed_block() {
+        let mock_server = MockServer::start().await;
+        // The first Put Block request fails.
+        Mock::given(method("PUT"))
+            .and(query_param("comp", "block"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(query_param("comp", "block"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(query_param("comp", "blocklist"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut b = AzblobBuilder::default();
+        b.container("test");
+        b.endpoint(&mock_server.uri());
+        b.account_name("devstoreaccount1");
+        b.account_key("Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==");
+        b.write_min_size(4);
+        b.write_max_concurrent_parts(2);
+        let backend = b.build().expect("build must succeed");
+
+        let (_, mut w) = backend
+            .write("obj", OpWrite::new())
+            .await
+            .expect("write must succeed");
+        for _ in 0..2 {
+            oio::Write::write(&mut w, Bytes::from(vec![0; 5]))
+                .await
+                .expect("write must succeed");
+        }
+        oio::Write::close(&mut w)
+            .await
+            .expect("close must retry the failed block");
+
+        let reqs = mock_server
+            .received_requests()
+            .await
+            .expect("requests must be recorded");
+        let failed = reqs[0]
+            .url
+            .query_pairs()
+            .find(|(k, _)| k == "blockid")
+            .map(|(_, v)| v.to_string())
+            .expect("block id must exist");
+        let body =
+            String::from_utf8_lossy(&reqs.last().expect("must have request").body).to_string();
+        assert_eq!(body.matches("<Latest>").count(), 2, "{body}");
+        assert!(
+            body.contains(&format!("<Latest>{failed}</Latest>")),
+            "{body}"
+        );
     }
 }
@@ -220,6 +220,13 @@ impl AzdfsBuilder {
 
 impl Builder for AzdfsBuilder {
     type Accessor = AzdfsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "filesystem",
+        "endpoint",
+        "account_name",
+        "account_key",
+    ];
     const SCHEME: Scheme = Scheme::Azdfs;
 
     fn build(&mut self) -> Result<Self::Accessor> {
@@ -57,6 +57,7 @@ impl DashmapBuilder {
 impl Builder for DashmapBuilder {
     const SCHEME: Scheme = Scheme::Dashmap;
     type Accessor = DashmapBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = Self::default();
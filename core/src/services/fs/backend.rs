@@ -15,7 +15,6 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::cmp::min;
 use std::collections::HashMap;
 use std::io;
 use std::io::SeekFrom;
@@ -29,8 +28,12 @@ use log::debug;
 use tokio::fs;
 use uuid::Uuid;
 
+use super::copy::copy_file;
+use super::direct::blocking_open_direct;
+use super::direct::open_direct;
 use super::error::parse_io_error;
 use super::pager::FsPager;
+use super::pager::SymlinkOptions;
 use super::writer::FsWriter;
 use crate::ops::*;
 use crate::raw::*;
@@ -57,9 +60,129 @@ use crate::*;
 /// # Configuration
 ///
 /// - `root`: Set the work dir for backend.
+/// - `atomic_write_dir`: Set the temp dir used by atomic write.
+/// - `disable_atomic_write`: Write files in place instead of atomically.
+/// - `disable_follow_symlink`: Report symlinks themselves instead of following them.
+/// - `skip_dangling_symlink`: Skip symlinks that can't be followed while listing.
+/// - `enable_symlink_root_check`: Refuse to follow symlinks resolving outside of root.
+/// - `preserve_mtime`: Keep the modified time of source files while copying.
+/// - `dir_permissions`: Set the permission bits in octal of created dirs, like `755`.
+/// - `enable_preallocate`: Reserve space for the content length declared in write.
+/// - `enable_direct_io`: Write files with `O_DIRECT` to bypass the page cache.
+/// - `list_batch_size`: Set the max count of entries returned in one page of list.
+/// - `durability`: Set how written data is synced, one of `none`, `data` and `full`.
+/// - `create_root`: Create root while building if it doesn't exist, `true` by default.
+/// - `read_only`: Reject all operations that mutate files under root.
 ///
 /// Refer to [`FsBuilder`]'s public API docs for more information.
 ///
+/// # Atomic Write
+///
+/// By default, fs writes data into a temp file and renames it to the
+/// target path after the file has been fsynced in `close()`. Readers
+/// will never observe a partially written file, and a crash in the
+/// middle of writing leaves the old content untouched.
+///
+/// The temp file is created next to the target as
+/// `.{name}.{uuid}.opendal-tmp` unless `atomic_write_dir` is set, and
+/// `name` is truncated to keep the temp file name short. Such temp files
+/// are skipped while listing, including the ones left by a crash, which
+/// are safe to remove. `atomic_write_dir` must be on the same filesystem
+/// as `root`, otherwise the final rename can't be atomic and `build` will
+/// return an error.
+///
+/// Writing to a symlink replaces its target instead of the symlink. The
+/// permissions and owner (if permitted) of an existing target are copied
+/// to the temp file before it is renamed, but the target will be a new
+/// file and hard links to the old one won't see the new content.
+///
+/// Use [`FsBuilder::disable_atomic_write`] to write files in place.
+///
+/// # Durability
+///
+/// A successful `close()` means nothing if data is still in the page
+/// cache while the power is cut. [`FsBuilder::durability`] sets the
+/// default [`Durability`] of writes, which can be overridden by
+/// [`OpWrite::with_durability`]:
+///
+/// - [`Durability::None`]: don't sync, the fastest but data written
+///   recently could be lost or the file could be empty after a crash.
+/// - [`Durability::Data`]: `fdatasync` the file in `close()`.
+/// - [`Durability::Full`] (default): `fsync` the file in `close()`, and
+///   the parent dir after the temp file is renamed in atomic write.
+///   Flushing the writer via `AsyncWrite` will `fdatasync` the file.
+///
+/// # Root
+///
+/// Root will be created while building if it doesn't exist. Use
+/// [`FsBuilder::create_root`] with `false` to require an existing root
+/// instead, `build` will return [`ErrorKind::NotFound`] if root is
+/// missing. Either way, `build` returns [`ErrorKind::NotADirectory`] if
+/// root is not a dir.
+///
+/// [`FsBuilder::read_only`] serves a static tree: write, create_dir,
+/// delete, copy and rename will be disabled in capability and return
+/// [`ErrorKind::PermissionDenied`].
+///
+/// # Symlink
+///
+/// Symlinks are followed by default, listed symlinks will carry their
+/// targets' mode. Symlinks that can't be followed are listed as
+/// [`EntryMode::Unknown`] with [`Metadata::symlink_target`] set.
+///
+/// - [`FsBuilder::disable_follow_symlink`] reports symlinks themselves in
+///   both `stat` and `list`.
+/// - [`FsBuilder::skip_dangling_symlink`] skips symlinks that can't be
+///   followed during `list` with a warning.
+/// - [`FsBuilder::enable_symlink_root_check`] returns
+///   [`ErrorKind::PermissionDenied`] for paths resolving outside of root
+///   via symlinks, and such symlinks won't be followed during `list`.
+///
+/// # Create Dir
+///
+/// Like object storage services, `create_dir` creates all missing parent
+/// dirs too and succeeds if the dir already exists. Parent dirs created
+/// while writing, copying or renaming files are handled in the same way.
+///
+/// Created dirs use permission bits `0o777` on unix, which will be
+/// masked by the process's umask (`0o755` with the common umask `022`).
+/// Use [`FsBuilder::dir_permissions`] to override them.
+///
+/// # Copy
+///
+/// `copy` never moves data through userspace if possible. On linux, fs
+/// tries to reflink the file first which is instant on filesystems like
+/// btrfs and xfs, then falls back to `copy_file_range` and a regular copy
+/// (for example, while copying across filesystems).
+///
+/// Permissions are always copied. Use [`FsBuilder::preserve_mtime`] to
+/// copy modified time too.
+///
+/// # List
+///
+/// Entries are read from the dir in batches of `list_batch_size` (1000
+/// by default, overridden by the limit in [`OpList`]) instead of being
+/// buffered all at once. Modes of entries come from the dir entries
+/// without stat, other metadata like size and last modified time will be
+/// fetched by an extra `stat` only if users ask for them.
+///
+/// Entries removed between reading the dir and resolving them are
+/// skipped.
+///
+/// # Preallocate and Direct IO
+///
+/// Both options are designed for large sequential writes and only take
+/// effect on linux. They are ignored silently on other platforms and
+/// filesystems that don't support them, and are not used for appending.
+///
+/// - [`FsBuilder::enable_preallocate`] reserves space for the content
+///   length set in [`OpWrite`] via `fallocate` before writing, which
+///   avoids fragmentation of large files on filesystems like xfs. The
+///   file size is not changed.
+/// - [`FsBuilder::enable_direct_io`] opens files with `O_DIRECT` to avoid
+///   double caching. Data will be buffered and written in 4KiB aligned
+///   chunks, the unaligned tail is written without `O_DIRECT` in `close`.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -90,7 +213,19 @@ use crate::*;
 pub struct FsBuilder {
     root: Option<PathBuf>,
     atomic_write_dir: Option<PathBuf>,
+    disable_atomic_write: bool,
     enable_path_check: bool,
+    disable_follow_symlink: bool,
+    skip_dangling_symlink: bool,
+    enable_symlink_root_check: bool,
+    preserve_mtime: bool,
+    dir_permissions: Option<u32>,
+    enable_preallocate: bool,
+    enable_direct_io: bool,
+    list_batch_size: Option<usize>,
+    durability: Option<Durability>,
+    create_root: Option<bool>,
+    read_only: bool,
 }
 
 impl FsBuilder {
@@ -106,6 +241,9 @@ impl FsBuilder {
     }
 
     /// Set temp dir for atomic write.
+    ///
+    /// The dir must be on the same filesystem as root. If not set, temp
+    /// files will be created in the same dir of the target file.
     pub fn atomic_write_dir(&mut self, dir: &str) -> &mut Self {
         self.atomic_write_dir = if dir.is_empty() {
             None
@@ -116,6 +254,16 @@ impl FsBuilder {
         self
     }
 
+    /// Disable atomic write so that files will be written in place.
+    ///
+    /// Readers could observe partially written files, and a crash in the
+    /// middle of writing will leave a truncated file.
+    pub fn disable_atomic_write(&mut self) -> &mut Self {
+        self.disable_atomic_write = true;
+
+        self
+    }
+
     /// OpenDAL requires all input path are normalized to make sure the
     /// behavior is consistent. By enable path check, we can make sure
     /// fs will behave the same as other services.
@@ -127,11 +275,134 @@ impl FsBuilder {
 
         self
     }
+
+    /// Disable following symlinks, so that `stat` and `list` will report
+    /// symlinks themselves with their targets in metadata.
+    pub fn disable_follow_symlink(&mut self) -> &mut Self {
+        self.disable_follow_symlink = true;
+
+        self
+    }
+
+    /// Skip symlinks that can't be followed while listing instead of
+    /// returning them as [`EntryMode::Unknown`].
+    pub fn skip_dangling_symlink(&mut self) -> &mut Self {
+        self.skip_dangling_symlink = true;
+
+        self
+    }
+
+    /// Refuse to follow symlinks that resolve outside of root.
+    ///
+    /// Enabling this feature will lead to extra canonicalize calls in
+    /// all operations that follow symlinks.
+    pub fn enable_symlink_root_check(&mut self) -> &mut Self {
+        self.enable_symlink_root_check = true;
+
+        self
+    }
+
+    /// Keep the modified time of source files while copying.
+    ///
+    /// Only supported on unix, this option will be ignored on other
+    /// platforms.
+    pub fn preserve_mtime(&mut self) -> &mut Self {
+        self.preserve_mtime = true;
+
+        self
+    }
+
+    /// Set the permission bits of dirs created by fs, for example `0o750`.
+    ///
+    /// The bits are still masked by the process's umask. Only supported on
+    /// unix, this option will be ignored on other platforms.
+    pub fn dir_permissions(&mut self, mode: u32) -> &mut Self {
+        self.dir_permissions = Some(mode);
+
+        self
+    }
+
+    /// Reserve space for the content length declared in write before
+    /// writing data.
+    ///
+    /// Only supported on linux, this option will be ignored on other
+    /// platforms or filesystems that don't support `fallocate`.
+    pub fn enable_preallocate(&mut self) -> &mut Self {
+        self.enable_preallocate = true;
+
+        self
+    }
+
+    /// Open files with `O_DIRECT` while writing.
+    ///
+    /// Only supported on linux, this option will be ignored on other
+    /// platforms or filesystems that don't support `O_DIRECT`.
+    pub fn enable_direct_io(&mut self) -> &mut Self {
+        self.enable_direct_io = true;
+
+        self
+    }
+
+    /// Set the max count of entries returned in one page of list.
+    ///
+    /// Default to 1000, the limit set in [`OpList`] takes precedence.
+    pub fn list_batch_size(&mut self, size: usize) -> &mut Self {
+        self.list_batch_size = Some(size);
+
+        self
+    }
+
+    /// Set the default durability of writes.
+    ///
+    /// Default to [`Durability::Full`], the durability set in [`OpWrite`]
+    /// takes precedence.
+    pub fn durability(&mut self, durability: Durability) -> &mut Self {
+        self.durability = Some(durability);
+
+        self
+    }
+
+    /// Set whether root should be created while building if it doesn't
+    /// exist.
+    ///
+    /// Default to `true`. If set to `false`, `build` will fail with
+    /// [`ErrorKind::NotFound`] for a missing root.
+    pub fn create_root(&mut self, create: bool) -> &mut Self {
+        self.create_root = Some(create);
+
+        self
+    }
+
+    /// Set whether fs is read only.
+    ///
+    /// All operations that mutate files under root will return
+    /// [`ErrorKind::PermissionDenied`].
+    pub fn read_only(&mut self, read_only: bool) -> &mut Self {
+        self.read_only = read_only;
+
+        self
+    }
 }
 
 impl Builder for FsBuilder {
     const SCHEME: Scheme = Scheme::Fs;
     type Accessor = FsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "atomic_write_dir",
+        "disable_atomic_write",
+        "disable_follow_symlink",
+        "skip_dangling_symlink",
+        "enable_symlink_root_check",
+        "preserve_mtime",
+        "dir_permissions",
+        "enable_preallocate",
+        "enable_direct_io",
+        "list_batch_size",
+        "durability",
+        "create_root",
+        "read_only",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = FsBuilder::default();
@@ -139,6 +410,47 @@ impl Builder for FsBuilder {
         map.get("root").map(|v| builder.root(v));
         map.get("atomic_write_dir")
             .map(|v| builder.atomic_write_dir(v));
+        map.get("disable_atomic_write")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_atomic_write());
+        map.get("disable_follow_symlink")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_follow_symlink());
+        map.get("skip_dangling_symlink")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.skip_dangling_symlink());
+        map.get("enable_symlink_root_check")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_symlink_root_check());
+        map.get("preserve_mtime")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.preserve_mtime());
+        map.get("dir_permissions")
+            .and_then(|v| u32::from_str_radix(v.trim_start_matches("0o"), 8).ok())
+            .map(|v| builder.dir_permissions(v));
+        map.get("enable_preallocate")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_preallocate());
+        map.get("enable_direct_io")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_direct_io());
+        map.get("list_batch_size")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.list_batch_size(v));
+        map.get("durability")
+            .and_then(|v| match v.as_str() {
+                "none" => Some(Durability::None),
+                "data" => Some(Durability::Data),
+                "full" => Some(Durability::Full),
+                _ => None,
+            })
+            .map(|v| builder.durability(v));
+        map.get("create_root")
+            .and_then(|v| v.parse::<bool>().ok())
+            .map(|v| builder.create_root(v));
+        map.get("read_only")
+            .and_then(|v| v.parse::<bool>().ok())
+            .map(|v| builder.read_only(v));
 
         builder
     }
@@ -155,9 +467,24 @@ impl Builder for FsBuilder {
         }?;
         debug!("backend use root {}", root.to_string_lossy());
 
-        // If root dir is not exist, we must create it.
-        if let Err(e) = std::fs::metadata(&root) {
-            if e.kind() == io::ErrorKind::NotFound {
+        match std::fs::metadata(&root) {
+            Ok(meta) if !meta.is_dir() => {
+                return Err(
+                    Error::new(ErrorKind::NotADirectory, "root is not a directory")
+                        .with_operation("Builder::build")
+                        .with_context("root", root.to_string_lossy()),
+                );
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if !self.create_root.unwrap_or(true) {
+                    return Err(Error::new(ErrorKind::NotFound, "root is not exist")
+                        .with_operation("Builder::build")
+                        .with_context("root", root.to_string_lossy())
+                        .set_source(e));
+                }
+
+                // If root dir is not exist, we must create it.
                 std::fs::create_dir_all(&root).map_err(|e| {
                     Error::new(ErrorKind::Unexpected, "create root dir failed")
                         .with_operation("Builder::build")
@@ -165,6 +492,15 @@ impl Builder for FsBuilder {
                         .set_source(e)
                 })?;
             }
+            // Leave other errors like permission denied to canonicalize.
+            Err(_) => {}
+        }
+
+        if self.list_batch_size == Some(0) {
+            return Err(
+                Error::new(ErrorKind::ConfigInvalid, "list_batch_size must be positive")
+                    .with_operation("Builder::build"),
+            );
         }
 
         let atomic_write_dir = self.atomic_write_dir.take();
@@ -185,6 +521,9 @@ impl Builder for FsBuilder {
 
         // Canonicalize the root directory. This should work since we already know that we can
         // get the metadata of the path.
+        //
+        // Symlink root check compares canonicalized paths with root, so root must be
+        // canonicalized here once, otherwise a symlinked root will refuse everything.
         let root = root.canonicalize().map_err(|e| {
             Error::new(
                 ErrorKind::Unexpected,
@@ -211,11 +550,28 @@ impl Builder for FsBuilder {
             })
             .unwrap_or(Ok(None))?;
 
+        if let Some(d) = &atomic_write_dir {
+            ensure_same_filesystem(&root, d)?;
+        }
+
         debug!("backend build finished: {:?}", &self);
         Ok(FsBackend {
             root,
             atomic_write_dir,
+            atomic_write: !self.disable_atomic_write,
             enable_path_check: self.enable_path_check,
+            symlink: SymlinkOptions {
+                follow: !self.disable_follow_symlink,
+                skip_dangling: self.skip_dangling_symlink,
+                root_check: self.enable_symlink_root_check,
+            },
+            preserve_mtime: self.preserve_mtime,
+            dir_permissions: self.dir_permissions,
+            preallocate: self.enable_preallocate,
+            direct_io: self.enable_direct_io,
+            list_batch_size: self.list_batch_size,
+            durability: self.durability.unwrap_or(Durability::Full),
+            read_only: self.read_only,
         })
     }
 }
@@ -225,20 +581,226 @@ impl Builder for FsBuilder {
 pub struct FsBackend {
     root: PathBuf,
     atomic_write_dir: Option<PathBuf>,
+    atomic_write: bool,
     enable_path_check: bool,
+    symlink: SymlinkOptions,
+    preserve_mtime: bool,
+    dir_permissions: Option<u32>,
+    preallocate: bool,
+    direct_io: bool,
+    list_batch_size: Option<usize>,
+    durability: Durability,
+    read_only: bool,
+}
+
+/// Rename can't be atomic across filesystems, so atomic_write_dir must
+/// live on the same device as root.
+#[cfg(unix)]
+fn ensure_same_filesystem(root: &Path, dir: &Path) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let dev_of = |p: &Path| {
+        std::fs::metadata(p).map(|m| m.dev()).map_err(|e| {
+            Error::new(ErrorKind::Unexpected, "read metadata of dir failed")
+                .with_operation("Builder::build")
+                .with_context("path", p.to_string_lossy())
+                .set_source(e)
+        })
+    };
+
+    if dev_of(root)? != dev_of(dir)? {
+        return Err(Error::new(
+            ErrorKind::ConfigInvalid,
+            "atomic_write_dir must be on the same filesystem as root",
+        )
+        .with_operation("Builder::build")
+        .with_context("root", root.to_string_lossy())
+        .with_context("atomic_write_dir", dir.to_string_lossy()));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_same_filesystem(_: &Path, _: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Check if path resolves to a location inside root after following
+/// symlinks. Paths not exist yet are checked via their closest existing
+/// ancestors.
+pub(super) async fn is_within_root(root: &Path, p: &Path) -> Result<bool> {
+    let mut cur = p.to_path_buf();
+    loop {
+        match fs::canonicalize(&cur).await {
+            Ok(v) => return Ok(v.starts_with(root)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(parse_io_error(err)),
+        }
+
+        // Dangling symlink could be followed while creating files, so
+        // we need to check its target instead of its parent.
+        cur = match fs::read_link(&cur).await {
+            Ok(target) => cur.parent().map(|v| v.join(&target)).unwrap_or(target),
+            Err(_) => match cur.parent() {
+                Some(v) => v.to_path_buf(),
+                None => return Ok(false),
+            },
+        };
+    }
+}
+
+/// Synchronously check if path resolves to a location inside root after
+/// following symlinks.
+pub(super) fn blocking_is_within_root(root: &Path, p: &Path) -> Result<bool> {
+    let mut cur = p.to_path_buf();
+    loop {
+        match std::fs::canonicalize(&cur) {
+            Ok(v) => return Ok(v.starts_with(root)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(parse_io_error(err)),
+        }
+
+        // Dangling symlink could be followed while creating files, so
+        // we need to check its target instead of its parent.
+        cur = match std::fs::read_link(&cur) {
+            Ok(target) => cur.parent().map(|v| v.join(&target)).unwrap_or(target),
+            Err(_) => match cur.parent() {
+                Some(v) => v.to_path_buf(),
+                None => return Ok(false),
+            },
+        };
+    }
 }
 
+/// Suffix of temp files created by atomic write.
+const TMP_FILE_SUFFIX: &str = ".opendal-tmp";
+/// Max bytes of the target name kept in temp file name, so that temp file
+/// name won't exceed `NAME_MAX` (255 bytes on most filesystems).
+const TMP_FILE_NAME_MAX: usize = 128;
+/// Max symlinks followed while resolving write target, the same as the
+/// `MAXSYMLINKS` of linux.
+const MAX_SYMLINK_DEPTH: usize = 40;
+
 #[inline]
 fn tmp_file_of(path: &str) -> String {
     let name = get_basename(path);
+    let mut end = name.len().min(TMP_FILE_NAME_MAX);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
     let uuid = Uuid::new_v4().to_string();
 
-    format!("{name}.{uuid}")
+    format!("{}.{uuid}{TMP_FILE_SUFFIX}", &name[..end])
+}
+
+/// Check if the file name is a temp file created next to target by
+/// atomic write.
+pub(super) fn is_tmp_file(name: &str) -> bool {
+    name.starts_with('.') && name.ends_with(TMP_FILE_SUFFIX)
+}
+
+/// Follow symlinks of the write target so that atomic write will replace
+/// the symlink's target instead of the symlink itself.
+async fn resolve_write_target(mut p: PathBuf) -> PathBuf {
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        p = match fs::read_link(&p).await {
+            Ok(target) => p.parent().map(|v| v.join(&target)).unwrap_or(target),
+            Err(_) => break,
+        };
+    }
+    p
+}
+
+/// Synchronously follow symlinks of the write target.
+fn blocking_resolve_write_target(mut p: PathBuf) -> PathBuf {
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        p = match std::fs::read_link(&p) {
+            Ok(target) => p.parent().map(|v| v.join(&target)).unwrap_or(target),
+            Err(_) => break,
+        };
+    }
+    p
+}
+
+/// Change owner of the temp file to the owner of target. Changing owner
+/// requires privileges, so failures are ignored and the current owner is
+/// kept.
+#[cfg(unix)]
+fn copy_owner<F: std::os::unix::io::AsRawFd>(f: &F, meta: &std::fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: fd is valid during this call.
+    let ret = unsafe { libc::fchown(f.as_raw_fd(), meta.uid(), meta.gid()) };
+    if ret != 0 {
+        debug!(
+            "fs keep owner of temp file: {:?}",
+            io::Error::last_os_error()
+        );
+    }
 }
 
 impl FsBackend {
+    // Make sure fs is not read only before mutating files.
+    fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(Error::new(ErrorKind::PermissionDenied, "fs is read only")
+                .with_context("root", self.root.to_string_lossy()));
+        }
+
+        Ok(())
+    }
+
+    // Make sure path doesn't resolve outside of root via symlinks.
+    async fn check_symlink_root(&self, p: &Path) -> Result<()> {
+        if self.symlink.root_check && !is_within_root(&self.root, p).await? {
+            return Err(new_symlink_escape_error(p));
+        }
+
+        Ok(())
+    }
+
+    // Synchronously make sure path doesn't resolve outside of root via symlinks.
+    fn blocking_check_symlink_root(&self, p: &Path) -> Result<()> {
+        if self.symlink.root_check && !blocking_is_within_root(&self.root, p)? {
+            return Err(new_symlink_escape_error(p));
+        }
+
+        Ok(())
+    }
+
+    // Build the temp path next to the target for atomic write.
+    fn sibling_tmp_path(target_path: &Path, path: &str) -> PathBuf {
+        target_path.with_file_name(format!(".{}", tmp_file_of(path)))
+    }
+
     // Synchronously build write path and ensure the parent dirs created
-    fn blocking_ensure_write_abs_path(parent: &Path, path: &str) -> Result<PathBuf> {
+    /// Create dir and all its missing parents with `dir_permissions`.
+    async fn create_dir_all(&self, p: &Path) -> Result<()> {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        if let Some(mode) = self.dir_permissions {
+            builder.mode(mode);
+        }
+
+        builder.create(p).await.map_err(parse_io_error)
+    }
+
+    fn blocking_create_dir_all(&self, p: &Path) -> Result<()> {
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        if let Some(mode) = self.dir_permissions {
+            use std::os::unix::fs::DirBuilderExt;
+
+            builder.mode(mode);
+        }
+
+        builder.create(p).map_err(parse_io_error)
+    }
+
+    fn blocking_ensure_write_abs_path(&self, parent: &Path, path: &str) -> Result<PathBuf> {
         let p = parent.join(path);
 
         // Create dir before write path.
@@ -258,13 +820,13 @@ impl FsBackend {
             })?
             .to_path_buf();
 
-        std::fs::create_dir_all(parent).map_err(parse_io_error)?;
+        self.blocking_create_dir_all(&parent)?;
 
         Ok(p)
     }
 
     // Build write path and ensure the parent dirs created
-    async fn ensure_write_abs_path(parent: &Path, path: &str) -> Result<PathBuf> {
+    async fn ensure_write_abs_path(&self, parent: &Path, path: &str) -> Result<PathBuf> {
         let p = parent.join(path);
 
         // Create dir before write path.
@@ -284,10 +846,181 @@ impl FsBackend {
             })?
             .to_path_buf();
 
-        fs::create_dir_all(&parent).await.map_err(parse_io_error)?;
+        self.create_dir_all(&parent).await?;
 
         Ok(p)
     }
+
+    // Build metadata from fs metadata, target is set if path is a symlink
+    // that has not been followed.
+    fn build_metadata(
+        &self,
+        path: &str,
+        meta: std::fs::Metadata,
+        target: Option<PathBuf>,
+    ) -> Result<Metadata> {
+        let last_modified = meta
+            .modified()
+            .map(DateTime::from)
+            .map_err(parse_io_error)?;
+
+        // Symlink itself is neither a file nor a dir, and its target could
+        // be missing, so we don't check the path of it.
+        if let Some(target) = target {
+            let m = Metadata::new(EntryMode::Unknown)
+                .with_content_length(meta.len())
+                .with_last_modified(last_modified)
+                .with_symlink_target(target.to_string_lossy().to_string());
+            return Ok(with_unix_metadata(m, &meta));
+        }
+
+        if self.enable_path_check && meta.is_dir() != path.ends_with('/') {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "file mode is not match with its path",
+            ));
+        }
+
+        let mode = if meta.is_dir() {
+            EntryMode::DIR
+        } else if meta.is_file() {
+            EntryMode::FILE
+        } else {
+            EntryMode::Unknown
+        };
+
+        let m = Metadata::new(mode)
+            .with_content_length(meta.len())
+            .with_last_modified(last_modified);
+        Ok(with_unix_metadata(m, &meta))
+    }
+
+    // Apply unix permissions in `OpWrite` to the opened file, so that
+    // they won't be masked by umask.
+    async fn set_write_permissions(f: &fs::File, args: &OpWrite) -> Result<()> {
+        #[cfg(unix)]
+        if let Some(v) = args.unix_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            f.set_permissions(std::fs::Permissions::from_mode(v))
+                .await
+                .map_err(parse_io_error)?;
+        }
+        #[cfg(not(unix))]
+        let _ = (f, args);
+
+        Ok(())
+    }
+
+    // Copy permissions and owner of the existing target to the temp file
+    // which is going to replace it.
+    async fn copy_target_permissions(f: &fs::File, target: &Path) -> Result<()> {
+        let meta = match fs::metadata(target).await {
+            Ok(v) => v,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(parse_io_error(err)),
+        };
+
+        f.set_permissions(meta.permissions())
+            .await
+            .map_err(parse_io_error)?;
+        #[cfg(unix)]
+        copy_owner(f, &meta);
+
+        Ok(())
+    }
+
+    // Synchronously copy permissions and owner of the existing target to
+    // the temp file.
+    fn blocking_copy_target_permissions(f: &std::fs::File, target: &Path) -> Result<()> {
+        let meta = match std::fs::metadata(target) {
+            Ok(v) => v,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(parse_io_error(err)),
+        };
+
+        f.set_permissions(meta.permissions())
+            .map_err(parse_io_error)?;
+        #[cfg(unix)]
+        copy_owner(f, &meta);
+
+        Ok(())
+    }
+
+    // Synchronously apply unix permissions in `OpWrite` to the opened file.
+    fn blocking_set_write_permissions(f: &std::fs::File, args: &OpWrite) -> Result<()> {
+        #[cfg(unix)]
+        if let Some(v) = args.unix_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            f.set_permissions(std::fs::Permissions::from_mode(v))
+                .map_err(parse_io_error)?;
+        }
+        #[cfg(not(unix))]
+        let _ = (f, args);
+
+        Ok(())
+    }
+}
+
+/// Reserve space for `size` bytes without changing the file size.
+///
+/// Filesystems that don't support `fallocate` are ignored.
+#[cfg(target_os = "linux")]
+fn preallocate<F: std::os::unix::io::AsRawFd>(f: &F, size: u64) -> Result<()> {
+    if size == 0 {
+        return Ok(());
+    }
+
+    // SAFETY: fd is valid during this call.
+    let ret = unsafe {
+        libc::fallocate(
+            f.as_raw_fd(),
+            libc::FALLOC_FL_KEEP_SIZE,
+            0,
+            size as libc::off_t,
+        )
+    };
+    if ret == 0 {
+        return Ok(());
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EOPNOTSUPP) | Some(libc::ENOSYS) => {
+            debug!("fallocate is not supported, ignore preallocate: {err}");
+            Ok(())
+        }
+        _ => Err(parse_io_error(err)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn preallocate<F>(_: &F, _: u64) -> Result<()> {
+    Ok(())
+}
+
+fn new_symlink_escape_error(p: &Path) -> Error {
+    Error::new(
+        ErrorKind::PermissionDenied,
+        "path resolves outside of root via symlink",
+    )
+    .with_context("path", p.to_string_lossy())
+}
+
+/// Fill permissions and owner of the entry on unix.
+#[cfg(unix)]
+fn with_unix_metadata(m: Metadata, meta: &std::fs::Metadata) -> Metadata {
+    use std::os::unix::fs::MetadataExt;
+
+    m.with_unix_permissions(meta.mode() & 0o7777)
+        .with_uid(meta.uid())
+        .with_gid(meta.gid())
+}
+
+#[cfg(not(unix))]
+fn with_unix_metadata(m: Metadata, _: &std::fs::Metadata) -> Metadata {
+    m
 }
 
 #[async_trait]
@@ -305,21 +1038,25 @@ impl Accessor for FsBackend {
             .set_root(&self.root.to_string_lossy())
             .set_capability(Capability {
                 stat: true,
+                stat_with_no_follow_symlink: true,
 
                 read: true,
                 read_can_seek: true,
                 read_with_range: true,
 
-                write: true,
-                write_without_content_length: true,
-                create_dir: true,
-                delete: true,
+                write: !self.read_only,
+                write_without_content_length: !self.read_only,
+                write_with_append: !self.read_only,
+                write_with_unix_permissions: cfg!(unix) && !self.read_only,
+                write_with_durability: !self.read_only,
+                create_dir: !self.read_only,
+                delete: !self.read_only,
 
                 list: true,
                 list_with_delimiter_slash: true,
 
-                copy: true,
-                rename: true,
+                copy: !self.read_only,
+                rename: !self.read_only,
                 blocking: true,
 
                 ..Default::default()
@@ -329,9 +1066,11 @@ impl Accessor for FsBackend {
     }
 
     async fn create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
+        self.check_writable()?;
         let p = self.root.join(path.trim_end_matches('/'));
+        self.check_symlink_root(&p).await?;
 
-        fs::create_dir_all(&p).await.map_err(parse_io_error)?;
+        self.create_dir_all(&p).await?;
 
         Ok(RpCreateDir::default())
     }
@@ -349,6 +1088,7 @@ impl Accessor for FsBackend {
         use oio::ReadExt;
 
         let p = self.root.join(path.trim_end_matches('/'));
+        self.check_symlink_root(&p).await?;
 
         let mut f = fs::OpenOptions::new()
             .read(true)
@@ -381,113 +1121,151 @@ impl Accessor for FsBackend {
 
         let f = Compat::new(f);
 
-        let br = args.range();
-        let (start, end) = match (br.offset(), br.size()) {
-            // Read a specific range.
-            (Some(offset), Some(size)) => (offset, min(offset + size, total_length)),
-            // Read from offset.
-            (Some(offset), None) => (offset, total_length),
-            // Read the last size bytes.
-            (None, Some(size)) => (
-                if total_length > size {
-                    total_length - size
-                } else {
-                    0
-                },
-                total_length,
-            ),
-            // Read the whole file.
-            (None, None) => (0, total_length),
-        };
+        let range = args.range().resolve(total_length)?;
+        let (start, end) = (range.start, range.end);
 
         let mut r = oio::into_reader::from_fd(f, start, end);
 
         // Rewind to make sure we are on the correct offset.
         r.seek(SeekFrom::Start(0)).await?;
 
-        Ok((RpRead::new(end - start), r))
+        Ok((RpRead::from_range(range, total_length), r))
     }
 
-    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let (target_path, tmp_path) = if let Some(atomic_write_dir) = &self.atomic_write_dir {
-            let target_path = Self::ensure_write_abs_path(&self.root, path).await?;
-            let tmp_path =
-                Self::ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path)).await?;
-            (target_path, Some(tmp_path))
-        } else {
-            let p = Self::ensure_write_abs_path(&self.root, path).await?;
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.check_writable()?;
+        self.check_symlink_root(&self.root.join(path)).await?;
+
+        if args.append() {
+            let p = self.ensure_write_abs_path(&self.root, path).await?;
+            let f = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&p)
+                .await
+                .map_err(parse_io_error)?;
+            Self::set_write_permissions(&f, &args).await?;
+
+            let w = FsWriter::new_append(p, f)
+                .with_durability(args.durability().unwrap_or(self.durability));
+            return Ok((RpWrite::new(), w));
+        }
 
-            (p, None)
+        let mut target_path = self.ensure_write_abs_path(&self.root, path).await?;
+        if self.atomic_write {
+            target_path = resolve_write_target(target_path).await;
+        }
+        let tmp_path = match &self.atomic_write_dir {
+            _ if !self.atomic_write => None,
+            Some(atomic_write_dir) => Some(
+                self.ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path))
+                    .await?,
+            ),
+            None => Some(Self::sibling_tmp_path(&target_path, path)),
         };
 
-        let f = tokio::fs::OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(tmp_path.as_ref().unwrap_or(&target_path))
-            .await
-            .map_err(parse_io_error)?;
+        let p = tmp_path.as_ref().unwrap_or(&target_path);
+        let direct = if self.direct_io {
+            open_direct(p).await.map_err(parse_io_error)?
+        } else {
+            None
+        };
+        let (f, direct) = match direct {
+            Some((f, w)) => (f, Some(w)),
+            None => {
+                let f = fs::OpenOptions::new()
+                    .create(true)
+                    .truncate(true)
+                    .write(true)
+                    .open(p)
+                    .await
+                    .map_err(parse_io_error)?;
+                (f, None)
+            }
+        };
+        if tmp_path.is_some() {
+            Self::copy_target_permissions(&f, &target_path).await?;
+        }
+        Self::set_write_permissions(&f, &args).await?;
+        if let (true, Some(size)) = (self.preallocate, args.content_length()) {
+            preallocate(&f, size)?;
+        }
 
-        Ok((RpWrite::new(), FsWriter::new(target_path, tmp_path, f)))
+        let mut w = FsWriter::new(target_path, tmp_path, f)
+            .with_durability(args.durability().unwrap_or(self.durability));
+        if let Some(direct) = direct {
+            w = w.with_direct(direct);
+        }
+        Ok((RpWrite::new(), w))
     }
 
     async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
+        self.check_writable()?;
         let from = self.root.join(from.trim_end_matches('/'));
+        self.check_symlink_root(&from).await?;
+        self.check_symlink_root(&self.root.join(to.trim_end_matches('/')))
+            .await?;
 
         // try to get the metadata of the source file to ensure it exists
         tokio::fs::metadata(&from).await.map_err(parse_io_error)?;
 
-        let to = Self::ensure_write_abs_path(&self.root, to.trim_end_matches('/')).await?;
+        let to = self
+            .ensure_write_abs_path(&self.root, to.trim_end_matches('/'))
+            .await?;
 
-        tokio::fs::copy(from, to).await.map_err(parse_io_error)?;
+        let preserve_mtime = self.preserve_mtime;
+        tokio::task::spawn_blocking(move || copy_file(&from, &to, preserve_mtime))
+            .await
+            .map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "copy task failed")
+                    .with_operation(Operation::Copy)
+                    .set_source(err)
+            })?
+            .map_err(parse_io_error)?;
 
         Ok(RpCopy::default())
     }
 
     async fn rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
+        self.check_writable()?;
         let from = self.root.join(from.trim_end_matches('/'));
 
         // try to get the metadata of the source file to ensure it exists
         tokio::fs::metadata(&from).await.map_err(parse_io_error)?;
 
-        let to = Self::ensure_write_abs_path(&self.root, to.trim_end_matches('/')).await?;
+        self.check_symlink_root(&self.root.join(to.trim_end_matches('/')))
+            .await?;
+        let to = self
+            .ensure_write_abs_path(&self.root, to.trim_end_matches('/'))
+            .await?;
 
         tokio::fs::rename(from, to).await.map_err(parse_io_error)?;
 
         Ok(RpRename::default())
     }
 
-    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let meta = tokio::fs::metadata(&p).await.map_err(parse_io_error)?;
-
-        if self.enable_path_check && meta.is_dir() != path.ends_with('/') {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "file mode is not match with its path",
-            ));
+        let meta = if args.follow_symlink() && self.symlink.follow {
+            self.check_symlink_root(&p).await?;
+            fs::metadata(&p).await
+        } else {
+            fs::symlink_metadata(&p).await
         }
+        .map_err(parse_io_error)?;
 
-        let mode = if meta.is_dir() {
-            EntryMode::DIR
-        } else if meta.is_file() {
-            EntryMode::FILE
+        let target = if meta.file_type().is_symlink() {
+            Some(fs::read_link(&p).await.map_err(parse_io_error)?)
         } else {
-            EntryMode::Unknown
+            None
         };
-        let m = Metadata::new(mode)
-            .with_content_length(meta.len())
-            .with_last_modified(
-                meta.modified()
-                    .map(DateTime::from)
-                    .map_err(parse_io_error)?,
-            );
 
-        Ok(RpStat::new(m))
+        self.build_metadata(path, meta, target).map(RpStat::new)
     }
 
     async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        self.check_writable()?;
         let p = self.root.join(path.trim_end_matches('/'));
 
         let meta = tokio::fs::metadata(&p).await;
@@ -509,6 +1287,7 @@ impl Accessor for FsBackend {
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         let p = self.root.join(path.trim_end_matches('/'));
+        self.check_symlink_root(&p).await?;
 
         let f = match tokio::fs::read_dir(&p).await {
             Ok(rd) => rd,
@@ -521,15 +1300,18 @@ impl Accessor for FsBackend {
             }
         };
 
-        let rd = FsPager::new(&self.root, f, args.limit());
+        let limit = args.limit().or(self.list_batch_size);
+        let rd = FsPager::new(&self.root, f, limit, self.symlink);
 
         Ok((RpList::default(), Some(rd)))
     }
 
     fn blocking_create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
+        self.check_writable()?;
         let p = self.root.join(path.trim_end_matches('/'));
+        self.blocking_check_symlink_root(&p)?;
 
-        std::fs::create_dir_all(p).map_err(parse_io_error)?;
+        self.blocking_create_dir_all(&p)?;
 
         Ok(RpCreateDir::default())
     }
@@ -538,6 +1320,7 @@ impl Accessor for FsBackend {
         use oio::BlockingRead;
 
         let p = self.root.join(path.trim_end_matches('/'));
+        self.blocking_check_symlink_root(&p)?;
 
         let mut f = std::fs::OpenOptions::new()
             .read(true)
@@ -567,112 +1350,134 @@ impl Accessor for FsBackend {
             f.seek(SeekFrom::End(0)).map_err(parse_io_error)?
         };
 
-        let br = args.range();
-        let (start, end) = match (br.offset(), br.size()) {
-            // Read a specific range.
-            (Some(offset), Some(size)) => (offset, min(offset + size, total_length)),
-            // Read from offset.
-            (Some(offset), None) => (offset, total_length),
-            // Read the last size bytes.
-            (None, Some(size)) => (
-                if total_length > size {
-                    total_length - size
-                } else {
-                    0
-                },
-                total_length,
-            ),
-            // Read the whole file.
-            (None, None) => (0, total_length),
-        };
+        let range = args.range().resolve(total_length)?;
+        let (start, end) = (range.start, range.end);
 
         let mut r = oio::into_blocking_reader::from_fd(f, start, end);
 
         // Rewind to make sure we are on the correct offset.
         r.seek(SeekFrom::Start(0))?;
 
-        Ok((RpRead::new(end - start), r))
+        Ok((RpRead::from_range(range, total_length), r))
     }
 
-    fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        let (target_path, tmp_path) = if let Some(atomic_write_dir) = &self.atomic_write_dir {
-            let target_path = Self::blocking_ensure_write_abs_path(&self.root, path)?;
-            let tmp_path =
-                Self::blocking_ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path))?;
-            (target_path, Some(tmp_path))
-        } else {
-            let p = Self::blocking_ensure_write_abs_path(&self.root, path)?;
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.check_writable()?;
+        self.blocking_check_symlink_root(&self.root.join(path))?;
+
+        if args.append() {
+            let p = self.blocking_ensure_write_abs_path(&self.root, path)?;
+            let f = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&p)
+                .map_err(parse_io_error)?;
+            Self::blocking_set_write_permissions(&f, &args)?;
+
+            let w = FsWriter::new_append(p, f)
+                .with_durability(args.durability().unwrap_or(self.durability));
+            return Ok((RpWrite::new(), w));
+        }
 
-            (p, None)
+        let mut target_path = self.blocking_ensure_write_abs_path(&self.root, path)?;
+        if self.atomic_write {
+            target_path = blocking_resolve_write_target(target_path);
+        }
+        let tmp_path = match &self.atomic_write_dir {
+            _ if !self.atomic_write => None,
+            Some(atomic_write_dir) => {
+                Some(self.blocking_ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path))?)
+            }
+            None => Some(Self::sibling_tmp_path(&target_path, path)),
         };
 
-        let f = std::fs::OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(tmp_path.as_ref().unwrap_or(&target_path))
-            .map_err(parse_io_error)?;
+        let p = tmp_path.as_ref().unwrap_or(&target_path);
+        let direct = if self.direct_io {
+            blocking_open_direct(p).map_err(parse_io_error)?
+        } else {
+            None
+        };
+        let (f, direct) = match direct {
+            Some((f, w)) => (f, Some(w)),
+            None => {
+                let f = std::fs::OpenOptions::new()
+                    .create(true)
+                    .truncate(true)
+                    .write(true)
+                    .open(p)
+                    .map_err(parse_io_error)?;
+                (f, None)
+            }
+        };
+        if tmp_path.is_some() {
+            Self::blocking_copy_target_permissions(&f, &target_path)?;
+        }
+        Self::blocking_set_write_permissions(&f, &args)?;
+        if let (true, Some(size)) = (self.preallocate, args.content_length()) {
+            preallocate(&f, size)?;
+        }
 
-        Ok((RpWrite::new(), FsWriter::new(target_path, tmp_path, f)))
+        let mut w = FsWriter::new(target_path, tmp_path, f)
+            .with_durability(args.durability().unwrap_or(self.durability));
+        if let Some(direct) = direct {
+            w = w.with_direct(direct);
+        }
+        Ok((RpWrite::new(), w))
     }
 
     fn blocking_copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
+        self.check_writable()?;
         let from = self.root.join(from.trim_end_matches('/'));
+        self.blocking_check_symlink_root(&from)?;
+        self.blocking_check_symlink_root(&self.root.join(to.trim_end_matches('/')))?;
 
         // try to get the metadata of the source file to ensure it exists
         std::fs::metadata(&from).map_err(parse_io_error)?;
 
-        let to = Self::blocking_ensure_write_abs_path(&self.root, to.trim_end_matches('/'))?;
+        let to = self.blocking_ensure_write_abs_path(&self.root, to.trim_end_matches('/'))?;
 
-        std::fs::copy(from, to).map_err(parse_io_error)?;
+        copy_file(&from, &to, self.preserve_mtime).map_err(parse_io_error)?;
 
         Ok(RpCopy::default())
     }
 
     fn blocking_rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
+        self.check_writable()?;
         let from = self.root.join(from.trim_end_matches('/'));
 
         // try to get the metadata of the source file to ensure it exists
         std::fs::metadata(&from).map_err(parse_io_error)?;
 
-        let to = Self::blocking_ensure_write_abs_path(&self.root, to.trim_end_matches('/'))?;
+        self.blocking_check_symlink_root(&self.root.join(to.trim_end_matches('/')))?;
+        let to = self.blocking_ensure_write_abs_path(&self.root, to.trim_end_matches('/'))?;
 
         std::fs::rename(from, to).map_err(parse_io_error)?;
 
         Ok(RpRename::default())
     }
 
-    fn blocking_stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let meta = std::fs::metadata(p).map_err(parse_io_error)?;
-
-        if self.enable_path_check && meta.is_dir() != path.ends_with('/') {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "file mode is not match with its path",
-            ));
+        let meta = if args.follow_symlink() && self.symlink.follow {
+            self.blocking_check_symlink_root(&p)?;
+            std::fs::metadata(&p)
+        } else {
+            std::fs::symlink_metadata(&p)
         }
+        .map_err(parse_io_error)?;
 
-        let mode = if meta.is_dir() {
-            EntryMode::DIR
-        } else if meta.is_file() {
-            EntryMode::FILE
+        let target = if meta.file_type().is_symlink() {
+            Some(std::fs::read_link(&p).map_err(parse_io_error)?)
         } else {
-            EntryMode::Unknown
+            None
         };
-        let m = Metadata::new(mode)
-            .with_content_length(meta.len())
-            .with_last_modified(
-                meta.modified()
-                    .map(DateTime::from)
-                    .map_err(parse_io_error)?,
-            );
 
-        Ok(RpStat::new(m))
+        self.build_metadata(path, meta, target).map(RpStat::new)
     }
 
     fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        self.check_writable()?;
         let p = self.root.join(path.trim_end_matches('/'));
 
         let meta = std::fs::metadata(&p);
@@ -694,6 +1499,7 @@ impl Accessor for FsBackend {
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
         let p = self.root.join(path.trim_end_matches('/'));
+        self.blocking_check_symlink_root(&p)?;
 
         let f = match std::fs::read_dir(p) {
             Ok(rd) => rd,
@@ -706,7 +1512,8 @@ impl Accessor for FsBackend {
             }
         };
 
-        let rd = FsPager::new(&self.root, f, args.limit());
+        let limit = args.limit().or(self.list_batch_size);
+        let rd = FsPager::new(&self.root, f, limit, self.symlink);
 
         Ok((RpList::default(), Some(rd)))
     }
@@ -728,6 +1535,687 @@ mod tests {
             let tmp_file = tmp_file_of(path);
             assert!(tmp_file.len() > expected_prefix.len());
             assert!(tmp_file.starts_with(expected_prefix));
+            assert!(is_tmp_file(&format!(".{tmp_file}")));
         }
+
+        // Long names are truncated to keep temp file name within NAME_MAX.
+        let tmp_file = tmp_file_of(&"测".repeat(85));
+        assert!(tmp_file.len() < 255);
+        assert!(tmp_file.starts_with(&"测".repeat(42)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stat_symlink() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("create dir must succeed");
+        std::fs::write(root.join("file"), "hello").expect("write must succeed");
+        std::os::unix::fs::symlink("file", root.join("link")).expect("symlink must succeed");
+        std::os::unix::fs::symlink("not_exist", root.join("broken")).expect("symlink must succeed");
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+        let no_follow = OpStat::new().with_follow_symlink(false);
+
+        // Follow symlink by default.
+        let meta = op.stat("link").await?;
+        assert_eq!(meta.mode(), EntryMode::FILE);
+        assert_eq!(meta.content_length(), 5);
+        assert!(!meta.is_symlink());
+
+        let meta = op.stat_with("link", no_follow.clone()).await?;
+        assert!(meta.is_symlink());
+        assert_eq!(meta.mode(), EntryMode::Unknown);
+        assert_eq!(meta.symlink_target(), Some("file"));
+
+        // Broken symlink can be stat without following it.
+        let err = op.stat("broken").await.expect_err("stat must fail");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        let meta = op.stat_with("broken", no_follow.clone()).await?;
+        assert!(meta.is_symlink());
+        assert_eq!(meta.symlink_target(), Some("not_exist"));
+
+        // Regular file is not affected.
+        let meta = op.blocking().stat_with("file", no_follow)?;
+        assert_eq!(meta.mode(), EntryMode::FILE);
+        assert!(!meta.is_symlink());
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    /// Create a root with a file, a dir, an outside file and symlinks to
+    /// them, returns (root, outside).
+    #[cfg(unix)]
+    fn prepare_symlink_root() -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+        let root = base.join("root");
+        let outside = base.join("outside");
+        std::fs::create_dir_all(root.join("dir")).expect("create dir must succeed");
+        std::fs::create_dir_all(&outside).expect("create dir must succeed");
+        std::fs::write(root.join("file"), "hello").expect("write must succeed");
+        std::fs::write(outside.join("secret"), "secret").expect("write must succeed");
+
+        let symlink = |target: &Path, link: &str| {
+            std::os::unix::fs::symlink(target, root.join(link)).expect("symlink must succeed")
+        };
+        symlink(Path::new("file"), "link_file");
+        symlink(Path::new("dir"), "link_dir");
+        symlink(Path::new("not_exist"), "broken");
+        symlink(&outside.join("secret"), "escape_file");
+        symlink(&outside, "escape_dir");
+
+        (root, base)
+    }
+
+    /// List root via backend, returns sorted (path, mode, symlink target).
+    #[cfg(unix)]
+    async fn list_root(backend: &FsBackend) -> Result<Vec<(String, EntryMode, Option<String>)>> {
+        use oio::Page;
+
+        let (_, pager) = backend.list("/", OpList::new()).await?;
+        let mut pager = pager.expect("root must exist");
+
+        let mut entries = vec![];
+        while let Some(page) = pager.next().await? {
+            for e in page {
+                let meta = e.metadata();
+                let target = if meta.is_symlink() {
+                    meta.symlink_target().map(|v| v.to_string())
+                } else {
+                    None
+                };
+                entries.push((e.path().to_string(), e.mode(), target));
+            }
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(entries)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_symlink() -> Result<()> {
+        let (root, base) = prepare_symlink_root();
+        let outside = base.join("outside");
+        let root_str = root.to_string_lossy().to_string();
+        let target = |v: &Path| Some(v.to_string_lossy().to_string());
+        let entry = |path: &str, mode| (path.to_string(), mode, None);
+
+        // Follow symlinks by default, dangling ones are returned as is.
+        let mut builder = FsBuilder::default();
+        builder.root(&root_str);
+        let backend = builder.build()?;
+        assert_eq!(
+            list_root(&backend).await?,
+            vec![
+                (
+                    "broken".to_string(),
+                    EntryMode::Unknown,
+                    target(Path::new("not_exist"))
+                ),
+                entry("dir/", EntryMode::DIR),
+                entry("escape_dir/", EntryMode::DIR),
+                entry("escape_file", EntryMode::FILE),
+                entry("file", EntryMode::FILE),
+                entry("link_dir/", EntryMode::DIR),
+                entry("link_file", EntryMode::FILE),
+            ]
+        );
+
+        // Skip dangling symlinks.
+        let mut builder = FsBuilder::default();
+        builder.root(&root_str).skip_dangling_symlink();
+        let backend = builder.build()?;
+        let entries = list_root(&backend).await?;
+        assert_eq!(entries.len(), 6);
+        assert!(entries.iter().all(|(path, _, _)| path != "broken"));
+
+        // Don't follow symlinks.
+        let mut builder = FsBuilder::default();
+        builder.root(&root_str).disable_follow_symlink();
+        let backend = builder.build()?;
+        assert_eq!(
+            list_root(&backend).await?,
+            vec![
+                (
+                    "broken".to_string(),
+                    EntryMode::Unknown,
+                    target(Path::new("not_exist"))
+                ),
+                entry("dir/", EntryMode::DIR),
+                (
+                    "escape_dir".to_string(),
+                    EntryMode::Unknown,
+                    target(&outside)
+                ),
+                (
+                    "escape_file".to_string(),
+                    EntryMode::Unknown,
+                    target(&outside.join("secret"))
+                ),
+                entry("file", EntryMode::FILE),
+                (
+                    "link_dir".to_string(),
+                    EntryMode::Unknown,
+                    target(Path::new("dir"))
+                ),
+                (
+                    "link_file".to_string(),
+                    EntryMode::Unknown,
+                    target(Path::new("file"))
+                ),
+            ]
+        );
+
+        // Don't follow symlinks but skip dangling ones.
+        let mut builder = FsBuilder::default();
+        builder
+            .root(&root_str)
+            .disable_follow_symlink()
+            .skip_dangling_symlink();
+        let backend = builder.build()?;
+        let entries = list_root(&backend).await?;
+        assert_eq!(entries.len(), 6);
+        assert!(entries.iter().all(|(path, mode, _)| path == "dir/"
+            || path == "file"
+            || *mode == EntryMode::Unknown));
+
+        // Symlinks escaping root are not followed.
+        let mut builder = FsBuilder::default();
+        builder
+            .root(&root_str)
+            .skip_dangling_symlink()
+            .enable_symlink_root_check();
+        let backend = builder.build()?;
+        assert_eq!(
+            list_root(&backend).await?,
+            vec![
+                entry("dir/", EntryMode::DIR),
+                (
+                    "escape_dir".to_string(),
+                    EntryMode::Unknown,
+                    target(&outside)
+                ),
+                (
+                    "escape_file".to_string(),
+                    EntryMode::Unknown,
+                    target(&outside.join("secret"))
+                ),
+                entry("file", EntryMode::FILE),
+                entry("link_dir/", EntryMode::DIR),
+                entry("link_file", EntryMode::FILE),
+            ]
+        );
+
+        std::fs::remove_dir_all(&base).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_root_check() -> Result<()> {
+        let (root, base) = prepare_symlink_root();
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+        // Symlinks escaping root are followed by default.
+        assert_eq!(op.read("escape_file").await?, b"secret");
+
+        let mut builder = FsBuilder::default();
+        builder
+            .root(&root.to_string_lossy())
+            .enable_symlink_root_check();
+        let op = Operator::new(builder)?.finish();
+
+        // Symlinks inside root are allowed.
+        assert_eq!(op.read("link_file").await?, b"hello");
+        assert_eq!(op.stat("link_dir/").await?.mode(), EntryMode::DIR);
+        op.write("link_dir/new", "world").await?;
+        assert_eq!(op.read("dir/new").await?, b"world");
+
+        // Symlinks escaping root are refused.
+        let cases: Vec<Result<()>> = vec![
+            op.read("escape_file").await.map(|_| ()),
+            op.stat("escape_file").await.map(|_| ()),
+            op.write("escape_dir/new", "world").await,
+            op.create_dir("escape_dir/new/").await,
+            op.copy("escape_file", "copied").await,
+            op.copy("file", "escape_dir/copied").await,
+            op.rename("file", "escape_dir/renamed").await,
+            op.list("escape_dir/").await.map(|_| ()),
+            op.blocking().read("escape_file").map(|_| ()),
+            op.blocking().write("escape_dir/new", "world"),
+        ];
+        for res in cases {
+            let err = res.expect_err("must be refused");
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied, "{err}");
+        }
+        assert!(!base.join("outside").join("new").exists());
+
+        // Symlinks can still be stat without following them.
+        let meta = op
+            .stat_with("escape_file", OpStat::new().with_follow_symlink(false))
+            .await?;
+        assert!(meta.is_symlink());
+
+        std::fs::remove_dir_all(&base).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_root_check_with_symlinked_root() -> Result<()> {
+        let (root, base) = prepare_symlink_root();
+        let root_link = base.join("root_link");
+        std::os::unix::fs::symlink(&root, &root_link).expect("symlink must succeed");
+
+        let new_builder = || {
+            let mut builder = FsBuilder::default();
+            builder
+                .root(&root_link.to_string_lossy())
+                .enable_symlink_root_check();
+            builder
+        };
+        let backend = new_builder().build()?;
+
+        let entries = list_root(&backend).await?;
+        let mode_of = |path: &str| {
+            entries
+                .iter()
+                .find(|(p, _, _)| p == path)
+                .map(|(_, mode, _)| *mode)
+        };
+        assert_eq!(mode_of("link_file"), Some(EntryMode::FILE));
+        assert_eq!(mode_of("link_dir/"), Some(EntryMode::DIR));
+        assert_eq!(mode_of("escape_file"), Some(EntryMode::Unknown));
+
+        let op = Operator::new(new_builder())?.finish();
+        assert_eq!(op.read("link_file").await?, b"hello");
+        let err = op.read("escape_file").await.expect_err("must be refused");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        std::fs::remove_dir_all(&base).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stat_without_follow_symlink() -> Result<()> {
+        let (root, base) = prepare_symlink_root();
+
+        let mut builder = FsBuilder::default();
+        builder
+            .root(&root.to_string_lossy())
+            .disable_follow_symlink();
+        let op = Operator::new(builder)?.finish();
+
+        // Symlinks are reported as is even if following is requested.
+        let meta = op.stat("link_file").await?;
+        assert!(meta.is_symlink());
+        assert_eq!(meta.symlink_target(), Some("file"));
+        let meta = op.blocking().stat("broken")?;
+        assert!(meta.is_symlink());
+        assert_eq!(meta.symlink_target(), Some("not_exist"));
+
+        std::fs::remove_dir_all(&base).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        op.write("dir/file", "old").await?;
+
+        // Target must not be touched before close.
+        let mut w = op.writer("dir/file").await?;
+        w.write("new content").await?;
+        assert_eq!(op.read("dir/file").await?, b"old");
+        w.close().await?;
+        assert_eq!(op.read("dir/file").await?, b"new content");
+
+        // Aborted writer leaves the target as is.
+        let mut w = op.writer("dir/file").await?;
+        w.write("aborted").await?;
+        w.abort().await?;
+        assert_eq!(op.read("dir/file").await?, b"new content");
+
+        // No temp file should be left.
+        let names: Vec<_> = std::fs::read_dir(root.join("dir"))
+            .expect("read dir must succeed")
+            .map(|e| e.expect("entry must be valid").file_name())
+            .collect();
+        assert_eq!(names, vec!["file"]);
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_atomic_write_keeps_target() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "old").await?;
+        std::fs::set_permissions(root.join("file"), std::fs::Permissions::from_mode(0o600))
+            .expect("set permissions must succeed");
+        std::os::unix::fs::symlink("file", root.join("link")).expect("symlink must succeed");
+        // Temp file left by a crash.
+        std::fs::write(root.join(format!(".{}", tmp_file_of("file"))), "orphan")
+            .expect("write must succeed");
+
+        // Write via symlink replaces its target and keeps permissions.
+        op.write("link", "new").await?;
+        let meta = std::fs::symlink_metadata(root.join("link")).expect("stat must succeed");
+        assert!(meta.file_type().is_symlink());
+        assert_eq!(
+            std::fs::read(root.join("file")).expect("read must succeed"),
+            b"new"
+        );
+        let meta = std::fs::metadata(root.join("file")).expect("stat must succeed");
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+
+        // Temp files are skipped while listing.
+        let mut paths = vec![];
+        let mut l = op.list("/").await?;
+        while let Some(page) = l.next_page().await? {
+            paths.extend(page.into_iter().map(|e| e.path().to_string()));
+        }
+        paths.sort();
+        assert_eq!(paths, vec!["file", "link"]);
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disable_atomic_write() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("disable_atomic_write".to_string(), "true".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "old").await?;
+
+        // Data is written to target in place.
+        let mut w = op.writer("file").await?;
+        w.write("new").await?;
+        assert_eq!(op.read("file").await?, b"new");
+        w.close().await?;
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_preserve_mtime() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy()).preserve_mtime();
+        let op = Operator::new(builder)?.finish();
+
+        op.write("src", "Hello, World!").await?;
+        let src = root.join("src");
+        std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o640))
+            .expect("set permissions must succeed");
+        let src_meta = std::fs::metadata(&src).expect("stat must succeed");
+
+        op.copy("src", "dir/async").await?;
+        op.blocking().copy("src", "dir/blocking")?;
+
+        for name in ["dir/async", "dir/blocking"] {
+            assert_eq!(op.read(name).await?, b"Hello, World!");
+            let meta = std::fs::metadata(root.join(name)).expect("stat must succeed");
+            assert_eq!(meta.permissions().mode() & 0o777, 0o640);
+            assert_eq!(
+                meta.modified().expect("mtime must exist"),
+                src_meta.modified().expect("mtime must exist")
+            );
+        }
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_create_dir_with_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("dir_permissions".to_string(), "750".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+
+        // Missing parents are created and existing dirs are fine.
+        op.create_dir("a/b/c/").await?;
+        op.create_dir("a/b/c/").await?;
+        op.blocking().create_dir("a/d/")?;
+        op.write("e/f/file", "Hello, World!").await?;
+
+        for dir in ["a", "a/b", "a/b/c", "a/d", "e", "e/f"] {
+            let meta = std::fs::metadata(root.join(dir)).expect("stat must succeed");
+            assert!(meta.is_dir());
+            // umask could only remove bits.
+            assert_eq!(meta.permissions().mode() & 0o777 & !0o750, 0, "{dir}");
+        }
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_direct_io_and_preallocate() -> Result<()> {
+        use rand::prelude::*;
+
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("enable_preallocate".to_string(), "true".to_string()),
+            ("enable_direct_io".to_string(), "true".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+
+        let mut content = vec![0; 3 * 1024 * 1024 + 123];
+        thread_rng().fill_bytes(&mut content);
+
+        let mut w = op
+            .writer_with(
+                "async",
+                OpWrite::new().with_content_length(content.len() as u64),
+            )
+            .await?;
+        for chunk in content.chunks(1000 * 1000) {
+            w.write(chunk.to_vec()).await?;
+        }
+        w.close().await?;
+
+        op.blocking().write("blocking", content.clone())?;
+
+        for name in ["async", "blocking"] {
+            // Preallocate must not change the file size.
+            assert_eq!(op.stat(name).await?.content_length(), content.len() as u64);
+            assert_eq!(op.read(name).await?, content);
+        }
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_batch_size() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("list_batch_size".to_string(), "2".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+        for i in 0..5 {
+            op.write(&format!("dir/{i}"), "").await?;
+        }
+
+        let mut pages = vec![];
+        let mut l = op.list("dir/").await?;
+        while let Some(page) = l.next_page().await? {
+            pages.push(page.len());
+        }
+        assert_eq!(pages, [2, 2, 1]);
+
+        // Limit in OpList takes precedence.
+        let mut l = op.list_with("dir/", OpList::new().with_limit(4)).await?;
+        assert_eq!(l.next_page().await?.map(|v| v.len()), Some(4));
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy()).list_batch_size(0);
+        assert_eq!(
+            builder.build().unwrap_err().kind(),
+            ErrorKind::ConfigInvalid
+        );
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_durability() -> Result<()> {
+        use futures::AsyncWriteExt;
+
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("durability".to_string(), "none".to_string()),
+        ]));
+        assert_eq!(builder.durability, Some(Durability::None));
+        let op = Operator::new(builder)?.finish();
+        assert!(op.info().capability().write_with_durability);
+
+        for (name, durability) in [
+            ("none", Durability::None),
+            ("data", Durability::Data),
+            ("full", Durability::Full),
+        ] {
+            let args = OpWrite::new().with_durability(durability);
+            op.write_with(name, args.clone(), "Hello, World!").await?;
+            assert_eq!(op.read(name).await?, b"Hello, World!");
+
+            let path = format!("blocking_{name}");
+            op.blocking()
+                .write_with(&path, args.clone(), "Hello, World!")?;
+            assert_eq!(op.read(&path).await?, b"Hello, World!");
+
+            let path = format!("append_{name}");
+            let mut w = op.writer_with(&path, args.with_append(true)).await?;
+            w.write_all(b"Hello, ").await.expect("write must succeed");
+            w.flush().await.expect("flush must succeed");
+            assert_eq!(op.read(&path).await?, b"Hello, ");
+            w.write_all(b"World!").await.expect("write must succeed");
+            w.close().await.expect("close must succeed");
+            assert_eq!(op.read(&path).await?, b"Hello, World!");
+        }
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_root() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+
+        let mut builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("create_root".to_string(), "false".to_string()),
+        ]));
+        let err = builder.build().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(!root.exists());
+
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        builder.build()?;
+        assert!(root.is_dir());
+
+        // Root exists now, so it's fine to not create it.
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy()).create_root(false);
+        builder.build()?;
+
+        let file = root.join("file");
+        std::fs::write(&file, "hello").expect("write must succeed");
+        for create in [true, false] {
+            let mut builder = FsBuilder::default();
+            builder.root(&file.to_string_lossy()).create_root(create);
+            let err = builder.build().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::NotADirectory);
+        }
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_only() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("dir")).expect("create dir must succeed");
+        std::fs::write(root.join("file"), "hello").expect("write must succeed");
+
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("read_only".to_string(), "true".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+        let cap = op.info().capability();
+        assert!(cap.read && cap.list);
+        assert!(!cap.write && !cap.create_dir && !cap.delete && !cap.copy && !cap.rename);
+
+        assert_eq!(op.read("file").await?, b"hello");
+        assert_eq!(op.stat("dir/").await?.mode(), EntryMode::DIR);
+
+        let errs = vec![
+            op.write("file", "world").await.unwrap_err(),
+            op.create_dir("new/").await.unwrap_err(),
+            op.delete("file").await.unwrap_err(),
+            op.copy("file", "copied").await.unwrap_err(),
+            op.rename("file", "renamed").await.unwrap_err(),
+            op.blocking().write("file", "world").unwrap_err(),
+            op.blocking().create_dir("new/").unwrap_err(),
+            op.blocking().delete("file").unwrap_err(),
+            op.blocking().copy("file", "copied").unwrap_err(),
+            op.blocking().rename("file", "renamed").unwrap_err(),
+        ];
+        for err in errs {
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        }
+        assert_eq!(op.read("file").await?, b"hello");
+        assert!(!op.is_exist("new/").await?);
+        assert!(!op.is_exist("copied").await?);
+
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+        Ok(())
     }
 }
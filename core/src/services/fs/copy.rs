@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::io;
+use std::path::Path;
+
+/// Copy a file from `from` to `to` without moving data through userspace
+/// when possible.
+///
+/// - On linux, a reflink (`FICLONE`) will be tried first which makes
+///   copying instant on filesystems like btrfs and xfs.
+/// - Otherwise, we fall back to [`std::fs::copy`], which uses
+///   `copy_file_range` on linux and falls back to a regular copy by
+///   itself if that's not supported (for example `EXDEV` on old kernels).
+///
+/// Permissions of `from` are always copied to `to`, the same as
+/// [`std::fs::copy`]. Modified time is copied if `preserve_mtime` is set,
+/// which is only supported on unix for now.
+pub(super) fn copy_file(from: &Path, to: &Path, preserve_mtime: bool) -> io::Result<()> {
+    if !reflink(from, to)? {
+        std::fs::copy(from, to)?;
+    }
+
+    if preserve_mtime {
+        copy_mtime(from, to)?;
+    }
+
+    Ok(())
+}
+
+/// `FICLONE` is `_IOW(0x94, 9, int)`, it's not exported by libc yet.
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    )
+))]
+const FICLONE: libc::c_ulong = 0x80049409;
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    ))
+))]
+const FICLONE: libc::c_ulong = 0x40049409;
+
+/// Try to clone `from` into `to`.
+///
+/// Returns `false` if reflink is not supported so that callers can fall
+/// back to a regular copy.
+#[cfg(target_os = "linux")]
+fn reflink(from: &Path, to: &Path) -> io::Result<bool> {
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+
+    let src = File::open(from)?;
+    let meta = src.metadata()?;
+    // Leave the error reporting of non-regular files to std::fs::copy.
+    if !meta.is_file() {
+        return Ok(false);
+    }
+
+    let dst = File::create(to)?;
+    // SAFETY: both fds are valid during this call.
+    let ret = unsafe { libc::ioctl(dst.as_raw_fd(), FICLONE as _, src.as_raw_fd()) };
+    if ret == 0 {
+        dst.set_permissions(meta.permissions())?;
+        return Ok(true);
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        // Returned by filesystems without reflink support, or when `from`
+        // and `to` live on different filesystems.
+        Some(libc::EOPNOTSUPP)
+        | Some(libc::ENOTTY)
+        | Some(libc::EXDEV)
+        | Some(libc::EINVAL)
+        | Some(libc::ENOSYS) => Ok(false),
+        _ => Err(err),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_: &Path, _: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(unix)]
+fn copy_mtime(from: &Path, to: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let meta = std::fs::metadata(from)?;
+    let path = CString::new(to.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let times = [
+        // Keep the access time untouched.
+        libc::timespec {
+            tv_sec: 0,
+            tv_nsec: libc::UTIME_OMIT,
+        },
+        libc::timespec {
+            tv_sec: meta.mtime() as libc::time_t,
+            tv_nsec: meta.mtime_nsec() as _,
+        },
+    ];
+    // SAFETY: path and times are valid during this call.
+    let ret = unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times.as_ptr(), 0) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// `File::set_modified` requires rust 1.75 which is higher than our MSRV.
+#[cfg(not(unix))]
+fn copy_mtime(_: &Path, _: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,259 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::io;
+use std::path::Path;
+
+/// Open file at path for writing with `O_DIRECT`.
+///
+/// Returns `None` if `O_DIRECT` is not supported by current platform or
+/// the filesystem, callers should fall back to a regular open.
+#[cfg(target_os = "linux")]
+pub(super) async fn open_direct(p: &Path) -> io::Result<Option<(tokio::fs::File, DirectWriter)>> {
+    let res = tokio::fs::OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(p)
+        .await;
+
+    match res {
+        Ok(f) => {
+            let w = DirectWriter::new(f.try_clone().await?.into_std().await);
+            Ok(Some((f, w)))
+        }
+        Err(err) if err.raw_os_error() == Some(libc::EINVAL) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) async fn open_direct(_: &Path) -> io::Result<Option<(tokio::fs::File, DirectWriter)>> {
+    Ok(None)
+}
+
+/// Blocking version of [`open_direct`].
+#[cfg(target_os = "linux")]
+pub(super) fn blocking_open_direct(p: &Path) -> io::Result<Option<(std::fs::File, DirectWriter)>> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let res = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(p);
+
+    match res {
+        Ok(f) => {
+            let w = DirectWriter::new(f.try_clone()?);
+            Ok(Some((f, w)))
+        }
+        Err(err) if err.raw_os_error() == Some(libc::EINVAL) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn blocking_open_direct(_: &Path) -> io::Result<Option<(std::fs::File, DirectWriter)>> {
+    Ok(None)
+}
+
+/// Alignment of memory, offset and length required by `O_DIRECT`.
+///
+/// 4KiB is the logical block size of most devices and a multiple of the
+/// others.
+#[cfg(target_os = "linux")]
+const DIRECT_IO_ALIGN: usize = 4096;
+/// Max size of a single write.
+#[cfg(target_os = "linux")]
+const DIRECT_IO_CHUNK: usize = 256 * DIRECT_IO_ALIGN;
+
+/// DirectWriter writes a file opened with `O_DIRECT`.
+///
+/// Data is buffered and written in multiples of [`DIRECT_IO_ALIGN`] from
+/// an aligned buffer. The unaligned tail will be written in
+/// [`DirectWriter::finish`] after `O_DIRECT` has been cleared.
+///
+/// All writes are positional, so a failed `write` or `finish` can be
+/// retried with the same input.
+#[cfg(target_os = "linux")]
+pub struct DirectWriter {
+    f: std::fs::File,
+    /// Backing memory of the aligned buffer, over allocated so that
+    /// we can find an aligned start in it.
+    mem: Vec<u8>,
+    /// Data that has not been written, always shorter than [`DIRECT_IO_ALIGN`].
+    pending: Vec<u8>,
+    pos: u64,
+}
+
+#[cfg(target_os = "linux")]
+impl DirectWriter {
+    fn new(f: std::fs::File) -> Self {
+        Self {
+            f,
+            mem: vec![0; DIRECT_IO_CHUNK + DIRECT_IO_ALIGN],
+            pending: Vec::with_capacity(DIRECT_IO_ALIGN),
+            pos: 0,
+        }
+    }
+
+    pub(super) fn write(&mut self, bs: &[u8]) -> io::Result<()> {
+        use std::os::unix::fs::FileExt;
+
+        let total = self.pending.len() + bs.len();
+        let aligned = total / DIRECT_IO_ALIGN * DIRECT_IO_ALIGN;
+        if aligned == 0 {
+            self.pending.extend_from_slice(bs);
+            return Ok(());
+        }
+
+        let offset = self.mem.as_ptr().align_offset(DIRECT_IO_ALIGN);
+        let buf = &mut self.mem[offset..offset + DIRECT_IO_CHUNK];
+
+        let mut written = 0;
+        while written < aligned {
+            let size = (aligned - written).min(DIRECT_IO_CHUNK);
+            fill(&mut buf[..size], &self.pending, bs, written);
+            self.f
+                .write_all_at(&buf[..size], self.pos + written as u64)?;
+            written += size;
+        }
+
+        // `pending` is always shorter than `aligned`, so the rest must be
+        // in `bs`.
+        let rest = &bs[aligned - self.pending.len()..];
+        self.pending.clear();
+        self.pending.extend_from_slice(rest);
+        self.pos += aligned as u64;
+
+        Ok(())
+    }
+
+    /// Write the unaligned tail without `O_DIRECT`.
+    pub(super) fn finish(&mut self) -> io::Result<()> {
+        use std::os::unix::fs::FileExt;
+        use std::os::unix::io::AsRawFd;
+
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        // File status flags are shared by all duplicated fds, this will
+        // clear `O_DIRECT` on the file held by the writer too.
+        let fd = self.f.as_raw_fd();
+        // SAFETY: fd is valid during this call.
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: fd is valid during this call.
+        if unsafe { libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_DIRECT) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        self.f.write_all_at(&self.pending, self.pos)?;
+        self.pos += self.pending.len() as u64;
+        self.pending.clear();
+
+        Ok(())
+    }
+}
+
+/// Copy `a` and `b` chained together starting from `start` into `dst`.
+#[cfg(target_os = "linux")]
+fn fill(dst: &mut [u8], a: &[u8], b: &[u8], start: usize) {
+    let mut n = 0;
+    if start < a.len() {
+        n = (a.len() - start).min(dst.len());
+        dst[..n].copy_from_slice(&a[start..start + n]);
+        if n == dst.len() {
+            return;
+        }
+    }
+
+    let start = start + n - a.len();
+    let size = dst.len() - n;
+    dst[n..].copy_from_slice(&b[start..start + size]);
+}
+
+/// `O_DIRECT` is only supported on linux, there is no way to create
+/// this writer on other platforms.
+#[cfg(not(target_os = "linux"))]
+pub enum DirectWriter {}
+
+#[cfg(not(target_os = "linux"))]
+impl DirectWriter {
+    pub(super) fn write(&mut self, _: &[u8]) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub(super) fn finish(&mut self) -> io::Result<()> {
+        match *self {}
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use rand::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let mut dst = [0; 4];
+        fill(&mut dst, b"abc", b"def", 1);
+        assert_eq!(&dst, b"bcde");
+        fill(&mut dst, b"abc", b"defgh", 4);
+        assert_eq!(&dst, b"efgh");
+        fill(&mut dst, b"abcdef", b"gh", 0);
+        assert_eq!(&dst, b"abcd");
+    }
+
+    #[test]
+    fn test_direct_writer() {
+        let p = std::env::temp_dir().join(format!("opendal-{}", uuid::Uuid::new_v4()));
+
+        let mut rng = thread_rng();
+        let mut content = vec![0; 3 * DIRECT_IO_CHUNK + 1234];
+        rng.fill_bytes(&mut content);
+
+        let (_f, mut w) = match blocking_open_direct(&p).expect("open must succeed") {
+            Some(v) => v,
+            // O_DIRECT is not supported by the filesystem of temp dir.
+            None => {
+                std::fs::remove_file(&p).expect("remove file must succeed");
+                return;
+            }
+        };
+
+        let mut written = 0;
+        while written < content.len() {
+            let size = rng
+                .gen_range(1..DIRECT_IO_CHUNK / 2)
+                .min(content.len() - written);
+            w.write(&content[written..written + size])
+                .expect("write must succeed");
+            written += size;
+        }
+        w.finish().expect("finish must succeed");
+
+        assert_eq!(std::fs::read(&p).expect("read must succeed"), content);
+        std::fs::remove_file(&p).expect("remove file must succeed");
+    }
+}
@@ -25,6 +25,7 @@ pub fn parse_io_error(err: io::Error) -> Error {
     use io::ErrorKind::*;
 
     let (kind, retryable) = match err.kind() {
+        _ if is_insufficient_storage(&err) => (ErrorKind::InsufficientStorage, false),
         NotFound => (ErrorKind::NotFound, false),
         PermissionDenied => (ErrorKind::PermissionDenied, false),
         Interrupted | UnexpectedEof | TimedOut | WouldBlock => (ErrorKind::Unexpected, true),
@@ -39,3 +40,61 @@ pub fn parse_io_error(err: io::Error) -> Error {
 
     err
 }
+
+/// Returns whether this error is caused by no space left on device or disk
+/// quota exceeded.
+///
+/// `io::ErrorKind::StorageFull` is not stable yet, so we check the raw os
+/// error instead.
+fn is_insufficient_storage(err: &io::Error) -> bool {
+    // ENOSPC
+    #[cfg(unix)]
+    const CODES: &[i32] = &[
+        28,
+        // EDQUOT
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        122,
+        #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+        69,
+    ];
+    // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+    #[cfg(windows)]
+    const CODES: &[i32] = &[39, 112];
+    #[cfg(not(any(unix, windows)))]
+    const CODES: &[i32] = &[];
+
+    err.raw_os_error()
+        .map(|code| CODES.contains(&code))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_io_error_no_space() {
+        let err = parse_io_error(io::Error::from_raw_os_error(28));
+        assert_eq!(err.kind(), ErrorKind::InsufficientStorage);
+        assert!(!err.is_temporary());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_io_error_disk_quota_exceeded() {
+        let err = parse_io_error(io::Error::from_raw_os_error(122));
+        assert_eq!(err.kind(), ErrorKind::InsufficientStorage);
+        assert!(!err.is_temporary());
+    }
+
+    #[test]
+    fn test_parse_io_error() {
+        let err = parse_io_error(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        let err = parse_io_error(io::Error::from(io::ErrorKind::Interrupted));
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+    }
+}
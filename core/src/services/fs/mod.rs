@@ -18,6 +18,8 @@
 mod backend;
 pub use backend::FsBuilder as Fs;
 
+mod copy;
+mod direct;
 mod error;
 mod pager;
 mod writer;
@@ -15,32 +15,171 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use log::warn;
 
+use super::backend::blocking_is_within_root;
+use super::backend::is_tmp_file;
+use super::backend::is_within_root;
 use super::error::parse_io_error;
 use crate::raw::*;
 use crate::EntryMode;
 use crate::Metadata;
 use crate::Result;
 
+/// Options about how symlinks are handled.
+#[derive(Debug, Clone, Copy)]
+pub struct SymlinkOptions {
+    /// Follow symlinks to report their targets' metadata.
+    pub follow: bool,
+    /// Skip symlinks that can't be followed while listing.
+    pub skip_dangling: bool,
+    /// Refuse to follow symlinks that resolve outside of root.
+    pub root_check: bool,
+}
+
+impl Default for SymlinkOptions {
+    fn default() -> Self {
+        Self {
+            follow: true,
+            skip_dangling: false,
+            root_check: false,
+        }
+    }
+}
+
 pub struct FsPager<P> {
     root: PathBuf,
+    symlink: SymlinkOptions,
 
     size: usize,
     rd: P,
 }
 
 impl<P> FsPager<P> {
-    pub fn new(root: &Path, rd: P, limit: Option<usize>) -> Self {
+    pub fn new(root: &Path, rd: P, limit: Option<usize>, symlink: SymlinkOptions) -> Self {
         Self {
             root: root.to_owned(),
+            symlink,
             size: limit.unwrap_or(1000),
             rd,
         }
     }
+
+    /// Build entry for path whose type is known.
+    fn entry_of(rel_path: &str, is_file: bool, is_dir: bool) -> oio::Entry {
+        if is_file {
+            oio::Entry::new(rel_path, Metadata::new(EntryMode::FILE))
+        } else if is_dir {
+            // Make sure we are returning the correct path.
+            oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
+        } else {
+            oio::Entry::new(rel_path, Metadata::new(EntryMode::Unknown))
+        }
+    }
+
+    /// Build entry for the symlink itself.
+    fn symlink_entry_of(rel_path: &str, target: PathBuf) -> oio::Entry {
+        oio::Entry::new(
+            rel_path,
+            Metadata::new(EntryMode::Unknown)
+                .with_symlink_target(target.to_string_lossy().to_string()),
+        )
+    }
+
+    /// Returns `true` if the followed symlink should be skipped.
+    fn skip_dangling(&self, entry_path: &Path, err: io::Error) -> bool {
+        if self.symlink.skip_dangling {
+            warn!(
+                "fs skip dangling symlink {}: {err:?}",
+                entry_path.to_string_lossy()
+            );
+        }
+
+        self.symlink.skip_dangling
+    }
+}
+
+impl FsPager<tokio::fs::ReadDir> {
+    /// Resolve symlink entry, returns `None` if it should be skipped.
+    async fn resolve_symlink(
+        &self,
+        entry_path: &Path,
+        rel_path: &str,
+    ) -> Result<Option<oio::Entry>> {
+        if self.symlink.follow || self.symlink.skip_dangling {
+            match tokio::fs::metadata(entry_path).await {
+                Ok(meta) => {
+                    if self.symlink.follow
+                        && (!self.symlink.root_check
+                            || is_within_root(&self.root, entry_path).await?)
+                    {
+                        return Ok(Some(Self::entry_of(
+                            rel_path,
+                            meta.is_file(),
+                            meta.is_dir(),
+                        )));
+                    }
+                }
+                Err(err) => {
+                    if self.skip_dangling(entry_path, err) {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        let target = match tokio::fs::read_link(entry_path).await {
+            Ok(target) => target,
+            // The symlink has been removed after the dir was read.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(parse_io_error(err)),
+        };
+        Ok(Some(Self::symlink_entry_of(rel_path, target)))
+    }
+}
+
+impl FsPager<std::fs::ReadDir> {
+    /// Resolve symlink entry, returns `None` if it should be skipped.
+    fn blocking_resolve_symlink(
+        &self,
+        entry_path: &Path,
+        rel_path: &str,
+    ) -> Result<Option<oio::Entry>> {
+        if self.symlink.follow || self.symlink.skip_dangling {
+            match std::fs::metadata(entry_path) {
+                Ok(meta) => {
+                    if self.symlink.follow
+                        && (!self.symlink.root_check
+                            || blocking_is_within_root(&self.root, entry_path)?)
+                    {
+                        return Ok(Some(Self::entry_of(
+                            rel_path,
+                            meta.is_file(),
+                            meta.is_dir(),
+                        )));
+                    }
+                }
+                Err(err) => {
+                    if self.skip_dangling(entry_path, err) {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        let target = match std::fs::read_link(entry_path) {
+            Ok(target) => target,
+            // The symlink has been removed after the dir was read.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(parse_io_error(err)),
+        };
+        Ok(Some(Self::symlink_entry_of(rel_path, target)))
+    }
 }
 
 #[async_trait]
@@ -48,7 +187,8 @@ impl oio::Page for FsPager<tokio::fs::ReadDir> {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
         let mut oes: Vec<oio::Entry> = Vec::with_capacity(self.size);
 
-        for _ in 0..self.size {
+        // Symlinks could be skipped, so we can't rely on the count of loops.
+        while oes.len() < self.size {
             let de = match self.rd.next_entry().await.map_err(parse_io_error)? {
                 Some(de) => de,
                 None => break,
@@ -63,19 +203,30 @@ impl oio::Page for FsPager<tokio::fs::ReadDir> {
                     .replace('\\', "/"),
             );
 
+            // Skip temp files of atomic write, including the ones left by
+            // a crash.
+            if is_tmp_file(&de.file_name().to_string_lossy()) {
+                continue;
+            }
+
             // On Windows and most Unix platforms this function is free
             // (no extra system calls needed), but some Unix platforms may
             // require the equivalent call to symlink_metadata to learn about
             // the target file type.
-            let file_type = de.file_type().await.map_err(parse_io_error)?;
+            let file_type = match de.file_type().await {
+                Ok(v) => v,
+                // The entry has been removed after the dir was read.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(parse_io_error(err)),
+            };
 
-            let d = if file_type.is_file() {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::FILE))
-            } else if file_type.is_dir() {
-                // Make sure we are returning the correct path.
-                oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
+            let d = if file_type.is_symlink() {
+                match self.resolve_symlink(&entry_path, &rel_path).await? {
+                    Some(d) => d,
+                    None => continue,
+                }
             } else {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::Unknown))
+                Self::entry_of(&rel_path, file_type.is_file(), file_type.is_dir())
             };
 
             oes.push(d)
@@ -89,7 +240,8 @@ impl oio::BlockingPage for FsPager<std::fs::ReadDir> {
     fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
         let mut oes: Vec<oio::Entry> = Vec::with_capacity(self.size);
 
-        for _ in 0..self.size {
+        // Symlinks could be skipped, so we can't rely on the count of loops.
+        while oes.len() < self.size {
             let de = match self.rd.next() {
                 Some(de) => de.map_err(parse_io_error)?,
                 None => break,
@@ -104,19 +256,30 @@ impl oio::BlockingPage for FsPager<std::fs::ReadDir> {
                     .replace('\\', "/"),
             );
 
+            // Skip temp files of atomic write, including the ones left by
+            // a crash.
+            if is_tmp_file(&de.file_name().to_string_lossy()) {
+                continue;
+            }
+
             // On Windows and most Unix platforms this function is free
             // (no extra system calls needed), but some Unix platforms may
             // require the equivalent call to symlink_metadata to learn about
             // the target file type.
-            let file_type = de.file_type().map_err(parse_io_error)?;
+            let file_type = match de.file_type() {
+                Ok(v) => v,
+                // The entry has been removed after the dir was read.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(parse_io_error(err)),
+            };
 
-            let d = if file_type.is_file() {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::FILE))
-            } else if file_type.is_dir() {
-                // Make sure we are returning the correct path.
-                oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
+            let d = if file_type.is_symlink() {
+                match self.blocking_resolve_symlink(&entry_path, &rel_path)? {
+                    Some(d) => d,
+                    None => continue,
+                }
             } else {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::Unknown))
+                Self::entry_of(&rel_path, file_type.is_file(), file_type.is_dir())
             };
 
             oes.push(d)
@@ -125,3 +288,38 @@ impl oio::BlockingPage for FsPager<std::fs::ReadDir> {
         Ok(if oes.is_empty() { None } else { Some(oes) })
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use oio::BlockingPage;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_skip_removed_entries() {
+        let root = std::env::temp_dir().join(format!("opendal-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("create dir must succeed");
+        std::fs::write(root.join("file"), "hello").expect("write must succeed");
+        for i in 0..10 {
+            std::os::unix::fs::symlink("file", root.join(format!("link_{i}")))
+                .expect("symlink must succeed");
+        }
+
+        let rd = std::fs::read_dir(&root).expect("read dir must succeed");
+        let mut pager = FsPager::new(&root, rd, Some(1), SymlinkOptions::default());
+        let mut paths = vec![];
+        paths.extend(pager.next().expect("next must succeed").unwrap_or_default());
+
+        // Remove all symlinks after the dir has been read.
+        for i in 0..10 {
+            let _ = std::fs::remove_file(root.join(format!("link_{i}")));
+        }
+        while let Some(entries) = pager.next().expect("removed entries must be skipped") {
+            paths.extend(entries);
+        }
+
+        assert!(paths.len() <= 2, "{paths:?}");
+        std::fs::remove_dir_all(&root).expect("remove dir must succeed");
+    }
+}
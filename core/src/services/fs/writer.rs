@@ -19,13 +19,17 @@ use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use tokio::io::AsyncSeekExt;
 use tokio::io::AsyncWriteExt;
 
+use super::direct::DirectWriter;
 use super::error::parse_io_error;
+use crate::ops::Durability;
 use crate::raw::*;
 use crate::*;
 
@@ -34,6 +38,9 @@ pub struct FsWriter<F> {
     tmp_path: Option<PathBuf>,
     f: F,
     pos: u64,
+    append: bool,
+    direct: Option<Arc<Mutex<DirectWriter>>>,
+    durability: Durability,
 }
 
 impl<F> FsWriter<F> {
@@ -43,8 +50,68 @@ impl<F> FsWriter<F> {
             tmp_path,
             f,
             pos: 0,
+            append: false,
+            direct: None,
+            durability: Durability::Full,
         }
     }
+
+    /// Write data via [`DirectWriter`] instead of `f`, which must be
+    /// opened with `O_DIRECT`.
+    pub fn with_direct(mut self, w: DirectWriter) -> Self {
+        self.direct = Some(Arc::new(Mutex::new(w)));
+        self
+    }
+
+    /// Create a writer for file opened with `O_APPEND`.
+    ///
+    /// Every write will be appended to the end of file by OS, so we
+    /// can't seek back to retry a partial write, and atomic write is
+    /// not used.
+    pub fn new_append(target_path: PathBuf, f: F) -> Self {
+        Self {
+            target_path,
+            tmp_path: None,
+            f,
+            pos: 0,
+            append: true,
+            direct: None,
+            durability: Durability::Full,
+        }
+    }
+
+    /// Set how written data is synced in `close` and `flush`.
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+}
+
+/// Run `f` with the locked [`DirectWriter`].
+///
+/// The lock could only be poisoned if a previous write panicked, in
+/// which case the file content is unknown.
+fn with_direct<T>(
+    w: &Mutex<DirectWriter>,
+    f: impl FnOnce(&mut DirectWriter) -> std::io::Result<T>,
+) -> Result<T> {
+    let mut w = w
+        .lock()
+        .map_err(|_| Error::new(ErrorKind::Unexpected, "direct writer has been poisoned"))?;
+    f(&mut w).map_err(parse_io_error)
+}
+
+/// Run `f` with the locked [`DirectWriter`] in the blocking thread pool.
+async fn with_direct_async(
+    w: &Arc<Mutex<DirectWriter>>,
+    f: impl FnOnce(&mut DirectWriter) -> std::io::Result<()> + Send + 'static,
+) -> Result<()> {
+    let w = w.clone();
+    tokio::task::spawn_blocking(move || with_direct(&w, f))
+        .await
+        .map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "direct write task failed").set_source(err)
+        })?
 }
 
 #[async_trait]
@@ -54,34 +121,77 @@ impl oio::Write for FsWriter<tokio::fs::File> {
     /// File could be partial written, so we will seek to start to make sure
     /// we write the same content.
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.f
-            .seek(SeekFrom::Start(self.pos))
-            .await
-            .map_err(parse_io_error)?;
+        if let Some(w) = &self.direct {
+            return with_direct_async(w, move |w| w.write(&bs)).await;
+        }
+
+        if !self.append {
+            self.f
+                .seek(SeekFrom::Start(self.pos))
+                .await
+                .map_err(parse_io_error)?;
+        }
         self.f.write_all(&bs).await.map_err(parse_io_error)?;
         self.pos += bs.len() as u64;
 
         Ok(())
     }
 
+    /// Abort is only supported for atomic write, in which case the temp
+    /// file will be removed and the target is left untouched.
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        match &self.tmp_path {
+            Some(tmp_path) => tokio::fs::remove_file(tmp_path)
+                .await
+                .map_err(parse_io_error),
+            None => Err(Error::new(
+                ErrorKind::Unsupported,
+                "output writer doesn't support abort",
+            )),
+        }
     }
 
     async fn close(&mut self) -> Result<()> {
-        self.f.sync_all().await.map_err(parse_io_error)?;
+        if let Some(w) = &self.direct {
+            with_direct_async(w, |w| w.finish()).await?;
+        }
+
+        match self.durability {
+            // tokio writes data in background, flush to wait for them
+            // and catch their errors.
+            Durability::None => self.f.flush().await.map_err(parse_io_error)?,
+            Durability::Data => self.f.sync_data().await.map_err(parse_io_error)?,
+            Durability::Full => self.f.sync_all().await.map_err(parse_io_error)?,
+        }
 
         if let Some(tmp_path) = &self.tmp_path {
             tokio::fs::rename(tmp_path, &self.target_path)
                 .await
                 .map_err(parse_io_error)?;
+
+            // Sync the parent dir to make sure the rename is persisted.
+            #[cfg(unix)]
+            if let (Durability::Full, Some(parent)) = (self.durability, self.target_path.parent()) {
+                tokio::fs::File::open(parent)
+                    .await
+                    .map_err(parse_io_error)?
+                    .sync_all()
+                    .await
+                    .map_err(parse_io_error)?;
+            }
         }
 
         Ok(())
     }
+
+    /// Only the strictest durability syncs data while flushing, others
+    /// just wait for the ongoing writes and leave syncing to `close`.
+    async fn flush(&mut self) -> Result<()> {
+        match self.durability {
+            Durability::Full => self.f.sync_data().await.map_err(parse_io_error),
+            _ => self.f.flush().await.map_err(parse_io_error),
+        }
+    }
 }
 
 impl oio::BlockingWrite for FsWriter<std::fs::File> {
@@ -90,9 +200,15 @@ impl oio::BlockingWrite for FsWriter<std::fs::File> {
     /// File could be partial written, so we will seek to start to make sure
     /// we write the same content.
     fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.f
-            .seek(SeekFrom::Start(self.pos))
-            .map_err(parse_io_error)?;
+        if let Some(w) = &self.direct {
+            return with_direct(w, |w| w.write(&bs));
+        }
+
+        if !self.append {
+            self.f
+                .seek(SeekFrom::Start(self.pos))
+                .map_err(parse_io_error)?;
+        }
         self.f.write_all(&bs).map_err(parse_io_error)?;
         self.pos += bs.len() as u64;
 
@@ -100,10 +216,27 @@ impl oio::BlockingWrite for FsWriter<std::fs::File> {
     }
 
     fn close(&mut self) -> Result<()> {
-        self.f.sync_all().map_err(parse_io_error)?;
+        if let Some(w) = &self.direct {
+            with_direct(w, |w| w.finish())?;
+        }
+
+        match self.durability {
+            Durability::None => {}
+            Durability::Data => self.f.sync_data().map_err(parse_io_error)?,
+            Durability::Full => self.f.sync_all().map_err(parse_io_error)?,
+        }
 
         if let Some(tmp_path) = &self.tmp_path {
             std::fs::rename(tmp_path, &self.target_path).map_err(parse_io_error)?;
+
+            // Sync the parent dir to make sure the rename is persisted.
+            #[cfg(unix)]
+            if let (Durability::Full, Some(parent)) = (self.durability, self.target_path.parent()) {
+                std::fs::File::open(parent)
+                    .map_err(parse_io_error)?
+                    .sync_all()
+                    .map_err(parse_io_error)?;
+            }
         }
 
         Ok(())
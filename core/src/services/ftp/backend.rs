@@ -160,6 +160,7 @@ impl FtpBuilder {
 impl Builder for FtpBuilder {
     const SCHEME: Scheme = Scheme::Ftp;
     type Accessor = FtpBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "endpoint", "user", "password"];
 
     fn build(&mut self) -> Result<Self::Accessor> {
         debug!("ftp backend build started: {:?}", &self);
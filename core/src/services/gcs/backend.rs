@@ -21,17 +21,26 @@ use std::fmt::Formatter;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use http::header::HOST;
 use http::StatusCode;
 use log::debug;
+use log::warn;
 use reqsign::GoogleCredentialLoader;
 use reqsign::GoogleSigner;
 use reqsign::GoogleTokenLoad;
 use reqsign::GoogleTokenLoader;
 use serde::Deserialize;
 use serde_json;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::sync::Mutex;
 
+use super::core::check_storage_class;
 use super::core::GcsCore;
+use super::core::GcsCustomerEncryption;
+use super::core::MAX_PRESIGN_EXPIRE;
 use super::error::parse_error;
 use super::pager::GcsPager;
 use super::writer::GcsWriter;
@@ -40,9 +49,12 @@ use crate::raw::*;
 use crate::*;
 
 const DEFAULT_GCS_ENDPOINT: &str = "https://storage.googleapis.com";
+const X_GOOG_GENERATION: &str = "x-goog-generation";
 const DEFAULT_GCS_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
 /// It's recommended that you use at least 8 MiB for the chunk size.
 const DEFAULT_WRITE_FIXED_SIZE: usize = 8 * 1024 * 1024;
+/// The max count of source objects in one compose request.
+const MAX_COMPOSE_SOURCES: usize = 32;
 /// Google Cloud Storage service.
 ///
 /// # Capabilities
@@ -59,6 +71,7 @@ const DEFAULT_WRITE_FIXED_SIZE: usize = 8 * 1024 * 1024;
 /// - [x] list
 /// - [x] scan
 /// - [x] presign
+/// - [x] compose
 /// - [ ] blocking
 ///
 /// # Configuration
@@ -67,11 +80,60 @@ const DEFAULT_WRITE_FIXED_SIZE: usize = 8 * 1024 * 1024;
 /// - `bucket`: Set the container name for backend
 /// - `endpoint`: Customizable endpoint setting
 /// - `credentials`: Credential string for GCS OAuth2
+/// - `credential_path`: Credential file path for GCS OAuth2
+/// - `scope`: Scope of tokens, default to `devstorage.read_write`
+/// - `service_account`: Service account used to fetch token from vm metadata
+/// - `disable_config_load`: Disable loading credential from env and well-known location
+/// - `disable_vm_metadata`: Disable loading token from vm metadata server
 /// - `predefined_acl`: Predefined ACL for GCS
 /// - `default_storage_class`: Default storage class for GCS
+/// - `customer_encryption_key`: Base64 encoded customer supplied encryption key (CSEK)
+/// - `customer_encryption_key_sha256`: Base64 encoded SHA256 of the customer supplied encryption key
+/// - `kms_key_name`: Cloud KMS key used to encrypt written objects (CMEK)
 ///
 /// You can refer to [`GcsBuilder`]'s docs for more information
 ///
+/// # Credential
+///
+/// Tokens are loaded from the following sources in order:
+///
+/// - The credential set by `credential` or `credential_path`.
+/// - The file pointed by env `GOOGLE_APPLICATION_CREDENTIALS`, or the
+///   well-known location of gcloud.
+/// - The token endpoint of vm metadata server, which is available on GCE
+///   and GKE with workload identity.
+///
+/// Tokens are cached and refreshed before they expire. Concurrent requests
+/// will wait for the same refresh instead of loading at the same time.
+///
+/// Use `disable_config_load` and `disable_vm_metadata` to force a specific
+/// source.
+///
+/// # Encryption
+///
+/// Customer supplied encryption key (CSEK) is sent along with reads, writes
+/// and every chunk of resumable uploads. Customer managed encryption key
+/// (CMEK) set by `kms_key_name` is used for writes and copies only, reads
+/// will be decrypted by GCS transparently.
+///
+/// `stat` returns `AES256` as [`Metadata::server_side_encryption`] with the
+/// key SHA256 as key id for objects protected by CSEK, and `KMS` with the
+/// KMS key name for objects protected by CMEK.
+///
+/// # Compose
+///
+/// GCS composes at most 32 source objects in one request. Larger sets of
+/// sources are composed into intermediate objects named
+/// `<path>.compose-<uuid>` level by level first, which will be removed
+/// after the final object has been composed.
+///
+/// # Presign
+///
+/// Presigned requests are V4 signed urls of XML API, which require a service
+/// account credential with private key. They can be valid for at most 7 days.
+/// Presigned write requests carry the content type given in [`OpWrite`], and
+/// uploads must be sent with the same `Content-Type` header.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -120,11 +182,16 @@ pub struct GcsBuilder {
     credential: Option<String>,
     /// credential path for GCS service.
     credential_path: Option<String>,
+    disable_config_load: bool,
+    disable_vm_metadata: bool,
 
     http_client: Option<HttpClient>,
     customed_token_loader: Option<Box<dyn GoogleTokenLoad>>,
     predefined_acl: Option<String>,
     default_storage_class: Option<String>,
+    customer_encryption_key: Option<String>,
+    customer_encryption_key_sha256: Option<String>,
+    kms_key_name: Option<String>,
 
     /// the fixed size writer uses to flush into underlying storage.
     write_fixed_size: Option<usize>,
@@ -176,6 +243,9 @@ impl GcsBuilder {
     }
 
     /// set the endpoint GCS service uses
+    ///
+    /// If user inputs endpoint without scheme like "storage.googleapis.com",
+    /// we will prepend "https://" before it.
     pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
         if !endpoint.is_empty() {
             self.endpoint = Some(endpoint.to_string())
@@ -199,6 +269,27 @@ impl GcsBuilder {
         self
     }
 
+    /// Disable config load so that opendal will not load credential from
+    /// environment.
+    ///
+    /// For examples:
+    ///
+    /// - envs like `GOOGLE_APPLICATION_CREDENTIALS`
+    /// - files like `~/.config/gcloud/application_default_credentials.json`
+    pub fn disable_config_load(&mut self) -> &mut Self {
+        self.disable_config_load = true;
+        self
+    }
+
+    /// Disable load token from vm metadata.
+    ///
+    /// This option is used to disable the default behavior of opendal
+    /// to load token from the metadata server of GCE and GKE.
+    pub fn disable_vm_metadata(&mut self) -> &mut Self {
+        self.disable_vm_metadata = true;
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -246,6 +337,47 @@ impl GcsBuilder {
         self
     }
 
+    /// Set the base64 encoded customer supplied encryption key (CSEK).
+    ///
+    /// `customer_encryption_key_sha256` must be set along with it.
+    pub fn customer_encryption_key(&mut self, key: &str) -> &mut Self {
+        if !key.is_empty() {
+            self.customer_encryption_key = Some(key.to_string())
+        };
+        self
+    }
+
+    /// Set the base64 encoded SHA256 of customer supplied encryption key.
+    pub fn customer_encryption_key_sha256(&mut self, key_sha256: &str) -> &mut Self {
+        if !key_sha256.is_empty() {
+            self.customer_encryption_key_sha256 = Some(key_sha256.to_string())
+        };
+        self
+    }
+
+    /// Enable encryption with the given raw customer supplied encryption
+    /// key, which must be 32 bytes for `AES256`.
+    ///
+    /// NOTE: This function should not be used along with `kms_key_name`.
+    pub fn encryption_with_customer_key(&mut self, key: &[u8]) -> &mut Self {
+        self.customer_encryption_key = Some(BASE64_STANDARD.encode(key));
+        self.customer_encryption_key_sha256 =
+            Some(BASE64_STANDARD.encode(Sha256::digest(key).as_slice()));
+        self
+    }
+
+    /// Set the Cloud KMS key used to encrypt written objects (CMEK), like
+    /// `projects/my-pet-project/locations/us-east1/keyRings/my-key-ring/cryptoKeys/my-key`.
+    ///
+    /// NOTE: This function should not be used along with customer supplied
+    /// encryption key.
+    pub fn kms_key_name(&mut self, name: &str) -> &mut Self {
+        if !name.is_empty() {
+            self.kms_key_name = Some(name.to_string())
+        };
+        self
+    }
+
     /// The buffer size should be a multiple of 256 KiB (256 x 1024 bytes), unless it's the last chunk that completes the upload.
     /// Larger chunk sizes typically make uploads faster, but note that there's a tradeoff between speed and memory usage.
     /// It's recommended that you use at least 8 MiB for the chunk size.
@@ -271,6 +403,10 @@ impl Debug for GcsBuilder {
             ds.field("predefined_acl", &self.predefined_acl);
         }
         ds.field("default_storage_class", &self.default_storage_class);
+        if self.customer_encryption_key.is_some() {
+            ds.field("customer_encryption_key", &"<redacted>");
+        }
+        ds.field("kms_key_name", &self.kms_key_name);
         ds.finish()
     }
 }
@@ -278,6 +414,22 @@ impl Debug for GcsBuilder {
 impl Builder for GcsBuilder {
     const SCHEME: Scheme = Scheme::Gcs;
     type Accessor = GcsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "bucket",
+        "endpoint",
+        "credential",
+        "credential_path",
+        "scope",
+        "service_account",
+        "disable_config_load",
+        "disable_vm_metadata",
+        "predefined_acl",
+        "default_storage_class",
+        "customer_encryption_key",
+        "customer_encryption_key_sha256",
+        "kms_key_name",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = GcsBuilder::default();
@@ -286,10 +438,25 @@ impl Builder for GcsBuilder {
         map.get("bucket").map(|v| builder.bucket(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
         map.get("credential").map(|v| builder.credential(v));
+        map.get("credential_path")
+            .map(|v| builder.credential_path(v));
         map.get("scope").map(|v| builder.scope(v));
+        map.get("service_account")
+            .map(|v| builder.service_account(v));
+        map.get("disable_config_load")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_config_load());
+        map.get("disable_vm_metadata")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_vm_metadata());
         map.get("predefined_acl").map(|v| builder.predefined_acl(v));
         map.get("default_storage_class")
             .map(|v| builder.default_storage_class(v));
+        map.get("customer_encryption_key")
+            .map(|v| builder.customer_encryption_key(v));
+        map.get("customer_encryption_key_sha256")
+            .map(|v| builder.customer_encryption_key_sha256(v));
+        map.get("kms_key_name").map(|v| builder.kms_key_name(v));
 
         builder
     }
@@ -303,11 +470,9 @@ impl Builder for GcsBuilder {
         // Handle endpoint and bucket name
         let bucket = match self.bucket.is_empty() {
             false => Ok(&self.bucket),
-            true => Err(
-                Error::new(ErrorKind::ConfigInvalid, "The bucket is misconfigured")
-                    .with_operation("Builder::build")
-                    .with_context("service", Scheme::Gcs),
-            ),
+            true => Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Gcs)),
         }?;
 
         // TODO: server side encryption
@@ -321,10 +486,13 @@ impl Builder for GcsBuilder {
             })?
         };
 
-        let endpoint = self
-            .endpoint
-            .clone()
-            .unwrap_or_else(|| DEFAULT_GCS_ENDPOINT.to_string());
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => normalize_endpoint(endpoint).map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::Gcs)
+            })?,
+            None => DEFAULT_GCS_ENDPOINT.to_string(),
+        };
         debug!("backend use endpoint: {endpoint}");
 
         let mut cred_loader = GoogleCredentialLoader::default();
@@ -334,6 +502,17 @@ impl Builder for GcsBuilder {
         if let Some(cred) = &self.credential_path {
             cred_loader = cred_loader.with_path(cred);
         }
+        if self.disable_config_load {
+            cred_loader = cred_loader
+                .with_disable_env()
+                .with_disable_well_known_location();
+        }
+        let cred = cred_loader.load().map_err(|err| {
+            Error::new(ErrorKind::ConfigInvalid, "load credential failed")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Gcs)
+                .set_source(err)
+        })?;
 
         let scope = if let Some(scope) = &self.scope {
             scope
@@ -345,7 +524,18 @@ impl Builder for GcsBuilder {
         if let Some(account) = &self.service_account {
             token_loader = token_loader.with_service_account(account);
         }
-        if let Ok(Some(cred)) = cred_loader.load() {
+        if self.disable_vm_metadata {
+            token_loader = token_loader.with_disable_vm_metadata(true);
+        }
+        if cred.is_none() && self.disable_vm_metadata && self.customed_token_loader.is_none() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "no credential available, please set credential, credential_path or enable vm metadata",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Gcs));
+        }
+        if let Some(cred) = cred {
             token_loader = token_loader.with_credentials(cred)
         }
         if let Some(loader) = self.customed_token_loader.take() {
@@ -365,6 +555,44 @@ impl Builder for GcsBuilder {
             .with_context("write_fixed_size", write_fixed_size.to_string()));
         }
 
+        let customer_encryption = match (
+            self.customer_encryption_key.take(),
+            self.customer_encryption_key_sha256.take(),
+        ) {
+            (Some(key), Some(key_sha256)) => Some((key, key_sha256)),
+            (None, None) => None,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "customer encryption key and its sha256 must be set together",
+                )
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Gcs))
+            }
+        };
+        if customer_encryption.is_some() && self.kms_key_name.is_some() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "customer encryption key can't be used along with kms key name",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Gcs));
+        }
+        let customer_encryption = customer_encryption
+            .map(|(key, key_sha256)| {
+                let mut key = build_header_value(&key)?;
+                key.set_sensitive(true);
+                Ok::<_, Error>(GcsCustomerEncryption {
+                    key,
+                    key_sha256: build_header_value(&key_sha256)?,
+                })
+            })
+            .transpose()
+            .map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::Gcs)
+            })?;
+
         let backend = GcsBackend {
             core: Arc::new(GcsCore {
                 endpoint,
@@ -373,9 +601,12 @@ impl Builder for GcsBuilder {
                 client,
                 signer,
                 token_loader,
+                token_refresh: Mutex::new(()),
                 credential_loader: cred_loader,
                 predefined_acl: self.predefined_acl.clone(),
                 default_storage_class: self.default_storage_class.clone(),
+                customer_encryption,
+                kms_key_name: self.kms_key_name.clone(),
                 write_fixed_size,
             }),
         };
@@ -390,6 +621,68 @@ pub struct GcsBackend {
     core: Arc<GcsCore>,
 }
 
+impl GcsBackend {
+    async fn compose_once(&self, path: &str, sources: &[String]) -> Result<()> {
+        let resp = self.core.gcs_compose_object(path, sources).await?;
+
+        if resp.status().is_success() {
+            resp.into_body().consume().await?;
+            Ok(())
+        } else {
+            Err(parse_error(resp).await?)
+        }
+    }
+
+    /// Compose sources into path, intermediate objects created on the way
+    /// will be pushed into `intermediates` so that callers can remove them
+    /// whatever the result is.
+    async fn compose_tree(
+        &self,
+        path: &str,
+        mut sources: Vec<String>,
+        intermediates: &mut Vec<String>,
+    ) -> Result<()> {
+        while sources.len() > MAX_COMPOSE_SOURCES {
+            let mut next = Vec::with_capacity(sources.len() / MAX_COMPOSE_SOURCES + 1);
+            let mut tasks = Vec::new();
+
+            for chunk in sources.chunks(MAX_COMPOSE_SOURCES) {
+                if chunk.len() == 1 {
+                    next.push(chunk[0].clone());
+                    continue;
+                }
+
+                let p = format!("{path}.compose-{}", uuid::Uuid::new_v4());
+                intermediates.push(p.clone());
+                next.push(p.clone());
+                tasks.push(async move { self.compose_once(&p, chunk).await });
+            }
+
+            futures::future::try_join_all(tasks).await?;
+            sources = next;
+        }
+
+        self.compose_once(path, &sources).await
+    }
+
+    /// Delete objects concurrently, not existing objects will be ignored.
+    async fn delete_objects(&self, paths: &[String]) -> Result<()> {
+        let tasks = paths.iter().map(|p| async move {
+            let resp = self.core.gcs_delete_object(p, None, None).await?;
+
+            if resp.status().is_success() || resp.status() == StatusCode::NOT_FOUND {
+                resp.into_body().consume().await?;
+                Ok(())
+            } else {
+                Err(parse_error(resp).await?)
+            }
+        });
+
+        futures::future::try_join_all(tasks).await?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Accessor for GcsBackend {
     type Reader = IncomingAsyncBody;
@@ -408,18 +701,25 @@ impl Accessor for GcsBackend {
                 stat: true,
                 stat_with_if_match: true,
                 stat_with_if_none_match: true,
+                stat_with_generation: true,
 
                 read: true,
                 read_can_next: true,
                 read_with_range: true,
                 read_with_if_match: true,
                 read_with_if_none_match: true,
+                read_with_generation: true,
 
                 write: true,
                 write_with_content_type: true,
+                write_with_storage_class: true,
                 write_without_content_length: true,
+                write_can_resume: true,
+                write_with_if_generation_match: true,
                 delete: true,
+                delete_with_if_generation_match: true,
                 copy: true,
+                compose: true,
 
                 list: true,
                 list_with_limit: true,
@@ -431,6 +731,7 @@ impl Accessor for GcsBackend {
                 presign_stat: true,
                 presign_read: true,
                 presign_write: true,
+                presign_max_expire: Some(MAX_PRESIGN_EXPIRE),
 
                 ..Default::default()
             });
@@ -438,9 +739,15 @@ impl Accessor for GcsBackend {
     }
 
     async fn create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
-        let mut req = self
-            .core
-            .gcs_insert_object_request(path, Some(0), None, AsyncBody::Empty)?;
+        let mut req = self.core.gcs_insert_object_request(
+            path,
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+            AsyncBody::Empty,
+        )?;
 
         self.core.sign(&mut req).await?;
 
@@ -457,11 +764,20 @@ impl Accessor for GcsBackend {
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
         let resp = self
             .core
-            .gcs_get_object(path, args.range(), args.if_match(), args.if_none_match())
+            .gcs_get_object(
+                path,
+                args.range(),
+                args.if_match(),
+                args.if_none_match(),
+                args.generation(),
+            )
             .await?;
 
         if resp.status().is_success() {
-            let meta = parse_into_metadata(path, resp.headers())?;
+            let mut meta = parse_into_metadata(path, resp.headers())?;
+            if let Some(v) = parse_header_to_str(resp.headers(), X_GOOG_GENERATION)? {
+                meta.set_generation(parse_generation(v)?);
+            }
             Ok((RpRead::with_metadata(meta), resp.into_body()))
         } else {
             Err(parse_error(resp).await?)
@@ -469,10 +785,20 @@ impl Accessor for GcsBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        Ok((
-            RpWrite::default(),
-            GcsWriter::new(self.core.clone(), path, args),
-        ))
+        if let Some(storage_class) = args.storage_class() {
+            check_storage_class(storage_class)?;
+        }
+
+        let w = match args.resume() {
+            Some((session_url, offset)) => {
+                let session_url = session_url.to_string();
+                GcsWriter::resume_multipart(self.core.clone(), path, args, &session_url, offset)
+                    .await?
+            }
+            None => GcsWriter::new(self.core.clone(), path, args),
+        };
+
+        Ok((RpWrite::default(), w))
     }
 
     async fn copy(&self, from: &str, to: &str, _: OpCopy) -> Result<RpCopy> {
@@ -486,6 +812,24 @@ impl Accessor for GcsBackend {
         }
     }
 
+    async fn compose(&self, path: &str, args: OpCompose) -> Result<RpCompose> {
+        let mut intermediates = Vec::new();
+        let res = self
+            .compose_tree(path, args.sources().to_vec(), &mut intermediates)
+            .await;
+
+        if let Err(err) = self.delete_objects(&intermediates).await {
+            warn!("gcs compose failed to remove intermediate objects: {err:?}");
+        }
+        res?;
+
+        if args.delete_sources() {
+            self.delete_objects(args.sources()).await?;
+        }
+
+        Ok(RpCompose::default())
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         // Stat root always returns a DIR.
         if path == "/" {
@@ -494,7 +838,12 @@ impl Accessor for GcsBackend {
 
         let resp = self
             .core
-            .gcs_get_object_metadata(path, args.if_match(), args.if_none_match())
+            .gcs_get_object_metadata(
+                path,
+                args.if_match(),
+                args.if_none_match(),
+                args.generation(),
+            )
             .await?;
 
         if resp.status().is_success() {
@@ -519,11 +868,26 @@ impl Accessor for GcsBackend {
                 .parse::<u64>()
                 .map_err(|e| Error::new(ErrorKind::Unexpected, "parse u64").set_source(e))?;
             m.set_content_length(size);
+            m.set_is_dir_marker(is_dir_marker(path, Some(size)));
             if !meta.content_type.is_empty() {
                 m.set_content_type(&meta.content_type);
             }
 
             m.set_last_modified(parse_datetime_from_rfc3339(&meta.updated)?);
+            if !meta.generation.is_empty() {
+                m.set_generation(parse_generation(&meta.generation)?);
+            }
+            if !meta.storage_class.is_empty() {
+                m.set_storage_class(&meta.storage_class);
+            }
+
+            if let Some(v) = meta.customer_encryption {
+                m.set_server_side_encryption(&v.encryption_algorithm);
+                m.set_server_side_encryption_key_id(&v.key_sha256);
+            } else if !meta.kms_key_name.is_empty() {
+                m.set_server_side_encryption("KMS");
+                m.set_server_side_encryption_key_id(&meta.kms_key_name);
+            }
 
             Ok(RpStat::new(m))
         } else if resp.status() == StatusCode::NOT_FOUND && path.ends_with('/') {
@@ -533,8 +897,15 @@ impl Accessor for GcsBackend {
         }
     }
 
-    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
-        let resp = self.core.gcs_delete_object(path).await?;
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let resp = self
+            .core
+            .gcs_delete_object(
+                path,
+                args.if_generation_match(),
+                args.if_metageneration_match(),
+            )
+            .await?;
 
         // deleting not existing objects is ok
         if resp.status().is_success() || resp.status() == StatusCode::NOT_FOUND {
@@ -560,20 +931,41 @@ impl Accessor for GcsBackend {
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         // We will not send this request out, just for signing.
         let mut req = match args.operation() {
-            PresignOperation::Stat(v) => {
-                self.core
-                    .gcs_head_object_xml_request(path, v.if_match(), v.if_none_match())?
-            }
-            PresignOperation::Read(v) => self.core.gcs_get_object_xml_request(
+            PresignOperation::Stat(v) => self.core.gcs_head_object_xml_request(
                 path,
-                v.range(),
                 v.if_match(),
                 v.if_none_match(),
+                v.generation(),
             )?,
-            PresignOperation::Write(_) => {
-                self.core
-                    .gcs_insert_object_xml_request(path, None, AsyncBody::Empty)?
+            PresignOperation::Read(v) => {
+                // XML API only supports overriding content disposition and
+                // content type of the response.
+                if v.override_cache_control().is_some() {
+                    return Err(Error::new(
+                        ErrorKind::Unsupported,
+                        "presign read with override cache control is not supported",
+                    )
+                    .with_operation(Operation::Presign)
+                    .with_context("service", Scheme::Gcs));
+                }
+
+                self.core.gcs_get_object_xml_request(
+                    path,
+                    v.range(),
+                    v.if_match(),
+                    v.if_none_match(),
+                    v.override_content_disposition(),
+                    v.generation(),
+                )?
             }
+            // Content type will be signed, so uploads must carry the same one.
+            PresignOperation::Write(v) => self.core.gcs_insert_object_xml_request(
+                path,
+                v.content_type(),
+                v.if_generation_match(),
+                v.if_metageneration_match(),
+                AsyncBody::Empty,
+            )?,
         };
 
         self.core.sign_query(&mut req, args.expire()).await?;
@@ -620,10 +1012,43 @@ struct GetObjectJsonResponse {
     ///
     /// For example: `"contentType": "image/png",`
     content_type: String,
+    /// GCS will return generation in string.
+    ///
+    /// For example: `"generation": "1660563214863653"`
+    generation: String,
+    /// Storage class of this object.
+    ///
+    /// For example: `"storageClass": "STANDARD"`
+    storage_class: String,
+    /// Cloud KMS key used to encrypt this object, only set for objects
+    /// protected by CMEK.
+    kms_key_name: String,
+    /// Only set for objects protected by CSEK.
+    customer_encryption: Option<CustomerEncryption>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CustomerEncryption {
+    encryption_algorithm: String,
+    key_sha256: String,
+}
+
+/// Parse the generation returned by GCS in string.
+pub(super) fn parse_generation(v: &str) -> Result<i64> {
+    v.parse::<i64>().map_err(|e| {
+        Error::new(ErrorKind::Unexpected, "parse generation")
+            .with_context("generation", v)
+            .set_source(e)
+    })
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
     use super::*;
 
     #[test]
@@ -656,5 +1081,380 @@ mod tests {
         assert_eq!(meta.md5_hash, "fHcEH1vPwA6eTPqxuasXcg==");
         assert_eq!(meta.etag, "CKWasoTgyPkCEAE=");
         assert_eq!(meta.content_type, "image/png");
+        assert_eq!(meta.generation, "1660563214863653");
+        assert_eq!(meta.storage_class, "STANDARD");
+        assert!(meta.customer_encryption.is_none());
+        assert_eq!(meta.kms_key_name, "");
+    }
+
+    #[test]
+    fn test_deserialize_get_object_json_response_with_encryption() {
+        let content = r#"{
+  "name": "1.png",
+  "bucket": "example",
+  "size": "56535",
+  "customerEncryption": {
+    "encryptionAlgorithm": "AES256",
+    "keySha256": "5n3Ra+PRMe0Ud4gOLjWU17vuvoeEcdcqt8LWLHx1k8M="
+  }
+}"#;
+        let meta: GetObjectJsonResponse =
+            serde_json::from_str(content).expect("json Deserialize must succeed");
+        let v = meta
+            .customer_encryption
+            .expect("must have customer encryption");
+        assert_eq!(v.encryption_algorithm, "AES256");
+        assert_eq!(v.key_sha256, "5n3Ra+PRMe0Ud4gOLjWU17vuvoeEcdcqt8LWLHx1k8M=");
+
+        let content = r#"{
+  "name": "1.png",
+  "bucket": "example",
+  "size": "56535",
+  "kmsKeyName": "projects/p/locations/us/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1"
+}"#;
+        let meta: GetObjectJsonResponse =
+            serde_json::from_str(content).expect("json Deserialize must succeed");
+        assert!(meta.customer_encryption.is_none());
+        assert_eq!(
+            meta.kms_key_name,
+            "projects/p/locations/us/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1"
+        );
+    }
+
+    #[test]
+    fn test_build_without_bucket() {
+        let err = GcsBuilder::default().build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+    }
+
+    #[test]
+    fn test_build_normalize_endpoint() {
+        let cases = vec![
+            (
+                "schemeless",
+                "storage.googleapis.com",
+                "https://storage.googleapis.com",
+            ),
+            ("http", "http://localhost", "http://localhost"),
+            (
+                "https",
+                "https://storage.googleapis.com",
+                "https://storage.googleapis.com",
+            ),
+            (
+                "with port",
+                "http://127.0.0.1:4443",
+                "http://127.0.0.1:4443",
+            ),
+            (
+                "trailing slash",
+                "https://storage.googleapis.com/",
+                "https://storage.googleapis.com",
+            ),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut builder = GcsBuilder::default();
+            builder.bucket("test").endpoint(input);
+            let backend = builder.build().expect("build must succeed");
+
+            assert_eq!(backend.core.endpoint, expected, "{name}");
+        }
+
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("ftp://storage.googleapis.com");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_build_without_credential() {
+        let mut builder = GcsBuilder::from_map(HashMap::from([
+            ("bucket".to_string(), "test".to_string()),
+            ("disable_config_load".to_string(), "on".to_string()),
+            ("disable_vm_metadata".to_string(), "true".to_string()),
+        ]));
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("no credential available"));
+
+        // Token could still be fetched from vm metadata.
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test").disable_config_load();
+        builder.build().expect("build must succeed");
+
+        // Credential is base64 encoded `invalid`, which is not a valid json.
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .credential("aW52YWxpZA==")
+            .disable_vm_metadata();
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_build_with_encryption() {
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test").customer_encryption_key("a2V5");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("must be set together"));
+
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .encryption_with_customer_key(&[0; 32])
+            .kms_key_name("projects/p/locations/us/keyRings/r/cryptoKeys/k");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("kms key name"));
+
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .encryption_with_customer_key(&[0; 32]);
+        assert!(!format!("{builder:?}").contains("AAAA"));
+        let backend = builder.build().expect("build must succeed");
+        let v = backend
+            .core
+            .customer_encryption
+            .as_ref()
+            .expect("must have customer encryption");
+        assert_eq!(v.key, "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+        assert!(v.key.is_sensitive());
+        assert_eq!(v.key_sha256, "Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU=");
+    }
+
+    #[test]
+    fn test_encryption_requests() {
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .encryption_with_customer_key(&[0; 32]);
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_get_object_request("test", BytesRange::default(), None, None, None)
+            .expect("request must be built");
+        assert_eq!(
+            req.headers().get("x-goog-encryption-algorithm").unwrap(),
+            "AES256"
+        );
+        assert_eq!(
+            req.headers().get("x-goog-encryption-key-sha256").unwrap(),
+            "Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU="
+        );
+
+        let req = backend
+            .core
+            .gcs_insert_object_request("test", Some(0), None, None, None, None, AsyncBody::Empty)
+            .expect("request must be built");
+        assert!(req.headers().contains_key("x-goog-encryption-key"));
+
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .kms_key_name("projects/p/locations/us/keyRings/r/cryptoKeys/k");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_insert_object_request("test", Some(0), None, None, None, None, AsyncBody::Empty)
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/upload/storage/v1/b/test/o?uploadType=media&name=test&kmsKeyName=projects%2Fp%2Flocations%2Fus%2FkeyRings%2Fr%2FcryptoKeys%2Fk"
+        );
+        assert!(!req.headers().contains_key("x-goog-encryption-key"));
+    }
+
+    #[test]
+    fn test_get_object_xml_request() {
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test").root("/dir");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_get_object_xml_request(
+                "a b.txt",
+                BytesRange::default(),
+                None,
+                None,
+                Some("attachment; filename=\"a b.txt\""),
+                None,
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/test/dir/a%20b.txt?response-content-disposition=attachment%3B%20filename%3D%22a%20b.txt%22"
+        );
+
+        let req = backend
+            .core
+            .gcs_get_object_xml_request(
+                "a b.txt",
+                BytesRange::default(),
+                None,
+                None,
+                None,
+                Some(1660563214863653),
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/test/dir/a%20b.txt?generation=1660563214863653"
+        );
+    }
+
+    #[test]
+    fn test_generation_requests() {
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_get_object_request(
+                "test",
+                BytesRange::default(),
+                None,
+                None,
+                Some(1660563214863653),
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/storage/v1/b/test/o/test?alt=media&generation=1660563214863653"
+        );
+
+        let req = backend
+            .core
+            .gcs_head_object_request("test", None, None, Some(1660563214863653))
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/storage/v1/b/test/o/test?generation=1660563214863653"
+        );
+
+        let req = backend
+            .core
+            .gcs_insert_object_request(
+                "test",
+                Some(0),
+                None,
+                None,
+                Some(0),
+                Some(1),
+                AsyncBody::Empty,
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/upload/storage/v1/b/test/o?uploadType=media&name=test&ifGenerationMatch=0&ifMetagenerationMatch=1"
+        );
+
+        let req = backend
+            .core
+            .gcs_insert_object_xml_request("test", None, Some(0), None, AsyncBody::Empty)
+            .expect("request must be built");
+        assert_eq!(
+            req.headers().get("x-goog-if-generation-match").unwrap(),
+            "0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_with_storage_class() {
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_insert_object_request(
+                "test",
+                Some(5),
+                None,
+                Some("COLDLINE"),
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/upload/storage/v1/b/test/o?uploadType=multipart&name=test"
+        );
+
+        // s3 storage class is not supported by gcs.
+        let res = backend
+            .write("test", OpWrite::new().with_storage_class("GLACIER"))
+            .await;
+        assert_eq!(
+            res.err().map(|err| err.kind()),
+            Some(ErrorKind::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_compose_object_request() {
+        let mut builder = GcsBuilder::default();
+        builder
+            .bucket("test")
+            .root("/dir")
+            .default_storage_class("NEARLINE")
+            .kms_key_name("projects/p/locations/us/keyRings/r/cryptoKeys/k");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .gcs_compose_object_request("a b.txt", &["shard-0".to_string(), "shard-1".to_string()])
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://storage.googleapis.com/storage/v1/b/test/o/dir%2Fa%20b.txt/compose?kmsKeyName=projects%2Fp%2Flocations%2Fus%2FkeyRings%2Fr%2FcryptoKeys%2Fk"
+        );
+        let bs = match req.into_body() {
+            AsyncBody::Bytes(bs) => bs,
+            _ => panic!("body must be bytes"),
+        };
+        assert_eq!(
+            String::from_utf8_lossy(&bs),
+            r#"{"sourceObjects":[{"name":"dir/shard-0"},{"name":"dir/shard-1"}],"destination":{"storageClass":"NEARLINE"}}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_generation() {
+        assert_eq!(
+            parse_generation("1660563214863653").unwrap(),
+            1660563214863653
+        );
+        assert_eq!(
+            parse_generation("invalid").unwrap_err().kind(),
+            ErrorKind::Unexpected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presign_with_too_long_expire() {
+        let mut builder = GcsBuilder::default();
+        builder.bucket("test");
+        let backend = builder.build().expect("build must succeed");
+
+        let err = backend
+            .presign(
+                "test",
+                OpPresign::new(OpRead::new(), Duration::from_secs(8 * 24 * 60 * 60)),
+            )
+            .await
+            .expect_err("presign must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
     }
 }
@@ -29,19 +29,35 @@ use http::header::CONTENT_RANGE;
 use http::header::CONTENT_TYPE;
 use http::header::IF_MATCH;
 use http::header::IF_NONE_MATCH;
+use http::HeaderValue;
 use http::Request;
 use http::Response;
 use once_cell::sync::Lazy;
-use reqsign::GoogleCredential;
 use reqsign::GoogleCredentialLoader;
 use reqsign::GoogleSigner;
 use reqsign::GoogleToken;
 use reqsign::GoogleTokenLoader;
+use serde::Serialize;
+use tokio::sync::Mutex;
 
 use super::uri::percent_encode_path;
 use crate::raw::*;
 use crate::*;
 
+/// The max expire duration of V4 signed url.
+///
+/// Reference: <https://cloud.google.com/storage/docs/access-control/signed-urls#example>
+pub const MAX_PRESIGN_EXPIRE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const X_GOOG_ENCRYPTION_ALGORITHM: &str = "x-goog-encryption-algorithm";
+const X_GOOG_ENCRYPTION_KEY: &str = "x-goog-encryption-key";
+const X_GOOG_ENCRYPTION_KEY_SHA256: &str = "x-goog-encryption-key-sha256";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_ALGORITHM: &str = "x-goog-copy-source-encryption-algorithm";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_KEY: &str = "x-goog-copy-source-encryption-key";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_KEY_SHA256: &str = "x-goog-copy-source-encryption-key-sha256";
+const X_GOOG_IF_GENERATION_MATCH: &str = "x-goog-if-generation-match";
+const X_GOOG_IF_METAGENERATION_MATCH: &str = "x-goog-if-metageneration-match";
+
 pub struct GcsCore {
     pub endpoint: String,
     pub bucket: String,
@@ -50,14 +66,29 @@ pub struct GcsCore {
     pub client: HttpClient,
     pub signer: GoogleSigner,
     pub token_loader: GoogleTokenLoader,
+    /// Guard the refresh of token so that concurrent requests will wait for
+    /// the same refresh, tokens are cached inside `token_loader`.
+    pub token_refresh: Mutex<()>,
     pub credential_loader: GoogleCredentialLoader,
 
     pub predefined_acl: Option<String>,
     pub default_storage_class: Option<String>,
+    pub customer_encryption: Option<GcsCustomerEncryption>,
+    pub kms_key_name: Option<String>,
 
     pub write_fixed_size: usize,
 }
 
+/// Customer supplied encryption key (CSEK) of gcs.
+///
+/// Reference: <https://cloud.google.com/storage/docs/encryption/customer-supplied-keys>
+pub struct GcsCustomerEncryption {
+    /// Base64 encoded key, which is marked as sensitive.
+    pub key: HeaderValue,
+    /// Base64 encoded SHA256 of the key.
+    pub key_sha256: HeaderValue,
+}
+
 impl Debug for GcsCore {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut de = f.debug_struct("Backend");
@@ -73,6 +104,7 @@ static BACKOFF: Lazy<ExponentialBuilder> =
 
 impl GcsCore {
     async fn load_token(&self) -> Result<GoogleToken> {
+        let _guard = self.token_refresh.lock().await;
         let cred = { || self.token_loader.load() }
             .retry(&*BACKOFF)
             .await
@@ -83,24 +115,9 @@ impl GcsCore {
         } else {
             Err(Error::new(
                 ErrorKind::ConfigInvalid,
-                "no valid credential found",
-            ))
-        }
-    }
-
-    fn load_credential(&self) -> Result<GoogleCredential> {
-        let cred = self
-            .credential_loader
-            .load()
-            .map_err(new_request_credential_error)?;
-
-        if let Some(cred) = cred {
-            Ok(cred)
-        } else {
-            Err(Error::new(
-                ErrorKind::ConfigInvalid,
-                "no valid credential found",
-            ))
+                "no valid credential found from config, env or vm metadata",
+            )
+            .with_context("service", Scheme::Gcs))
         }
     }
 
@@ -110,12 +127,50 @@ impl GcsCore {
         self.signer.sign(req, &cred).map_err(new_request_sign_error)
     }
 
+    /// Sign request with V4 signature in query.
+    ///
+    /// Only service account credentials carry the private key that
+    /// required by V4 signing, tokens loaded from workload identity or
+    /// vm metadata can't be used here.
     pub async fn sign_query<T>(&self, req: &mut Request<T>, duration: Duration) -> Result<()> {
-        let cred = self.load_credential()?;
+        if duration > MAX_PRESIGN_EXPIRE {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "presign expire can't be longer than 7 days",
+            )
+            .with_operation(Operation::Presign)
+            .with_context("service", Scheme::Gcs)
+            .with_context("expire", format!("{duration:?}")));
+        }
 
-        self.signer
-            .sign_query(req, duration, &cred)
-            .map_err(new_request_sign_error)
+        let cred = self
+            .credential_loader
+            .load()
+            .map_err(new_request_credential_error)?
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unsupported,
+                    "presign requires a service account credential with private key",
+                )
+                .with_operation(Operation::Presign)
+                .with_context("service", Scheme::Gcs)
+            })?;
+
+        self.signer.sign_query(req, duration, &cred).map_err(|err| {
+            // reqsign refuses to sign with external account which
+            // doesn't have private key.
+            if err.to_string().contains("external account") {
+                Error::new(
+                    ErrorKind::Unsupported,
+                    "presign requires a service account credential with private key",
+                )
+                .with_operation(Operation::Presign)
+                .with_context("service", Scheme::Gcs)
+                .set_source(err)
+            } else {
+                new_request_sign_error(err)
+            }
+        })
     }
 
     #[inline]
@@ -125,21 +180,59 @@ impl GcsCore {
 }
 
 impl GcsCore {
+    /// Insert customer supplied encryption key headers for reads and writes.
+    pub fn insert_encryption_headers(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if let Some(v) = &self.customer_encryption {
+            req = req
+                .header(X_GOOG_ENCRYPTION_ALGORITHM, "AES256")
+                .header(X_GOOG_ENCRYPTION_KEY, v.key.clone())
+                .header(X_GOOG_ENCRYPTION_KEY_SHA256, v.key_sha256.clone());
+        }
+
+        req
+    }
+
+    /// Insert customer supplied encryption key headers for the source of
+    /// copy.
+    pub fn insert_copy_source_encryption_headers(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if let Some(v) = &self.customer_encryption {
+            req = req
+                .header(X_GOOG_COPY_SOURCE_ENCRYPTION_ALGORITHM, "AES256")
+                .header(X_GOOG_COPY_SOURCE_ENCRYPTION_KEY, v.key.clone())
+                .header(
+                    X_GOOG_COPY_SOURCE_ENCRYPTION_KEY_SHA256,
+                    v.key_sha256.clone(),
+                );
+        }
+
+        req
+    }
+
     pub fn gcs_get_object_request(
         &self,
         path: &str,
         range: BytesRange,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/storage/v1/b/{}/o/{}?alt=media",
             self.endpoint,
             self.bucket,
             percent_encode_path(&p)
         );
+        if let Some(v) = generation {
+            write!(url, "&generation={v}").expect("write into string must succeed");
+        }
 
         let mut req = Request::get(&url);
 
@@ -153,6 +246,8 @@ impl GcsCore {
             req = req.header(http::header::RANGE, range.to_header());
         }
 
+        req = self.insert_encryption_headers(req);
+
         let req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -167,10 +262,31 @@ impl GcsCore {
         range: BytesRange,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        override_content_disposition: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!("{}/{}/{}", self.endpoint, self.bucket, p);
+        let mut url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.bucket,
+            raw::percent_encode_path(&p)
+        );
+
+        let mut query_args = Vec::new();
+        if let Some(v) = override_content_disposition {
+            query_args.push(format!(
+                "response-content-disposition={}",
+                percent_encode_path(v)
+            ));
+        }
+        if let Some(v) = generation {
+            query_args.push(format!("generation={v}"));
+        }
+        if !query_args.is_empty() {
+            write!(url, "?{}", query_args.join("&")).expect("write into string must succeed");
+        }
 
         let mut req = Request::get(&url);
 
@@ -197,27 +313,35 @@ impl GcsCore {
         range: BytesRange,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.gcs_get_object_request(path, range, if_match, if_none_match)?;
+        let mut req =
+            self.gcs_get_object_request(path, range, if_match, if_none_match, generation)?;
 
         self.sign(&mut req).await?;
         self.send(req).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn gcs_insert_object_request(
         &self,
         path: &str,
         size: Option<usize>,
         content_type: Option<&str>,
+        storage_class: Option<&str>,
+        if_generation_match: Option<i64>,
+        if_metageneration_match: Option<i64>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
+        // The given storage class overrides the default one.
+        let storage_class = storage_class.or(self.default_storage_class.as_deref());
 
         let mut url = format!(
             "{}/upload/storage/v1/b/{}/o?uploadType={}&name={}",
             self.endpoint,
             self.bucket,
-            if self.default_storage_class.is_some() {
+            if storage_class.is_some() {
                 "multipart"
             } else {
                 "media"
@@ -228,12 +352,17 @@ impl GcsCore {
         if let Some(acl) = &self.predefined_acl {
             write!(&mut url, "&predefinedAcl={}", acl).unwrap();
         }
+        write_preconditions(&mut url, if_generation_match, if_metageneration_match);
+        if let Some(v) = &self.kms_key_name {
+            write!(&mut url, "&kmsKeyName={}", percent_encode_path(v)).unwrap();
+        }
 
         let mut req = Request::post(&url);
 
         req = req.header(CONTENT_LENGTH, size.unwrap_or_default());
+        req = self.insert_encryption_headers(req);
 
-        if let Some(storage_class) = &self.default_storage_class {
+        if let Some(storage_class) = storage_class {
             req = req.header(CONTENT_TYPE, "multipart/related; boundary=my-boundary");
 
             let mut req_body = BytesMut::with_capacity(100);
@@ -272,11 +401,18 @@ impl GcsCore {
         &self,
         path: &str,
         content_type: Option<&str>,
+        if_generation_match: Option<i64>,
+        if_metageneration_match: Option<i64>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!("{}/{}/{}", self.endpoint, self.bucket, p);
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.bucket,
+            raw::percent_encode_path(&p)
+        );
 
         let mut req = Request::put(&url);
 
@@ -284,6 +420,13 @@ impl GcsCore {
             req = req.header(CONTENT_TYPE, content_type);
         }
 
+        if let Some(v) = if_generation_match {
+            req = req.header(X_GOOG_IF_GENERATION_MATCH, v);
+        }
+        if let Some(v) = if_metageneration_match {
+            req = req.header(X_GOOG_IF_METAGENERATION_MATCH, v);
+        }
+
         if let Some(acl) = &self.predefined_acl {
             req = req.header("x-goog-acl", acl);
         }
@@ -302,15 +445,19 @@ impl GcsCore {
         path: &str,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/storage/v1/b/{}/o/{}",
             self.endpoint,
             self.bucket,
             percent_encode_path(&p)
         );
+        if let Some(v) = generation {
+            write!(url, "?generation={v}").expect("write into string must succeed");
+        }
 
         let mut req = Request::get(&url);
 
@@ -335,10 +482,19 @@ impl GcsCore {
         path: &str,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!("{}/{}/{}", self.endpoint, self.bucket, p);
+        let mut url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.bucket,
+            raw::percent_encode_path(&p)
+        );
+        if let Some(v) = generation {
+            write!(url, "?generation={v}").expect("write into string must succeed");
+        }
 
         let mut req = Request::head(&url);
 
@@ -362,24 +518,41 @@ impl GcsCore {
         path: &str,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        generation: Option<i64>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.gcs_head_object_request(path, if_match, if_none_match)?;
+        let mut req = self.gcs_head_object_request(path, if_match, if_none_match, generation)?;
 
         self.sign(&mut req).await?;
 
         self.send(req).await
     }
 
-    pub async fn gcs_delete_object(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+    pub async fn gcs_delete_object(
+        &self,
+        path: &str,
+        if_generation_match: Option<i64>,
+        if_metageneration_match: Option<i64>,
+    ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/storage/v1/b/{}/o/{}",
             self.endpoint,
             self.bucket,
             percent_encode_path(&p)
         );
 
+        let mut query_args = Vec::new();
+        if let Some(v) = if_generation_match {
+            query_args.push(format!("ifGenerationMatch={v}"));
+        }
+        if let Some(v) = if_metageneration_match {
+            query_args.push(format!("ifMetagenerationMatch={v}"));
+        }
+        if !query_args.is_empty() {
+            write!(url, "?{}", query_args.join("&")).expect("write into string must succeed");
+        }
+
         let mut req = Request::delete(&url)
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -397,7 +570,7 @@ impl GcsCore {
         let source = build_abs_path(&self.root, from);
         let dest = build_abs_path(&self.root, to);
 
-        let req_uri = format!(
+        let mut req_uri = format!(
             "{}/storage/v1/b/{}/o/{}/copyTo/b/{}/o/{}",
             self.endpoint,
             self.bucket,
@@ -406,8 +579,15 @@ impl GcsCore {
             percent_encode_path(&dest)
         );
 
-        let mut req = Request::post(req_uri)
-            .header(CONTENT_LENGTH, 0)
+        if let Some(v) = &self.kms_key_name {
+            write!(req_uri, "?destinationKmsKeyName={}", percent_encode_path(v))
+                .expect("write into string must succeed");
+        }
+
+        let mut req = Request::post(req_uri).header(CONTENT_LENGTH, 0);
+        req = self.insert_encryption_headers(req);
+        req = self.insert_copy_source_encryption_headers(req);
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
@@ -415,6 +595,66 @@ impl GcsCore {
         self.send(req).await
     }
 
+    pub fn gcs_compose_object_request(
+        &self,
+        path: &str,
+        sources: &[String],
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut url = format!(
+            "{}/storage/v1/b/{}/o/{}/compose",
+            self.endpoint,
+            self.bucket,
+            percent_encode_path(&p)
+        );
+
+        let mut query_args = Vec::new();
+        if let Some(acl) = &self.predefined_acl {
+            query_args.push(format!("destinationPredefinedAcl={acl}"));
+        }
+        if let Some(v) = &self.kms_key_name {
+            query_args.push(format!("kmsKeyName={}", percent_encode_path(v)));
+        }
+        if !query_args.is_empty() {
+            write!(url, "?{}", query_args.join("&")).expect("write into string must succeed");
+        }
+
+        let compose = ComposeRequest {
+            source_objects: sources
+                .iter()
+                .map(|v| ComposeSourceObject {
+                    name: build_abs_path(&self.root, v),
+                })
+                .collect(),
+            destination: ComposeDestination {
+                storage_class: self.default_storage_class.clone(),
+            },
+        };
+        let bs = Bytes::from(serde_json::to_vec(&compose).map_err(new_json_serialize_error)?);
+
+        let mut req = Request::post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, bs.len());
+        req = self.insert_encryption_headers(req);
+        let req = req
+            .body(AsyncBody::Bytes(bs))
+            .map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    pub async fn gcs_compose_object(
+        &self,
+        path: &str,
+        sources: &[String],
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.gcs_compose_object_request(path, sources)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
     pub async fn gcs_list_objects(
         &self,
         path: &str,
@@ -432,7 +672,10 @@ impl GcsCore {
             percent_encode_path(&p)
         );
         if !delimiter.is_empty() {
-            write!(url, "&delimiter={delimiter}").expect("write into string must succeed");
+            // Make dir placeholder objects returned in items too, so that
+            // we can expose their metadata.
+            write!(url, "&delimiter={delimiter}&includeTrailingDelimiter=true")
+                .expect("write into string must succeed");
         }
         if let Some(limit) = limit {
             write!(url, "&maxResults={limit}").expect("write into string must succeed");
@@ -463,19 +706,43 @@ impl GcsCore {
         self.send(req).await
     }
 
+    /// Preconditions are checked while the upload is completed.
     pub async fn gcs_initiate_resumable_upload(
         &self,
         path: &str,
+        storage_class: Option<&str>,
+        if_generation_match: Option<i64>,
+        if_metageneration_match: Option<i64>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
-        let url = format!(
+        let mut url = format!(
             "{}/upload/storage/v1/b/{}/o?uploadType=resumable&name={}",
             self.endpoint, self.bucket, p
         );
+        write_preconditions(&mut url, if_generation_match, if_metageneration_match);
+        if let Some(v) = &self.kms_key_name {
+            write!(&mut url, "&kmsKeyName={}", percent_encode_path(v))
+                .expect("write into string must succeed");
+        }
 
-        let mut req = Request::post(&url)
-            .header(CONTENT_LENGTH, 0)
-            .body(AsyncBody::Empty)
+        // Object metadata like storage class is carried by the initiate request.
+        let body = match storage_class.or(self.default_storage_class.as_deref()) {
+            Some(v) => {
+                let metadata = ResumableUploadMetadata {
+                    storage_class: v.to_string(),
+                };
+                Bytes::from(serde_json::to_vec(&metadata).map_err(new_json_serialize_error)?)
+            }
+            None => Bytes::new(),
+        };
+
+        let mut req = Request::post(&url).header(CONTENT_LENGTH, body.len());
+        if !body.is_empty() {
+            req = req.header(CONTENT_TYPE, "application/json");
+        }
+        req = self.insert_encryption_headers(req);
+        let mut req = req
+            .body(AsyncBody::Bytes(body))
             .map_err(new_request_build_error)?;
 
         self.sign(&mut req).await?;
@@ -506,6 +773,7 @@ impl GcsCore {
         req = req
             .header(CONTENT_LENGTH, size)
             .header(CONTENT_RANGE, range_header);
+        req = self.insert_encryption_headers(req);
 
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
@@ -520,17 +788,22 @@ impl GcsCore {
         bs: Bytes,
     ) -> Result<Response<IncomingAsyncBody>> {
         let size = bs.len() as u64;
+        let range_header = if size == 0 {
+            // Finalize the upload without sending more data.
+            format!("bytes */{written_bytes}")
+        } else {
+            format!(
+                "bytes {}-{}/{}",
+                written_bytes,
+                written_bytes + size - 1,
+                written_bytes + size
+            )
+        };
         let mut req = Request::post(location)
             .header(CONTENT_LENGTH, size)
-            .header(
-                CONTENT_RANGE,
-                format!(
-                    "bytes {}-{}/{}",
-                    written_bytes,
-                    written_bytes + size - 1,
-                    written_bytes + size
-                ),
-            )
+            .header(CONTENT_RANGE, range_header);
+        req = self.insert_encryption_headers(req);
+        let mut req = req
             .body(AsyncBody::Bytes(bs))
             .map_err(new_request_build_error)?;
 
@@ -539,6 +812,24 @@ impl GcsCore {
         self.send(req).await
     }
 
+    /// Query the status of resumable upload via an empty put.
+    ///
+    /// Reference: [Checking the status of a resumable upload](https://cloud.google.com/storage/docs/performing-resumable-uploads#status-check)
+    pub async fn gcs_query_resumable_upload(
+        &self,
+        location: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::put(location)
+            .header(CONTENT_LENGTH, 0)
+            .header(CONTENT_RANGE, "bytes */*")
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
     pub async fn gcs_abort_resumable_upload(
         &self,
         location: &str,
@@ -553,3 +844,72 @@ impl GcsCore {
         self.send(req).await
     }
 }
+
+/// Append generation preconditions to the url that already has queries.
+fn write_preconditions(
+    url: &mut String,
+    if_generation_match: Option<i64>,
+    if_metageneration_match: Option<i64>,
+) {
+    if let Some(v) = if_generation_match {
+        write!(url, "&ifGenerationMatch={v}").expect("write into string must succeed");
+    }
+    if let Some(v) = if_metageneration_match {
+        write!(url, "&ifMetagenerationMatch={v}").expect("write into string must succeed");
+    }
+}
+
+/// Request body of compose.
+///
+/// Reference: <https://cloud.google.com/storage/docs/json_api/v1/objects/compose>
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeRequest {
+    source_objects: Vec<ComposeSourceObject>,
+    destination: ComposeDestination,
+}
+
+/// Storage classes that can be applied to objects, `MULTI_REGIONAL`,
+/// `REGIONAL` and `DURABLE_REDUCED_AVAILABILITY` are legacy ones.
+///
+/// Reference: <https://cloud.google.com/storage/docs/storage-classes>
+const STORAGE_CLASSES: [&str; 7] = [
+    "STANDARD",
+    "NEARLINE",
+    "COLDLINE",
+    "ARCHIVE",
+    "MULTI_REGIONAL",
+    "REGIONAL",
+    "DURABLE_REDUCED_AVAILABILITY",
+];
+
+/// Check if given storage class is supported by gcs.
+pub fn check_storage_class(storage_class: &str) -> Result<()> {
+    if STORAGE_CLASSES.contains(&storage_class) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "storage class is not supported")
+            .with_context("service", Scheme::Gcs)
+            .with_context("storage_class", storage_class),
+    )
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumableUploadMetadata {
+    storage_class: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeSourceObject {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeDestination {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_class: Option<String>,
+}
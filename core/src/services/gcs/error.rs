@@ -54,12 +54,17 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
     let bs = body.bytes().await?;
 
+    let gcs_err = de::from_slice::<GcsErrorResponse>(&bs);
+
     let (kind, retryable) = match parts.status {
+        StatusCode::BAD_REQUEST if gcs_err.as_ref().map_or(false, is_encryption_key_error) => {
+            (ErrorKind::PermissionDenied, false)
+        }
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
-            (ErrorKind::ConditionNotMatch, false)
-        }
+        StatusCode::PRECONDITION_FAILED => (ErrorKind::ConditionNotMatch, false),
+        StatusCode::NOT_MODIFIED => (ErrorKind::NotModified, false),
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -67,7 +72,7 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
 
-    let message = match de::from_slice::<GcsErrorResponse>(&bs) {
+    let message = match gcs_err {
         Ok(gcs_err) => format!("{gcs_err:?}"),
         Err(_) => String::from_utf8_lossy(&bs).into_owned(),
     };
@@ -81,6 +86,24 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     Ok(err)
 }
 
+/// Check whether the error is caused by a missing or mismatched customer
+/// supplied encryption key, for example reading an object encrypted with
+/// CSEK without providing the key.
+///
+/// GCS returns `400 Bad Request` for them, but they are permission problems
+/// from the view of users.
+fn is_encryption_key_error(err: &GcsErrorResponse) -> bool {
+    err.error.errors.iter().any(|v| {
+        matches!(
+            v.reason.as_str(),
+            "resourceIsEncryptedWithCustomerEncryptionKey"
+                | "resourceNotEncryptedWithCustomerEncryptionKey"
+                | "customerEncryptionKeyIsIncorrect"
+                | "customerEncryptionKeySha256IsInvalid"
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +141,36 @@ mod tests {
         assert_eq!(out.error.errors[0].location_type, "header");
         assert_eq!(out.error.errors[0].location, "Authorization");
     }
+
+    #[tokio::test]
+    async fn test_parse_encryption_key_error() {
+        let bs = bytes::Bytes::from(
+            r#"
+{
+"error": {
+ "errors": [
+  {
+   "domain": "global",
+   "reason": "resourceIsEncryptedWithCustomerEncryptionKey",
+   "message": "The target object is encrypted by a customer-supplied encryption key."
+  }
+ ],
+ "code": 400,
+ "message": "The target object is encrypted by a customer-supplied encryption key."
+ }
+}
+"#,
+        );
+        let resp = Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(IncomingAsyncBody::new(
+                Box::new(futures::stream::iter(vec![Ok(bs)])),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(!err.is_temporary());
+    }
 }
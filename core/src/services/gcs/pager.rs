@@ -15,12 +15,15 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json;
 
+use super::backend::parse_generation;
 use super::core::GcsCore;
 use super::error::parse_error;
 use crate::raw::*;
@@ -41,6 +44,9 @@ pub struct GcsPager {
 
     page_token: String,
     done: bool,
+    /// Dirs that have been returned, GCS could return the same prefix in
+    /// different pages.
+    visited_dirs: HashSet<String>,
 }
 
 impl GcsPager {
@@ -62,6 +68,7 @@ impl GcsPager {
 
             page_token: "".to_string(),
             done: false,
+            visited_dirs: HashSet::new(),
         }
     }
 }
@@ -98,51 +105,97 @@ impl oio::Page for GcsPager {
             self.done = true;
         }
 
-        let mut entries = Vec::with_capacity(output.prefixes.len() + output.items.len());
+        let entries = merge_entries(
+            &self.core.root,
+            &self.path,
+            self.start_after.as_deref(),
+            &mut self.visited_dirs,
+            output,
+        )?;
 
-        for prefix in output.prefixes {
-            let de = oio::Entry::new(
-                &build_rel_path(&self.core.root, &prefix),
-                Metadata::new(EntryMode::DIR),
-            );
+        Ok(Some(entries))
+    }
+}
+
+/// Merge prefixes and items of a page into entries in lexical order.
+///
+/// - Dir placeholder objects (zero-byte objects whose names end with `/`)
+///   are returned by both `prefixes` and `items` while
+///   `includeTrailingDelimiter` is set, they will be merged into one dir
+///   entry with metadata of the object.
+/// - Dirs that have been returned in previous pages will be skipped.
+/// - The listing dir itself and the inclusive `start_after` are skipped.
+fn merge_entries(
+    root: &str,
+    path: &str,
+    start_after: Option<&str>,
+    visited_dirs: &mut HashSet<String>,
+    output: ListResponse,
+) -> Result<Vec<oio::Entry>> {
+    let mut merged: BTreeMap<String, Option<ListResponseItem>> = BTreeMap::new();
+    for prefix in output.prefixes {
+        merged.entry(prefix).or_insert(None);
+    }
+    for item in output.items {
+        merged.insert(item.name.clone(), Some(item));
+    }
+
+    let abs_path = build_abs_path(root, path);
+    let mut entries = Vec::with_capacity(merged.len());
 
-            entries.push(de);
+    for (name, item) in merged {
+        if name == abs_path {
+            continue;
         }
 
-        for object in output.items {
-            if object.name.ends_with('/') {
-                continue;
-            }
+        // exclude the inclusive start_after itself
+        let rel_path = build_rel_path(root, &name);
+        if start_after == Some(rel_path.as_str()) {
+            continue;
+        }
 
-            // exclude the inclusive start_after itself
-            let path = &build_rel_path(&self.core.root, &object.name);
-            if self.start_after.as_ref() == Some(path) {
+        let mut meta = if name.ends_with('/') {
+            if !visited_dirs.insert(name.clone()) {
                 continue;
             }
+            Metadata::new(EntryMode::DIR)
+        } else {
+            Metadata::new(EntryMode::FILE)
+        };
 
-            let mut meta = Metadata::new(EntryMode::FILE);
-
-            // set metadata fields
-            meta.set_content_md5(object.md5_hash.as_str());
-            meta.set_etag(object.etag.as_str());
+        if let Some(item) = item {
+            set_item_metadata(&mut meta, &item)?;
+            meta.set_is_dir_marker(is_dir_marker(&name, meta.content_length_raw()));
+        }
 
-            let size = object.size.parse().map_err(|e| {
-                Error::new(ErrorKind::Unexpected, "parse u64 from list response").set_source(e)
-            })?;
-            meta.set_content_length(size);
-            if !object.content_type.is_empty() {
-                meta.set_content_type(&object.content_type);
-            }
+        entries.push(oio::Entry::with(rel_path, meta));
+    }
 
-            meta.set_last_modified(parse_datetime_from_rfc3339(object.updated.as_str())?);
+    Ok(entries)
+}
 
-            let de = oio::Entry::new(path, meta);
+fn set_item_metadata(meta: &mut Metadata, item: &ListResponseItem) -> Result<()> {
+    if !item.md5_hash.is_empty() {
+        meta.set_content_md5(item.md5_hash.as_str());
+    }
+    if !item.etag.is_empty() {
+        meta.set_etag(item.etag.as_str());
+    }
 
-            entries.push(de);
-        }
+    let size = item.size.parse().map_err(|e| {
+        Error::new(ErrorKind::Unexpected, "parse u64 from list response").set_source(e)
+    })?;
+    meta.set_content_length(size);
+    if !item.content_type.is_empty() {
+        meta.set_content_type(&item.content_type);
+    }
 
-        Ok(Some(entries))
+    meta.set_last_modified(parse_datetime_from_rfc3339(item.updated.as_str())?);
+    if !item.generation.is_empty() {
+        meta.set_generation(parse_generation(&item.generation)?);
     }
+
+    Ok(())
 }
 
 /// Response JSON from GCS list objects API.
@@ -172,6 +225,7 @@ struct ListResponseItem {
     md5_hash: String,
     updated: String,
     content_type: String,
+    generation: String,
 }
 
 #[cfg(test)]
@@ -239,12 +293,14 @@ mod tests {
         assert_eq!(output.items[0].md5_hash, "fHcEH1vPwA6eTPqxuasXcg==");
         assert_eq!(output.items[0].etag, "CKWasoTgyPkCEAE=");
         assert_eq!(output.items[0].updated, "2022-08-15T11:33:34.866Z");
+        assert_eq!(output.items[0].generation, "1660563214863653");
         assert_eq!(output.items[1].name, "2.png");
         assert_eq!(output.items[1].size, "45506");
         assert_eq!(output.items[1].md5_hash, "e6LsGusU7pFJZk+114NV1g==");
         assert_eq!(output.items[1].etag, "CIm0s4TgyPkCEAE=");
         assert_eq!(output.items[1].updated, "2022-08-15T11:33:34.886Z");
         assert_eq!(output.items[1].content_type, "image/png");
+        assert_eq!(output.items[1].generation, "1660563214883337");
         assert_eq!(output.prefixes, vec!["dir/", "test/"])
     }
 
@@ -317,4 +373,98 @@ mod tests {
         assert_eq!(output.items[1].updated, "2022-08-15T11:33:34.886Z");
         assert_eq!(output.prefixes, vec!["dir/", "test/"])
     }
+
+    #[test]
+    fn test_merge_entries() {
+        let pages = vec![
+            r#"{
+  "kind": "storage#objects",
+  "prefixes": ["dir/a/"],
+  "nextPageToken": "CgZkaXIvYS8=",
+  "items": [
+    {
+      "name": "dir/",
+      "size": "0",
+      "etag": "CKWasoTgyPkCEAE=",
+      "updated": "2022-08-15T11:33:34.866Z",
+      "generation": "1660563214863653"
+    },
+    {
+      "name": "dir/a",
+      "size": "56535",
+      "md5Hash": "fHcEH1vPwA6eTPqxuasXcg==",
+      "etag": "CIm0s4TgyPkCEAE=",
+      "contentType": "image/png",
+      "updated": "2022-08-15T11:33:34.886Z",
+      "generation": "1660563214883337"
+    },
+    {
+      "name": "dir/a/",
+      "size": "0",
+      "etag": "CJ3ds4TgyPkCEAE=",
+      "updated": "2022-08-15T11:33:34.900Z",
+      "generation": "1660563214900000"
+    }
+  ]
+}"#,
+            r#"{
+  "kind": "storage#objects",
+  "prefixes": ["dir/a/", "dir/b/"],
+  "items": [
+    {
+      "name": "dir/c",
+      "size": "1",
+      "updated": "2022-08-15T11:33:34.910Z"
+    }
+  ]
+}"#,
+        ];
+
+        let mut visited = HashSet::new();
+        let mut entries = vec![];
+        for page in pages {
+            let output: ListResponse =
+                serde_json::from_str(page).expect("JSON deserialize must succeed");
+            entries.extend(
+                merge_entries("/", "dir/", None, &mut visited, output).expect("must succeed"),
+            );
+        }
+
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["dir/a", "dir/a/", "dir/b/", "dir/c"]);
+
+        // File and dir with the same name are both returned.
+        assert_eq!(entries[0].mode(), EntryMode::FILE);
+        assert_eq!(entries[0].metadata().content_length(), 56535);
+        assert_eq!(entries[0].metadata().content_type(), Some("image/png"));
+        assert_eq!(entries[1].mode(), EntryMode::DIR);
+        // Dir placeholder carries the metadata of its object.
+        assert_eq!(entries[1].metadata().etag(), Some("CJ3ds4TgyPkCEAE="));
+        assert_eq!(entries[1].metadata().generation(), Some(1660563214900000));
+        assert!(entries[1].metadata().is_dir_marker());
+        // Prefix without placeholder object only has mode.
+        assert_eq!(entries[2].mode(), EntryMode::DIR);
+        assert_eq!(entries[2].metadata().etag(), None);
+        assert!(!entries[2].metadata().is_dir_marker());
+        assert!(!entries[3].metadata().is_dir_marker());
+        assert_eq!(entries[3].metadata().content_length(), 1);
+    }
+
+    #[test]
+    fn test_merge_entries_with_start_after() {
+        let output: ListResponse = serde_json::from_str(
+            r#"{
+  "items": [
+    {"name": "root/dir/a", "size": "1", "updated": "2022-08-15T11:33:34.866Z"},
+    {"name": "root/dir/b", "size": "1", "updated": "2022-08-15T11:33:34.866Z"}
+  ]
+}"#,
+        )
+        .expect("JSON deserialize must succeed");
+
+        let entries = merge_entries("/root/", "dir/", Some("dir/a"), &mut HashSet::new(), output)
+            .expect("must succeed");
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["dir/b"]);
+    }
 }
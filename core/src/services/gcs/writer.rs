@@ -19,6 +19,8 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use http::header::RANGE;
+use http::HeaderMap;
 use http::StatusCode;
 
 use super::core::GcsCore;
@@ -36,6 +38,9 @@ pub struct GcsWriter {
     written: u64,
     buffer: oio::VectorCursor,
     write_fixed_size: usize,
+    /// Set if the last upload failed, the committed bytes must be
+    /// re-synchronized with GCS before next upload.
+    resync: bool,
 }
 
 impl GcsWriter {
@@ -50,14 +55,51 @@ impl GcsWriter {
             written: 0,
             buffer: oio::VectorCursor::new(),
             write_fixed_size,
+            resync: false,
         }
     }
 
+    /// Resume a resumable upload that initiated before.
+    ///
+    /// GCS will be queried for the committed bytes of this session, which
+    /// must be equal to `offset`.
+    pub async fn resume_multipart(
+        core: Arc<GcsCore>,
+        path: &str,
+        op: OpWrite,
+        session_url: &str,
+        offset: u64,
+    ) -> Result<Self> {
+        let committed = query_committed(&core, session_url).await.map_err(|err| {
+            err.with_operation("GcsWriter::resume_multipart")
+                .with_context("path", path)
+        })?;
+
+        if committed != offset {
+            return Err(Error::new(
+                ErrorKind::ConditionNotMatch,
+                "offset doesn't match the committed bytes of resumable upload",
+            )
+            .with_operation("GcsWriter::resume_multipart")
+            .with_context("path", path)
+            .with_context("offset", offset.to_string())
+            .with_context("committed", committed.to_string()));
+        }
+
+        let mut w = GcsWriter::new(core, path, op);
+        w.location = Some(session_url.to_string());
+        w.written = committed;
+        Ok(w)
+    }
+
     async fn write_oneshot(&self, bs: Bytes) -> Result<()> {
         let mut req = self.core.gcs_insert_object_request(
             &percent_encode_path(&self.path),
             Some(bs.len()),
             self.op.content_type(),
+            self.op.storage_class(),
+            self.op.if_generation_match(),
+            self.op.if_metageneration_match(),
             AsyncBody::Bytes(bs),
         )?;
 
@@ -77,7 +119,15 @@ impl GcsWriter {
     }
 
     async fn initiate_upload(&self) -> Result<String> {
-        let resp = self.core.gcs_initiate_resumable_upload(&self.path).await?;
+        let resp = self
+            .core
+            .gcs_initiate_resumable_upload(
+                &self.path,
+                self.op.storage_class(),
+                self.op.if_generation_match(),
+                self.op.if_metageneration_match(),
+            )
+            .await?;
         let status = resp.status();
 
         match status {
@@ -96,10 +146,15 @@ impl GcsWriter {
         }
     }
 
-    async fn write_part(&self, location: &str, bs: Bytes) -> Result<()> {
+    /// Upload a part and return the committed bytes of this session.
+    ///
+    /// GCS may persist only part of the uploaded bytes, the actual
+    /// committed bytes are carried by the `Range` header of `308` response.
+    async fn write_part(&self, location: &str, bs: Bytes) -> Result<u64> {
+        let size = bs.len() as u64;
         let mut req = self.core.gcs_upload_in_resumable_upload(
             location,
-            bs.len() as u64,
+            size,
             self.written,
             false,
             AsyncBody::Bytes(bs),
@@ -111,17 +166,47 @@ impl GcsWriter {
 
         let status = resp.status();
         match status {
-            StatusCode::OK | StatusCode::PERMANENT_REDIRECT => Ok(()),
+            StatusCode::PERMANENT_REDIRECT => {
+                let committed = parse_committed_bytes(resp.headers())?;
+                resp.into_body().consume().await?;
+                Ok(committed)
+            }
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(self.written + size)
+            }
             _ => Err(parse_error(resp).await?),
         }
     }
+
+    /// Drop the committed bytes from buffer and move forward.
+    fn advance(&mut self, committed: u64) -> Result<()> {
+        consume_committed(&mut self.buffer, self.written, committed)?;
+        self.written = committed;
+        Ok(())
+    }
+
+    /// Query the committed bytes from GCS if the last upload failed.
+    ///
+    /// The failed upload may have been persisted partly or even fully, so
+    /// we can't simply retry with the same range.
+    async fn resync(&mut self, location: &str) -> Result<()> {
+        if !self.resync {
+            return Ok(());
+        }
+
+        let committed = query_committed(&self.core, location).await?;
+        self.advance(committed)?;
+        self.resync = false;
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl oio::Write for GcsWriter {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
         let location = match &self.location {
-            Some(location) => location,
+            Some(location) => location.clone(),
             None => {
                 if self.op.content_length().unwrap_or_default() == bs.len() as u64
                     && self.written == 0
@@ -129,8 +214,8 @@ impl oio::Write for GcsWriter {
                     return self.write_oneshot(bs).await;
                 } else {
                     let location = self.initiate_upload().await?;
-                    self.location = Some(location);
-                    self.location.as_deref().unwrap()
+                    self.location = Some(location.clone());
+                    location
                 }
             }
         };
@@ -141,6 +226,10 @@ impl oio::Write for GcsWriter {
         }
 
         self.buffer.push(bs);
+        if let Err(e) = self.resync(&location).await {
+            self.buffer.pop();
+            return Err(e);
+        }
         // Return directly if the buffer is not full
         if self.buffer.len() <= self.write_fixed_size {
             return Ok(());
@@ -148,16 +237,14 @@ impl oio::Write for GcsWriter {
 
         let bs = self.buffer.peak_exact(self.write_fixed_size);
 
-        match self.write_part(location, bs).await {
-            Ok(_) => {
-                self.buffer.take(self.write_fixed_size);
-                self.written += self.write_fixed_size as u64;
-                Ok(())
-            }
+        match self.write_part(&location, bs).await {
+            Ok(committed) => self.advance(committed),
             Err(e) => {
                 // If the upload fails, we should pop the given bs to make sure
-                // write is re-enter safe.
+                // write is re-enter safe, and re-synchronize the committed
+                // bytes before next upload.
                 self.buffer.pop();
+                self.resync = true;
                 Err(e)
             }
         }
@@ -187,28 +274,171 @@ impl oio::Write for GcsWriter {
 
     async fn close(&mut self) -> Result<()> {
         let location = if let Some(location) = &self.location {
-            location
+            location.clone()
         } else {
             return Ok(());
         };
 
-        let bs = self.buffer.peak_exact(self.buffer.len());
+        self.resync(&location).await?;
 
-        let resp = self
-            .core
-            .gcs_complete_resumable_upload(location, self.written, bs)
-            .await?;
+        loop {
+            let bs = self.buffer.peak_exact(self.buffer.len());
 
-        let status = resp.status();
-        match status {
-            StatusCode::OK => {
-                resp.into_body().consume().await?;
+            let resp = match self
+                .core
+                .gcs_complete_resumable_upload(&location, self.written, bs)
+                .await
+            {
+                Ok(resp) => resp,
+                Err(err) => {
+                    self.resync = true;
+                    return Err(err);
+                }
+            };
 
-                self.location = None;
-                self.buffer.clear();
-                Ok(())
+            let status = resp.status();
+            match status {
+                StatusCode::OK | StatusCode::CREATED => {
+                    resp.into_body().consume().await?;
+
+                    self.location = None;
+                    self.buffer.clear();
+                    return Ok(());
+                }
+                // Only part of the last chunk has been persisted, upload
+                // the rest again.
+                StatusCode::PERMANENT_REDIRECT => {
+                    let committed = parse_committed_bytes(resp.headers())?;
+                    resp.into_body().consume().await?;
+                    if committed <= self.written {
+                        return Err(Error::new(
+                            ErrorKind::Unexpected,
+                            "resumable upload makes no progress",
+                        ));
+                    }
+                    self.advance(committed)?;
+                }
+                _ => return Err(parse_error(resp).await?),
             }
-            _ => Err(parse_error(resp).await?),
         }
     }
+
+    /// Returns the session url and committed bytes of resumable upload,
+    /// `None` if upload is not initiated yet.
+    ///
+    /// # Notes
+    ///
+    /// Anyone holding the session url can upload to this session, please
+    /// keep it as a secret.
+    fn resume(&self) -> Option<(&str, u64)> {
+        self.location.as_deref().map(|url| (url, self.written))
+    }
+}
+
+/// Query the committed bytes of a resumable upload session.
+async fn query_committed(core: &GcsCore, location: &str) -> Result<u64> {
+    let resp = core.gcs_query_resumable_upload(location).await?;
+    let status = resp.status();
+
+    match status {
+        StatusCode::PERMANENT_REDIRECT => {
+            let committed = parse_committed_bytes(resp.headers())?;
+            resp.into_body().consume().await?;
+            Ok(committed)
+        }
+        StatusCode::OK | StatusCode::CREATED => {
+            resp.into_body().consume().await?;
+            Err(Error::new(
+                ErrorKind::ConditionNotMatch,
+                "resumable upload has already been completed",
+            ))
+        }
+        _ => Err(parse_error(resp).await?),
+    }
+}
+
+/// Consume the bytes between `written` and `committed` from buffer.
+///
+/// Returns error if `committed` is out of the range that buffer holds.
+fn consume_committed(buffer: &mut oio::VectorCursor, written: u64, committed: u64) -> Result<()> {
+    if committed < written || committed - written > buffer.len() as u64 {
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            "committed bytes of resumable upload is out of the buffered range",
+        )
+        .with_context("written", written.to_string())
+        .with_context("buffered", buffer.len().to_string())
+        .with_context("committed", committed.to_string()));
+    }
+
+    buffer.take((committed - written) as usize);
+    Ok(())
+}
+
+/// Parse the committed bytes from the `Range` header of a `308` response.
+///
+/// GCS returns `Range: bytes=0-{last_byte}` if some bytes have been
+/// committed, and omits it if nothing has been committed.
+fn parse_committed_bytes(headers: &HeaderMap) -> Result<u64> {
+    let range = match parse_header_to_str(headers, RANGE.as_str())? {
+        None => return Ok(0),
+        Some(v) => v,
+    };
+
+    range
+        .strip_prefix("bytes=0-")
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|last| last + 1)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "range header of resumable upload is invalid",
+            )
+            .with_context("range", range)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_committed_bytes() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_committed_bytes(&headers).unwrap(), 0);
+
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-262143"));
+        assert_eq!(parse_committed_bytes(&headers).unwrap(), 262144);
+
+        headers.insert(RANGE, HeaderValue::from_static("bytes=1-262143"));
+        assert_eq!(
+            parse_committed_bytes(&headers).unwrap_err().kind(),
+            ErrorKind::Unexpected
+        );
+    }
+
+    #[test]
+    fn test_consume_committed() {
+        let mut buffer = oio::VectorCursor::new();
+        buffer.push(Bytes::from("Hello, "));
+        buffer.push(Bytes::from("World!"));
+
+        // Nothing has been persisted.
+        consume_committed(&mut buffer, 10, 10).expect("must succeed");
+        assert_eq!(buffer.len(), 13);
+
+        // Only part of the uploaded bytes has been persisted.
+        consume_committed(&mut buffer, 10, 19).expect("must succeed");
+        assert_eq!(buffer.peak_all(), Bytes::from("rld!"));
+
+        // Committed bytes go backward.
+        let err = consume_committed(&mut buffer, 19, 18).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+
+        // Committed bytes exceed the buffer.
+        let err = consume_committed(&mut buffer, 19, 25).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+    }
 }
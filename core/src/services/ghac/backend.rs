@@ -207,6 +207,7 @@ impl GhacBuilder {
 impl Builder for GhacBuilder {
     const SCHEME: Scheme = Scheme::Ghac;
     type Accessor = GhacBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "version", "enable_create_simulation"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = GhacBuilder::default();
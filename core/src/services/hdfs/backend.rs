@@ -17,11 +17,14 @@
 
 use std::cmp::min;
 use std::collections::HashMap;
+use std::ffi::CString;
 use std::fmt::Debug;
 use std::io;
 use std::io::SeekFrom;
 use std::path::PathBuf;
+use std::ptr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use log::debug;
@@ -53,6 +56,26 @@ use crate::*;
 /// - [ ] ~~presign~~
 /// - [x] blocking
 ///
+/// # Append and Truncate
+///
+/// Write with [`OpWrite::with_append`] appends to the existing file, the
+/// file will be created if it doesn't exist.
+///
+/// [`Operator::truncate`] is supported on Hadoop 2.7+. If the new size is
+/// not on a block boundary, HDFS adjusts the last block in background and
+/// truncate will wait for it to complete, so the file can be appended
+/// right after truncate returns.
+///
+/// # Replication and Permissions
+///
+/// Stat returns the owner, group, permissions, replication and block size
+/// of entries. Write with [`OpWrite::with_replication`] and
+/// [`OpWrite::with_block_size`] to override the cluster defaults of new
+/// files, and [`OpWrite::with_unix_permissions`] to set their permissions.
+///
+/// [`Operator::set_permissions`] and [`Operator::set_owner`] are supported
+/// as well, changing owner requires superuser privilege.
+///
 /// # Differences with webhdfs
 ///
 /// [Webhdfs][crate::services::Webhdfs] is powered by hdfs's RESTful HTTP API.
@@ -175,6 +198,7 @@ impl HdfsBuilder {
 impl Builder for HdfsBuilder {
     const SCHEME: Scheme = Scheme::Hdfs;
     type Accessor = HdfsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "name_node"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = HdfsBuilder::default();
@@ -201,6 +225,18 @@ impl Builder for HdfsBuilder {
 
         let client = hdrs::Client::connect(name_node).map_err(parse_io_error)?;
 
+        // hdrs doesn't expose its filesystem handle, so we connect again for
+        // operations like truncate. Hadoop caches filesystem instances, both
+        // handles will share the same one.
+        let fs = {
+            let name_node =
+                CString::new(name_node.as_str()).map_err(|err| parse_io_error(err.into()))?;
+            unsafe { hdfs_sys::hdfsConnect(name_node.as_ptr(), 0) }
+        };
+        if fs.is_null() {
+            return Err(parse_io_error(io::Error::last_os_error()));
+        }
+
         // Create root dir if not exist.
         if let Err(e) = client.metadata(&root) {
             if e.kind() == io::ErrorKind::NotFound {
@@ -214,21 +250,128 @@ impl Builder for HdfsBuilder {
         Ok(HdfsBackend {
             root,
             client: Arc::new(client),
+            fs,
         })
     }
 }
 
+/// Delay between polls while truncate is still in progress.
+const TRUNCATE_POLL_DELAY: Duration = Duration::from_millis(100);
+/// Max delay between polls while truncate is still in progress.
+const TRUNCATE_MAX_POLL_DELAY: Duration = Duration::from_secs(1);
+/// Max times to poll before returning a temporary error.
+const TRUNCATE_MAX_POLLS: usize = 60;
+
 /// Backend for hdfs services.
 #[derive(Debug, Clone)]
 pub struct HdfsBackend {
     root: String,
     client: Arc<hdrs::Client>,
+    /// The raw filesystem handle, only used by operations that hdrs
+    /// doesn't support.
+    fs: hdfs_sys::hdfsFS,
 }
 
-/// hdrs::Client is thread-safe.
+/// hdrs::Client and hdfsFS are thread-safe.
 unsafe impl Send for HdfsBackend {}
 unsafe impl Sync for HdfsBackend {}
 
+impl HdfsBackend {
+    /// Build open options for write.
+    ///
+    /// HDFS can only append to existing files, so we create the file
+    /// instead if it doesn't exist.
+    fn write_options(&self, p: &str, args: &OpWrite) -> Result<hdrs::OpenOptions> {
+        let mut opts = self.client.open_file();
+
+        let append = args.append()
+            && match self.client.metadata(p) {
+                Ok(_) => true,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+                Err(err) => return Err(parse_io_error(err)),
+            };
+        if append {
+            opts.append(true);
+        } else {
+            opts.create(true).write(true);
+        }
+
+        // Replication and block size only take effect on newly created files.
+        if let Some(replication) = args.replication() {
+            opts.with_replication(usize::from(replication));
+        }
+        if let Some(block_size) = args.block_size() {
+            // libhdfs takes block size as a 32-bit int.
+            if block_size > i32::MAX as u64 {
+                return Err(
+                    Error::new(ErrorKind::Unsupported, "block size is too large")
+                        .with_context("block_size", block_size.to_string()),
+                );
+            }
+            opts.with_blocksize(block_size as usize);
+        }
+
+        Ok(opts)
+    }
+
+    /// Set unix permission bits of `p`.
+    fn chmod(&self, p: &str, mode: u32) -> Result<()> {
+        let mode = i16::try_from(mode).map_err(|_| {
+            Error::new(ErrorKind::Unsupported, "permissions are invalid")
+                .with_context("mode", format!("{mode:o}"))
+        })?;
+        let path = CString::new(p).map_err(|err| parse_io_error(err.into()))?;
+
+        match unsafe { hdfs_sys::hdfsChmod(self.fs, path.as_ptr(), mode) } {
+            0 => Ok(()),
+            _ => Err(parse_io_error(io::Error::last_os_error())),
+        }
+    }
+
+    /// Set owner and group of `p`, `None` means keeping it unchanged.
+    fn chown(&self, p: &str, owner: Option<&str>, group: Option<&str>) -> Result<()> {
+        let path = CString::new(p).map_err(|err| parse_io_error(err.into()))?;
+        let owner = owner
+            .map(CString::new)
+            .transpose()
+            .map_err(|err| parse_io_error(err.into()))?;
+        let group = group
+            .map(CString::new)
+            .transpose()
+            .map_err(|err| parse_io_error(err.into()))?;
+
+        match unsafe {
+            hdfs_sys::hdfsChown(
+                self.fs,
+                path.as_ptr(),
+                owner.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+                group.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            )
+        } {
+            0 => Ok(()),
+            _ => Err(parse_io_error(io::Error::last_os_error())),
+        }
+    }
+
+    /// Truncate file to `size`.
+    ///
+    /// Returns `false` if HDFS is still adjusting the last block in
+    /// background.
+    fn truncate_file(&self, p: &str, size: u64) -> Result<bool> {
+        let size = i64::try_from(size).map_err(|_| {
+            Error::new(ErrorKind::Unexpected, "truncate size is too large")
+                .with_context("size", size.to_string())
+        })?;
+        let path = CString::new(p).map_err(|err| parse_io_error(err.into()))?;
+
+        match unsafe { hdfs_sys::hdfsTruncateFile(self.fs, path.as_ptr(), size) } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(parse_io_error(io::Error::last_os_error())),
+        }
+    }
+}
+
 #[async_trait]
 impl Accessor for HdfsBackend {
     type Reader = oio::into_reader::FdReader<hdrs::AsyncFile>;
@@ -250,12 +393,20 @@ impl Accessor for HdfsBackend {
                 read_with_range: true,
 
                 write: true,
+                write_with_append: true,
+                write_with_unix_permissions: true,
+                write_with_replication: true,
+                write_with_block_size: true,
                 create_dir: true,
                 delete: true,
 
                 list: true,
                 list_with_delimiter_slash: true,
 
+                truncate: true,
+                set_permissions: true,
+                set_owner: true,
+
                 blocking: true,
 
                 ..Default::default()
@@ -288,26 +439,17 @@ impl Accessor for HdfsBackend {
             .await
             .map_err(parse_io_error)?;
 
-        let br = args.range();
-        let (start, end) = match (br.offset(), br.size()) {
-            // Read a specific range.
-            (Some(offset), Some(size)) => (offset, min(offset + size, meta.len())),
-            // Read from offset.
-            (Some(offset), None) => (offset, meta.len()),
-            // Read the last size bytes.
-            (None, Some(size)) => (meta.len() - size, meta.len()),
-            // Read the whole file.
-            (None, None) => (0, meta.len()),
-        };
+        let range = args.range().resolve(meta.len())?;
+        let (start, end) = (range.start, range.end);
 
         let mut r = oio::into_reader::from_fd(f, start, end);
         // Rewind to make sure we are on the correct offset.
         r.seek(SeekFrom::Start(0)).await?;
 
-        Ok((RpRead::new(end - start), r))
+        Ok((RpRead::from_range(range, meta.len()), r))
     }
 
-    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         let p = build_rooted_abs_path(&self.root, path);
 
         let parent = PathBuf::from(&p)
@@ -326,13 +468,13 @@ impl Accessor for HdfsBackend {
             .map_err(parse_io_error)?;
 
         let f = self
-            .client
-            .open_file()
-            .create(true)
-            .write(true)
+            .write_options(&p, &args)?
             .async_open(&p)
             .await
             .map_err(parse_io_error)?;
+        if let Some(mode) = args.unix_permissions() {
+            self.chmod(&p, mode)?;
+        }
 
         Ok((RpWrite::new(), HdfsWriter::new(f)))
     }
@@ -342,18 +484,7 @@ impl Accessor for HdfsBackend {
 
         let meta = self.client.metadata(&p).map_err(parse_io_error)?;
 
-        let mode = if meta.is_dir() {
-            EntryMode::DIR
-        } else if meta.is_file() {
-            EntryMode::FILE
-        } else {
-            EntryMode::Unknown
-        };
-        let mut m = Metadata::new(mode);
-        m.set_content_length(meta.len());
-        m.set_last_modified(meta.modified().into());
-
-        Ok(RpStat::new(m))
+        Ok(RpStat::new(parse_metadata(&meta)))
     }
 
     async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
@@ -402,6 +533,59 @@ impl Accessor for HdfsBackend {
         Ok((RpList::default(), Some(rd)))
     }
 
+    async fn truncate(&self, path: &str, args: OpTruncate) -> Result<RpTruncate> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        if self.truncate_file(&p, args.size())? {
+            return Ok(RpTruncate::default());
+        }
+
+        // HDFS is adjusting the last block in background. Truncate to the
+        // same size again will succeed once it's done, and fail with lease
+        // recovery in progress before that.
+        let mut delay = TRUNCATE_POLL_DELAY;
+        for _ in 0..TRUNCATE_MAX_POLLS {
+            tokio::time::sleep(delay).await;
+            delay = min(delay * 2, TRUNCATE_MAX_POLL_DELAY);
+
+            match self.truncate_file(&p, args.size()) {
+                Ok(true) => return Ok(RpTruncate::default()),
+                Ok(false) => continue,
+                Err(err) if err.is_temporary() => {
+                    debug!("truncate {p} is still in progress: {err:?}");
+                    continue;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(
+            Error::new(ErrorKind::Unexpected, "truncate is still in progress")
+                .with_context("path", &p)
+                .set_temporary(),
+        )
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        self.chmod(&p, args.mode())?;
+
+        Ok(RpSetPermissions::default())
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        self.chown(&p, args.owner(), args.group())?;
+
+        Ok(RpSetOwner::default())
+    }
+
     fn blocking_create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
         let p = build_rooted_abs_path(&self.root, path);
 
@@ -425,26 +609,17 @@ impl Accessor for HdfsBackend {
             .open(&p)
             .map_err(parse_io_error)?;
 
-        let br = args.range();
-        let (start, end) = match (br.offset(), br.size()) {
-            // Read a specific range.
-            (Some(offset), Some(size)) => (offset, min(offset + size, meta.len())),
-            // Read from offset.
-            (Some(offset), None) => (offset, meta.len()),
-            // Read the last size bytes.
-            (None, Some(size)) => (meta.len() - size, meta.len()),
-            // Read the whole file.
-            (None, None) => (0, meta.len()),
-        };
+        let range = args.range().resolve(meta.len())?;
+        let (start, end) = (range.start, range.end);
 
         let mut r = oio::into_blocking_reader::from_fd(f, start, end);
         // Rewind to make sure we are on the correct offset.
         r.seek(SeekFrom::Start(0))?;
 
-        Ok((RpRead::new(end - start), r))
+        Ok((RpRead::from_range(range, meta.len()), r))
     }
 
-    fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
         let p = build_rooted_abs_path(&self.root, path);
 
         let parent = PathBuf::from(&p)
@@ -463,12 +638,12 @@ impl Accessor for HdfsBackend {
             .map_err(parse_io_error)?;
 
         let f = self
-            .client
-            .open_file()
-            .create(true)
-            .write(true)
+            .write_options(&p, &args)?
             .open(&p)
             .map_err(parse_io_error)?;
+        if let Some(mode) = args.unix_permissions() {
+            self.chmod(&p, mode)?;
+        }
 
         Ok((RpWrite::new(), HdfsWriter::new(f)))
     }
@@ -478,18 +653,7 @@ impl Accessor for HdfsBackend {
 
         let meta = self.client.metadata(&p).map_err(parse_io_error)?;
 
-        let mode = if meta.is_dir() {
-            EntryMode::DIR
-        } else if meta.is_file() {
-            EntryMode::FILE
-        } else {
-            EntryMode::Unknown
-        };
-        let mut m = Metadata::new(mode);
-        m.set_content_length(meta.len());
-        m.set_last_modified(meta.modified().into());
-
-        Ok(RpStat::new(m))
+        Ok(RpStat::new(parse_metadata(&meta)))
     }
 
     fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
@@ -538,3 +702,27 @@ impl Accessor for HdfsBackend {
         Ok((RpList::default(), Some(rd)))
     }
 }
+
+/// Parse hdfs metadata into our metadata.
+fn parse_metadata(meta: &hdrs::Metadata) -> Metadata {
+    let mode = if meta.is_dir() {
+        EntryMode::DIR
+    } else if meta.is_file() {
+        EntryMode::FILE
+    } else {
+        EntryMode::Unknown
+    };
+    let mut m = Metadata::new(mode);
+    m.set_content_length(meta.len());
+    m.set_last_modified(meta.modified().into());
+    m.set_unix_permissions(u32::from(meta.permissions()));
+    m.set_owner(meta.owner());
+    m.set_group(meta.group());
+    // Replication and block size are always 0 for dirs.
+    if meta.is_file() {
+        m.set_replication(meta.replication());
+        m.set_block_size(meta.block_size());
+    }
+
+    m
+}
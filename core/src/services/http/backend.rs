@@ -20,8 +20,11 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 
 use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
 use http::header;
 use http::header::IF_MATCH;
+use http::header::IF_MODIFIED_SINCE;
 use http::header::IF_NONE_MATCH;
 use http::Request;
 use http::Response;
@@ -56,6 +59,15 @@ use crate::*;
 /// Only `read` ans `stat` are supported. We can use this service to visit any
 /// HTTP Server like nginx, caddy.
 ///
+/// `stat` is served by `HEAD`. For servers that don't allow `HEAD`, we will
+/// fall back to `GET` with `Range: bytes=0-0` and take the content length
+/// from `Content-Range`. The content length will be unknown if the server
+/// doesn't support range.
+///
+/// Cache hints like `If-None-Match` and `If-Modified-Since` will be sent
+/// as is, [`ErrorKind::NotModified`] will be returned if the content is
+/// still fresh.
+///
 /// # Configuration
 ///
 /// - `endpoint`: set the endpoint for http
@@ -173,6 +185,8 @@ impl HttpBuilder {
 impl Builder for HttpBuilder {
     const SCHEME: Scheme = Scheme::Http;
     type Accessor = HttpBackend;
+    const CONFIG_KEYS: &'static [&'static str] =
+        &["root", "endpoint", "username", "password", "token"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = HttpBuilder::default();
@@ -267,12 +281,14 @@ impl Accessor for HttpBackend {
                 stat: true,
                 stat_with_if_match: true,
                 stat_with_if_none_match: true,
+                stat_with_if_modified_since: true,
 
                 read: true,
                 read_can_next: true,
                 read_with_range: true,
                 read_with_if_match: true,
                 read_with_if_none_match: true,
+                read_with_if_modified_since: true,
 
                 ..Default::default()
             });
@@ -282,7 +298,13 @@ impl Accessor for HttpBackend {
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
         let resp = self
-            .http_get(path, args.range(), args.if_match(), args.if_none_match())
+            .http_get(
+                path,
+                args.range(),
+                args.if_match(),
+                args.if_none_match(),
+                args.if_modified_since(),
+            )
             .await?;
 
         let status = resp.status();
@@ -303,13 +325,20 @@ impl Accessor for HttpBackend {
         }
 
         let resp = self
-            .http_head(path, args.if_match(), args.if_none_match())
+            .http_head(
+                path,
+                args.if_match(),
+                args.if_none_match(),
+                args.if_modified_since(),
+            )
             .await?;
 
         let status = resp.status();
 
         match status {
             StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            // Some servers don't allow HEAD, try GET the first byte instead.
+            StatusCode::METHOD_NOT_ALLOWED => self.stat_by_get(path, &args).await,
             // HTTP Server like nginx could return FORBIDDEN if auto-index
             // is not enabled, we should ignore them.
             StatusCode::NOT_FOUND | StatusCode::FORBIDDEN if path.ends_with('/') => {
@@ -321,12 +350,58 @@ impl Accessor for HttpBackend {
 }
 
 impl HttpBackend {
+    /// Stat via `GET` with `Range: bytes=0-0`.
+    ///
+    /// Content length is taken from the total size of `Content-Range`, and
+    /// left unknown if the server returns the whole content instead.
+    async fn stat_by_get(&self, path: &str, args: &OpStat) -> Result<RpStat> {
+        let resp = self
+            .http_get(
+                path,
+                BytesRange::new(Some(0), Some(1)),
+                args.if_match(),
+                args.if_none_match(),
+                args.if_modified_since(),
+            )
+            .await?;
+
+        let status = resp.status();
+
+        match status {
+            // Empty content will get RANGE_NOT_SATISFIABLE with
+            // `Content-Range: bytes */0`.
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT | StatusCode::RANGE_NOT_SATISFIABLE => {
+                let size = parse_content_range(resp.headers())?.and_then(|v| v.size());
+
+                // Content length and range here only describe the returned
+                // part, which should not be taken as the metadata.
+                let mut headers = resp.headers().clone();
+                headers.remove(header::CONTENT_LENGTH);
+                headers.remove(header::CONTENT_RANGE);
+
+                let mut meta = parse_into_metadata(path, &headers)?;
+                if let Some(size) = size {
+                    meta.set_content_length(size);
+                }
+                // The body is dropped without reading, so we won't
+                // download the whole content from servers without range
+                // support.
+                Ok(RpStat::new(meta))
+            }
+            StatusCode::NOT_FOUND | StatusCode::FORBIDDEN if path.ends_with('/') => {
+                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
     async fn http_get(
         &self,
         path: &str,
         range: BytesRange,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        if_modified_since: Option<DateTime<Utc>>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_rooted_abs_path(&self.root, path);
 
@@ -342,6 +417,13 @@ impl HttpBackend {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
+        if let Some(if_modified_since) = if_modified_since {
+            req = req.header(
+                IF_MODIFIED_SINCE,
+                format_datetime_into_http_date(if_modified_since),
+            );
+        }
+
         if let Some(auth) = &self.authorization {
             req = req.header(header::AUTHORIZATION, auth.clone())
         }
@@ -362,6 +444,7 @@ impl HttpBackend {
         path: &str,
         if_match: Option<&str>,
         if_none_match: Option<&str>,
+        if_modified_since: Option<DateTime<Utc>>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_rooted_abs_path(&self.root, path);
 
@@ -377,6 +460,13 @@ impl HttpBackend {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
+        if let Some(if_modified_since) = if_modified_since {
+            req = req.header(
+                IF_MODIFIED_SINCE,
+                format_datetime_into_http_date(if_modified_since),
+            );
+        }
+
         if let Some(auth) = &self.authorization {
             req = req.header(header::AUTHORIZATION, auth.clone())
         }
@@ -392,6 +482,7 @@ impl HttpBackend {
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
+    use chrono::TimeZone;
     use wiremock::matchers::basic_auth;
     use wiremock::matchers::bearer_token;
     use wiremock::matchers::headers;
@@ -565,4 +656,101 @@ mod tests {
         assert_eq!(bs.content_length(), 128);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_read_with_if_modified_since() -> Result<()> {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            // wiremock splits header values by `,`.
+            .and(headers(
+                "if-modified-since",
+                vec!["Sun", "01 Jan 2023 00:00:00 GMT"],
+            ))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        builder.root("/");
+        let op = Operator::new(builder)?.finish();
+
+        let since = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let err = op
+            .read_with("hello", OpRead::new().with_if_modified_since(since))
+            .await
+            .expect_err("read must fail");
+        assert_eq!(err.kind(), ErrorKind::NotModified);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stat_fallback_to_get() -> Result<()> {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/hello"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            .and(headers("range", vec!["bytes=0-0"]))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("content-length", "1")
+                    .insert_header("content-range", "bytes 0-0/128")
+                    .insert_header("etag", "\"abc\"")
+                    .set_body_string("H"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        builder.root("/");
+        let op = Operator::new(builder)?.finish();
+        let meta = op.stat("hello").await?;
+
+        assert_eq!(meta.mode(), EntryMode::FILE);
+        assert_eq!(meta.content_length_raw(), Some(128));
+        assert_eq!(meta.content_range(), None);
+        assert_eq!(meta.etag(), Some("\"abc\""));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stat_fallback_to_get_without_range_support() -> Result<()> {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/hello"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-length", "13")
+                    .set_body_string("Hello, World!"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        builder.root("/");
+        let op = Operator::new(builder)?.finish();
+        let meta = op.stat("hello").await?;
+
+        assert_eq!(meta.mode(), EntryMode::FILE);
+        assert_eq!(meta.content_length_raw(), None);
+        Ok(())
+    }
 }
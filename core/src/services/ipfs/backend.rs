@@ -19,6 +19,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use http::Request;
@@ -29,10 +30,13 @@ use prost::Message;
 
 use super::error::parse_error;
 use super::ipld::PBNode;
+use super::ipns::IpnsResolver;
 use crate::ops::*;
 use crate::raw::*;
 use crate::*;
 
+const DEFAULT_IPNS_CACHE_TTL: Duration = Duration::from_secs(60);
+
 /// IPFS file system support based on [IPFS HTTP Gateway](https://docs.ipfs.tech/concepts/ipfs-gateway/).
 ///
 /// # Capabilities
@@ -55,9 +59,21 @@ use crate::*;
 ///
 /// - `root`: Set the work directory for backend
 /// - `endpoint`: Customizable endpoint setting
+/// - `ipns_resolver`: Set the IPFS RPC endpoint to resolve IPNS names
+/// - `ipns_cache_ttl`: Set the seconds to cache resolved IPNS names
+/// - `ipns_pinned_cid`: Pin the IPNS name of root to given CID
 ///
 /// You can refer to [`IpfsBuilder`]'s docs for more information
 ///
+/// # IPNS
+///
+/// Root like `/ipns/opendal.apache.org/` will be resolved into
+/// `/ipfs/<cid>/` before sending requests, so that all requests will visit
+/// the same version of content. The resolved CID is cached for
+/// `ipns_cache_ttl` (default to 60s), and will be resolved again if any
+/// path is not found under the cached CID, so that rolling updates of the
+/// name can be picked up.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -90,6 +106,9 @@ use crate::*;
 pub struct IpfsBuilder {
     endpoint: Option<String>,
     root: Option<String>,
+    ipns_resolver: Option<String>,
+    ipns_cache_ttl: Option<Duration>,
+    ipns_pinned_cid: Option<String>,
     http_client: Option<HttpClient>,
 }
 
@@ -129,6 +148,38 @@ impl IpfsBuilder {
         self
     }
 
+    /// Set the IPFS RPC endpoint to resolve IPNS names via
+    /// `/api/v0/name/resolve`, for example: `http://127.0.0.1:5001`.
+    ///
+    /// If not set, IPNS names will be resolved by the `X-Ipfs-Roots`
+    /// header returned by gateway, which also works for DNSLink names
+    /// like `/ipns/opendal.apache.org`.
+    pub fn ipns_resolver(&mut self, resolver: &str) -> &mut Self {
+        if !resolver.is_empty() {
+            self.ipns_resolver = Some(resolver.trim_end_matches('/').to_string());
+        }
+
+        self
+    }
+
+    /// Set the duration to cache resolved IPNS names.
+    ///
+    /// Default: 60s
+    pub fn ipns_cache_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.ipns_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Pin the IPNS name of root to given CID instead of resolving it,
+    /// which is useful to get reproducible results.
+    pub fn ipns_pinned_cid(&mut self, cid: &str) -> &mut Self {
+        if !cid.is_empty() {
+            self.ipns_pinned_cid = Some(cid.to_string());
+        }
+
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -144,11 +195,24 @@ impl IpfsBuilder {
 impl Builder for IpfsBuilder {
     const SCHEME: Scheme = Scheme::Ipfs;
     type Accessor = IpfsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "endpoint",
+        "ipns_resolver",
+        "ipns_cache_ttl",
+        "ipns_pinned_cid",
+    ];
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = IpfsBuilder::default();
 
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("ipns_resolver").map(|v| builder.ipns_resolver(v));
+        map.get("ipns_cache_ttl")
+            .and_then(|v| v.parse().ok())
+            .map(|v| builder.ipns_cache_ttl(Duration::from_secs(v)));
+        map.get("ipns_pinned_cid")
+            .map(|v| builder.ipns_pinned_cid(v));
 
         builder
     }
@@ -184,11 +248,29 @@ impl Builder for IpfsBuilder {
             })?
         };
 
+        let ipns = IpnsResolver::new(
+            &root,
+            &endpoint,
+            self.ipns_resolver.clone(),
+            client.clone(),
+            self.ipns_pinned_cid.clone(),
+            self.ipns_cache_ttl.unwrap_or(DEFAULT_IPNS_CACHE_TTL),
+        );
+        if ipns.is_none() && self.ipns_pinned_cid.is_some() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "ipns_pinned_cid can only be used with /ipns/ root",
+            )
+            .with_context("service", Scheme::Ipfs)
+            .with_context("root", &root));
+        }
+
         debug!("backend build finished: {:?}", &self);
         Ok(IpfsBackend {
             root,
             endpoint,
             client,
+            ipns: ipns.map(Arc::new),
         })
     }
 }
@@ -199,6 +281,7 @@ pub struct IpfsBackend {
     endpoint: String,
     root: String,
     client: HttpClient,
+    ipns: Option<Arc<IpnsResolver>>,
 }
 
 impl Debug for IpfsBackend {
@@ -207,6 +290,7 @@ impl Debug for IpfsBackend {
             .field("endpoint", &self.endpoint)
             .field("root", &self.root)
             .field("client", &self.client)
+            .field("ipns", &self.ipns)
             .finish()
     }
 }
@@ -414,56 +498,92 @@ impl Accessor for IpfsBackend {
 }
 
 impl IpfsBackend {
-    async fn ipfs_get(&self, path: &str, range: BytesRange) -> Result<Response<IncomingAsyncBody>> {
-        let p = build_rooted_abs_path(&self.root, path);
-
-        let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+    /// Get the root to send requests, IPNS root will be resolved into
+    /// `/ipfs/<cid>/`.
+    async fn resolve_root(&self) -> Result<String> {
+        match &self.ipns {
+            Some(ipns) => ipns.resolve().await,
+            None => Ok(self.root.clone()),
+        }
+    }
 
-        let mut req = Request::get(&url);
+    /// Send request built with resolved root.
+    ///
+    /// If the path is not found under the cached CID of an IPNS root, the
+    /// name will be resolved again and the request will be retried with
+    /// the new CID.
+    async fn send_with_root(
+        &self,
+        build: impl Fn(&str) -> Result<Request<AsyncBody>>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let root = self.resolve_root().await?;
+        let resp = self.client.send(build(&root)?).await?;
+
+        if resp.status() != StatusCode::NOT_FOUND {
+            return Ok(resp);
+        }
+        match &self.ipns {
+            Some(ipns) if ipns.invalidate() => {}
+            _ => return Ok(resp),
+        }
 
-        if !range.is_full() {
-            req = req.header(http::header::RANGE, range.to_header());
+        let new_root = self.resolve_root().await?;
+        if new_root == root {
+            return Ok(resp);
         }
+        debug!("ipfs root is changed from {root} to {new_root}, retrying");
+
+        resp.into_body().consume().await?;
+        self.client.send(build(&new_root)?).await
+    }
+
+    async fn ipfs_get(&self, path: &str, range: BytesRange) -> Result<Response<IncomingAsyncBody>> {
+        self.send_with_root(|root| {
+            let p = build_rooted_abs_path(root, path);
 
-        let req = req
-            .body(AsyncBody::Empty)
-            .map_err(new_request_build_error)?;
+            let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+
+            let mut req = Request::get(&url);
+
+            if !range.is_full() {
+                req = req.header(http::header::RANGE, range.to_header());
+            }
 
-        self.client.send(req).await
+            req.body(AsyncBody::Empty).map_err(new_request_build_error)
+        })
+        .await
     }
 
     async fn ipfs_head(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
-        let p = build_rooted_abs_path(&self.root, path);
-
-        let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+        self.send_with_root(|root| {
+            let p = build_rooted_abs_path(root, path);
 
-        let req = Request::head(&url);
+            let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
 
-        let req = req
-            .body(AsyncBody::Empty)
-            .map_err(new_request_build_error)?;
+            let req = Request::head(&url);
 
-        self.client.send(req).await
+            req.body(AsyncBody::Empty).map_err(new_request_build_error)
+        })
+        .await
     }
 
     async fn ipfs_list(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
-        let p = build_rooted_abs_path(&self.root, path);
+        self.send_with_root(|root| {
+            let p = build_rooted_abs_path(root, path);
 
-        let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+            let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
 
-        let mut req = Request::get(&url);
+            let mut req = Request::get(&url);
 
-        // Use "application/vnd.ipld.raw" to disable IPLD codec deserialization
-        // OpenDAL will parse ipld data directly.
-        //
-        // ref: https://github.com/ipfs/specs/blob/main/http-gateways/PATH_GATEWAY.md
-        req = req.header(http::header::ACCEPT, "application/vnd.ipld.raw");
+            // Use "application/vnd.ipld.raw" to disable IPLD codec deserialization
+            // OpenDAL will parse ipld data directly.
+            //
+            // ref: https://github.com/ipfs/specs/blob/main/http-gateways/PATH_GATEWAY.md
+            req = req.header(http::header::ACCEPT, "application/vnd.ipld.raw");
 
-        let req = req
-            .body(AsyncBody::Empty)
-            .map_err(new_request_build_error)?;
-
-        self.client.send(req).await
+            req.body(AsyncBody::Empty).map_err(new_request_build_error)
+        })
+        .await
     }
 }
 
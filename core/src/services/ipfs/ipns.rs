@@ -0,0 +1,308 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use http::Request;
+use http::StatusCode;
+use log::debug;
+use serde::Deserialize;
+
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+/// IpnsResolver resolves the IPNS name in root like `/ipns/opendal.apache.org/`
+/// into `/ipfs/<cid>/`.
+///
+/// Resolved CID will be cached for `ttl` so that we don't need to resolve
+/// it for every request.
+pub struct IpnsResolver {
+    /// The IPNS name, for example: `opendal.apache.org`.
+    name: String,
+    /// The path after the name which always ends with `/`, could be empty.
+    rest: String,
+
+    endpoint: String,
+    resolver: Option<String>,
+    client: HttpClient,
+
+    pinned_cid: Option<String>,
+    ttl: Duration,
+    cache: Mutex<Option<(String, Instant)>>,
+}
+
+impl Debug for IpnsResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpnsResolver")
+            .field("name", &self.name)
+            .field("resolver", &self.resolver)
+            .field("pinned_cid", &self.pinned_cid)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl IpnsResolver {
+    /// Create a new resolver for root.
+    ///
+    /// Returns `None` if root is not an IPNS path.
+    pub fn new(
+        root: &str,
+        endpoint: &str,
+        resolver: Option<String>,
+        client: HttpClient,
+        pinned_cid: Option<String>,
+        ttl: Duration,
+    ) -> Option<Self> {
+        let v = root.strip_prefix("/ipns/")?;
+        let (name, rest) = v.split_once('/').unwrap_or((v, ""));
+
+        Some(Self {
+            name: name.to_string(),
+            rest: rest.to_string(),
+
+            endpoint: endpoint.to_string(),
+            resolver,
+            client,
+
+            pinned_cid,
+            ttl,
+            cache: Mutex::new(None),
+        })
+    }
+
+    /// Resolve the root into `/ipfs/<cid>/...`.
+    pub async fn resolve(&self) -> Result<String> {
+        if let Some(cid) = &self.pinned_cid {
+            return Ok(self.build_root(cid));
+        }
+
+        if let Some((cid, resolved_at)) = &*self.cache.lock().expect("lock must be valid") {
+            if resolved_at.elapsed() < self.ttl {
+                return Ok(self.build_root(cid));
+            }
+        }
+
+        let cid = match &self.resolver {
+            Some(resolver) => self.resolve_by_rpc(resolver).await,
+            None => self.resolve_by_gateway().await,
+        }
+        .map_err(|err| {
+            err.with_operation("IpnsResolver::resolve")
+                .with_context("name", &self.name)
+        })?;
+        debug!("ipns name {} resolved to {}", self.name, cid);
+
+        let root = self.build_root(&cid);
+        *self.cache.lock().expect("lock must be valid") = Some((cid, Instant::now()));
+        Ok(root)
+    }
+
+    /// Drop the cached CID so that the name will be resolved again.
+    ///
+    /// Returns `false` if the resolution is pinned or nothing cached.
+    pub fn invalidate(&self) -> bool {
+        if self.pinned_cid.is_some() {
+            return false;
+        }
+
+        self.cache
+            .lock()
+            .expect("lock must be valid")
+            .take()
+            .is_some()
+    }
+
+    fn build_root(&self, cid: &str) -> String {
+        format!("/ipfs/{}/{}", cid, self.rest)
+    }
+
+    /// Resolve by the `X-Ipfs-Roots` header returned by gateway, whose
+    /// first value is the CID of the name.
+    async fn resolve_by_gateway(&self) -> Result<String> {
+        let url = format!(
+            "{}/ipns/{}/",
+            self.endpoint,
+            percent_encode_path(&self.name)
+        );
+
+        let req = Request::head(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp = self.client.send(req).await?;
+
+        match resp.status() {
+            StatusCode::OK | StatusCode::FOUND | StatusCode::MOVED_PERMANENTLY => {
+                let roots = parse_header_to_str(resp.headers(), "x-ipfs-roots")?;
+                match roots.and_then(|v| v.split(',').next()) {
+                    Some(cid) if !cid.trim().is_empty() => Ok(cid.trim().to_string()),
+                    _ => Err(Error::new(
+                        ErrorKind::Unexpected,
+                        "gateway doesn't return x-ipfs-roots for ipns name",
+                    )),
+                }
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    /// Resolve by the `/api/v0/name/resolve` RPC API of IPFS daemon.
+    async fn resolve_by_rpc(&self, resolver: &str) -> Result<String> {
+        let url = format!(
+            "{}/api/v0/name/resolve?arg={}&recursive=true",
+            resolver,
+            percent_encode_path(&self.name)
+        );
+
+        let req = Request::post(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp = self.client.send(req).await?;
+
+        match resp.status() {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let out: NameResolveResponse =
+                    serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+                match out
+                    .path
+                    .strip_prefix("/ipfs/")
+                    .and_then(|v| v.split('/').next())
+                {
+                    Some(cid) if !cid.is_empty() => Ok(cid.to_string()),
+                    _ => Err(Error::new(
+                        ErrorKind::Unexpected,
+                        "ipns name is not resolved to an ipfs path",
+                    )
+                    .with_context("path", out.path)),
+                }
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct NameResolveResponse {
+    #[serde(rename = "Path")]
+    path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_by_gateway() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/ipns/opendal.apache.org/"))
+            .respond_with(ResponseTemplate::new(200).insert_header("x-ipfs-roots", "QmA,QmB"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let r = IpnsResolver::new(
+            "/ipns/opendal.apache.org/docs/",
+            &mock_server.uri(),
+            None,
+            HttpClient::new()?,
+            None,
+            Duration::from_secs(60),
+        )
+        .expect("must be ipns");
+
+        assert_eq!(r.resolve().await?, "/ipfs/QmA/docs/");
+        // The second resolve should hit the cache.
+        assert_eq!(r.resolve().await?, "/ipfs/QmA/docs/");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_by_rpc() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/name/resolve"))
+            .and(query_param("arg", "opendal.apache.org"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"Path":"/ipfs/QmA"}"#))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let r = IpnsResolver::new(
+            "/ipns/opendal.apache.org/",
+            "http://127.0.0.1:8080",
+            Some(mock_server.uri()),
+            HttpClient::new()?,
+            None,
+            Duration::from_secs(60),
+        )
+        .expect("must be ipns");
+
+        assert_eq!(r.resolve().await?, "/ipfs/QmA/");
+        // Invalidate should force another resolve.
+        assert!(r.invalidate());
+        assert_eq!(r.resolve().await?, "/ipfs/QmA/");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_pinned() -> Result<()> {
+        let r = IpnsResolver::new(
+            "/ipns/opendal.apache.org/",
+            "http://127.0.0.1:8080",
+            None,
+            HttpClient::new()?,
+            Some("QmPinned".to_string()),
+            Duration::from_secs(60),
+        )
+        .expect("must be ipns");
+
+        assert_eq!(r.resolve().await?, "/ipfs/QmPinned/");
+        assert!(!r.invalidate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_ipfs_root() {
+        let r = IpnsResolver::new(
+            "/ipfs/QmA/",
+            "http://127.0.0.1:8080",
+            None,
+            HttpClient::new().expect("must init"),
+            None,
+            Duration::from_secs(60),
+        );
+        assert!(r.is_none());
+    }
+}
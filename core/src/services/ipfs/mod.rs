@@ -20,3 +20,4 @@ pub use backend::IpfsBuilder as Ipfs;
 
 mod error;
 mod ipld;
+mod ipns;
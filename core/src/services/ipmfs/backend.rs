@@ -57,6 +57,7 @@ pub struct IpmfsBackend {
     root: String,
     endpoint: String,
     client: HttpClient,
+    pub(crate) write_fixed_size: usize,
 }
 
 impl fmt::Debug for IpmfsBackend {
@@ -69,11 +70,17 @@ impl fmt::Debug for IpmfsBackend {
 }
 
 impl IpmfsBackend {
-    pub(crate) fn new(root: String, client: HttpClient, endpoint: String) -> Self {
+    pub(crate) fn new(
+        root: String,
+        client: HttpClient,
+        endpoint: String,
+        write_fixed_size: usize,
+    ) -> Self {
         Self {
             root,
             client,
             endpoint,
+            write_fixed_size,
         }
     }
 }
@@ -99,6 +106,7 @@ impl Accessor for IpmfsBackend {
                 read_with_range: true,
 
                 write: true,
+                write_without_content_length: true,
                 delete: true,
 
                 list: true,
@@ -138,14 +146,7 @@ impl Accessor for IpmfsBackend {
         }
     }
 
-    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        if args.content_length().is_none() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "write without content length is not supported",
-            ));
-        }
-
+    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         Ok((
             RpWrite::default(),
             IpmfsWriter::new(self.clone(), path.to_string()),
@@ -177,6 +178,10 @@ impl Accessor for IpmfsBackend {
 
                 let mut meta = Metadata::new(mode);
                 meta.set_content_length(res.size);
+                // The CID of this entry, which changes on every write.
+                if !res.hash.is_empty() {
+                    meta.set_etag(&res.hash);
+                }
 
                 Ok(RpStat::new(meta))
             }
@@ -252,7 +257,7 @@ impl IpmfsBackend {
         self.client.send(req).await
     }
 
-    async fn ipmfs_rm(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+    pub(crate) async fn ipmfs_rm(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
         let p = build_rooted_abs_path(&self.root, path);
 
         let url = format!(
@@ -303,19 +308,27 @@ impl IpmfsBackend {
         self.client.send(req).await
     }
 
-    /// Support write from reader.
+    /// Write `body` at `offset` of the file.
+    ///
+    /// The file will be created or truncated if `offset` is `0`. Written
+    /// content won't be flushed, call [`IpmfsBackend::ipmfs_flush`] after
+    /// all content has been written.
     pub async fn ipmfs_write(
         &self,
         path: &str,
+        offset: u64,
         body: Bytes,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_rooted_abs_path(&self.root, path);
 
-        let url = format!(
-            "{}/api/v0/files/write?arg={}&parents=true&create=true&truncate=true",
+        let mut url = format!(
+            "{}/api/v0/files/write?arg={}&parents=true&create=true&flush=false&offset={offset}",
             self.endpoint,
             percent_encode_path(&p)
         );
+        if offset == 0 {
+            url.push_str("&truncate=true");
+        }
 
         let multipart = Multipart::new().part(FormDataPart::new("data").content(body));
 
@@ -324,6 +337,24 @@ impl IpmfsBackend {
 
         self.client.send(req).await
     }
+
+    /// Flush the file and return its CID.
+    pub async fn ipmfs_flush(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/api/v0/files/flush?arg={}",
+            self.endpoint,
+            percent_encode_path(&p)
+        );
+
+        let req = Request::post(url);
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
 }
 
 #[derive(Deserialize, Default, Debug)]
@@ -333,4 +364,6 @@ struct IpfsStatResponse {
     size: u64,
     #[serde(rename = "Type")]
     file_type: String,
+    #[serde(rename = "Hash")]
+    hash: String,
 }
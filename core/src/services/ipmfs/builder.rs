@@ -23,6 +23,9 @@ use super::backend::IpmfsBackend;
 use crate::raw::*;
 use crate::*;
 
+/// The default chunk size of each `files/write` call.
+const DEFAULT_WRITE_FIXED_SIZE: usize = 8 * 1024 * 1024;
+
 /// IPFS file system support based on [IPFS MFS](https://docs.ipfs.tech/concepts/file-systems/) API.
 ///
 /// # Capabilities
@@ -40,6 +43,7 @@ use crate::*;
 ///
 /// - `root`: Set the work directory for backend
 /// - `endpoint`: Customizable endpoint setting
+/// - `write_fixed_size`: Set the chunk size of writing, default to 8 MiB
 ///
 /// You can refer to [`IpmfsBuilder`]'s docs for more information
 ///
@@ -69,6 +73,7 @@ use crate::*;
 pub struct IpmfsBuilder {
     root: Option<String>,
     endpoint: Option<String>,
+    write_fixed_size: Option<usize>,
     http_client: Option<HttpClient>,
 }
 
@@ -96,6 +101,18 @@ impl IpmfsBuilder {
         self
     }
 
+    /// Set the chunk size of writing.
+    ///
+    /// Content will be written by multiple `files/write` calls with each
+    /// of them carries at most `write_fixed_size` bytes, so that large
+    /// files won't be sent in one request.
+    ///
+    /// Default: 8 MiB
+    pub fn write_fixed_size(&mut self, write_fixed_size: usize) -> &mut Self {
+        self.write_fixed_size = Some(write_fixed_size);
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -111,12 +128,16 @@ impl IpmfsBuilder {
 impl Builder for IpmfsBuilder {
     const SCHEME: Scheme = Scheme::Ipmfs;
     type Accessor = IpmfsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "endpoint", "write_fixed_size"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = IpmfsBuilder::default();
 
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("write_fixed_size")
+            .and_then(|v| v.parse().ok())
+            .map(|v| builder.write_fixed_size(v));
 
         builder
     }
@@ -130,6 +151,16 @@ impl Builder for IpmfsBuilder {
             .clone()
             .unwrap_or_else(|| "http://localhost:5001".to_string());
 
+        let write_fixed_size = self.write_fixed_size.unwrap_or(DEFAULT_WRITE_FIXED_SIZE);
+        if write_fixed_size == 0 {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "write_fixed_size must be positive",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Ipmfs));
+        }
+
         let client = if let Some(client) = self.http_client.take() {
             client
         } else {
@@ -140,6 +171,6 @@ impl Builder for IpmfsBuilder {
         };
 
         debug!("backend build finished: {:?}", &self);
-        Ok(IpmfsBackend::new(root, client, endpoint))
+        Ok(IpmfsBackend::new(root, client, endpoint, write_fixed_size))
     }
 }
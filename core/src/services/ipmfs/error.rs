@@ -52,6 +52,7 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let ipfs_error = de::from_slice::<IpfsError>(&bs).ok();
 
     let (kind, retryable) = match parts.status {
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::INTERNAL_SERVER_ERROR => {
             if let Some(ie) = &ipfs_error {
                 match ie.message.as_str() {
@@ -18,45 +18,157 @@
 use async_trait::async_trait;
 use bytes::Bytes;
 use http::StatusCode;
+use log::debug;
+use serde::Deserialize;
 
 use super::backend::IpmfsBackend;
 use super::error::parse_error;
 use crate::raw::*;
 use crate::*;
 
+/// IpmfsWriter writes content by offset-based `files/write` calls with
+/// at most `write_fixed_size` bytes each, and flushes the file at close.
 pub struct IpmfsWriter {
     backend: IpmfsBackend,
 
     path: String,
+    written: u64,
+    buffer: oio::VectorCursor,
+    write_fixed_size: usize,
 }
 
 impl IpmfsWriter {
     pub fn new(backend: IpmfsBackend, path: String) -> Self {
-        IpmfsWriter { backend, path }
+        let write_fixed_size = backend.write_fixed_size;
+        IpmfsWriter {
+            backend,
+            path,
+            written: 0,
+            buffer: oio::VectorCursor::new(),
+            write_fixed_size,
+        }
+    }
+
+    /// Write all chunks of `bs` at `self.written`.
+    ///
+    /// Writing to the same offset again will overwrite the content, so
+    /// it's safe to retry the whole `bs` if any chunk failed.
+    async fn write_chunks(&self, bs: Bytes) -> Result<()> {
+        let mut offset = 0;
+        loop {
+            let size = (bs.len() - offset).min(self.write_fixed_size);
+            let chunk = bs.slice(offset..offset + size);
+
+            let resp = self
+                .backend
+                .ipmfs_write(&self.path, self.written + offset as u64, chunk)
+                .await?;
+
+            let status = resp.status();
+
+            match status {
+                StatusCode::CREATED | StatusCode::OK => {
+                    resp.into_body().consume().await?;
+                }
+                _ => return Err(parse_error(resp).await?),
+            }
+
+            offset += size;
+            if offset >= bs.len() {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn flush_file(&self) -> Result<String> {
+        let resp = self.backend.ipmfs_flush(&self.path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let res: IpfsFlushResponse =
+                    serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+                Ok(res.cid)
+            }
+            _ => Err(parse_error(resp).await?),
+        }
     }
 }
 
 #[async_trait]
 impl oio::Write for IpmfsWriter {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        let resp = self.backend.ipmfs_write(&self.path, bs).await?;
+        // Ignore empty bytes
+        if bs.is_empty() {
+            return Ok(());
+        }
+
+        self.buffer.push(bs);
+        // Return directly if the buffer is not full
+        if self.buffer.len() < self.write_fixed_size {
+            return Ok(());
+        }
+
+        let size = self.buffer.len() / self.write_fixed_size * self.write_fixed_size;
+        let bs = self.buffer.peak_exact(size);
+
+        match self.write_chunks(bs).await {
+            Ok(()) => {
+                self.buffer.take(size);
+                self.written += size as u64;
+                Ok(())
+            }
+            Err(e) => {
+                // If the write fails, we should pop the given bs to make sure
+                // write is re-enter safe.
+                self.buffer.pop();
+                Err(e)
+            }
+        }
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.buffer.clear();
+        if self.written == 0 {
+            return Ok(());
+        }
+
+        let resp = self.backend.ipmfs_rm(&self.path).await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::CREATED | StatusCode::OK => {
+            StatusCode::OK => {
                 resp.into_body().consume().await?;
+                self.written = 0;
                 Ok(())
             }
             _ => Err(parse_error(resp).await?),
         }
     }
 
-    async fn abort(&mut self) -> Result<()> {
-        Ok(())
-    }
-
     async fn close(&mut self) -> Result<()> {
+        // Empty files still need to be created by an empty write.
+        if !self.buffer.is_empty() || self.written == 0 {
+            let bs = self.buffer.peak_all();
+            let size = bs.len();
+            self.write_chunks(bs).await?;
+            self.buffer.take(size);
+            self.written += size as u64;
+        }
+
+        let cid = self.flush_file().await?;
+        debug!("ipmfs file {} flushed with cid {}", self.path, cid);
+
         Ok(())
     }
 }
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct IpfsFlushResponse {
+    #[serde(rename = "Cid")]
+    cid: String,
+}
@@ -17,6 +17,8 @@
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -42,9 +44,15 @@ use crate::*;
 /// - [x] scan
 /// - [ ] presign
 /// - [ ] blocking
+///
+/// # Configuration
+///
+/// - `root`: Set the root for BTreeMap.
+/// - `max_size`: Set the max in-memory size of all stored entries.
 #[derive(Default)]
 pub struct MemoryBuilder {
     root: Option<String>,
+    max_size: Option<usize>,
 }
 
 impl MemoryBuilder {
@@ -53,16 +61,33 @@ impl MemoryBuilder {
         self.root = Some(path.into());
         self
     }
+
+    /// Set the max in-memory size of all stored entries in bytes.
+    ///
+    /// The size of an entry includes both its content and metadata. Writes
+    /// that would exceed this size will fail with
+    /// [`ErrorKind::InsufficientStorage`], and deleting entries will free the
+    /// size they take.
+    ///
+    /// No limit by default.
+    pub fn max_size(&mut self, size: usize) -> &mut Self {
+        self.max_size = Some(size);
+        self
+    }
 }
 
 impl Builder for MemoryBuilder {
     const SCHEME: Scheme = Scheme::Memory;
     type Accessor = MemoryBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "max_size"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = Self::default();
 
         map.get("root").map(|v| builder.root(v));
+        map.get("max_size")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.max_size(v));
 
         builder
     }
@@ -70,6 +95,8 @@ impl Builder for MemoryBuilder {
     fn build(&mut self) -> Result<Self::Accessor> {
         let adapter = Adapter {
             inner: Arc::new(Mutex::new(BTreeMap::default())),
+            size: Arc::new(AtomicUsize::new(0)),
+            max_size: self.max_size,
         };
 
         Ok(MemoryBackend::new(adapter).with_root(self.root.as_deref().unwrap_or_default()))
@@ -82,6 +109,10 @@ pub type MemoryBackend = typed_kv::Backend<Adapter>;
 #[derive(Debug, Clone)]
 pub struct Adapter {
     inner: Arc<Mutex<BTreeMap<String, typed_kv::Value>>>,
+    /// The in-memory size of all stored entries, only updated while
+    /// holding the lock of `inner`.
+    size: Arc<AtomicUsize>,
+    max_size: Option<usize>,
 }
 
 #[async_trait]
@@ -115,7 +146,25 @@ impl typed_kv::Adapter for Adapter {
     }
 
     fn blocking_set(&self, path: &str, value: typed_kv::Value) -> Result<()> {
-        self.inner.lock().insert(path.to_string(), value);
+        let mut inner = self.inner.lock();
+
+        let old_size = inner.get(path).map(|v| v.size()).unwrap_or_default();
+        let new_size = self.size.load(Ordering::Relaxed) - old_size + value.size();
+        if let Some(max_size) = self.max_size {
+            if new_size > max_size {
+                return Err(Error::new(
+                    ErrorKind::InsufficientStorage,
+                    "memory service exceeds its max size",
+                )
+                .with_operation("set")
+                .with_context("path", path)
+                .with_context("max_size", max_size.to_string())
+                .with_context("size", new_size.to_string()));
+            }
+        }
+
+        inner.insert(path.to_string(), value);
+        self.size.store(new_size, Ordering::Relaxed);
 
         Ok(())
     }
@@ -125,7 +174,11 @@ impl typed_kv::Adapter for Adapter {
     }
 
     fn blocking_delete(&self, path: &str) -> Result<()> {
-        self.inner.lock().remove(path);
+        let mut inner = self.inner.lock();
+
+        if let Some(v) = inner.remove(path) {
+            self.size.fetch_sub(v.size(), Ordering::Relaxed);
+        }
 
         Ok(())
     }
@@ -163,4 +216,29 @@ mod tests {
         let b2 = MemoryBuilder::default().build().unwrap();
         assert_ne!(b1.info().name(), b2.info().name())
     }
+
+    #[tokio::test]
+    async fn test_max_size() {
+        let size = typed_kv::Value {
+            metadata: Metadata::new(EntryMode::FILE),
+            value: bytes::Bytes::from(vec![0; 1024]),
+        }
+        .size();
+
+        let mut builder = MemoryBuilder::default();
+        builder.max_size(size * 2);
+        let op = Operator::new(builder).unwrap().finish();
+
+        op.write("a", vec![0; 1024]).await.unwrap();
+        op.write("b", vec![0; 1024]).await.unwrap();
+        // Overwriting existing entry should reuse its size.
+        op.write("b", vec![1; 1024]).await.unwrap();
+
+        let err = op.write("c", vec![0; 1024]).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InsufficientStorage);
+
+        // Delete should free the size.
+        op.delete("a").await.unwrap();
+        op.write("c", vec![0; 1024]).await.unwrap();
+    }
 }
@@ -98,6 +98,8 @@ pub use obs::Obs;
 mod oss;
 #[cfg(feature = "services-oss")]
 pub use oss::Oss;
+#[cfg(feature = "services-oss")]
+pub use oss::OssCredentialLoad;
 
 #[cfg(feature = "services-redis")]
 mod redis;
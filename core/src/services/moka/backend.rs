@@ -130,6 +130,14 @@ impl MokaBuilder {
 impl Builder for MokaBuilder {
     const SCHEME: Scheme = Scheme::Moka;
     type Accessor = MokaBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "name",
+        "max_capacity",
+        "time_to_live",
+        "time_to_idle",
+        "num_segments",
+        "thread_pool_enabled",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = MokaBuilder::default();
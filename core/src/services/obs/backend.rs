@@ -189,6 +189,13 @@ impl ObsBuilder {
 impl Builder for ObsBuilder {
     const SCHEME: Scheme = Scheme::Obs;
     type Accessor = ObsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "bucket",
+        "endpoint",
+        "access_key_id",
+        "secret_access_key",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = ObsBuilder::default();
@@ -210,11 +217,10 @@ impl Builder for ObsBuilder {
         debug!("backend use root {}", root);
 
         let bucket = match &self.bucket {
-            Some(bucket) => Ok(bucket.to_string()),
-            None => Err(
-                Error::new(ErrorKind::ConfigInvalid, "The bucket is misconfigured")
-                    .with_context("service", Scheme::Obs),
-            ),
+            Some(bucket) if !bucket.is_empty() => Ok(bucket.to_string()),
+            _ => Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Obs)),
         }?;
         debug!("backend use bucket {}", &bucket);
 
@@ -446,3 +452,21 @@ impl Accessor for ObsBackend {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_without_bucket() {
+        let err = ObsBuilder::default().build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+
+        let mut b = ObsBuilder::default();
+        b.bucket("test");
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("endpoint is empty"));
+    }
+}
@@ -16,7 +16,6 @@
 // under the License.
 
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::sync::Arc;
@@ -31,7 +30,13 @@ use reqsign::AliyunLoader;
 use reqsign::AliyunOssSigner;
 
 use super::core::*;
+use super::credential::CustomedLoader;
+use super::credential::EcsRamRoleLoad;
+use super::credential::OssCredentialLoad;
+use super::error::is_restore_in_progress_error;
 use super::error::parse_error;
+use super::error::parse_error_from_parts;
+use super::error::parse_oss_error_code;
 use super::pager::OssPager;
 use super::writer::OssWriter;
 use crate::ops::*;
@@ -39,6 +44,13 @@ use crate::raw::*;
 use crate::*;
 
 const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
+/// CopyObject only supports objects that are not larger than 1 GiB, larger
+/// objects must be copied via UploadPartCopy.
+const MAX_COPY_OBJECT_SIZE: u64 = 1024 * 1024 * 1024;
+/// The default part size of UploadPartCopy.
+const DEFAULT_COPY_PART_SIZE: u64 = 256 * 1024 * 1024;
+/// OSS allows at most 10,000 parts in a multipart upload.
+const MAX_PARTS: u64 = 10_000;
 /// Aliyun Object Storage Service (OSS) support
 ///
 /// # Capabilities
@@ -55,6 +67,7 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 /// - [x] list
 /// - [x] scan
 /// - [x] presign
+/// - [x] get_acl
 /// - [ ] blocking
 ///
 /// # Configuration
@@ -62,9 +75,19 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 /// - `root`: Set the work dir for backend.
 /// - `bucket`: Set the container name for backend.
 /// - `endpoint`: Set the endpoint for backend.
+/// - `region`: Set the region for backend, used to derive endpoint if it's not set.
+/// - `enable_internal_endpoint`: Use the internal endpoint derived from region.
+/// - `enable_cname`: Treat endpoint as a CNAME bound to the bucket.
 /// - `presign_endpoint`: Set the endpoint for presign.
+/// - `enable_presign_cname`: Treat presign endpoint as a CNAME bound to the bucket.
 /// - `access_key_id`: Set the access_key_id for backend.
 /// - `access_key_secret`: Set the access_key_secret for backend.
+/// - `security_token`: Set the security_token (STS temporary credentials) for backend.
+/// - `ecs_ram_role`: Set the RAM role of ECS instance to load credentials from.
+/// - `server_side_encryption`: Set the server side encryption algorithm, `AES256` or `KMS`.
+/// - `server_side_encryption_key_id`: Set the KMS CMK id for server side encryption.
+/// - `server_side_data_encryption`: Set the data encryption algorithm for KMS, like `SM4`.
+/// - `default_storage_class`: Set the storage class for new objects, like `Archive`.
 /// - `role_arn`: Set the role of backend.
 /// - `oidc_token`: Set the oidc_token for backend.
 /// - `allow_anonymous`: Set the backend access OSS in anonymous way.
@@ -116,16 +139,27 @@ pub struct OssBuilder {
     root: Option<String>,
 
     endpoint: Option<String>,
+    region: Option<String>,
+    enable_internal_endpoint: bool,
+    enable_cname: bool,
     presign_endpoint: Option<String>,
+    enable_presign_cname: bool,
     bucket: String,
 
     // sse options
     server_side_encryption: Option<String>,
     server_side_encryption_key_id: Option<String>,
+    server_side_data_encryption: Option<String>,
+
+    default_storage_class: Option<String>,
 
     // authenticate options
     access_key_id: Option<String>,
     access_key_secret: Option<String>,
+    /// temporary credentials, check the official [doc](https://www.alibabacloud.com/help/en/oss/developer-reference/use-temporary-access-credentials-provided-by-sts-to-access-oss) for detail
+    security_token: Option<String>,
+    ecs_ram_role: Option<String>,
+    customed_credential_load: Option<Box<dyn OssCredentialLoad>>,
 
     http_client: Option<HttpClient>,
     /// the size of each part, and the range is 5MB ~ 5 GB.
@@ -137,7 +171,8 @@ impl Debug for OssBuilder {
         let mut d = f.debug_struct("Builder");
         d.field("root", &self.root)
             .field("bucket", &self.bucket)
-            .field("endpoint", &self.endpoint);
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region);
 
         d.finish_non_exhaustive()
     }
@@ -165,6 +200,9 @@ impl OssBuilder {
     }
 
     /// Set endpoint of this backend.
+    ///
+    /// If user inputs endpoint without scheme like "oss-cn-hangzhou.aliyuncs.com",
+    /// we will prepend "https://" before it.
     pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
         if !endpoint.is_empty() {
             // Trim trailing `/` so that we can accept `http://127.0.0.1:9000/`
@@ -174,6 +212,50 @@ impl OssBuilder {
         self
     }
 
+    /// Set region of this backend, like `cn-hangzhou` or `oss-cn-hangzhou`.
+    ///
+    /// If endpoint is not set, we will use the public endpoint of this
+    /// region like `https://oss-cn-hangzhou.aliyuncs.com`.
+    pub fn region(&mut self, region: &str) -> &mut Self {
+        if !region.is_empty() {
+            self.region = Some(region.to_string())
+        }
+
+        self
+    }
+
+    /// Use the internal endpoint of region like `https://oss-cn-hangzhou-internal.aliyuncs.com`
+    /// to avoid the egress charges while running on ECS in the same region.
+    ///
+    /// Presigned urls will still use the public endpoint of region unless
+    /// presign_endpoint is set, since they are usually handed out to users
+    /// outside.
+    ///
+    /// # Notes
+    ///
+    /// Region is required and endpoint must not be set, otherwise building
+    /// will fail.
+    pub fn enable_internal_endpoint(&mut self) -> &mut Self {
+        self.enable_internal_endpoint = true;
+        self
+    }
+
+    /// Treat endpoint as a CNAME (custom domain) bound to the bucket.
+    ///
+    /// - By default, opendal will send requests to `https://bucket_name.endpoint/path`
+    /// - Enabled, opendal will send requests to `https://endpoint/path`
+    ///
+    /// Presigned urls will use the same rule if presign_endpoint is not set.
+    ///
+    /// # Notes
+    ///
+    /// Building will fail if endpoint already contains the bucket name or
+    /// internal endpoint is enabled.
+    pub fn enable_cname(&mut self) -> &mut Self {
+        self.enable_cname = true;
+        self
+    }
+
     /// Set a endpoint for generating presigned urls.
     ///
     /// You can offer a public endpoint like <https://oss-cn-beijing.aliyuncs.com> to return a presinged url for
@@ -191,6 +273,15 @@ impl OssBuilder {
         self
     }
 
+    /// Treat presign_endpoint as a CNAME (custom domain) bound to the bucket.
+    ///
+    /// - By default, opendal will generate presigned urls like `https://bucket_name.presign_endpoint/path`
+    /// - Enabled, opendal will generate presigned urls like `https://presign_endpoint/path`
+    pub fn enable_presign_cname(&mut self) -> &mut Self {
+        self.enable_presign_cname = true;
+        self
+    }
+
     /// Set access_key_id of this backend.
     ///
     /// - If access_key_id is set, we will take user's input first.
@@ -215,6 +306,49 @@ impl OssBuilder {
         self
     }
 
+    /// Set security_token of this backend.
+    ///
+    /// The security token is issued by Alibaba Cloud STS together with
+    /// temporary access_key_id and access_key_secret, and will be sent via
+    /// `x-oss-security-token`.
+    ///
+    /// # Notes
+    ///
+    /// Security token will expire. Users should build a new operator with
+    /// the refreshed token, or use [`OssBuilder::ecs_ram_role`],
+    /// [`OssBuilder::customed_credential_load`] or configure
+    /// `ALIBABA_CLOUD_ROLE_ARN` with OIDC related envs instead so that
+    /// credentials can be refreshed automatically.
+    pub fn security_token(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.security_token = Some(v.to_string())
+        }
+
+        self
+    }
+
+    /// Set the RAM role attached to current ECS instance.
+    ///
+    /// If set, OpenDAL will load temporary credentials of this role from
+    /// ECS instance metadata service, and refresh them before they expire.
+    pub fn ecs_ram_role(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.ecs_ram_role = Some(v.to_string())
+        }
+
+        self
+    }
+
+    /// Adding a customed credential load for service.
+    ///
+    /// Credentials returned by the loader will be cached and refreshed
+    /// before they expire. Loader errors will be returned as
+    /// `ConfigInvalid` with the original error as source.
+    pub fn customed_credential_load(&mut self, cred: Box<dyn OssCredentialLoad>) -> &mut Self {
+        self.customed_credential_load = Some(cred);
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -227,44 +361,59 @@ impl OssBuilder {
     }
 
     /// preprocess the endpoint option
-    fn parse_endpoint(&self, endpoint: &Option<String>, bucket: &str) -> Result<(String, String)> {
-        let (endpoint, host) = match endpoint.clone() {
+    fn parse_endpoint(
+        &self,
+        endpoint: &Option<String>,
+        bucket: &str,
+        is_cname: bool,
+    ) -> Result<(String, String)> {
+        let ep = match endpoint {
             Some(ep) => {
-                let uri = ep.parse::<Uri>().map_err(|err| {
-                    Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
-                        .with_context("service", Scheme::Oss)
-                        .with_context("endpoint", &ep)
-                        .set_source(err)
-                })?;
-                let host = uri.host().ok_or_else(|| {
-                    Error::new(ErrorKind::ConfigInvalid, "endpoint host is empty")
-                        .with_context("service", Scheme::Oss)
-                        .with_context("endpoint", &ep)
-                })?;
-                let full_host = format!("{bucket}.{host}");
-                let endpoint = match uri.scheme_str() {
-                    Some(scheme_str) => match scheme_str {
-                        "http" | "https" => format!("{scheme_str}://{full_host}"),
-                        _ => {
-                            return Err(Error::new(
-                                ErrorKind::ConfigInvalid,
-                                "endpoint protocol is invalid",
-                            )
-                            .with_context("service", Scheme::Oss));
-                        }
-                    },
-                    None => format!("https://{full_host}"),
-                };
-                (endpoint, full_host)
+                normalize_endpoint(ep).map_err(|err| err.with_context("service", Scheme::Oss))?
             }
             None => {
                 return Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")
                     .with_context("service", Scheme::Oss));
             }
         };
+        // normalize_endpoint makes sure the endpoint is valid with scheme and host.
+        let uri = ep
+            .parse::<Uri>()
+            .expect("normalized endpoint must be valid");
+        let authority = uri.authority().expect("normalized endpoint must have host");
+
+        if is_cname && authority.host().starts_with(&format!("{bucket}.")) {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "endpoint must not contain bucket name while cname is enabled",
+            )
+            .with_context("service", Scheme::Oss)
+            .with_context("endpoint", ep));
+        }
+
+        let host = if is_cname {
+            authority.to_string()
+        } else {
+            format!("{bucket}.{authority}")
+        };
+        let endpoint = format!("{}://{host}", uri.scheme_str().unwrap_or("https"));
         Ok((endpoint, host))
     }
 
+    /// Build the endpoint of region.
+    ///
+    /// Returns `None` if region is not set.
+    fn region_endpoint(&self, internal: bool) -> Option<String> {
+        self.region.as_ref().map(|region| {
+            let region = region.trim_start_matches("oss-");
+            if internal {
+                format!("https://oss-{region}-internal.aliyuncs.com")
+            } else {
+                format!("https://oss-{region}.aliyuncs.com")
+            }
+        })
+    }
+
     /// Set server_side_encryption for this backend.
     ///
     /// Available values: `AES256`, `KMS`.
@@ -300,6 +449,39 @@ impl OssBuilder {
         self
     }
 
+    /// Set server_side_data_encryption for this backend.
+    ///
+    /// Available values: `SM4`. OSS will use `AES256` to encrypt data if
+    /// not set.
+    ///
+    /// # Notes
+    ///
+    /// This option requires server_side_encryption to be `KMS`, otherwise
+    /// an error will be returned during build.
+    pub fn server_side_data_encryption(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.server_side_data_encryption = Some(v.to_string())
+        }
+        self
+    }
+
+    /// Set the default storage class for new objects.
+    ///
+    /// Available values are:
+    /// - `Standard`
+    /// - `IA`
+    /// - `Archive`
+    /// - `ColdArchive`
+    /// - `DeepColdArchive`
+    ///
+    /// Objects will inherit the storage class of bucket if not set.
+    pub fn default_storage_class(&mut self, class: &str) -> &mut Self {
+        if !class.is_empty() {
+            self.default_storage_class = Some(class.to_string())
+        }
+        self
+    }
+
     /// set the minimum size of unsized write, it should be greater than 5 MB.
     /// Reference: [OSS Multipart upload](https://www.alibabacloud.com/help/en/object-storage-service/latest/multipart-upload-6)
     pub fn write_min_size(&mut self, write_min_size: usize) -> &mut Self {
@@ -312,6 +494,25 @@ impl OssBuilder {
 impl Builder for OssBuilder {
     const SCHEME: Scheme = Scheme::Oss;
     type Accessor = OssBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "bucket",
+        "endpoint",
+        "region",
+        "enable_internal_endpoint",
+        "enable_cname",
+        "presign_endpoint",
+        "enable_presign_cname",
+        "access_key_id",
+        "access_key_secret",
+        "security_token",
+        "ecs_ram_role",
+        "server_side_encryption",
+        "server_side_encryption_key_id",
+        "server_side_data_encryption",
+        "default_storage_class",
+        "write_min_size",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = OssBuilder::default();
@@ -319,15 +520,31 @@ impl Builder for OssBuilder {
         map.get("root").map(|v| builder.root(v));
         map.get("bucket").map(|v| builder.bucket(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("region").map(|v| builder.region(v));
+        map.get("enable_internal_endpoint")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_internal_endpoint());
+        map.get("enable_cname")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_cname());
         map.get("presign_endpoint")
             .map(|v| builder.presign_endpoint(v));
+        map.get("enable_presign_cname")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_presign_cname());
         map.get("access_key_id").map(|v| builder.access_key_id(v));
         map.get("access_key_secret")
             .map(|v| builder.access_key_secret(v));
+        map.get("security_token").map(|v| builder.security_token(v));
+        map.get("ecs_ram_role").map(|v| builder.ecs_ram_role(v));
         map.get("server_side_encryption")
             .map(|v| builder.server_side_encryption(v));
         map.get("server_side_encryption_key_id")
             .map(|v| builder.server_side_encryption_key_id(v));
+        map.get("server_side_data_encryption")
+            .map(|v| builder.server_side_data_encryption(v));
+        map.get("default_storage_class")
+            .map(|v| builder.default_storage_class(v));
         map.get("write_min_size")
             .map(|v| builder.write_min_size(v.parse::<usize>().unwrap()));
         builder
@@ -342,10 +559,9 @@ impl Builder for OssBuilder {
         // Handle endpoint, region and bucket name.
         let bucket = match self.bucket.is_empty() {
             false => Ok(&self.bucket),
-            true => Err(
-                Error::new(ErrorKind::ConfigInvalid, "The bucket is misconfigured")
-                    .with_context("service", Scheme::Oss),
-            ),
+            true => Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Oss)),
         }?;
 
         let client = if let Some(client) = self.http_client.take() {
@@ -357,13 +573,40 @@ impl Builder for OssBuilder {
             })?
         };
 
-        // Retrieve endpoint and host by parsing the endpoint option and bucket. If presign_endpoint is not
-        // set, take endpoint as default presign_endpoint.
-        let (endpoint, host) = self.parse_endpoint(&self.endpoint, bucket)?;
+        if self.enable_internal_endpoint {
+            let msg = if self.region.is_none() {
+                Some("region is required while internal endpoint is enabled")
+            } else if self.endpoint.is_some() {
+                Some("endpoint can't be set while internal endpoint is enabled")
+            } else if self.enable_cname {
+                Some("cname can't be used with internal endpoint")
+            } else {
+                None
+            };
+            if let Some(msg) = msg {
+                return Err(Error::new(ErrorKind::ConfigInvalid, msg)
+                    .with_operation("Builder::build")
+                    .with_context("service", Scheme::Oss));
+            }
+        }
+
+        // Retrieve endpoint and host by parsing the endpoint option and bucket. If endpoint is not set,
+        // derive it from region.
+        let input_endpoint = self
+            .endpoint
+            .clone()
+            .or_else(|| self.region_endpoint(self.enable_internal_endpoint));
+        let (endpoint, host) = self.parse_endpoint(&input_endpoint, bucket, self.enable_cname)?;
         debug!("backend use bucket {}, endpoint: {}", &bucket, &endpoint);
 
+        // If presign_endpoint is not set, take endpoint as default presign_endpoint. Internal endpoint
+        // can't be accessed outside, so we use the public endpoint of region instead.
         let presign_endpoint = if self.presign_endpoint.is_some() {
-            self.parse_endpoint(&self.presign_endpoint, bucket)?.0
+            self.parse_endpoint(&self.presign_endpoint, bucket, self.enable_presign_cname)?
+                .0
+        } else if self.enable_internal_endpoint {
+            self.parse_endpoint(&self.region_endpoint(false), bucket, false)?
+                .0
         } else {
             endpoint.clone()
         };
@@ -385,6 +628,35 @@ impl Builder for OssBuilder {
             ),
         };
 
+        let is_kms = self
+            .server_side_encryption
+            .as_deref()
+            .map_or(false, |v| v.eq_ignore_ascii_case("KMS"));
+        if self.server_side_data_encryption.is_some() && !is_kms {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "server_side_data_encryption requires server_side_encryption to be KMS",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Oss));
+        }
+
+        let server_side_data_encryption = match &self.server_side_data_encryption {
+            None => None,
+            Some(v) => Some(
+                build_header_value(v)
+                    .map_err(|err| err.with_context("key", "server_side_data_encryption"))?,
+            ),
+        };
+
+        let default_storage_class = match &self.default_storage_class {
+            None => None,
+            Some(v) => Some(
+                build_header_value(v)
+                    .map_err(|err| err.with_context("key", "default_storage_class"))?,
+            ),
+        };
+
         let mut cfg = AliyunConfig::default();
         // Load cfg from env first.
         cfg = cfg.from_env();
@@ -397,8 +669,21 @@ impl Builder for OssBuilder {
             cfg.access_key_secret = Some(v);
         }
 
+        if let Some(v) = self.security_token.take() {
+            cfg.security_token = Some(v);
+        }
+
         let loader = AliyunLoader::new(client.client(), cfg);
 
+        let customed_loader = match self.customed_credential_load.take() {
+            Some(v) => Some(v),
+            None => self
+                .ecs_ram_role
+                .take()
+                .map(|v| Box::new(EcsRamRoleLoad::new(&v)) as Box<dyn OssCredentialLoad>),
+        }
+        .map(|v| CustomedLoader::new(client.client(), v));
+
         let signer = AliyunOssSigner::new(bucket);
 
         let write_min_size = self.write_min_size.unwrap_or(DEFAULT_WRITE_MIN_SIZE);
@@ -420,9 +705,12 @@ impl Builder for OssBuilder {
                 presign_endpoint,
                 signer,
                 loader,
+                customed_loader,
                 client,
                 server_side_encryption,
                 server_side_encryption_key_id,
+                server_side_data_encryption,
+                default_storage_class,
                 write_min_size,
             }),
         })
@@ -435,6 +723,152 @@ pub struct OssBackend {
     core: Arc<OssCore>,
 }
 
+impl OssBackend {
+    /// Copy objects larger than [`MAX_COPY_OBJECT_SIZE`] via UploadPartCopy.
+    ///
+    /// Metadata and tags of target can only be set while initiating the
+    /// upload, so the metadata of source will be set again if not replaced.
+    async fn copy_multipart(
+        &self,
+        from: &str,
+        to: &str,
+        args: &OpCopy,
+        source: &http::HeaderMap,
+        size: u64,
+    ) -> Result<RpCopy> {
+        let tagging = match args.tagging_directive() {
+            Some(CopyDirective::Replace) => args.tagging(),
+            _ => {
+                let count = parse_header_to_str(source, constants::X_OSS_TAGGING_COUNT)?;
+                if count.map_or(false, |v| v != "0") {
+                    return Err(Error::new(
+                        ErrorKind::Unsupported,
+                        "copying tags of objects larger than 1 GiB is not supported, please replace them instead",
+                    )
+                    .with_operation("copy")
+                    .with_context("from", from));
+                }
+                None
+            }
+        };
+
+        let resp = match args.metadata_directive() {
+            Some(CopyDirective::Replace) => {
+                self.core
+                    .oss_initiate_copy_upload(to, args.content_type(), None, None, tagging)
+                    .await?
+            }
+            _ => {
+                self.core
+                    .oss_initiate_copy_upload(
+                        to,
+                        parse_content_type(source)?,
+                        parse_content_disposition(source)?,
+                        parse_header_to_str(source, http::header::CACHE_CONTROL.as_str())?,
+                        tagging,
+                    )
+                    .await?
+            }
+        };
+        let upload_id = match resp.status() {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let result: InitiateMultipartUploadResult =
+                    quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+                result.upload_id
+            }
+            _ => return Err(parse_error(resp).await?),
+        };
+
+        match self.copy_parts(from, to, args, &upload_id, size).await {
+            Ok(rp) => Ok(rp),
+            Err(err) => {
+                // Abort the upload to avoid leaving parts behind, the error
+                // of abort is ignored since the copy has failed already.
+                if let Ok(resp) = self.core.oss_abort_multipart_upload(to, &upload_id).await {
+                    let _ = resp.into_body().consume().await;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn copy_parts(
+        &self,
+        from: &str,
+        to: &str,
+        args: &OpCopy,
+        upload_id: &str,
+        size: u64,
+    ) -> Result<RpCopy> {
+        let part_size = ((size + MAX_PARTS - 1) / MAX_PARTS).max(DEFAULT_COPY_PART_SIZE);
+
+        let mut parts = Vec::with_capacity(((size + part_size - 1) / part_size) as usize);
+        let mut offset = 0;
+        while offset < size {
+            let part_number = parts.len() + 1;
+            let range = BytesRange::new(Some(offset), Some(part_size.min(size - offset)));
+
+            let resp = self
+                .core
+                .oss_upload_part_copy(from, to, upload_id, part_number, range, args)
+                .await?;
+            match resp.status() {
+                StatusCode::OK => {
+                    let bs = resp.into_body().bytes().await?;
+                    let result: CopyPartResult = quick_xml::de::from_reader(bs.reader())
+                        .map_err(new_xml_deserialize_error)?;
+                    parts.push(MultipartUploadPart {
+                        part_number,
+                        etag: result.etag,
+                    });
+                }
+                _ => return Err(parse_error(resp).await?),
+            }
+
+            offset += part_size;
+        }
+
+        let resp = self
+            .core
+            .oss_complete_multipart_upload_request(to, upload_id, false, &parts)
+            .await?;
+        match resp.status() {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpCopy::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    /// Stat the symlink itself via GetSymlink.
+    async fn stat_symlink(&self, path: &str) -> Result<RpStat> {
+        let resp = self.core.oss_get_symlink(path).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let target = self.core.parse_symlink_target(resp.headers())?;
+                let mut m = Metadata::new(EntryMode::Unknown).with_symlink_target(target);
+                if let Some(v) = parse_last_modified(resp.headers())? {
+                    m.set_last_modified(v);
+                }
+                resp.into_body().consume().await?;
+
+                Ok(RpStat::new(m))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
+/// Returns whether the object is a symlink via `x-oss-object-type`.
+fn is_symlink(headers: &http::HeaderMap) -> Result<bool> {
+    Ok(parse_header_to_str(headers, constants::X_OSS_OBJECT_TYPE)?
+        .map_or(false, |v| v.eq_ignore_ascii_case("Symlink")))
+}
+
 #[async_trait]
 impl Accessor for OssBackend {
     type Reader = IncomingAsyncBody;
@@ -453,20 +887,30 @@ impl Accessor for OssBackend {
                 stat: true,
                 stat_with_if_match: true,
                 stat_with_if_none_match: true,
+                stat_with_no_follow_symlink: true,
 
                 read: true,
                 read_can_next: true,
                 read_with_range: true,
                 read_with_if_match: true,
                 read_with_if_none_match: true,
+                read_with_override_cache_control: true,
+                read_with_override_content_disposition: true,
+                read_with_no_follow_symlink: true,
 
                 write: true,
                 write_with_cache_control: true,
+                write_with_expires: true,
                 write_with_content_type: true,
+                write_with_acl: true,
                 write_without_content_length: true,
                 delete: true,
                 create_dir: true,
                 copy: true,
+                copy_with_if_match: true,
+                copy_with_if_none_match: true,
+                copy_with_metadata_directive: true,
+                copy_with_tagging_directive: true,
 
                 list: true,
                 list_with_delimiter_slash: true,
@@ -480,6 +924,12 @@ impl Accessor for OssBackend {
                 batch: true,
                 batch_max_operations: Some(1000),
 
+                restore: true,
+
+                create_symlink: true,
+
+                get_acl: true,
+
                 ..Default::default()
             });
 
@@ -511,6 +961,7 @@ impl Accessor for OssBackend {
                 args.if_match(),
                 args.if_none_match(),
                 args.override_content_disposition(),
+                args.override_cache_control(),
             )
             .await?;
 
@@ -518,6 +969,13 @@ impl Accessor for OssBackend {
 
         match status {
             StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
+                if !args.follow_symlink() && is_symlink(resp.headers())? {
+                    return Err(Error::new(
+                        ErrorKind::ConditionNotMatch,
+                        "read path is a symlink while following symlink is disabled",
+                    ));
+                }
+
                 let meta = parse_into_metadata(path, resp.headers())?;
                 Ok((RpRead::with_metadata(meta), resp.into_body()))
             }
@@ -526,14 +984,31 @@ impl Accessor for OssBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        if let Some(acl) = args.acl() {
+            check_canned_acl(acl)?;
+        }
+
         Ok((
             RpWrite::default(),
             OssWriter::new(self.core.clone(), path, args),
         ))
     }
 
-    async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
-        let resp = self.core.oss_copy_object(from, to).await?;
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let resp = self
+            .core
+            .oss_head_object(from, args.if_match(), args.if_none_match())
+            .await?;
+        let source = match resp.status() {
+            StatusCode::OK => resp.headers().clone(),
+            _ => return Err(parse_error(resp).await?),
+        };
+        let size = parse_content_length(&source)?.unwrap_or_default();
+        if size > MAX_COPY_OBJECT_SIZE {
+            return self.copy_multipart(from, to, &args, &source, size).await;
+        }
+
+        let resp = self.core.oss_copy_object(from, to, &args).await?;
         let status = resp.status();
 
         match status {
@@ -559,11 +1034,38 @@ impl Accessor for OssBackend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            StatusCode::OK => {
+                let headers = resp.headers();
+                if !args.follow_symlink() && is_symlink(headers)? {
+                    return self.stat_symlink(path).await;
+                }
+
+                let mut m = parse_into_metadata(path, headers)?;
+                if let Some(v) =
+                    parse_header_to_str(headers, constants::X_OSS_SERVER_SIDE_ENCRYPTION)?
+                {
+                    m.set_server_side_encryption(v);
+                }
+                if let Some(v) =
+                    parse_header_to_str(headers, constants::X_OSS_SERVER_SIDE_ENCRYPTION_KEY_ID)?
+                {
+                    m.set_server_side_encryption_key_id(v);
+                }
+                if let Some(v) = parse_header_to_str(headers, constants::X_OSS_STORAGE_CLASS)? {
+                    m.set_storage_class(v);
+                }
+                if let Some(v) = parse_header_to_str(headers, constants::X_OSS_RESTORE)? {
+                    m.set_restore_status(v);
+                }
+
+                Ok(RpStat::new(m))
+            }
             StatusCode::NOT_FOUND if path.ends_with('/') => {
                 let m = Metadata::new(EntryMode::DIR);
                 Ok(RpStat::new(m))
             }
+            // HEAD follows symlink, so broken symlink will be not found.
+            StatusCode::NOT_FOUND if !args.follow_symlink() => self.stat_symlink(path).await,
 
             _ => Err(parse_error(resp).await?),
         }
@@ -581,6 +1083,59 @@ impl Accessor for OssBackend {
         }
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        let resp = self.core.oss_restore_object(path, &args).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::ACCEPTED => {
+                resp.into_body().consume().await?;
+                Ok(RpRestore::default())
+            }
+            // OSS returns `RestoreAlreadyInProgress` while the object is
+            // being restored, it's safe to treat it as succeeded.
+            StatusCode::CONFLICT => {
+                let (parts, body) = resp.into_parts();
+                let bs = body.bytes().await?;
+                if is_restore_in_progress_error(&bs) {
+                    Ok(RpRestore::default())
+                } else {
+                    Err(parse_error_from_parts(parts, bs))
+                }
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn get_acl(&self, path: &str, _: OpGetAcl) -> Result<RpGetAcl> {
+        let resp = self.core.oss_get_object_acl(path).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let out: AccessControlPolicy =
+                    quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+
+                Ok(RpGetAcl::new(out.into_acl()))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn create_symlink(&self, path: &str, args: OpCreateSymlink) -> Result<RpCreateSymlink> {
+        let resp = self.core.oss_put_symlink(path, args.target()).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpCreateSymlink::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         Ok((
             RpList::default(),
@@ -602,16 +1157,25 @@ impl Accessor for OssBackend {
                 v.if_match(),
                 v.if_none_match(),
                 v.override_content_disposition(),
+                v.override_cache_control(),
             )?,
-            PresignOperation::Write(v) => self.core.oss_put_object_request(
-                path,
-                None,
-                v.content_type(),
-                v.content_disposition(),
-                v.cache_control(),
-                AsyncBody::Empty,
-                true,
-            )?,
+            PresignOperation::Write(v) => {
+                if let Some(acl) = v.acl() {
+                    check_canned_acl(acl)?;
+                }
+
+                self.core.oss_put_object_request(
+                    path,
+                    None,
+                    v.content_type(),
+                    v.content_disposition(),
+                    v.cache_control(),
+                    v.expires(),
+                    v.acl(),
+                    AsyncBody::Empty,
+                    true,
+                )?
+            }
         };
 
         self.core.sign_query(&mut req, args.expire()).await?;
@@ -628,52 +1192,61 @@ impl Accessor for OssBackend {
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
         let ops = args.into_operation();
-        // Sadly, OSS will not return failed keys, so we will build
-        // a set to calculate the failed keys.
-        let mut keys = HashSet::new();
-
-        let ops_len = ops.len();
-        if ops_len > 1000 {
+        if ops.len() > 1000 {
             return Err(Error::new(
                 ErrorKind::Unsupported,
                 "oss services only allow delete up to 1000 keys at once",
             )
-            .with_context("length", ops_len.to_string()));
+            .with_context("length", ops.len().to_string()));
         }
 
-        let paths = ops
-            .into_iter()
-            .map(|(p, _)| {
-                keys.insert(p.clone());
-                p
-            })
-            .collect();
+        let paths: Vec<String> = ops.into_iter().map(|(p, _)| p).collect();
 
-        let resp = self.core.oss_delete_objects(paths).await?;
+        let resp = self.core.oss_delete_objects(paths.clone()).await?;
 
         let status = resp.status();
 
         if let StatusCode::OK = status {
             let bs = resp.into_body().bytes().await?;
 
-            let result: DeleteObjectsResult =
-                quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
-
-            let mut batched_result = Vec::with_capacity(ops_len);
-            for i in result.deleted {
-                let path = build_rel_path(&self.core.root, &i.key);
-                keys.remove(&path);
-                batched_result.push((path, Ok(RpDelete::default().into())));
+            // OSS returns empty body in quiet mode if all keys are deleted.
+            let result: DeleteObjectsResult = if bs.is_empty() {
+                DeleteObjectsResult::default()
+            } else {
+                quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?
+            };
+
+            // We are using quiet mode, so only failed keys will be returned.
+            let mut errors = HashMap::with_capacity(result.error.len());
+            for err in result.error {
+                errors.insert(decode_url_encoded_key(&err.key)?, err);
             }
-            // TODO: we should handle those errors with code.
-            for i in keys {
-                batched_result.push((
-                    i,
-                    Err(Error::new(
-                        ErrorKind::Unexpected,
-                        "oss delete this key failed for reason we don't know",
-                    )),
-                ));
+
+            let mut batched_result = Vec::with_capacity(paths.len());
+            for path in paths {
+                let key = build_abs_path(&self.core.root, &path);
+
+                let err = match errors.remove(&key) {
+                    // Delete is idempotent, deleting a non-exist key should succeed.
+                    Some(err) if err.code == "NoSuchKey" => None,
+                    v => v,
+                };
+
+                match err {
+                    None => batched_result.push((path, Ok(RpDelete::default().into()))),
+                    Some(i) => {
+                        let (kind, retryable) =
+                            parse_oss_error_code(&i.code).unwrap_or((ErrorKind::Unexpected, false));
+                        let mut err = Error::new(kind, &i.message)
+                            .with_context("service", Scheme::Oss)
+                            .with_context("code", &i.code);
+                        if retryable {
+                            err = err.set_temporary();
+                        }
+
+                        batched_result.push((path, Err(err)));
+                    }
+                }
             }
 
             Ok(RpBatch::new(batched_result))
@@ -682,3 +1255,479 @@ impl Accessor for OssBackend {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_without_bucket() {
+        let err = OssBuilder::default().build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+    }
+
+    #[test]
+    fn test_build_data_encryption_without_kms() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .server_side_encryption("AES256")
+            .server_side_data_encryption("SM4");
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(
+            err.to_string().contains("server_side_data_encryption"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presign_write_with_sse_headers() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .access_key_id("access_key_id")
+            .access_key_secret("access_key_secret")
+            .server_side_encryption("KMS")
+            .server_side_encryption_key_id("cmk-id")
+            .server_side_data_encryption("SM4");
+        let backend = builder.build().expect("build must succeed");
+
+        let rp = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(OpWrite::new(), std::time::Duration::from_secs(3600)),
+            )
+            .await
+            .expect("presign must succeed");
+        let req = rp.into_presigned_request();
+
+        let headers = req.header();
+        assert_eq!(headers[constants::X_OSS_SERVER_SIDE_ENCRYPTION], "KMS");
+        assert_eq!(
+            headers[constants::X_OSS_SERVER_SIDE_ENCRYPTION_KEY_ID],
+            "cmk-id"
+        );
+        assert_eq!(headers[constants::X_OSS_SERVER_SIDE_DATA_ENCRYPTION], "SM4");
+        assert!(req.uri().to_string().contains("Signature="));
+    }
+
+    #[tokio::test]
+    async fn test_presign_write_with_storage_class() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .access_key_id("access_key_id")
+            .access_key_secret("access_key_secret")
+            .default_storage_class("Archive");
+        let backend = builder.build().expect("build must succeed");
+
+        let rp = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(OpWrite::new(), std::time::Duration::from_secs(3600)),
+            )
+            .await
+            .expect("presign must succeed");
+        let req = rp.into_presigned_request();
+
+        assert_eq!(req.header()[constants::X_OSS_STORAGE_CLASS], "Archive");
+    }
+
+    #[tokio::test]
+    async fn test_presign_write_with_acl() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .access_key_id("access_key_id")
+            .access_key_secret("access_key_secret");
+        let backend = builder.build().expect("build must succeed");
+
+        let rp = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(
+                    OpWrite::new().with_acl("public-read"),
+                    std::time::Duration::from_secs(3600),
+                ),
+            )
+            .await
+            .expect("presign must succeed");
+        let req = rp.into_presigned_request();
+        assert_eq!(req.header()[constants::X_OSS_OBJECT_ACL], "public-read");
+
+        // s3 only canned acl is not supported by oss.
+        let err = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(
+                    OpWrite::new().with_acl("authenticated-read"),
+                    std::time::Duration::from_secs(3600),
+                ),
+            )
+            .await
+            .expect_err("presign must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_symlink_target() {
+        let mut builder = OssBuilder::default();
+        builder
+            .root("/root/")
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com");
+        let backend = builder.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .oss_put_symlink_request("latest", "v1.0/data csv")
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://test.oss-cn-beijing.aliyuncs.com/root/latest?symlink"
+        );
+        assert_eq!(
+            req.headers()[constants::X_OSS_SYMLINK_TARGET],
+            "root/v1.0/data%20csv"
+        );
+
+        let cases = vec![
+            ("root/v1.0/data%20csv", "v1.0/data csv"),
+            ("other/data", "/other/data"),
+        ];
+        for (header, expected) in cases {
+            let mut headers = http::HeaderMap::new();
+            headers.insert(
+                constants::X_OSS_SYMLINK_TARGET,
+                http::HeaderValue::from_static(header),
+            );
+            assert_eq!(
+                backend
+                    .core
+                    .parse_symlink_target(&headers)
+                    .expect("parse must succeed"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_copy_request() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com");
+        let backend = builder.build().expect("build must succeed");
+
+        let args = OpCopy::new()
+            .with_if_match("\"etag\"")
+            .with_metadata_directive(CopyDirective::Replace)
+            .with_content_type("text/plain")
+            .with_tagging_directive(CopyDirective::Copy)
+            .with_tagging("ignored=true");
+        let req = backend
+            .core
+            .oss_copy_object_request("from file", "to", &args)
+            .expect("request must be built");
+        let headers = req.headers();
+        assert_eq!(headers[constants::X_OSS_COPY_SOURCE], "/test/from%20file");
+        assert_eq!(headers[constants::X_OSS_COPY_SOURCE_IF_MATCH], "\"etag\"");
+        assert_eq!(headers[constants::X_OSS_METADATA_DIRECTIVE], "REPLACE");
+        assert_eq!(headers[http::header::CONTENT_TYPE], "text/plain");
+        assert_eq!(headers[constants::X_OSS_TAGGING_DIRECTIVE], "COPY");
+        assert!(!headers.contains_key(constants::X_OSS_TAGGING));
+
+        // Content type is ignored while copying metadata.
+        let args = OpCopy::new().with_content_type("text/plain");
+        let req = backend
+            .core
+            .oss_copy_object_request("from", "to", &args)
+            .expect("request must be built");
+        assert!(!req.headers().contains_key(http::header::CONTENT_TYPE));
+        assert!(!req
+            .headers()
+            .contains_key(constants::X_OSS_METADATA_DIRECTIVE));
+
+        let req = backend
+            .core
+            .oss_upload_part_copy_request(
+                "from",
+                "to",
+                "upload_id",
+                2,
+                BytesRange::new(Some(1024), Some(1024)),
+                &OpCopy::new(),
+            )
+            .expect("request must be built");
+        assert_eq!(
+            req.uri().to_string(),
+            "https://test.oss-cn-beijing.aliyuncs.com/to?partNumber=2&uploadId=upload_id"
+        );
+        assert_eq!(
+            req.headers()[constants::X_OSS_COPY_SOURCE_RANGE],
+            "bytes=1024-2047"
+        );
+    }
+
+    /// Compute the query string signature of oss.
+    ///
+    /// Reference: <https://help.aliyun.com/document_detail/31952.html>
+    fn oss_query_signature(secret: &str, string_to_sign: &str) -> String {
+        use base64::engine::general_purpose;
+        use base64::Engine;
+        use hmac::Hmac;
+        use hmac::Mac;
+        use sha1::Sha1;
+
+        let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes()).expect("must success");
+        mac.update(string_to_sign.as_bytes());
+        general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    fn query_pairs(uri: &Uri) -> HashMap<String, String> {
+        uri.query()
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    percent_encoding::percent_decode_str(v)
+                        .decode_utf8_lossy()
+                        .to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_presign_read() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .presign_endpoint("https://cdn.example.com")
+            .enable_presign_cname()
+            .access_key_id("access_key_id")
+            .access_key_secret("access_key_secret");
+        let backend = builder.build().expect("build must succeed");
+
+        let rp = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(
+                    OpRead::new().with_override_cache_control("no-cache"),
+                    std::time::Duration::from_secs(3600),
+                ),
+            )
+            .await
+            .expect("presign must succeed");
+        let req = rp.into_presigned_request();
+
+        assert_eq!(req.method(), http::Method::GET);
+        assert_eq!(req.uri().host(), Some("cdn.example.com"));
+        assert_eq!(req.uri().path(), "/path/to/file");
+        // Content-Type is signed, presigned url for read must not require it.
+        assert!(!req.header().contains_key(http::header::CONTENT_TYPE));
+
+        let query = query_pairs(req.uri());
+        assert_eq!(query["OSSAccessKeyId"], "access_key_id");
+        assert_eq!(query["response-cache-control"], "no-cache");
+        assert!(!query.contains_key("security-token"));
+
+        let string_to_sign = format!(
+            "GET\n\n\n{}\n/test/path/to/file?response-cache-control=no-cache",
+            query["Expires"]
+        );
+        assert_eq!(
+            query["Signature"],
+            oss_query_signature("access_key_secret", &string_to_sign)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presign_write_with_security_token() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://oss-cn-beijing.aliyuncs.com")
+            .access_key_id("access_key_id")
+            .access_key_secret("access_key_secret")
+            .security_token("sts/token");
+        let backend = builder.build().expect("build must succeed");
+
+        let rp = backend
+            .presign(
+                "path/to/file",
+                OpPresign::new(
+                    OpWrite::new().with_content_type("text/plain"),
+                    std::time::Duration::from_secs(3600),
+                ),
+            )
+            .await
+            .expect("presign must succeed");
+        let req = rp.into_presigned_request();
+
+        assert_eq!(req.method(), http::Method::PUT);
+        assert_eq!(req.uri().host(), Some("test.oss-cn-beijing.aliyuncs.com"));
+        assert_eq!(req.header()[http::header::CONTENT_TYPE], "text/plain");
+
+        let query = query_pairs(req.uri());
+        assert_eq!(query["OSSAccessKeyId"], "access_key_id");
+        assert_eq!(query["security-token"], "sts/token");
+
+        let string_to_sign = format!(
+            "PUT\n\ntext/plain\n{}\n/test/path/to/file?security-token=sts/token",
+            query["Expires"]
+        );
+        assert_eq!(
+            query["Signature"],
+            oss_query_signature("access_key_secret", &string_to_sign)
+        );
+    }
+
+    #[test]
+    fn test_build_normalize_endpoint() {
+        let cases = vec![
+            (
+                "schemeless",
+                "oss-cn-hangzhou.aliyuncs.com",
+                "https://test.oss-cn-hangzhou.aliyuncs.com",
+            ),
+            (
+                "http",
+                "http://oss-cn-hangzhou.aliyuncs.com",
+                "http://test.oss-cn-hangzhou.aliyuncs.com",
+            ),
+            (
+                "https",
+                "https://oss-cn-hangzhou.aliyuncs.com",
+                "https://test.oss-cn-hangzhou.aliyuncs.com",
+            ),
+            (
+                "with port",
+                "http://oss.example.com:8080",
+                "http://test.oss.example.com:8080",
+            ),
+            (
+                "trailing slash",
+                "https://oss-cn-hangzhou.aliyuncs.com/",
+                "https://test.oss-cn-hangzhou.aliyuncs.com",
+            ),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut builder = OssBuilder::default();
+            builder.bucket("test").endpoint(input);
+            let backend = builder.build().expect("build must succeed");
+
+            assert_eq!(backend.core.endpoint, expected, "{name}");
+            assert_eq!(
+                backend.core.host,
+                expected.split_once("://").unwrap().1,
+                "{name}"
+            );
+        }
+
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("ftp://oss-cn-hangzhou.aliyuncs.com");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_build_with_cname() {
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://cdn.example.com")
+            .enable_cname();
+        let backend = builder.build().expect("build must succeed");
+        assert_eq!(backend.core.endpoint, "https://cdn.example.com");
+        assert_eq!(backend.core.host, "cdn.example.com");
+        assert_eq!(backend.core.presign_endpoint, "https://cdn.example.com");
+
+        // Bucket prefixed endpoint is not a valid cname.
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .endpoint("https://test.oss-cn-hangzhou.aliyuncs.com")
+            .enable_cname();
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("cname"), "{err}");
+    }
+
+    #[test]
+    fn test_build_with_region() {
+        let mut builder = OssBuilder::default();
+        builder.bucket("test").region("cn-hangzhou");
+        let backend = builder.build().expect("build must succeed");
+        assert_eq!(
+            backend.core.endpoint,
+            "https://test.oss-cn-hangzhou.aliyuncs.com"
+        );
+        assert_eq!(
+            backend.core.presign_endpoint,
+            "https://test.oss-cn-hangzhou.aliyuncs.com"
+        );
+
+        let mut builder = OssBuilder::default();
+        builder
+            .bucket("test")
+            .region("oss-cn-hangzhou")
+            .enable_internal_endpoint();
+        let backend = builder.build().expect("build must succeed");
+        assert_eq!(
+            backend.core.endpoint,
+            "https://test.oss-cn-hangzhou-internal.aliyuncs.com"
+        );
+        assert_eq!(
+            backend.core.host,
+            "test.oss-cn-hangzhou-internal.aliyuncs.com"
+        );
+        // Presigned urls should be accessible outside.
+        assert_eq!(
+            backend.core.presign_endpoint,
+            "https://test.oss-cn-hangzhou.aliyuncs.com"
+        );
+    }
+
+    #[test]
+    fn test_build_with_internal_endpoint_invalid() {
+        let mut without_region = OssBuilder::default();
+        without_region.bucket("test").enable_internal_endpoint();
+
+        let mut with_endpoint = OssBuilder::default();
+        with_endpoint
+            .bucket("test")
+            .region("cn-hangzhou")
+            .endpoint("https://oss-cn-hangzhou.aliyuncs.com")
+            .enable_internal_endpoint();
+
+        let mut with_cname = OssBuilder::default();
+        with_cname
+            .bucket("test")
+            .region("cn-hangzhou")
+            .enable_cname()
+            .enable_internal_endpoint();
+
+        for (name, mut builder) in [
+            ("without region", without_region),
+            ("with endpoint", with_endpoint),
+            ("with cname", with_cname),
+        ] {
+            let err = builder.build().expect_err("build must fail");
+            assert_eq!(err.kind(), ErrorKind::ConfigInvalid, "{name}");
+        }
+    }
+}
@@ -20,33 +20,75 @@ use std::fmt::Formatter;
 use std::time::Duration;
 
 use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Utc;
 use http::header::CACHE_CONTROL;
 use http::header::CONTENT_DISPOSITION;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
+use http::header::EXPIRES;
 use http::header::IF_MATCH;
 use http::header::IF_NONE_MATCH;
 use http::header::RANGE;
+use http::HeaderMap;
 use http::HeaderName;
 use http::HeaderValue;
 use http::Request;
 use http::Response;
+use log::debug;
+use percent_encoding::percent_decode_str;
 use reqsign::AliyunCredential;
 use reqsign::AliyunLoader;
 use reqsign::AliyunOssSigner;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::credential::CustomedLoader;
+use crate::ops::CopyDirective;
+use crate::ops::OpCopy;
+use crate::ops::OpRestore;
 use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
-mod constants {
+pub mod constants {
     pub const X_OSS_SERVER_SIDE_ENCRYPTION: &str = "x-oss-server-side-encryption";
 
     pub const X_OSS_SERVER_SIDE_ENCRYPTION_KEY_ID: &str = "x-oss-server-side-encryption-key-id";
 
+    pub const X_OSS_SERVER_SIDE_DATA_ENCRYPTION: &str = "x-oss-server-side-data-encryption";
+
     pub const RESPONSE_CONTENT_DISPOSITION: &str = "response-content-disposition";
+
+    pub const RESPONSE_CACHE_CONTROL: &str = "response-cache-control";
+
+    pub const X_OSS_STORAGE_CLASS: &str = "x-oss-storage-class";
+
+    pub const X_OSS_OBJECT_ACL: &str = "x-oss-object-acl";
+
+    pub const X_OSS_RESTORE: &str = "x-oss-restore";
+
+    pub const X_OSS_COPY_SOURCE: &str = "x-oss-copy-source";
+
+    pub const X_OSS_COPY_SOURCE_RANGE: &str = "x-oss-copy-source-range";
+
+    pub const X_OSS_COPY_SOURCE_IF_MATCH: &str = "x-oss-copy-source-if-match";
+
+    pub const X_OSS_COPY_SOURCE_IF_NONE_MATCH: &str = "x-oss-copy-source-if-none-match";
+
+    pub const X_OSS_METADATA_DIRECTIVE: &str = "x-oss-metadata-directive";
+
+    pub const X_OSS_TAGGING_DIRECTIVE: &str = "x-oss-tagging-directive";
+
+    pub const X_OSS_TAGGING: &str = "x-oss-tagging";
+
+    pub const X_OSS_TAGGING_COUNT: &str = "x-oss-tagging-count";
+
+    pub const X_OSS_REQUEST_ID: &str = "x-oss-request-id";
+
+    pub const X_OSS_OBJECT_TYPE: &str = "x-oss-object-type";
+
+    pub const X_OSS_SYMLINK_TARGET: &str = "x-oss-symlink-target";
 }
 
 pub struct OssCore {
@@ -54,16 +96,20 @@ pub struct OssCore {
     pub bucket: String,
     /// buffered host string
     ///
-    /// format: <bucket-name>.<endpoint-domain-name>
+    /// format: <bucket-name>.<endpoint-domain-name>, or <endpoint-domain-name>
+    /// while cname is enabled.
     pub host: String,
     pub endpoint: String,
     pub presign_endpoint: String,
 
     pub server_side_encryption: Option<HeaderValue>,
     pub server_side_encryption_key_id: Option<HeaderValue>,
+    pub server_side_data_encryption: Option<HeaderValue>,
+    pub default_storage_class: Option<HeaderValue>,
 
     pub client: HttpClient,
     pub loader: AliyunLoader,
+    pub customed_loader: Option<CustomedLoader>,
     pub signer: AliyunOssSigner,
     pub write_min_size: usize,
 }
@@ -81,6 +127,10 @@ impl Debug for OssCore {
 
 impl OssCore {
     async fn load_credential(&self) -> Result<Option<AliyunCredential>> {
+        if let Some(loader) = &self.customed_loader {
+            return loader.load().await;
+        }
+
         let cred = self
             .loader
             .load()
@@ -118,7 +168,20 @@ impl OssCore {
 
     #[inline]
     pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
-        self.client.send(req).await
+        let resp = self.client.send(req).await?;
+
+        // Errors carry the request id already, record it for succeeded
+        // requests so that users could still find it while debugging.
+        if resp.status().is_success() {
+            if let Ok(Some(v)) = parse_header_to_str(resp.headers(), constants::X_OSS_REQUEST_ID) {
+                debug!(
+                    "oss request succeeded: status {}, request_id {v}",
+                    resp.status()
+                );
+            }
+        }
+
+        Ok(resp)
     }
 
     /// Set sse headers
@@ -143,6 +206,27 @@ impl OssCore {
                 v,
             )
         }
+        if let Some(v) = &self.server_side_data_encryption {
+            req = req.header(
+                HeaderName::from_static(constants::X_OSS_SERVER_SIDE_DATA_ENCRYPTION),
+                v,
+            )
+        }
+        req
+    }
+
+    /// Set storage class header
+    ///
+    /// # Note
+    ///
+    /// Only PutObject and InitiateMultipartUpload accept the storage class.
+    pub fn insert_storage_class_header(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if let Some(v) = &self.default_storage_class {
+            req = req.header(HeaderName::from_static(constants::X_OSS_STORAGE_CLASS), v)
+        }
         req
     }
 }
@@ -156,6 +240,8 @@ impl OssCore {
         content_type: Option<&str>,
         content_disposition: Option<&str>,
         cache_control: Option<&str>,
+        expires: Option<DateTime<Utc>>,
+        acl: Option<&str>,
         body: AsyncBody,
         is_presign: bool,
     ) -> Result<Request<AsyncBody>> {
@@ -179,13 +265,23 @@ impl OssCore {
             req = req.header(CACHE_CONTROL, cache_control)
         }
 
+        if let Some(expires) = expires {
+            req = req.header(EXPIRES, format_datetime_into_http_date(expires))
+        }
+
+        if let Some(acl) = acl {
+            req = req.header(HeaderName::from_static(constants::X_OSS_OBJECT_ACL), acl)
+        }
+
         // set sse headers
         req = self.insert_sse_headers(req);
+        req = self.insert_storage_class_header(req);
 
         let req = req.body(body).map_err(new_request_build_error)?;
         Ok(req)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn oss_get_object_request(
         &self,
         path: &str,
@@ -194,6 +290,7 @@ impl OssCore {
         if_match: Option<&str>,
         if_none_match: Option<&str>,
         override_content_disposition: Option<&str>,
+        override_cache_control: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
         let endpoint = self.get_endpoint(is_presign);
@@ -208,13 +305,24 @@ impl OssCore {
                 percent_encode_path(override_content_disposition)
             ))
         }
+        if let Some(override_cache_control) = override_cache_control {
+            query_args.push(format!(
+                "{}={}",
+                constants::RESPONSE_CACHE_CONTROL,
+                percent_encode_path(override_cache_control)
+            ))
+        }
 
         if !query_args.is_empty() {
             url.push_str(&format!("?{}", query_args.join("&")));
         }
 
         let mut req = Request::get(&url);
-        req = req.header(CONTENT_TYPE, "application/octet-stream");
+        // Content-Type is part of the string to sign, presigned urls
+        // must not carry it since clients like browsers won't send it.
+        if !is_presign {
+            req = req.header(CONTENT_TYPE, "application/octet-stream");
+        }
 
         if !range.is_full() {
             req = req.header(RANGE, range.to_header());
@@ -308,6 +416,7 @@ impl OssCore {
         if_match: Option<&str>,
         if_none_match: Option<&str>,
         override_content_disposition: Option<&str>,
+        override_cache_control: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let mut req = self.oss_get_object_request(
             path,
@@ -316,6 +425,7 @@ impl OssCore {
             if_match,
             if_none_match,
             override_content_disposition,
+            override_cache_control,
         )?;
         self.sign(&mut req).await?;
         self.send(req).await
@@ -333,6 +443,60 @@ impl OssCore {
         self.send(req).await
     }
 
+    pub fn oss_put_symlink_request(&self, path: &str, target: &str) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let t = build_abs_path(&self.root, target);
+        let url = format!("{}/{}?symlink", self.endpoint, percent_encode_path(&p));
+
+        Request::put(&url)
+            .header(CONTENT_LENGTH, 0)
+            .header(constants::X_OSS_SYMLINK_TARGET, percent_encode_path(&t))
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)
+    }
+
+    pub async fn oss_put_symlink(
+        &self,
+        path: &str,
+        target: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.oss_put_symlink_request(path, target)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    pub async fn oss_get_symlink(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let url = format!("{}/{}?symlink", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Parse the target of symlink from the response of GetSymlink.
+    ///
+    /// Target outside of root will be returned as an absolute path.
+    pub fn parse_symlink_target(&self, headers: &HeaderMap) -> Result<String> {
+        let target =
+            parse_header_to_str(headers, constants::X_OSS_SYMLINK_TARGET)?.ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "symlink target is not in the response header",
+                )
+            })?;
+        let target = decode_url_encoded_key(target)?;
+
+        match target.strip_prefix(&self.root[1..]) {
+            Some(rel) if !rel.is_empty() => Ok(rel.to_string()),
+            _ => Ok(format!("/{target}")),
+        }
+    }
+
     pub async fn oss_put_object(
         &self,
         path: &str,
@@ -348,6 +512,8 @@ impl OssCore {
             content_type,
             content_disposition,
             cache_control,
+            None,
+            None,
             body,
             false,
         )?;
@@ -356,12 +522,32 @@ impl OssCore {
         self.send(req).await
     }
 
-    pub async fn oss_copy_object(
+    /// Build the value of `x-oss-copy-source` header.
+    fn copy_source(&self, from: &str) -> String {
+        let source = build_abs_path(&self.root, from);
+        format!("/{}/{}", self.bucket, percent_encode_path(&source))
+    }
+
+    /// Insert `x-oss-copy-source-if-match` and `x-oss-copy-source-if-none-match`.
+    fn insert_copy_source_condition_headers(
+        mut req: http::request::Builder,
+        args: &OpCopy,
+    ) -> http::request::Builder {
+        if let Some(v) = args.if_match() {
+            req = req.header(constants::X_OSS_COPY_SOURCE_IF_MATCH, v);
+        }
+        if let Some(v) = args.if_none_match() {
+            req = req.header(constants::X_OSS_COPY_SOURCE_IF_NONE_MATCH, v);
+        }
+        req
+    }
+
+    pub fn oss_copy_object_request(
         &self,
         from: &str,
         to: &str,
-    ) -> Result<Response<IncomingAsyncBody>> {
-        let source = build_abs_path(&self.root, from);
+        args: &OpCopy,
+    ) -> Result<Request<AsyncBody>> {
         let target = build_abs_path(&self.root, to);
 
         let url = format!(
@@ -369,13 +555,81 @@ impl OssCore {
             self.get_endpoint(false),
             percent_encode_path(&target)
         );
-        let source = format!("/{}/{}", self.bucket, percent_encode_path(&source));
 
         let mut req = Request::put(&url);
 
         req = self.insert_sse_headers(req);
 
-        req = req.header("x-oss-copy-source", source);
+        req = req.header(constants::X_OSS_COPY_SOURCE, self.copy_source(from));
+        req = Self::insert_copy_source_condition_headers(req, args);
+
+        if let Some(directive) = args.metadata_directive() {
+            req = req.header(
+                constants::X_OSS_METADATA_DIRECTIVE,
+                copy_directive_to_str(directive),
+            );
+        }
+        if args.metadata_directive() == Some(CopyDirective::Replace) {
+            if let Some(v) = args.content_type() {
+                req = req.header(CONTENT_TYPE, v);
+            }
+        }
+
+        if let Some(directive) = args.tagging_directive() {
+            req = req.header(
+                constants::X_OSS_TAGGING_DIRECTIVE,
+                copy_directive_to_str(directive),
+            );
+        }
+        if args.tagging_directive() == Some(CopyDirective::Replace) {
+            if let Some(v) = args.tagging() {
+                req = req.header(constants::X_OSS_TAGGING, v);
+            }
+        }
+
+        req.body(AsyncBody::Empty).map_err(new_request_build_error)
+    }
+
+    pub async fn oss_copy_object(
+        &self,
+        from: &str,
+        to: &str,
+        args: &OpCopy,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.oss_copy_object_request(from, to, args)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Initiate multipart upload for copy, metadata and tags of target
+    /// can only be set here.
+    pub async fn oss_initiate_copy_upload(
+        &self,
+        to: &str,
+        content_type: Option<&str>,
+        content_disposition: Option<&str>,
+        cache_control: Option<&str>,
+        tagging: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, to);
+        let url = format!("{}/{}?uploads", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::post(&url);
+        if let Some(v) = content_type {
+            req = req.header(CONTENT_TYPE, v);
+        }
+        if let Some(v) = content_disposition {
+            req = req.header(CONTENT_DISPOSITION, v);
+        }
+        if let Some(v) = cache_control {
+            req = req.header(CACHE_CONTROL, v);
+        }
+        if let Some(v) = tagging {
+            req = req.header(constants::X_OSS_TAGGING, v);
+        }
+        req = self.insert_sse_headers(req);
+        req = self.insert_storage_class_header(req);
 
         let mut req = req
             .body(AsyncBody::Empty)
@@ -385,6 +639,70 @@ impl OssCore {
         self.send(req).await
     }
 
+    pub fn oss_upload_part_copy_request(
+        &self,
+        from: &str,
+        to: &str,
+        upload_id: &str,
+        part_number: usize,
+        range: BytesRange,
+        args: &OpCopy,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, to);
+        let url = format!(
+            "{}/{}?partNumber={}&uploadId={}",
+            self.endpoint,
+            percent_encode_path(&p),
+            part_number,
+            percent_encode_path(upload_id)
+        );
+
+        let mut req = Request::put(&url)
+            .header(CONTENT_LENGTH, 0)
+            .header(constants::X_OSS_COPY_SOURCE, self.copy_source(from))
+            .header(constants::X_OSS_COPY_SOURCE_RANGE, range.to_header());
+        req = Self::insert_copy_source_condition_headers(req, args);
+
+        req.body(AsyncBody::Empty).map_err(new_request_build_error)
+    }
+
+    pub async fn oss_upload_part_copy(
+        &self,
+        from: &str,
+        to: &str,
+        upload_id: &str,
+        part_number: usize,
+        range: BytesRange,
+        args: &OpCopy,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req =
+            self.oss_upload_part_copy_request(from, to, upload_id, part_number, range, args)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    pub async fn oss_abort_multipart_upload(
+        &self,
+        path: &str,
+        upload_id: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let url = format!(
+            "{}/{}?uploadId={}",
+            self.endpoint,
+            percent_encode_path(&p),
+            percent_encode_path(upload_id)
+        );
+
+        let mut req = Request::delete(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
     pub async fn oss_list_object(
         &self,
         path: &str,
@@ -408,11 +726,14 @@ impl OssCore {
         &self,
         paths: Vec<String>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let url = format!("{}/?delete", self.endpoint);
+        // Ask OSS to url encode keys in response so that keys with special
+        // characters can be returned correctly.
+        let url = format!("{}/?delete&encoding-type=url", self.endpoint);
 
         let req = Request::post(&url);
 
         let content = quick_xml::se::to_string(&DeleteObjectsRequest {
+            quiet: true,
             object: paths
                 .into_iter()
                 .map(|path| DeleteObjectsRequestObject {
@@ -438,6 +759,63 @@ impl OssCore {
         self.send(req).await
     }
 
+    pub fn oss_restore_object_request(
+        &self,
+        path: &str,
+        args: &OpRestore,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let url = format!("{}/{}?restore", self.endpoint, percent_encode_path(&p));
+
+        let req = Request::post(&url);
+
+        // OSS uses its default restore days and tier if request body is empty.
+        if args.days().is_none() && args.tier().is_none() {
+            return req
+                .header(CONTENT_LENGTH, 0)
+                .body(AsyncBody::Empty)
+                .map_err(new_request_build_error);
+        }
+
+        let content = quick_xml::se::to_string(&RestoreRequest {
+            days: args.days(),
+            job_parameters: args.tier().map(|tier| RestoreRequestJobParameters {
+                tier: tier.to_string(),
+            }),
+        })
+        .map_err(new_xml_deserialize_error)?;
+        // Make sure content length has been set to avoid post with chunked encoding.
+        let req = req.header(CONTENT_LENGTH, content.len());
+        // Set content-type to `application/xml` to avoid mixed with form post.
+        let req = req.header(CONTENT_TYPE, "application/xml");
+
+        req.body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)
+    }
+
+    pub async fn oss_restore_object(
+        &self,
+        path: &str,
+        args: &OpRestore,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.oss_restore_object_request(path, args)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    pub async fn oss_get_object_acl(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let url = format!("{}/{}?acl", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
     fn get_endpoint(&self, is_presign: bool) -> &str {
         if is_presign {
             &self.presign_endpoint
@@ -451,20 +829,31 @@ impl OssCore {
         path: &str,
         args: &OpWrite,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let cache_control = args.cache_control();
         let req = self
-            .oss_initiate_upload_request(path, None, None, cache_control, AsyncBody::Empty, false)
+            .oss_initiate_upload_request(
+                path,
+                None,
+                None,
+                args.cache_control(),
+                args.expires(),
+                args.acl(),
+                AsyncBody::Empty,
+                false,
+            )
             .await?;
         self.send(req).await
     }
 
     /// Creates a request that initiates multipart upload
+    #[allow(clippy::too_many_arguments)]
     async fn oss_initiate_upload_request(
         &self,
         path: &str,
         content_type: Option<&str>,
         content_disposition: Option<&str>,
         cache_control: Option<&str>,
+        expires: Option<DateTime<Utc>>,
+        acl: Option<&str>,
         body: AsyncBody,
         is_presign: bool,
     ) -> Result<Request<AsyncBody>> {
@@ -481,7 +870,14 @@ impl OssCore {
         if let Some(cache_control) = cache_control {
             req = req.header(CACHE_CONTROL, cache_control);
         }
+        if let Some(expires) = expires {
+            req = req.header(EXPIRES, format_datetime_into_http_date(expires));
+        }
+        if let Some(acl) = acl {
+            req = req.header(HeaderName::from_static(constants::X_OSS_OBJECT_ACL), acl);
+        }
         req = self.insert_sse_headers(req);
+        req = self.insert_storage_class_header(req);
         let mut req = req.body(body).map_err(new_request_build_error)?;
         self.sign(&mut req).await?;
         Ok(req)
@@ -554,10 +950,98 @@ impl OssCore {
     }
 }
 
+/// Decode key encoded with `encoding-type=url`.
+pub fn decode_url_encoded_key(key: &str) -> Result<String> {
+    percent_decode_str(&key.replace('+', " "))
+        .decode_utf8()
+        .map(|v| v.into_owned())
+        .map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "url encoded key is not valid utf-8")
+                .with_context("key", key)
+                .set_source(err)
+        })
+}
+
+fn copy_directive_to_str(directive: CopyDirective) -> &'static str {
+    match directive {
+        CopyDirective::Copy => "COPY",
+        CopyDirective::Replace => "REPLACE",
+    }
+}
+
+/// Canned ACLs that can be applied to objects, `default` means
+/// inheriting the bucket's ACL.
+///
+/// ref: <https://www.alibabacloud.com/help/en/oss/developer-reference/putobjectacl>
+const CANNED_ACLS: [&str; 4] = ["default", "private", "public-read", "public-read-write"];
+
+/// Check if given canned ACL is supported by oss.
+pub fn check_canned_acl(acl: &str) -> Result<()> {
+    if CANNED_ACLS.contains(&acl) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "canned acl is not supported")
+            .with_context("service", Scheme::Oss)
+            .with_context("acl", acl),
+    )
+}
+
+/// Result of GetObjectACL.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlPolicy {
+    pub owner: Option<AccessControlPolicyOwner>,
+    pub access_control_list: AccessControlList,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlPolicyOwner {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlList {
+    pub grant: String,
+}
+
+impl AccessControlPolicy {
+    /// Convert into acl.
+    ///
+    /// OSS only returns canned ACL. `default` means the object inherits
+    /// the bucket's ACL, which is unknown here.
+    pub fn into_acl(self) -> Acl {
+        let mut acl = Acl::new();
+
+        if let Some(owner) = self.owner {
+            let mut v = AclOwner::new(&owner.id);
+            if let Some(name) = &owner.display_name {
+                v = v.with_display_name(name);
+            }
+            acl.set_owner(v);
+        }
+
+        let grant = self.access_control_list.grant;
+        if !grant.is_empty() && grant != "default" {
+            acl.set_canned_acl(&grant);
+        }
+
+        acl
+    }
+}
+
 /// Request of DeleteObjects.
 #[derive(Default, Debug, Serialize)]
 #[serde(default, rename = "Delete", rename_all = "PascalCase")]
 pub struct DeleteObjectsRequest {
+    /// In quiet mode, the response includes only keys where the delete
+    /// operation encountered an error.
+    pub quiet: bool,
     pub object: Vec<DeleteObjectsRequestObject>,
 }
 
@@ -572,11 +1056,13 @@ pub struct DeleteObjectsRequestObject {
 #[serde(default, rename = "DeleteResult", rename_all = "PascalCase")]
 pub struct DeleteObjectsResult {
     pub deleted: Vec<DeleteObjectsResultDeleted>,
+    pub error: Vec<DeleteObjectsResultError>,
 }
 
 #[derive(Default, Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteObjectsResultDeleted {
+    #[cfg(test)]
     pub key: String,
 }
 
@@ -613,6 +1099,32 @@ pub struct CompleteMultipartUploadRequest {
     pub part: Vec<MultipartUploadPart>,
 }
 
+/// Request of RestoreObject.
+#[derive(Default, Debug, Serialize)]
+#[serde(rename = "RestoreRequest", rename_all = "PascalCase")]
+pub struct RestoreRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_parameters: Option<RestoreRequestJobParameters>,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreRequestJobParameters {
+    pub tier: String,
+}
+
+/// Result of UploadPartCopy.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct CopyPartResult {
+    pub last_modified: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+#[cfg(test)]
 #[derive(Default, Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct CompleteMultipartUploadResult {
@@ -630,10 +1142,47 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_deserialize_access_control_policy() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<AccessControlPolicy>
+    <Owner>
+        <ID>0022012****</ID>
+        <DisplayName>0022012****</DisplayName>
+    </Owner>
+    <AccessControlList>
+        <Grant>public-read</Grant>
+    </AccessControlList>
+</AccessControlPolicy>"#,
+        );
+
+        let out: AccessControlPolicy =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+        let acl = out.into_acl();
+
+        assert_eq!(acl.owner().map(|v| v.id()), Some("0022012****"));
+        assert_eq!(acl.canned_acl(), Some("public-read"));
+        assert!(acl.grants().is_empty());
+        assert!(acl.is_public());
+
+        let bs = Bytes::from(
+            r#"<AccessControlPolicy>
+    <AccessControlList>
+        <Grant>default</Grant>
+    </AccessControlList>
+</AccessControlPolicy>"#,
+        );
+        let out: AccessControlPolicy =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+        assert_eq!(out.into_acl().canned_acl(), None);
+    }
+
     /// This example is from https://www.alibabacloud.com/help/zh/object-storage-service/latest/deletemultipleobjects
     #[test]
     fn test_serialize_delete_objects_request() {
         let req = DeleteObjectsRequest {
+            quiet: true,
             object: vec![
                 DeleteObjectsRequestObject {
                     key: "multipart.data".to_string(),
@@ -652,6 +1201,7 @@ mod tests {
         pretty_assertions::assert_eq!(
             actual,
             r#"<Delete>
+  <Quiet>true</Quiet>
   <Object>
     <Key>multipart.data</Key>
   </Object>
@@ -667,6 +1217,57 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_serialize_delete_objects_request_with_escape() {
+        let req = DeleteObjectsRequest {
+            quiet: true,
+            object: vec![DeleteObjectsRequestObject {
+                key: "a&b<c>.txt".to_string(),
+            }],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<Delete>
+  <Quiet>true</Quiet>
+  <Object>
+    <Key>a&amp;b&lt;c&gt;.txt</Key>
+  </Object>
+</Delete>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        )
+    }
+
+    #[test]
+    fn test_deserialize_delete_objects_result_with_error() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<DeleteResult>
+    <EncodingType>url</EncodingType>
+    <Error>
+       <Key>a%26b%3Cc%3E.txt</Key>
+       <Code>AccessDenied</Code>
+       <Message>Access Denied</Message>
+    </Error>
+</DeleteResult>"#,
+        );
+
+        let out: DeleteObjectsResult =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+
+        assert!(out.deleted.is_empty());
+        assert_eq!(out.error.len(), 1);
+        assert_eq!(
+            decode_url_encoded_key(&out.error[0].key).expect("must success"),
+            "a&b<c>.txt"
+        );
+        assert_eq!(out.error[0].code, "AccessDenied");
+        assert_eq!(out.error[0].message, "Access Denied");
+    }
+
     /// This example is from https://www.alibabacloud.com/help/zh/object-storage-service/latest/deletemultipleobjects
     #[test]
     fn test_deserialize_delete_objects_result() {
@@ -755,6 +1356,54 @@ mod tests {
         )
     }
 
+    /// This example is from https://www.alibabacloud.com/help/en/object-storage-service/latest/restoreobject
+    #[test]
+    fn test_serialize_restore_request() {
+        let req = RestoreRequest {
+            days: Some(2),
+            job_parameters: Some(RestoreRequestJobParameters {
+                tier: "Standard".to_string(),
+            }),
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<RestoreRequest>
+    <Days>2</Days>
+    <JobParameters>
+        <Tier>Standard</Tier>
+    </JobParameters>
+</RestoreRequest>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        );
+
+        let req = RestoreRequest {
+            days: Some(2),
+            job_parameters: None,
+        };
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+        assert_eq!(actual, "<RestoreRequest><Days>2</Days></RestoreRequest>");
+    }
+
+    /// This example is from https://www.alibabacloud.com/help/en/object-storage-service/latest/uploadpartcopy
+    #[test]
+    fn test_deserialize_copy_part_result() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<CopyPartResult xmlns="http://doc.oss-cn-hangzhou.aliyuncs.com">
+    <LastModified>2014-07-17T06:27:54.000Z</LastModified>
+    <ETag>"5B3C1A2E053D763E1B002CC607C5****"</ETag>
+</CopyPartResult>"#,
+        );
+
+        let result: CopyPartResult = quick_xml::de::from_reader(bs.reader()).unwrap();
+        assert_eq!(result.etag, "\"5B3C1A2E053D763E1B002CC607C5****\"");
+        assert_eq!(result.last_modified, "2014-07-17T06:27:54.000Z");
+    }
+
     #[test]
     fn test_deserialize_complete_oss_multipart_result() {
         let bytes = Bytes::from(
@@ -0,0 +1,336 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use log::debug;
+use reqsign::AliyunCredential;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::*;
+
+/// The endpoint of ECS instance metadata service.
+const ECS_METADATA_ENDPOINT: &str = "http://100.100.100.200";
+
+/// OssCredentialLoad is used to load credential for oss service.
+///
+/// Credentials returned by loader will be cached until they are about to
+/// expire (2 minutes before `expires_in`), then the loader will be called
+/// again to refresh them.
+#[async_trait]
+pub trait OssCredentialLoad: 'static + Send + Sync + Debug {
+    /// Load credential from sources.
+    ///
+    /// - If succeed, return `Ok(Some(cred))`
+    /// - If not found, return `Ok(None)`
+    /// - If unexpected errors happened, return `Err(err)`
+    async fn load_credential(
+        &self,
+        client: reqwest::Client,
+    ) -> anyhow::Result<Option<AliyunCredential>>;
+}
+
+/// EcsRamRoleLoad loads temporary credentials of the RAM role attached to
+/// current ECS instance via instance metadata service.
+///
+/// Reference: <https://www.alibabacloud.com/help/en/ecs/user-guide/attach-an-instance-ram-role-to-an-ecs-instance>
+#[derive(Debug)]
+pub struct EcsRamRoleLoad {
+    endpoint: String,
+    role_name: String,
+}
+
+impl EcsRamRoleLoad {
+    pub fn new(role_name: &str) -> Self {
+        Self {
+            endpoint: ECS_METADATA_ENDPOINT.to_string(),
+            role_name: role_name.to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl OssCredentialLoad for EcsRamRoleLoad {
+    async fn load_credential(
+        &self,
+        client: reqwest::Client,
+    ) -> anyhow::Result<Option<AliyunCredential>> {
+        let url = format!(
+            "{}/latest/meta-data/ram/security-credentials/{}",
+            self.endpoint.trim_end_matches('/'),
+            self.role_name
+        );
+
+        let resp = client.get(&url).send().await?;
+        let status = resp.status();
+        let bs = resp.bytes().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "request ecs metadata service failed with status {}: {}",
+                status,
+                String::from_utf8_lossy(&bs)
+            ));
+        }
+
+        let resp: EcsRamRoleResponse = serde_json::from_slice(&bs)?;
+        if resp.code != "Success" {
+            return Err(anyhow!(
+                "ecs metadata service returned code {} for role {}",
+                resp.code,
+                self.role_name
+            ));
+        }
+
+        let expires_in = DateTime::parse_from_rfc3339(&resp.expiration)?.with_timezone(&Utc);
+        debug!(
+            "oss credential of ecs ram role {} loaded, expires at {}",
+            self.role_name, expires_in
+        );
+
+        Ok(Some(AliyunCredential {
+            access_key_id: resp.access_key_id,
+            access_key_secret: resp.access_key_secret,
+            security_token: Some(resp.security_token),
+            expires_in: Some(expires_in),
+        }))
+    }
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct EcsRamRoleResponse {
+    code: String,
+    access_key_id: String,
+    access_key_secret: String,
+    security_token: String,
+    expiration: String,
+}
+
+/// CustomedLoader caches the credential loaded by [`OssCredentialLoad`].
+///
+/// The cache is guarded by an async mutex, so concurrent requests that
+/// find the credential expired will wait for the same refresh instead of
+/// calling the loader at the same time.
+pub struct CustomedLoader {
+    client: reqwest::Client,
+    loader: Box<dyn OssCredentialLoad>,
+    credential: Mutex<Option<AliyunCredential>>,
+}
+
+impl Debug for CustomedLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomedLoader")
+            .field("loader", &self.loader)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CustomedLoader {
+    pub fn new(client: reqwest::Client, loader: Box<dyn OssCredentialLoad>) -> Self {
+        Self {
+            client,
+            loader,
+            credential: Mutex::new(None),
+        }
+    }
+
+    pub async fn load(&self) -> Result<Option<AliyunCredential>> {
+        let mut cred = self.credential.lock().await;
+        if let Some(v) = cred.as_ref().filter(|v| v.is_valid()) {
+            return Ok(Some(v.clone()));
+        }
+
+        let loaded = self
+            .loader
+            .load_credential(self.client.clone())
+            .await
+            .map_err(|err| {
+                Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "load credential via customed loader failed",
+                )
+                .with_operation("OssCredentialLoad::load_credential")
+                .with_context("service", Scheme::Oss)
+                .set_source(err)
+            })?;
+
+        *cred = loaded.clone();
+        Ok(loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingLoad {
+        count: Arc<AtomicUsize>,
+        expires_in: Option<DateTime<Utc>>,
+    }
+
+    #[async_trait]
+    impl OssCredentialLoad for CountingLoad {
+        async fn load_credential(
+            &self,
+            _: reqwest::Client,
+        ) -> anyhow::Result<Option<AliyunCredential>> {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+            Ok(Some(AliyunCredential {
+                access_key_id: "ak".to_string(),
+                access_key_secret: "sk".to_string(),
+                security_token: Some("token".to_string()),
+                expires_in: self.expires_in,
+            }))
+        }
+    }
+
+    #[derive(Debug)]
+    struct FailedLoad;
+
+    #[async_trait]
+    impl OssCredentialLoad for FailedLoad {
+        async fn load_credential(
+            &self,
+            _: reqwest::Client,
+        ) -> anyhow::Result<Option<AliyunCredential>> {
+            Err(anyhow!("metadata service is unreachable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_customed_loader_dedup_refresh() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let loader = Arc::new(CustomedLoader::new(
+            reqwest::Client::new(),
+            Box::new(CountingLoad {
+                count: count.clone(),
+                expires_in: Some(Utc::now() + chrono::Duration::hours(1)),
+            }),
+        ));
+
+        let tasks = (0..8).map(|_| {
+            let loader = loader.clone();
+            tokio::spawn(async move { loader.load().await })
+        });
+        for task in tasks {
+            let cred = task.await.expect("must success").expect("must success");
+            assert_eq!(
+                cred.expect("must exist").security_token.as_deref(),
+                Some("token")
+            );
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_customed_loader_refresh_before_expiry() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let loader = CustomedLoader::new(
+            reqwest::Client::new(),
+            Box::new(CountingLoad {
+                count: count.clone(),
+                // Credential expires within the refresh buffer.
+                expires_in: Some(Utc::now() + chrono::Duration::minutes(1)),
+            }),
+        );
+
+        loader.load().await.expect("must success");
+        loader.load().await.expect("must success");
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_customed_loader_error() {
+        let loader = CustomedLoader::new(reqwest::Client::new(), Box::new(FailedLoad));
+
+        let err = match loader.load().await {
+            Ok(_) => panic!("load must fail"),
+            Err(err) => err,
+        };
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(format!("{err:?}").contains("metadata service is unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_ecs_ram_role_load() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/latest/meta-data/ram/security-credentials/test-role"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+  "AccessKeyId" : "STS.****************",
+  "AccessKeySecret" : "ak-secret",
+  "Expiration" : "2017-11-01T05:20:01Z",
+  "SecurityToken" : "sts-token",
+  "LastUpdated" : "2017-10-31T23:20:01Z",
+  "Code" : "Success"
+}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let cred = EcsRamRoleLoad::new("test-role")
+            .with_endpoint(&server.uri())
+            .load_credential(reqwest::Client::new())
+            .await
+            .expect("must success")
+            .expect("must exist");
+
+        assert_eq!(cred.access_key_id, "STS.****************");
+        assert_eq!(cred.access_key_secret, "ak-secret");
+        assert_eq!(cred.security_token.as_deref(), Some("sts-token"));
+        assert_eq!(
+            cred.expires_in,
+            Some(
+                DateTime::parse_from_rfc3339("2017-11-01T05:20:01Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+
+        let res = EcsRamRoleLoad::new("not-exist")
+            .with_endpoint(&server.uri())
+            .load_credential(reqwest::Client::new())
+            .await;
+        assert!(res.is_err());
+    }
+}
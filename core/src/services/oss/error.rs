@@ -16,11 +16,15 @@
 // under the License.
 
 use bytes::Buf;
+use bytes::Bytes;
+use http::response::Parts;
 use http::Response;
 use http::StatusCode;
+use log::warn;
 use quick_xml::de;
 use serde::Deserialize;
 
+use super::core::constants::X_OSS_REQUEST_ID;
 use crate::raw::*;
 use crate::Error;
 use crate::ErrorKind;
@@ -36,17 +40,36 @@ struct OssError {
     host_id: String,
 }
 
+impl OssError {
+    /// OSS may return error response with whitespace around element text,
+    /// so we need to trim them before using.
+    fn trim(self) -> Self {
+        Self {
+            code: self.code.trim().to_string(),
+            message: self.message.trim().to_string(),
+            request_id: self.request_id.trim().to_string(),
+            host_id: self.host_id.trim().to_string(),
+        }
+    }
+}
+
 /// Parse error response into Error.
 pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
     let bs = body.bytes().await?;
 
-    let (kind, retryable) = match parts.status {
+    Ok(parse_error_from_parts(parts, bs))
+}
+
+/// Parse error response into Error with body that has been read.
+pub fn parse_error_from_parts(parts: Parts, bs: Bytes) -> Error {
+    let (mut kind, mut retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
-            (ErrorKind::ConditionNotMatch, false)
-        }
+        StatusCode::PRECONDITION_FAILED => (ErrorKind::ConditionNotMatch, false),
+        StatusCode::NOT_MODIFIED => (ErrorKind::NotModified, false),
+        StatusCode::INSUFFICIENT_STORAGE => (ErrorKind::InsufficientStorage, false),
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -54,22 +77,102 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
 
-    let message = match de::from_reader::<_, OssError>(bs.clone().reader()) {
-        Ok(oss_err) => format!("{oss_err:?}"),
-        Err(_) => String::from_utf8_lossy(&bs).into_owned(),
+    let oss_err = de::from_reader::<_, OssError>(bs.clone().reader())
+        .ok()
+        .map(OssError::trim)
+        .filter(|v| !v.code.is_empty());
+
+    let message = match &oss_err {
+        Some(oss_err) => {
+            (kind, retryable) = parse_oss_error_code(&oss_err.code).unwrap_or((kind, retryable));
+            if oss_err.code == "RequestTimeTooSkewed" {
+                warn!(
+                    "oss request time is too skewed from server, please check the clock: {}",
+                    oss_err.message
+                );
+            }
+            oss_err.message.clone()
+        }
+        None => String::from_utf8_lossy(&bs).into_owned(),
     };
 
-    let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
+    // Prefer request id in headers which is returned even if body is empty,
+    // for example, the response of HEAD.
+    let request_id = parse_header_to_str(&parts.headers, X_OSS_REQUEST_ID)
+        .ok()
+        .flatten()
+        .map(|v| v.to_string())
+        .or_else(|| oss_err.as_ref().map(|v| v.request_id.clone()))
+        .filter(|v| !v.is_empty());
+
+    let mut err = Error::new(kind, &message);
+    if let Some(oss_err) = oss_err {
+        err = err.with_context("code", oss_err.code);
+    }
+    if let Some(request_id) = request_id {
+        err = err.with_request_id(request_id);
+    }
+    if let Ok(Some(v)) = parse_retry_after(&parts.headers) {
+        err = err.with_retry_after(v);
+    }
+    err = err.with_context("response", format!("{parts:?}"));
 
     if retryable {
         err = err.set_temporary();
     }
 
-    Ok(err)
+    err
+}
+
+/// Returns whether this error response means the object is being restored.
+pub fn is_restore_in_progress_error(bs: &Bytes) -> bool {
+    de::from_reader::<_, OssError>(bs.clone().reader())
+        .map(|v| v.code.trim() == "RestoreAlreadyInProgress")
+        .unwrap_or_default()
+}
+
+/// Returns the ErrorKind of this code and whether the error is retryable.
+/// All possible error code: <https://www.alibabacloud.com/help/en/object-storage-service/latest/error-responses>
+pub fn parse_oss_error_code(code: &str) -> Option<(ErrorKind, bool)> {
+    match code {
+        "NoSuchKey" => Some((ErrorKind::NotFound, false)),
+        // > The specified bucket does not exist.
+        //
+        // The bucket is part of the config, so it's the config that is wrong
+        // instead of the given path.
+        "NoSuchBucket" => Some((ErrorKind::ConfigInvalid, false)),
+        "AccessDenied" | "SignatureDoesNotMatch" | "InvalidAccessKeyId" => {
+            Some((ErrorKind::PermissionDenied, false))
+        }
+        // > The difference between the request time and the server time
+        // > is greater than 15 minutes.
+        //
+        // The request time will be regenerated while retrying, it's Ok to
+        // retry after the local clock is corrected.
+        "RequestTimeTooSkewed" => Some((ErrorKind::Unexpected, true)),
+        // > The position of append does not match the current length of
+        // > the object.
+        "PositionNotEqualToLength" => Some((ErrorKind::ConditionNotMatch, false)),
+        "ObjectNotAppendable" => Some((ErrorKind::Unsupported, false)),
+        // > The operation is not supported for this resource.
+        //
+        // Returned while reading archived objects that are not restored.
+        "InvalidObjectState" => Some((ErrorKind::Archived, false)),
+        // > The storage capacity of the bucket exceeds the quota.
+        "QuotaExceeded" => Some((ErrorKind::InsufficientStorage, false)),
+        // > Please reduce your request rate.
+        "SlowDown" | "Throttling" | "QpsLimitExceeded" => Some((ErrorKind::RateLimited, true)),
+        "InternalError" | "RequestTimeout" | "ServiceUnavailable" => {
+            Some((ErrorKind::Unexpected, true))
+        }
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::stream;
+
     use super::*;
 
     /// Error response example is from https://www.alibabacloud.com/help/en/object-storage-service/latest/error-responses
@@ -103,4 +206,159 @@ mod tests {
         assert_eq!(out.request_id, "1D842BC54255****");
         assert_eq!(out.host_id, "oss-cn-hangzhou.aliyuncs.com");
     }
+
+    #[test]
+    fn test_is_restore_in_progress_error() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>RestoreAlreadyInProgress</Code>
+  <Message>The restore operation is in progress.</Message>
+  <RequestId>5C3D9175B6FC201293AD****</RequestId>
+</Error>"#,
+        );
+        assert!(is_restore_in_progress_error(&bs));
+
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>OperationNotSupported</Code>
+  <Message>The operation is not supported for this resource.</Message>
+  <RequestId>5C3D9175B6FC201293AD****</RequestId>
+</Error>"#,
+        );
+        assert!(!is_restore_in_progress_error(&bs));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_code() {
+        let cases = vec![
+            (
+                StatusCode::NOT_FOUND,
+                "NoSuchKey",
+                ErrorKind::NotFound,
+                false,
+            ),
+            (
+                StatusCode::NOT_FOUND,
+                "NoSuchBucket",
+                ErrorKind::ConfigInvalid,
+                false,
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "SignatureDoesNotMatch",
+                ErrorKind::PermissionDenied,
+                false,
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "RequestTimeTooSkewed",
+                ErrorKind::Unexpected,
+                true,
+            ),
+            (
+                StatusCode::CONFLICT,
+                "PositionNotEqualToLength",
+                ErrorKind::ConditionNotMatch,
+                false,
+            ),
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SlowDown",
+                ErrorKind::RateLimited,
+                true,
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "InvalidObjectState",
+                ErrorKind::Archived,
+                false,
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "QuotaExceeded",
+                ErrorKind::InsufficientStorage,
+                false,
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                "InvalidObjectName",
+                ErrorKind::Unexpected,
+                false,
+            ),
+        ];
+
+        for (status, code, kind, temporary) in cases {
+            let bs = Bytes::from(format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>
+    {code}
+  </Code>
+  <Message>
+    test message
+  </Message>
+  <RequestId>
+    5C3D9175B6FC201293AD****
+  </RequestId>
+</Error>"#
+            ));
+            let resp = Response::builder()
+                .status(status)
+                .body(IncomingAsyncBody::new(
+                    Box::new(stream::iter(vec![Ok(bs)])),
+                    None,
+                ))
+                .expect("must success");
+
+            let err = parse_error(resp).await.expect("must success");
+            assert_eq!(err.kind(), kind, "{code}");
+            assert_eq!(err.is_temporary(), temporary, "{code}");
+
+            let msg = err.to_string();
+            assert!(msg.contains("test message"), "{msg}");
+            assert!(msg.contains(&format!("code: {code}")), "{msg}");
+            assert!(
+                msg.contains("request_id: 5C3D9175B6FC201293AD****"),
+                "{msg}"
+            );
+            assert_eq!(err.request_id(), Some("5C3D9175B6FC201293AD****"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_request_id_from_header() {
+        // Response of HEAD doesn't have body.
+        let resp = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(X_OSS_REQUEST_ID, "534B371674E88A4D8906****")
+            .body(IncomingAsyncBody::new(Box::new(stream::empty()), None))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.request_id(), Some("534B371674E88A4D8906****"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_retry_after() {
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(60))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let cases = vec![("5".to_string(), 5..=5), (future, 58..=60)];
+
+        for (retry_after, expected) in cases {
+            let resp = Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(http::header::RETRY_AFTER, &retry_after)
+                .body(IncomingAsyncBody::new(Box::new(stream::empty()), None))
+                .expect("must success");
+
+            let err = parse_error(resp).await.expect("must success");
+            assert!(err.is_temporary());
+            let secs = err.retry_after().expect("must be set").as_secs();
+            assert!(expected.contains(&secs), "{retry_after}: {secs}");
+        }
+    }
 }
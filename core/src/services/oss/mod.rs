@@ -18,6 +18,9 @@
 mod backend;
 pub use backend::OssBuilder as Oss;
 
+mod credential;
+pub use credential::OssCredentialLoad;
+
 mod core;
 mod error;
 mod pager;
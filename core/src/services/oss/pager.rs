@@ -106,6 +106,9 @@ impl oio::Page for OssPager {
             meta.set_etag(&object.etag);
             meta.set_content_length(object.size);
             meta.set_last_modified(parse_datetime_from_rfc3339(object.last_modified.as_str())?);
+            if object.object_type == "Symlink" {
+                meta.set_is_symlink(true);
+            }
 
             let rel = build_rel_path(&self.core.root, &object.key);
             let path = unescape(&rel)
@@ -140,6 +143,9 @@ struct Content {
     #[serde(rename = "ETag")]
     etag: String,
     size: u64,
+    /// Type of object, like `Normal`, `Appendable`, `Multipart` and `Symlink`.
+    #[serde(rename = "Type")]
+    object_type: String,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -191,6 +197,7 @@ mod tests {
         <LastModified>2020-05-18T05:45:59.000Z</LastModified>
         <ETag>"35A27C2B9EAEEB6F48FD7FB5861D****"</ETag>
         <Size>25</Size>
+        <Type>Symlink</Type>
         <StorageClass>STANDARD</StorageClass>
         <Owner>
             <ID>1686240967192623</ID>
@@ -215,18 +222,21 @@ mod tests {
                     last_modified: "2020-05-18T05:45:54.000Z".to_string(),
                     etag: "\"35A27C2B9EAEEB6F48FD7FB5861D****\"".to_string(),
                     size: 25,
+                    object_type: "".to_string(),
                 },
                 Content {
                     key: "ba".to_string(),
                     last_modified: "2020-05-18T11:17:58.000Z".to_string(),
                     etag: "\"35A27C2B9EAEEB6F48FD7FB5861D****\"".to_string(),
                     size: 25,
+                    object_type: "".to_string(),
                 },
                 Content {
                     key: "bc".to_string(),
                     last_modified: "2020-05-18T05:45:59.000Z".to_string(),
                     etag: "\"35A27C2B9EAEEB6F48FD7FB5861D****\"".to_string(),
                     size: 25,
+                    object_type: "Symlink".to_string(),
                 }
             ]
         )
@@ -62,6 +62,8 @@ impl OssWriter {
             self.op.content_type(),
             self.op.content_disposition(),
             self.op.cache_control(),
+            self.op.expires(),
+            self.op.acl(),
             AsyncBody::Bytes(bs),
             false,
         )?;
@@ -133,30 +135,63 @@ impl OssWriter {
 #[async_trait]
 impl oio::Write for OssWriter {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        let upload_id = match &self.upload_id {
-            Some(upload_id) => upload_id,
-            None => {
-                if self.op.content_length().unwrap_or_default() == bs.len() as u64 {
-                    return self.write_oneshot(bs).await;
-                } else {
-                    let upload_id = self.initiate_upload().await?;
-                    self.upload_id = Some(upload_id);
-                    self.upload_id.as_deref().unwrap()
+        if self.upload_id.is_none() {
+            let content_length = self.op.content_length();
+            // Write the whole object in one request.
+            if self.buffer.is_empty() && content_length.unwrap_or_default() == bs.len() as u64 {
+                return self.write_oneshot(bs).await;
+            }
+
+            self.buffer.push(bs);
+
+            // Objects that fit in one part will be written in one request
+            // instead of initiate + upload part + complete.
+            let threshold = self.buffer_size as u64;
+            match content_length {
+                Some(size) if size <= threshold => {
+                    if (self.buffer.len() as u64) < size {
+                        return Ok(());
+                    }
+
+                    return match self.write_oneshot(self.buffer.peak_all()).await {
+                        Ok(_) => {
+                            self.buffer.clear();
+                            Ok(())
+                        }
+                        Err(e) => {
+                            self.buffer.pop();
+                            Err(e)
+                        }
+                    };
                 }
+                // Keep buffering until the data exceeds one part.
+                _ if self.buffer.len() as u64 <= threshold => return Ok(()),
+                _ => {}
             }
-        };
 
-        // Ignore empty bytes
-        if bs.is_empty() {
-            return Ok(());
-        }
+            match self.initiate_upload().await {
+                Ok(upload_id) => self.upload_id = Some(upload_id),
+                Err(e) => {
+                    // Make sure write is re-enter safe.
+                    self.buffer.pop();
+                    return Err(e);
+                }
+            }
+        } else {
+            // Ignore empty bytes
+            if bs.is_empty() {
+                return Ok(());
+            }
 
-        self.buffer.push(bs);
-        // Return directly if the buffer is not full
-        if self.buffer.len() <= self.buffer_size {
-            return Ok(());
+            self.buffer.push(bs);
+            // Return directly if the buffer is not full
+            if self.buffer.len() <= self.buffer_size {
+                return Ok(());
+            }
         }
 
+        let upload_id = self.upload_id.as_deref().expect("upload id must be valid");
+
         let bs = self.buffer.peak_at_least(self.buffer_size);
         let size = bs.len();
 
@@ -187,6 +222,11 @@ impl oio::Write for OssWriter {
         let upload_id = if let Some(upload_id) = &self.upload_id {
             upload_id
         } else {
+            // Data that fits in one part is still buffered.
+            if !self.buffer.is_empty() {
+                self.write_oneshot(self.buffer.peak_all()).await?;
+                self.buffer.clear();
+            }
             return Ok(());
         };
 
@@ -22,14 +22,27 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use bb8::RunError;
 use http::Uri;
-use redis::aio::ConnectionManager;
+use redis::aio::ConnectionLike;
+use redis::aio::MultiplexedConnection;
+use redis::cluster::ClusterClient;
+use redis::cluster::ClusterClientBuilder;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::Sentinel;
+use redis::sentinel::SentinelNodeConnectionInfo;
 use redis::AsyncCommands;
 use redis::Client;
+use redis::Cmd;
 use redis::ConnectionAddr;
 use redis::ConnectionInfo;
+use redis::Pipeline;
 use redis::RedisConnectionInfo;
 use redis::RedisError;
+use redis::RedisFuture;
+use redis::TlsCertificates;
+use redis::TlsMode;
+use redis::Value;
 use tokio::sync::OnceCell;
 
 use crate::raw::adapters::kv;
@@ -61,9 +74,16 @@ const DEFAULT_REDIS_PORT: u16 = 6379;
 ///
 /// - `root`: Set the working directory of `OpenDAL`
 /// - `endpoint`: Set the network address of redis server
+/// - `cluster_endpoints`: Set the seed endpoints of redis cluster, separated by `,`
+/// - `sentinel_endpoints`: Set the endpoints of redis sentinels, separated by `,`
+/// - `master_name`: Set the master name monitored by redis sentinels
 /// - `username`: Set the username of Redis
 /// - `password`: Set the password for authentication
 /// - `db`: Set the DB of redis
+/// - `tls_ca_cert`: Set the path of custom CA certificate for `rediss://` endpoints
+/// - `connection_pool_max_size`: Set the max size of connection pool
+/// - `connection_timeout`: Set the timeout in seconds of getting a connection
+/// - `idle_timeout`: Set the timeout in seconds of idle connections in pool
 ///
 /// You can refer to [`RedisBuilder`]'s docs for more information
 ///
@@ -111,6 +131,26 @@ pub struct RedisBuilder {
     db: i64,
     /// The default ttl for put operations.
     default_ttl: Option<Duration>,
+    /// the seed endpoints of redis cluster.
+    ///
+    /// default is None, which means cluster mode is disabled.
+    cluster_endpoints: Option<String>,
+    /// the endpoints of redis sentinels.
+    ///
+    /// default is None, which means sentinel mode is disabled.
+    sentinel_endpoints: Option<String>,
+    /// the master name monitored by redis sentinels.
+    master_name: Option<String>,
+    /// the path of custom CA certificate in PEM format.
+    ///
+    /// default is None, which means using the system roots.
+    tls_ca_cert: Option<String>,
+    /// the max size of connection pool.
+    connection_pool_max_size: Option<u32>,
+    /// the timeout of getting a connection from pool.
+    connection_timeout: Option<Duration>,
+    /// the timeout of idle connections in pool.
+    idle_timeout: Option<Duration>,
 }
 
 impl Debug for RedisBuilder {
@@ -127,6 +167,15 @@ impl Debug for RedisBuilder {
         if self.password.is_some() {
             ds.field("password", &"<redacted>");
         }
+        if let Some(endpoints) = self.cluster_endpoints.clone() {
+            ds.field("cluster_endpoints", &endpoints);
+        }
+        if let Some(endpoints) = self.sentinel_endpoints.clone() {
+            ds.field("sentinel_endpoints", &endpoints);
+        }
+        if let Some(master_name) = self.master_name.clone() {
+            ds.field("master_name", &master_name);
+        }
         ds.finish()
     }
 }
@@ -137,6 +186,7 @@ impl RedisBuilder {
     /// currently supported schemes:
     /// - no scheme: will be seen as "tcp"
     /// - "tcp" or "redis": unsecured redis connections
+    /// - "rediss": secured redis connections over TLS
     /// - "unix" or "redis+unix": unix socket connection
     pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
         if !endpoint.is_empty() {
@@ -190,79 +240,259 @@ impl RedisBuilder {
         }
         self
     }
+
+    /// set the seed endpoints of redis cluster, separated by `,`.
+    ///
+    /// For example: `redis://127.0.0.1:7000,redis://127.0.0.1:7001`
+    ///
+    /// If set, cluster mode will be enabled and `MOVED` / `ASK` redirects
+    /// will be followed by the cluster client. `endpoint` will be ignored.
+    pub fn cluster_endpoints(&mut self, endpoints: &str) -> &mut Self {
+        if !endpoints.is_empty() {
+            self.cluster_endpoints = Some(endpoints.to_owned());
+        }
+        self
+    }
+
+    /// set the endpoints of redis sentinels, separated by `,`.
+    ///
+    /// If set, the master will be discovered by sentinels with
+    /// [`RedisBuilder::master_name`]. `endpoint` will be ignored.
+    pub fn sentinel_endpoints(&mut self, endpoints: &str) -> &mut Self {
+        if !endpoints.is_empty() {
+            self.sentinel_endpoints = Some(endpoints.to_owned());
+        }
+        self
+    }
+
+    /// set the master name monitored by redis sentinels.
+    pub fn master_name(&mut self, master_name: &str) -> &mut Self {
+        if !master_name.is_empty() {
+            self.master_name = Some(master_name.to_owned());
+        }
+        self
+    }
+
+    /// set the path of custom CA certificate in PEM format for `rediss://`
+    /// endpoints.
+    ///
+    /// default: use the system roots
+    pub fn tls_ca_cert(&mut self, path: &str) -> &mut Self {
+        if !path.is_empty() {
+            self.tls_ca_cert = Some(path.to_owned());
+        }
+        self
+    }
+
+    /// set the max size of connection pool.
+    ///
+    /// default: 10
+    pub fn connection_pool_max_size(&mut self, max_size: u32) -> &mut Self {
+        self.connection_pool_max_size = Some(max_size);
+        self
+    }
+
+    /// set the timeout of getting a connection from pool.
+    ///
+    /// default: 30s
+    pub fn connection_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// set the timeout of idle connections in pool.
+    ///
+    /// default: 10 minutes
+    pub fn idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
 }
 
 impl Builder for RedisBuilder {
     const SCHEME: Scheme = Scheme::Redis;
     type Accessor = RedisBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "endpoint",
+        "cluster_endpoints",
+        "sentinel_endpoints",
+        "master_name",
+        "username",
+        "password",
+        "db",
+        "tls_ca_cert",
+        "connection_pool_max_size",
+        "connection_timeout",
+        "idle_timeout",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = RedisBuilder::default();
 
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("cluster_endpoints")
+            .map(|v| builder.cluster_endpoints(v));
+        map.get("sentinel_endpoints")
+            .map(|v| builder.sentinel_endpoints(v));
+        map.get("master_name").map(|v| builder.master_name(v));
         map.get("username").map(|v| builder.username(v));
         map.get("password").map(|v| builder.password(v));
         map.get("db")
             .map(|v| v.parse::<i64>().map(|v| builder.db(v)));
+        map.get("tls_ca_cert").map(|v| builder.tls_ca_cert(v));
+        map.get("connection_pool_max_size")
+            .and_then(|v| v.parse::<u32>().ok())
+            .map(|v| builder.connection_pool_max_size(v));
+        map.get("connection_timeout")
+            .and_then(|v| v.parse().ok())
+            .map(|v| builder.connection_timeout(Duration::from_secs(v)));
+        map.get("idle_timeout")
+            .and_then(|v| v.parse().ok())
+            .map(|v| builder.idle_timeout(Duration::from_secs(v)));
 
         builder
     }
 
     fn build(&mut self) -> Result<Self::Accessor> {
-        let endpoint = self
-            .endpoint
-            .clone()
-            .unwrap_or_else(|| DEFAULT_REDIS_ENDPOINT.to_string());
-
-        let ep_url = endpoint.parse::<Uri>().map_err(|e| {
-            Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
-                .with_context("service", Scheme::Redis)
-                .with_context("endpoint", endpoint)
-                .set_source(e)
-        })?;
-
-        let con_addr = match ep_url.scheme_str() {
-            Some("tcp") | Some("redis") | None => {
-                let host = ep_url
-                    .host()
-                    .map(|h| h.to_string())
-                    .unwrap_or_else(|| "127.0.0.1".to_string());
-                let port = ep_url.port_u16().unwrap_or(DEFAULT_REDIS_PORT);
-                ConnectionAddr::Tcp(host, port)
-            }
-            // TODO: wait for upstream to support `rustls` based TLS connection.
-            Some("unix") | Some("redis+unix") => {
-                let path = PathBuf::from(ep_url.path());
-                ConnectionAddr::Unix(path)
-            }
-            Some(s) => {
-                return Err(
-                    Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
-                        .with_context("service", Scheme::Redis)
-                        .with_context("scheme", s),
-                )
-            }
-        };
-
         let redis_info = RedisConnectionInfo {
             db: self.db,
             username: self.username.clone(),
             password: self.password.clone(),
         };
 
-        let con_info = ConnectionInfo {
-            addr: con_addr,
-            redis: redis_info,
+        let root_cert = match &self.tls_ca_cert {
+            Some(path) => Some(std::fs::read(path).map_err(|e| {
+                Error::new(ErrorKind::ConfigInvalid, "tls ca cert is invalid")
+                    .with_context("service", Scheme::Redis)
+                    .with_context("tls_ca_cert", path)
+                    .set_source(e)
+            })?),
+            None => None,
         };
 
-        let client = Client::open(con_info).map_err(|e| {
-            Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
-                .with_context("service", Scheme::Redis)
-                .with_context("endpoint", self.endpoint.as_ref().unwrap())
-                .with_context("db", self.db.to_string())
-                .set_source(e)
-        })?;
+        let (endpoint, client) = match (&self.cluster_endpoints, &self.sentinel_endpoints) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "cluster_endpoints and sentinel_endpoints can't be set at the same time",
+                )
+                .with_context("service", Scheme::Redis))
+            }
+            (Some(endpoints), None) => {
+                // Redis cluster only supports db 0.
+                if self.db != 0 {
+                    return Err(Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "db is not supported in cluster mode",
+                    )
+                    .with_context("service", Scheme::Redis)
+                    .with_context("db", self.db.to_string()));
+                }
+
+                let nodes = parse_endpoints(endpoints)?
+                    .into_iter()
+                    .map(|addr| ConnectionInfo {
+                        addr,
+                        redis: redis_info.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                let is_tls = nodes
+                    .iter()
+                    .any(|v| matches!(v.addr, ConnectionAddr::TcpTls { .. }));
+
+                let mut builder = ClusterClientBuilder::new(nodes);
+                if is_tls {
+                    builder = builder.tls(TlsMode::Secure);
+                }
+                if let Some(root_cert) = root_cert {
+                    builder = builder.certs(TlsCertificates {
+                        client_tls: None,
+                        root_cert: Some(root_cert),
+                    });
+                }
+                let client = builder.build().map_err(|e| {
+                    Error::new(ErrorKind::ConfigInvalid, "invalid cluster endpoints")
+                        .with_context("service", Scheme::Redis)
+                        .with_context("cluster_endpoints", endpoints)
+                        .set_source(e)
+                })?;
+
+                (endpoints.clone(), RedisClient::Cluster(client))
+            }
+            (None, Some(endpoints)) => {
+                let master_name = self.master_name.clone().ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "master_name is required in sentinel mode",
+                    )
+                    .with_context("service", Scheme::Redis)
+                })?;
+                if root_cert.is_some() {
+                    return Err(Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "tls_ca_cert is not supported in sentinel mode",
+                    )
+                    .with_context("service", Scheme::Redis));
+                }
+
+                let sentinels = parse_endpoints(endpoints)?
+                    .into_iter()
+                    .map(|addr| ConnectionInfo {
+                        addr,
+                        redis: RedisConnectionInfo::default(),
+                    })
+                    .collect::<Vec<_>>();
+                let tls_mode = sentinels
+                    .iter()
+                    .any(|v| matches!(v.addr, ConnectionAddr::TcpTls { .. }))
+                    .then_some(TlsMode::Secure);
+
+                (
+                    endpoints.clone(),
+                    RedisClient::Sentinel {
+                        sentinels,
+                        master_name,
+                        node_info: SentinelNodeConnectionInfo {
+                            tls_mode,
+                            redis_connection_info: Some(redis_info),
+                        },
+                    },
+                )
+            }
+            (None, None) => {
+                let endpoint = self
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_REDIS_ENDPOINT.to_string());
+
+                let con_info = ConnectionInfo {
+                    addr: parse_connection_addr(&endpoint)?,
+                    redis: redis_info,
+                };
+
+                let client = match root_cert {
+                    Some(root_cert) => Client::build_with_tls(
+                        con_info,
+                        TlsCertificates {
+                            client_tls: None,
+                            root_cert: Some(root_cert),
+                        },
+                    ),
+                    None => Client::open(con_info),
+                }
+                .map_err(|e| {
+                    Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
+                        .with_context("service", Scheme::Redis)
+                        .with_context("endpoint", &endpoint)
+                        .with_context("db", self.db.to_string())
+                        .set_source(e)
+                })?;
+
+                (endpoint, RedisClient::Single(client))
+            }
+        };
 
         let root = normalize_root(
             self.root
@@ -273,22 +503,84 @@ impl Builder for RedisBuilder {
 
         let conn = OnceCell::new();
         Ok(RedisBackend::new(Adapter {
+            endpoint,
             client,
             conn,
+            connection_pool_max_size: self.connection_pool_max_size,
+            connection_timeout: self.connection_timeout,
+            idle_timeout: self.idle_timeout,
             default_ttl: self.default_ttl,
         })
         .with_root(&root))
     }
 }
 
+/// Parse endpoints separated by `,` into connection addresses.
+fn parse_endpoints(endpoints: &str) -> Result<Vec<ConnectionAddr>> {
+    let addrs = endpoints
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(parse_connection_addr)
+        .collect::<Result<Vec<_>>>()?;
+
+    if addrs.is_empty() {
+        return Err(Error::new(ErrorKind::ConfigInvalid, "endpoints are empty")
+            .with_context("service", Scheme::Redis)
+            .with_context("endpoints", endpoints));
+    }
+
+    Ok(addrs)
+}
+
+fn parse_connection_addr(endpoint: &str) -> Result<ConnectionAddr> {
+    let ep_url = endpoint.parse::<Uri>().map_err(|e| {
+        Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
+            .with_context("service", Scheme::Redis)
+            .with_context("endpoint", endpoint)
+            .set_source(e)
+    })?;
+
+    let host = || {
+        ep_url
+            .host()
+            .map(|h| h.to_string())
+            .unwrap_or_else(|| "127.0.0.1".to_string())
+    };
+    let port = ep_url.port_u16().unwrap_or(DEFAULT_REDIS_PORT);
+
+    match ep_url.scheme_str() {
+        Some("tcp") | Some("redis") | None => Ok(ConnectionAddr::Tcp(host(), port)),
+        Some("rediss") => Ok(ConnectionAddr::TcpTls {
+            host: host(),
+            port,
+            insecure: false,
+            tls_params: None,
+        }),
+        Some("unix") | Some("redis+unix") => {
+            let path = PathBuf::from(ep_url.path());
+            Ok(ConnectionAddr::Unix(path))
+        }
+        Some(s) => Err(
+            Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
+                .with_context("service", Scheme::Redis)
+                .with_context("scheme", s),
+        ),
+    }
+}
+
 /// Backend for redis services.
 pub type RedisBackend = kv::Backend<Adapter>;
 
 #[derive(Clone)]
 pub struct Adapter {
-    client: Client,
-    conn: OnceCell<ConnectionManager>,
+    endpoint: String,
+    client: RedisClient,
+    conn: OnceCell<bb8::Pool<RedisConnectionManager>>,
 
+    connection_pool_max_size: Option<u32>,
+    connection_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
     default_ttl: Option<Duration>,
 }
 
@@ -297,21 +589,47 @@ impl Debug for Adapter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut ds = f.debug_struct("Adapter");
 
-        let info = self.client.get_connection_info();
-        ds.field("addr", &info.addr);
-        ds.field("db", &info.redis.db);
-        ds.field("user", &info.redis.username);
+        ds.field("endpoint", &self.endpoint);
+        ds.field("client", &self.client);
+        ds.field("connection_pool_max_size", &self.connection_pool_max_size);
+        ds.field("connection_timeout", &self.connection_timeout);
+        ds.field("idle_timeout", &self.idle_timeout);
         ds.finish()
     }
 }
 
 impl Adapter {
-    async fn conn(&self) -> Result<ConnectionManager> {
-        Ok(self
+    async fn conn(&self) -> Result<bb8::PooledConnection<'_, RedisConnectionManager>> {
+        let pool = self
             .conn
-            .get_or_try_init(|| async { ConnectionManager::new(self.client.clone()).await })
-            .await?
-            .clone())
+            .get_or_try_init(|| async {
+                let mgr = RedisConnectionManager {
+                    client: self.client.clone(),
+                };
+
+                let mut builder = bb8::Pool::builder();
+                if let Some(v) = self.connection_pool_max_size {
+                    builder = builder.max_size(v);
+                }
+                if let Some(v) = self.connection_timeout {
+                    builder = builder.connection_timeout(v);
+                }
+                if let Some(v) = self.idle_timeout {
+                    builder = builder.idle_timeout(Some(v));
+                }
+
+                builder.build(mgr).await.map_err(|err| {
+                    Error::new(ErrorKind::ConfigInvalid, "connect to redis failed").set_source(err)
+                })
+            })
+            .await?;
+
+        pool.get().await.map_err(|err| match err {
+            RunError::TimedOut => {
+                Error::new(ErrorKind::Unexpected, "get connection from pool failed").set_temporary()
+            }
+            RunError::User(err) => err,
+        })
     }
 }
 
@@ -320,7 +638,7 @@ impl kv::Adapter for Adapter {
     fn metadata(&self) -> kv::Metadata {
         kv::Metadata::new(
             Scheme::Redis,
-            &self.client.get_connection_info().addr.to_string(),
+            &self.endpoint,
             Capability {
                 read: true,
                 write: true,
@@ -340,7 +658,7 @@ impl kv::Adapter for Adapter {
     async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
         let mut conn = self.conn().await?;
         match self.default_ttl {
-            Some(ttl) => conn.set_ex(key, value, ttl.as_secs() as usize).await?,
+            Some(ttl) => conn.set_ex(key, value, ttl.as_secs()).await?,
             None => conn.set(key, value).await?,
         }
         Ok(())
@@ -359,6 +677,128 @@ impl kv::Adapter for Adapter {
     }
 }
 
+/// The client used to build connections to redis.
+#[derive(Clone)]
+enum RedisClient {
+    Single(Client),
+    Cluster(ClusterClient),
+    Sentinel {
+        sentinels: Vec<ConnectionInfo>,
+        master_name: String,
+        node_info: SentinelNodeConnectionInfo,
+    },
+}
+
+// implement `Debug` manually, or password may be leaked.
+impl Debug for RedisClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedisClient::Single(client) => {
+                let info = client.get_connection_info();
+                f.debug_struct("Single")
+                    .field("addr", &info.addr)
+                    .field("db", &info.redis.db)
+                    .field("user", &info.redis.username)
+                    .finish()
+            }
+            RedisClient::Cluster(_) => f.debug_struct("Cluster").finish(),
+            RedisClient::Sentinel {
+                sentinels,
+                master_name,
+                ..
+            } => f
+                .debug_struct("Sentinel")
+                .field(
+                    "sentinels",
+                    &sentinels.iter().map(|v| &v.addr).collect::<Vec<_>>(),
+                )
+                .field("master_name", master_name)
+                .finish(),
+        }
+    }
+}
+
+/// A connection to a single redis node or a redis cluster.
+enum RedisConnection {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// A `bb8::ManageConnection` for redis.
+#[derive(Clone, Debug)]
+struct RedisConnectionManager {
+    client: RedisClient,
+}
+
+#[async_trait]
+impl bb8::ManageConnection for RedisConnectionManager {
+    type Connection = RedisConnection;
+    type Error = Error;
+
+    async fn connect(&self) -> std::result::Result<Self::Connection, Self::Error> {
+        match &self.client {
+            RedisClient::Single(client) => Ok(RedisConnection::Single(
+                client.get_multiplexed_tokio_connection().await?,
+            )),
+            RedisClient::Cluster(client) => Ok(RedisConnection::Cluster(
+                client.get_async_connection().await?,
+            )),
+            // Discover the master for every new connection so that we can
+            // follow the failover.
+            RedisClient::Sentinel {
+                sentinels,
+                master_name,
+                node_info,
+            } => {
+                let mut sentinel = Sentinel::build(sentinels.clone())?;
+                let client = sentinel
+                    .async_master_for(master_name, Some(node_info))
+                    .await?;
+                Ok(RedisConnection::Single(
+                    client.get_multiplexed_tokio_connection().await?,
+                ))
+            }
+        }
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> std::result::Result<(), Self::Error> {
+        let _: String = redis::cmd("PING").query_async(conn).await?;
+        Ok(())
+    }
+
+    fn has_broken(&self, _: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
 impl From<RedisError> for Error {
     fn from(e: RedisError) -> Self {
         Error::new(ErrorKind::Unexpected, e.category())
@@ -366,3 +806,65 @@ impl From<RedisError> for Error {
             .set_temporary()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_connection_addr() {
+        let cases = vec![
+            (
+                "127.0.0.1:6379",
+                ConnectionAddr::Tcp("127.0.0.1".to_string(), 6379),
+            ),
+            (
+                "redis://127.0.0.1",
+                ConnectionAddr::Tcp("127.0.0.1".to_string(), 6379),
+            ),
+            (
+                "rediss://redis.example.com:6380",
+                ConnectionAddr::TcpTls {
+                    host: "redis.example.com".to_string(),
+                    port: 6380,
+                    insecure: false,
+                    tls_params: None,
+                },
+            ),
+        ];
+
+        for (endpoint, expected) in cases {
+            let addr = parse_connection_addr(endpoint).expect("must succeed");
+            assert_eq!(addr, expected, "{endpoint}");
+        }
+
+        assert!(parse_connection_addr("http://127.0.0.1:6379").is_err());
+    }
+
+    #[test]
+    fn test_build_with_invalid_mode() {
+        let mut builder = RedisBuilder::default();
+        builder
+            .cluster_endpoints("redis://127.0.0.1:7000")
+            .sentinel_endpoints("redis://127.0.0.1:26379");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        let mut builder = RedisBuilder::default();
+        builder.sentinel_endpoints("redis://127.0.0.1:26379");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        let mut builder = RedisBuilder::default();
+        builder.cluster_endpoints("redis://127.0.0.1:7000").db(1);
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_build_cluster() {
+        let mut builder = RedisBuilder::default();
+        builder.cluster_endpoints("redis://127.0.0.1:7000, redis://127.0.0.1:7001");
+        builder.build().expect("build must succeed");
+    }
+}
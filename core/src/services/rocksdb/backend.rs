@@ -117,6 +117,7 @@ impl RocksdbBuilder {
 impl Builder for RocksdbBuilder {
     const SCHEME: Scheme = Scheme::Rocksdb;
     type Accessor = RocksdbBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["datadir"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = RocksdbBuilder::default();
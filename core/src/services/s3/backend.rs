@@ -19,12 +19,19 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use bytes::Buf;
+use bytes::Bytes;
+use bytes::BytesMut;
+use http::HeaderValue;
 use http::StatusCode;
 use log::debug;
 use md5::Digest;
@@ -36,8 +43,13 @@ use reqsign::AwsLoader;
 use reqsign::AwsV4Signer;
 
 use super::core::*;
+use super::credential::ProfileLoader;
+use super::error::is_object_lock_error;
+use super::error::is_restore_in_progress_error;
 use super::error::parse_error;
+use super::error::parse_error_from_parts;
 use super::error::parse_s3_error_code;
+use super::error::OBJECT_LOCK_ERROR_MESSAGE;
 use super::pager::S3Pager;
 use super::writer::S3Writer;
 use crate::ops::*;
@@ -56,6 +68,14 @@ static ENDPOINT_TEMPLATES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new
 });
 
 const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
+/// The minimum part size of s3 multipart upload, except the last part.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+/// The maximum size of a part and a single PUT request.
+const MAX_PART_SIZE: usize = 5 * 1024 * 1024 * 1024;
+/// The maximum size of GetObjectAttributes response we will read, larger
+/// responses are likely the content of object returned by services that
+/// ignore the `attributes` query.
+const MAX_GET_OBJECT_ATTRIBUTES_SIZE: usize = 1024 * 1024;
 /// Aws S3 and compatible services (including minio, digitalocean space, Tencent Cloud Object Storage(COS) and so on) support.
 /// For more information about s3-compatible services, refer to [Compatible Services](#compatible-services).
 ///
@@ -73,6 +93,7 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 /// - [x] list
 /// - [x] scan
 /// - [x] presign
+/// - [x] get_acl
 /// - [ ] blocking
 ///
 /// # Configuration
@@ -81,10 +102,15 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 /// - `bucket`: Set the container name for backend.
 /// - `endpoint`: Set the endpoint for backend.
 /// - `region`: Set the region for backend.
+/// - `signing_region`: Override the region used in SigV4 signing.
+/// - `signing_service`: Override the service name used in SigV4 signing.
 /// - `access_key_id`: Set the access_key_id for backend.
 /// - `secret_access_key`: Set the secret_access_key for backend.
 /// - `security_token`: Set the security_token for backend.
 /// - `default_storage_class`: Set the default storage_class for backend.
+/// - `default_object_lock_mode`: Set the default object lock mode for backend.
+/// - `default_object_lock_retain_period`: Set the default object lock retain period in seconds for backend.
+/// - `default_object_lock_legal_hold`: Place a legal hold on written objects.
 /// - `server_side_encryption`: Set the server_side_encryption for backend.
 /// - `server_side_encryption_aws_kms_key_id`: Set the server_side_encryption_aws_kms_key_id for backend.
 /// - `server_side_encryption_customer_algorithm`: Set the server_side_encryption_customer_algorithm for backend.
@@ -92,6 +118,17 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 /// - `server_side_encryption_customer_key_md5`: Set the server_side_encryption_customer_key_md5 for backend.
 /// - `disable_config_load`: Disable aws config load from env
 /// - `enable_virtual_host_style`: Enable virtual host style.
+/// - `allow_anonymous`: Allow sending unsigned requests when credential is not loaded.
+/// - `disable_credential_loader`: Skip credential loading and send all requests unsigned.
+/// - `write_min_size`: Set the part size of multipart upload.
+/// - `write_multipart_threshold`: Set the max size that will be written in a single PUT.
+/// - `write_max_concurrent_parts`: Set the max number of parts uploaded concurrently.
+/// - `enable_accelerate`: Enable S3 Transfer Acceleration.
+/// - `enable_dualstack`: Enable dual-stack (IPv4 and IPv6) endpoint.
+/// - `enable_list_objects_v1`: Use ListObjects (v1) instead of ListObjectsV2 for listing.
+/// - `enable_get_object_attributes`: Use GetObjectAttributes instead of HeadObject for stat.
+/// - `enable_payload_signing`: Sign the payload hash instead of sending `UNSIGNED-PAYLOAD` while uploading.
+/// - `enable_write_with_if_none_match`: Send `If-None-Match` on writes to custom endpoints.
 ///
 /// Refer to [`S3Builder`]'s public API docs for more information.
 ///
@@ -103,6 +140,12 @@ const DEFAULT_WRITE_MIN_SIZE: usize = 8 * 1024 * 1024;
 ///
 /// But OpenDAL will not refresh the temporary security credentials, please keep in mind to refresh those credentials in time.
 ///
+/// Temporary credentials returned by `credential_process` or the SSO token cache (`aws sso login`)
+/// configured in the profile of aws shared config file will be refreshed automatically. They will be
+/// reloaded once expired or rejected by S3 with `ExpiredToken`, and concurrent requests will share
+/// the same refresh. If they can't be loaded, for example, the SSO token has been expired, the
+/// default credential chain will be used instead.
+///
 /// # Server Side Encryption
 ///
 /// OpenDAL provides full support of S3 Server Side Encryption(SSE) features.
@@ -290,6 +333,8 @@ pub struct S3Builder {
     bucket: String,
     endpoint: Option<String>,
     region: Option<String>,
+    signing_region: Option<String>,
+    signing_service: Option<String>,
     role_arn: Option<String>,
     external_id: Option<String>,
     access_key_id: Option<String>,
@@ -300,6 +345,9 @@ pub struct S3Builder {
     server_side_encryption_customer_key: Option<String>,
     server_side_encryption_customer_key_md5: Option<String>,
     default_storage_class: Option<String>,
+    default_object_lock_mode: Option<String>,
+    default_object_lock_retain_period: Option<Duration>,
+    default_object_lock_legal_hold: bool,
 
     /// temporary credentials, check the official [doc](https://docs.aws.amazon.com/IAM/latest/UserGuide/id_credentials_temp.html) for detail
     security_token: Option<String>,
@@ -307,7 +355,14 @@ pub struct S3Builder {
     disable_config_load: bool,
     disable_ec2_metadata: bool,
     allow_anonymous: bool,
+    disable_credential_loader: bool,
     enable_virtual_host_style: bool,
+    enable_accelerate: bool,
+    enable_dualstack: bool,
+    enable_list_objects_v1: bool,
+    enable_get_object_attributes: bool,
+    enable_payload_signing: bool,
+    enable_write_with_if_none_match: bool,
 
     http_client: Option<HttpClient>,
     customed_credential_load: Option<Box<dyn AwsCredentialLoad>>,
@@ -315,6 +370,13 @@ pub struct S3Builder {
     /// the part size of s3 multipart upload, which should be 5 MiB to 5 GiB.
     /// There is no minimum size limit on the last part of your multipart upload
     write_min_size: Option<usize>,
+    /// the max size that will be written in a single PUT request.
+    write_multipart_threshold: Option<usize>,
+    /// the max number of parts that can be uploaded concurrently.
+    write_max_concurrent_parts: Option<usize>,
+
+    /// the first invalid value met in `from_map`, returned by `build`.
+    config_error: Option<Error>,
 }
 
 impl Debug for S3Builder {
@@ -324,7 +386,9 @@ impl Debug for S3Builder {
         d.field("root", &self.root)
             .field("bucket", &self.bucket)
             .field("endpoint", &self.endpoint)
-            .field("region", &self.region);
+            .field("region", &self.region)
+            .field("signing_region", &self.signing_region)
+            .field("signing_service", &self.signing_service);
 
         d.finish_non_exhaustive()
     }
@@ -385,6 +449,32 @@ impl S3Builder {
         self
     }
 
+    /// Set the region used in SigV4 signing, independent of the region
+    /// used to build endpoint.
+    ///
+    /// Some S3 compatible services require a specific signing region,
+    /// for example, Cloudflare R2 uses `auto`.
+    ///
+    /// If not set, `region` will be used.
+    pub fn signing_region(&mut self, region: &str) -> &mut Self {
+        if !region.is_empty() {
+            self.signing_region = Some(region.to_string())
+        }
+
+        self
+    }
+
+    /// Set the service name used in SigV4 signing.
+    ///
+    /// If not set, `s3` will be used.
+    pub fn signing_service(&mut self, service: &str) -> &mut Self {
+        if !service.is_empty() {
+            self.signing_service = Some(service.to_string())
+        }
+
+        self
+    }
+
     /// Set access_key_id of this backend.
     ///
     /// - If access_key_id is set, we will take user's input first.
@@ -447,6 +537,43 @@ impl S3Builder {
         self
     }
 
+    /// Set default object lock mode for this backend.
+    ///
+    /// Available values:
+    /// - `GOVERNANCE`
+    /// - `COMPLIANCE`
+    ///
+    /// All objects written by this backend will be locked in this mode,
+    /// [`S3Builder::default_object_lock_retain_period`] must be set too.
+    ///
+    /// # Note
+    ///
+    /// Object lock must be enabled while creating the bucket.
+    pub fn default_object_lock_mode(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.default_object_lock_mode = Some(v.to_uppercase())
+        }
+
+        self
+    }
+
+    /// Set default object lock retain period for this backend.
+    ///
+    /// The `x-amz-object-lock-retain-until-date` of every written object
+    /// will be set to now plus this period.
+    pub fn default_object_lock_retain_period(&mut self, v: Duration) -> &mut Self {
+        self.default_object_lock_retain_period = Some(v);
+
+        self
+    }
+
+    /// Place a legal hold on all objects written by this backend.
+    pub fn default_object_lock_legal_hold(&mut self) -> &mut Self {
+        self.default_object_lock_legal_hold = true;
+
+        self
+    }
+
     /// Set server_side_encryption for this backend.
     ///
     /// Available values: `AES256`, `aws:kms`.
@@ -640,6 +767,21 @@ impl S3Builder {
         self
     }
 
+    /// Disable credential loader so that opendal will never try to load
+    /// credential from env, profile or ec2 metadata and send all requests
+    /// without signing.
+    ///
+    /// This is useful to access public buckets without any credential
+    /// lookup latency.
+    ///
+    /// # Notes
+    ///
+    /// `presign` is not supported in this mode.
+    pub fn disable_credential_loader(&mut self) -> &mut Self {
+        self.disable_credential_loader = true;
+        self
+    }
+
     /// Enable virtual host style so that opendal will send API requests
     /// in virtual host style instead of path style.
     ///
@@ -650,6 +792,88 @@ impl S3Builder {
         self
     }
 
+    /// Enable [Transfer Acceleration](https://docs.aws.amazon.com/AmazonS3/latest/userguide/transfer-acceleration.html)
+    /// so that opendal will send API requests to `https://bucket_name.s3-accelerate.amazonaws.com`.
+    ///
+    /// # Notes
+    ///
+    /// Transfer Acceleration is only available for AWS S3 with virtual
+    /// host style, building will fail if custom endpoint is set or virtual
+    /// host style is not enabled.
+    pub fn enable_accelerate(&mut self) -> &mut Self {
+        self.enable_accelerate = true;
+        self
+    }
+
+    /// Enable [dual-stack endpoints](https://docs.aws.amazon.com/AmazonS3/latest/userguide/dual-stack-endpoints.html)
+    /// so that opendal will send API requests to `https://s3.dualstack.{region}.amazonaws.com`.
+    ///
+    /// # Notes
+    ///
+    /// Dual-stack endpoints are only available for AWS S3, building will fail
+    /// if custom endpoint is set.
+    pub fn enable_dualstack(&mut self) -> &mut Self {
+        self.enable_dualstack = true;
+        self
+    }
+
+    /// Enable list objects v1 so that opendal will list objects via
+    /// `ListObjects` instead of `ListObjectsV2`.
+    ///
+    /// This is useful for s3 compatible services (like old Ceph RGW)
+    /// that don't implement `ListObjectsV2`. OpenDAL will also fallback
+    /// to `ListObjects` automatically if the service returns a v1 response
+    /// or `501 Not Implemented` for `ListObjectsV2`.
+    pub fn enable_list_objects_v1(&mut self) -> &mut Self {
+        self.enable_list_objects_v1 = true;
+        self
+    }
+
+    /// Enable GetObjectAttributes so that `stat` will return the parts count,
+    /// checksums and storage class of objects.
+    ///
+    /// # Notes
+    ///
+    /// - `s3:GetObjectAttributes` permission is required.
+    /// - GetObjectAttributes doesn't return `Content-Type`, `Cache-Control`
+    ///   and `Content-Disposition`, they will be missing in returned metadata.
+    /// - Conditional stat will always use `HeadObject`.
+    /// - OpenDAL will fall back to `HeadObject` if the service doesn't
+    ///   support GetObjectAttributes.
+    pub fn enable_get_object_attributes(&mut self) -> &mut Self {
+        self.enable_get_object_attributes = true;
+        self
+    }
+
+    /// Enable payload signing so that `PutObject` and `UploadPart` will
+    /// send the SHA256 of body in `x-amz-content-sha256` instead of
+    /// `UNSIGNED-PAYLOAD`.
+    ///
+    /// # Notes
+    ///
+    /// By default, opendal sends `UNSIGNED-PAYLOAD`, the body is not covered
+    /// by the signature and its integrity relies on the transport (TLS for
+    /// `https` endpoints). Enabling this option protects the body over plain
+    /// `http` too, at the cost of hashing the whole body before sending it.
+    pub fn enable_payload_signing(&mut self) -> &mut Self {
+        self.enable_payload_signing = true;
+        self
+    }
+
+    /// Enable conditional write with `If-None-Match` for custom endpoints.
+    ///
+    /// # Notes
+    ///
+    /// AWS S3 supports `If-None-Match` on `PutObject` and
+    /// `CompleteMultipartUpload`, so it's always enabled for AWS endpoints.
+    /// Many S3 compatible services ignore this header silently and overwrite
+    /// the existing object, so it's disabled by default for custom endpoints.
+    /// Only enable it if the service is known to honor the header.
+    pub fn enable_write_with_if_none_match(&mut self) -> &mut Self {
+        self.enable_write_with_if_none_match = true;
+        self
+    }
+
     /// Adding a customed credential load for service.
     pub fn customed_credential_load(&mut self, cred: Box<dyn AwsCredentialLoad>) -> &mut Self {
         self.customed_credential_load = Some(cred);
@@ -683,15 +907,9 @@ impl S3Builder {
         true
     }
 
-    /// Build endpoint with given region.
-    fn build_endpoint(&self, region: &str) -> String {
-        let bucket = {
-            debug_assert!(self.is_bucket_valid(), "bucket must be valid");
-
-            self.bucket.as_str()
-        };
-
-        let mut endpoint = match &self.endpoint {
+    /// Returns the endpoint with scheme prefixed and bucket name trimmed.
+    fn normalized_endpoint(&self) -> String {
+        let endpoint = match &self.endpoint {
             Some(endpoint) => {
                 if endpoint.starts_with("http") {
                     endpoint.to_string()
@@ -704,10 +922,151 @@ impl S3Builder {
         };
 
         // If endpoint contains bucket name, we should trim them.
-        endpoint = endpoint.replace(&format!("//{bucket}."), "//");
+        endpoint.replace(&format!("//{}.", self.bucket), "//")
+    }
+
+    /// Build object lock mode and retain period which must be set together.
+    fn build_object_lock_retention(
+        &self,
+    ) -> Result<(Option<HeaderValue>, Option<chrono::Duration>)> {
+        match (
+            &self.default_object_lock_mode,
+            self.default_object_lock_retain_period,
+        ) {
+            (None, None) => Ok((None, None)),
+            (Some(mode), Some(period)) => {
+                if mode != "GOVERNANCE" && mode != "COMPLIANCE" {
+                    return Err(Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "object lock mode must be GOVERNANCE or COMPLIANCE",
+                    )
+                    .with_operation("Builder::build")
+                    .with_context("service", Scheme::S3)
+                    .with_context("default_object_lock_mode", mode));
+                }
+                if period.is_zero() {
+                    return Err(Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "object lock retain period must be greater than zero",
+                    )
+                    .with_operation("Builder::build")
+                    .with_context("service", Scheme::S3));
+                }
+
+                // The retain until date is calculated while writing, make
+                // sure it won't overflow.
+                let period = chrono::Duration::from_std(period)
+                    .ok()
+                    .filter(|v| chrono::Utc::now().checked_add_signed(*v).is_some())
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::ConfigInvalid,
+                            "object lock retain period is too large",
+                        )
+                        .with_operation("Builder::build")
+                        .with_context("service", Scheme::S3)
+                        .with_context(
+                            "default_object_lock_retain_period",
+                            format!("{}s", period.as_secs()),
+                        )
+                    })?;
+
+                let mode = build_header_value(mode)
+                    .map_err(|err| err.with_context("key", "default_object_lock_mode"))?;
+                Ok((Some(mode), Some(period)))
+            }
+            _ => Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "object lock mode and retain period must be set together",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::S3)),
+        }
+    }
+
+    /// Check if accelerate and dualstack can be applied to current config.
+    fn check_endpoint_features(&self) -> Result<()> {
+        if !self.enable_accelerate && !self.enable_dualstack {
+            return Ok(());
+        }
+
+        if !ENDPOINT_TEMPLATES.contains_key(self.normalized_endpoint().as_str()) {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "accelerate and dualstack can't be used with custom endpoint",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::S3)
+            .with_context("endpoint", self.endpoint.as_deref().unwrap_or_default()));
+        }
+        if self.enable_accelerate && !self.enable_virtual_host_style {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "accelerate requires virtual host style to be enabled",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::S3));
+        }
+        Ok(())
+    }
+
+    /// Parse the value of given config key.
+    ///
+    /// The first failure will be kept and returned by [`Builder::build`].
+    fn parse_config<T>(&mut self, key: &'static str, value: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        match value.parse() {
+            Ok(v) => Some(v),
+            Err(err) => {
+                if self.config_error.is_none() {
+                    self.config_error = Some(
+                        Error::new(ErrorKind::ConfigInvalid, "config value is invalid")
+                            .with_operation("Builder::from_map")
+                            .with_context("service", Scheme::S3)
+                            .with_context("key", key)
+                            .with_context("value", value)
+                            .set_source(err),
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    /// Check if the endpoint is served by AWS S3.
+    fn is_aws_endpoint(&self) -> bool {
+        let endpoint = self.normalized_endpoint();
+        let host = endpoint
+            .split_once("://")
+            .map_or(endpoint.as_str(), |(_, v)| v)
+            .split(['/', ':'])
+            .next()
+            .unwrap_or_default();
+
+        host.ends_with(".amazonaws.com") || host.ends_with(".amazonaws.com.cn")
+    }
+
+    /// Build endpoint with given region.
+    fn build_endpoint(&self, region: &str) -> String {
+        let bucket = {
+            debug_assert!(self.is_bucket_valid(), "bucket must be valid");
+
+            self.bucket.as_str()
+        };
+
+        let mut endpoint = self.normalized_endpoint();
 
         // Update with endpoint templates.
         endpoint = if let Some(template) = ENDPOINT_TEMPLATES.get(endpoint.as_str()) {
+            let template = match (self.enable_accelerate, self.enable_dualstack) {
+                (true, true) => "https://s3-accelerate.dualstack.amazonaws.com",
+                (true, false) => "https://s3-accelerate.amazonaws.com",
+                (false, true) => "https://s3.dualstack.{region}.amazonaws.com",
+                (false, false) => template,
+            };
             template.replace("{region}", region)
         } else {
             // If we don't know where about this endpoint, just leave
@@ -727,16 +1086,88 @@ impl S3Builder {
 
     /// set the minimum size of unsized write, it should be greater than 5 MB.
     /// Reference: [Amazon S3 multipart upload limits](https://docs.aws.amazon.com/AmazonS3/latest/userguide/qfacts.html)
+    ///
+    /// This is the part size of multipart upload. If the content length is
+    /// known while writing, the part size will be increased automatically
+    /// to make sure the object can be uploaded within 10,000 parts.
+    #[doc(alias = "part_size")]
     pub fn write_min_size(&mut self, write_min_size: usize) -> &mut Self {
         self.write_min_size = Some(write_min_size);
 
         self
     }
+
+    /// Set the multipart threshold of write, objects smaller than or equal
+    /// to this size will be written in a single PUT request.
+    ///
+    /// - If the content length is known, the object will be buffered and
+    ///   written at once if it's not larger than this threshold.
+    /// - If the content length is unknown, opendal will buffer data until
+    ///   it exceeds this threshold before starting multipart upload.
+    ///
+    /// Default to the same value of `write_min_size`, and it should not be
+    /// larger than 5 GiB.
+    pub fn write_multipart_threshold(&mut self, threshold: usize) -> &mut Self {
+        self.write_multipart_threshold = Some(threshold);
+
+        self
+    }
+
+    /// Set the max number of parts that can be uploaded concurrently
+    /// while doing multipart upload.
+    ///
+    /// Default to 1 which means parts will be uploaded one by one.
+    ///
+    /// # Notes
+    ///
+    /// Parts are sent in the background while writing, so a failed write
+    /// could not be re-entered if this value is larger than 1.
+    pub fn write_max_concurrent_parts(&mut self, n: usize) -> &mut Self {
+        self.write_max_concurrent_parts = Some(n);
+
+        self
+    }
 }
 
 impl Builder for S3Builder {
     const SCHEME: Scheme = Scheme::S3;
     type Accessor = S3Backend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "bucket",
+        "endpoint",
+        "region",
+        "signing_region",
+        "signing_service",
+        "access_key_id",
+        "secret_access_key",
+        "security_token",
+        "role_arn",
+        "external_id",
+        "server_side_encryption",
+        "server_side_encryption_aws_kms_key_id",
+        "server_side_encryption_customer_algorithm",
+        "server_side_encryption_customer_key",
+        "server_side_encryption_customer_key_md5",
+        "disable_config_load",
+        "disable_ec2_metadata",
+        "enable_virtual_host_style",
+        "enable_accelerate",
+        "enable_dualstack",
+        "enable_list_objects_v1",
+        "enable_payload_signing",
+        "enable_write_with_if_none_match",
+        "enable_get_object_attributes",
+        "allow_anonymous",
+        "disable_credential_loader",
+        "default_storage_class",
+        "default_object_lock_mode",
+        "default_object_lock_retain_period",
+        "default_object_lock_legal_hold",
+        "write_min_size",
+        "write_multipart_threshold",
+        "write_max_concurrent_parts",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = S3Builder::default();
@@ -745,6 +1176,9 @@ impl Builder for S3Builder {
         map.get("bucket").map(|v| builder.bucket(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
         map.get("region").map(|v| builder.region(v));
+        map.get("signing_region").map(|v| builder.signing_region(v));
+        map.get("signing_service")
+            .map(|v| builder.signing_service(v));
         map.get("access_key_id").map(|v| builder.access_key_id(v));
         map.get("secret_access_key")
             .map(|v| builder.secret_access_key(v));
@@ -770,11 +1204,49 @@ impl Builder for S3Builder {
         map.get("enable_virtual_host_style")
             .filter(|v| *v == "on" || *v == "true")
             .map(|_| builder.enable_virtual_host_style());
+        map.get("enable_accelerate")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_accelerate());
+        map.get("enable_dualstack")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_dualstack());
+        map.get("enable_list_objects_v1")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_list_objects_v1());
+        map.get("enable_payload_signing")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_payload_signing());
+        map.get("enable_write_with_if_none_match")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_write_with_if_none_match());
+        map.get("enable_get_object_attributes")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_get_object_attributes());
         map.get("allow_anonymous")
             .filter(|v| *v == "on" || *v == "true")
             .map(|_| builder.allow_anonymous());
+        map.get("disable_credential_loader")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_credential_loader());
         map.get("default_storage_class")
             .map(|v| builder.default_storage_class(v));
+        map.get("default_object_lock_mode")
+            .map(|v| builder.default_object_lock_mode(v));
+        map.get("default_object_lock_retain_period")
+            .and_then(|v| builder.parse_config("default_object_lock_retain_period", v))
+            .map(|v| builder.default_object_lock_retain_period(Duration::from_secs(v)));
+        map.get("default_object_lock_legal_hold")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.default_object_lock_legal_hold());
+        map.get("write_min_size")
+            .and_then(|v| builder.parse_config("write_min_size", v))
+            .map(|v| builder.write_min_size(v));
+        map.get("write_multipart_threshold")
+            .and_then(|v| builder.parse_config("write_multipart_threshold", v))
+            .map(|v| builder.write_multipart_threshold(v));
+        map.get("write_max_concurrent_parts")
+            .and_then(|v| builder.parse_config("write_max_concurrent_parts", v))
+            .map(|v| builder.write_max_concurrent_parts(v));
 
         builder
     }
@@ -782,20 +1254,40 @@ impl Builder for S3Builder {
     fn build(&mut self) -> Result<Self::Accessor> {
         debug!("backend build started: {:?}", &self);
 
+        if let Some(err) = self.config_error.take() {
+            return Err(err);
+        }
+
         let root = normalize_root(&self.root.take().unwrap_or_default());
         debug!("backend use root {}", &root);
 
         // Handle bucket name.
-        let bucket = if self.is_bucket_valid() {
-            Ok(&self.bucket)
-        } else {
-            Err(
-                Error::new(ErrorKind::ConfigInvalid, "The bucket is misconfigured")
-                    .with_context("service", Scheme::S3),
+        if self.bucket.is_empty() {
+            return Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::S3));
+        }
+        if !self.is_bucket_valid() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "bucket can't contain dot(.) while virtual host style is enabled",
             )
-        }?;
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::S3)
+            .with_context("bucket", &self.bucket));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            let endpoint = normalize_endpoint(endpoint).map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::S3)
+            })?;
+            self.endpoint = Some(endpoint);
+        }
+        let bucket = &self.bucket;
         debug!("backend use bucket {}", &bucket);
 
+        self.check_endpoint_features()?;
+
         let default_storage_class = match &self.default_storage_class {
             None => None,
             Some(v) => Some(
@@ -803,6 +1295,9 @@ impl Builder for S3Builder {
             ),
         };
 
+        let (default_object_lock_mode, default_object_lock_retain_period) =
+            self.build_object_lock_retention()?;
+
         let server_side_encryption = match &self.server_side_encryption {
             None => None,
             Some(v) => Some(
@@ -901,6 +1396,18 @@ impl Builder for S3Builder {
         let endpoint = self.build_endpoint(&region);
         debug!("backend use endpoint: {endpoint}");
 
+        // Static credentials and customed credential loader always take
+        // precedence over the ones loaded via `credential_process` or sso.
+        let profile_loader = if self.disable_config_load
+            || self.disable_credential_loader
+            || self.customed_credential_load.is_some()
+            || cfg.access_key_id.is_some()
+        {
+            None
+        } else {
+            ProfileLoader::from_profile(client.clone(), &cfg.profile)
+        };
+
         let mut loader = AwsLoader::new(client.client(), cfg);
         if self.disable_ec2_metadata {
             loader = loader.with_disable_ec2_metadata();
@@ -909,14 +1416,39 @@ impl Builder for S3Builder {
             loader = loader.with_customed_credential_loader(v);
         }
 
-        let signer = AwsV4Signer::new("s3", &region);
+        let signing_region = self.signing_region.as_deref().unwrap_or(&region);
+        let signing_service = self.signing_service.as_deref().unwrap_or("s3");
+        debug!("backend use signing region {signing_region} and service {signing_service}");
+        let signer = AwsV4Signer::new(signing_service, signing_region);
         let write_min_size = self.write_min_size.unwrap_or(DEFAULT_WRITE_MIN_SIZE);
-        if write_min_size < 5 * 1024 * 1024 {
+        if !(MIN_PART_SIZE..=MAX_PART_SIZE).contains(&write_min_size) {
             return Err(Error::new(
                 ErrorKind::ConfigInvalid,
                 "The write minimum buffer size is misconfigured",
             )
-            .with_context("service", Scheme::S3));
+            .with_context("service", Scheme::S3)
+            .with_context("write_min_size", write_min_size.to_string()));
+        }
+        let write_multipart_threshold = self.write_multipart_threshold.unwrap_or(write_min_size);
+        if write_multipart_threshold > MAX_PART_SIZE {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "The write multipart threshold is misconfigured",
+            )
+            .with_context("service", Scheme::S3)
+            .with_context(
+                "write_multipart_threshold",
+                write_multipart_threshold.to_string(),
+            ));
+        }
+        let write_max_concurrent_parts = self.write_max_concurrent_parts.unwrap_or(1);
+        if write_max_concurrent_parts == 0 {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "The write max concurrent parts is misconfigured",
+            )
+            .with_context("service", Scheme::S3)
+            .with_context("write_max_concurrent_parts", "0"));
         }
 
         debug!("backend build finished");
@@ -931,11 +1463,24 @@ impl Builder for S3Builder {
                 server_side_encryption_customer_key,
                 server_side_encryption_customer_key_md5,
                 default_storage_class,
+                default_object_lock_mode,
+                default_object_lock_retain_period,
+                default_object_lock_legal_hold: self.default_object_lock_legal_hold,
                 allow_anonymous: self.allow_anonymous,
+                disable_credential_loader: self.disable_credential_loader,
+                enable_list_objects_v1: self.enable_list_objects_v1,
+                enable_get_object_attributes: self.enable_get_object_attributes,
+                enable_payload_signing: self.enable_payload_signing,
+                write_with_if_none_match: self.enable_write_with_if_none_match
+                    || self.is_aws_endpoint(),
+                get_object_attributes_unsupported: AtomicBool::new(false),
                 signer,
                 loader,
+                profile_loader,
                 client,
                 write_min_size,
+                write_multipart_threshold,
+                write_max_concurrent_parts,
             }),
         })
     }
@@ -947,6 +1492,75 @@ pub struct S3Backend {
     core: Arc<S3Core>,
 }
 
+impl S3Backend {
+    /// Stat via GetObjectAttributes.
+    ///
+    /// Returns `None` if we should fall back to HeadObject.
+    async fn stat_via_get_object_attributes(&self, path: &str) -> Result<Option<Metadata>> {
+        use oio::ReadExt;
+
+        let resp = self.core.s3_get_object_attributes(path).await?;
+
+        match resp.status() {
+            StatusCode::OK => {
+                let headers = resp.headers().clone();
+                // Service could ignore the `attributes` query and return the
+                // content of object, only read a bounded prefix of the body
+                // so that we won't download the whole object.
+                if parse_content_length(&headers)?
+                    .map_or(false, |v| v > MAX_GET_OBJECT_ATTRIBUTES_SIZE as u64)
+                {
+                    self.mark_get_object_attributes_unsupported();
+                    return Ok(None);
+                }
+                let mut body = resp.into_body();
+                let mut bs = BytesMut::new();
+                while let Some(chunk) = body.next().await {
+                    bs.extend_from_slice(&chunk?);
+                    if bs.len() > MAX_GET_OBJECT_ATTRIBUTES_SIZE {
+                        self.mark_get_object_attributes_unsupported();
+                        return Ok(None);
+                    }
+                }
+
+                match quick_xml::de::from_reader::<_, GetObjectAttributesOutput>(bs.reader()) {
+                    Ok(out) => out.into_metadata(&headers).map(Some),
+                    Err(err) => {
+                        // Service could ignore the `attributes` query and
+                        // return the content directly.
+                        debug!("s3 GetObjectAttributes returns invalid response: {err:?}");
+                        self.mark_get_object_attributes_unsupported();
+                        Ok(None)
+                    }
+                }
+            }
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED => {
+                self.mark_get_object_attributes_unsupported();
+                resp.into_body().consume().await?;
+                Ok(None)
+            }
+            // Bad request could be returned by services that don't support
+            // GetObjectAttributes, but could also be caused by this request
+            // only, so we fall back to HeadObject without remembering it.
+            //
+            // Permission could be granted for HeadObject but not for
+            // GetObjectAttributes, let HeadObject decide.
+            StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN => {
+                resp.into_body().consume().await?;
+                Ok(None)
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    fn mark_get_object_attributes_unsupported(&self) {
+        debug!("s3 GetObjectAttributes is not supported, fall back to HeadObject");
+        self.core
+            .get_object_attributes_unsupported
+            .store(true, Ordering::Relaxed);
+    }
+}
+
 #[async_trait]
 impl Accessor for S3Backend {
     type Reader = IncomingAsyncBody;
@@ -976,7 +1590,12 @@ impl Accessor for S3Backend {
 
                 write: true,
                 write_with_cache_control: true,
+                write_with_expires: true,
+                write_with_if_none_match: self.core.write_with_if_none_match,
                 write_with_content_type: true,
+                write_with_acl: true,
+                write_with_storage_class: true,
+                write_with_object_lock: true,
                 write_without_content_length: true,
                 create_dir: true,
                 delete: true,
@@ -988,14 +1607,20 @@ impl Accessor for S3Backend {
                 list_without_delimiter: true,
                 list_with_delimiter_slash: true,
 
-                presign: true,
-                presign_stat: true,
-                presign_read: true,
-                presign_write: true,
+                // Presign requires credential to sign the query.
+                presign: !self.core.disable_credential_loader,
+                presign_stat: !self.core.disable_credential_loader,
+                presign_read: !self.core.disable_credential_loader,
+                presign_write: !self.core.disable_credential_loader,
+                presign_max_expire: Some(MAX_PRESIGN_EXPIRE),
 
                 batch: true,
+                batch_delete: true,
                 batch_max_operations: Some(1000),
 
+                get_acl: true,
+                restore: true,
+
                 ..Default::default()
             });
 
@@ -1003,9 +1628,20 @@ impl Accessor for S3Backend {
     }
 
     async fn create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
-        let mut req =
-            self.core
-                .s3_put_object_request(path, Some(0), None, None, None, AsyncBody::Empty)?;
+        let mut req = self.core.s3_put_object_request(
+            path,
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            // Send an empty buffered body so that content-md5 will be set.
+            AsyncBody::Bytes(Bytes::new()),
+        )?;
 
         self.core.sign(&mut req).await?;
 
@@ -1046,6 +1682,16 @@ impl Accessor for S3Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        if let Some(acl) = args.acl() {
+            check_canned_acl(acl)?;
+        }
+        if let Some(storage_class) = args.storage_class() {
+            check_storage_class(storage_class)?;
+        }
+        if let Some((mode, _)) = args.object_lock_retention() {
+            check_object_lock_mode(mode)?;
+        }
+
         Ok((
             RpWrite::default(),
             S3Writer::new(self.core.clone(), path, args),
@@ -1075,6 +1721,20 @@ impl Accessor for S3Backend {
             return Ok(RpStat::new(Metadata::new(EntryMode::DIR)));
         }
 
+        if self.core.enable_get_object_attributes
+            && !self
+                .core
+                .get_object_attributes_unsupported
+                .load(Ordering::Relaxed)
+            && !path.ends_with('/')
+            && args.if_none_match().is_none()
+            && args.if_match().is_none()
+        {
+            if let Some(meta) = self.stat_via_get_object_attributes(path).await? {
+                return Ok(RpStat::new(meta));
+            }
+        }
+
         let resp = self
             .core
             .s3_head_object(path, args.if_none_match(), args.if_match())
@@ -1083,7 +1743,21 @@ impl Accessor for S3Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            StatusCode::OK => {
+                let mut meta = parse_into_metadata(path, resp.headers())?;
+                // S3 will omit `x-amz-storage-class` for `STANDARD` objects.
+                if let Some(v) =
+                    parse_header_to_str(resp.headers(), constants::X_AMZ_STORAGE_CLASS)?
+                {
+                    meta.set_storage_class(v);
+                }
+                // Only archived objects that have been restored or are being
+                // restored have `x-amz-restore`.
+                if let Some(v) = parse_header_to_str(resp.headers(), constants::X_AMZ_RESTORE)? {
+                    meta.set_restore_status(v);
+                }
+                Ok(RpStat::new(meta))
+            }
             StatusCode::NOT_FOUND if path.ends_with('/') => {
                 Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
             }
@@ -1102,6 +1776,48 @@ impl Accessor for S3Backend {
         }
     }
 
+    async fn restore(&self, path: &str, args: OpRestore) -> Result<RpRestore> {
+        let resp = self.core.s3_restore_object(path, &args).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::ACCEPTED => {
+                resp.into_body().consume().await?;
+                Ok(RpRestore::default())
+            }
+            // S3 returns `RestoreAlreadyInProgress` while the object is
+            // being restored, it's safe to treat it as succeeded.
+            StatusCode::CONFLICT => {
+                let (parts, body) = resp.into_parts();
+                let bs = body.bytes().await?;
+                if is_restore_in_progress_error(&bs) {
+                    Ok(RpRestore::default())
+                } else {
+                    Err(parse_error_from_parts(parts, bs))
+                }
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn get_acl(&self, path: &str, _: OpGetAcl) -> Result<RpGetAcl> {
+        let resp = self.core.s3_get_object_acl(path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+
+                let out: AccessControlPolicy =
+                    quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+
+                Ok(RpGetAcl::new(out.into_acl()))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         Ok((
             RpList::default(),
@@ -1116,6 +1832,15 @@ impl Accessor for S3Backend {
     }
 
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        if self.core.disable_credential_loader {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "presign is not supported while credential loader is disabled",
+            )
+            .with_operation(Operation::Presign)
+            .with_context("service", Scheme::S3));
+        }
+
         // We will not send this request out, just for signing.
         let mut req = match args.operation() {
             PresignOperation::Stat(v) => {
@@ -1130,10 +1855,19 @@ impl Accessor for S3Backend {
                 v.if_none_match(),
                 v.if_match(),
             )?,
-            PresignOperation::Write(_) => {
-                self.core
-                    .s3_put_object_request(path, None, None, None, None, AsyncBody::Empty)?
-            }
+            PresignOperation::Write(_) => self.core.s3_put_object_request(
+                path,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                AsyncBody::Empty,
+            )?,
         };
 
         self.core.sign_query(&mut req, args.expire()).await?;
@@ -1158,9 +1892,9 @@ impl Accessor for S3Backend {
             .with_context("length", ops.len().to_string()));
         }
 
-        let paths = ops.into_iter().map(|(p, _)| p).collect();
+        let paths: Vec<String> = ops.into_iter().map(|(p, _)| p).collect();
 
-        let resp = self.core.s3_delete_objects(paths).await?;
+        let resp = self.core.s3_delete_objects(paths.clone()).await?;
 
         let status = resp.status();
 
@@ -1170,24 +1904,46 @@ impl Accessor for S3Backend {
             let result: DeleteObjectsResult =
                 quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
 
-            let mut batched_result = Vec::with_capacity(result.deleted.len() + result.error.len());
-            for i in result.deleted {
-                let path = build_rel_path(&self.core.root, &i.key);
-                batched_result.push((path, Ok(RpDelete::default().into())));
-            }
-            // TODO: we should handle those errors with code.
-            for i in result.error {
-                let path = build_rel_path(&self.core.root, &i.key);
-
-                // set the error kind and mark temporary if retryable
-                let (kind, retryable) =
-                    parse_s3_error_code(i.code.as_str()).unwrap_or((ErrorKind::Unexpected, false));
-                let mut err = Error::new(kind, &format!("{i:?}"));
-                if retryable {
-                    err = err.set_temporary();
+            // We are using quiet mode, so only failed keys will be returned.
+            // Some s3 compatible services may still return the deleted keys,
+            // which will be treated as succeeded as well.
+            let mut errors: HashMap<String, DeleteObjectsResultError> = result
+                .error
+                .into_iter()
+                .map(|v| (v.key.clone(), v))
+                .collect();
+
+            let mut batched_result = Vec::with_capacity(paths.len());
+            for path in paths {
+                let key = build_abs_path(&self.core.root, &path);
+
+                let err = match errors.remove(&key) {
+                    // Delete is idempotent, deleting a non-exist key should succeed.
+                    Some(err) if err.code == "NoSuchKey" => None,
+                    v => v,
+                };
+
+                match err {
+                    None => batched_result.push((path, Ok(RpDelete::default().into()))),
+                    Some(i) => {
+                        // set the error kind and mark temporary if retryable
+                        let (kind, retryable) = parse_s3_error_code(i.code.as_str())
+                            .unwrap_or((ErrorKind::Unexpected, false));
+                        let message = if is_object_lock_error(&i.code, &i.message) {
+                            format!("{OBJECT_LOCK_ERROR_MESSAGE}: {i:?}")
+                        } else {
+                            format!("{i:?}")
+                        };
+                        let mut err = Error::new(kind, &message)
+                            .with_context("service", Scheme::S3)
+                            .with_context("code", &i.code);
+                        if retryable {
+                            err = err.set_temporary();
+                        }
+
+                        batched_result.push((path, Err(err)));
+                    }
                 }
-
-                batched_result.push((path, Err(err)));
             }
 
             Ok(RpBatch::new(batched_result))
@@ -1199,6 +1955,16 @@ impl Accessor for S3Backend {
 
 #[cfg(test)]
 mod tests {
+    use chrono::DateTime;
+    use chrono::Utc;
+    use percent_encoding::percent_decode_str;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
     use super::*;
 
     #[test]
@@ -1256,4 +2022,940 @@ mod tests {
             assert_eq!(endpoint, "https://test.s3.us-east-2.amazonaws.com");
         }
     }
+
+    #[test]
+    fn test_write_with_if_none_match() {
+        let cases = vec![
+            ("default endpoint", None, false, true),
+            (
+                "aws endpoint",
+                Some("s3.us-east-2.amazonaws.com"),
+                false,
+                true,
+            ),
+            (
+                "aws cn endpoint",
+                Some("https://s3.cn-north-1.amazonaws.com.cn"),
+                false,
+                true,
+            ),
+            (
+                "custom endpoint",
+                Some("http://127.0.0.1:9000"),
+                false,
+                false,
+            ),
+            (
+                "fake aws endpoint",
+                Some("http://amazonaws.com.example.com"),
+                false,
+                false,
+            ),
+            (
+                "custom endpoint enabled",
+                Some("http://127.0.0.1:9000"),
+                true,
+                true,
+            ),
+        ];
+
+        for (name, endpoint, enable, expected) in cases {
+            let mut b = S3Builder::default();
+            b.bucket("test").region("us-east-1");
+            if let Some(endpoint) = endpoint {
+                b.endpoint(endpoint);
+            }
+            if enable {
+                b.enable_write_with_if_none_match();
+            }
+
+            let accessor = b.build().expect("build must succeed");
+            assert_eq!(
+                accessor.info().capability().write_with_if_none_match,
+                expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presign_with_credential_loader_disabled() {
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.disable_credential_loader();
+        let backend = b.build().expect("build must succeed");
+
+        assert!(!backend.info().capability().presign);
+
+        let err = backend
+            .presign(
+                "test",
+                OpPresign::new(OpStat::new(), Duration::from_secs(60)),
+            )
+            .await
+            .expect_err("presign must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+
+    #[tokio::test]
+    async fn test_presign_stat() -> Result<()> {
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint("https://s3.amazonaws.com");
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        let op = Operator::new(b)?.finish();
+
+        let req = op
+            .presign_stat("path/to/file", Duration::from_secs(3600))
+            .await?;
+        assert_eq!(req.method(), http::Method::HEAD);
+        assert_eq!(req.uri().path(), "/test/path/to/file");
+
+        let query: HashMap<String, String> =
+            parse_query(req.uri().query().expect("query must exist"));
+        assert_eq!(query["X-Amz-Algorithm"], "AWS4-HMAC-SHA256");
+        assert!(query["X-Amz-Credential"].starts_with("access_key_id/"));
+        assert!(query["X-Amz-Credential"].ends_with("/us-east-1/s3/aws4_request"));
+        assert_eq!(query["X-Amz-Expires"], "3600");
+        assert_eq!(query["X-Amz-SignedHeaders"], "host");
+        assert!(query.contains_key("X-Amz-Date"));
+        assert_eq!(query["X-Amz-Signature"].len(), 64);
+
+        // SigV4 presigned urls can't be valid for more than 7 days.
+        op.presign_stat("path/to/file", MAX_PRESIGN_EXPIRE).await?;
+        let err = op
+            .presign_stat("path/to/file", MAX_PRESIGN_EXPIRE + Duration::from_secs(1))
+            .await
+            .expect_err("presign must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        Ok(())
+    }
+
+    fn parse_query(query: &str) -> HashMap<String, String> {
+        query
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    percent_decode_str(v).decode_utf8_lossy().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_signing_region_and_service() {
+        let cases = vec![
+            ("default", None, None, "/us-east-2/s3/aws4_request"),
+            ("region", Some("auto"), None, "/auto/s3/aws4_request"),
+            (
+                "region and service",
+                Some("auto"),
+                Some("custom"),
+                "/auto/custom/aws4_request",
+            ),
+        ];
+
+        for (name, signing_region, signing_service, expected_scope) in cases {
+            let mut b = S3Builder::default();
+            b.bucket("test")
+                .region("us-east-2")
+                .endpoint("http://127.0.0.1:9000")
+                .access_key_id("access_key_id")
+                .secret_access_key("secret_access_key")
+                .disable_config_load();
+            if let Some(v) = signing_region {
+                b.signing_region(v);
+            }
+            if let Some(v) = signing_service {
+                b.signing_service(v);
+            }
+            let backend = b.build().expect("build must succeed");
+            // Endpoint is still built with the logical region.
+            assert!(backend.core.endpoint.starts_with("http://127.0.0.1:9000"));
+
+            let mut req = http::Request::get("http://127.0.0.1:9000/test/path")
+                .body(AsyncBody::Empty)
+                .expect("request must be valid");
+            backend
+                .core
+                .sign(&mut req)
+                .await
+                .expect("sign must succeed");
+            let authorization = req
+                .headers()
+                .get(http::header::AUTHORIZATION)
+                .expect("authorization must be set")
+                .to_str()
+                .expect("authorization must be valid");
+            assert!(authorization.contains(expected_scope), "{name}");
+
+            let mut req = http::Request::get("http://127.0.0.1:9000/test/path")
+                .body(AsyncBody::Empty)
+                .expect("request must be valid");
+            backend
+                .core
+                .sign_query(&mut req, Duration::from_secs(60))
+                .await
+                .expect("sign must succeed");
+            let credential = req
+                .uri()
+                .query()
+                .and_then(|q| q.split('&').find(|v| v.starts_with("X-Amz-Credential=")))
+                .expect("credential must be set");
+            assert!(
+                credential.contains(&expected_scope.replace('/', "%2F")),
+                "{name}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_with_tricky_key() {
+        let mut b = S3Builder::default();
+        b.bucket("test")
+            .region("us-east-1")
+            .endpoint("http://127.0.0.1:9000")
+            .access_key_id("access_key_id")
+            .secret_access_key("secret_access_key")
+            .disable_config_load();
+        let backend = b.build().expect("build must succeed");
+
+        let key = "a b/c+d#e/ünïcödé.txt";
+        // The path we send must be the same as the canonical URI of SigV4.
+        let encoded = "a%20b/c%2Bd%23e/%C3%BCn%C3%AFc%C3%B6d%C3%A9.txt";
+
+        let mut req = backend
+            .core
+            .s3_get_object_request(key, BytesRange::default(), None, None, None, None)
+            .expect("must succeed");
+        backend
+            .core
+            .sign(&mut req)
+            .await
+            .expect("sign must succeed");
+        assert_eq!(req.uri().path(), format!("/test/{encoded}"));
+
+        let req = backend
+            .core
+            .s3_copy_object_request(key, "to")
+            .expect("must succeed");
+        assert_eq!(
+            req.headers()["x-amz-copy-source"],
+            format!("test/{encoded}").as_str()
+        );
+    }
+
+    #[test]
+    fn test_build_with_invalid_bucket() {
+        let mut b = S3Builder::default();
+        b.region("us-east-1");
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+
+        let mut b = S3Builder::default();
+        b.region("us-east-1");
+        b.bucket("test.bucket");
+        b.enable_virtual_host_style();
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket can't contain dot"));
+    }
+
+    #[test]
+    fn test_build_endpoint_with_accelerate_and_dualstack() {
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.enable_dualstack();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://s3.dualstack.us-east-2.amazonaws.com/test"
+        );
+
+        b.enable_virtual_host_style();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://test.s3.dualstack.us-east-2.amazonaws.com"
+        );
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.enable_virtual_host_style();
+        b.enable_accelerate();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://test.s3-accelerate.amazonaws.com"
+        );
+
+        b.enable_dualstack();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://test.s3-accelerate.dualstack.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn test_check_endpoint_features() {
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.endpoint("http://127.0.0.1:9000");
+        b.enable_dualstack();
+        let err = b.check_endpoint_features().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.enable_accelerate();
+        let err = b.check_endpoint_features().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        b.enable_virtual_host_style();
+        b.endpoint("s3.amazonaws.com");
+        assert!(b.check_endpoint_features().is_ok());
+    }
+
+    #[test]
+    fn test_build_object_lock_retention() {
+        let mut b = S3Builder::default();
+        b.default_object_lock_mode("governance");
+        let err = b.build_object_lock_retention().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("must be set together"));
+
+        b.default_object_lock_retain_period(Duration::from_secs(3600));
+        let (mode, period) = b.build_object_lock_retention().expect("must succeed");
+        assert_eq!(mode, Some(HeaderValue::from_static("GOVERNANCE")));
+        assert_eq!(period, Some(chrono::Duration::seconds(3600)));
+
+        b.default_object_lock_retain_period(Duration::from_secs(u64::MAX));
+        let err = b.build_object_lock_retention().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        b.default_object_lock_retain_period(Duration::from_secs(3600));
+        b.default_object_lock_mode("unknown");
+        let err = b.build_object_lock_retention().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_put_object_request_with_object_lock() {
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.default_object_lock_mode("COMPLIANCE");
+        b.default_object_lock_retain_period(Duration::from_secs(3600));
+        b.default_object_lock_legal_hold();
+        let backend = b.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        let headers = req.headers();
+        assert_eq!(headers["x-amz-object-lock-mode"], "COMPLIANCE");
+        assert_eq!(headers["x-amz-object-lock-legal-hold"], "ON");
+        assert!(headers.contains_key("x-amz-object-lock-retain-until-date"));
+        assert_eq!(headers["content-md5"], "XUFAKrxLKna5cZ2REBfFkg==");
+
+        // Retention and legal hold of write override the default ones.
+        let retain_until = DateTime::parse_from_rfc3339("2030-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(("GOVERNANCE", retain_until)),
+                Some(false),
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        let headers = req.headers();
+        assert_eq!(headers["x-amz-object-lock-mode"], "GOVERNANCE");
+        assert_eq!(
+            headers["x-amz-object-lock-retain-until-date"],
+            "2030-01-01T00:00:00Z"
+        );
+        assert_eq!(headers["x-amz-object-lock-legal-hold"], "OFF");
+    }
+
+    #[tokio::test]
+    async fn test_content_md5_without_object_lock() {
+        let mut b = S3Builder::default();
+        b.bucket("test").region("us-east-1");
+        let backend = b.build().expect("build must succeed");
+
+        // Buffered bodies always carry content-md5 since the bucket may
+        // have default retention.
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        assert_eq!(req.headers()["content-md5"], "XUFAKrxLKna5cZ2REBfFkg==");
+        assert!(!req.headers().contains_key("x-amz-object-lock-mode"));
+        assert!(!req.headers().contains_key("x-amz-object-lock-legal-hold"));
+
+        let req = backend
+            .core
+            .s3_upload_part_request(
+                "test",
+                "upload_id",
+                1,
+                Some(5),
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        assert_eq!(req.headers()["content-md5"], "XUFAKrxLKna5cZ2REBfFkg==");
+
+        // Presigned requests don't carry body.
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                AsyncBody::Empty,
+            )
+            .expect("must succeed");
+        assert!(!req.headers().contains_key("content-md5"));
+
+        let res = backend
+            .write(
+                "test",
+                OpWrite::new().with_object_lock_retention("governance", Utc::now()),
+            )
+            .await;
+        assert_eq!(
+            res.err().map(|err| err.kind()),
+            Some(ErrorKind::Unsupported)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_with_acl() {
+        let mut b = S3Builder::default();
+        b.bucket("test")
+            .region("us-east-1")
+            .access_key_id("access_key_id")
+            .secret_access_key("secret_access_key")
+            .disable_config_load();
+        let backend = b.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                Some("public-read"),
+                None,
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        assert_eq!(req.headers()["x-amz-acl"], "public-read");
+
+        // oss only canned acl is not supported by s3.
+        let res = backend
+            .write("test", OpWrite::new().with_acl("default"))
+            .await;
+        assert_eq!(
+            res.err().map(|err| err.kind()),
+            Some(ErrorKind::Unsupported)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_with_storage_class() {
+        let mut b = S3Builder::default();
+        b.bucket("test")
+            .region("us-east-1")
+            .access_key_id("access_key_id")
+            .secret_access_key("secret_access_key")
+            .default_storage_class("STANDARD_IA")
+            .disable_config_load();
+        let backend = b.build().expect("build must succeed");
+
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        assert_eq!(req.headers()["x-amz-storage-class"], "STANDARD_IA");
+
+        // The given storage class overrides the default one.
+        let req = backend
+            .core
+            .s3_put_object_request(
+                "test",
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("GLACIER_IR"),
+                None,
+                None,
+                AsyncBody::Bytes(Bytes::from("hello")),
+            )
+            .expect("must succeed");
+        assert_eq!(req.headers()["x-amz-storage-class"], "GLACIER_IR");
+
+        // azblob access tier is not supported by s3.
+        let res = backend
+            .write("test", OpWrite::new().with_storage_class("Cool"))
+            .await;
+        assert_eq!(
+            res.err().map(|err| err.kind()),
+            Some(ErrorKind::Unsupported)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_object_request_with_payload_signing() {
+        let cases = vec![
+            ("unsigned payload", false, "UNSIGNED-PAYLOAD"),
+            (
+                "signed payload",
+                true,
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            ),
+        ];
+
+        for (name, signing, expected) in cases {
+            let mut b = S3Builder::default();
+            b.bucket("test")
+                .region("us-east-1")
+                .access_key_id("access_key_id")
+                .secret_access_key("secret_access_key")
+                .disable_config_load();
+            if signing {
+                b.enable_payload_signing();
+            }
+            let backend = b.build().expect("build must succeed");
+
+            let mut req = backend
+                .core
+                .s3_put_object_request(
+                    "test",
+                    Some(5),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    AsyncBody::Bytes(Bytes::from("hello")),
+                )
+                .expect("must succeed");
+            backend
+                .core
+                .sign(&mut req)
+                .await
+                .expect("sign must succeed");
+            assert_eq!(req.headers()["x-amz-content-sha256"], expected, "{name}");
+
+            let mut req = backend
+                .core
+                .s3_upload_part_request(
+                    "test",
+                    "upload_id",
+                    1,
+                    Some(5),
+                    AsyncBody::Bytes(Bytes::from("hello")),
+                )
+                .expect("must succeed");
+            backend
+                .core
+                .sign(&mut req)
+                .await
+                .expect("sign must succeed");
+            assert_eq!(req.headers()["x-amz-content-sha256"], expected, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stat_via_get_object_attributes() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/test/hello"))
+            .and(query_param("attributes", ""))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("last-modified", "Fri, 28 Apr 2023 07:00:00 GMT")
+                    .set_body_string(
+                        r#"<GetObjectAttributesResponse>
+  <ETag>6f8a52a5a5b8c1a0c9f5e7b8c56b3b2f-2</ETag>
+  <ObjectParts><PartsCount>2</PartsCount></ObjectParts>
+  <StorageClass>STANDARD_IA</StorageClass>
+  <ObjectSize>128</ObjectSize>
+</GetObjectAttributesResponse>"#,
+                    ),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint(&mock_server.uri());
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        b.enable_get_object_attributes();
+        let backend = b.build().expect("build must succeed");
+
+        let meta = backend
+            .stat("hello", OpStat::new())
+            .await
+            .expect("stat must succeed")
+            .into_metadata();
+        assert_eq!(meta.content_length(), 128);
+        assert_eq!(meta.parts_count(), Some(2));
+        assert_eq!(meta.storage_class(), Some("STANDARD_IA"));
+        assert_eq!(meta.etag(), Some("\"6f8a52a5a5b8c1a0c9f5e7b8c56b3b2f-2\""));
+    }
+
+    #[tokio::test]
+    async fn test_stat_via_get_object_attributes_fallback() {
+        let mock_server = MockServer::start().await;
+        // Bad request falls back to HeadObject for this stat only.
+        Mock::given(method("GET"))
+            .and(path("/test/hello"))
+            .and(query_param("attributes", ""))
+            .respond_with(ResponseTemplate::new(400))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // Service ignores `attributes` and returns the content.
+        Mock::given(method("GET"))
+            .and(path("/test/hello"))
+            .and(query_param("attributes", ""))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![
+                0;
+                MAX_GET_OBJECT_ATTRIBUTES_SIZE
+                    + 1
+            ]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/test/hello"))
+            .respond_with(ResponseTemplate::new(200).insert_header("content-length", "128"))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint(&mock_server.uri());
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        b.enable_get_object_attributes();
+        let backend = b.build().expect("build must succeed");
+
+        for _ in 0..3 {
+            let meta = backend
+                .stat("hello", OpStat::new())
+                .await
+                .expect("stat must succeed")
+                .into_metadata();
+            assert_eq!(meta.content_length(), 128);
+        }
+        assert!(backend
+            .core
+            .get_object_attributes_unsupported
+            .load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_write_retry_failed_part() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/test/obj"))
+            .and(query_param("uploads", ""))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<InitiateMultipartUploadResult><UploadId>upload</UploadId></InitiateMultipartUploadResult>"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // The first upload of part 2 fails.
+        Mock::given(method("PUT"))
+            .and(path("/test/obj"))
+            .and(query_param("partNumber", "2"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/test/obj"))
+            .respond_with(ResponseTemplate::new(200).insert_header("etag", "\"etag\""))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/test/obj"))
+            .and(query_param("uploadId", "upload"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint(&mock_server.uri());
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        b.write_min_size(MIN_PART_SIZE);
+        b.write_max_concurrent_parts(2);
+        let backend = b.build().expect("build must succeed");
+
+        let (_, mut w) = backend
+            .write("obj", OpWrite::new())
+            .await
+            .expect("write must succeed");
+        for _ in 0..3 {
+            oio::Write::write(&mut w, Bytes::from(vec![0; MIN_PART_SIZE + 1]))
+                .await
+                .expect("write must succeed");
+        }
+        oio::Write::close(&mut w)
+            .await
+            .expect("close must retry the failed part");
+
+        let reqs = mock_server
+            .received_requests()
+            .await
+            .expect("requests must be recorded");
+        let body = String::from_utf8_lossy(&reqs.last().expect("must have request").body)
+            .replace([' ', '\n'], "");
+        let parts = (1..=3)
+            .map(|v| body.find(&format!("<PartNumber>{v}</PartNumber>")))
+            .collect::<Vec<_>>();
+        assert!(parts.iter().all(|v| v.is_some()), "{body}");
+        assert!(parts.windows(2).all(|v| v[0] < v[1]), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/test/archived"))
+            .and(query_param("restore", ""))
+            .respond_with(ResponseTemplate::new(409).set_body_string(
+                r#"<Error><Code>RestoreAlreadyInProgress</Code><Message>Object restore is already in progress</Message></Error>"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/test/archived"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-length", "128")
+                    .insert_header("x-amz-storage-class", "GLACIER")
+                    .insert_header("x-amz-restore", r#"ongoing-request="true""#),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint(&mock_server.uri());
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        let backend = b.build().expect("build must succeed");
+
+        backend
+            .restore("archived", OpRestore::new().with_days(3).with_tier("Bulk"))
+            .await
+            .expect("restore in progress must succeed");
+
+        let meta = backend
+            .stat("archived", OpStat::new())
+            .await
+            .expect("stat must succeed")
+            .into_metadata();
+        assert_eq!(meta.storage_class(), Some("GLACIER"));
+        assert_eq!(meta.restore_status(), Some(r#"ongoing-request="true""#));
+    }
+
+    #[tokio::test]
+    async fn test_stat_fallback_to_head_object() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/test/hello"))
+            .and(query_param("attributes", ""))
+            .respond_with(ResponseTemplate::new(501))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/test/hello"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-length", "128")
+                    .insert_header("x-amz-storage-class", "GLACIER_IR"),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut b = S3Builder::default();
+        b.bucket("test");
+        b.region("us-east-1");
+        b.endpoint(&mock_server.uri());
+        b.access_key_id("access_key_id");
+        b.secret_access_key("secret_access_key");
+        b.enable_get_object_attributes();
+        let backend = b.build().expect("build must succeed");
+
+        // GetObjectAttributes should only be tried once.
+        for _ in 0..2 {
+            let meta = backend
+                .stat("hello", OpStat::new())
+                .await
+                .expect("stat must succeed")
+                .into_metadata();
+            assert_eq!(meta.content_length(), 128);
+            assert_eq!(meta.storage_class(), Some("GLACIER_IR"));
+        }
+    }
+
+    #[test]
+    fn test_build_normalize_endpoint() {
+        let cases = vec![
+            (
+                "schemeless",
+                "s3.amazonaws.com",
+                "https://s3.us-east-1.amazonaws.com/test",
+            ),
+            (
+                "https",
+                "https://s3.us-east-1.amazonaws.com",
+                "https://s3.us-east-1.amazonaws.com/test",
+            ),
+            (
+                "http with port",
+                "http://127.0.0.1:9000",
+                "http://127.0.0.1:9000/test",
+            ),
+            (
+                "schemeless with port",
+                "minio.example.com:9000",
+                "https://minio.example.com:9000/test",
+            ),
+            (
+                "trailing slash",
+                "http://127.0.0.1:9000/",
+                "http://127.0.0.1:9000/test",
+            ),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut b = S3Builder::default();
+            b.bucket("test").region("us-east-1").endpoint(input);
+            let backend = b.build().expect("build must succeed");
+
+            assert_eq!(backend.core.endpoint, expected, "{name}");
+        }
+
+        let mut b = S3Builder::default();
+        b.bucket("test")
+            .region("us-east-1")
+            .endpoint("ftp://127.0.0.1:9000");
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_from_map_invalid_value() {
+        for key in [
+            "write_min_size",
+            "write_multipart_threshold",
+            "write_max_concurrent_parts",
+            "default_object_lock_retain_period",
+        ] {
+            let map = HashMap::from([
+                ("bucket".to_string(), "test".to_string()),
+                ("region".to_string(), "us-east-1".to_string()),
+                (key.to_string(), "8MiB".to_string()),
+            ]);
+            let err = S3Builder::from_map(map)
+                .build()
+                .expect_err("build must fail");
+            assert_eq!(err.kind(), ErrorKind::ConfigInvalid, "{key}");
+            assert!(err.to_string().contains(key), "{key}");
+        }
+    }
 }
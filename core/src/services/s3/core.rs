@@ -19,29 +19,53 @@ use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
+use std::time::Instant;
 
 use bytes::Bytes;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use futures::stream;
 use http::header::HeaderName;
 use http::header::CACHE_CONTROL;
 use http::header::CONTENT_DISPOSITION;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
+use http::header::EXPIRES;
 use http::header::IF_MATCH;
 use http::header::IF_NONE_MATCH;
+use http::HeaderMap;
 use http::HeaderValue;
 use http::Request;
 use http::Response;
+use http::StatusCode;
+use log::debug;
 use reqsign::AwsCredential;
 use reqsign::AwsLoader;
 use reqsign::AwsV4Signer;
 use serde::Deserialize;
 use serde::Serialize;
-
+use sha2::Digest;
+use sha2::Sha256;
+
+use super::credential::ProfileLoader;
+use super::error::is_expired_token_error;
+use super::error::parse_error;
+use super::error::CredentialInvalidated;
+use crate::ops::OpRestore;
 use crate::raw::*;
 use crate::*;
 
-mod constants {
+/// The max expire duration of SigV4 presigned urls.
+///
+/// Reference: <https://docs.aws.amazon.com/AmazonS3/latest/userguide/using-presigned-url.html>
+pub const MAX_PRESIGN_EXPIRE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub mod constants {
+    pub const CONTENT_MD5: &str = "content-md5";
+
     pub const X_AMZ_COPY_SOURCE: &str = "x-amz-copy-source";
 
     pub const X_AMZ_SERVER_SIDE_ENCRYPTION: &str = "x-amz-server-side-encryption";
@@ -54,6 +78,15 @@ mod constants {
     pub const X_AMZ_SERVER_SIDE_ENCRYPTION_AWS_KMS_KEY_ID: &str =
         "x-amz-server-side-encryption-aws-kms-key-id";
     pub const X_AMZ_STORAGE_CLASS: &str = "x-amz-storage-class";
+    pub const X_AMZ_ACL: &str = "x-amz-acl";
+    pub const X_AMZ_CONTENT_SHA_256: &str = "x-amz-content-sha256";
+
+    pub const X_AMZ_OBJECT_ATTRIBUTES: &str = "x-amz-object-attributes";
+    pub const X_AMZ_RESTORE: &str = "x-amz-restore";
+
+    pub const X_AMZ_OBJECT_LOCK_MODE: &str = "x-amz-object-lock-mode";
+    pub const X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE: &str = "x-amz-object-lock-retain-until-date";
+    pub const X_AMZ_OBJECT_LOCK_LEGAL_HOLD: &str = "x-amz-object-lock-legal-hold";
 
     pub const X_AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM: &str =
         "x-amz-copy-source-server-side-encryption-customer-algorithm";
@@ -62,6 +95,9 @@ mod constants {
     pub const X_AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5: &str =
         "x-amz-copy-source-server-side-encryption-customer-key-md5";
 
+    pub const X_AMZ_REQUEST_ID: &str = "x-amz-request-id";
+    pub const X_AMZ_ID_2: &str = "x-amz-id-2";
+
     pub const RESPONSE_CONTENT_DISPOSITION: &str = "response-content-disposition";
     pub const RESPONSE_CACHE_CONTROL: &str = "response-cache-control";
 }
@@ -76,12 +112,27 @@ pub struct S3Core {
     pub server_side_encryption_customer_key: Option<HeaderValue>,
     pub server_side_encryption_customer_key_md5: Option<HeaderValue>,
     pub default_storage_class: Option<HeaderValue>,
+    pub default_object_lock_mode: Option<HeaderValue>,
+    pub default_object_lock_retain_period: Option<chrono::Duration>,
+    pub default_object_lock_legal_hold: bool,
     pub allow_anonymous: bool,
+    pub disable_credential_loader: bool,
+    pub enable_list_objects_v1: bool,
+    pub enable_get_object_attributes: bool,
+    pub enable_payload_signing: bool,
+    pub write_with_if_none_match: bool,
+    /// Set while GetObjectAttributes is not supported by the endpoint, so
+    /// that we can fall back to HeadObject directly.
+    pub get_object_attributes_unsupported: AtomicBool,
 
     pub signer: AwsV4Signer,
     pub loader: AwsLoader,
+    /// Loader for `credential_process` or sso configured in aws profile.
+    pub profile_loader: Option<ProfileLoader>,
     pub client: HttpClient,
     pub write_min_size: usize,
+    pub write_multipart_threshold: usize,
+    pub write_max_concurrent_parts: usize,
 }
 
 impl Debug for S3Core {
@@ -97,6 +148,26 @@ impl Debug for S3Core {
 impl S3Core {
     /// If credential is not found, we will not sign the request.
     async fn load_credential(&self) -> Result<Option<AwsCredential>> {
+        // Never touch the credential chain if loader has been disabled.
+        if self.disable_credential_loader {
+            return Ok(None);
+        }
+
+        // Fallback to the default credential chain if profile failed to
+        // load credential, for example, the sso token has been expired.
+        let profile_err = match &self.profile_loader {
+            Some(loader) => {
+                match loader.load().await {
+                    Ok(cred) => return Ok(Some(cred)),
+                    Err(err) => {
+                        debug!("load credential from profile failed, fallback to default chain: {err:?}");
+                        Some(err)
+                    }
+                }
+            }
+            None => None,
+        };
+
         let cred = self
             .loader
             .load()
@@ -108,6 +179,8 @@ impl S3Core {
         } else if self.allow_anonymous {
             // If allow_anonymous has been set, we will not sign the request.
             Ok(None)
+        } else if let Some(err) = profile_err {
+            Err(err)
         } else {
             // Mark this error as temporary since it could be caused by AWS STS.
             Err(Error::new(
@@ -140,9 +213,157 @@ impl S3Core {
             .map_err(new_request_sign_error)
     }
 
-    #[inline]
     pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
-        self.client.send(req).await
+        let sent_at = Instant::now();
+        let resp = self.client.send(req).await?;
+
+        // Errors carry the request id already, record it for succeeded
+        // requests so that users could still find it while debugging.
+        if resp.status().is_success() {
+            if let Ok(Some(v)) = parse_header_to_str(resp.headers(), constants::X_AMZ_REQUEST_ID) {
+                debug!(
+                    "s3 request succeeded: status {}, request_id {v}",
+                    resp.status()
+                );
+            }
+        }
+
+        // Temporary credentials loaded from profile could be expired or
+        // revoked before their expiration, invalidate them so that the
+        // next retry will load new ones.
+        if let Some(loader) = &self.profile_loader {
+            if matches!(
+                resp.status(),
+                StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN
+            ) {
+                let (mut parts, body) = resp.into_parts();
+                let bs = body.bytes().await?;
+                if is_expired_token_error(&bs) {
+                    loader.invalidate(sent_at).await;
+                    parts.extensions.insert(CredentialInvalidated);
+                }
+
+                // Rebuild the response so that callers can handle it as usual.
+                let size = bs.len() as u64;
+                let body = IncomingAsyncBody::new(Box::new(stream::iter(vec![Ok(bs)])), Some(size));
+                return Ok(Response::from_parts(parts, body));
+            }
+        }
+
+        // Requests are sent without signing, give users a hint that
+        // credentials are required for this bucket.
+        if self.disable_credential_loader && resp.status() == StatusCode::FORBIDDEN {
+            let err = parse_error(resp).await?;
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "anonymous access is denied, credentials are required to access this bucket",
+            )
+            .with_context("service", Scheme::S3)
+            .set_source(err));
+        }
+
+        Ok(resp)
+    }
+
+    /// Insert the SHA256 of body for PutObject and UploadPart if payload
+    /// signing is enabled.
+    ///
+    /// Signer will send `UNSIGNED-PAYLOAD` if this header is missing.
+    pub fn insert_payload_hash_header(
+        &self,
+        req: http::request::Builder,
+        body: &AsyncBody,
+    ) -> http::request::Builder {
+        if !self.enable_payload_signing {
+            return req;
+        }
+
+        req.header(
+            HeaderName::from_static(constants::X_AMZ_CONTENT_SHA_256),
+            format_async_body_sha256(body),
+        )
+    }
+
+    /// Insert object lock headers for PutObject and CreateMultipartUpload,
+    /// the given retention and legal hold override the default ones.
+    pub fn insert_object_lock_headers(
+        &self,
+        mut req: http::request::Builder,
+        retention: Option<(&str, DateTime<Utc>)>,
+        legal_hold: Option<bool>,
+    ) -> http::request::Builder {
+        let retention = match (
+            retention,
+            &self.default_object_lock_mode,
+            self.default_object_lock_retain_period,
+        ) {
+            (Some((mode, retain_until)), _, _) => Some((mode, retain_until)),
+            (None, Some(mode), Some(period)) => Some((
+                mode.to_str().expect("object lock mode must be valid"),
+                Utc::now() + period,
+            )),
+            _ => None,
+        };
+        if let Some((mode, retain_until)) = retention {
+            req = req
+                .header(
+                    HeaderName::from_static(constants::X_AMZ_OBJECT_LOCK_MODE),
+                    mode,
+                )
+                .header(
+                    HeaderName::from_static(constants::X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE),
+                    retain_until.to_rfc3339_opts(SecondsFormat::Secs, true),
+                );
+        }
+
+        match legal_hold.unwrap_or(self.default_object_lock_legal_hold) {
+            true => req.header(
+                HeaderName::from_static(constants::X_AMZ_OBJECT_LOCK_LEGAL_HOLD),
+                "ON",
+            ),
+            // Only send OFF while it's asked explicitly.
+            false if legal_hold.is_some() => req.header(
+                HeaderName::from_static(constants::X_AMZ_OBJECT_LOCK_LEGAL_HOLD),
+                "OFF",
+            ),
+            false => req,
+        }
+    }
+
+    /// Insert content-md5 for PutObject and UploadPart with buffered body.
+    ///
+    /// Object lock requires it for requests with retention settings,
+    /// including the default retention of bucket that we don't know, so we
+    /// always calculate it. Presigned requests don't carry body and will
+    /// skip it.
+    fn insert_content_md5_header(
+        &self,
+        req: http::request::Builder,
+        body: &AsyncBody,
+    ) -> http::request::Builder {
+        match body {
+            AsyncBody::Bytes(bs) => req.header(
+                HeaderName::from_static(constants::CONTENT_MD5),
+                format_content_md5(bs),
+            ),
+            AsyncBody::Empty => req,
+        }
+    }
+
+    /// Insert `x-amz-storage-class`, the given storage class overrides the
+    /// default one.
+    fn insert_storage_class_header(
+        &self,
+        req: http::request::Builder,
+        storage_class: Option<&str>,
+    ) -> http::request::Builder {
+        match storage_class {
+            Some(v) => req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v),
+            None => match &self.default_storage_class {
+                Some(v) => req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v),
+                None => req,
+            },
+        }
     }
 
     /// # Note
@@ -237,6 +458,46 @@ impl S3Core {
         Ok(req)
     }
 
+    pub async fn s3_get_object_attributes(
+        &self,
+        path: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!("{}/{}?attributes", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::get(&url);
+
+        req = req.header(
+            HeaderName::from_static(constants::X_AMZ_OBJECT_ATTRIBUTES),
+            "ETag,Checksum,ObjectParts,StorageClass,ObjectSize",
+        );
+
+        req = self.insert_sse_headers(req, false);
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
+    pub async fn s3_get_object_acl(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!("{}/{}?acl", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
     pub fn s3_get_object_request(
         &self,
         path: &str,
@@ -317,6 +578,7 @@ impl S3Core {
         self.send(req).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn s3_put_object_request(
         &self,
         path: &str,
@@ -324,6 +586,11 @@ impl S3Core {
         content_type: Option<&str>,
         content_disposition: Option<&str>,
         cache_control: Option<&str>,
+        expires: Option<DateTime<Utc>>,
+        acl: Option<&str>,
+        storage_class: Option<&str>,
+        object_lock_retention: Option<(&str, DateTime<Utc>)>,
+        object_lock_legal_hold: Option<bool>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -348,14 +615,31 @@ impl S3Core {
             req = req.header(CACHE_CONTROL, cache_control)
         }
 
-        // Set storage class header
-        if let Some(v) = &self.default_storage_class {
-            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        if let Some(expires) = expires {
+            req = req.header(EXPIRES, format_datetime_into_http_date(expires))
+        }
+
+        if let Some(acl) = acl {
+            req = req.header(HeaderName::from_static(constants::X_AMZ_ACL), acl)
         }
 
+        // Set storage class header, the given storage class overrides the default one.
+        req = self.insert_storage_class_header(req, storage_class);
+
         // Set SSE headers.
         req = self.insert_sse_headers(req, true);
 
+        // Set object lock headers.
+        req = self.insert_object_lock_headers(req, object_lock_retention, object_lock_legal_hold);
+
+        req = self.insert_content_md5_header(req, &body);
+
+        // Presigned requests don't know the size, and must keep payload
+        // unsigned.
+        if size.is_some() {
+            req = self.insert_payload_hash_header(req, &body);
+        }
+
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
 
@@ -389,11 +673,7 @@ impl S3Core {
         self.send(req).await
     }
 
-    pub async fn s3_copy_object(
-        &self,
-        from: &str,
-        to: &str,
-    ) -> Result<Response<IncomingAsyncBody>> {
+    pub fn s3_copy_object_request(&self, from: &str, to: &str) -> Result<Request<AsyncBody>> {
         let from = build_abs_path(&self.root, from);
         let to = build_abs_path(&self.root, to);
 
@@ -441,11 +721,22 @@ impl S3Core {
             )
         }
 
-        let mut req = req
-            .header(constants::X_AMZ_COPY_SOURCE, percent_encode_path(&source))
+        // Source has been encoded already, don't encode it again.
+        let req = req
+            .header(constants::X_AMZ_COPY_SOURCE, &source)
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
+        Ok(req)
+    }
+
+    pub async fn s3_copy_object(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.s3_copy_object_request(from, to)?;
+
         self.sign(&mut req).await?;
 
         self.send(req).await
@@ -499,12 +790,58 @@ impl S3Core {
         self.send(req).await
     }
 
+    /// List objects with ListObjects (v1) which uses `marker` instead of
+    /// `continuation-token` for pagination.
+    ///
+    /// Only used for services that don't implement ListObjectsV2.
+    pub async fn s3_list_objects_v1(
+        &self,
+        path: &str,
+        marker: &str,
+        delimiter: &str,
+        limit: Option<usize>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        // Request url encoded keys so that keys contains invalid xml
+        // characters can be returned correctly.
+        let mut url = format!("{}?encoding-type=url", self.endpoint);
+        if !p.is_empty() {
+            write!(url, "&prefix={}", percent_encode_path(&p))
+                .expect("write into string must succeed");
+        }
+        if !delimiter.is_empty() {
+            write!(url, "&delimiter={delimiter}").expect("write into string must succeed");
+        }
+        if let Some(limit) = limit {
+            write!(url, "&max-keys={limit}").expect("write into string must succeed");
+        }
+        if !marker.is_empty() {
+            write!(url, "&marker={}", percent_encode_path(marker))
+                .expect("write into string must succeed");
+        }
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn s3_initiate_multipart_upload(
         &self,
         path: &str,
         content_type: Option<&str>,
         content_disposition: Option<&str>,
         cache_control: Option<&str>,
+        expires: Option<DateTime<Utc>>,
+        acl: Option<&str>,
+        storage_class: Option<&str>,
+        object_lock_retention: Option<(&str, DateTime<Utc>)>,
+        object_lock_legal_hold: Option<bool>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
@@ -524,14 +861,24 @@ impl S3Core {
             req = req.header(CACHE_CONTROL, cache_control)
         }
 
-        // Set storage class header
-        if let Some(v) = &self.default_storage_class {
-            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        if let Some(expires) = expires {
+            req = req.header(EXPIRES, format_datetime_into_http_date(expires))
+        }
+
+        if let Some(acl) = acl {
+            req = req.header(HeaderName::from_static(constants::X_AMZ_ACL), acl)
         }
 
+        // Set storage class header, the given storage class overrides the default one.
+        req = self.insert_storage_class_header(req, storage_class);
+
         // Set SSE headers.
         let req = self.insert_sse_headers(req, true);
 
+        // Set object lock headers.
+        let req =
+            self.insert_object_lock_headers(req, object_lock_retention, object_lock_legal_hold);
+
         let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -568,6 +915,11 @@ impl S3Core {
         // Set SSE headers.
         req = self.insert_sse_headers(req, true);
 
+        // Parts of object with retention settings also require content-md5.
+        req = self.insert_content_md5_header(req, &body);
+
+        req = self.insert_payload_hash_header(req, &body);
+
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
 
@@ -578,6 +930,7 @@ impl S3Core {
         &self,
         path: &str,
         upload_id: &str,
+        if_none_match: Option<&str>,
         parts: &[CompleteMultipartUploadRequestPart],
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -592,7 +945,11 @@ impl S3Core {
         let req = Request::post(&url);
 
         // Set SSE headers.
-        let req = self.insert_sse_headers(req, true);
+        let mut req = self.insert_sse_headers(req, true);
+
+        if let Some(if_none_match) = if_none_match {
+            req = req.header(IF_NONE_MATCH, if_none_match);
+        }
 
         let content = quick_xml::se::to_string(&CompleteMultipartUploadRequest {
             part: parts.to_vec(),
@@ -634,6 +991,10 @@ impl S3Core {
         self.send(req).await
     }
 
+    /// Delete objects in batch with DeleteObjects.
+    ///
+    /// The request is sent in quiet mode so that S3 will only return the
+    /// keys that failed to be deleted.
     pub async fn s3_delete_objects(
         &self,
         paths: Vec<String>,
@@ -643,6 +1004,7 @@ impl S3Core {
         let req = Request::post(&url);
 
         let content = quick_xml::se::to_string(&DeleteObjectsRequest {
+            quiet: true,
             object: paths
                 .into_iter()
                 .map(|path| DeleteObjectsRequestObject {
@@ -667,6 +1029,327 @@ impl S3Core {
 
         self.send(req).await
     }
+
+    pub fn s3_restore_object_request(
+        &self,
+        path: &str,
+        args: &OpRestore,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let url = format!("{}/{}?restore", self.endpoint, percent_encode_path(&p));
+
+        let req = Request::post(&url);
+
+        let content = quick_xml::se::to_string(&RestoreRequest {
+            days: args.days(),
+            glacier_job_parameters: args.tier().map(|tier| RestoreRequestJobParameters {
+                tier: tier.to_string(),
+            }),
+        })
+        .map_err(new_xml_deserialize_error)?;
+        // Make sure content length has been set to avoid post with chunked encoding.
+        let req = req.header(CONTENT_LENGTH, content.len());
+        // Set content-type to `application/xml` to avoid mixed with form post.
+        let req = req.header(CONTENT_TYPE, "application/xml");
+        let req = req.header(
+            constants::CONTENT_MD5,
+            format_content_md5(content.as_bytes()),
+        );
+
+        req.body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)
+    }
+
+    pub async fn s3_restore_object(
+        &self,
+        path: &str,
+        args: &OpRestore,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.s3_restore_object_request(path, args)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+}
+
+/// Canned ACLs that can be applied to objects.
+///
+/// ref: <https://docs.aws.amazon.com/AmazonS3/latest/userguide/acl-overview.html#canned-acl>
+const CANNED_ACLS: [&str; 7] = [
+    "private",
+    "public-read",
+    "public-read-write",
+    "authenticated-read",
+    "aws-exec-read",
+    "bucket-owner-read",
+    "bucket-owner-full-control",
+];
+
+/// Check if given canned ACL is supported by s3.
+pub fn check_canned_acl(acl: &str) -> Result<()> {
+    if CANNED_ACLS.contains(&acl) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "canned acl is not supported")
+            .with_context("service", Scheme::S3)
+            .with_context("acl", acl),
+    )
+}
+
+/// Storage classes that can be applied to objects.
+///
+/// ref: <https://docs.aws.amazon.com/AmazonS3/latest/API/API_PutObject.html#AmazonS3-PutObject-request-header-StorageClass>
+const STORAGE_CLASSES: [&str; 11] = [
+    "STANDARD",
+    "REDUCED_REDUNDANCY",
+    "STANDARD_IA",
+    "ONEZONE_IA",
+    "INTELLIGENT_TIERING",
+    "GLACIER",
+    "DEEP_ARCHIVE",
+    "OUTPOSTS",
+    "GLACIER_IR",
+    "SNOW",
+    "EXPRESS_ONEZONE",
+];
+
+/// Check if given object lock mode is supported by s3.
+pub fn check_object_lock_mode(mode: &str) -> Result<()> {
+    if mode == "GOVERNANCE" || mode == "COMPLIANCE" {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "object lock mode is not supported")
+            .with_context("service", Scheme::S3)
+            .with_context("object_lock_mode", mode),
+    )
+}
+
+/// Check if given storage class is supported by s3.
+pub fn check_storage_class(storage_class: &str) -> Result<()> {
+    if STORAGE_CLASSES.contains(&storage_class) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::Unsupported, "storage class is not supported")
+            .with_context("service", Scheme::S3)
+            .with_context("storage_class", storage_class),
+    )
+}
+
+/// Calculate the hex encoded SHA256 of given body.
+fn format_async_body_sha256(body: &AsyncBody) -> String {
+    let bs: &[u8] = match body {
+        AsyncBody::Empty => &[],
+        AsyncBody::Bytes(bs) => bs,
+    };
+
+    format!("{:x}", Sha256::digest(bs))
+}
+
+/// Result of GetObjectAttributes
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct GetObjectAttributesOutput {
+    pub e_tag: Option<String>,
+    pub checksum: Option<GetObjectAttributesChecksum>,
+    pub object_parts: Option<GetObjectAttributesParts>,
+    pub storage_class: Option<String>,
+    pub object_size: Option<u64>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct GetObjectAttributesChecksum {
+    #[serde(rename = "ChecksumCRC32")]
+    pub checksum_crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub checksum_crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub checksum_sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub checksum_sha256: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct GetObjectAttributesParts {
+    #[serde(alias = "TotalPartsCount")]
+    pub parts_count: Option<u64>,
+}
+
+impl GetObjectAttributesOutput {
+    /// Convert into metadata, `Last-Modified` is only returned in headers.
+    pub fn into_metadata(self, headers: &HeaderMap) -> Result<Metadata> {
+        let mut m = Metadata::new(EntryMode::FILE);
+
+        if let Some(v) = self.object_size {
+            m.set_content_length(v);
+        }
+        // ETag returned in body is not quoted, keep it the same as HeadObject.
+        if let Some(v) = self.e_tag {
+            if v.starts_with('"') {
+                m.set_etag(&v);
+            } else {
+                m.set_etag(&format!("\"{v}\""));
+            }
+        }
+        if let Some(v) = parse_last_modified(headers)? {
+            m.set_last_modified(v);
+        }
+        if let Some(v) = self.storage_class {
+            m.set_storage_class(&v);
+        }
+        if let Some(v) = self.object_parts.and_then(|v| v.parts_count) {
+            m.set_parts_count(v);
+        }
+        if let Some(checksum) = self.checksum {
+            for (algorithm, v) in [
+                ("CRC32", checksum.checksum_crc32),
+                ("CRC32C", checksum.checksum_crc32c),
+                ("SHA1", checksum.checksum_sha1),
+                ("SHA256", checksum.checksum_sha256),
+            ] {
+                if let Some(v) = v {
+                    m.set_checksum(algorithm, &v);
+                }
+            }
+        }
+
+        Ok(m)
+    }
+}
+
+/// Result of GetObjectAcl
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlPolicy {
+    pub owner: Option<AccessControlPolicyOwner>,
+    pub access_control_list: AccessControlList,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlPolicyOwner {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlList {
+    pub grant: Vec<AccessControlListGrant>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlListGrant {
+    pub grantee: AccessControlListGrantee,
+    pub permission: String,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AccessControlListGrantee {
+    #[serde(rename = "ID")]
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    #[serde(rename = "URI")]
+    pub uri: Option<String>,
+    pub email_address: Option<String>,
+}
+
+impl AccessControlPolicy {
+    /// Convert into acl.
+    ///
+    /// GetObjectAcl doesn't return canned ACL, we will infer it from grants
+    /// if they match one of the canned ACLs that only grant to groups.
+    pub fn into_acl(self) -> Acl {
+        let mut acl = Acl::new();
+
+        let owner_id = self.owner.as_ref().map(|v| v.id.clone());
+        if let Some(owner) = self.owner {
+            let mut v = AclOwner::new(&owner.id);
+            if let Some(name) = &owner.display_name {
+                v = v.with_display_name(name);
+            }
+            acl.set_owner(v);
+        }
+
+        for grant in self.access_control_list.grant {
+            let grantee = match (grant.grantee.uri, grant.grantee.email_address) {
+                (Some(uri), _) if uri == "http://acs.amazonaws.com/groups/global/AllUsers" => {
+                    AclGrantee::AllUsers
+                }
+                (Some(uri), _)
+                    if uri == "http://acs.amazonaws.com/groups/global/AuthenticatedUsers" =>
+                {
+                    AclGrantee::AuthenticatedUsers
+                }
+                (Some(uri), _) => AclGrantee::Group(uri),
+                (None, Some(email)) => AclGrantee::Email(email),
+                (None, None) => AclGrantee::User {
+                    id: grant.grantee.id.unwrap_or_default(),
+                    display_name: grant.grantee.display_name,
+                },
+            };
+            let permission = match grant.permission.as_str() {
+                "READ" => AclPermission::Read,
+                "WRITE" => AclPermission::Write,
+                "READ_ACP" => AclPermission::ReadAcp,
+                "WRITE_ACP" => AclPermission::WriteAcp,
+                "FULL_CONTROL" => AclPermission::FullControl,
+                v => AclPermission::Other(v.to_string()),
+            };
+            acl.push_grant(AclGrant::new(grantee, permission));
+        }
+
+        if let Some(v) = infer_canned_acl(owner_id.as_deref(), acl.grants()) {
+            acl.set_canned_acl(v);
+        }
+
+        acl
+    }
+}
+
+/// Infer canned ACL from grants, returns `None` if they don't match.
+fn infer_canned_acl(owner_id: Option<&str>, grants: &[AclGrant]) -> Option<&'static str> {
+    let owner_id = owner_id?;
+
+    let mut owner_full_control = false;
+    let mut others = Vec::new();
+    for grant in grants {
+        match grant.grantee() {
+            AclGrantee::User { id, .. }
+                if id == owner_id && grant.permission() == &AclPermission::FullControl =>
+            {
+                owner_full_control = true
+            }
+            AclGrantee::AllUsers | AclGrantee::AuthenticatedUsers => {
+                others.push((grant.grantee(), grant.permission()))
+            }
+            _ => return None,
+        }
+    }
+    if !owner_full_control {
+        return None;
+    }
+
+    match others.as_slice() {
+        [] => Some("private"),
+        [(AclGrantee::AllUsers, AclPermission::Read)] => Some("public-read"),
+        [(AclGrantee::AllUsers, AclPermission::Read), (AclGrantee::AllUsers, AclPermission::Write)]
+        | [(AclGrantee::AllUsers, AclPermission::Write), (AclGrantee::AllUsers, AclPermission::Read)] => {
+            Some("public-read-write")
+        }
+        [(AclGrantee::AuthenticatedUsers, AclPermission::Read)] => Some("authenticated-read"),
+        _ => None,
+    }
 }
 
 /// Result of CreateMultipartUpload
@@ -724,6 +1407,9 @@ pub struct CompleteMultipartUploadRequestPart {
 #[derive(Default, Debug, Serialize)]
 #[serde(default, rename = "Delete", rename_all = "PascalCase")]
 pub struct DeleteObjectsRequest {
+    /// In quiet mode, the response includes only keys where the delete
+    /// operation encountered an error.
+    pub quiet: bool,
     pub object: Vec<DeleteObjectsRequestObject>,
 }
 
@@ -733,6 +1419,25 @@ pub struct DeleteObjectsRequestObject {
     pub key: String,
 }
 
+/// Request of RestoreObject.
+///
+/// `Days` must be omitted while restoring objects in the archive access
+/// tiers of `INTELLIGENT_TIERING`.
+#[derive(Default, Debug, Serialize)]
+#[serde(rename = "RestoreRequest", rename_all = "PascalCase")]
+pub struct RestoreRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glacier_job_parameters: Option<RestoreRequestJobParameters>,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestoreRequestJobParameters {
+    pub tier: String,
+}
+
 /// Result of DeleteObjects.
 #[derive(Default, Debug, Deserialize)]
 #[serde(default, rename = "DeleteResult", rename_all = "PascalCase")]
@@ -744,6 +1449,7 @@ pub struct DeleteObjectsResult {
 #[derive(Default, Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteObjectsResultDeleted {
+    #[cfg(test)]
     pub key: String,
 }
 
@@ -762,6 +1468,133 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_deserialize_access_control_policy() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<AccessControlPolicy xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Owner>
+    <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+    <DisplayName>mtd@amazon.com</DisplayName>
+  </Owner>
+  <AccessControlList>
+    <Grant>
+      <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CanonicalUser">
+        <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+        <DisplayName>mtd@amazon.com</DisplayName>
+      </Grantee>
+      <Permission>FULL_CONTROL</Permission>
+    </Grant>
+    <Grant>
+      <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Group">
+        <URI>http://acs.amazonaws.com/groups/global/AllUsers</URI>
+      </Grantee>
+      <Permission>READ</Permission>
+    </Grant>
+  </AccessControlList>
+</AccessControlPolicy>"#,
+        );
+
+        let out: AccessControlPolicy =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+        let acl = out.into_acl();
+
+        let owner = acl.owner().expect("owner must exist");
+        assert_eq!(
+            owner.id(),
+            "75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a"
+        );
+        assert_eq!(owner.display_name(), Some("mtd@amazon.com"));
+        assert_eq!(acl.grants().len(), 2);
+        assert_eq!(acl.grants()[0].permission(), &AclPermission::FullControl);
+        assert_eq!(acl.grants()[1].grantee(), &AclGrantee::AllUsers);
+        assert_eq!(acl.canned_acl(), Some("public-read"));
+        assert!(acl.is_public());
+    }
+
+    #[test]
+    fn test_infer_canned_acl() {
+        let owner = AclGrant::new(
+            AclGrantee::User {
+                id: "owner".to_string(),
+                display_name: None,
+            },
+            AclPermission::FullControl,
+        );
+        let all_users_read = AclGrant::new(AclGrantee::AllUsers, AclPermission::Read);
+        let all_users_write = AclGrant::new(AclGrantee::AllUsers, AclPermission::Write);
+        let authenticated_read = AclGrant::new(AclGrantee::AuthenticatedUsers, AclPermission::Read);
+        let email_read = AclGrant::new(
+            AclGrantee::Email("user@example.com".to_string()),
+            AclPermission::Read,
+        );
+
+        let cases = vec![
+            ("private", vec![owner.clone()], Some("private")),
+            (
+                "public read",
+                vec![owner.clone(), all_users_read.clone()],
+                Some("public-read"),
+            ),
+            (
+                "public read write",
+                vec![owner.clone(), all_users_write, all_users_read.clone()],
+                Some("public-read-write"),
+            ),
+            (
+                "authenticated read",
+                vec![owner.clone(), authenticated_read],
+                Some("authenticated-read"),
+            ),
+            ("extra grant", vec![owner, email_read], None),
+            ("no owner grant", vec![all_users_read], None),
+        ];
+
+        for (name, grants, expected) in cases {
+            assert_eq!(infer_canned_acl(Some("owner"), &grants), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_get_object_attributes_output() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<GetObjectAttributesResponse>
+  <ETag>6f8a52a5a5b8c1a0c9f5e7b8c56b3b2f-37</ETag>
+  <Checksum>
+    <ChecksumCRC32C>2U5/JQ==</ChecksumCRC32C>
+  </Checksum>
+  <ObjectParts>
+    <IsTruncated>false</IsTruncated>
+    <PartsCount>37</PartsCount>
+  </ObjectParts>
+  <StorageClass>STANDARD</StorageClass>
+  <ObjectSize>310378496</ObjectSize>
+</GetObjectAttributesResponse>"#,
+        );
+
+        let out: GetObjectAttributesOutput =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::LAST_MODIFIED,
+            HeaderValue::from_static("Fri, 28 Apr 2023 07:00:00 GMT"),
+        );
+        let meta = out.into_metadata(&headers).expect("must success");
+        assert_eq!(meta.content_length(), 310378496);
+        assert_eq!(meta.etag(), Some("\"6f8a52a5a5b8c1a0c9f5e7b8c56b3b2f-37\""));
+        assert_eq!(meta.storage_class(), Some("STANDARD"));
+        assert_eq!(meta.parts_count(), Some(37));
+        assert_eq!(
+            meta.checksums()
+                .and_then(|v| v.get("CRC32C"))
+                .map(|v| v.as_str()),
+            Some("2U5/JQ==")
+        );
+        assert!(meta.last_modified().is_some());
+    }
+
     /// This example is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_CreateMultipartUpload.html#API_CreateMultipartUpload_Examples
     #[test]
     fn test_deserialize_initiate_multipart_upload_result() {
@@ -832,6 +1665,7 @@ mod tests {
     #[test]
     fn test_serialize_delete_objects_request() {
         let req = DeleteObjectsRequest {
+            quiet: false,
             object: vec![
                 DeleteObjectsRequestObject {
                     key: "sample1.txt".to_string(),
@@ -847,6 +1681,7 @@ mod tests {
         pretty_assertions::assert_eq!(
             actual,
             r#"<Delete>
+             <Quiet>false</Quiet>
              <Object>
              <Key>sample1.txt</Key>
              </Object>
@@ -886,4 +1721,75 @@ mod tests {
         assert_eq!(out.error[0].code, "AccessDenied");
         assert_eq!(out.error[0].message, "Access Denied");
     }
+
+    #[test]
+    fn test_serialize_delete_objects_request_with_quiet_and_escape() {
+        let req = DeleteObjectsRequest {
+            quiet: true,
+            object: vec![DeleteObjectsRequestObject {
+                key: "a&b<c.txt".to_string(),
+            }],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<Delete>
+             <Quiet>true</Quiet>
+             <Object>
+             <Key>a&amp;b&lt;c.txt</Key>
+             </Object>
+             </Delete>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        )
+    }
+
+    #[test]
+    fn test_deserialize_delete_objects_result_with_escape() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <DeleteResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+             <Error>
+              <Key>a&amp;b&lt;c.txt</Key>
+              <Code>NoSuchKey</Code>
+              <Message>The specified key does not exist.</Message>
+             </Error>
+            </DeleteResult>"#,
+        );
+
+        let out: DeleteObjectsResult =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+
+        assert_eq!(out.deleted.len(), 0);
+        assert_eq!(out.error.len(), 1);
+        assert_eq!(out.error[0].key, "a&b<c.txt");
+        assert_eq!(out.error[0].code, "NoSuchKey");
+    }
+
+    /// This example is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_RestoreObject.html#API_RestoreObject_Examples
+    #[test]
+    fn test_serialize_restore_request() {
+        let req = RestoreRequest {
+            days: Some(2),
+            glacier_job_parameters: Some(RestoreRequestJobParameters {
+                tier: "Standard".to_string(),
+            }),
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<RestoreRequest>
+    <Days>2</Days>
+    <GlacierJobParameters>
+        <Tier>Standard</Tier>
+    </GlacierJobParameters>
+</RestoreRequest>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        );
+    }
 }
@@ -0,0 +1,487 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use chrono::Utc;
+use http::Request;
+use http::StatusCode;
+use log::debug;
+use reqsign::AwsCredential;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+/// ProfileLoader loads temporary credentials from the `credential_process`
+/// or the SSO token cache configured in aws shared config file.
+///
+/// Credentials are cached until they are expired or invalidated. The cache
+/// is guarded by an async mutex, so concurrent requests will wait for the
+/// same refresh instead of spawning many subprocesses.
+pub struct ProfileLoader {
+    source: ProfileSource,
+    client: HttpClient,
+    credential: Mutex<Option<(AwsCredential, Instant)>>,
+}
+
+impl Debug for ProfileLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfileLoader")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProfileSource {
+    /// Credentials are returned by an external command.
+    Process(String),
+    /// Credentials are exchanged by the token in SSO cache.
+    Sso {
+        start_url: String,
+        region: String,
+        account_id: String,
+        role_name: String,
+    },
+}
+
+impl ProfileLoader {
+    /// Build a loader from aws shared config file.
+    ///
+    /// Returns `None` if given profile doesn't have `credential_process` or
+    /// sso settings.
+    pub fn from_profile(client: HttpClient, profile: &str) -> Option<Self> {
+        let path = config_file_path()?;
+        let content = fs::read_to_string(&path)
+            .map_err(|err| debug!("read aws config file {path:?} failed: {err:?}"))
+            .ok()?;
+        let source = parse_profile_source(&content, profile)?;
+        debug!("s3 credential will be loaded from profile via {source:?}");
+
+        Some(Self {
+            source,
+            client,
+            credential: Mutex::default(),
+        })
+    }
+
+    /// Load credential from cache or refresh it if expired.
+    pub async fn load(&self) -> Result<AwsCredential> {
+        let mut cred = self.credential.lock().await;
+        match cred.as_ref() {
+            Some((v, _)) if v.is_valid() => return Ok(v.clone()),
+            _ => (),
+        }
+
+        let v = match &self.source {
+            ProfileSource::Process(cmd) => load_via_process(cmd).await?,
+            ProfileSource::Sso {
+                start_url,
+                region,
+                account_id,
+                role_name,
+            } => {
+                let token = load_sso_token(start_url)?;
+                self.load_via_sso(&token, region, account_id, role_name)
+                    .await?
+            }
+        };
+        *cred = Some((v.clone(), Instant::now()));
+
+        Ok(v)
+    }
+
+    /// Invalidate the cached credential if it's loaded before `sent_at`.
+    ///
+    /// Credentials loaded after the request has been sent are kept, so
+    /// requests failed at the same time will only trigger one refresh.
+    pub async fn invalidate(&self, sent_at: Instant) {
+        let mut cred = self.credential.lock().await;
+        if matches!(cred.as_ref(), Some((_, loaded_at)) if *loaded_at <= sent_at) {
+            debug!("s3 credential has been rejected by service, invalidate it");
+            *cred = None;
+        }
+    }
+
+    async fn load_via_sso(
+        &self,
+        token: &str,
+        region: &str,
+        account_id: &str,
+        role_name: &str,
+    ) -> Result<AwsCredential> {
+        let url = format!(
+            "https://portal.sso.{region}.amazonaws.com/federation/credentials?role_name={}&account_id={}",
+            percent_encode_path(role_name),
+            percent_encode_path(account_id)
+        );
+
+        let req = Request::get(&url)
+            .header("x-amz-sso_bearer_token", token)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp = self.client.send(req).await?;
+        if resp.status() != StatusCode::OK {
+            let err = parse_error(resp).await?;
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "load credential via sso failed",
+            )
+            .with_context("role_name", role_name)
+            .with_context("account_id", account_id)
+            .set_source(err));
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        let resp: SsoCredentialResponse =
+            serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+        let cred = resp.role_credentials;
+
+        Ok(AwsCredential {
+            access_key_id: cred.access_key_id,
+            secret_access_key: cred.secret_access_key,
+            session_token: Some(cred.session_token),
+            expires_in: Some(parse_datetime_from_from_timestamp_millis(cred.expiration)?),
+        })
+    }
+}
+
+/// Returns the path of aws shared config file.
+fn config_file_path() -> Option<PathBuf> {
+    match env::var("AWS_CONFIG_FILE") {
+        Ok(v) => match v.strip_prefix("~/") {
+            Some(v) => dirs::home_dir().map(|home| home.join(v)),
+            None => Some(PathBuf::from(v)),
+        },
+        Err(_) => dirs::home_dir().map(|home| home.join(".aws").join("config")),
+    }
+}
+
+/// Parse the sections of an ini file.
+///
+/// Only the subset used by aws shared config file is supported.
+fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            sections.entry(name.clone()).or_insert_with(HashMap::new);
+            current = Some(name);
+            continue;
+        }
+
+        if let (Some(section), Some((k, v))) = (&current, line.split_once('=')) {
+            sections
+                .get_mut(section)
+                .expect("section must exist")
+                .insert(k.trim().to_string(), v.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+/// Parse the credential source of given profile.
+fn parse_profile_source(content: &str, profile: &str) -> Option<ProfileSource> {
+    let sections = parse_ini(content);
+
+    let props = sections.get(&format!("profile {profile}")).or_else(|| {
+        if profile == "default" {
+            sections.get("default")
+        } else {
+            None
+        }
+    })?;
+
+    if let Some(v) = props.get("credential_process") {
+        return Some(ProfileSource::Process(v.to_string()));
+    }
+
+    let account_id = props.get("sso_account_id")?;
+    let role_name = props.get("sso_role_name")?;
+    // Settings could be shared by a `sso-session` section.
+    let session = match props.get("sso_session") {
+        Some(v) => sections.get(&format!("sso-session {v}"))?,
+        None => props,
+    };
+
+    Some(ProfileSource::Sso {
+        start_url: session.get("sso_start_url")?.to_string(),
+        region: session.get("sso_region")?.to_string(),
+        account_id: account_id.to_string(),
+        role_name: role_name.to_string(),
+    })
+}
+
+/// The output of `credential_process`.
+///
+/// Reference: <https://docs.aws.amazon.com/cli/latest/userguide/cli-configure-sourcing-external.html>
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProcessCredentialOutput {
+    version: u32,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expiration: Option<String>,
+}
+
+fn parse_process_output(bs: &[u8]) -> Result<AwsCredential> {
+    let output: ProcessCredentialOutput =
+        serde_json::from_slice(bs).map_err(new_json_deserialize_error)?;
+    if output.version != 1 {
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            "credential_process output version is not supported",
+        )
+        .with_context("version", output.version.to_string()));
+    }
+
+    Ok(AwsCredential {
+        access_key_id: output.access_key_id,
+        secret_access_key: output.secret_access_key,
+        session_token: output.session_token,
+        expires_in: output
+            .expiration
+            .map(|v| parse_datetime_from_rfc3339(&v))
+            .transpose()?,
+    })
+}
+
+async fn load_via_process(cmd: &str) -> Result<AwsCredential> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+
+    let output = command.arg(cmd).output().await.map_err(|err| {
+        Error::new(ErrorKind::Unexpected, "run credential_process failed")
+            .with_context("command", cmd)
+            .set_source(err)
+    })?;
+    if !output.status.success() {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            "credential_process exited with failure",
+        )
+        .with_context("command", cmd)
+        .with_context("status", output.status.to_string())
+        .with_context("stderr", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    parse_process_output(&output.stdout).map_err(|err| err.with_context("command", cmd))
+}
+
+/// The token cached by `aws sso login`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoToken {
+    access_token: String,
+    expires_at: String,
+    start_url: Option<String>,
+}
+
+/// Find the latest token for `start_url` in sso cache dir.
+///
+/// The cache file is named by the sha1 of session name or start url, we
+/// scan the whole dir and match the `startUrl` field instead.
+fn load_sso_token(start_url: &str) -> Result<String> {
+    let dir = dirs::home_dir()
+        .map(|home| home.join(".aws").join("sso").join("cache"))
+        .ok_or_else(|| Error::new(ErrorKind::Unexpected, "home dir is not found"))?;
+
+    let entries = fs::read_dir(&dir).map_err(|err| {
+        Error::new(ErrorKind::PermissionDenied, "read sso cache dir failed")
+            .with_context("path", dir.to_string_lossy())
+            .set_source(err)
+    })?;
+
+    let token = entries
+        .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+        .filter_map(|bs| serde_json::from_slice::<SsoToken>(&bs).ok())
+        .filter(|token| token.start_url.as_deref() == Some(start_url))
+        .filter_map(|token| {
+            let expires_at = parse_datetime_from_rfc3339(&token.expires_at).ok()?;
+            Some((expires_at, token.access_token))
+        })
+        .max_by_key(|(expires_at, _)| *expires_at);
+
+    match token {
+        Some((expires_at, token)) if expires_at > Utc::now() => Ok(token),
+        Some(_) => Err(Error::new(
+            ErrorKind::PermissionDenied,
+            "sso token is expired, please run `aws sso login` again",
+        )
+        .with_context("start_url", start_url)),
+        None => Err(Error::new(
+            ErrorKind::PermissionDenied,
+            "sso token is not found, please run `aws sso login` first",
+        )
+        .with_context("start_url", start_url)),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoCredentialResponse {
+    role_credentials: SsoRoleCredentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoRoleCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+    expiration: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile_source() {
+        let content = r#"
+[default]
+region = us-east-1
+credential_process = /usr/bin/get-creds --profile default
+
+[profile dev]
+sso_session = my-sso
+sso_account_id = 123456789012
+sso_role_name = ReadOnly
+
+[profile legacy]
+sso_start_url = https://legacy.awsapps.com/start
+sso_region = us-west-2
+sso_account_id = 210987654321
+sso_role_name = Admin
+
+[profile static]
+aws_access_key_id = ak
+
+[sso-session my-sso]
+sso_start_url = https://my-sso.awsapps.com/start
+sso_region = eu-west-1
+"#;
+
+        assert_eq!(
+            parse_profile_source(content, "default"),
+            Some(ProfileSource::Process(
+                "/usr/bin/get-creds --profile default".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_profile_source(content, "dev"),
+            Some(ProfileSource::Sso {
+                start_url: "https://my-sso.awsapps.com/start".to_string(),
+                region: "eu-west-1".to_string(),
+                account_id: "123456789012".to_string(),
+                role_name: "ReadOnly".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_profile_source(content, "legacy"),
+            Some(ProfileSource::Sso {
+                start_url: "https://legacy.awsapps.com/start".to_string(),
+                region: "us-west-2".to_string(),
+                account_id: "210987654321".to_string(),
+                role_name: "Admin".to_string(),
+            })
+        );
+        assert_eq!(parse_profile_source(content, "static"), None);
+        assert_eq!(parse_profile_source(content, "not_exist"), None);
+    }
+
+    #[test]
+    fn test_parse_process_output() {
+        let bs = r#"{
+  "Version": 1,
+  "AccessKeyId": "an AWS access key",
+  "SecretAccessKey": "your AWS secret access key",
+  "SessionToken": "the AWS session token for temporary credentials",
+  "Expiration": "2023-05-01T12:00:00Z"
+}"#;
+
+        let cred = parse_process_output(bs.as_bytes()).expect("must succeed");
+        assert_eq!(cred.access_key_id, "an AWS access key");
+        assert_eq!(cred.secret_access_key, "your AWS secret access key");
+        assert_eq!(
+            cred.session_token.as_deref(),
+            Some("the AWS session token for temporary credentials")
+        );
+        assert_eq!(cred.expires_in.map(|v| v.timestamp()), Some(1682942400),);
+
+        let err = match parse_process_output(
+            br#"{"Version": 2, "AccessKeyId": "ak", "SecretAccessKey": "sk"}"#,
+        ) {
+            Ok(_) => panic!("must fail"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("version is not supported"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_load_and_invalidate() {
+        let loader = ProfileLoader {
+            source: ProfileSource::Process(
+                r#"echo '{"Version": 1, "AccessKeyId": "ak", "SecretAccessKey": "sk"}'"#
+                    .to_string(),
+            ),
+            client: HttpClient::new().expect("must init"),
+            credential: Mutex::default(),
+        };
+
+        let cred = loader.load().await.expect("must succeed");
+        assert_eq!(cred.access_key_id, "ak");
+        let loaded_at = loader.credential.lock().await.as_ref().map(|v| v.1);
+
+        // Requests sent before loading should not invalidate the new credential.
+        loader
+            .invalidate(loaded_at.unwrap() - std::time::Duration::from_secs(1))
+            .await;
+        assert!(loader.credential.lock().await.is_some());
+
+        loader.invalidate(Instant::now()).await;
+        assert!(loader.credential.lock().await.is_none());
+    }
+}
@@ -16,11 +16,15 @@
 // under the License.
 
 use bytes::Buf;
+use bytes::Bytes;
+use http::response::Parts;
 use http::Response;
 use http::StatusCode;
 use quick_xml::de;
 use serde::Deserialize;
 
+use super::core::constants::X_AMZ_ID_2;
+use super::core::constants::X_AMZ_REQUEST_ID;
 use crate::raw::*;
 use crate::Error;
 use crate::ErrorKind;
@@ -36,17 +40,29 @@ struct S3Error {
     request_id: String,
 }
 
+/// Marker inserted into the extensions of error response while the rejected
+/// credential has been invalidated, so that the next attempt will sign with
+/// a refreshed one.
+#[derive(Debug, Clone, Copy)]
+pub struct CredentialInvalidated;
+
 /// Parse error response into Error.
 pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
     let bs = body.bytes().await?;
 
+    Ok(parse_error_from_parts(parts, bs))
+}
+
+/// Parse error response into Error with body that has been read.
+pub fn parse_error_from_parts(parts: Parts, bs: Bytes) -> Error {
     let (mut kind, mut retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
-            (ErrorKind::ConditionNotMatch, false)
-        }
+        StatusCode::PRECONDITION_FAILED => (ErrorKind::ConditionNotMatch, false),
+        StatusCode::NOT_MODIFIED => (ErrorKind::NotModified, false),
+        StatusCode::INSUFFICIENT_STORAGE => (ErrorKind::InsufficientStorage, false),
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -58,17 +74,81 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
         .map(|s3_err| (format!("{s3_err:?}"), Some(s3_err)))
         .unwrap_or_else(|_| (String::from_utf8_lossy(&bs).into_owned(), None));
 
-    if let Some(s3_err) = s3_err {
+    if let Some(s3_err) = &s3_err {
         (kind, retryable) = parse_s3_error_code(s3_err.code.as_str()).unwrap_or((kind, retryable));
     }
+    // Retrying with the same expired credential is meaningless, only retry
+    // if the credential will be refreshed.
+    if parts.extensions.get::<CredentialInvalidated>().is_some() {
+        retryable = true;
+    }
 
-    let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
+    // Give users a clear hint while the object is protected by object lock.
+    let message = match &s3_err {
+        Some(s3_err) if is_object_lock_error(&s3_err.code, &s3_err.message) => {
+            format!("{OBJECT_LOCK_ERROR_MESSAGE}: {message}")
+        }
+        _ => message,
+    };
+
+    let mut err = Error::new(kind, &message);
+
+    // Prefer request id in headers which is returned even if body is empty,
+    // for example, the response of HEAD.
+    let request_id = parse_header_to_str(&parts.headers, X_AMZ_REQUEST_ID)
+        .ok()
+        .flatten()
+        .map(|v| v.to_string())
+        .or_else(|| s3_err.as_ref().map(|v| v.request_id.clone()))
+        .filter(|v| !v.is_empty());
+    if let Some(request_id) = request_id {
+        err = err.with_request_id(request_id);
+    }
+    if let Ok(Some(host_id)) = parse_header_to_str(&parts.headers, X_AMZ_ID_2) {
+        err = err.with_context("host_id", host_id);
+    }
+    if let Ok(Some(v)) = parse_retry_after(&parts.headers) {
+        err = err.with_retry_after(v);
+    }
+
+    let mut err = err.with_context("response", format!("{parts:?}"));
 
     if retryable {
         err = err.set_temporary();
     }
 
-    Ok(err)
+    err
+}
+
+/// Returns whether this error response means the credential is expired.
+pub fn is_expired_token_error(bs: &Bytes) -> bool {
+    de::from_reader::<_, S3Error>(bs.clone().reader())
+        .map(|v| {
+            matches!(
+                v.code.as_str(),
+                "ExpiredToken" | "InvalidToken" | "TokenRefreshRequired"
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Returns whether this error response means the object is being restored.
+pub fn is_restore_in_progress_error(bs: &Bytes) -> bool {
+    de::from_reader::<_, S3Error>(bs.clone().reader())
+        .map(|v| v.code == "RestoreAlreadyInProgress")
+        .unwrap_or_default()
+}
+
+/// The message of errors caused by object lock.
+pub const OBJECT_LOCK_ERROR_MESSAGE: &str =
+    "object is protected by object lock and can't be deleted or overwritten before retention expires";
+
+/// Returns whether this error is caused by object lock.
+///
+/// S3 returns `AccessDenied` with message like `Access Denied because object
+/// protected by object lock.` for objects under retention or legal hold.
+pub fn is_object_lock_error(code: &str, message: &str) -> bool {
+    code == "AccessDenied" && message.to_lowercase().contains("object lock")
 }
 
 /// Returns the Errorkind of this code and whether the error is retryable.
@@ -95,12 +175,33 @@ pub fn parse_s3_error_code(code: &str) -> Option<(ErrorKind, bool)> {
         // indicates a temporary issue with the service or server, such as high load,
         // maintenance, or an internal problem.
         "ServiceUnavailable" => Some((ErrorKind::Unexpected, true)),
+        // > The provided token has expired.
+        //
+        // Only retryable while the credential could be refreshed, see
+        // `CredentialInvalidated`.
+        "ExpiredToken" | "InvalidToken" | "TokenRefreshRequired" => {
+            Some((ErrorKind::PermissionDenied, false))
+        }
+        // > The operation is not valid for the object's storage class.
+        //
+        // Returned while reading archived objects that are not restored.
+        "InvalidObjectState" => Some((ErrorKind::Archived, false)),
+        // S3 compatible services return these codes while the quota of
+        // user or bucket is exceeded, or the disks are full:
+        //
+        // - `QuotaExceeded`: ceph rgw
+        // - `XMinioStorageFull` and `XMinioAdminBucketQuotaExceeded`: minio
+        "QuotaExceeded" | "XMinioStorageFull" | "XMinioAdminBucketQuotaExceeded" => {
+            Some((ErrorKind::InsufficientStorage, false))
+        }
         _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::stream;
+
     use super::*;
 
     /// Error response example is from https://docs.aws.amazon.com/AmazonS3/latest/API/ErrorResponses.html
@@ -126,4 +227,156 @@ mod tests {
         assert_eq!(out.resource, "/mybucket/myfoto.jpg");
         assert_eq!(out.request_id, "4442587FB7D0A2F9");
     }
+
+    #[tokio::test]
+    async fn test_parse_object_lock_error() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>AccessDenied</Code>
+  <Message>Access Denied because object protected by object lock.</Message>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#,
+        );
+        let resp = Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(IncomingAsyncBody::new(
+                Box::new(stream::iter(vec![Ok(bs.clone())])),
+                None,
+            ))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains(OBJECT_LOCK_ERROR_MESSAGE));
+        assert_eq!(err.request_id(), Some("4442587FB7D0A2F9"));
+    }
+
+    #[test]
+    fn test_is_restore_in_progress_error() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>RestoreAlreadyInProgress</Code>
+  <Message>Object restore is already in progress</Message>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#,
+        );
+        assert!(is_restore_in_progress_error(&bs));
+
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>InvalidObjectState</Code>
+  <Message>The operation is not valid for the object's storage class</Message>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#,
+        );
+        assert!(!is_restore_in_progress_error(&bs));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_request_id_from_header() {
+        // Response of HEAD doesn't have body.
+        let resp = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(X_AMZ_REQUEST_ID, "GCYDTQX51YRSF4ZF")
+            .header(
+                X_AMZ_ID_2,
+                "EH0vV6lTwWk+lFXqCMCBSk1oovqhG4bzALU9+sUudyw7TEVrfWm2o/AFJKhYKpdGqOoBZGgMTC0=",
+            )
+            .body(IncomingAsyncBody::new(Box::new(stream::empty()), None))
+            .expect("must success");
+
+        let err = parse_error(resp).await.expect("must success");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.request_id(), Some("GCYDTQX51YRSF4ZF"));
+        assert!(err.to_string().contains(
+            "host_id: EH0vV6lTwWk+lFXqCMCBSk1oovqhG4bzALU9+sUudyw7TEVrfWm2o/AFJKhYKpdGqOoBZGgMTC0="
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_retry_after() {
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(60))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let cases = vec![("5".to_string(), 5..=5), (future, 58..=60)];
+
+        for (retry_after, expected) in cases {
+            let resp = Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(http::header::RETRY_AFTER, &retry_after)
+                .body(IncomingAsyncBody::new(Box::new(stream::empty()), None))
+                .expect("must success");
+
+            let err = parse_error(resp).await.expect("must success");
+            assert!(err.is_temporary());
+            let secs = err.retry_after().expect("must be set").as_secs();
+            assert!(expected.contains(&secs), "{retry_after}: {secs}");
+            assert!(err.to_string().contains("retry_after: "));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_quota_exceeded_error() {
+        let cases = vec![
+            (StatusCode::FORBIDDEN, "QuotaExceeded"),
+            (StatusCode::INSUFFICIENT_STORAGE, "XMinioStorageFull"),
+            (StatusCode::BAD_REQUEST, "XMinioAdminBucketQuotaExceeded"),
+        ];
+
+        for (status, code) in cases {
+            let bs = Bytes::from(format!(
+                "<Error><Code>{code}</Code><Message>test message</Message></Error>"
+            ));
+            let resp = Response::builder()
+                .status(status)
+                .body(IncomingAsyncBody::new(
+                    Box::new(stream::iter(vec![Ok(bs)])),
+                    None,
+                ))
+                .expect("must success");
+
+            let err = parse_error(resp).await.expect("must success");
+            assert_eq!(err.kind(), ErrorKind::InsufficientStorage, "{code}");
+            assert!(!err.is_temporary(), "{code}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_expired_token_error() {
+        let bs = Bytes::from(
+            r#"<Error><Code>ExpiredToken</Code><Message>The provided token has expired.</Message></Error>"#,
+        );
+
+        for invalidated in [false, true] {
+            let mut resp = Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(IncomingAsyncBody::new(
+                    Box::new(stream::iter(vec![Ok(bs.clone())])),
+                    None,
+                ))
+                .expect("must success");
+            if invalidated {
+                resp.extensions_mut().insert(CredentialInvalidated);
+            }
+
+            let err = parse_error(resp).await.expect("must success");
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+            assert_eq!(err.is_temporary(), invalidated);
+        }
+    }
+
+    #[test]
+    fn test_is_expired_token_error() {
+        let bs = Bytes::from(
+            r#"<Error><Code>ExpiredToken</Code><Message>The provided token has expired.</Message></Error>"#,
+        );
+        assert!(is_expired_token_error(&bs));
+
+        let bs = Bytes::from(r#"<Error><Code>AccessDenied</Code></Error>"#);
+        assert!(!is_expired_token_error(&bs));
+        assert!(!is_expired_token_error(&Bytes::from("not xml")));
+    }
 }
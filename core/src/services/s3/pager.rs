@@ -19,6 +19,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Buf;
+use percent_encoding::percent_decode_str;
 use quick_xml::de;
 use serde::Deserialize;
 
@@ -26,6 +27,8 @@ use super::core::S3Core;
 use super::error::parse_error;
 use crate::raw::*;
 use crate::EntryMode;
+use crate::Error;
+use crate::ErrorKind;
 use crate::Metadata;
 use crate::Result;
 
@@ -39,6 +42,10 @@ pub struct S3Pager {
     /// Amazon S3 starts listing **after** this specified key
     start_after: Option<String>,
 
+    /// Use ListObjects (v1) instead of ListObjectsV2.
+    ///
+    /// `token` will be the `marker` of ListObjects if `v1` is true.
+    v1: bool,
     token: String,
     done: bool,
 }
@@ -51,7 +58,8 @@ impl S3Pager {
         limit: Option<usize>,
         start_after: Option<&str>,
     ) -> Self {
-        Self {
+        let enable_list_objects_v1 = core.enable_list_objects_v1;
+        let mut pager = Self {
             core,
 
             path: path.to_string(),
@@ -59,9 +67,27 @@ impl S3Pager {
             limit,
             start_after: start_after.map(String::from),
 
+            v1: false,
             token: "".to_string(),
             done: false,
+        };
+        if enable_list_objects_v1 {
+            pager.switch_to_v1();
         }
+        pager
+    }
+
+    /// Switch to ListObjects (v1) from the start of this listing.
+    ///
+    /// ListObjects (v1) doesn't support `start-after`, but `marker` has
+    /// the same semantics.
+    fn switch_to_v1(&mut self) {
+        self.v1 = true;
+        self.token = self
+            .start_after
+            .as_ref()
+            .map(|v| build_abs_path(&self.core.root, v))
+            .unwrap_or_default();
     }
 }
 
@@ -72,16 +98,36 @@ impl oio::Page for S3Pager {
             return Ok(None);
         }
 
-        let resp = self
-            .core
-            .s3_list_objects(
-                &self.path,
-                &self.token,
-                &self.delimiter,
-                self.limit,
-                self.start_after.clone(),
-            )
-            .await?;
+        let resp = loop {
+            let resp = if self.v1 {
+                self.core
+                    .s3_list_objects_v1(&self.path, &self.token, &self.delimiter, self.limit)
+                    .await?
+            } else {
+                self.core
+                    .s3_list_objects(
+                        &self.path,
+                        &self.token,
+                        &self.delimiter,
+                        self.limit,
+                        self.start_after.clone(),
+                    )
+                    .await?
+            };
+
+            // Some s3 compatible services don't implement ListObjectsV2 at all,
+            // fallback to ListObjects (v1) if this is the first request.
+            if !self.v1
+                && self.token.is_empty()
+                && resp.status() == http::StatusCode::NOT_IMPLEMENTED
+            {
+                resp.into_body().consume().await?;
+                self.switch_to_v1();
+                continue;
+            }
+
+            break resp;
+        };
 
         if resp.status() != http::StatusCode::OK {
             return Err(parse_error(resp).await?);
@@ -89,21 +135,41 @@ impl oio::Page for S3Pager {
 
         let bs = resp.into_body().bytes().await?;
 
-        let output: Output = de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+        let mut output: Output = de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+        output.decode()?;
 
         // Try our best to check whether this list is done.
         //
         // - Check `is_truncated`
-        // - Check `next_continuation_token`
+        // - Check `next_continuation_token` (or `next_marker` for v1)
         // - Check the length of `common_prefixes` and `contents` (very rarely case)
-        self.done = if let Some(is_truncated) = output.is_truncated {
-            !is_truncated
-        } else if let Some(next_continuation_token) = output.next_continuation_token.as_ref() {
-            next_continuation_token.is_empty()
+        if self.v1 {
+            self.done = match output.is_truncated {
+                Some(is_truncated) => !is_truncated,
+                None => output.common_prefixes.is_empty() && output.contents.is_empty(),
+            };
+            self.token = output.next_marker();
         } else {
-            output.common_prefixes.is_empty() && output.contents.is_empty()
-        };
-        self.token = output.next_continuation_token.clone().unwrap_or_default();
+            self.done = if let Some(is_truncated) = output.is_truncated {
+                !is_truncated
+            } else if let Some(next_continuation_token) = output.next_continuation_token.as_ref() {
+                next_continuation_token.is_empty()
+            } else {
+                output.common_prefixes.is_empty() && output.contents.is_empty()
+            };
+            self.token = output.next_continuation_token.clone().unwrap_or_default();
+
+            // The service ignored `list-type=2` and returned a ListObjects (v1)
+            // response, continue listing with `marker` instead.
+            if !self.done && self.token.is_empty() {
+                self.v1 = true;
+                self.token = output.next_marker();
+            }
+        }
+        // Stop listing instead of starting over from the beginning.
+        if self.v1 && self.token.is_empty() {
+            self.done = true;
+        }
 
         let mut entries = Vec::with_capacity(output.common_prefixes.len() + output.contents.len());
 
@@ -157,10 +223,61 @@ impl oio::Page for S3Pager {
 struct Output {
     is_truncated: Option<bool>,
     next_continuation_token: Option<String>,
+    /// Only returned by ListObjects (v1) when `delimiter` is specified.
+    next_marker: Option<String>,
+    encoding_type: Option<String>,
     common_prefixes: Vec<OutputCommonPrefix>,
     contents: Vec<OutputContent>,
 }
 
+impl Output {
+    /// Decode keys and prefixes if they are url encoded.
+    fn decode(&mut self) -> Result<()> {
+        if self.encoding_type.as_deref() != Some("url") {
+            return Ok(());
+        }
+
+        if let Some(v) = self.next_marker.as_mut() {
+            *v = decode_url_encoded_key(v)?;
+        }
+        for prefix in self.common_prefixes.iter_mut() {
+            prefix.prefix = decode_url_encoded_key(&prefix.prefix)?;
+        }
+        for object in self.contents.iter_mut() {
+            object.key = decode_url_encoded_key(&object.key)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the marker to use for the next ListObjects (v1) request.
+    ///
+    /// `NextMarker` is only returned when `delimiter` is specified,
+    /// otherwise we should use the last key in this response.
+    fn next_marker(&self) -> String {
+        if let Some(v) = self.next_marker.as_ref().filter(|v| !v.is_empty()) {
+            return v.clone();
+        }
+
+        let last_key = self.contents.last().map(|v| v.key.as_str());
+        let last_prefix = self.common_prefixes.last().map(|v| v.prefix.as_str());
+        last_key.max(last_prefix).unwrap_or_default().to_string()
+    }
+}
+
+/// Decode key encoded with `encoding-type=url`.
+///
+/// S3 encodes space as `+` in this case.
+fn decode_url_encoded_key(key: &str) -> Result<String> {
+    percent_decode_str(&key.replace('+', " "))
+        .decode_utf8()
+        .map(|v| v.into_owned())
+        .map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "url encoded key is not valid utf-8")
+                .with_context("key", key)
+                .set_source(err)
+        })
+}
+
 #[derive(Default, Debug, Eq, PartialEq, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 struct OutputContent {
@@ -244,4 +361,91 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_parse_list_output_v1() {
+        let bs = bytes::Bytes::from(
+            r#"<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>example-bucket</Name>
+  <Prefix>photos/</Prefix>
+  <Marker></Marker>
+  <NextMarker>photos/2006/February/</NextMarker>
+  <MaxKeys>2</MaxKeys>
+  <Delimiter>/</Delimiter>
+  <IsTruncated>true</IsTruncated>
+  <Contents>
+    <Key>photos/2006</Key>
+    <LastModified>2016-04-30T23:51:29.000Z</LastModified>
+    <ETag>"d41d8cd98f00b204e9800998ecf8427e"</ETag>
+    <Size>56</Size>
+    <StorageClass>STANDARD</StorageClass>
+  </Contents>
+  <CommonPrefixes>
+    <Prefix>photos/2006/February/</Prefix>
+  </CommonPrefixes>
+</ListBucketResult>"#,
+        );
+
+        let mut out: Output = de::from_reader(bs.reader()).expect("must success");
+        out.decode().expect("must success");
+
+        assert!(out.is_truncated.unwrap());
+        assert!(out.next_continuation_token.is_none());
+        assert_eq!(out.next_marker(), "photos/2006/February/");
+        assert_eq!(out.contents[0].key, "photos/2006");
+    }
+
+    #[test]
+    fn test_parse_list_output_v1_without_next_marker() {
+        let bs = bytes::Bytes::from(
+            r#"<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>example-bucket</Name>
+  <Prefix>photos/</Prefix>
+  <Marker>photos/a</Marker>
+  <MaxKeys>2</MaxKeys>
+  <EncodingType>url</EncodingType>
+  <IsTruncated>true</IsTruncated>
+  <Contents>
+    <Key>photos/b%26c</Key>
+    <LastModified>2016-04-30T23:51:29.000Z</LastModified>
+    <ETag>"d41d8cd98f00b204e9800998ecf8427e"</ETag>
+    <Size>56</Size>
+  </Contents>
+  <Contents>
+    <Key>photos/hello+world%2B%E4%BD%A0%E5%A5%BD</Key>
+    <LastModified>2016-04-30T23:51:29.000Z</LastModified>
+    <ETag>"d41d8cd98f00b204e9800998ecf8427e"</ETag>
+    <Size>100</Size>
+  </Contents>
+</ListBucketResult>"#,
+        );
+
+        let mut out: Output = de::from_reader(bs.reader()).expect("must success");
+        out.decode().expect("must success");
+
+        assert!(out.is_truncated.unwrap());
+        assert_eq!(
+            out.contents
+                .iter()
+                .map(|v| v.key.clone())
+                .collect::<Vec<String>>(),
+            vec!["photos/b&c", "photos/hello world+你好"]
+        );
+        // NextMarker is not returned without delimiter, use the last key.
+        assert_eq!(out.next_marker(), "photos/hello world+你好");
+    }
+
+    #[test]
+    fn test_decode_url_encoded_key_without_encoding_type() {
+        let mut out = Output {
+            contents: vec![OutputContent {
+                key: "a+b%20c".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        out.decode().expect("must success");
+
+        assert_eq!(out.contents[0].key, "a+b%20c");
+    }
 }
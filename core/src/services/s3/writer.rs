@@ -15,12 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Buf;
 use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::stream::FuturesOrdered;
+use futures::StreamExt;
+use http::header::IF_NONE_MATCH;
 use http::StatusCode;
+use log::debug;
 
 use super::core::*;
 use super::error::parse_error;
@@ -28,6 +34,9 @@ use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
+/// AWS S3 allows at most 10,000 parts in a multipart upload.
+const MAX_PARTS: u64 = 10_000;
+
 pub struct S3Writer {
     core: Arc<S3Core>,
 
@@ -38,11 +47,28 @@ pub struct S3Writer {
     parts: Vec<CompleteMultipartUploadRequestPart>,
     buffer: oio::VectorCursor,
     buffer_size: usize,
+
+    /// Parts that are uploading concurrently.
+    futures: FuturesOrdered<BoxFuture<'static, Result<CompleteMultipartUploadRequestPart>>>,
+    /// Part numbers and bytes of parts that are uploading concurrently, in
+    /// the same order of `futures`.
+    ///
+    /// Bytes are kept until the part is uploaded so that failed parts can
+    /// be retried.
+    inflight: VecDeque<(usize, Bytes)>,
+    /// Parts that failed to upload, they must be uploaded again with the
+    /// same part number before completing the upload.
+    failed: VecDeque<(usize, Bytes)>,
 }
 
+/// # Safety
+///
+/// S3Writer will only be accessed by `&mut Self`
+unsafe impl Sync for S3Writer {}
+
 impl S3Writer {
     pub fn new(core: Arc<S3Core>, path: &str, op: OpWrite) -> Self {
-        let buffer_size = core.write_min_size;
+        let buffer_size = part_size(core.write_min_size, op.content_length());
         S3Writer {
             core,
             path: path.to_string(),
@@ -52,6 +78,9 @@ impl S3Writer {
             parts: vec![],
             buffer: oio::VectorCursor::new(),
             buffer_size,
+            futures: FuturesOrdered::new(),
+            inflight: VecDeque::new(),
+            failed: VecDeque::new(),
         }
     }
 
@@ -62,9 +91,25 @@ impl S3Writer {
             self.op.content_type(),
             self.op.content_disposition(),
             self.op.cache_control(),
+            self.op.expires(),
+            self.op.acl(),
+            self.op.storage_class(),
+            self.op.object_lock_retention(),
+            self.op.object_lock_legal_hold(),
             AsyncBody::Bytes(bs),
         )?;
 
+        if let Some(if_none_match) = self.op.if_none_match() {
+            req.headers_mut().insert(
+                IF_NONE_MATCH,
+                if_none_match.parse().map_err(|err| {
+                    Error::new(ErrorKind::Unexpected, "header value is invalid")
+                        .with_operation("S3Writer::write_oneshot")
+                        .set_source(err)
+                })?,
+            );
+        }
+
         self.core.sign(&mut req).await?;
 
         let resp = self.core.send(req).await?;
@@ -88,6 +133,11 @@ impl S3Writer {
                 self.op.content_type(),
                 self.op.content_disposition(),
                 self.op.cache_control(),
+                self.op.expires(),
+                self.op.acl(),
+                self.op.storage_class(),
+                self.op.object_lock_retention(),
+                self.op.object_lock_legal_hold(),
             )
             .await?;
 
@@ -106,79 +156,224 @@ impl S3Writer {
         }
     }
 
-    async fn write_part(
+    /// The part number of the next part.
+    ///
+    /// Every allocated part number is either uploaded, in-flight or failed.
+    fn next_part_number(&self) -> usize {
+        // AWS S3 requires part number must between [1..=10000]
+        self.parts.len() + self.inflight.len() + self.failed.len() + 1
+    }
+
+    /// Build a future to upload given bytes as the part of `part_number`.
+    fn write_part(
         &self,
         upload_id: &str,
+        part_number: usize,
         bs: Bytes,
-    ) -> Result<CompleteMultipartUploadRequestPart> {
-        // AWS S3 requires part number must between [1..=10000]
-        let part_number = self.parts.len() + 1;
-
-        let mut req = self.core.s3_upload_part_request(
-            &self.path,
-            upload_id,
-            part_number,
-            Some(bs.len() as u64),
-            AsyncBody::Bytes(bs),
-        )?;
+    ) -> BoxFuture<'static, Result<CompleteMultipartUploadRequestPart>> {
+        let core = self.core.clone();
+        let path = self.path.clone();
+        let upload_id = upload_id.to_string();
+
+        Box::pin(async move {
+            let mut req = core.s3_upload_part_request(
+                &path,
+                &upload_id,
+                part_number,
+                Some(bs.len() as u64),
+                AsyncBody::Bytes(bs),
+            )?;
+
+            core.sign(&mut req).await?;
+
+            let resp = core.send(req).await?;
+
+            let status = resp.status();
+
+            match status {
+                StatusCode::OK => {
+                    let etag = parse_etag(resp.headers())?
+                        .ok_or_else(|| {
+                            Error::new(
+                                ErrorKind::Unexpected,
+                                "ETag not present in returning response",
+                            )
+                        })?
+                        .to_string();
+
+                    resp.into_body().consume().await?;
+
+                    Ok(CompleteMultipartUploadRequestPart { part_number, etag })
+                }
+                _ => Err(parse_error(resp).await?),
+            }
+        })
+    }
 
-        self.core.sign(&mut req).await?;
+    /// Wait for the oldest in-flight part.
+    ///
+    /// The failed part will be kept and retried by [`Self::retry_failed_parts`].
+    async fn wait_oldest_part(&mut self) -> Result<()> {
+        let part = self.futures.next().await.expect("part future must exist");
+        let (part_number, bs) = self.inflight.pop_front().expect("part must be in-flight");
 
-        let resp = self.core.send(req).await?;
+        match part {
+            Ok(part) => {
+                self.parts.push(part);
+                Ok(())
+            }
+            Err(e) => {
+                self.failed.push_back((part_number, bs));
+                Err(e)
+            }
+        }
+    }
 
-        let status = resp.status();
+    /// Wait for all concurrent uploading parts to finish.
+    async fn wait_parts(&mut self) -> Result<()> {
+        let mut res = Ok(());
+        while !self.futures.is_empty() {
+            if let Err(e) = self.wait_oldest_part().await {
+                res = res.and(Err(e));
+            }
+        }
+        res
+    }
 
-        match status {
-            StatusCode::OK => {
-                let etag = parse_etag(resp.headers())?
-                    .ok_or_else(|| {
-                        Error::new(
-                            ErrorKind::Unexpected,
-                            "ETag not present in returning response",
-                        )
-                    })?
-                    .to_string();
+    /// Upload failed parts again with the same part number.
+    async fn retry_failed_parts(&mut self, upload_id: &str) -> Result<()> {
+        while let Some((part_number, bs)) = self.failed.front().cloned() {
+            let part = self.write_part(upload_id, part_number, bs).await?;
+            self.failed.pop_front();
+            self.parts.push(part);
+        }
+        Ok(())
+    }
 
-                resp.into_body().consume().await?;
+    /// Returns whether we should wait for the oldest in-flight part before
+    /// accepting more data.
+    fn is_full(&self) -> bool {
+        if self.futures.is_empty() {
+            return false;
+        }
+        if self.futures.len() >= self.core.write_max_concurrent_parts {
+            return true;
+        }
 
-                Ok(CompleteMultipartUploadRequestPart { part_number, etag })
+        match self.op.buffer_limit() {
+            Some(limit) => {
+                self.inflight.iter().map(|(_, bs)| bs.len()).sum::<usize>() + self.buffer.len()
+                    > limit
             }
-            _ => Err(parse_error(resp).await?),
+            None => false,
+        }
+    }
+}
+
+/// Calculate the part size of multipart upload.
+///
+/// If the content length is known, the part size will be increased to make
+/// sure the object can be uploaded within [`MAX_PARTS`] parts.
+fn part_size(min_size: usize, content_length: Option<u64>) -> usize {
+    match content_length {
+        Some(size) => {
+            let size = ((size + MAX_PARTS - 1) / MAX_PARTS) as usize;
+            size.max(min_size)
         }
+        None => min_size,
     }
 }
 
 #[async_trait]
 impl oio::Write for S3Writer {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        let upload_id = match &self.upload_id {
-            Some(upload_id) => upload_id,
-            None => {
-                if self.op.content_length().unwrap_or_default() == bs.len() as u64 {
-                    return self.write_oneshot(bs).await;
-                } else {
-                    let upload_id = self.initiate_upload().await?;
-                    self.upload_id = Some(upload_id);
-                    self.upload_id.as_deref().unwrap()
+        if self.upload_id.is_none() {
+            let content_length = self.op.content_length();
+            // Write the whole object in one request.
+            if self.buffer.is_empty() && content_length.unwrap_or_default() == bs.len() as u64 {
+                return self.write_oneshot(bs).await;
+            }
+
+            self.buffer.push(bs);
+
+            let threshold = self.core.write_multipart_threshold as u64;
+            match content_length {
+                // The object is small enough, wait for all data to be
+                // buffered and write them in one request.
+                Some(size) if size <= threshold => {
+                    if (self.buffer.len() as u64) < size {
+                        return Ok(());
+                    }
+
+                    return match self.write_oneshot(self.buffer.peak_all()).await {
+                        Ok(_) => {
+                            self.buffer.clear();
+                            Ok(())
+                        }
+                        Err(e) => {
+                            self.buffer.pop();
+                            Err(e)
+                        }
+                    };
+                }
+                // The size is unknown, keep buffering until threshold or
+                // buffer limit is reached.
+                None if self.buffer.len() as u64
+                    <= self
+                        .op
+                        .buffer_limit()
+                        .map_or(threshold, |v| threshold.min(v as u64)) =>
+                {
+                    return Ok(())
                 }
+                _ => {}
             }
-        };
 
-        // Ignore empty bytes
-        if bs.is_empty() {
-            return Ok(());
+            match self.initiate_upload().await {
+                Ok(upload_id) => self.upload_id = Some(upload_id),
+                Err(e) => {
+                    // Make sure write is re-enter safe.
+                    self.buffer.pop();
+                    return Err(e);
+                }
+            }
+        } else {
+            // Ignore empty bytes
+            if bs.is_empty() {
+                return Ok(());
+            }
+
+            // Retry failed parts and wait for the oldest part if there are
+            // too many parts or bytes in flight before accepting given bs,
+            // so that write is re-enter safe.
+            let upload_id = self.upload_id.clone().expect("upload id must be valid");
+            self.retry_failed_parts(&upload_id).await?;
+            while self.is_full() {
+                self.wait_oldest_part().await?;
+            }
+
+            self.buffer.push(bs);
         }
 
-        self.buffer.push(bs);
         // Return directly if the buffer is not full
         if self.buffer.len() <= self.buffer_size {
             return Ok(());
         }
 
+        let upload_id = self.upload_id.clone().expect("upload id must be valid");
         let bs = self.buffer.peak_at_least(self.buffer_size);
         let size = bs.len();
+        let part_number = self.next_part_number();
 
-        match self.write_part(upload_id, bs).await {
+        if self.core.write_max_concurrent_parts > 1 {
+            self.buffer.take(size);
+            self.futures
+                .push_back(self.write_part(&upload_id, part_number, bs.clone()));
+            self.inflight.push_back((part_number, bs));
+            return Ok(());
+        }
+
+        match self.write_part(&upload_id, part_number, bs).await {
             Ok(part) => {
                 self.buffer.take(size);
                 self.parts.push(part);
@@ -197,9 +392,15 @@ impl oio::Write for S3Writer {
         let upload_id = if let Some(upload_id) = &self.upload_id {
             upload_id
         } else {
+            self.buffer.clear();
             return Ok(());
         };
 
+        // Drop all uploading parts.
+        self.futures = FuturesOrdered::new();
+        self.inflight.clear();
+        self.failed.clear();
+
         let resp = self
             .core
             .s3_abort_multipart_upload(&self.path, upload_id)
@@ -215,30 +416,45 @@ impl oio::Write for S3Writer {
     }
 
     async fn close(&mut self) -> Result<()> {
-        let upload_id = if let Some(upload_id) = &self.upload_id {
+        let upload_id = if let Some(upload_id) = self.upload_id.clone() {
             upload_id
         } else {
+            // Data that smaller than multipart threshold is still buffered.
+            if !self.buffer.is_empty() {
+                self.write_oneshot(self.buffer.peak_all()).await?;
+                self.buffer.clear();
+            }
             return Ok(());
         };
 
+        // Failed parts will be retried below, the upload must never be
+        // completed without them.
+        if let Err(e) = self.wait_parts().await {
+            debug!("s3 writer part upload failed, retrying: {e:?}");
+        }
+        self.retry_failed_parts(&upload_id).await?;
+
         // Make sure internal buffer has been flushed.
         if !self.buffer.is_empty() {
             let bs = self.buffer.peak_exact(self.buffer.len());
+            let part_number = self.next_part_number();
 
-            match self.write_part(upload_id, bs).await {
-                Ok(part) => {
-                    self.buffer.clear();
-                    self.parts.push(part);
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            }
+            let part = self.write_part(&upload_id, part_number, bs).await?;
+            self.buffer.clear();
+            self.parts.push(part);
         }
 
+        // Parts may be uploaded out of order while retrying.
+        self.parts.sort_by_key(|v| v.part_number);
+
         let resp = self
             .core
-            .s3_complete_multipart_upload(&self.path, upload_id, &self.parts)
+            .s3_complete_multipart_upload(
+                &self.path,
+                &upload_id,
+                self.op.if_none_match(),
+                &self.parts,
+            )
             .await?;
 
         let status = resp.status();
@@ -253,3 +469,21 @@ impl oio::Write for S3Writer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_size() {
+        let min_size = 8 * 1024 * 1024;
+
+        assert_eq!(part_size(min_size, None), min_size);
+        assert_eq!(part_size(min_size, Some(1024)), min_size);
+        // 1 TiB object requires larger parts to stay under 10,000 parts.
+        assert_eq!(
+            part_size(min_size, Some(1024 * 1024 * 1024 * 1024)),
+            109_951_163
+        );
+    }
+}
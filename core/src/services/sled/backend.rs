@@ -94,6 +94,7 @@ impl SledBuilder {
 impl Builder for SledBuilder {
     const SCHEME: Scheme = Scheme::Sled;
     type Accessor = SledBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["datadir", "root"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = SledBuilder::default();
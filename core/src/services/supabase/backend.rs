@@ -159,6 +159,7 @@ impl SupabaseBuilder {
 impl Builder for SupabaseBuilder {
     const SCHEME: Scheme = Scheme::Supabase;
     type Accessor = SupabaseBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["root", "bucket", "endpoint", "key"];
 
     fn from_map(map: std::collections::HashMap<String, String>) -> Self {
         let mut builder = SupabaseBuilder::default();
@@ -176,8 +177,20 @@ impl Builder for SupabaseBuilder {
         debug!("backend use root {}", &root);
 
         let bucket = &self.bucket;
+        if bucket.is_empty() {
+            return Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Supabase));
+        }
 
-        let endpoint = self.endpoint.take().unwrap_or_default();
+        let endpoint = match self.endpoint.take() {
+            Some(v) if !v.is_empty() => v,
+            _ => {
+                return Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")
+                    .with_operation("Builder::build")
+                    .with_context("service", Scheme::Supabase))
+            }
+        };
 
         let http_client = if let Some(client) = self.http_client.take() {
             client
@@ -327,3 +340,23 @@ impl Accessor for SupabaseBackend {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_without_required_fields() {
+        let err = SupabaseBuilder::default()
+            .build()
+            .expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+
+        let mut b = SupabaseBuilder::default();
+        b.bucket("test");
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("endpoint is empty"));
+    }
+}
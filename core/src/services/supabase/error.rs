@@ -67,6 +67,8 @@ fn parse_supabase_error(err: &SupabaseError) -> (ErrorKind, bool) {
         (ErrorKind::PermissionDenied, false)
     } else if code == StatusCode::PRECONDITION_FAILED.as_u16() {
         (ErrorKind::ConditionNotMatch, false)
+    } else if code == StatusCode::RANGE_NOT_SATISFIABLE.as_u16() {
+        (ErrorKind::RangeNotSatisfied, false)
     } else {
         (ErrorKind::Unexpected, false)
     }
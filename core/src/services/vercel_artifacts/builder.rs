@@ -98,6 +98,7 @@ impl Builder for VercelArtifactsBuilder {
     const SCHEME: Scheme = Scheme::VercelArtifacts;
 
     type Accessor = VercelArtifactsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &["access_token"];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = Self::default();
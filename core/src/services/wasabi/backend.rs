@@ -701,6 +701,26 @@ impl WasabiBuilder {
 impl Builder for WasabiBuilder {
     const SCHEME: Scheme = Scheme::Wasabi;
     type Accessor = WasabiBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "bucket",
+        "endpoint",
+        "region",
+        "access_key_id",
+        "secret_access_key",
+        "security_token",
+        "role_arn",
+        "external_id",
+        "server_side_encryption",
+        "server_side_encryption_aws_kms_key_id",
+        "server_side_encryption_customer_algorithm",
+        "server_side_encryption_customer_key",
+        "server_side_encryption_customer_key_md5",
+        "disable_config_load",
+        "disable_ec2_metadata",
+        "enable_virtual_host_style",
+        "default_storage_class",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = WasabiBuilder::default();
@@ -747,14 +767,21 @@ impl Builder for WasabiBuilder {
         debug!("backend use root {}", &root);
 
         // Handle bucket name.
-        let bucket = if self.is_bucket_valid() {
-            Ok(&self.bucket)
-        } else {
-            Err(
-                Error::new(ErrorKind::ConfigInvalid, "The bucket is misconfigured")
-                    .with_context("service", Scheme::S3),
+        if self.bucket.is_empty() {
+            return Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Wasabi));
+        }
+        if !self.is_bucket_valid() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "bucket can't contain dot(.) while virtual host style is enabled",
             )
-        }?;
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Wasabi)
+            .with_context("bucket", &self.bucket));
+        }
+        let bucket = &self.bucket;
         debug!("backend use bucket {}", &bucket);
 
         let default_storage_class = match &self.default_storage_class {
@@ -927,6 +954,7 @@ impl Accessor for WasabiBackend {
                 presign_stat: true,
                 presign_read: true,
                 presign_write: true,
+                presign_max_expire: Some(MAX_PRESIGN_EXPIRE),
 
                 batch: true,
 
@@ -1179,4 +1207,13 @@ mod tests {
             assert_eq!(endpoint, "https://test.s3.us-east-2.wasabisys.com");
         }
     }
+
+    #[test]
+    fn test_build_without_bucket() {
+        let mut b = WasabiBuilder::default();
+        b.region("us-east-1");
+        let err = b.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+    }
 }
@@ -39,6 +39,9 @@ use serde::Serialize;
 use crate::raw::*;
 use crate::*;
 
+/// The max expire duration of SigV4 presigned urls.
+pub const MAX_PRESIGN_EXPIRE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 mod constants {
     pub const X_AMZ_COPY_SOURCE: &str = "x-amz-copy-source";
 
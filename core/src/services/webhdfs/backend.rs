@@ -17,6 +17,8 @@
 
 use core::fmt::Debug;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use http::header::CONTENT_LENGTH;
@@ -24,6 +26,7 @@ use http::header::CONTENT_TYPE;
 use http::Request;
 use http::Response;
 use http::StatusCode;
+use http::Uri;
 use log::debug;
 use tokio::sync::OnceCell;
 
@@ -31,7 +34,8 @@ use super::error::parse_error;
 use super::message::BooleanResp;
 use super::message::FileStatusType;
 use super::message::FileStatusWrapper;
-use super::message::FileStatusesWrapper;
+use super::message::LocationResp;
+use super::pager::status_to_metadata;
 use super::pager::WebhdfsPager;
 use super::writer::WebhdfsWriter;
 use crate::ops::*;
@@ -72,9 +76,22 @@ const WEBHDFS_DEFAULT_ENDPOINT: &str = "http://127.0.0.1:9870";
 /// - `root`: The root path of the WebHDFS service.
 /// - `endpoint`: The endpoint of the WebHDFS service.
 /// - `delegation`: The delegation token for WebHDFS.
+/// - `datanode_endpoint`: Override the endpoint of DataNodes redirected to.
+/// - `enable_noredirect`: Get DataNode locations via `noredirect=true`.
 ///
 /// Refer to [`Builder`]'s public API docs for more information
 ///
+/// # Redirect
+///
+/// WebHDFS reads and writes in two steps: the NameNode redirects `OPEN`
+/// and `CREATE` to a DataNode via `307` with the location in header, or
+/// in the JSON body with `noredirect=true`. Both are supported, and `OPEN`
+/// served directly by gateways like HttpFS works too.
+///
+/// DataNode hostnames are usually not resolvable outside of the cluster,
+/// use [`WebhdfsBuilder::datanode_endpoint`] to send requests to a proxy
+/// instead.
+///
 /// # Examples
 ///
 /// ## Via Builder
@@ -113,6 +130,8 @@ pub struct WebhdfsBuilder {
     root: Option<String>,
     endpoint: Option<String>,
     delegation: Option<String>,
+    datanode_endpoint: Option<String>,
+    enable_noredirect: bool,
 }
 
 impl Debug for WebhdfsBuilder {
@@ -120,6 +139,8 @@ impl Debug for WebhdfsBuilder {
         f.debug_struct("Builder")
             .field("root", &self.root)
             .field("endpoint", &self.endpoint)
+            .field("datanode_endpoint", &self.datanode_endpoint)
+            .field("enable_noredirect", &self.enable_noredirect)
             .finish_non_exhaustive()
     }
 }
@@ -161,20 +182,50 @@ impl WebhdfsBuilder {
     /// Set the delegation token of this backend,
     /// used for authentication
     ///
-    /// # Note
-    /// The builder prefers using delegation token over username.
-    /// If both are set, delegation token will be used.
+    /// The token will be sent as the `delegation` parameter of every
+    /// request, which is required by secured clusters without SPNEGO.
     pub fn delegation(&mut self, delegation: &str) -> &mut Self {
         if !delegation.is_empty() {
             self.delegation = Some(delegation.to_string());
         }
         self
     }
+
+    /// Set the endpoint that requests redirected to DataNodes will be sent
+    /// to, for example a proxy in front of the cluster.
+    ///
+    /// The scheme, host and port of redirect locations will be replaced by
+    /// this endpoint, while the path and query are kept.
+    ///
+    /// If user inputs endpoint without scheme, we will prepend `http://`
+    /// to it.
+    pub fn datanode_endpoint(&mut self, endpoint: &str) -> &mut Self {
+        if !endpoint.is_empty() {
+            self.datanode_endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        }
+        self
+    }
+
+    /// Ask the NameNode to return the DataNode location in the JSON body
+    /// instead of a `307` redirect via `noredirect=true`.
+    ///
+    /// Only supported since Hadoop 3.
+    pub fn enable_noredirect(&mut self) -> &mut Self {
+        self.enable_noredirect = true;
+        self
+    }
 }
 
 impl Builder for WebhdfsBuilder {
     const SCHEME: Scheme = Scheme::Webhdfs;
     type Accessor = WebhdfsBackend;
+    const CONFIG_KEYS: &'static [&'static str] = &[
+        "root",
+        "endpoint",
+        "delegation",
+        "datanode_endpoint",
+        "enable_noredirect",
+    ];
 
     fn from_map(map: HashMap<String, String>) -> Self {
         let mut builder = WebhdfsBuilder::default();
@@ -182,6 +233,11 @@ impl Builder for WebhdfsBuilder {
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
         map.get("delegation").map(|v| builder.delegation(v));
+        map.get("datanode_endpoint")
+            .map(|v| builder.datanode_endpoint(v));
+        map.get("enable_noredirect")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_noredirect());
 
         builder
     }
@@ -212,10 +268,18 @@ impl Builder for WebhdfsBuilder {
         };
         debug!("backend use endpoint {}", endpoint);
 
+        let datanode_endpoint = self.datanode_endpoint.take().map(|endpoint| {
+            if endpoint.starts_with("http") {
+                endpoint
+            } else {
+                format!("http://{endpoint}")
+            }
+        });
+
         let auth = self
             .delegation
             .take()
-            .map(|dt| format!("delegation_token={dt}"));
+            .map(|dt| format!("delegation={}", percent_encode_path(&dt)));
 
         let client = HttpClient::new()?;
 
@@ -223,8 +287,11 @@ impl Builder for WebhdfsBuilder {
             root,
             endpoint,
             auth,
+            datanode_endpoint,
+            noredirect: self.enable_noredirect,
             client,
             root_checker: OnceCell::new(),
+            list_batch_disabled: Arc::new(AtomicBool::new(false)),
         };
 
         Ok(backend)
@@ -237,7 +304,11 @@ pub struct WebhdfsBackend {
     root: String,
     endpoint: String,
     auth: Option<String>,
+    datanode_endpoint: Option<String>,
+    noredirect: bool,
     root_checker: OnceCell<()>,
+    /// Set if the NameNode doesn't support LISTSTATUS_BATCH.
+    pub(super) list_batch_disabled: Arc<AtomicBool>,
 
     pub client: HttpClient,
 }
@@ -250,7 +321,7 @@ impl WebhdfsBackend {
         &self,
         path: &str,
         size: Option<usize>,
-        content_type: Option<&str>,
+        args: &OpWrite,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -268,6 +339,18 @@ impl WebhdfsBackend {
         if let Some(auth) = &self.auth {
             url += format!("&{auth}").as_str();
         }
+        if self.noredirect && !path.ends_with('/') {
+            url += "&noredirect=true";
+        }
+        if let Some(mode) = args.unix_permissions() {
+            url += &format!("&permission={mode:o}");
+        }
+        if let Some(replication) = args.replication() {
+            url += &format!("&replication={replication}");
+        }
+        if let Some(block_size) = args.block_size() {
+            url += &format!("&blocksize={block_size}");
+        }
 
         let req = Request::put(&url)
             .body(AsyncBody::Empty)
@@ -280,17 +363,17 @@ impl WebhdfsBackend {
 
         let resp = self.client.send(req).await?;
 
-        // should be a 307 TEMPORARY_REDIRECT
-        if resp.status() != StatusCode::TEMPORARY_REDIRECT {
-            return Err(parse_error(resp).await?);
-        }
-        let re_url = self.follow_redirect(resp)?;
+        // should be a 307 TEMPORARY_REDIRECT, or 200 OK with noredirect
+        let re_url = match resp.status() {
+            StatusCode::TEMPORARY_REDIRECT | StatusCode::OK => self.follow_redirect(resp).await?,
+            _ => return Err(parse_error(resp).await?),
+        };
 
         let mut re_builder = Request::put(re_url);
         if let Some(size) = size {
             re_builder = re_builder.header(CONTENT_LENGTH, size.to_string());
         }
-        if let Some(content_type) = content_type {
+        if let Some(content_type) = args.content_type() {
             re_builder = re_builder.header(CONTENT_TYPE, content_type);
         }
 
@@ -311,16 +394,11 @@ impl WebhdfsBackend {
         if let Some(auth) = &self.auth {
             url += &format!("&{auth}");
         }
+        if self.noredirect {
+            url += "&noredirect=true";
+        }
 
         if !range.is_full() {
-            // Webhdfs does not support read from end
-            if range.offset().is_none() && range.size().is_some() {
-                return Err(Error::new(
-                    ErrorKind::Unsupported,
-                    "webhdfs doesn't support read with suffix range",
-                ));
-            };
-
             if let Some(offset) = range.offset() {
                 url += &format!("&offset={offset}");
             }
@@ -336,7 +414,7 @@ impl WebhdfsBackend {
         Ok(req)
     }
 
-    fn webhdfs_list_status_request(&self, path: &str) -> Result<Request<AsyncBody>> {
+    pub(super) fn webhdfs_list_status_request(&self, path: &str) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
         let mut url = format!(
             "{}/webhdfs/v1/{}?op=LISTSTATUS",
@@ -353,6 +431,30 @@ impl WebhdfsBackend {
         Ok(req)
     }
 
+    pub(super) fn webhdfs_list_status_batch_request(
+        &self,
+        path: &str,
+        start_after: &str,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let mut url = format!(
+            "{}/webhdfs/v1/{}?op=LISTSTATUS_BATCH",
+            self.endpoint,
+            percent_encode_path(&p),
+        );
+        if !start_after.is_empty() {
+            url += format!("&startAfter={}", percent_encode_path(start_after)).as_str();
+        }
+        if let Some(auth) = &self.auth {
+            url += format!("&{auth}").as_str();
+        }
+
+        let req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+        Ok(req)
+    }
+
     async fn webhdfs_read_file(
         &self,
         path: &str,
@@ -361,12 +463,13 @@ impl WebhdfsBackend {
         let req = self.webhdfs_open_request(path, &range).await?;
         let resp = self.client.send(req).await?;
 
-        // webhdfs namenode will redirect us to datanode for data transfer.
-        if resp.status() != StatusCode::TEMPORARY_REDIRECT {
-            return Err(parse_error(resp).await?);
-        }
-
-        let location = self.follow_redirect(resp)?;
+        // webhdfs namenode will redirect us to datanode for data transfer,
+        // while gateways like HttpFS could serve the data directly.
+        let location = match resp.status() {
+            StatusCode::TEMPORARY_REDIRECT => self.follow_redirect(resp).await?,
+            StatusCode::OK if self.noredirect => self.follow_redirect(resp).await?,
+            _ => return Ok(resp),
+        };
         let req = Request::get(&location)
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -392,6 +495,58 @@ impl WebhdfsBackend {
         self.client.send(req).await
     }
 
+    async fn webhdfs_set_permission(
+        &self,
+        path: &str,
+        mode: u32,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let mut url = format!(
+            "{}/webhdfs/v1/{}?op=SETPERMISSION&permission={:o}",
+            self.endpoint,
+            percent_encode_path(&p),
+            mode,
+        );
+        if let Some(auth) = &self.auth {
+            url += format!("&{auth}").as_str();
+        }
+
+        let req = Request::put(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
+    async fn webhdfs_set_owner(
+        &self,
+        path: &str,
+        owner: Option<&str>,
+        group: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let mut url = format!(
+            "{}/webhdfs/v1/{}?op=SETOWNER",
+            self.endpoint,
+            percent_encode_path(&p),
+        );
+        if let Some(owner) = owner {
+            url += format!("&owner={}", percent_encode_path(owner)).as_str();
+        }
+        if let Some(group) = group {
+            url += format!("&group={}", percent_encode_path(group)).as_str();
+        }
+        if let Some(auth) = &self.auth {
+            url += format!("&{auth}").as_str();
+        }
+
+        let req = Request::put(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
     async fn webhdfs_delete(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
         let mut url = format!(
@@ -410,25 +565,52 @@ impl WebhdfsBackend {
         self.client.send(req).await
     }
 
-    /// get redirect destination from 307 TEMPORARY_REDIRECT http response
-    fn follow_redirect(&self, resp: Response<IncomingAsyncBody>) -> Result<String> {
-        let location = parse_location(resp.headers())?.ok_or_else(|| {
-            Error::new(
-                ErrorKind::Unexpected,
-                "webhdfs expect to have redirect location but got none",
-            )
-        })?;
+    /// get redirect destination from 307 TEMPORARY_REDIRECT http response,
+    /// or the JSON body of `noredirect=true` response.
+    async fn follow_redirect(&self, resp: Response<IncomingAsyncBody>) -> Result<String> {
+        let location = match parse_location(resp.headers())? {
+            Some(location) => location.to_string(),
+            None => {
+                let bs = resp.into_body().bytes().await?;
+                serde_json::from_slice::<LocationResp>(&bs)
+                    .map_err(|err| {
+                        Error::new(
+                            ErrorKind::Unexpected,
+                            "webhdfs expect to have redirect location but got none",
+                        )
+                        .set_source(err)
+                    })?
+                    .location
+            }
+        };
+
+        self.rewrite_location(&location)
+    }
 
-        let location = if location.starts_with('/') {
+    /// Rewrite the redirect location to make sure it's reachable.
+    fn rewrite_location(&self, location: &str) -> Result<String> {
+        if location.starts_with('/') {
             // location starts with `/` means it's a relative path to current
             // endpoint. We should prepend the endpoint to it so that we can
             // send request to the correct location.
-            format!("{}/{location}", self.endpoint)
-        } else {
-            location.to_string()
+            return Ok(format!("{}{location}", self.endpoint));
+        }
+
+        let datanode_endpoint = match &self.datanode_endpoint {
+            Some(endpoint) => endpoint,
+            None => return Ok(location.to_string()),
         };
 
-        Ok(location)
+        let uri = location.parse::<Uri>().map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "webhdfs redirect location is invalid",
+            )
+            .with_context("location", location)
+            .set_source(err)
+        })?;
+        let path_and_query = uri.path_and_query().map(|v| v.as_str()).unwrap_or("/");
+        Ok(format!("{datanode_endpoint}{path_and_query}"))
     }
 
     async fn check_root(&self) -> Result<()> {
@@ -478,12 +660,18 @@ impl Accessor for WebhdfsBackend {
                 read_with_range: true,
 
                 write: true,
+                write_with_unix_permissions: true,
+                write_with_replication: true,
+                write_with_block_size: true,
                 create_dir: true,
                 delete: true,
 
                 list: true,
                 list_with_delimiter_slash: true,
 
+                set_permissions: true,
+                set_owner: true,
+
                 ..Default::default()
             });
         am
@@ -492,7 +680,7 @@ impl Accessor for WebhdfsBackend {
     /// Create a file or directory
     async fn create_dir(&self, path: &str, _: OpCreateDir) -> Result<RpCreateDir> {
         let req = self
-            .webhdfs_create_object_request(path, Some(0), None, AsyncBody::Empty)
+            .webhdfs_create_object_request(path, Some(0), &OpWrite::default(), AsyncBody::Empty)
             .await?;
 
         let resp = self.client.send(req).await?;
@@ -524,11 +712,35 @@ impl Accessor for WebhdfsBackend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let range = args.range();
+        let mut range = args.range();
+
+        // WebHDFS doesn't support read from end, so we resolve suffix range
+        // into offset and length with the file size.
+        let mut content_range = None;
+        if range.offset().is_none() && range.size().is_some() {
+            let size = self
+                .stat(path, OpStat::new())
+                .await?
+                .into_metadata()
+                .content_length();
+            let resolved = range.resolve(size)?;
+            range = BytesRange::new(Some(resolved.start), Some(resolved.end - resolved.start));
+            // Always carry the total size so that callers don't need to
+            // stat again.
+            let mut cr = BytesContentRange::default().with_size(size);
+            if !resolved.is_empty() {
+                cr = cr.with_range(resolved.start, resolved.end - 1);
+            }
+            content_range = Some(cr);
+        }
+
         let resp = self.webhdfs_read_file(path, range).await?;
         match resp.status() {
             StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
-                let meta = parse_into_metadata(path, resp.headers())?;
+                let mut meta = parse_into_metadata(path, resp.headers())?;
+                if let Some(content_range) = content_range {
+                    meta.set_content_range(content_range);
+                }
                 Ok((RpRead::with_metadata(meta), resp.into_body()))
             }
             _ => Err(parse_error(resp).await?),
@@ -565,16 +777,7 @@ impl Accessor for WebhdfsBackend {
                     .map_err(new_json_deserialize_error)?
                     .file_status;
 
-                let meta = match file_status.ty {
-                    FileStatusType::Directory => Metadata::new(EntryMode::DIR),
-                    FileStatusType::File => Metadata::new(EntryMode::FILE)
-                        .with_content_length(file_status.length)
-                        .with_last_modified(parse_datetime_from_from_timestamp_millis(
-                            file_status.modification_time,
-                        )?),
-                };
-
-                Ok(RpStat::new(meta))
+                Ok(RpStat::new(status_to_metadata(&file_status)?))
             }
 
             _ => Err(parse_error(resp).await?),
@@ -602,25 +805,314 @@ impl Accessor for WebhdfsBackend {
         }
 
         let path = path.trim_end_matches('/');
-        let req = self.webhdfs_list_status_request(path)?;
 
-        let resp = self.client.send(req).await?;
+        Ok((RpList::default(), WebhdfsPager::new(self.clone(), path)))
+    }
+
+    async fn set_permissions(
+        &self,
+        path: &str,
+        args: OpSetPermissions,
+    ) -> Result<RpSetPermissions> {
+        let resp = self.webhdfs_set_permission(path, args.mode()).await?;
+
         match resp.status() {
             StatusCode::OK => {
-                let bs = resp.into_body().bytes().await?;
-                let file_statuses = serde_json::from_slice::<FileStatusesWrapper>(&bs)
-                    .map_err(new_json_deserialize_error)?
-                    .file_statuses
-                    .file_status;
-
-                let objects = WebhdfsPager::new(path, file_statuses);
-                Ok((RpList::default(), objects))
+                resp.into_body().consume().await?;
+                Ok(RpSetPermissions::default())
             }
-            StatusCode::NOT_FOUND => {
-                let objects = WebhdfsPager::new(path, vec![]);
-                Ok((RpList::default(), objects))
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<RpSetOwner> {
+        let resp = self
+            .webhdfs_set_owner(path, args.owner(), args.group())
+            .await?;
+
+        match resp.status() {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpSetOwner::default())
             }
             _ => Err(parse_error(resp).await?),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::body_string;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::matchers::query_param_is_missing;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    /// Location of a DataNode that is not resolvable.
+    const DATANODE_LOCATION: &str =
+        "http://datanode.internal:9864/webhdfs/v1/file?op=OPEN&namenoderpcaddress=nn:8020&offset=0";
+
+    async fn new_operator(
+        namenode: &MockServer,
+        datanode: &MockServer,
+        noredirect: bool,
+    ) -> Operator {
+        let mut builder = WebhdfsBuilder::default();
+        builder
+            .endpoint(&namenode.uri())
+            .datanode_endpoint(&datanode.uri());
+        if noredirect {
+            builder.enable_noredirect();
+        }
+
+        Operator::new(builder).expect("build must succeed").finish()
+    }
+
+    #[tokio::test]
+    async fn test_read_with_datanode_endpoint() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "OPEN"))
+            .and(query_param_is_missing("noredirect"))
+            .respond_with(ResponseTemplate::new(307).insert_header("location", DATANODE_LOCATION))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("namenoderpcaddress", "nn:8020"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+            .expect(2)
+            .mount(&datanode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, false).await;
+        assert_eq!(op.read("file").await?, b"hello");
+
+        // Location without header should be read from body.
+        namenode.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .respond_with(
+                ResponseTemplate::new(307).set_body_json(json!({ "Location": DATANODE_LOCATION })),
+            )
+            .expect(1)
+            .mount(&namenode)
+            .await;
+        assert_eq!(op.read("file").await?, b"hello");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_noredirect() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "CREATE"))
+            .and(query_param("noredirect", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "Location": "http://datanode.internal:9864/webhdfs/v1/file?op=CREATE&namenoderpcaddress=nn:8020&overwrite=true"
+            })))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("namenoderpcaddress", "nn:8020"))
+            .and(body_string("hello"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&datanode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, true).await;
+        op.write("file", "hello").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_replication() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "CREATE"))
+            .and(query_param("permission", "640"))
+            .and(query_param("replication", "2"))
+            .and(query_param("blocksize", "67108864"))
+            .respond_with(ResponseTemplate::new(307).insert_header(
+                "location",
+                "http://datanode.internal:9864/webhdfs/v1/file?op=CREATE&namenoderpcaddress=nn:8020&replication=2",
+            ))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("replication", "2"))
+            .and(body_string("hello"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&datanode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, false).await;
+        op.write_with(
+            "file",
+            OpWrite::new()
+                .with_unix_permissions(0o640)
+                .with_replication(2)
+                .with_block_size(64 * 1024 * 1024),
+            "hello",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_permissions_and_owner() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "SETPERMISSION"))
+            .and(query_param("permission", "1777"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "SETOWNER"))
+            .and(query_param("group", "hadoop"))
+            .and(query_param_is_missing("owner"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, false).await;
+        op.set_permissions("file", 0o1777).await?;
+        op.set_owner("file", OpSetOwner::new().with_group("hadoop"))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_with_suffix() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/"))
+            .and(query_param("op", "GETFILESTATUS"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "FileStatus": {"length": 0, "modificationTime": 0, "pathSuffix": "", "type": "DIRECTORY"}
+            })))
+            .mount(&namenode)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "GETFILESTATUS"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "FileStatus": {"length": 10, "modificationTime": 0, "pathSuffix": "", "type": "FILE"}
+            })))
+            .expect(3)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "OPEN"))
+            .and(query_param("offset", "6"))
+            .and(query_param("length", "4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("6789"))
+            .expect(2)
+            .mount(&namenode)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "OPEN"))
+            .and(query_param("offset", "0"))
+            .and(query_param("length", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("0123456789"))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, false).await;
+        let r = op.reader_with("file", OpRead::new().with_suffix(4)).await?;
+        assert_eq!(
+            r.content_range().and_then(|v| v.range()),
+            Some(6..10),
+            "suffix range"
+        );
+        assert_eq!(op.range_read("file", ..4).await?, b"6789");
+
+        // Suffix larger than the file should read the whole file.
+        let r = op
+            .reader_with("file", OpRead::new().with_suffix(100))
+            .await?;
+        assert_eq!(r.content_range().and_then(|v| v.range()), Some(0..10));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_served_directly() -> Result<()> {
+        let namenode = MockServer::start().await;
+        let datanode = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("op", "OPEN"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+            .expect(1)
+            .mount(&namenode)
+            .await;
+
+        let op = new_operator(&namenode, &datanode, false).await;
+        assert_eq!(op.read("file").await?, b"hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_location() {
+        let mut builder = WebhdfsBuilder::default();
+        builder.endpoint("http://namenode:9870");
+        let backend = builder.build().expect("build must succeed");
+        assert_eq!(
+            backend
+                .rewrite_location("/webhdfs/v1/file?op=OPEN")
+                .expect("rewrite must succeed"),
+            "http://namenode:9870/webhdfs/v1/file?op=OPEN"
+        );
+        assert_eq!(
+            backend
+                .rewrite_location(DATANODE_LOCATION)
+                .expect("rewrite must succeed"),
+            DATANODE_LOCATION
+        );
+
+        let mut builder = WebhdfsBuilder::default();
+        builder.datanode_endpoint("proxy:8080/");
+        let backend = builder.build().expect("build must succeed");
+        assert_eq!(
+            backend
+                .rewrite_location(DATANODE_LOCATION)
+                .expect("rewrite must succeed"),
+            "http://proxy:8080/webhdfs/v1/file?op=OPEN&namenoderpcaddress=nn:8020&offset=0"
+        );
+    }
+}
@@ -46,9 +46,27 @@ pub(super) async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Err
     parse_error_msg(parts, &s)
 }
 
-fn parse_error_msg(parts: Parts, body: &str) -> Result<Error> {
+/// Check if the error body means the op is not supported by the
+/// NameNode, for example LISTSTATUS_BATCH before Hadoop 2.8.
+pub(super) fn is_unsupported_op(body: &[u8], op: &str) -> bool {
+    match serde_json::from_slice::<WebHdfsErrorWrapper>(body) {
+        Ok(v) => {
+            let e = v.remote_exception;
+            e.exception == "UnsupportedOperationException"
+                || (e.exception == "IllegalArgumentException" && e.message.contains(op))
+        }
+        Err(_) => false,
+    }
+}
+
+pub(super) fn parse_error_msg(parts: Parts, body: &str) -> Result<Error> {
+    let remote_exception = serde_json::from_str::<WebHdfsErrorWrapper>(body)
+        .map(|v| v.remote_exception)
+        .ok();
+
     let (kind, retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
+        StatusCode::RANGE_NOT_SATISFIABLE => (ErrorKind::RangeNotSatisfied, false),
         StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
         // passing invalid arguments will return BAD_REQUEST
         // should be unretryable
@@ -60,12 +78,20 @@ fn parse_error_msg(parts: Parts, body: &str) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
 
-    let message = match serde_json::from_str::<WebHdfsErrorWrapper>(body) {
-        Ok(wh_error) => format!("{:?}", wh_error.remote_exception),
-        Err(_) => body.to_owned(),
+    // Security exceptions like invalid or expired delegation tokens are
+    // not always returned with 401 or 403.
+    let kind = match remote_exception.as_ref().map(|v| v.exception.as_str()) {
+        Some("SecurityException" | "AccessControlException" | "InvalidToken") => {
+            ErrorKind::PermissionDenied
+        }
+        _ => kind,
     };
 
-    let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
+    let mut err = match &remote_exception {
+        Some(v) => Error::new(kind, &v.message).with_context("exception", &v.java_class_name),
+        None => Error::new(kind, body),
+    }
+    .with_context("response", format!("{parts:?}"));
 
     if retryable {
         err = err.set_temporary();
@@ -122,4 +148,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_parse_error_permission_denied() -> Result<()> {
+        let cases = vec![
+            (
+                StatusCode::UNAUTHORIZED,
+                "SecurityException",
+                "java.lang.SecurityException",
+                "Failed to obtain user group information",
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "AccessControlException",
+                "org.apache.hadoop.security.AccessControlException",
+                "Permission denied: user=dr.who, access=WRITE",
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                "InvalidToken",
+                "org.apache.hadoop.security.token.SecretManager$InvalidToken",
+                "token (HDFS_DELEGATION_TOKEN token 7 for hdfs) is expired",
+            ),
+        ];
+
+        for (status, exception, java_class_name, message) in cases {
+            let body = serde_json::json!({
+                "RemoteException": {
+                    "exception": exception,
+                    "javaClassName": java_class_name,
+                    "message": message,
+                }
+            });
+            let body = IncomingAsyncBody::new(
+                Box::new(stream::iter(vec![Ok(bytes::Bytes::from(body.to_string()))])),
+                None,
+            );
+            let resp = Response::builder().status(status).body(body).unwrap();
+
+            let err = parse_error(resp).await?;
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied, "{exception}");
+            assert!(err.to_string().contains(message), "{exception}: {err}");
+            assert!(
+                err.to_string().contains(java_class_name),
+                "{exception}: {err}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_unsupported_op() {
+        let cases = vec![
+            (
+                r#"{"RemoteException":{"exception":"IllegalArgumentException","javaClassName":"java.lang.IllegalArgumentException","message":"Invalid value for webhdfs parameter \"op\": No enum constant org.apache.hadoop.hdfs.web.resources.GetOpParam.Op.LISTSTATUS_BATCH"}}"#,
+                true,
+            ),
+            (
+                r#"{"RemoteException":{"exception":"UnsupportedOperationException","javaClassName":"java.lang.UnsupportedOperationException","message":"LISTSTATUS_BATCH is not supported"}}"#,
+                true,
+            ),
+            (
+                r#"{"RemoteException":{"exception":"IllegalArgumentException","javaClassName":"java.lang.IllegalArgumentException","message":"Invalid value for webhdfs parameter \"startAfter\""}}"#,
+                false,
+            ),
+            ("Bad Request", false),
+        ];
+
+        for (body, expected) in cases {
+            assert_eq!(
+                is_unsupported_op(body.as_bytes(), "LISTSTATUS_BATCH"),
+                expected,
+                "{body}"
+            );
+        }
+    }
 }
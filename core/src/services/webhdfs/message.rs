@@ -24,6 +24,12 @@ pub(super) struct BooleanResp {
     pub boolean: bool,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct LocationResp {
+    pub location: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub(super) struct FileStatusWrapper {
@@ -42,6 +48,19 @@ pub(super) struct FileStatuses {
     pub file_status: Vec<FileStatus>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct DirectoryListingWrapper {
+    pub directory_listing: DirectoryListing,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct DirectoryListing {
+    pub partial_listing: FileStatusesWrapper,
+    pub remaining_entries: u32,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileStatus {
@@ -51,6 +70,20 @@ pub struct FileStatus {
     pub path_suffix: String,
     #[serde(rename = "type")]
     pub ty: FileStatusType,
+
+    /// Permission in octal like `755`.
+    #[serde(default)]
+    pub permission: String,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub group: String,
+    /// Replication factor, always `0` for directories.
+    #[serde(default)]
+    pub replication: u16,
+    /// Block size, always `0` for directories.
+    #[serde(default)]
+    pub block_size: u64,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -63,8 +96,7 @@ pub enum FileStatusType {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::raw::oio::Page;
-    use crate::services::webhdfs::pager::WebhdfsPager;
+    use crate::services::webhdfs::pager::status_to_entry;
     use crate::EntryMode;
 
     #[test]
@@ -91,6 +123,11 @@ mod test {
         assert_eq!(status.file_status.modification_time, 1320173277227);
         assert_eq!(status.file_status.path_suffix, "");
         assert_eq!(status.file_status.ty, FileStatusType::Directory);
+        assert_eq!(status.file_status.permission, "777");
+        assert_eq!(status.file_status.owner, "webuser");
+        assert_eq!(status.file_status.group, "supergroup");
+        assert_eq!(status.file_status.replication, 0);
+        assert_eq!(status.file_status.block_size, 0);
     }
 
     #[tokio::test]
@@ -149,17 +186,97 @@ mod test {
             .file_statuses
             .file_status;
 
-        let mut pager = WebhdfsPager::new("listing/directory", file_statuses);
-        let mut entries = vec![];
-        while let Some(oes) = pager.next().await.expect("must success") {
-            entries.extend(oes);
-        }
+        let entries = file_statuses
+            .into_iter()
+            .map(|v| status_to_entry("listing/directory", v))
+            .collect::<crate::Result<Vec<_>>>()
+            .expect("must success");
 
-        entries.sort_by(|a, b| a.path().cmp(b.path()));
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].path(), "listing/directory/a.patch");
         assert_eq!(entries[0].mode(), EntryMode::FILE);
+        assert_eq!(entries[0].metadata().content_length(), 24930);
+        assert_eq!(entries[0].metadata().unix_permissions(), Some(0o644));
+        assert_eq!(entries[0].metadata().owner(), Some("webuser"));
+        assert_eq!(entries[0].metadata().group(), Some("supergroup"));
+        assert_eq!(entries[0].metadata().replication(), Some(1));
+        assert_eq!(entries[0].metadata().block_size(), Some(33554432));
+        assert_eq!(
+            entries[0]
+                .metadata()
+                .last_modified()
+                .map(|v| v.timestamp_millis()),
+            Some(1320171722771)
+        );
         assert_eq!(entries[1].path(), "listing/directory/bar/");
         assert_eq!(entries[1].mode(), EntryMode::DIR);
+        assert_eq!(entries[1].metadata().unix_permissions(), Some(0o711));
+        assert_eq!(entries[1].metadata().owner(), Some("szetszwo"));
+        assert_eq!(
+            entries[1]
+                .metadata()
+                .last_modified()
+                .map(|v| v.timestamp_millis()),
+            Some(1320895981256)
+        );
+    }
+
+    #[test]
+    fn test_directory_listing() {
+        let json = r#"
+{
+  "DirectoryListing": {
+    "partialListing": {
+      "FileStatuses": {
+        "FileStatus": [
+          {
+            "accessTime": 0,
+            "blockSize": 0,
+            "childrenNum": 0,
+            "fileId": 16389,
+            "group": "supergroup",
+            "length": 0,
+            "modificationTime": 1473305882563,
+            "owner": "andrew",
+            "pathSuffix": "bardir",
+            "permission": "755",
+            "replication": 0,
+            "storagePolicy": 0,
+            "type": "DIRECTORY"
+          },
+          {
+            "accessTime": 1473305896945,
+            "blockSize": 1024,
+            "childrenNum": 0,
+            "fileId": 16388,
+            "group": "supergroup",
+            "length": 0,
+            "modificationTime": 1473305896965,
+            "owner": "andrew",
+            "pathSuffix": "bazfile",
+            "permission": "644",
+            "replication": 3,
+            "storagePolicy": 0,
+            "type": "FILE"
+          }
+        ]
+      }
+    },
+    "remainingEntries": 2
+  }
+}
+"#;
+
+        let listing = serde_json::from_str::<DirectoryListingWrapper>(json)
+            .expect("must success")
+            .directory_listing;
+        assert_eq!(listing.remaining_entries, 2);
+
+        let statuses = listing.partial_listing.file_statuses.file_status;
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].path_suffix, "bardir");
+        assert_eq!(statuses[0].ty, FileStatusType::Directory);
+        assert_eq!(statuses[1].path_suffix, "bazfile");
+        assert_eq!(statuses[1].ty, FileStatusType::File);
     }
 }
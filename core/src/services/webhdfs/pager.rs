@@ -15,23 +15,102 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::sync::atomic::Ordering;
+
 use async_trait::async_trait;
+use http::StatusCode;
+use log::debug;
 
+use super::backend::WebhdfsBackend;
+use super::error::is_unsupported_op;
+use super::error::parse_error;
+use super::error::parse_error_msg;
+use super::message::DirectoryListingWrapper;
 use super::message::FileStatus;
 use super::message::FileStatusType;
+use super::message::FileStatusesWrapper;
 use crate::raw::*;
 use crate::*;
 
+/// WebhdfsPager lists dir page by page via LISTSTATUS_BATCH, and falls
+/// back to LISTSTATUS if the NameNode doesn't support it.
 pub struct WebhdfsPager {
+    backend: WebhdfsBackend,
     path: String,
-    statuses: Vec<FileStatus>,
+
+    /// `pathSuffix` of the last entry returned, used as `startAfter`.
+    start_after: String,
+    done: bool,
 }
 
 impl WebhdfsPager {
-    pub fn new(path: &str, statuses: Vec<FileStatus>) -> Self {
+    pub fn new(backend: WebhdfsBackend, path: &str) -> Self {
         Self {
+            backend,
             path: path.to_string(),
-            statuses,
+            start_after: String::new(),
+            done: false,
+        }
+    }
+
+    /// List the whole dir via LISTSTATUS.
+    async fn list_status(&mut self) -> Result<Vec<FileStatus>> {
+        self.done = true;
+
+        let req = self.backend.webhdfs_list_status_request(&self.path)?;
+        let resp = self.backend.client.send(req).await?;
+        match resp.status() {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let statuses = serde_json::from_slice::<FileStatusesWrapper>(&bs)
+                    .map_err(new_json_deserialize_error)?
+                    .file_statuses
+                    .file_status;
+                Ok(statuses)
+            }
+            StatusCode::NOT_FOUND => Ok(vec![]),
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    /// List the next page via LISTSTATUS_BATCH.
+    ///
+    /// Returns `None` if LISTSTATUS_BATCH is not supported.
+    async fn list_status_batch(&mut self) -> Result<Option<Vec<FileStatus>>> {
+        let req = self
+            .backend
+            .webhdfs_list_status_batch_request(&self.path, &self.start_after)?;
+        let resp = self.backend.client.send(req).await?;
+        match resp.status() {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let listing = serde_json::from_slice::<DirectoryListingWrapper>(&bs)
+                    .map_err(new_json_deserialize_error)?
+                    .directory_listing;
+
+                let statuses = listing.partial_listing.file_statuses.file_status;
+                match statuses.last() {
+                    Some(status) if listing.remaining_entries > 0 => {
+                        self.start_after = status.path_suffix.clone()
+                    }
+                    _ => self.done = true,
+                }
+                Ok(Some(statuses))
+            }
+            StatusCode::NOT_FOUND => {
+                self.done = true;
+                Ok(Some(vec![]))
+            }
+            StatusCode::BAD_REQUEST => {
+                let (parts, body) = resp.into_parts();
+                let bs = body.bytes().await?;
+                if is_unsupported_op(&bs, "LISTSTATUS_BATCH") {
+                    return Ok(None);
+                }
+
+                Err(parse_error_msg(parts, &String::from_utf8_lossy(&bs))?)
+            }
+            _ => Err(parse_error(resp).await?),
         }
     }
 }
@@ -39,38 +118,178 @@ impl WebhdfsPager {
 #[async_trait]
 impl oio::Page for WebhdfsPager {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        if self.statuses.is_empty() {
+        if self.done {
             return Ok(None);
         }
 
-        let mut entries = Vec::with_capacity(self.statuses.len());
-
-        while let Some(status) = self.statuses.pop() {
-            let mut path = if self.path.is_empty() {
-                status.path_suffix.to_string()
-            } else {
-                format!("{}/{}", self.path, status.path_suffix)
-            };
-
-            let meta = match status.ty {
-                FileStatusType::Directory => Metadata::new(EntryMode::DIR),
-                FileStatusType::File => Metadata::new(EntryMode::FILE)
-                    .with_content_length(status.length)
-                    .with_last_modified(parse_datetime_from_from_timestamp_millis(
-                        status.modification_time,
-                    )?),
-            };
-
-            if meta.mode().is_file() {
-                path = path.trim_end_matches('/').to_string();
-            }
-            if meta.mode().is_dir() {
-                path += "/"
+        let statuses = if self.backend.list_batch_disabled.load(Ordering::Relaxed) {
+            self.list_status().await?
+        } else {
+            match self.list_status_batch().await? {
+                Some(statuses) => statuses,
+                None => {
+                    debug!("webhdfs doesn't support LISTSTATUS_BATCH, fallback to LISTSTATUS");
+                    self.backend
+                        .list_batch_disabled
+                        .store(true, Ordering::Relaxed);
+                    self.list_status().await?
+                }
             }
-            let entry = oio::Entry::new(&path, meta);
-            entries.push(entry);
-        }
+        };
+
+        let entries = statuses
+            .into_iter()
+            .map(|status| status_to_entry(&self.path, status))
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(Some(entries))
     }
 }
+
+/// Convert the file status of LISTSTATUS into an entry under `dir`.
+pub(super) fn status_to_entry(dir: &str, status: FileStatus) -> Result<oio::Entry> {
+    let meta = status_to_metadata(&status)?;
+
+    let path = if dir.is_empty() {
+        status.path_suffix
+    } else {
+        format!("{}/{}", dir, status.path_suffix)
+    };
+    let path = match status.ty {
+        FileStatusType::Directory => format!("{}/", path.trim_end_matches('/')),
+        FileStatusType::File => path.trim_end_matches('/').to_string(),
+    };
+
+    Ok(oio::Entry::new(&path, meta))
+}
+
+/// Convert the file status into metadata.
+pub(super) fn status_to_metadata(status: &FileStatus) -> Result<Metadata> {
+    let mut meta = match status.ty {
+        FileStatusType::Directory => Metadata::new(EntryMode::DIR),
+        FileStatusType::File => {
+            let mut meta = Metadata::new(EntryMode::FILE);
+            meta.set_content_length(status.length)
+                .set_replication(status.replication)
+                .set_block_size(status.block_size);
+            meta
+        }
+    };
+    meta.set_last_modified(parse_datetime_from_from_timestamp_millis(
+        status.modification_time,
+    )?);
+
+    // Gateways may omit these fields, leave them unset instead of empty.
+    if let Ok(mode) = u32::from_str_radix(&status.permission, 8) {
+        meta.set_unix_permissions(mode);
+    }
+    if !status.owner.is_empty() {
+        meta.set_owner(&status.owner);
+    }
+    if !status.group.is_empty() {
+        meta.set_group(&status.group);
+    }
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::matchers::query_param_is_missing;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+    use crate::services::Webhdfs;
+
+    fn file_status(name: &str) -> serde_json::Value {
+        json!({
+            "length": 1,
+            "modificationTime": 1473305896965i64,
+            "pathSuffix": name,
+            "type": "FILE"
+        })
+    }
+
+    async fn list(mock_server: &MockServer) -> Result<Vec<String>> {
+        let mut builder = Webhdfs::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+
+        op.list("dir/")
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_list_status_batch() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/dir"))
+            .and(query_param("op", "LISTSTATUS_BATCH"))
+            .and(query_param_is_missing("startAfter"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "DirectoryListing": {
+                    "partialListing": {"FileStatuses": {"FileStatus": [file_status("a")]}},
+                    "remainingEntries": 1
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/dir"))
+            .and(query_param("op", "LISTSTATUS_BATCH"))
+            .and(query_param("startAfter", "a"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "DirectoryListing": {
+                    "partialListing": {"FileStatuses": {"FileStatus": [file_status("b")]}},
+                    "remainingEntries": 0
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(list(&mock_server).await?, vec!["dir/a", "dir/b"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_status_batch_fallback() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/dir"))
+            .and(query_param("op", "LISTSTATUS_BATCH"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "RemoteException": {
+                    "exception": "IllegalArgumentException",
+                    "javaClassName": "java.lang.IllegalArgumentException",
+                    "message": "Invalid value for webhdfs parameter \"op\": No enum constant org.apache.hadoop.hdfs.web.resources.GetOpParam.Op.LISTSTATUS_BATCH"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/webhdfs/v1/dir"))
+            .and(query_param("op", "LISTSTATUS"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "FileStatuses": {"FileStatus": [file_status("a"), file_status("b")]}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(list(&mock_server).await?, vec!["dir/a", "dir/b"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Access control list of an object.
+///
+/// Services expose different parts of ACL, for example:
+///
+/// - s3 returns owner and explicit grants, canned ACL will be inferred
+///   from grants if they match one of the well-known canned ACLs.
+/// - oss returns owner and canned ACL only.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Acl {
+    owner: Option<AclOwner>,
+    canned_acl: Option<String>,
+    grants: Vec<AclGrant>,
+}
+
+impl Acl {
+    /// Create a new empty acl.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Owner of this object.
+    pub fn owner(&self) -> Option<&AclOwner> {
+        self.owner.as_ref()
+    }
+
+    /// Set owner of this object.
+    pub fn set_owner(&mut self, owner: AclOwner) -> &mut Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Canned ACL of this object like `private` and `public-read`.
+    ///
+    /// `None` means the canned ACL is unknown.
+    pub fn canned_acl(&self) -> Option<&str> {
+        self.canned_acl.as_deref()
+    }
+
+    /// Set canned ACL of this object.
+    pub fn set_canned_acl(&mut self, v: &str) -> &mut Self {
+        self.canned_acl = Some(v.to_string());
+        self
+    }
+
+    /// Explicit grants of this object.
+    pub fn grants(&self) -> &[AclGrant] {
+        &self.grants
+    }
+
+    /// Push a grant into this acl.
+    pub fn push_grant(&mut self, grant: AclGrant) -> &mut Self {
+        self.grants.push(grant);
+        self
+    }
+
+    /// Check if this object can be read by anonymous users.
+    pub fn is_public(&self) -> bool {
+        if matches!(
+            self.canned_acl(),
+            Some("public-read") | Some("public-read-write")
+        ) {
+            return true;
+        }
+
+        self.grants.iter().any(|v| {
+            v.grantee == AclGrantee::AllUsers
+                && matches!(
+                    v.permission,
+                    AclPermission::Read | AclPermission::FullControl
+                )
+        })
+    }
+}
+
+/// Owner of an object.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AclOwner {
+    id: String,
+    display_name: Option<String>,
+}
+
+impl AclOwner {
+    /// Create a new owner with id.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            display_name: None,
+        }
+    }
+
+    /// Set display name of this owner.
+    pub fn with_display_name(mut self, v: &str) -> Self {
+        self.display_name = Some(v.to_string());
+        self
+    }
+
+    /// ID of this owner.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Display name of this owner.
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+}
+
+/// A grant gives permission to grantee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AclGrant {
+    grantee: AclGrantee,
+    permission: AclPermission,
+}
+
+impl AclGrant {
+    /// Create a new grant.
+    pub fn new(grantee: AclGrantee, permission: AclPermission) -> Self {
+        Self {
+            grantee,
+            permission,
+        }
+    }
+
+    /// Grantee of this grant.
+    pub fn grantee(&self) -> &AclGrantee {
+        &self.grantee
+    }
+
+    /// Permission of this grant.
+    pub fn permission(&self) -> &AclPermission {
+        &self.permission
+    }
+}
+
+/// Grantee of an [`AclGrant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AclGrantee {
+    /// A user identified by id.
+    User {
+        /// ID of the user.
+        id: String,
+        /// Display name of the user.
+        display_name: Option<String>,
+    },
+    /// A user identified by email address.
+    Email(String),
+    /// Anyone, including anonymous users.
+    AllUsers,
+    /// Any authenticated user.
+    AuthenticatedUsers,
+    /// Other groups identified by uri.
+    Group(String),
+}
+
+/// Permission of an [`AclGrant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AclPermission {
+    /// Grantee can read the object.
+    Read,
+    /// Grantee can write the object.
+    Write,
+    /// Grantee can read the acl.
+    ReadAcp,
+    /// Grantee can write the acl.
+    WriteAcp,
+    /// Grantee has all permissions above.
+    FullControl,
+    /// Permissions not known by OpenDAL.
+    Other(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        let mut acl = Acl::new();
+        assert!(!acl.is_public());
+
+        acl.push_grant(AclGrant::new(
+            AclGrantee::AuthenticatedUsers,
+            AclPermission::Read,
+        ));
+        assert!(!acl.is_public());
+
+        acl.push_grant(AclGrant::new(AclGrantee::AllUsers, AclPermission::Read));
+        assert!(acl.is_public());
+
+        let mut acl = Acl::new();
+        acl.set_canned_acl("public-read");
+        assert!(acl.is_public());
+    }
+}
@@ -41,10 +41,33 @@ pub trait Builder: Default {
     const SCHEME: Scheme;
     /// The accessor that built by this builder.
     type Accessor: Accessor;
+    /// Keys that can be accepted by [`Builder::from_map`].
+    const CONFIG_KEYS: &'static [&'static str];
 
     /// Construct a builder from given map which contains several parameters needed by underlying service.
     fn from_map(map: HashMap<String, String>) -> Self;
 
+    /// Check that all keys in given map are listed in [`Builder::CONFIG_KEYS`],
+    /// so that typos in config won't be ignored silently.
+    fn check_config_keys(map: &HashMap<String, String>) -> Result<()> {
+        let mut unknown: Vec<&str> = map
+            .keys()
+            .map(|k| k.as_str())
+            .filter(|k| !Self::CONFIG_KEYS.contains(k))
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        unknown.sort_unstable();
+        Err(
+            Error::new(ErrorKind::ConfigInvalid, "config key is unknown")
+                .with_operation("Builder::from_map")
+                .with_context("service", Self::SCHEME)
+                .with_context("keys", unknown.join(",")),
+        )
+    }
+
     /// Construct a builder from given iterator.
     fn from_iter(iter: impl Iterator<Item = (String, String)>) -> Self
     where
@@ -79,6 +102,7 @@ impl Builder for () {
     const SCHEME: Scheme = Scheme::Custom("dummy");
 
     type Accessor = ();
+    const CONFIG_KEYS: &'static [&'static str] = &[];
 
     fn from_map(_: HashMap<String, String>) -> Self {}
 
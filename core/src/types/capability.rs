@@ -16,6 +16,7 @@
 // under the License.
 
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// Capability is used to describe what operations are supported
 /// by current Operator.
@@ -52,6 +53,14 @@ pub struct Capability {
     pub stat_with_if_match: bool,
     /// If operator supports stat with if none match natively, it will be true.
     pub stat_with_if_none_match: bool,
+    /// If operator supports stat with if modified since natively, it will be true.
+    pub stat_with_if_modified_since: bool,
+    /// If operator supports stat without following symlink natively, it will be true.
+    pub stat_with_no_follow_symlink: bool,
+    /// If operator supports stat with snapshot natively, it will be true.
+    pub stat_with_snapshot: bool,
+    /// If operator supports stat with generation natively, it will be true.
+    pub stat_with_generation: bool,
 
     /// If operator supports read natively, it will be true.
     pub read: bool,
@@ -67,10 +76,18 @@ pub struct Capability {
     pub read_with_if_match: bool,
     /// If operator supports read with if none match natively, it will be true.
     pub read_with_if_none_match: bool,
+    /// If operator supports read with if modified since natively, it will be true.
+    pub read_with_if_modified_since: bool,
     /// if operator supports read with override cache control natively, it will be true.
     pub read_with_override_cache_control: bool,
     /// if operator supports read with override content disposition natively, it will be true.
     pub read_with_override_content_disposition: bool,
+    /// If operator supports read without following symlink natively, it will be true.
+    pub read_with_no_follow_symlink: bool,
+    /// If operator supports read with snapshot natively, it will be true.
+    pub read_with_snapshot: bool,
+    /// If operator supports read with generation natively, it will be true.
+    pub read_with_generation: bool,
 
     /// If operator supports write natively, it will be true.
     pub write: bool,
@@ -85,15 +102,55 @@ pub struct Capability {
     pub write_with_content_disposition: bool,
     /// If operator supports write with cache control natively, it will be true.
     pub write_with_cache_control: bool,
+    /// If operator supports write with expires natively, it will be true.
+    pub write_with_expires: bool,
+    /// If operator supports write with append natively, it will be true.
+    pub write_with_append: bool,
+    /// If operator supports resuming write from a previous upload session
+    /// natively, it will be true.
+    pub write_can_resume: bool,
+    /// If operator supports write with if none match natively, it will be true.
+    pub write_with_if_none_match: bool,
+    /// If operator supports write with storage class natively, it will be true.
+    pub write_with_storage_class: bool,
+    /// If operator supports write with if generation match and if
+    /// metageneration match natively, it will be true.
+    pub write_with_if_generation_match: bool,
+    /// If operator supports write with unix permissions natively, it will be true.
+    pub write_with_unix_permissions: bool,
+    /// If operator supports write with replication natively, it will be true.
+    pub write_with_replication: bool,
+    /// If operator supports write with block size natively, it will be true.
+    pub write_with_block_size: bool,
+    /// If operator supports write with canned acl natively, it will be true.
+    pub write_with_acl: bool,
+    /// If operator supports write with durability natively, it will be true.
+    pub write_with_durability: bool,
+    /// If operator supports write with object lock retention and legal
+    /// hold natively, it will be true.
+    pub write_with_object_lock: bool,
 
     /// If operator supports create dir natively, it will be true.
     pub create_dir: bool,
 
     /// If operator supports delete natively, it will be true.
     pub delete: bool,
+    /// If operator supports delete with snapshots directive natively, it will be true.
+    pub delete_with_snapshots: bool,
+    /// If operator supports delete with if generation match and if
+    /// metageneration match natively, it will be true.
+    pub delete_with_if_generation_match: bool,
 
     /// If operator supports copy natively, it will be true.
     pub copy: bool,
+    /// If operator supports copy with if match natively, it will be true.
+    pub copy_with_if_match: bool,
+    /// If operator supports copy with if none match natively, it will be true.
+    pub copy_with_if_none_match: bool,
+    /// If operator supports copy with metadata directive natively, it will be true.
+    pub copy_with_metadata_directive: bool,
+    /// If operator supports copy with tagging directive natively, it will be true.
+    pub copy_with_tagging_directive: bool,
 
     /// If operator supports rename natively, it will be true.
     pub rename: bool,
@@ -108,6 +165,8 @@ pub struct Capability {
     pub list_with_delimiter_slash: bool,
     /// If backend supports list without delimiter.
     pub list_without_delimiter: bool,
+    /// If backend supports list with snapshots included.
+    pub list_with_snapshots: bool,
 
     /// If operator supports presign natively, it will be true.
     pub presign: bool,
@@ -117,6 +176,8 @@ pub struct Capability {
     pub presign_stat: bool,
     /// If operator supports presign write natively, it will be true.
     pub presign_write: bool,
+    /// The max expire duration of presigned requests.
+    pub presign_max_expire: Option<Duration>,
 
     /// If operator supports batch natively, it will be true.
     pub batch: bool,
@@ -125,6 +186,27 @@ pub struct Capability {
     /// The max operations that operator supports in batch.
     pub batch_max_operations: Option<usize>,
 
+    /// If operator supports restore archived objects natively, it will be true.
+    pub restore: bool,
+
+    /// If operator supports compose objects natively, it will be true.
+    pub compose: bool,
+
+    /// If operator supports get acl natively, it will be true.
+    pub get_acl: bool,
+
+    /// If operator supports create symlink natively, it will be true.
+    pub create_symlink: bool,
+
+    /// If operator supports truncate natively, it will be true.
+    pub truncate: bool,
+
+    /// If operator supports set permissions natively, it will be true.
+    pub set_permissions: bool,
+
+    /// If operator supports set owner natively, it will be true.
+    pub set_owner: bool,
+
     /// If operator supports blocking natively, it will be true.
     pub blocking: bool,
 }
@@ -163,6 +245,27 @@ impl Debug for Capability {
         if self.batch {
             s.push("Batch");
         }
+        if self.restore {
+            s.push("Restore");
+        }
+        if self.compose {
+            s.push("Compose");
+        }
+        if self.get_acl {
+            s.push("GetAcl");
+        }
+        if self.create_symlink {
+            s.push("CreateSymlink");
+        }
+        if self.truncate {
+            s.push("Truncate");
+        }
+        if self.set_permissions {
+            s.push("SetPermissions");
+        }
+        if self.set_owner {
+            s.push("SetOwner");
+        }
         if self.blocking {
             s.push("Blocking");
         }
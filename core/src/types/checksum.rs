@@ -0,0 +1,343 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Write;
+use std::io;
+use std::pin::Pin;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use futures::AsyncRead;
+use futures::Stream;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::*;
+
+/// Algorithm used to compute [`Checksum`] while reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChecksumAlgorithm {
+    /// CRC32C (Castagnoli), in big-endian 4 bytes.
+    Crc32c,
+    /// SHA-256 in 32 bytes.
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    /// Name of this algorithm, the same as keys of
+    /// [`Metadata::checksums`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32c => "CRC32C",
+            ChecksumAlgorithm::Sha256 => "SHA256",
+        }
+    }
+}
+
+impl Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Checksum computed from the content read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    algorithm: ChecksumAlgorithm,
+    digest: Vec<u8>,
+}
+
+impl Checksum {
+    /// Algorithm of this checksum.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Raw digest of this checksum.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Lowercase hex encoded digest, like the output of `sha256sum`.
+    pub fn to_hex(&self) -> String {
+        self.digest
+            .iter()
+            .fold(String::with_capacity(self.digest.len() * 2), |mut s, b| {
+                let _ = write!(s, "{b:02x}");
+                s
+            })
+    }
+
+    /// Base64 encoded digest, which is the format used by services like
+    /// s3 and gcs.
+    pub fn to_base64(&self) -> String {
+        BASE64_STANDARD.encode(&self.digest)
+    }
+}
+
+/// Hasher computes checksum incrementally.
+enum Hasher {
+    Crc32c(u32),
+    Sha256(Box<Sha256>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Crc32c => Hasher::Crc32c(0),
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Box::default()),
+        }
+    }
+
+    fn update(&mut self, bs: &[u8]) {
+        match self {
+            Hasher::Crc32c(crc) => *crc = crc32c_update(*crc, bs),
+            Hasher::Sha256(h) => h.update(bs),
+        }
+    }
+
+    fn finish(self) -> Checksum {
+        match self {
+            Hasher::Crc32c(crc) => Checksum {
+                algorithm: ChecksumAlgorithm::Crc32c,
+                digest: crc.to_be_bytes().to_vec(),
+            },
+            Hasher::Sha256(h) => Checksum {
+                algorithm: ChecksumAlgorithm::Sha256,
+                digest: h.finalize().to_vec(),
+            },
+        }
+    }
+}
+
+/// Lookup table of CRC32C with reversed polynomial `0x82F63B78`.
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82F6_3B78
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32c_update(crc: u32, bs: &[u8]) -> u32 {
+    !bs.iter().fold(!crc, |crc, b| {
+        CRC32C_TABLE[((crc ^ *b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// ChecksumReader computes checksum of content while reading it from the
+/// wrapped [`Reader`], so content doesn't need to be read twice.
+///
+/// Created by [`Reader::with_checksum`] or
+/// [`Operator::reader_with_checksum`].
+///
+/// # Usage
+///
+/// ChecksumReader implements `AsyncRead` and `Stream<Item = <io::Result<Bytes>>>`
+/// like [`Reader`], [`ChecksumReader::checksum`] returns the checksum after
+/// all content has been read.
+///
+/// Seek is not supported since the checksum must be computed over the
+/// content in order.
+pub struct ChecksumReader {
+    inner: Reader,
+    hasher: Option<Hasher>,
+    checksum: Option<Checksum>,
+}
+
+impl ChecksumReader {
+    pub(crate) fn new(inner: Reader, algorithm: ChecksumAlgorithm) -> Self {
+        ChecksumReader {
+            inner,
+            hasher: Some(Hasher::new(algorithm)),
+            checksum: None,
+        }
+    }
+
+    /// Checksum of the content read.
+    ///
+    /// Returns `None` until the reader has been read to the end.
+    pub fn checksum(&self) -> Option<&Checksum> {
+        self.checksum.as_ref()
+    }
+
+    fn update(&mut self, bs: &[u8]) {
+        if bs.is_empty() {
+            if let Some(hasher) = self.hasher.take() {
+                self.checksum = Some(hasher.finish());
+            }
+        } else if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(bs);
+        }
+    }
+}
+
+impl AsyncRead for ChecksumReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        // Read nothing into an empty buf doesn't mean EOF.
+        if n > 0 || !buf.is_empty() {
+            self.update(&buf[..n]);
+        }
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl tokio::io::AsyncRead for ChecksumReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let remaining = buf.remaining();
+        ready!(tokio::io::AsyncRead::poll_read(
+            Pin::new(&mut self.inner),
+            cx,
+            buf
+        ))?;
+        if buf.filled().len() > filled || remaining > 0 {
+            self.update(&buf.filled()[filled..]);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Stream for ChecksumReader {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+            Some(Ok(bs)) => {
+                if !bs.is_empty() {
+                    self.update(&bs);
+                }
+                Poll::Ready(Some(Ok(bs)))
+            }
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => {
+                self.update(&[]);
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncReadExt;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services;
+
+    #[test]
+    fn test_crc32c() {
+        // Test vectors from RFC 3720.
+        let cases = vec![
+            ("zeros", vec![0; 32], 0x8A91_36AA),
+            ("ones", vec![0xFF; 32], 0x62A8_AB43),
+            ("incrementing", (0..32).collect::<Vec<u8>>(), 0x46DD_794E),
+            ("numbers", b"123456789".to_vec(), 0xE306_9283),
+        ];
+
+        for (name, input, expected) in cases {
+            assert_eq!(crc32c_update(0, &input), expected, "{name}");
+
+            let (left, right) = input.split_at(input.len() / 2);
+            assert_eq!(
+                crc32c_update(crc32c_update(0, left), right),
+                expected,
+                "{name} in chunks"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checksum_reader() -> Result<()> {
+        let op = Operator::new(services::Memory::default())?.finish();
+        op.write("file", "hello, world").await?;
+
+        let sha256 = "09ca7e4eaa6e8ae9c7d261167129184883644d07dfba7cbfbc4c8a2e08360d5b";
+
+        let (bs, checksum) = op
+            .read_with_checksum("file", ChecksumAlgorithm::Sha256)
+            .await?;
+        assert_eq!(bs, b"hello, world");
+        assert_eq!(checksum.algorithm(), ChecksumAlgorithm::Sha256);
+        assert_eq!(checksum.to_hex(), sha256);
+
+        let mut r = op
+            .reader_with_checksum("file", ChecksumAlgorithm::Sha256)
+            .await?;
+        assert!(r.checksum().is_none());
+        let bs: Vec<Bytes> = (&mut r).try_collect().await.expect("read must succeed");
+        assert_eq!(bs.concat(), b"hello, world");
+        assert_eq!(r.checksum().map(|v| v.to_hex()).as_deref(), Some(sha256));
+
+        let mut r = op
+            .reader_with_checksum("file", ChecksumAlgorithm::Crc32c)
+            .await?;
+        let mut buf = vec![];
+        r.read_to_end(&mut buf).await.expect("read must succeed");
+        assert_eq!(buf, b"hello, world");
+        let checksum = r.checksum().expect("checksum must be computed");
+        assert_eq!(
+            checksum.digest(),
+            crc32c_update(0, b"hello, world").to_be_bytes()
+        );
+
+        // Checksum of a range read covers the range only.
+        let mut r = op
+            .range_reader("file", 7..)
+            .await?
+            .with_checksum(ChecksumAlgorithm::Sha256);
+        let mut buf = vec![];
+        r.read_to_end(&mut buf).await.expect("read must succeed");
+        assert_eq!(
+            r.checksum().map(|v| v.digest().to_vec()),
+            Some(Sha256::digest(b"world").to_vec())
+        );
+
+        Ok(())
+    }
+}
@@ -40,6 +40,7 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::io;
+use std::time::Duration;
 
 /// Result that is a wrapper of `Result<T, opendal::Error>`
 pub type Result<T> = std::result::Result<T, Error>;
@@ -76,7 +77,10 @@ pub enum ErrorKind {
     ///
     /// For example, in S3, the `condition` can be:
     /// 1. writing a file with If-Match header but the file's ETag is not match (will get a 412 Precondition Failed).
-    /// 2. reading a file with If-None-Match header but the file's ETag is match (will get a 304 Not Modified).
+    /// 2. reading a file with If-Match header but the file's ETag is not match (will get a 412 Precondition Failed).
+    ///
+    /// Conditions that only tell the content is not modified will return
+    /// [`ErrorKind::NotModified`] instead.
     ///
     /// As OpenDAL cannot handle the `condition not match` error, it will always return this error to users.
     /// So users could to handle this error by themselves.
@@ -99,6 +103,49 @@ pub enum ErrorKind {
     /// - Users expected to read 1024 bytes, but service returned less bytes.
     /// - Service expected to write 1024 bytes, but users write less bytes.
     ContentIncomplete,
+    /// The operation is interrupted.
+    ///
+    /// This error kind means the previous operation has been cancelled
+    /// (its future was dropped) before it finished, so the state is
+    /// unknown.
+    ///
+    /// For examples:
+    ///
+    /// - Users drop the future of `Writer::write` and then call `write` again.
+    ///
+    /// This error kind is not mapped to `std::io::ErrorKind::Interrupted`,
+    /// since io utils will retry the operation blindly on it.
+    Interrupted,
+    /// The object is archived and needs to be restored before reading.
+    ///
+    /// Use [`Operator::restore`] to restore it, reading will still return
+    /// this error until the restore finished.
+    Archived,
+    /// The service doesn't have enough space or quota to store the data.
+    ///
+    /// For examples:
+    ///
+    /// - The local disk is full or the disk quota is exceeded in `fs`.
+    /// - The quota is exceeded in s3 compatible services.
+    /// - The `memory` service reaches its `max_size`.
+    ///
+    /// Retrying won't help until some space is freed.
+    InsufficientStorage,
+    /// The requested range is not satisfiable.
+    ///
+    /// This error kind means the range starts at or beyond the end of
+    /// the content, for example, reading `100..200` on a 50 bytes file.
+    ///
+    /// Ranges that start within the content but extend beyond its end
+    /// will be clamped instead, see
+    /// [`BytesRange::resolve`](crate::raw::BytesRange::resolve) for details.
+    RangeNotSatisfied,
+    /// The content is not modified since the given condition.
+    ///
+    /// This error kind means the cached copy described by `If-None-Match`
+    /// or `If-Modified-Since` is still fresh (services will return a
+    /// `304 Not Modified`), so users can keep using their cached content.
+    NotModified,
 }
 
 impl ErrorKind {
@@ -130,6 +177,11 @@ impl From<ErrorKind> for &'static str {
             ErrorKind::ConditionNotMatch => "ConditionNotMatch",
             ErrorKind::ContentTruncated => "ContentTruncated",
             ErrorKind::ContentIncomplete => "ContentIncomplete",
+            ErrorKind::Interrupted => "Interrupted",
+            ErrorKind::Archived => "Archived",
+            ErrorKind::InsufficientStorage => "InsufficientStorage",
+            ErrorKind::RangeNotSatisfied => "RangeNotSatisfied",
+            ErrorKind::NotModified => "NotModified",
         }
     }
 }
@@ -175,18 +227,30 @@ pub struct Error {
     operation: &'static str,
     context: Vec<(&'static str, String)>,
     source: Option<anyhow::Error>,
+
+    /// Boxed to keep `Result<T>` small since most errors don't carry them.
+    hints: Option<Box<ErrorHints>>,
+}
+
+/// Typed hints returned by services or transports.
+#[derive(Debug, Default)]
+struct ErrorHints {
+    request_id: Option<String>,
+    network: Option<&'static str>,
+    retry_after: Option<Duration>,
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{} ({}) at {}", self.kind, self.status, self.operation)?;
 
-        if !self.context.is_empty() {
+        let context = self.displayed_context();
+        if !context.is_empty() {
             write!(f, ", context: {{ ")?;
             write!(
                 f,
                 "{}",
-                self.context
+                context
                     .iter()
                     .map(|(k, v)| format!("{k}: {v}"))
                     .collect::<Vec<_>>()
@@ -218,6 +282,7 @@ impl Debug for Error {
             de.field("operation", &self.operation);
             de.field("context", &self.context);
             de.field("source", &self.source);
+            de.field("hints", &self.hints);
             return de.finish();
         }
 
@@ -227,10 +292,11 @@ impl Debug for Error {
         }
         writeln!(f)?;
 
-        if !self.context.is_empty() {
+        let context = self.displayed_context();
+        if !context.is_empty() {
             writeln!(f)?;
             writeln!(f, "Context:")?;
-            for (k, v) in self.context.iter() {
+            for (k, v) in context.iter() {
                 writeln!(f, "    {k}: {v}")?;
             }
         }
@@ -260,9 +326,35 @@ impl Error {
             operation: "",
             context: Vec::default(),
             source: None,
+
+            hints: None,
         }
     }
 
+    /// Return context with typed fields appended so that they can be
+    /// displayed together.
+    fn displayed_context(&self) -> Vec<(&'static str, String)> {
+        let mut context = self.context.clone();
+        let hints = match &self.hints {
+            Some(hints) => hints,
+            None => return context,
+        };
+        if let Some(v) = &hints.request_id {
+            context.push(("request_id", v.clone()));
+        }
+        if let Some(v) = hints.network {
+            context.push(("network", v.to_string()));
+        }
+        if let Some(v) = hints.retry_after {
+            context.push(("retry_after", format!("{v:?}")));
+        }
+        context
+    }
+
+    fn hints_mut(&mut self) -> &mut ErrorHints {
+        self.hints.get_or_insert_with(Default::default)
+    }
+
     /// Update error's operation.
     ///
     /// # Notes
@@ -284,6 +376,38 @@ impl Error {
         self
     }
 
+    /// Add request id returned by service in error.
+    ///
+    /// Request id will be displayed as context `request_id` and can be
+    /// fetched via [`Error::request_id`].
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.hints_mut().request_id = Some(request_id.into());
+        self
+    }
+
+    /// Mark this error as a transport level error, for example, failed to
+    /// connect or timed out while waiting for response.
+    ///
+    /// Only `connect` errors guarantee that the request never reached the
+    /// service, a `timeout` may happen after the service received it.
+    ///
+    /// The cause will be displayed as context `network` and can be fetched
+    /// via [`Error::network_error`].
+    pub fn with_network_error(mut self, cause: &'static str) -> Self {
+        self.hints_mut().network = Some(cause);
+        self
+    }
+
+    /// Add the delay suggested by service before retrying, for example,
+    /// the `Retry-After` header of http responses.
+    ///
+    /// The delay will be displayed as context `retry_after` and can be
+    /// fetched via [`Error::retry_after`].
+    pub fn with_retry_after(mut self, dur: Duration) -> Self {
+        self.hints_mut().retry_after = Some(dur);
+        self
+    }
+
     /// Set source for error.
     ///
     /// # Notes
@@ -335,6 +459,29 @@ impl Error {
     pub fn is_temporary(&self) -> bool {
         self.status == ErrorStatus::Temporary
     }
+
+    /// Return the request id returned by service, which is useful while
+    /// asking service providers for help.
+    pub fn request_id(&self) -> Option<&str> {
+        self.hints.as_ref()?.request_id.as_deref()
+    }
+
+    /// Return the cause of transport level error like `connect` and
+    /// `timeout`.
+    ///
+    /// `None` means the error is not caused by network, users can use this
+    /// to tell broken config or network from errors returned by services.
+    pub fn network_error(&self) -> Option<&'static str> {
+        self.hints.as_ref()?.network
+    }
+
+    /// Return the delay suggested by service before retrying.
+    ///
+    /// [`RetryLayer`][crate::layers::RetryLayer] will wait at least this
+    /// long before the next attempt.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.hints.as_ref()?.retry_after
+    }
 }
 
 impl From<Error> for io::Error {
@@ -366,6 +513,8 @@ mod tests {
             ("called", "send_async".to_string()),
         ],
         source: Some(anyhow!("networking error")),
+
+        hints: None,
     });
 
     #[test]
@@ -392,4 +541,38 @@ Source: networking error
 "#
         )
     }
+
+    #[test]
+    fn test_error_request_id() {
+        let err = Error::new(ErrorKind::Unexpected, "something wrong happened");
+        assert_eq!(err.request_id(), None);
+
+        let err = err
+            .with_context("path", "/path/to/file")
+            .with_request_id("5C3D9175B6FC201293AD****");
+        assert_eq!(err.request_id(), Some("5C3D9175B6FC201293AD****"));
+        assert!(err
+            .to_string()
+            .contains("request_id: 5C3D9175B6FC201293AD****"));
+    }
+
+    #[test]
+    fn test_error_network_error() {
+        let err = Error::new(ErrorKind::Unexpected, "send async request");
+        assert_eq!(err.network_error(), None);
+
+        let err = err.with_network_error("connect");
+        assert_eq!(err.network_error(), Some("connect"));
+        assert!(err.to_string().contains("network: connect"));
+    }
+
+    #[test]
+    fn test_error_retry_after() {
+        let err = Error::new(ErrorKind::RateLimited, "slow down");
+        assert_eq!(err.retry_after(), None);
+
+        let err = err.with_retry_after(Duration::from_millis(1500));
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(1500)));
+        assert!(err.to_string().contains("retry_after: 1.5s"));
+    }
 }
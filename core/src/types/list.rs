@@ -18,7 +18,6 @@
 use std::collections::VecDeque;
 use std::mem;
 use std::pin::Pin;
-use std::task::ready;
 use std::task::Context;
 use std::task::Poll;
 
@@ -36,10 +35,27 @@ use crate::*;
 ///
 /// User can use lister as `Stream<Item = Result<Entry>>` or
 /// call `next_page` directly.
+///
+/// # Prefetch
+///
+/// By default, lister will only fetch the next page after all entries of
+/// current page have been consumed. Users can set prefetch depth via
+/// [`OpList::with_prefetch`] to fetch more pages ahead while consuming
+/// entries of current page. Prefetch only works with
+/// `Stream<Item = Result<Entry>>`.
 pub struct Lister {
     pager: Option<oio::Pager>,
 
     buf: VecDeque<oio::Entry>,
+    /// Pages that have been fetched ahead but not consumed yet.
+    pages: VecDeque<Vec<oio::Entry>>,
+    /// How many pages could be fetched ahead.
+    prefetch: usize,
+    /// Error returned while fetching ahead, will be returned after all
+    /// fetched entries have been consumed.
+    err: Option<Error>,
+    /// Whether the pager has been exhausted.
+    done: bool,
     /// We will move `pager` inside future and return it back while future is ready.
     /// Thus, we should not allow calling other function while we already have
     /// a future.
@@ -58,10 +74,26 @@ impl Lister {
         Self {
             pager: Some(pager),
             buf: VecDeque::default(),
+            pages: VecDeque::default(),
+            prefetch: 0,
+            err: None,
+            done: false,
             fut: None,
         }
     }
 
+    /// Set the prefetch depth of this lister.
+    pub(crate) fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Returns whether we should start fetching the next page.
+    fn should_fetch(&self) -> bool {
+        let buffered = self.pages.len() + usize::from(!self.buf.is_empty());
+        self.fut.is_none() && !self.done && self.err.is_none() && buffered <= self.prefetch
+    }
+
     /// has_next can be used to check if there are more pages.
     pub async fn has_next(&mut self) -> Result<bool> {
         debug_assert!(
@@ -72,6 +104,10 @@ impl Lister {
         if !self.buf.is_empty() {
             return Ok(true);
         }
+        if let Some(oes) = self.pages.pop_front() {
+            self.buf = oes.into();
+            return Ok(true);
+        }
 
         let entries = match self
             .pager
@@ -106,6 +142,8 @@ impl Lister {
 
         let entries = if !self.buf.is_empty() {
             mem::take(&mut self.buf)
+        } else if let Some(oes) = self.pages.pop_front() {
+            oes.into()
         } else {
             match self
                 .pager
@@ -130,35 +168,55 @@ impl Stream for Lister {
     type Item = Result<Entry>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if let Some(oe) = self.buf.pop_front() {
-            return Poll::Ready(Some(Ok(oe.into_entry())));
-        }
+        loop {
+            if self.should_fetch() {
+                let mut pager = self.pager.take().expect("pager must be valid");
+                let fut = async move {
+                    let res = pager.next().await;
 
-        if let Some(fut) = self.fut.as_mut() {
-            let (op, res) = ready!(fut.poll_unpin(cx));
-            self.pager = Some(op);
+                    (pager, res)
+                };
+                self.fut = Some(Box::pin(fut));
+            }
 
-            return match res? {
-                Some(oes) => {
+            // Drive the ongoing fetch even if we still have entries to
+            // return, so that pages could be fetched ahead.
+            if let Some(fut) = self.fut.as_mut() {
+                if let Poll::Ready((op, res)) = fut.poll_unpin(cx) {
+                    self.pager = Some(op);
                     self.fut = None;
-                    self.buf = oes.into();
-                    self.poll_next(cx)
-                }
-                None => {
-                    self.fut = None;
-                    Poll::Ready(None)
+
+                    match res {
+                        Ok(Some(oes)) => self.pages.push_back(oes),
+                        Ok(None) => self.done = true,
+                        Err(err) => self.err = Some(err),
+                    }
+                    continue;
                 }
-            };
-        }
+            }
 
-        let mut pager = self.pager.take().expect("pager must be valid");
-        let fut = async move {
-            let res = pager.next().await;
+            if let Some(oe) = self.buf.pop_front() {
+                return Poll::Ready(Some(Ok(oe.into_entry())));
+            }
 
-            (pager, res)
-        };
-        self.fut = Some(Box::pin(fut));
-        self.poll_next(cx)
+            if let Some(oes) = self.pages.pop_front() {
+                self.buf = oes.into();
+                continue;
+            }
+
+            if let Some(err) = self.err.take() {
+                return Poll::Ready(Some(Err(err)));
+            }
+
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            // The ongoing fetch is pending and we have nothing to return.
+            if self.fut.is_some() {
+                return Poll::Pending;
+            }
+        }
     }
 }
 
@@ -224,3 +282,70 @@ impl Iterator for BlockingLister {
         self.next()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures::StreamExt;
+
+    use super::*;
+
+    /// MockPager returns `pages` pages with one entry per page and
+    /// records how many pages have been fetched.
+    struct MockPager {
+        pages: usize,
+        fetched: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl oio::Page for MockPager {
+        async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+            let fetched = self.fetched.load(Ordering::SeqCst);
+            if fetched >= self.pages {
+                return Ok(None);
+            }
+
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(vec![oio::Entry::new(
+                &format!("file-{fetched}"),
+                Metadata::new(EntryMode::FILE),
+            )]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lister_prefetch() {
+        for (prefetch, expected) in [(0, 1), (1, 2), (3, 4), (100, 10)] {
+            let fetched = Arc::new(AtomicUsize::new(0));
+            let pager = MockPager {
+                pages: 10,
+                fetched: fetched.clone(),
+            };
+            let mut lister = Lister::new(Box::new(pager)).with_prefetch(prefetch);
+
+            let entry = lister
+                .next()
+                .await
+                .expect("must have entry")
+                .expect("list must succeed");
+            assert_eq!(entry.path(), "file-0");
+            assert_eq!(fetched.load(Ordering::SeqCst), expected, "{prefetch}");
+
+            let paths = lister
+                .map(|v| v.map(|v| v.path().to_string()))
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>>>()
+                .expect("list must succeed");
+            assert_eq!(
+                paths,
+                (1..10).map(|v| format!("file-{v}")).collect::<Vec<_>>()
+            );
+        }
+    }
+}
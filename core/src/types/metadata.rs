@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
+
 use chrono::prelude::*;
 use flagset::flags;
 use flagset::FlagSet;
@@ -38,12 +40,41 @@ pub struct Metadata {
 
     cache_control: Option<String>,
     content_disposition: Option<String>,
+    content_encoding: Option<String>,
     content_length: Option<u64>,
     content_md5: Option<String>,
     content_range: Option<BytesContentRange>,
     content_type: Option<String>,
     etag: Option<String>,
     last_modified: Option<DateTime<Utc>>,
+    is_symlink: bool,
+    is_dir_marker: bool,
+
+    /// Metadata only provided by some services, boxed to keep `Metadata`
+    /// small. It's allocated while the first of them is set.
+    ext: Option<Box<MetadataExt>>,
+}
+
+/// MetadataExt carries metadata that only provided by some services.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct MetadataExt {
+    storage_class: Option<String>,
+    parts_count: Option<u64>,
+    checksums: Option<HashMap<String, String>>,
+    server_side_encryption: Option<String>,
+    server_side_encryption_key_id: Option<String>,
+    symlink_target: Option<String>,
+    restore_status: Option<String>,
+    snapshot: Option<String>,
+    expires: Option<DateTime<Utc>>,
+    generation: Option<i64>,
+    unix_permissions: Option<u32>,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    owner: Option<String>,
+    group: Option<String>,
+    replication: Option<u16>,
+    block_size: Option<u64>,
 }
 
 impl Metadata {
@@ -69,9 +100,19 @@ impl Metadata {
             last_modified: None,
             etag: None,
             content_disposition: None,
+            content_encoding: None,
+            is_symlink: false,
+            is_dir_marker: false,
+            ext: None,
         }
     }
 
+    /// Get the mutable ref of metadata only provided by some services,
+    /// allocate it if not exist.
+    fn ext_mut(&mut self) -> &mut MetadataExt {
+        self.ext.get_or_insert_with(Default::default)
+    }
+
     /// Get the bit from metadata.
     pub(crate) fn bit(&self) -> FlagSet<Metakey> {
         self.bit
@@ -246,6 +287,47 @@ impl Metadata {
         self
     }
 
+    /// Content Encoding of this entry.
+    ///
+    /// Content Encoding is defined by [RFC 9110](https://httpwg.org/specs/rfc9110.html#field.content-encoding).
+    pub fn content_encoding(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::ContentEncoding) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: content_encoding, maybe a bug"
+        );
+
+        self.content_encoding.as_deref()
+    }
+
+    /// Set Content Encoding of this entry.
+    ///
+    /// Content Encoding is defined by [RFC 9110](https://httpwg.org/specs/rfc9110.html#field.content-encoding).
+    pub fn set_content_encoding(&mut self, v: &str) -> &mut Self {
+        self.content_encoding = Some(v.to_string());
+        self.bit |= Metakey::ContentEncoding;
+        self
+    }
+
+    /// Set Content Encoding of this entry.
+    ///
+    /// Content Encoding is defined by [RFC 9110](https://httpwg.org/specs/rfc9110.html#field.content-encoding).
+    pub fn with_content_encoding(mut self, v: String) -> Self {
+        self.content_encoding = Some(v);
+        self.bit |= Metakey::ContentEncoding;
+        self
+    }
+
+    /// Remove Content Encoding of this entry after the content has been decoded.
+    ///
+    /// Content Length is removed too since it's the length of encoded content.
+    #[cfg(feature = "content-decoding")]
+    pub(crate) fn clear_content_encoding(&mut self) -> &mut Self {
+        self.content_encoding = None;
+        self.content_length = None;
+        self.bit -= Metakey::ContentEncoding | Metakey::ContentLength;
+        self
+    }
+
     /// Content Range of this entry.
     ///
     /// Content Range is defined by [RFC 9110](https://httpwg.org/specs/rfc9110.html#field.content-range).
@@ -321,7 +403,10 @@ impl Metadata {
     /// - `"33a64df551425fcc55e4d42a148795d9f25f89d4"`
     /// - `W/"0815"`
     ///
-    /// `"` is part of etag.
+    /// `"` is part of etag. The value is kept as returned by services, so
+    /// it's safe to echo it verbatim in `If-Match` or `If-None-Match`. Some
+    /// services return etag without quotes, use [`Metadata::etag_matches`]
+    /// instead of `==` to compare etags.
     pub fn etag(&self) -> Option<&str> {
         debug_assert!(
             self.bit.contains(Metakey::Etag) || self.bit.contains(Metakey::Complete),
@@ -365,6 +450,50 @@ impl Metadata {
         self
     }
 
+    /// Opaque tag of ETag without quotes and the weak indicator `W/`.
+    ///
+    /// For example, `"0815"`, `W/"0815"` and `0815` all return `0815`.
+    pub fn etag_opaque(&self) -> Option<&str> {
+        self.etag().map(|v| parse_etag(v).1)
+    }
+
+    /// Returns whether the ETag of this entry is a weak validator like `W/"0815"`.
+    pub fn is_weak_etag(&self) -> bool {
+        self.etag().map(|v| parse_etag(v).0).unwrap_or_default()
+    }
+
+    /// Compare the ETag of this entry with given etag in weak comparison
+    /// defined by [RFC 7232](https://httpwg.org/specs/rfc7232.html#rfc.section.2.3.2):
+    /// two etags match if their opaque tags are equal, no matter they are
+    /// weak or not.
+    ///
+    /// Quotes are optional for both sides. Returns false if this entry
+    /// doesn't have an ETag.
+    pub fn etag_matches(&self, other: &str) -> bool {
+        match self.etag() {
+            Some(v) => parse_etag(v).1 == parse_etag(other).1,
+            None => false,
+        }
+    }
+
+    /// Compare the ETag of this entry with given etag in strong comparison
+    /// defined by [RFC 7232](https://httpwg.org/specs/rfc7232.html#rfc.section.2.3.2):
+    /// two etags match only if neither of them is weak and their opaque
+    /// tags are equal.
+    ///
+    /// Quotes are optional for both sides. Returns false if this entry
+    /// doesn't have an ETag.
+    pub fn etag_strong_matches(&self, other: &str) -> bool {
+        match self.etag() {
+            Some(v) => {
+                let (weak, opaque) = parse_etag(v);
+                let (other_weak, other_opaque) = parse_etag(other);
+                !weak && !other_weak && opaque == other_opaque
+            }
+            None => false,
+        }
+    }
+
     /// Content-Disposition of this entry
     ///
     /// `Content-Disposition` is defined by [RFC 2616](https://www.rfc-editor/rfcs/2616) and
@@ -418,6 +547,544 @@ impl Metadata {
         self.bit |= Metakey::ContentDisposition;
         self
     }
+
+    /// Storage class of this entry.
+    ///
+    /// OpenDAL will return this value AS-IS like `STANDARD` or `GLACIER`.
+    pub fn storage_class(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::StorageClass) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: storage_class, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.storage_class.as_deref())
+    }
+
+    /// Set storage class of this entry.
+    pub fn set_storage_class(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().storage_class = Some(v.to_string());
+        self.bit |= Metakey::StorageClass;
+        self
+    }
+
+    /// Set storage class of this entry.
+    pub fn with_storage_class(mut self, v: String) -> Self {
+        self.ext_mut().storage_class = Some(v);
+        self.bit |= Metakey::StorageClass;
+        self
+    }
+
+    /// Parts count of this entry.
+    ///
+    /// Only entries uploaded by multipart upload have parts count. For
+    /// azblob append blobs, it's the count of committed blocks.
+    pub fn parts_count(&self) -> Option<u64> {
+        debug_assert!(
+            self.bit.contains(Metakey::PartsCount) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: parts_count, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.parts_count)
+    }
+
+    /// Set parts count of this entry.
+    pub fn set_parts_count(&mut self, v: u64) -> &mut Self {
+        self.ext_mut().parts_count = Some(v);
+        self.bit |= Metakey::PartsCount;
+        self
+    }
+
+    /// Set parts count of this entry.
+    pub fn with_parts_count(mut self, v: u64) -> Self {
+        self.ext_mut().parts_count = Some(v);
+        self.bit |= Metakey::PartsCount;
+        self
+    }
+
+    /// Checksums of this entry, keyed by algorithm like `CRC32C` or `SHA256`.
+    ///
+    /// OpenDAL will return the checksum AS-IS, it's usually base64 encoded.
+    pub fn checksums(&self) -> Option<&HashMap<String, String>> {
+        debug_assert!(
+            self.bit.contains(Metakey::Checksums) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: checksums, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.checksums.as_ref())
+    }
+
+    /// Set checksum of given algorithm for this entry.
+    pub fn set_checksum(&mut self, algorithm: &str, v: &str) -> &mut Self {
+        self.ext_mut()
+            .checksums
+            .get_or_insert_with(HashMap::new)
+            .insert(algorithm.to_string(), v.to_string());
+        self.bit |= Metakey::Checksums;
+        self
+    }
+
+    /// Set checksum of given algorithm for this entry.
+    pub fn with_checksum(mut self, algorithm: String, v: String) -> Self {
+        self.ext_mut()
+            .checksums
+            .get_or_insert_with(HashMap::new)
+            .insert(algorithm, v);
+        self.bit |= Metakey::Checksums;
+        self
+    }
+
+    /// Server side encryption algorithm of this entry.
+    ///
+    /// OpenDAL will return this value AS-IS like `AES256` or `KMS`.
+    pub fn server_side_encryption(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::ServerSideEncryption)
+                || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: server_side_encryption, maybe a bug"
+        );
+
+        self.ext
+            .as_ref()
+            .and_then(|v| v.server_side_encryption.as_deref())
+    }
+
+    /// Set server side encryption algorithm of this entry.
+    pub fn set_server_side_encryption(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().server_side_encryption = Some(v.to_string());
+        self.bit |= Metakey::ServerSideEncryption;
+        self
+    }
+
+    /// Set server side encryption algorithm of this entry.
+    pub fn with_server_side_encryption(mut self, v: String) -> Self {
+        self.ext_mut().server_side_encryption = Some(v);
+        self.bit |= Metakey::ServerSideEncryption;
+        self
+    }
+
+    /// Key id of server side encryption of this entry, like the KMS CMK id.
+    pub fn server_side_encryption_key_id(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::ServerSideEncryptionKeyId)
+                || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: server_side_encryption_key_id, maybe a bug"
+        );
+
+        self.ext
+            .as_ref()
+            .and_then(|v| v.server_side_encryption_key_id.as_deref())
+    }
+
+    /// Set key id of server side encryption of this entry.
+    pub fn set_server_side_encryption_key_id(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().server_side_encryption_key_id = Some(v.to_string());
+        self.bit |= Metakey::ServerSideEncryptionKeyId;
+        self
+    }
+
+    /// Set key id of server side encryption of this entry.
+    pub fn with_server_side_encryption_key_id(mut self, v: String) -> Self {
+        self.ext_mut().server_side_encryption_key_id = Some(v);
+        self.bit |= Metakey::ServerSideEncryptionKeyId;
+        self
+    }
+
+    /// Returns `true` if this entry is a directory marker.
+    ///
+    /// Object storage services don't have real directories, `create_dir`
+    /// on them will create a zero-byte object whose key ends with `/` to
+    /// represent the directory, which is called a directory marker.
+    ///
+    /// An entry is a directory marker if and only if:
+    ///
+    /// - its path ends with `/`, and
+    /// - it's backed by an existing object whose content length is `0`.
+    ///
+    /// Directory markers are always [`EntryMode::DIR`] and should not be
+    /// treated as downloadable files. Directories that only exist as
+    /// common prefixes of other objects are not directory markers.
+    pub fn is_dir_marker(&self) -> bool {
+        self.is_dir_marker
+    }
+
+    /// Mark whether this entry is a directory marker.
+    pub fn set_is_dir_marker(&mut self, v: bool) -> &mut Self {
+        self.is_dir_marker = v;
+        self
+    }
+
+    /// Mark whether this entry is a directory marker.
+    pub fn with_is_dir_marker(mut self, v: bool) -> Self {
+        self.is_dir_marker = v;
+        self
+    }
+
+    /// Returns `true` if this metadata is for a symlink.
+    ///
+    /// Only available while stat without following symlink, see
+    /// [`OpStat::with_follow_symlink`][crate::ops::OpStat::with_follow_symlink],
+    /// or listing on services that report symlinks.
+    pub fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    /// Mark whether this entry is a symlink.
+    ///
+    /// Use [`Metadata::set_symlink_target`] instead if target is known.
+    pub fn set_is_symlink(&mut self, v: bool) -> &mut Self {
+        self.is_symlink = v;
+        self
+    }
+
+    /// Mark whether this entry is a symlink.
+    pub fn with_is_symlink(mut self, v: bool) -> Self {
+        self.is_symlink = v;
+        self
+    }
+
+    /// Target of this symlink, which could be relative to the directory
+    /// of this path or not existing at all.
+    pub fn symlink_target(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::SymlinkTarget) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: symlink_target, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.symlink_target.as_deref())
+    }
+
+    /// Mark this entry as symlink with given target.
+    pub fn set_symlink_target(&mut self, v: &str) -> &mut Self {
+        self.is_symlink = true;
+        self.ext_mut().symlink_target = Some(v.to_string());
+        self.bit |= Metakey::SymlinkTarget;
+        self
+    }
+
+    /// Mark this entry as symlink with given target.
+    pub fn with_symlink_target(mut self, v: String) -> Self {
+        self.is_symlink = true;
+        self.ext_mut().symlink_target = Some(v);
+        self.bit |= Metakey::SymlinkTarget;
+        self
+    }
+
+    /// Restore status of this archived entry.
+    ///
+    /// The value is returned by services as is, like
+    /// `ongoing-request="false", expiry-date="Sun, 16 Apr 2017 08:12:33 GMT"`.
+    /// `None` means the entry is not archived or restore has not been
+    /// requested.
+    pub fn restore_status(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::RestoreStatus) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: restore_status, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.restore_status.as_deref())
+    }
+
+    /// Set restore status of this entry.
+    pub fn set_restore_status(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().restore_status = Some(v.to_string());
+        self.bit |= Metakey::RestoreStatus;
+        self
+    }
+
+    /// Set restore status of this entry.
+    pub fn with_restore_status(mut self, v: String) -> Self {
+        self.ext_mut().restore_status = Some(v);
+        self.bit |= Metakey::RestoreStatus;
+        self
+    }
+
+    /// Snapshot of this entry.
+    ///
+    /// The value is returned by services as is, like
+    /// `2023-05-01T08:12:33.1234567Z` for azblob. `None` means this entry
+    /// is the current one instead of a snapshot.
+    ///
+    /// Pass it to [`OpRead::with_snapshot`](crate::ops::OpRead::with_snapshot)
+    /// to read the content of this snapshot.
+    pub fn snapshot(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Snapshot) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: snapshot, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.snapshot.as_deref())
+    }
+
+    /// Set snapshot of this entry.
+    pub fn set_snapshot(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().snapshot = Some(v.to_string());
+        self.bit |= Metakey::Snapshot;
+        self
+    }
+
+    /// Set snapshot of this entry.
+    pub fn with_snapshot(mut self, v: String) -> Self {
+        self.ext_mut().snapshot = Some(v);
+        self.bit |= Metakey::Snapshot;
+        self
+    }
+
+    /// Generation of this entry.
+    ///
+    /// Generation is the version of object content used by gcs, which
+    /// changes every time the object is overwritten.
+    ///
+    /// Pass it to [`OpRead::with_generation`](crate::ops::OpRead::with_generation)
+    /// to read the content of this generation.
+    pub fn generation(&self) -> Option<i64> {
+        debug_assert!(
+            self.bit.contains(Metakey::Generation) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: generation, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.generation)
+    }
+
+    /// Set generation of this entry.
+    pub fn set_generation(&mut self, v: i64) -> &mut Self {
+        self.ext_mut().generation = Some(v);
+        self.bit |= Metakey::Generation;
+        self
+    }
+
+    /// Set generation of this entry.
+    pub fn with_generation(mut self, v: i64) -> Self {
+        self.ext_mut().generation = Some(v);
+        self.bit |= Metakey::Generation;
+        self
+    }
+
+    /// Unix permission bits of this entry like `0o755`, including the
+    /// setuid, setgid and sticky bits.
+    ///
+    /// Only services backed by unix file systems will return it.
+    pub fn unix_permissions(&self) -> Option<u32> {
+        debug_assert!(
+            self.bit.contains(Metakey::UnixPermissions) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: unix_permissions, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.unix_permissions)
+    }
+
+    /// Set unix permission bits of this entry.
+    pub fn set_unix_permissions(&mut self, v: u32) -> &mut Self {
+        self.ext_mut().unix_permissions = Some(v);
+        self.bit |= Metakey::UnixPermissions;
+        self
+    }
+
+    /// Set unix permission bits of this entry.
+    pub fn with_unix_permissions(mut self, v: u32) -> Self {
+        self.ext_mut().unix_permissions = Some(v);
+        self.bit |= Metakey::UnixPermissions;
+        self
+    }
+
+    /// User id of the owner of this entry.
+    ///
+    /// Only services backed by unix file systems will return it.
+    pub fn uid(&self) -> Option<u32> {
+        debug_assert!(
+            self.bit.contains(Metakey::Uid) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: uid, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.uid)
+    }
+
+    /// Set user id of the owner of this entry.
+    pub fn set_uid(&mut self, v: u32) -> &mut Self {
+        self.ext_mut().uid = Some(v);
+        self.bit |= Metakey::Uid;
+        self
+    }
+
+    /// Set user id of the owner of this entry.
+    pub fn with_uid(mut self, v: u32) -> Self {
+        self.ext_mut().uid = Some(v);
+        self.bit |= Metakey::Uid;
+        self
+    }
+
+    /// Group id of the owner of this entry.
+    ///
+    /// Only services backed by unix file systems will return it.
+    pub fn gid(&self) -> Option<u32> {
+        debug_assert!(
+            self.bit.contains(Metakey::Gid) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: gid, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.gid)
+    }
+
+    /// Set group id of the owner of this entry.
+    pub fn set_gid(&mut self, v: u32) -> &mut Self {
+        self.ext_mut().gid = Some(v);
+        self.bit |= Metakey::Gid;
+        self
+    }
+
+    /// Set group id of the owner of this entry.
+    pub fn with_gid(mut self, v: u32) -> Self {
+        self.ext_mut().gid = Some(v);
+        self.bit |= Metakey::Gid;
+        self
+    }
+
+    /// Name of the owner of this entry.
+    ///
+    /// Only services like hdfs that track owners by name will return it.
+    pub fn owner(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Owner) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: owner, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.owner.as_deref())
+    }
+
+    /// Set name of the owner of this entry.
+    pub fn set_owner(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().owner = Some(v.to_string());
+        self.bit |= Metakey::Owner;
+        self
+    }
+
+    /// Set name of the owner of this entry.
+    pub fn with_owner(mut self, v: String) -> Self {
+        self.ext_mut().owner = Some(v);
+        self.bit |= Metakey::Owner;
+        self
+    }
+
+    /// Name of the group of this entry.
+    ///
+    /// Only services like hdfs that track groups by name will return it.
+    pub fn group(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Group) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: group, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.group.as_deref())
+    }
+
+    /// Set name of the group of this entry.
+    pub fn set_group(&mut self, v: &str) -> &mut Self {
+        self.ext_mut().group = Some(v.to_string());
+        self.bit |= Metakey::Group;
+        self
+    }
+
+    /// Set name of the group of this entry.
+    pub fn with_group(mut self, v: String) -> Self {
+        self.ext_mut().group = Some(v);
+        self.bit |= Metakey::Group;
+        self
+    }
+
+    /// Replication factor of this entry, a.k.a., how many copies of each
+    /// block are stored.
+    ///
+    /// Only services like hdfs will return it, and only for files.
+    pub fn replication(&self) -> Option<u16> {
+        debug_assert!(
+            self.bit.contains(Metakey::Replication) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: replication, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.replication)
+    }
+
+    /// Set replication factor of this entry.
+    pub fn set_replication(&mut self, v: u16) -> &mut Self {
+        self.ext_mut().replication = Some(v);
+        self.bit |= Metakey::Replication;
+        self
+    }
+
+    /// Set replication factor of this entry.
+    pub fn with_replication(mut self, v: u16) -> Self {
+        self.ext_mut().replication = Some(v);
+        self.bit |= Metakey::Replication;
+        self
+    }
+
+    /// Block size in bytes of this entry.
+    ///
+    /// Only services like hdfs will return it, and only for files.
+    pub fn block_size(&self) -> Option<u64> {
+        debug_assert!(
+            self.bit.contains(Metakey::BlockSize) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: block_size, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.block_size)
+    }
+
+    /// Set block size of this entry.
+    pub fn set_block_size(&mut self, v: u64) -> &mut Self {
+        self.ext_mut().block_size = Some(v);
+        self.bit |= Metakey::BlockSize;
+        self
+    }
+
+    /// Set block size of this entry.
+    pub fn with_block_size(mut self, v: u64) -> Self {
+        self.ext_mut().block_size = Some(v);
+        self.bit |= Metakey::BlockSize;
+        self
+    }
+
+    /// Expires of this entry.
+    ///
+    /// `Expires` is defined by [RFC 7234](https://httpwg.org/specs/rfc7234.html#header.expires)
+    /// Refer to [MDN Expires](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Expires) for more information.
+    ///
+    /// OpenDAL parse the raw value into [`DateTime`] for convenient.
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        debug_assert!(
+            self.bit.contains(Metakey::Expires) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: expires, maybe a bug"
+        );
+
+        self.ext.as_ref().and_then(|v| v.expires)
+    }
+
+    /// Set expires of this entry.
+    pub fn set_expires(&mut self, v: DateTime<Utc>) -> &mut Self {
+        self.ext_mut().expires = Some(v);
+        self.bit |= Metakey::Expires;
+        self
+    }
+
+    /// Set expires of this entry.
+    pub fn with_expires(mut self, v: DateTime<Utc>) -> Self {
+        self.ext_mut().expires = Some(v);
+        self.bit |= Metakey::Expires;
+        self
+    }
+}
+
+/// Parse etag into whether it's weak and its opaque tag without quotes.
+fn parse_etag(v: &str) -> (bool, &str) {
+    let v = v.trim();
+    let (weak, v) = match v.strip_prefix("W/") {
+        Some(v) => (true, v),
+        None => (false, v),
+    };
+    let v = v
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(v);
+    (weak, v)
 }
 
 flags! {
@@ -445,6 +1112,8 @@ flags! {
         CacheControl,
         /// Key for content disposition.
         ContentDisposition,
+        /// Key for content encoding.
+        ContentEncoding,
         /// Key for content length.
         ContentLength,
         /// Key for content md5.
@@ -457,5 +1126,80 @@ flags! {
         Etag,
         /// Key for last last modified.
         LastModified,
+        /// Key for storage class.
+        StorageClass,
+        /// Key for parts count.
+        PartsCount,
+        /// Key for checksums.
+        Checksums,
+        /// Key for server side encryption.
+        ServerSideEncryption,
+        /// Key for server side encryption key id.
+        ServerSideEncryptionKeyId,
+        /// Key for symlink target.
+        SymlinkTarget,
+        /// Key for restore status.
+        RestoreStatus,
+        /// Key for snapshot.
+        Snapshot,
+        /// Key for expires.
+        Expires,
+        /// Key for generation.
+        Generation,
+        /// Key for unix permissions.
+        UnixPermissions,
+        /// Key for uid.
+        Uid,
+        /// Key for gid.
+        Gid,
+        /// Key for owner.
+        Owner,
+        /// Key for group.
+        Group,
+        /// Key for replication.
+        Replication,
+        /// Key for block size.
+        BlockSize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_etag() {
+        let cases = vec![
+            ("quoted", "\"abc123\"", (false, "abc123")),
+            ("unquoted", "abc123", (false, "abc123")),
+            ("weak", "W/\"abc123\"", (true, "abc123")),
+            ("weak unquoted", "W/abc123", (true, "abc123")),
+            ("multipart", "\"abc123-2\"", (false, "abc123-2")),
+        ];
+
+        for (name, input, expected) in cases {
+            assert_eq!(parse_etag(input), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let meta = Metadata::new(EntryMode::FILE).with_etag("\"abc123\"".to_string());
+        assert_eq!(meta.etag(), Some("\"abc123\""));
+        assert_eq!(meta.etag_opaque(), Some("abc123"));
+        assert!(!meta.is_weak_etag());
+
+        assert!(meta.etag_matches("abc123"));
+        assert!(meta.etag_matches("\"abc123\""));
+        assert!(meta.etag_matches("W/\"abc123\""));
+        assert!(!meta.etag_matches("\"abc\""));
+
+        assert!(meta.etag_strong_matches("abc123"));
+        assert!(!meta.etag_strong_matches("W/\"abc123\""));
+
+        let meta = Metadata::new(EntryMode::FILE).with_etag("W/\"abc123\"".to_string());
+        assert!(meta.is_weak_etag());
+        assert!(meta.etag_matches("\"abc123\""));
+        assert!(!meta.etag_strong_matches("\"abc123\""));
     }
 }
@@ -29,6 +29,11 @@ mod reader;
 pub use reader::BlockingReader;
 pub use reader::Reader;
 
+mod checksum;
+pub use checksum::Checksum;
+pub use checksum::ChecksumAlgorithm;
+pub use checksum::ChecksumReader;
+
 mod writer;
 pub use writer::BlockingWriter;
 pub use writer::Writer;
@@ -57,4 +62,11 @@ pub use scheme::Scheme;
 mod capability;
 pub use capability::Capability;
 
+mod acl;
+pub use acl::Acl;
+pub use acl::AclGrant;
+pub use acl::AclGrantee;
+pub use acl::AclOwner;
+pub use acl::AclPermission;
+
 pub mod ops;
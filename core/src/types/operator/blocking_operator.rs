@@ -29,6 +29,11 @@ use crate::*;
 ///
 /// Read [`concepts`][docs::concepts] for know more about [`Operator`].
 ///
+/// # Clone
+///
+/// BlockingOperator shares the same accessor with the [`Operator`] it
+/// comes from, so `clone()` is cheap and never rebuilds the backend.
+///
 /// # Examples
 ///
 /// Read more backend init examples in [`services`]
@@ -145,9 +150,30 @@ impl BlockingOperator {
     /// # }
     /// ```
     pub fn stat(&self, path: &str) -> Result<Metadata> {
+        self.stat_with(path, OpStat::new())
+    }
+
+    /// Get current path's metadata **without cache** directly with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::BlockingOperator;
+    /// # use opendal::ops::OpStat;
+    /// #
+    /// # fn test(op: BlockingOperator) -> Result<()> {
+    /// let meta = op.stat_with("link", OpStat::new().with_follow_symlink(false))?;
+    /// if meta.is_symlink() {
+    ///     println!("link to {:?}", meta.symlink_target())
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stat_with(&self, path: &str, args: OpStat) -> Result<Metadata> {
         let path = normalize_path(path);
 
-        let rp = self.inner().blocking_stat(&path, OpStat::new())?;
+        let rp = self.inner().blocking_stat(&path, args)?;
         let meta = rp.into_metadata();
 
         Ok(meta)
@@ -338,6 +364,13 @@ impl BlockingOperator {
     /// This function will allocate a new bytes internally. For more precise memory control or
     /// reading data lazily, please use [`BlockingOperator::range_reader`]
     ///
+    /// # Notes
+    ///
+    /// - The returning content's length may be smaller than the range specified:
+    ///   ranges extending beyond the end of file will be clamped.
+    /// - Returns [`ErrorKind::RangeNotSatisfied`](crate::ErrorKind::RangeNotSatisfied)
+    ///   if the range starts at or beyond the end of file.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -399,6 +432,13 @@ impl BlockingOperator {
 
     /// Create a new reader which can read the specified range.
     ///
+    /// # Notes
+    ///
+    /// - The returning content's length may be smaller than the range specified:
+    ///   ranges extending beyond the end of file will be clamped.
+    /// - Returns [`ErrorKind::RangeNotSatisfied`](crate::ErrorKind::RangeNotSatisfied)
+    ///   if the range starts at or beyond the end of file.
+    ///
     /// # Examples
     ///
     /// ```no_run
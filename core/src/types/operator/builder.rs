@@ -135,6 +135,192 @@ impl Operator {
         Ok(OperatorBuilder::new(acc))
     }
 
+    /// Create a new operator from given map after checking its keys via
+    /// [`Builder::check_config_keys`].
+    fn from_checked_map<B: Builder>(
+        map: HashMap<String, String>,
+    ) -> Result<OperatorBuilder<impl Accessor>> {
+        B::check_config_keys(&map)?;
+        Self::from_map::<B>(map)
+    }
+
+    /// Create a new operator from given scheme and map.
+    ///
+    /// Unlike [`Operator::from_map`], the service is picked at runtime
+    /// by `scheme`, so users can build operators from config files
+    /// without writing a `match` over all services.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::ConfigInvalid`] if the scheme is unknown or its
+    /// feature is not enabled, or if the required keys of this service are
+    /// missing, or if any key is not listed in [`Builder::CONFIG_KEYS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// use std::collections::HashMap;
+    ///
+    /// use opendal::Operator;
+    /// use opendal::Scheme;
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let map = HashMap::from([
+    ///         // Set the root for fs, all operations will happen under this root.
+    ///         //
+    ///         // NOTE: the root must be absolute path.
+    ///         ("root".to_string(), "/tmp".to_string()),
+    ///     ]);
+    ///
+    ///     // Build an `Operator` to start operating the storage.
+    ///     let op: Operator = Operator::via_map(Scheme::Fs, map)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[allow(unused_variables, unreachable_code)]
+    pub fn via_map(scheme: Scheme, map: HashMap<String, String>) -> Result<Operator> {
+        let op = match scheme {
+            #[cfg(feature = "services-azblob")]
+            Scheme::Azblob => Self::from_checked_map::<services::Azblob>(map)?.finish(),
+            #[cfg(feature = "services-azdfs")]
+            Scheme::Azdfs => Self::from_checked_map::<services::Azdfs>(map)?.finish(),
+            #[cfg(feature = "services-dashmap")]
+            Scheme::Dashmap => Self::from_checked_map::<services::Dashmap>(map)?.finish(),
+            #[cfg(feature = "services-fs")]
+            Scheme::Fs => Self::from_checked_map::<services::Fs>(map)?.finish(),
+            #[cfg(feature = "services-ftp")]
+            Scheme::Ftp => Self::from_checked_map::<services::Ftp>(map)?.finish(),
+            #[cfg(feature = "services-gcs")]
+            Scheme::Gcs => Self::from_checked_map::<services::Gcs>(map)?.finish(),
+            #[cfg(feature = "services-gdrive")]
+            Scheme::Gdrive => Self::from_checked_map::<services::Gdrive>(map)?.finish(),
+            #[cfg(feature = "services-ghac")]
+            Scheme::Ghac => Self::from_checked_map::<services::Ghac>(map)?.finish(),
+            #[cfg(feature = "services-hdfs")]
+            Scheme::Hdfs => Self::from_checked_map::<services::Hdfs>(map)?.finish(),
+            #[cfg(feature = "services-http")]
+            Scheme::Http => Self::from_checked_map::<services::Http>(map)?.finish(),
+            #[cfg(feature = "services-ipfs")]
+            Scheme::Ipfs => Self::from_checked_map::<services::Ipfs>(map)?.finish(),
+            #[cfg(feature = "services-ipmfs")]
+            Scheme::Ipmfs => Self::from_checked_map::<services::Ipmfs>(map)?.finish(),
+            #[cfg(feature = "services-memcached")]
+            Scheme::Memcached => Self::from_checked_map::<services::Memcached>(map)?.finish(),
+            #[cfg(feature = "services-memory")]
+            Scheme::Memory => Self::from_checked_map::<services::Memory>(map)?.finish(),
+            #[cfg(feature = "services-moka")]
+            Scheme::Moka => Self::from_checked_map::<services::Moka>(map)?.finish(),
+            #[cfg(feature = "services-obs")]
+            Scheme::Obs => Self::from_checked_map::<services::Obs>(map)?.finish(),
+            #[cfg(feature = "services-onedrive")]
+            Scheme::Onedrive => Self::from_checked_map::<services::Onedrive>(map)?.finish(),
+            #[cfg(feature = "services-oss")]
+            Scheme::Oss => Self::from_checked_map::<services::Oss>(map)?.finish(),
+            #[cfg(feature = "services-redis")]
+            Scheme::Redis => Self::from_checked_map::<services::Redis>(map)?.finish(),
+            #[cfg(feature = "services-rocksdb")]
+            Scheme::Rocksdb => Self::from_checked_map::<services::Rocksdb>(map)?.finish(),
+            #[cfg(feature = "services-s3")]
+            Scheme::S3 => Self::from_checked_map::<services::S3>(map)?.finish(),
+            #[cfg(feature = "services-sftp")]
+            Scheme::Sftp => Self::from_checked_map::<services::Sftp>(map)?.finish(),
+            #[cfg(feature = "services-sled")]
+            Scheme::Sled => Self::from_checked_map::<services::Sled>(map)?.finish(),
+            #[cfg(feature = "services-supabase")]
+            Scheme::Supabase => Self::from_checked_map::<services::Supabase>(map)?.finish(),
+            #[cfg(feature = "services-vercel-artifacts")]
+            Scheme::VercelArtifacts => {
+                Self::from_checked_map::<services::VercelArtifacts>(map)?.finish()
+            }
+            #[cfg(feature = "services-wasabi")]
+            Scheme::Wasabi => Self::from_checked_map::<services::Wasabi>(map)?.finish(),
+            #[cfg(feature = "services-webdav")]
+            Scheme::Webdav => Self::from_checked_map::<services::Webdav>(map)?.finish(),
+            #[cfg(feature = "services-webhdfs")]
+            Scheme::Webhdfs => Self::from_checked_map::<services::Webhdfs>(map)?.finish(),
+            v => return Err(unsupported_scheme("Operator::via_map", v)),
+        };
+
+        Ok(op)
+    }
+
+    /// Create a new operator from given scheme and env.
+    ///
+    /// Env values are loaded with prefix `OPENDAL_{SCHEME}_`, for example
+    /// `OPENDAL_S3_BUCKET`. See [`Builder::from_env`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::ConfigInvalid`] if the scheme is unknown or its
+    /// feature is not enabled, or if the required keys of this service are
+    /// missing.
+    #[allow(unused_variables, unreachable_code)]
+    pub fn via_env(scheme: Scheme) -> Result<Operator> {
+        let op = match scheme {
+            #[cfg(feature = "services-azblob")]
+            Scheme::Azblob => Self::from_env::<services::Azblob>()?.finish(),
+            #[cfg(feature = "services-azdfs")]
+            Scheme::Azdfs => Self::from_env::<services::Azdfs>()?.finish(),
+            #[cfg(feature = "services-dashmap")]
+            Scheme::Dashmap => Self::from_env::<services::Dashmap>()?.finish(),
+            #[cfg(feature = "services-fs")]
+            Scheme::Fs => Self::from_env::<services::Fs>()?.finish(),
+            #[cfg(feature = "services-ftp")]
+            Scheme::Ftp => Self::from_env::<services::Ftp>()?.finish(),
+            #[cfg(feature = "services-gcs")]
+            Scheme::Gcs => Self::from_env::<services::Gcs>()?.finish(),
+            #[cfg(feature = "services-gdrive")]
+            Scheme::Gdrive => Self::from_env::<services::Gdrive>()?.finish(),
+            #[cfg(feature = "services-ghac")]
+            Scheme::Ghac => Self::from_env::<services::Ghac>()?.finish(),
+            #[cfg(feature = "services-hdfs")]
+            Scheme::Hdfs => Self::from_env::<services::Hdfs>()?.finish(),
+            #[cfg(feature = "services-http")]
+            Scheme::Http => Self::from_env::<services::Http>()?.finish(),
+            #[cfg(feature = "services-ipfs")]
+            Scheme::Ipfs => Self::from_env::<services::Ipfs>()?.finish(),
+            #[cfg(feature = "services-ipmfs")]
+            Scheme::Ipmfs => Self::from_env::<services::Ipmfs>()?.finish(),
+            #[cfg(feature = "services-memcached")]
+            Scheme::Memcached => Self::from_env::<services::Memcached>()?.finish(),
+            #[cfg(feature = "services-memory")]
+            Scheme::Memory => Self::from_env::<services::Memory>()?.finish(),
+            #[cfg(feature = "services-moka")]
+            Scheme::Moka => Self::from_env::<services::Moka>()?.finish(),
+            #[cfg(feature = "services-obs")]
+            Scheme::Obs => Self::from_env::<services::Obs>()?.finish(),
+            #[cfg(feature = "services-onedrive")]
+            Scheme::Onedrive => Self::from_env::<services::Onedrive>()?.finish(),
+            #[cfg(feature = "services-oss")]
+            Scheme::Oss => Self::from_env::<services::Oss>()?.finish(),
+            #[cfg(feature = "services-redis")]
+            Scheme::Redis => Self::from_env::<services::Redis>()?.finish(),
+            #[cfg(feature = "services-rocksdb")]
+            Scheme::Rocksdb => Self::from_env::<services::Rocksdb>()?.finish(),
+            #[cfg(feature = "services-s3")]
+            Scheme::S3 => Self::from_env::<services::S3>()?.finish(),
+            #[cfg(feature = "services-sftp")]
+            Scheme::Sftp => Self::from_env::<services::Sftp>()?.finish(),
+            #[cfg(feature = "services-sled")]
+            Scheme::Sled => Self::from_env::<services::Sled>()?.finish(),
+            #[cfg(feature = "services-supabase")]
+            Scheme::Supabase => Self::from_env::<services::Supabase>()?.finish(),
+            #[cfg(feature = "services-vercel-artifacts")]
+            Scheme::VercelArtifacts => Self::from_env::<services::VercelArtifacts>()?.finish(),
+            #[cfg(feature = "services-wasabi")]
+            Scheme::Wasabi => Self::from_env::<services::Wasabi>()?.finish(),
+            #[cfg(feature = "services-webdav")]
+            Scheme::Webdav => Self::from_env::<services::Webdav>()?.finish(),
+            #[cfg(feature = "services-webhdfs")]
+            Scheme::Webhdfs => Self::from_env::<services::Webhdfs>()?.finish(),
+            v => return Err(unsupported_scheme("Operator::via_env", v)),
+        };
+
+        Ok(op)
+    }
+
     /// Create a new layer with dynamic dispatch.
     ///
     /// # Notes
@@ -274,3 +460,75 @@ impl<A: Accessor> OperatorBuilder<A> {
         Operator::from_inner(Arc::new(ob.accessor) as FusedAccessor)
     }
 }
+
+/// Build the error returned while the scheme is not supported.
+fn unsupported_scheme(operation: &'static str, scheme: Scheme) -> Error {
+    Error::new(
+        ErrorKind::ConfigInvalid,
+        "scheme is unknown or its feature is not enabled",
+    )
+    .with_operation(operation)
+    .with_context("scheme", scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "services-memory")]
+    fn test_via_map() {
+        let op = Operator::via_map(Scheme::Memory, HashMap::new()).expect("must succeed");
+        assert_eq!(op.info().scheme(), Scheme::Memory);
+
+        let err =
+            Operator::via_map(Scheme::Custom("unknown"), HashMap::new()).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("unknown"));
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_via_map_missing_required_key() {
+        let err = Operator::via_map(Scheme::S3, HashMap::new()).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("bucket is empty"));
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_via_map_unknown_key() {
+        let map = HashMap::from([
+            ("bucket".to_string(), "test".to_string()),
+            ("region".to_string(), "us-east-1".to_string()),
+            ("enable_virtual_host".to_string(), "true".to_string()),
+        ]);
+        let err = Operator::via_map(Scheme::S3, map).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("keys: enable_virtual_host"));
+    }
+
+    #[test]
+    fn test_via_map_unknown_key_of_memory() {
+        let map = HashMap::from([("max_szie".to_string(), "1024".to_string())]);
+        let err = Operator::via_map(Scheme::Memory, map).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("keys: max_szie"));
+
+        let map = HashMap::from([("max_size".to_string(), "1024".to_string())]);
+        Operator::via_map(Scheme::Memory, map).expect("must succeed");
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_via_map_invalid_value() {
+        let map = HashMap::from([
+            ("bucket".to_string(), "test".to_string()),
+            ("region".to_string(), "us-east-1".to_string()),
+            ("write_min_size".to_string(), "8MiB".to_string()),
+        ]);
+        let err = Operator::via_map(Scheme::S3, map).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("write_min_size"));
+    }
+}
@@ -15,23 +15,77 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::ops::RangeBounds;
 use std::time::Duration;
 
 use bytes::Bytes;
+use bytes::BytesMut;
 use flagset::FlagSet;
 use futures::stream;
 use futures::AsyncReadExt;
 use futures::Stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
+use serde::de::DeserializeOwned;
 use tokio::io::ReadBuf;
 
 use super::BlockingOperator;
 use crate::ops::*;
+use crate::raw::oio::ReadExt;
+use crate::raw::oio::Write;
 use crate::raw::*;
 use crate::*;
 
+/// The count of batch delete requests that could be sent concurrently by
+/// [`Operator::delete_iter`].
+const DELETE_BATCH_CONCURRENCY: usize = 8;
+
+/// State of the stream returned by [`Operator::read_jsonl`].
+struct JsonLinesState {
+    r: Reader,
+    /// Content that has been read but not returned yet.
+    buf: BytesMut,
+    /// Length of the prefix in `buf` that has no newline.
+    scanned: usize,
+    /// Number of the last returned line.
+    line: usize,
+    eof: bool,
+}
+
+impl JsonLinesState {
+    /// Returns the next non-blank line and its line number.
+    async fn next_line(&mut self) -> Result<Option<(usize, Bytes)>> {
+        loop {
+            let newline = self.buf[self.scanned..].iter().position(|b| *b == b'\n');
+            let bs = if let Some(pos) = newline {
+                let bs = self.buf.split_to(self.scanned + pos + 1).freeze();
+                self.scanned = 0;
+                bs
+            } else if self.eof {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                self.scanned = 0;
+                self.buf.split().freeze()
+            } else {
+                self.scanned = self.buf.len();
+                match ReadExt::next(&mut self.r).await {
+                    Some(bs) => self.buf.extend_from_slice(&bs?),
+                    None => self.eof = true,
+                }
+                continue;
+            };
+
+            self.line += 1;
+            if !bs.iter().all(|b| b.is_ascii_whitespace()) {
+                return Ok(Some((self.line, bs)));
+            }
+        }
+    }
+}
+
 /// Operator is the entry for all public async APIs.
 /// Developer should manipulate the data from storage service through Operator only by right.
 ///
@@ -39,6 +93,24 @@ use crate::*;
 /// like normalizing path from input, checking whether the path refers to one file or one directory, and so on.
 /// Read [`concepts`][docs::concepts] for more about [`Operator`].
 ///
+/// # Cancellation Safety
+///
+/// It's safe to drop the futures returned by one-shot operations like
+/// `stat`, `read`, `write`, `copy`, `rename`, `delete` and `create_dir`.
+/// Operator itself will not be affected, but the request may or may not
+/// have taken effect on the service.
+///
+/// [`Reader`] and [`Lister`] keep their ongoing futures inside, so it's
+/// fine to stop polling them and resume later. [`Writer`] is not
+/// cancellation safe, please read its docs for more details.
+///
+/// # Clone
+///
+/// Operator holds its accessor in an [`Arc`][std::sync::Arc], so `clone()`
+/// is cheap and never rebuilds the backend. All clones share the same
+/// accessor, including the http client and its connection pool, so it's
+/// fine to clone an operator for every spawned task.
+///
 /// # Examples
 ///
 /// Read more backend init examples in [`services`]
@@ -86,7 +158,7 @@ impl Operator {
         Self { accessor, limit }
     }
 
-    pub(super) fn into_inner(self) -> FusedAccessor {
+    pub(crate) fn into_inner(self) -> FusedAccessor {
         self.accessor
     }
 
@@ -171,6 +243,10 @@ impl Operator {
     /// returned by [`Lister`]. It's highly possible that metadata
     /// you want has already been cached.
     ///
+    /// On object storage services, stat a path ending with `/` which is
+    /// created by `create_dir` will return a directory marker, check
+    /// [`Metadata::is_dir_marker`] for details.
+    ///
     /// # Examples
     ///
     /// ```
@@ -455,7 +531,10 @@ impl Operator {
     ///
     /// # Notes
     ///
-    /// - The returning content's length may be smaller than the range specified.
+    /// - The returning content's length may be smaller than the range specified:
+    ///   ranges extending beyond the end of file will be clamped.
+    /// - Returns [`ErrorKind::RangeNotSatisfied`](crate::ErrorKind::RangeNotSatisfied)
+    ///   if the range starts at or beyond the end of file.
     ///
     /// # Examples
     ///
@@ -481,7 +560,10 @@ impl Operator {
     ///
     /// # Notes
     ///
-    /// - The returning content's length may be smaller than the range specified.
+    /// - The returning content's length may be smaller than the range specified:
+    ///   ranges extending beyond the end of file will be clamped.
+    /// - Returns [`ErrorKind::RangeNotSatisfied`](crate::ErrorKind::RangeNotSatisfied)
+    ///   if the range starts at or beyond the end of file.
     ///
     /// # Examples
     ///
@@ -517,7 +599,24 @@ impl Operator {
 
         let br = BytesRange::from(range);
 
-        let (rp, mut s) = self.inner().read(&path, args.with_range(br)).await?;
+        let args = args.with_range(br);
+        let (rp, s) = self.inner().read(&path, args.clone()).await?;
+        let (rp, mut s) = oio::decode_by_content_encoding(&args, rp, s);
+
+        // The length of decoded content is unknown until it's read.
+        if !rp.metadata().bit().contains(Metakey::ContentLength) {
+            let mut buffer = Vec::new();
+            s.read_to_end(&mut buffer).await.map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "read from storage")
+                    .with_operation("range_read")
+                    .with_context("service", self.inner().info().scheme().into_static())
+                    .with_context("path", &path)
+                    .with_context("range", br.to_string())
+                    .set_source(err)
+            })?;
+
+            return Ok(buffer);
+        }
 
         let length = rp.into_metadata().content_length() as usize;
         let mut buffer = Vec::with_capacity(length);
@@ -567,7 +666,10 @@ impl Operator {
     ///
     /// # Notes
     ///
-    /// - The returning content's length may be smaller than the range specified.
+    /// - The returning content's length may be smaller than the range specified:
+    ///   ranges extending beyond the end of file will be clamped.
+    /// - Returns [`ErrorKind::RangeNotSatisfied`](crate::ErrorKind::RangeNotSatisfied)
+    ///   if the range starts at or beyond the end of file.
     ///
     /// # Examples
     ///
@@ -619,6 +721,156 @@ impl Operator {
         Reader::create_dir(self.inner().clone(), &path, args).await
     }
 
+    /// Read the whole path into a bytes and compute its checksum in the
+    /// same pass.
+    ///
+    /// The checksum is computed from the content read, it's not verified
+    /// against the checksum stored by services.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::ChecksumAlgorithm;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let (bs, checksum) = op
+    ///     .read_with_checksum("path/to/file", ChecksumAlgorithm::Sha256)
+    ///     .await?;
+    /// println!("sha256 of {} bytes: {}", bs.len(), checksum.to_hex());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_with_checksum(
+        &self,
+        path: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<(Vec<u8>, Checksum)> {
+        let mut r = self.reader_with_checksum(path, algorithm).await?;
+
+        let mut buffer = Vec::new();
+        r.read_to_end(&mut buffer).await.map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "read from storage")
+                .with_operation("Operator::read_with_checksum")
+                .with_context("service", self.info().scheme().into_static())
+                .with_context("path", path)
+                .set_source(err)
+        })?;
+
+        let checksum = r
+            .checksum()
+            .cloned()
+            .expect("checksum must be computed after read to end");
+        Ok((buffer, checksum))
+    }
+
+    /// Create a new reader which computes checksum of the content while
+    /// reading.
+    ///
+    /// [`ChecksumReader::checksum`] returns the checksum after the reader
+    /// has been drained.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::ChecksumAlgorithm;
+    /// # use opendal::Operator;
+    /// # use futures::TryStreamExt;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut r = op
+    ///     .reader_with_checksum("path/to/file", ChecksumAlgorithm::Crc32c)
+    ///     .await?;
+    /// while let Some(bs) = r.try_next().await? {
+    ///     println!("read {} bytes", bs.len());
+    /// }
+    /// let checksum = r.checksum().expect("checksum must be computed");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reader_with_checksum(
+        &self,
+        path: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<ChecksumReader> {
+        let r = self.reader(path).await?;
+
+        Ok(r.with_checksum(algorithm))
+    }
+
+    /// Read newline delimited JSON at path as a stream of `T`.
+    ///
+    /// # Notes
+    ///
+    /// - Content is read and deserialized line by line, the whole file
+    ///   won't be buffered in memory.
+    /// - Blank lines are skipped, and both `\n` and `\r\n` are accepted.
+    /// - Lines failed to deserialize will return errors with the 1-based
+    ///   line number in context, the stream can still be polled after that.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use futures::TryStreamExt;
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    ///     id: u64,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut events = op.read_jsonl::<Event>("path/to/events.jsonl").await?;
+    /// while let Some(event) = events.try_next().await? {
+    ///     println!("{}", event.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_jsonl<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<impl Stream<Item = Result<T>> + Unpin> {
+        let r = self.reader(path).await?;
+
+        let scheme = self.info().scheme();
+        let path = normalize_path(path);
+        let state = JsonLinesState {
+            r,
+            buf: BytesMut::new(),
+            scanned: 0,
+            line: 0,
+            eof: false,
+        };
+
+        Ok(Box::pin(stream::unfold(Some(state), move |state| {
+            let path = path.clone();
+            async move {
+                let mut st = state?;
+                let (line, bs) = match st.next_line().await {
+                    Ok(Some(v)) => v,
+                    Ok(None) => return None,
+                    // Reading errors can't be recovered, end the stream after it.
+                    Err(err) => return Some((Err(err), None)),
+                };
+
+                let res = serde_json::from_slice(&bs).map_err(|err| {
+                    Error::new(ErrorKind::Unexpected, "deserialize json line failed")
+                        .with_operation("Operator::read_jsonl")
+                        .with_context("service", scheme)
+                        .with_context("path", &path)
+                        .with_context("line", line.to_string())
+                        .set_source(err)
+                });
+                Some((res, Some(st)))
+            }
+        })))
+    }
+
     /// Write bytes into path.
     ///
     /// # Notes
@@ -672,6 +924,29 @@ impl Operator {
     /// # }
     /// ```
     pub async fn copy(&self, from: &str, to: &str) -> Result<()> {
+        self.copy_with(from, to, OpCopy::new()).await
+    }
+
+    /// Copy a file from `from` to `to` with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use opendal::ops::CopyDirective;
+    /// use opendal::ops::OpCopy;
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let args = OpCopy::new()
+    ///     .with_metadata_directive(CopyDirective::Replace)
+    ///     .with_content_type("text/plain");
+    /// op.copy_with("path/to/file", "path/to/file2", args).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_with(&self, from: &str, to: &str, args: OpCopy) -> Result<()> {
         let from = normalize_path(from);
 
         if !validate_path(&from, EntryMode::FILE) {
@@ -704,7 +979,7 @@ impl Operator {
             );
         }
 
-        self.inner().copy(&from, &to, OpCopy::new()).await?;
+        self.inner().copy(&from, &to, args).await?;
 
         Ok(())
     }
@@ -767,6 +1042,159 @@ impl Operator {
         Ok(())
     }
 
+    /// Transfer a file from `from` of current operator to `to` of another
+    /// operator.
+    ///
+    /// Data will be streamed from the reader of current operator into the
+    /// writer of target operator, so the whole file will not be buffered
+    /// in memory.
+    ///
+    /// # Notes
+    ///
+    /// - `from` and `to` must be a file.
+    /// - `to` will be overwritten if it exists.
+    /// - `content-type`, `cache-control` and `content-disposition` will be
+    ///   preserved if target operator supports writing them.
+    /// - If both operators point to the same storage and `from` and `to`
+    ///   are the same, an `IsSameFile` error will occur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(from_op: Operator, to_op: Operator) -> Result<()> {
+    /// from_op
+    ///     .transfer("path/to/file", &to_op, "path/to/file")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn transfer(&self, from: &str, to_op: &Operator, to: &str) -> Result<()> {
+        let from = normalize_path(from);
+
+        if !validate_path(&from, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "from path is a directory")
+                    .with_operation("Operator::transfer")
+                    .with_context("service", self.info().scheme())
+                    .with_context("from", from),
+            );
+        }
+
+        let to = normalize_path(to);
+
+        if !validate_path(&to, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "to path is a directory")
+                    .with_operation("Operator::transfer")
+                    .with_context("service", to_op.info().scheme())
+                    .with_context("to", to),
+            );
+        }
+
+        let (from_info, to_info) = (self.info(), to_op.info());
+        if from == to
+            && from_info.scheme() == to_info.scheme()
+            && from_info.name() == to_info.name()
+            && from_info.root() == to_info.root()
+        {
+            return Err(
+                Error::new(ErrorKind::IsSameFile, "from and to paths are same")
+                    .with_operation("Operator::transfer")
+                    .with_context("service", self.info().scheme())
+                    .with_context("from", from)
+                    .with_context("to", to),
+            );
+        }
+
+        let meta = self
+            .inner()
+            .stat(&from, OpStat::new())
+            .await?
+            .into_metadata();
+
+        let cap = to_info.capability();
+        let mut args = OpWrite::new().with_content_length(meta.content_length());
+        if let Some(v) = meta.content_type().filter(|_| cap.write_with_content_type) {
+            args = args.with_content_type(v);
+        }
+        if let Some(v) = meta
+            .cache_control()
+            .filter(|_| cap.write_with_cache_control)
+        {
+            args = args.with_cache_control(v);
+        }
+        if let Some(v) = meta
+            .content_disposition()
+            .filter(|_| cap.write_with_content_disposition)
+        {
+            args = args.with_content_disposition(v);
+        }
+
+        let (_, mut r) = self.inner().read(&from, OpRead::new()).await?;
+        let (_, mut w) = to_op.inner().write(&to, args).await?;
+
+        let res = async {
+            while let Some(bs) = ReadExt::next(&mut r).await {
+                w.write(bs?).await?;
+            }
+            w.close().await
+        }
+        .await;
+
+        if let Err(err) = res {
+            // Abort is best effort here, the original error is more useful.
+            let _ = w.abort().await;
+            return Err(err
+                .with_operation("Operator::transfer")
+                .with_context("from", from)
+                .with_context("to", to));
+        }
+
+        Ok(())
+    }
+
+    /// Transfer files from current operator to another operator
+    /// concurrently.
+    ///
+    /// Every item of input is a pair of `(from, to)` path. At most
+    /// [`Operator::limit`] files will be transferred at the same time.
+    ///
+    /// Refer to [`Operator::transfer`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use futures::stream;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(from_op: Operator, to_op: Operator) -> Result<()> {
+    /// let stream = stream::iter(vec![
+    ///     ("abc".to_string(), "abc".to_string()),
+    ///     ("def".to_string(), "backup/def".to_string()),
+    /// ]);
+    /// from_op.with_limit(16).transfer_via(&to_op, stream).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn transfer_via(
+        &self,
+        to_op: &Operator,
+        input: impl Stream<Item = (String, String)> + Unpin,
+    ) -> Result<()> {
+        input
+            .map(Ok)
+            .try_for_each_concurrent(self.limit, |(from, to)| async move {
+                self.transfer(&from, to_op, &to).await
+            })
+            .await
+    }
+
     /// Write multiple bytes into path.
     ///
     /// Refer to [`Writer`] for more details.
@@ -877,6 +1305,59 @@ impl Operator {
         Ok(())
     }
 
+    /// Write bytes into path only if the path doesn't exist.
+    ///
+    /// Returns `Ok(true)` if the bytes have been written, or `Ok(false)` if
+    /// the path already exists and is left untouched.
+    ///
+    /// # Notes
+    ///
+    /// - Services that support `write_with_if_none_match` will write with
+    ///   `If-None-Match: *`, which is atomic.
+    /// - Other services will `stat` the path before writing. It's racy:
+    ///   another writer could create the path between `stat` and `write`,
+    ///   and its content will be overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// if !op.write_if_not_exists("path/to/lock", "owner").await? {
+    ///     println!("lock has been held by others");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_if_not_exists(&self, path: &str, bs: impl Into<Bytes>) -> Result<bool> {
+        if self.info().capability().write_with_if_none_match {
+            let res = self
+                .write_with(path, OpWrite::new().with_if_none_match("*"), bs)
+                .await;
+            return match res {
+                Ok(()) => Ok(true),
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::ConditionNotMatch | ErrorKind::AlreadyExists
+                    ) =>
+                {
+                    Ok(false)
+                }
+                Err(err) => Err(err),
+            };
+        }
+
+        if self.is_exist(path).await? {
+            return Ok(false);
+        }
+
+        self.write(path, bs).await?;
+        Ok(true)
+    }
+
     /// Delete the given path.
     ///
     /// # Notes
@@ -896,9 +1377,350 @@ impl Operator {
     /// # }
     /// ```
     pub async fn delete(&self, path: &str) -> Result<()> {
+        self.delete_with(path, OpDelete::new()).await
+    }
+
+    /// Delete given path with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::ops::DeleteSnapshots;
+    /// # use opendal::ops::OpDelete;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.delete_with(
+    ///     "test",
+    ///     OpDelete::new().with_delete_snapshots(DeleteSnapshots::Include),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_with(&self, path: &str, args: OpDelete) -> Result<()> {
+        let path = normalize_path(path);
+
+        let _ = self.inner().delete(&path, args).await?;
+
+        Ok(())
+    }
+
+    /// Restore an archived object so that it can be read.
+    ///
+    /// # Notes
+    ///
+    /// - Restore is asynchronous on most services, reading the object
+    ///   will still return [`ErrorKind::Archived`] until it's finished.
+    ///   Use [`Metadata::restore_status`] to check the progress.
+    /// - Restoring an object which is being restored or has been
+    ///   restored won't return errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.restore("test").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn restore(&self, path: &str) -> Result<()> {
+        self.restore_with(path, OpRestore::new()).await
+    }
+
+    /// Restore an archived object with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::ops::OpRestore;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.restore_with("test", OpRestore::new().with_days(3).with_tier("Expedited"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn restore_with(&self, path: &str, args: OpRestore) -> Result<()> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "restore path is a directory")
+                    .with_operation("Operator::restore")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let _ = self.inner().restore(&path, args).await?;
+
+        Ok(())
+    }
+
+    /// Compose sources into one object at path by concatenating them in
+    /// order on the server side.
+    ///
+    /// # Notes
+    ///
+    /// - Sources will be kept. Use [`Operator::compose_with`] with
+    ///   [`OpCompose::with_delete_sources`] to delete them after composed.
+    /// - Services may compose large sets of sources via intermediate
+    ///   objects, which will be removed before returning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.compose("test", &["shard-0", "shard-1"]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compose(&self, path: &str, sources: &[&str]) -> Result<()> {
+        let sources = sources.iter().map(|v| v.to_string()).collect();
+        self.compose_with(path, OpCompose::new(sources)).await
+    }
+
+    /// Compose sources into one object at path with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::ops::OpCompose;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let sources = vec!["shard-0".to_string(), "shard-1".to_string()];
+    /// op.compose_with("test", OpCompose::new(sources).with_delete_sources(true))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compose_with(&self, path: &str, args: OpCompose) -> Result<()> {
+        let path = normalize_path(path);
+        let args = args.map_sources(normalize_path);
+
+        if args.sources().is_empty() {
+            return Err(
+                Error::new(ErrorKind::Unexpected, "compose sources are empty")
+                    .with_operation("Operator::compose")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        for p in std::iter::once(&path).chain(args.sources()) {
+            if !validate_path(p, EntryMode::FILE) {
+                return Err(
+                    Error::new(ErrorKind::IsADirectory, "compose path is a directory")
+                        .with_operation("Operator::compose")
+                        .with_context("service", self.inner().info().scheme())
+                        .with_context("path", p),
+                );
+            }
+        }
+
+        let _ = self.inner().compose(&path, args).await?;
+
+        Ok(())
+    }
+
+    /// Get the access control list of the object at path.
+    ///
+    /// # Notes
+    ///
+    /// - Services only fill the ACL parts they expose, for example oss
+    ///   only returns the owner and canned ACL.
+    /// - Services without ACL support will return [`ErrorKind::Unsupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let acl = op.get_acl("test").await?;
+    /// if acl.is_public() {
+    ///     println!("test can be read by anyone");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_acl(&self, path: &str) -> Result<Acl> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "get_acl path is a directory")
+                    .with_operation("Operator::get_acl")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let rp = self.inner().get_acl(&path, OpGetAcl::new()).await?;
+
+        Ok(rp.into_acl())
+    }
+
+    /// Create a symlink at `path` which points to `target`.
+    ///
+    /// # Notes
+    ///
+    /// - `target` is relative to the root of operator, it's not required
+    ///   to exist.
+    /// - Existing entry at `path` will be overwritten.
+    /// - Use [`OpStat::with_follow_symlink`] to stat the symlink itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.create_symlink("latest", "v1.0/data.csv").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_symlink(&self, path: &str, target: &str) -> Result<()> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "symlink path is a directory")
+                    .with_operation("Operator::create_symlink")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let target = normalize_path(target);
+
+        let _ = self
+            .inner()
+            .create_symlink(&path, OpCreateSymlink::new(&target))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Truncate the file at `path` to `size` bytes.
+    ///
+    /// # Notes
+    ///
+    /// - `size` must not be larger than the current size of file, content
+    ///   after `size` will be discarded.
+    /// - Only services with `truncate` capability support it, others will
+    ///   return [`ErrorKind::Unsupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.truncate("path/to/file", 1024).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn truncate(&self, path: &str, size: u64) -> Result<()> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "truncate path is a directory")
+                    .with_operation("Operator::truncate")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let _ = self.inner().truncate(&path, OpTruncate::new(size)).await?;
+
+        Ok(())
+    }
+
+    /// Set unix permission bits like `0o755` of the file or dir at `path`.
+    ///
+    /// # Notes
+    ///
+    /// - Permission bits are set as is without being masked by umask.
+    /// - Only services with `set_permissions` capability support it, others
+    ///   will return [`ErrorKind::Unsupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.set_permissions("path/to/file", 0o640).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_permissions(&self, path: &str, mode: u32) -> Result<()> {
         let path = normalize_path(path);
 
-        let _ = self.inner().delete(&path, OpDelete::new()).await?;
+        let _ = self
+            .inner()
+            .set_permissions(&path, OpSetPermissions::new(mode))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set owner and group of the file or dir at `path`.
+    ///
+    /// # Notes
+    ///
+    /// - Owner or group that is not set will be kept unchanged.
+    /// - Only services with `set_owner` capability support it, others will
+    ///   return [`ErrorKind::Unsupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use opendal::ops::OpSetOwner;
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.set_owner(
+    ///     "path/to/file",
+    ///     OpSetOwner::new().with_owner("hive").with_group("hadoop"),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_owner(&self, path: &str, args: OpSetOwner) -> Result<()> {
+        let path = normalize_path(path);
+
+        if args.owner().is_none() && args.group().is_none() {
+            return Err(
+                Error::new(ErrorKind::Unexpected, "set owner without owner and group")
+                    .with_operation("Operator::set_owner")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let _ = self.inner().set_owner(&path, args).await?;
 
         Ok(())
     }
@@ -984,6 +1806,125 @@ impl Operator {
         Ok(())
     }
 
+    /// Delete given paths and return the result of every path in the same
+    /// order as input.
+    ///
+    /// Unlike [`Operator::remove`], failures of some paths won't stop
+    /// deleting others, so users can check which paths failed.
+    ///
+    /// # Notes
+    ///
+    /// If underlying services support delete in batch, paths will be
+    /// chunked by [`Operator::limit`] and deleted in batches concurrently.
+    /// Otherwise, they will be deleted one by one concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let results = op
+    ///     .delete_iter(vec!["abc".to_string(), "def".to_string()])
+    ///     .await;
+    /// for (path, result) in results {
+    ///     if let Err(err) = result {
+    ///         println!("failed to delete {path}: {err}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_iter(
+        &self,
+        iter: impl IntoIterator<Item = String>,
+    ) -> Vec<(String, Result<()>)> {
+        let paths: Vec<String> = iter.into_iter().collect();
+
+        if !self.info().can_batch() {
+            return stream::iter(paths)
+                .map(|path| async move {
+                    let res = self
+                        .inner()
+                        .delete(&normalize_path(&path), OpDelete::default())
+                        .await
+                        .map(|_| ());
+                    (path, res)
+                })
+                .buffered(self.limit)
+                .collect()
+                .await;
+        }
+
+        let chunks: Vec<Vec<String>> = paths
+            .chunks(self.limit())
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        stream::iter(chunks)
+            .map(|chunk| self.delete_batch(chunk))
+            .buffered(DELETE_BATCH_CONCURRENCY)
+            .flat_map(stream::iter)
+            .collect()
+            .await
+    }
+
+    /// Delete a chunk of paths in one batch and map the results back to
+    /// every path.
+    async fn delete_batch(&self, paths: Vec<String>) -> Vec<(String, Result<()>)> {
+        let ops = paths
+            .iter()
+            .map(|v| (normalize_path(v), OpDelete::default().into()))
+            .collect();
+
+        let results = match self.inner().batch(OpBatch::new(ops)).await {
+            Ok(rp) => rp.into_results(),
+            Err(err) => {
+                // Every path in this batch shares the same error.
+                return paths
+                    .into_iter()
+                    .map(|path| {
+                        let mut e = Error::new(err.kind(), &err.to_string())
+                            .with_operation("Operator::delete_iter")
+                            .with_context("path", &path);
+                        if err.is_temporary() {
+                            e = e.set_temporary();
+                        }
+                        (path, Err(e))
+                    })
+                    .collect();
+            }
+        };
+
+        let mut results: HashMap<String, VecDeque<Result<()>>> =
+            results
+                .into_iter()
+                .fold(HashMap::new(), |mut m, (path, res)| {
+                    m.entry(path).or_default().push_back(res.map(|_| ()));
+                    m
+                });
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let res = results
+                    .get_mut(&normalize_path(&path))
+                    .and_then(|v| v.pop_front())
+                    .unwrap_or_else(|| {
+                        Err(Error::new(
+                            ErrorKind::Unexpected,
+                            "batch delete didn't return result of this path",
+                        )
+                        .with_operation("Operator::delete_iter")
+                        .with_context("path", &path))
+                    });
+                (path, res)
+            })
+            .collect()
+    }
+
     /// Remove the path and all nested dirs and files recursively.
     ///
     /// # Notes
@@ -1173,9 +2114,17 @@ impl Operator {
             .with_context("path", &path));
         }
 
-        let (_, pager) = self.inner().list(&path, op).await?;
+        let prefetch = op.prefetch();
+        // Filter entries by predicates locally, it's fine even if the
+        // service has filtered them natively.
+        let filter =
+            (op.modified_after().is_some() || op.size_range().is_some()).then(|| op.clone());
+        let (_, mut pager) = self.inner().list(&path, op).await?;
+        if let Some(filter) = filter {
+            pager = Box::new(oio::to_filtered_pager(pager, &filter));
+        }
 
-        Ok(Lister::new(pager))
+        Ok(Lister::new(pager).with_prefetch(prefetch))
     }
 
     /// List dir in flat way.
@@ -1267,8 +2216,7 @@ impl Operator {
 
         let op = OpPresign::new(OpStat::new(), expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_op(&path, op).await
     }
 
     /// Presign an operation for read.
@@ -1302,8 +2250,7 @@ impl Operator {
 
         let op = OpPresign::new(OpRead::new(), expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_op(&path, op).await
     }
 
     /// Presign an operation for read option described in OpenDAL [rfc-1735](../../docs/rfcs/1735_operation_extension.md).
@@ -1337,8 +2284,7 @@ impl Operator {
 
         let op = OpPresign::new(op, expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_op(&path, op).await
     }
 
     /// Presign an operation for write.
@@ -1406,7 +2352,168 @@ impl Operator {
 
         let op = OpPresign::new(op, expire);
 
-        let rp = self.inner().presign(&path, op).await?;
+        self.presign_op(&path, op).await
+    }
+
+    /// Presign the operation after checking its expire against the max
+    /// expire supported by service.
+    async fn presign_op(&self, path: &str, op: OpPresign) -> Result<PresignedRequest> {
+        if let Some(max_expire) = self.info().capability().presign_max_expire {
+            if op.expire() > max_expire {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "presign expire is longer than the max supported by service",
+                )
+                .with_operation(Operation::Presign)
+                .with_context("service", self.info().scheme())
+                .with_context("path", path)
+                .with_context("expire", format!("{:?}", op.expire()))
+                .with_context("max_expire", format!("{max_expire:?}")));
+            }
+        }
+
+        let rp = self.inner().presign(path, op).await?;
         Ok(rp.into_presigned_request())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::services::Memory;
+
+    /// MockBatchService fails to delete paths containing `fail`, and fails
+    /// the whole batch if any path contains `broken`.
+    #[derive(Debug, Default)]
+    struct MockBatchService;
+
+    #[async_trait]
+    impl Accessor for MockBatchService {
+        type Reader = ();
+        type BlockingReader = ();
+        type Writer = ();
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capability(Capability {
+                delete: true,
+                batch: true,
+                batch_delete: true,
+                ..Default::default()
+            });
+            am
+        }
+
+        async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+            let ops = args.into_operation();
+            if ops.iter().any(|(p, _)| p.contains("broken")) {
+                return Err(Error::new(ErrorKind::Unexpected, "batch is broken").set_temporary());
+            }
+
+            // Return results in reversed order to make sure they are
+            // mapped back by path.
+            let results = ops
+                .into_iter()
+                .rev()
+                .map(|(p, _)| {
+                    let res = if p.contains("fail") {
+                        Err(Error::new(ErrorKind::PermissionDenied, "delete failed"))
+                    } else {
+                        Ok(RpDelete::default().into())
+                    };
+                    (p, res)
+                })
+                .collect();
+            Ok(RpBatch::new(results))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_iter_with_batch() {
+        let op = OperatorBuilder::new(MockBatchService)
+            .finish()
+            .with_limit(2);
+
+        let input: Vec<String> = vec!["a", "fail", "b", "c", "broken", "d"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let results = op.delete_iter(input.clone()).await;
+
+        assert_eq!(
+            results.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>(),
+            input
+        );
+        let failed: Vec<_> = results
+            .iter()
+            .filter_map(|(p, r)| r.as_ref().err().map(|e| (p.as_str(), e.kind())))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                ("fail", ErrorKind::PermissionDenied),
+                ("broken", ErrorKind::Unexpected),
+                ("d", ErrorKind::Unexpected),
+            ]
+        );
+        assert!(results[5].1.as_ref().unwrap_err().is_temporary());
+    }
+
+    /// Compare data pointers only, vtable addresses of trait objects are
+    /// not guaranteed to be unique.
+    fn same_accessor(a: &FusedAccessor, b: &FusedAccessor) -> bool {
+        std::ptr::eq(Arc::as_ptr(a).cast::<()>(), Arc::as_ptr(b).cast::<()>())
+    }
+
+    #[tokio::test]
+    async fn test_clone_shares_accessor() -> Result<()> {
+        let op = Operator::new(Memory::default())?.finish();
+        let cloned = op.clone();
+        assert!(same_accessor(op.inner(), cloned.inner()));
+
+        let limited = op.with_limit(10);
+        assert!(same_accessor(op.inner(), limited.inner()));
+
+        let blocking = op.blocking();
+        assert!(same_accessor(op.inner(), blocking.inner()));
+
+        cloned.write("test", "Hello, World!").await?;
+        assert_eq!(op.read("test").await?, b"Hello, World!");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_jsonl() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Item {
+            id: u64,
+        }
+
+        let op = Operator::new(Memory::default())?.finish();
+        op.write(
+            "test.jsonl",
+            "{\"id\":1}\r\n\n  \n{\"id\":2}\nnot json\n{\"id\":3}",
+        )
+        .await?;
+
+        let items: Vec<_> = op.read_jsonl::<Item>("test.jsonl").await?.collect().await;
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].as_ref().unwrap(), &Item { id: 1 });
+        assert_eq!(items[1].as_ref().unwrap(), &Item { id: 2 });
+        let err = items[2].as_ref().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.to_string().contains("line: 5"), "{err}");
+        assert_eq!(items[3].as_ref().unwrap(), &Item { id: 3 });
+
+        let res = op.read_jsonl::<Item>("not_exist.jsonl").await;
+        assert_eq!(res.err().map(|e| e.kind()), Some(ErrorKind::NotFound));
+        Ok(())
+    }
+}
@@ -21,6 +21,9 @@
 
 use std::time::Duration;
 
+use chrono::DateTime;
+use chrono::Utc;
+
 use crate::raw::*;
 
 /// Args for `create` operation.
@@ -40,15 +43,62 @@ impl OpCreateDir {
 ///
 /// The path must be normalized.
 #[derive(Debug, Clone, Default)]
-pub struct OpDelete {}
+pub struct OpDelete {
+    delete_snapshots: Option<DeleteSnapshots>,
+    if_generation_match: Option<i64>,
+    if_metageneration_match: Option<i64>,
+}
 
 impl OpDelete {
     /// Create a new `OpDelete`.
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Set how the snapshots of the path should be handled while
+    /// deleting.
+    pub fn with_delete_snapshots(mut self, v: DeleteSnapshots) -> Self {
+        self.delete_snapshots = Some(v);
+        self
+    }
+
+    /// Get how the snapshots should be handled while deleting.
+    pub fn delete_snapshots(&self) -> Option<DeleteSnapshots> {
+        self.delete_snapshots
+    }
+
+    /// Only delete if the generation of the path matches the given one.
+    pub fn with_if_generation_match(mut self, generation: i64) -> Self {
+        self.if_generation_match = Some(generation);
+        self
+    }
+
+    /// Get the generation that the path must match.
+    pub fn if_generation_match(&self) -> Option<i64> {
+        self.if_generation_match
+    }
+
+    /// Only delete if the metageneration of the path matches the given one.
+    pub fn with_if_metageneration_match(mut self, metageneration: i64) -> Self {
+        self.if_metageneration_match = Some(metageneration);
+        self
+    }
+
+    /// Get the metageneration that the path must match.
+    pub fn if_metageneration_match(&self) -> Option<i64> {
+        self.if_metageneration_match
     }
 }
 
+/// Directive of how the snapshots are handled while deleting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteSnapshots {
+    /// Delete the path along with all its snapshots.
+    Include,
+    /// Delete only the snapshots of the path but keep itself.
+    Only,
+}
+
 /// Args for `list` operation.
 #[derive(Debug, Clone)]
 pub struct OpList {
@@ -62,6 +112,18 @@ pub struct OpList {
 
     /// The delimiter used to for the list operation. Default to be `/`
     delimiter: String,
+
+    /// The count of pages that could be fetched ahead. Default to be `0`.
+    prefetch: usize,
+
+    /// Whether to include snapshots in the list result. Default to be `false`.
+    include_snapshots: bool,
+
+    /// Only return files modified after this time.
+    modified_after: Option<DateTime<Utc>>,
+
+    /// Only return files whose size is in this inclusive range.
+    size_range: Option<(u64, u64)>,
 }
 
 impl Default for OpList {
@@ -70,6 +132,10 @@ impl Default for OpList {
             limit: None,
             start_after: None,
             delimiter: "/".to_string(),
+            prefetch: 0,
+            include_snapshots: false,
+            modified_after: None,
+            size_range: None,
         }
     }
 }
@@ -112,6 +178,69 @@ impl OpList {
     pub fn delimiter(&self) -> &str {
         &self.delimiter
     }
+
+    /// Change the prefetch depth of this list operation.
+    ///
+    /// Lister will fetch at most `prefetch` pages ahead while entries of
+    /// current page are being consumed. Larger value reduces the latency
+    /// stall between pages but buffers more entries in memory.
+    ///
+    /// The default value is `0` which means the next page will only be
+    /// fetched after current page has been consumed.
+    pub fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Get the prefetch depth of list operation.
+    pub fn prefetch(&self) -> usize {
+        self.prefetch
+    }
+
+    /// Change whether to include snapshots in the list result.
+    ///
+    /// Snapshots will be returned as entries with the same path of their
+    /// base entry, check [`Metadata::snapshot`](crate::Metadata::snapshot)
+    /// to distinguish them.
+    pub fn with_include_snapshots(mut self, include: bool) -> Self {
+        self.include_snapshots = include;
+        self
+    }
+
+    /// Get whether to include snapshots in the list result.
+    pub fn include_snapshots(&self) -> bool {
+        self.include_snapshots
+    }
+
+    /// Only return files modified after given time.
+    ///
+    /// Files are filtered by the metadata returned along with list
+    /// response, no extra `stat` will be sent. Dirs and files without
+    /// last modified in list response are always returned.
+    pub fn with_modified_after(mut self, t: DateTime<Utc>) -> Self {
+        self.modified_after = Some(t);
+        self
+    }
+
+    /// Get the modified_after of list operation.
+    pub fn modified_after(&self) -> Option<DateTime<Utc>> {
+        self.modified_after
+    }
+
+    /// Only return files whose size is in `[min, max]`.
+    ///
+    /// Files are filtered by the metadata returned along with list
+    /// response, no extra `stat` will be sent. Dirs and files without
+    /// content length in list response are always returned.
+    pub fn with_size_range(mut self, min: u64, max: u64) -> Self {
+        self.size_range = Some((min, max));
+        self
+    }
+
+    /// Get the size_range of list operation.
+    pub fn size_range(&self) -> Option<(u64, u64)> {
+        self.size_range
+    }
 }
 
 /// Args for `presign` operation.
@@ -174,6 +303,195 @@ impl From<OpWrite> for PresignOperation {
     }
 }
 
+/// Args for `get_acl` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpGetAcl {}
+
+impl OpGetAcl {
+    /// Create a new `OpGetAcl`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Args for `compose` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpCompose {
+    sources: Vec<String>,
+    delete_sources: bool,
+}
+
+impl OpCompose {
+    /// Create a new `OpCompose` with sources to be concatenated in order.
+    pub fn new(sources: Vec<String>) -> Self {
+        Self {
+            sources,
+            delete_sources: false,
+        }
+    }
+
+    /// Get the sources of compose.
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Set whether to delete sources after they have been composed.
+    pub fn with_delete_sources(mut self, delete_sources: bool) -> Self {
+        self.delete_sources = delete_sources;
+        self
+    }
+
+    /// Get whether to delete sources after they have been composed.
+    pub fn delete_sources(&self) -> bool {
+        self.delete_sources
+    }
+
+    /// Map every source path with given function.
+    pub(crate) fn map_sources(mut self, f: impl Fn(&str) -> String) -> Self {
+        self.sources = self.sources.iter().map(|v| f(v)).collect();
+        self
+    }
+}
+
+/// Args for `restore` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpRestore {
+    days: Option<u32>,
+    tier: Option<String>,
+}
+
+impl OpRestore {
+    /// Create a new `OpRestore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the days that restored copy will be kept.
+    ///
+    /// Service's default will be used if not set.
+    pub fn with_days(mut self, days: u32) -> Self {
+        self.days = Some(days);
+        self
+    }
+
+    /// Get the days that restored copy will be kept.
+    pub fn days(&self) -> Option<u32> {
+        self.days
+    }
+
+    /// Set the tier of restore, like `Expedited`, `Standard` or `Bulk`.
+    ///
+    /// Available tiers are decided by services, service's default will be
+    /// used if not set.
+    pub fn with_tier(mut self, tier: &str) -> Self {
+        self.tier = Some(tier.to_string());
+        self
+    }
+
+    /// Get the tier of restore.
+    pub fn tier(&self) -> Option<&str> {
+        self.tier.as_deref()
+    }
+}
+
+/// Args for `create_symlink` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpCreateSymlink {
+    target: String,
+}
+
+impl OpCreateSymlink {
+    /// Create a new `OpCreateSymlink` which points to `target`.
+    ///
+    /// `target` is the path of the entry that symlink points to, it's
+    /// relative to the root of operator.
+    pub fn new(target: &str) -> Self {
+        Self {
+            target: target.to_string(),
+        }
+    }
+
+    /// Get the target of symlink.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+/// Args for `truncate` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpTruncate {
+    size: u64,
+}
+
+impl OpTruncate {
+    /// Create a new `OpTruncate` which truncates file to `size` bytes.
+    pub fn new(size: u64) -> Self {
+        Self { size }
+    }
+
+    /// Get the size that file will be truncated to.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Args for `set_permissions` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpSetPermissions {
+    mode: u32,
+}
+
+impl OpSetPermissions {
+    /// Create a new `OpSetPermissions` which sets unix permission bits
+    /// like `0o755`.
+    pub fn new(mode: u32) -> Self {
+        Self { mode }
+    }
+
+    /// Get the unix permission bits to set.
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+}
+
+/// Args for `set_owner` operation.
+#[derive(Debug, Clone, Default)]
+pub struct OpSetOwner {
+    owner: Option<String>,
+    group: Option<String>,
+}
+
+impl OpSetOwner {
+    /// Create a new `OpSetOwner`.
+    ///
+    /// Owner and group that are not set will be kept unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the owner to set.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Set the owner of entry.
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        self.owner = Some(owner.to_string());
+        self
+    }
+
+    /// Get the group to set.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Set the group of entry.
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+}
+
 /// Args for `batch` operation.
 #[derive(Debug, Clone)]
 pub struct OpBatch {
@@ -227,8 +545,13 @@ pub struct OpRead {
     br: BytesRange,
     if_match: Option<String>,
     if_none_match: Option<String>,
+    if_modified_since: Option<DateTime<Utc>>,
     override_cache_control: Option<String>,
     override_content_disposition: Option<String>,
+    no_follow_symlink: bool,
+    content_encoding_decode: bool,
+    snapshot: Option<String>,
+    generation: Option<i64>,
 }
 
 impl OpRead {
@@ -243,6 +566,18 @@ impl OpRead {
         self
     }
 
+    /// Create a new OpRead which reads the last `size` bytes, a.k.a.,
+    /// `Range: bytes=-<size>`.
+    ///
+    /// It's the same as `with_range((..size).into())`. The whole file will
+    /// be returned if it's smaller than `size`, use
+    /// [`Reader::content_range`](crate::Reader::content_range) to get the
+    /// absolute start position of returned content.
+    pub fn with_suffix(mut self, size: u64) -> Self {
+        self.br = BytesRange::new(None, Some(size));
+        self
+    }
+
     /// Get range from OpRead.
     pub fn range(&self) -> BytesRange {
         self.br
@@ -292,6 +627,109 @@ impl OpRead {
     pub fn if_none_match(&self) -> Option<&str> {
         self.if_none_match.as_deref()
     }
+
+    /// Set the If-Modified-Since of the option
+    ///
+    /// [`ErrorKind::NotModified`](crate::ErrorKind::NotModified) will be
+    /// returned if the content has not been modified since the given time.
+    pub fn with_if_modified_since(mut self, if_modified_since: DateTime<Utc>) -> Self {
+        self.if_modified_since = Some(if_modified_since);
+        self
+    }
+
+    /// Get If-Modified-Since from option
+    pub fn if_modified_since(&self) -> Option<DateTime<Utc>> {
+        self.if_modified_since
+    }
+
+    /// Set whether to follow symlink while read, default to `true`.
+    ///
+    /// If `false`, reading a symlink will return
+    /// [`ErrorKind::ConditionNotMatch`](crate::ErrorKind::ConditionNotMatch)
+    /// instead of the content of its target.
+    ///
+    /// Services that don't support this option will always follow symlinks.
+    pub fn with_follow_symlink(mut self, follow: bool) -> Self {
+        self.no_follow_symlink = !follow;
+        self
+    }
+
+    /// Get whether to follow symlink while read.
+    pub fn follow_symlink(&self) -> bool {
+        !self.no_follow_symlink
+    }
+
+    /// Decode the content by `Content-Encoding` of the response.
+    ///
+    /// If the response carries a recognized `Content-Encoding` like `gzip`,
+    /// the content will be decoded while reading, and the content encoding
+    /// as well as the content length will be cleared from the returning
+    /// metadata. Content with unrecognized encoding is returned as is.
+    ///
+    /// Range will be applied to the encoded content, so please only use
+    /// this option while reading the whole content.
+    ///
+    /// Requires the `content-decoding` feature.
+    #[cfg(feature = "content-decoding")]
+    pub fn with_content_encoding_decode(mut self) -> Self {
+        self.content_encoding_decode = true;
+        self
+    }
+
+    /// Get whether to decode the content by `Content-Encoding`.
+    pub fn content_encoding_decode(&self) -> bool {
+        self.content_encoding_decode
+    }
+
+    /// Set the snapshot to read instead of the current content.
+    pub fn with_snapshot(mut self, snapshot: &str) -> Self {
+        self.snapshot = Some(snapshot.to_string());
+        self
+    }
+
+    /// Get the snapshot to read.
+    pub fn snapshot(&self) -> Option<&str> {
+        self.snapshot.as_deref()
+    }
+
+    /// Set the generation to read instead of the live one.
+    pub fn with_generation(mut self, generation: i64) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    /// Get the generation to read.
+    pub fn generation(&self) -> Option<i64> {
+        self.generation
+    }
+
+    /// Check if this read carries nothing but a range, so that its content
+    /// can be served from any copy of the current object.
+    #[cfg(feature = "layers-moka-cache")]
+    pub(crate) fn is_range_only(&self) -> bool {
+        let OpRead {
+            br: _,
+            if_match,
+            if_none_match,
+            if_modified_since,
+            override_cache_control,
+            override_content_disposition,
+            no_follow_symlink,
+            content_encoding_decode,
+            snapshot,
+            generation,
+        } = self;
+
+        if_match.is_none()
+            && if_none_match.is_none()
+            && if_modified_since.is_none()
+            && override_cache_control.is_none()
+            && override_content_disposition.is_none()
+            && !no_follow_symlink
+            && !content_encoding_decode
+            && snapshot.is_none()
+            && generation.is_none()
+    }
 }
 
 /// Args for `stat` operation.
@@ -299,6 +737,10 @@ impl OpRead {
 pub struct OpStat {
     if_match: Option<String>,
     if_none_match: Option<String>,
+    if_modified_since: Option<DateTime<Utc>>,
+    no_follow_symlink: bool,
+    snapshot: Option<String>,
+    generation: Option<i64>,
 }
 
 impl OpStat {
@@ -328,6 +770,60 @@ impl OpStat {
     pub fn if_none_match(&self) -> Option<&str> {
         self.if_none_match.as_deref()
     }
+
+    /// Set the If-Modified-Since of the option
+    ///
+    /// [`ErrorKind::NotModified`](crate::ErrorKind::NotModified) will be
+    /// returned if the content has not been modified since the given time.
+    pub fn with_if_modified_since(mut self, if_modified_since: DateTime<Utc>) -> Self {
+        self.if_modified_since = Some(if_modified_since);
+        self
+    }
+
+    /// Get If-Modified-Since from option
+    pub fn if_modified_since(&self) -> Option<DateTime<Utc>> {
+        self.if_modified_since
+    }
+
+    /// Set whether to follow symlink while stat, default to `true`.
+    ///
+    /// If `false`, the symlink itself will be returned like `lstat` does,
+    /// and broken symlinks can be stat successfully. Check
+    /// [`Metadata::is_symlink`] and [`Metadata::symlink_target`] for
+    /// the link.
+    ///
+    /// Services that don't have symlinks will ignore this option.
+    pub fn with_follow_symlink(mut self, follow: bool) -> Self {
+        self.no_follow_symlink = !follow;
+        self
+    }
+
+    /// Get whether to follow symlink while stat.
+    pub fn follow_symlink(&self) -> bool {
+        !self.no_follow_symlink
+    }
+
+    /// Set the snapshot to stat instead of the current entry.
+    pub fn with_snapshot(mut self, snapshot: &str) -> Self {
+        self.snapshot = Some(snapshot.to_string());
+        self
+    }
+
+    /// Get the snapshot to stat.
+    pub fn snapshot(&self) -> Option<&str> {
+        self.snapshot.as_deref()
+    }
+
+    /// Set the generation to stat instead of the live one.
+    pub fn with_generation(mut self, generation: i64) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    /// Get the generation to stat.
+    pub fn generation(&self) -> Option<i64> {
+        self.generation
+    }
 }
 
 /// Args for `write` operation.
@@ -337,6 +833,21 @@ pub struct OpWrite {
     content_type: Option<String>,
     content_disposition: Option<String>,
     cache_control: Option<String>,
+    expires: Option<DateTime<Utc>>,
+    append: bool,
+    resume: Option<(String, u64)>,
+    if_none_match: Option<String>,
+    buffer_limit: Option<usize>,
+    storage_class: Option<String>,
+    if_generation_match: Option<i64>,
+    if_metageneration_match: Option<i64>,
+    unix_permissions: Option<u32>,
+    replication: Option<u16>,
+    block_size: Option<u64>,
+    acl: Option<String>,
+    durability: Option<Durability>,
+    object_lock_retention: Option<(String, DateTime<Utc>)>,
+    object_lock_legal_hold: Option<bool>,
 }
 
 impl OpWrite {
@@ -390,22 +901,369 @@ impl OpWrite {
         self.cache_control.as_deref()
     }
 
-    /// Set the content type of option
+    /// Set the cache control of option, like `max-age=3600`.
     pub fn with_cache_control(mut self, cache_control: &str) -> Self {
         self.cache_control = Some(cache_control.to_string());
         self
     }
+
+    /// Get the expires from option
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        self.expires
+    }
+
+    /// Set the expires of option, it will be returned as `Expires` header
+    /// while reading.
+    ///
+    /// Only services with `write_with_expires` capability support it,
+    /// others will return [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported).
+    pub fn with_expires(mut self, expires: DateTime<Utc>) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Get the append mode from option
+    pub fn append(&self) -> bool {
+        self.append
+    }
+
+    /// Set the append mode of option.
+    ///
+    /// In append mode, the existing content of the file will be kept and
+    /// all writes will be appended to the end of it. The file will be
+    /// created if it doesn't exist.
+    ///
+    /// # Notes
+    ///
+    /// - Content length means the length of data written by this writer
+    ///   instead of the final size of file. The file size is undefined
+    ///   while appending and can keep growing.
+    /// - Writes from concurrent appenders are interleaved at write
+    ///   boundaries as the underlying OS guarantees, but no order will be
+    ///   promised across writers.
+    /// - Services that don't support append will return
+    ///   [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported).
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Get the resumable session url and offset from option.
+    pub fn resume(&self) -> Option<(&str, u64)> {
+        self.resume
+            .as_ref()
+            .map(|(url, offset)| (url.as_str(), *offset))
+    }
+
+    /// Resume a previous resumable upload session from given offset.
+    ///
+    /// The writer will continue appending data from `offset` instead of
+    /// starting a new upload.
+    ///
+    /// # Notes
+    ///
+    /// - `offset` must be the committed bytes of this session, otherwise
+    ///   [`ErrorKind::ConditionNotMatch`](crate::ErrorKind::ConditionNotMatch)
+    ///   will be returned.
+    /// - Services that don't support resume will return
+    ///   [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported).
+    pub fn with_resume(mut self, session_url: &str, offset: u64) -> Self {
+        self.resume = Some((session_url.to_string(), offset));
+        self
+    }
+
+    /// Set the If-None-Match of the option.
+    ///
+    /// Set to `*` to write only if the path doesn't exist, or
+    /// [`ErrorKind::ConditionNotMatch`](crate::ErrorKind::ConditionNotMatch)
+    /// will be returned.
+    pub fn with_if_none_match(mut self, if_none_match: &str) -> Self {
+        self.if_none_match = Some(if_none_match.to_string());
+        self
+    }
+
+    /// Get If-None-Match from option
+    pub fn if_none_match(&self) -> Option<&str> {
+        self.if_none_match.as_deref()
+    }
+
+    /// Get the buffer limit from option.
+    pub fn buffer_limit(&self) -> Option<usize> {
+        self.buffer_limit
+    }
+
+    /// Set the max bytes that could be buffered in memory by the writer.
+    ///
+    /// Buffered bytes include both the bytes waiting to be uploaded and
+    /// the parts that are uploading concurrently. While the limit is
+    /// reached, writes will wait for in-flight parts to finish before
+    /// accepting more data, so that a fast producer can't exhaust the
+    /// memory.
+    ///
+    /// # Notes
+    ///
+    /// - A part is always allowed to be in flight, so the limit smaller
+    ///   than the part size makes parts uploaded one by one.
+    /// - Services that don't buffer data in memory will ignore this limit.
+    pub fn with_buffer_limit(mut self, limit: usize) -> Self {
+        self.buffer_limit = Some(limit);
+        self
+    }
+
+    /// Get the storage class from option.
+    pub fn storage_class(&self) -> Option<&str> {
+        self.storage_class.as_deref()
+    }
+
+    /// Set the storage class of option, like `STANDARD_IA` for s3 or
+    /// `Cool` for azblob.
+    ///
+    /// It overrides the default storage class configured in builder.
+    pub fn with_storage_class(mut self, storage_class: impl Into<String>) -> Self {
+        self.storage_class = Some(storage_class.into());
+        self
+    }
+
+    /// Get the generation that the path must match.
+    pub fn if_generation_match(&self) -> Option<i64> {
+        self.if_generation_match
+    }
+
+    /// Only write if the generation of the path matches the given one.
+    ///
+    /// Set to `0` to write only if the path doesn't exist.
+    pub fn with_if_generation_match(mut self, generation: i64) -> Self {
+        self.if_generation_match = Some(generation);
+        self
+    }
+
+    /// Get the metageneration that the path must match.
+    pub fn if_metageneration_match(&self) -> Option<i64> {
+        self.if_metageneration_match
+    }
+
+    /// Only write if the metageneration of the path matches the given one.
+    pub fn with_if_metageneration_match(mut self, metageneration: i64) -> Self {
+        self.if_metageneration_match = Some(metageneration);
+        self
+    }
+
+    /// Get the unix permissions from option.
+    pub fn unix_permissions(&self) -> Option<u32> {
+        self.unix_permissions
+    }
+
+    /// Set the unix permission bits like `0o755` of the written file.
+    ///
+    /// Permissions are set as is without being masked by umask. Services
+    /// without `write_with_unix_permissions` capability will ignore it.
+    pub fn with_unix_permissions(mut self, mode: u32) -> Self {
+        self.unix_permissions = Some(mode);
+        self
+    }
+
+    /// Get the replication from option.
+    pub fn replication(&self) -> Option<u16> {
+        self.replication
+    }
+
+    /// Set the replication factor of the written file.
+    ///
+    /// Services without `write_with_replication` capability will ignore it.
+    pub fn with_replication(mut self, replication: u16) -> Self {
+        self.replication = Some(replication);
+        self
+    }
+
+    /// Get the block size from option.
+    pub fn block_size(&self) -> Option<u64> {
+        self.block_size
+    }
+
+    /// Set the block size in bytes of the written file.
+    ///
+    /// Services without `write_with_block_size` capability will ignore it.
+    pub fn with_block_size(mut self, block_size: u64) -> Self {
+        self.block_size = Some(block_size);
+        self
+    }
+
+    /// Get the canned acl from option.
+    pub fn acl(&self) -> Option<&str> {
+        self.acl.as_deref()
+    }
+
+    /// Set the canned acl like `public-read` of the written object.
+    ///
+    /// Services with `write_with_acl` capability will validate it against
+    /// the canned acls they know and return [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported)
+    /// for unknown ones. Other services will ignore it.
+    pub fn with_acl(mut self, acl: impl Into<String>) -> Self {
+        self.acl = Some(acl.into());
+        self
+    }
+
+    /// Get the durability from option.
+    pub fn durability(&self) -> Option<Durability> {
+        self.durability
+    }
+
+    /// Set the durability of the written file.
+    ///
+    /// It overrides the default durability configured in builder. Services
+    /// without `write_with_durability` capability will ignore it.
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.durability = Some(durability);
+        self
+    }
+
+    /// Get the object lock retention mode and retain until date from option.
+    pub fn object_lock_retention(&self) -> Option<(&str, DateTime<Utc>)> {
+        self.object_lock_retention
+            .as_ref()
+            .map(|(mode, retain_until)| (mode.as_str(), *retain_until))
+    }
+
+    /// Set the object lock retention of the written object, like
+    /// `GOVERNANCE` or `COMPLIANCE` for s3. The object can't be overwritten
+    /// or deleted until `retain_until`.
+    ///
+    /// It overrides the default retention configured in builder. Services
+    /// without `write_with_object_lock` capability will return
+    /// [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported).
+    pub fn with_object_lock_retention(mut self, mode: &str, retain_until: DateTime<Utc>) -> Self {
+        self.object_lock_retention = Some((mode.to_string(), retain_until));
+        self
+    }
+
+    /// Get whether to place a legal hold on the written object.
+    pub fn object_lock_legal_hold(&self) -> Option<bool> {
+        self.object_lock_legal_hold
+    }
+
+    /// Set whether to place a legal hold on the written object.
+    ///
+    /// It overrides the default legal hold configured in builder. Services
+    /// without `write_with_object_lock` capability will return
+    /// [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported).
+    pub fn with_object_lock_legal_hold(mut self, legal_hold: bool) -> Self {
+        self.object_lock_legal_hold = Some(legal_hold);
+        self
+    }
+}
+
+/// Durability decides how written data is synced to disk before `close`
+/// returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    /// Don't sync at all, data could be lost after a power cut even if
+    /// `close` succeeded.
+    None,
+    /// Sync file data (`fdatasync`) in `close`.
+    Data,
+    /// Sync file data and metadata (`fsync`) in `close`, and the parent
+    /// dir after the temp file of atomic write is renamed. `flush` will
+    /// `fdatasync` the file too.
+    Full,
+}
+
+/// Directive of how the metadata or tags of copy target are set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyDirective {
+    /// Copy from the source.
+    Copy,
+    /// Replace with the ones given in the copy request.
+    Replace,
 }
 
 /// Args for `copy` operation.
 #[derive(Debug, Clone, Default)]
-pub struct OpCopy {}
+pub struct OpCopy {
+    if_match: Option<String>,
+    if_none_match: Option<String>,
+    metadata_directive: Option<CopyDirective>,
+    content_type: Option<String>,
+    tagging_directive: Option<CopyDirective>,
+    tagging: Option<String>,
+}
 
 impl OpCopy {
     /// Create a new `OpCopy`.
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Set the If-Match of the option, copy only happens if source's
+    /// etag matches.
+    pub fn with_if_match(mut self, if_match: &str) -> Self {
+        self.if_match = Some(if_match.to_string());
+        self
+    }
+
+    /// Get If-Match from option
+    pub fn if_match(&self) -> Option<&str> {
+        self.if_match.as_deref()
+    }
+
+    /// Set the If-None-Match of the option, copy only happens if source's
+    /// etag doesn't match.
+    pub fn with_if_none_match(mut self, if_none_match: &str) -> Self {
+        self.if_none_match = Some(if_none_match.to_string());
+        self
+    }
+
+    /// Get If-None-Match from option
+    pub fn if_none_match(&self) -> Option<&str> {
+        self.if_none_match.as_deref()
+    }
+
+    /// Set whether the metadata of target is copied from source or
+    /// replaced by the ones in this option.
+    pub fn with_metadata_directive(mut self, directive: CopyDirective) -> Self {
+        self.metadata_directive = Some(directive);
+        self
+    }
+
+    /// Get the metadata directive from option.
+    pub fn metadata_directive(&self) -> Option<CopyDirective> {
+        self.metadata_directive
+    }
+
+    /// Set the content type of target, only used with
+    /// [`CopyDirective::Replace`] metadata directive.
+    pub fn with_content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    /// Get the content type from option.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Set whether the tags of target is copied from source or replaced
+    /// by the ones in this option.
+    pub fn with_tagging_directive(mut self, directive: CopyDirective) -> Self {
+        self.tagging_directive = Some(directive);
+        self
+    }
+
+    /// Get the tagging directive from option.
+    pub fn tagging_directive(&self) -> Option<CopyDirective> {
+        self.tagging_directive
+    }
+
+    /// Set the tags of target in query string format like `k1=v1&k2=v2`,
+    /// only used with [`CopyDirective::Replace`] tagging directive.
+    pub fn with_tagging(mut self, tagging: &str) -> Self {
+        self.tagging = Some(tagging.to_string());
+        self
+    }
+
+    /// Get the tags from option.
+    pub fn tagging(&self) -> Option<&str> {
+        self.tagging.as_deref()
+    }
 }
 
 /// Args for `rename` operation.
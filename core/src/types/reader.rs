@@ -57,6 +57,7 @@ use crate::*;
 pub struct Reader {
     inner: oio::Reader,
     seek_state: SeekState,
+    content_range: Option<BytesContentRange>,
 }
 
 impl Reader {
@@ -68,13 +69,36 @@ impl Reader {
     /// We don't want to expose those details to users so keep this function
     /// in crate only.
     pub(crate) async fn create_dir(acc: FusedAccessor, path: &str, op: OpRead) -> Result<Self> {
-        let (_, r) = acc.read(path, op).await?;
+        let (rp, r) = acc.read(path, op.clone()).await?;
+        let (rp, r) = oio::decode_by_content_encoding(&op, rp, r);
 
         Ok(Reader {
             inner: r,
             seek_state: SeekState::Init,
+            content_range: content_range_of(&rp),
         })
     }
+
+    /// Get the content range actually returned by services.
+    ///
+    /// It's useful for suffix ranges like `..1024` to know the absolute
+    /// position of the content: `start` of the range is where the returned
+    /// content begins, and `size` is the size of the whole file.
+    ///
+    /// Returns `None` if services don't return content range, for example
+    /// while reading the whole file.
+    pub fn content_range(&self) -> Option<BytesContentRange> {
+        self.content_range
+    }
+
+    /// Compute checksum of the content while reading it.
+    ///
+    /// The checksum covers the content read from now on, for example the
+    /// range of a range reader. Refer to [`ChecksumReader`] for more
+    /// information.
+    pub fn with_checksum(self, algorithm: ChecksumAlgorithm) -> ChecksumReader {
+        ChecksumReader::new(self, algorithm)
+    }
 }
 
 impl oio::Read for Reader {
@@ -182,6 +206,7 @@ impl Stream for Reader {
 /// manner.
 pub struct BlockingReader {
     pub(crate) inner: oio::BlockingReader,
+    content_range: Option<BytesContentRange>,
 }
 
 impl BlockingReader {
@@ -195,9 +220,8 @@ impl BlockingReader {
     pub(crate) fn create(acc: FusedAccessor, path: &str, op: OpRead) -> Result<Self> {
         let acc_meta = acc.info();
 
-        let r = if acc_meta.capability().read_can_seek {
-            let (_, r) = acc.blocking_read(path, op)?;
-            r
+        let (rp, r) = if acc_meta.capability().read_can_seek {
+            acc.blocking_read(path, op)?
         } else {
             return Err(Error::new(
                 ErrorKind::Unsupported,
@@ -212,7 +236,17 @@ impl BlockingReader {
             Box::new(oio::into_streamable_reader(r, 256 * 1024))
         };
 
-        Ok(BlockingReader { inner: r })
+        Ok(BlockingReader {
+            inner: r,
+            content_range: content_range_of(&rp),
+        })
+    }
+
+    /// Get the content range actually returned by services.
+    ///
+    /// Refer to [`Reader::content_range`] for more information.
+    pub fn content_range(&self) -> Option<BytesContentRange> {
+        self.content_range
     }
 }
 
@@ -258,6 +292,15 @@ impl Iterator for BlockingReader {
     }
 }
 
+/// Get the content range from the reply of read if services returned it.
+fn content_range_of(rp: &RpRead) -> Option<BytesContentRange> {
+    if rp.metadata().bit().contains(Metakey::ContentRange) {
+        rp.metadata().content_range()
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::rngs::ThreadRng;
@@ -23,9 +23,11 @@ use std::task::Context;
 use std::task::Poll;
 
 use bytes::Bytes;
+use futures::future::poll_fn;
 use futures::future::BoxFuture;
 use futures::AsyncWrite;
 use futures::FutureExt;
+use log::warn;
 
 use crate::ops::OpWrite;
 use crate::raw::oio::Write;
@@ -40,6 +42,12 @@ use crate::*;
 /// Please make sure either `close` or `abort` has been called before
 /// dropping the writer otherwise the data could be lost.
 ///
+/// Dropping a writer that is neither closed nor aborted will log a
+/// warning with the path. Buffered data will be discarded and the
+/// started multipart upload (if any) will be left on the service, since
+/// drop can't wait for the abort request to finish. Please call `abort`
+/// explicitly to clean them up.
+///
 /// ## Notes
 ///
 /// Writer can be used in two ways:
@@ -54,8 +62,35 @@ use crate::*;
 /// Some services also supports `unsized` writer. They MAY buffer part of the data
 /// and flush them into storage at needs. And finally, the file will be available
 /// after `close` has been called.
+///
+/// ## Cancellation Safety
+///
+/// `write` and `close` are NOT cancellation safe: if their futures are
+/// dropped before ready, part of the data could have been sent to the
+/// service. Later `write` or `close` will return [`ErrorKind::Interrupted`]
+/// instead of producing a corrupted file, and users should call `abort`
+/// to clean up the upload.
+///
+/// `abort` is cancellation safe: it can be called again if its future has
+/// been dropped.
+///
+/// The `AsyncWrite` implementations keep the ongoing future inside the
+/// writer, so it's fine to stop polling them and resume later.
+///
+/// ## Flush
+///
+/// `poll_flush` of the `AsyncWrite` implementations asks the service to
+/// persist written data without closing the writer. Most services only
+/// persist data in `close`, so it's a no-op for them, while `fs` maps it
+/// to `fdatasync` for the strictest durability.
 pub struct Writer {
+    path: String,
     state: State,
+    /// Whether an async `write` or `close` has been started but not
+    /// finished, which means its future has been dropped halfway.
+    pending: bool,
+    /// Whether this writer has been closed or aborted.
+    finished: bool,
 }
 
 /// # Safety
@@ -75,20 +110,32 @@ impl Writer {
         let (_, w) = acc.write(path, op).await?;
 
         Ok(Writer {
+            path: path.to_string(),
             state: State::Idle(Some(w)),
+            pending: false,
+            finished: false,
         })
     }
 
     /// Write into inner writer.
+    ///
+    /// ## Notes
+    ///
+    /// If the future of previous `write` or `close` has been dropped
+    /// before it's ready, an [`ErrorKind::Interrupted`] error will be
+    /// returned since we don't know how much data has been written.
+    /// Please call `abort` to clean up the writer in this case.
     pub async fn write(&mut self, bs: impl Into<Bytes>) -> Result<()> {
+        self.check_idle("write")?;
+
+        self.pending = true;
         if let State::Idle(Some(w)) = &mut self.state {
-            w.write(bs.into()).await
-        } else {
-            unreachable!(
-                "writer state invalid while write, expect Idle, actual {}",
-                self.state
-            );
+            let res = w.write(bs.into()).await;
+            self.pending = false;
+            res?;
         }
+
+        Ok(())
     }
 
     /// Abort the writer and clean up all written data.
@@ -97,9 +144,20 @@ impl Writer {
     ///
     /// Abort should only be called when the writer is not closed or
     /// aborted, otherwise an unexpected error could be returned.
+    ///
+    /// Abort can be called after previous `write` or `close` has been
+    /// cancelled. The cancelled operation will be driven to finish first
+    /// if it's still ongoing.
     pub async fn abort(&mut self) -> Result<()> {
+        // Take back the inner writer if it has been moved into an ongoing
+        // future. The result doesn't matter since we will abort it.
+        let _ = poll_fn(|cx| self.poll_ongoing(cx)).await;
+
         if let State::Idle(Some(w)) = &mut self.state {
-            w.abort().await
+            w.abort().await?;
+            self.pending = false;
+            self.finished = true;
+            Ok(())
         } else {
             unreachable!(
                 "writer state invalid while abort, expect Idle, actual {}",
@@ -114,40 +172,84 @@ impl Writer {
     ///
     /// Close should only be called when the writer is not closed or
     /// aborted, otherwise an unexpected error could be returned.
+    ///
+    /// Like `write`, an [`ErrorKind::Interrupted`] error will be returned
+    /// if previous `write` or `close` has been cancelled.
     pub async fn close(&mut self) -> Result<()> {
+        self.check_idle("close")?;
+
+        self.pending = true;
         if let State::Idle(Some(w)) = &mut self.state {
-            w.close().await
-        } else {
-            unreachable!(
-                "writer state invalid while close, expect Idle, actual {}",
-                self.state
-            );
+            let res = w.close().await;
+            self.pending = false;
+            res?;
         }
+        self.finished = true;
+
+        Ok(())
     }
-}
 
-enum State {
-    Idle(Option<oio::Writer>),
-    Write(BoxFuture<'static, Result<(usize, oio::Writer)>>),
-    Close(BoxFuture<'static, Result<oio::Writer>>),
-}
+    /// Returns the session url and committed bytes of this write, which
+    /// can be persisted and passed to [`OpWrite::with_resume`] to resume
+    /// it later, even in another process.
+    ///
+    /// ## Notes
+    ///
+    /// - Only services with `write_can_resume` return it, and only after
+    ///   the upload session has been initiated.
+    /// - `None` will be returned while an `AsyncWrite` operation is ongoing.
+    /// - Anyone holding the session url can upload to this session, please
+    ///   keep it as a secret.
+    pub fn resume(&self) -> Option<(&str, u64)> {
+        match &self.state {
+            State::Idle(Some(w)) => w.resume(),
+            _ => None,
+        }
+    }
 
-impl Display for State {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            State::Idle(_) => write!(f, "Idle"),
-            State::Write(_) => write!(f, "Write"),
-            State::Close(_) => write!(f, "Close"),
+    /// Check that the writer is idle and no operation has been cancelled.
+    fn check_idle(&self, operation: &'static str) -> Result<()> {
+        match &self.state {
+            State::Idle(Some(_)) if !self.pending => Ok(()),
+            _ => Err(self.interrupted_error(operation)),
         }
     }
-}
 
-impl AsyncWrite for Writer {
-    fn poll_write(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &[u8],
-    ) -> Poll<io::Result<usize>> {
+    fn interrupted_error(&self, operation: &'static str) -> Error {
+        Error::new(
+            ErrorKind::Interrupted,
+            "previous operation of writer has been cancelled, please abort it",
+        )
+        .with_operation(operation)
+        .with_context("path", &self.path)
+    }
+
+    /// Drive the ongoing future created by `poll_write` or `poll_close` to
+    /// finish and put the inner writer back.
+    fn poll_ongoing(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let res = match &mut self.state {
+            State::Idle(_) => return Poll::Ready(Ok(())),
+            State::Write(fut) => {
+                let (w, res) = ready!(fut.poll_unpin(cx));
+                self.state = State::Idle(Some(w));
+                res.map(|_| ())
+            }
+            State::Close(fut) | State::Flush(fut) => {
+                let (w, res) = ready!(fut.poll_unpin(cx));
+                self.state = State::Idle(Some(w));
+                res
+            }
+        };
+
+        Poll::Ready(res)
+    }
+
+    fn poll_write_inner(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if self.pending {
+            let err = self.interrupted_error("write");
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+        }
+
         loop {
             match &mut self.state {
                 State::Idle(w) => {
@@ -157,31 +259,34 @@ impl AsyncWrite for Writer {
                     let bs = Bytes::from(buf.to_vec());
                     let size = bs.len();
                     let fut = async move {
-                        w.write(bs).await?;
-                        Ok((size, w))
+                        let res = w.write(bs).await.map(|_| size);
+                        (w, res)
                     };
                     self.state = State::Write(Box::pin(fut));
                 }
-                State::Write(fut) => match ready!(fut.poll_unpin(cx)) {
-                    Ok((size, w)) => {
-                        self.state = State::Idle(Some(w));
-                        return Poll::Ready(Ok(size));
-                    }
-                    Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
-                },
+                State::Write(fut) => {
+                    let (w, res) = ready!(fut.poll_unpin(cx));
+                    self.state = State::Idle(Some(w));
+                    return Poll::Ready(
+                        res.map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+                    );
+                }
                 State::Close(_) => {
                     unreachable!("invalid state of writer: poll_write with State::Close")
                 }
+                State::Flush(_) => {
+                    unreachable!("invalid state of writer: poll_write with State::Flush")
+                }
             };
         }
     }
 
-    /// Writer makes sure that every write is flushed.
-    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Poll::Ready(Ok(()))
-    }
+    fn poll_flush_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.pending {
+            let err = self.interrupted_error("flush");
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+        }
 
-    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         loop {
             match &mut self.state {
                 State::Idle(w) => {
@@ -189,65 +294,34 @@ impl AsyncWrite for Writer {
                         .take()
                         .expect("invalid state of writer: Idle state with empty write");
                     let fut = async move {
-                        w.close().await?;
-                        Ok(w)
+                        let res = w.flush().await;
+                        (w, res)
                     };
-                    self.state = State::Close(Box::pin(fut));
+                    self.state = State::Flush(Box::pin(fut));
                 }
                 State::Write(_) => {
-                    unreachable!("invalid state of writer: poll_close with State::Write")
-                }
-                State::Close(fut) => match ready!(fut.poll_unpin(cx)) {
-                    Ok(w) => {
-                        self.state = State::Idle(Some(w));
-                        return Poll::Ready(Ok(()));
-                    }
-                    Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
-                },
-            }
-        }
-    }
-}
-
-impl tokio::io::AsyncWrite for Writer {
-    fn poll_write(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &[u8],
-    ) -> Poll<io::Result<usize>> {
-        loop {
-            match &mut self.state {
-                State::Idle(w) => {
-                    let mut w = w
-                        .take()
-                        .expect("invalid state of writer: Idle state with empty write");
-                    let bs = Bytes::from(buf.to_vec());
-                    let size = bs.len();
-                    let fut = async move {
-                        w.write(bs).await?;
-                        Ok((size, w))
-                    };
-                    self.state = State::Write(Box::pin(fut));
+                    unreachable!("invalid state of writer: poll_flush with State::Write")
                 }
-                State::Write(fut) => match ready!(fut.poll_unpin(cx)) {
-                    Ok((size, w)) => {
-                        self.state = State::Idle(Some(w));
-                        return Poll::Ready(Ok(size));
-                    }
-                    Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
-                },
                 State::Close(_) => {
-                    unreachable!("invalid state of writer: poll_write with State::Close")
+                    unreachable!("invalid state of writer: poll_flush with State::Close")
                 }
-            };
+                State::Flush(fut) => {
+                    let (w, res) = ready!(fut.poll_unpin(cx));
+                    self.state = State::Idle(Some(w));
+                    return Poll::Ready(
+                        res.map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+                    );
+                }
+            }
         }
     }
 
-    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Poll::Ready(Ok(()))
-    }
+    fn poll_close_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.pending {
+            let err = self.interrupted_error("close");
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+        }
 
-    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         loop {
             match &mut self.state {
                 State::Idle(w) => {
@@ -255,30 +329,115 @@ impl tokio::io::AsyncWrite for Writer {
                         .take()
                         .expect("invalid state of writer: Idle state with empty write");
                     let fut = async move {
-                        w.close().await?;
-                        Ok(w)
+                        let res = w.close().await;
+                        (w, res)
                     };
                     self.state = State::Close(Box::pin(fut));
                 }
                 State::Write(_) => {
                     unreachable!("invalid state of writer: poll_close with State::Write")
                 }
-                State::Close(fut) => match ready!(fut.poll_unpin(cx)) {
-                    Ok(w) => {
-                        self.state = State::Idle(Some(w));
-                        return Poll::Ready(Ok(()));
+                State::Flush(_) => {
+                    unreachable!("invalid state of writer: poll_close with State::Flush")
+                }
+                State::Close(fut) => {
+                    let (w, res) = ready!(fut.poll_unpin(cx));
+                    self.state = State::Idle(Some(w));
+                    if res.is_ok() {
+                        self.finished = true;
                     }
-                    Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
-                },
+                    return Poll::Ready(
+                        res.map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+                    );
+                }
             }
         }
     }
 }
 
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if !self.finished {
+            warn!(
+                "writer of path {} is dropped without close or abort, written data could be lost",
+                self.path
+            );
+        }
+    }
+}
+
+/// State of writer.
+///
+/// Futures always hand the inner writer back together with the result, so
+/// that the writer can still be aborted if the operation failed.
+enum State {
+    Idle(Option<oio::Writer>),
+    Write(BoxFuture<'static, (oio::Writer, Result<usize>)>),
+    Close(BoxFuture<'static, (oio::Writer, Result<()>)>),
+    Flush(BoxFuture<'static, (oio::Writer, Result<()>)>),
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::Idle(_) => write!(f, "Idle"),
+            State::Write(_) => write!(f, "Write"),
+            State::Close(_) => write!(f, "Close"),
+            State::Flush(_) => write!(f, "Flush"),
+        }
+    }
+}
+
+impl AsyncWrite for Writer {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_inner(cx, buf)
+    }
+
+    /// Writer makes sure that every write is flushed, and flush asks the
+    /// service to persist them.
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush_inner(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_close_inner(cx)
+    }
+}
+
+impl tokio::io::AsyncWrite for Writer {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_inner(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush_inner(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_close_inner(cx)
+    }
+}
+
 /// BlockingWriter is designed to write data into given path in an blocking
 /// manner.
+///
+/// ## Notes
+///
+/// Please make sure `close` has been called before dropping the writer,
+/// otherwise the data could be lost and a warning will be logged.
 pub struct BlockingWriter {
+    path: String,
     pub(crate) inner: oio::BlockingWriter,
+    /// Whether this writer has been closed.
+    finished: bool,
 }
 
 impl BlockingWriter {
@@ -292,7 +451,11 @@ impl BlockingWriter {
     pub(crate) fn create(acc: FusedAccessor, path: &str, op: OpWrite) -> Result<Self> {
         let (_, w) = acc.blocking_write(path, op)?;
 
-        Ok(BlockingWriter { inner: w })
+        Ok(BlockingWriter {
+            path: path.to_string(),
+            inner: w,
+            finished: false,
+        })
     }
 
     /// Write into inner writer.
@@ -302,7 +465,20 @@ impl BlockingWriter {
 
     /// Close the writer and make sure all data have been stored.
     pub fn close(&mut self) -> Result<()> {
-        self.inner.close()
+        self.inner.close()?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for BlockingWriter {
+    fn drop(&mut self) {
+        if !self.finished {
+            warn!(
+                "blocking writer of path {} is dropped without close, written data could be lost",
+                self.path
+            );
+        }
     }
 }
 
@@ -319,3 +495,104 @@ impl io::Write for BlockingWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use futures::task::noop_waker_ref;
+    use futures::Future;
+
+    use super::*;
+
+    /// MockWriter takes a while to write and records whether it has been
+    /// aborted.
+    struct MockWriter {
+        aborted: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Write for MockWriter {
+        async fn write(&mut self, _: Bytes) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> Result<()> {
+            self.aborted.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn resume(&self) -> Option<(&str, u64)> {
+            Some(("https://example.com/session", 0))
+        }
+    }
+
+    fn new_writer(aborted: Arc<AtomicBool>) -> Writer {
+        Writer {
+            path: "test".to_string(),
+            state: State::Idle(Some(Box::new(MockWriter { aborted }))),
+            pending: false,
+            finished: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writer_cancelled_write() {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let mut w = new_writer(aborted.clone());
+
+        {
+            let mut fut = Box::pin(w.write("hello"));
+            let mut cx = Context::from_waker(noop_waker_ref());
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+        }
+
+        let err = w.write("world").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+        let err = w.close().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        w.abort().await.expect("abort must succeed");
+        assert!(aborted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_writer_cancelled_poll_write() {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let mut w = new_writer(aborted.clone());
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(Pin::new(&mut w).poll_write(&mut cx, b"hello").is_pending());
+
+        // The ongoing write is kept inside writer, so it can still be
+        // aborted after users stop polling it.
+        let err = w.write("world").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        w.abort().await.expect("abort must succeed");
+        assert!(aborted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_writer_resume() {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let mut w = new_writer(aborted);
+        assert_eq!(w.resume(), Some(("https://example.com/session", 0)));
+
+        // The inner writer has been moved into the ongoing write.
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(Pin::new(&mut w).poll_write(&mut cx, b"hello").is_pending());
+        assert_eq!(w.resume(), None);
+
+        w.abort().await.expect("abort must succeed");
+    }
+}
@@ -79,6 +79,7 @@ macro_rules! behavior_blocking_write_tests {
                 test_read_full,
                 test_read_range,
                 test_read_large_range,
+                test_read_over_range,
                 test_read_not_exist,
                 test_fuzz_range_reader,
                 test_fuzz_offset_reader,
@@ -287,6 +288,23 @@ pub fn test_read_large_range(op: BlockingOperator) -> Result<()> {
     Ok(())
 }
 
+/// Read range that starts beyond the end should return RangeNotSatisfied.
+pub fn test_read_over_range(op: BlockingOperator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.write(&path, content).expect("write must succeed");
+
+    let offset = size as u64 + 1;
+    let res = op.range_read(&path, offset..offset + 100);
+    assert!(res.is_err());
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::RangeNotSatisfied);
+
+    op.delete(&path).expect("delete must succeed");
+    Ok(())
+}
+
 /// Read not exist file should return NotFound
 pub fn test_read_not_exist(op: BlockingOperator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
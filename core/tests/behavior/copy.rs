@@ -16,6 +16,9 @@
 // under the License.
 
 use anyhow::Result;
+use opendal::ops::CopyDirective;
+use opendal::ops::OpCopy;
+use opendal::ops::OpWrite;
 use opendal::ErrorKind;
 use opendal::Operator;
 
@@ -59,122 +62,65 @@ macro_rules! behavior_copy_tests {
         $(
             behavior_copy_test!(
                 $service,
-
-                test_copy,
-                test_copy_non_existing_source,
-                test_copy_source_dir,
-                test_copy_target_dir,
-                test_copy_self,
-                test_copy_nested,
-                test_copy_overwrite,
+                test_copy_with_special_chars,
+                test_copy_with_metadata_directive,
+                test_copy_with_if_match,
 
             );
         )*
     };
 }
 
-/// Copy a file and test with stat.
-pub async fn test_copy(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
+/// Copy a file whose path contains special and non-ASCII chars.
+pub async fn test_copy_with_special_chars(op: Operator) -> Result<()> {
+    let dir = uuid::Uuid::new_v4().to_string();
+    let source_path = format!("{dir}/a b/c+d#e/ünïcödé.txt");
     let (source_content, _) = gen_bytes();
 
     op.write(&source_path, source_content.clone()).await?;
 
-    let target_path = uuid::Uuid::new_v4().to_string();
+    let target_path = format!("{dir}/a b/c+d#e/ünïcödé-copy.txt");
 
     op.copy(&source_path, &target_path).await?;
 
     let target_content = op.read(&target_path).await.expect("read must succeed");
     assert_eq!(target_content, source_content);
 
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy a nonexistent source should return an error.
-pub async fn test_copy_non_existing_source(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let target_path = uuid::Uuid::new_v4().to_string();
-
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::NotFound);
+    op.remove_all(&format!("{dir}/")).await?;
     Ok(())
 }
 
-/// Copy a dir as source should return an error.
-pub async fn test_copy_source_dir(op: Operator) -> Result<()> {
-    let source_path = format!("{}/", uuid::Uuid::new_v4());
-    let target_path = uuid::Uuid::new_v4().to_string();
-
-    op.create_dir(&source_path).await?;
+/// Copy with replace metadata directive should change target's metadata.
+pub async fn test_copy_with_metadata_directive(op: Operator) -> Result<()> {
+    if !op.info().capability().copy_with_metadata_directive {
+        return Ok(());
+    }
 
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsADirectory);
-    Ok(())
-}
-
-/// Copy to a dir should return an error.
-pub async fn test_copy_target_dir(op: Operator) -> Result<()> {
     let source_path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&source_path, content).await?;
-
-    let target_path = format!("{}/", uuid::Uuid::new_v4());
+    let (source_content, size) = gen_bytes();
 
-    op.create_dir(&target_path).await?;
+    op.write_with(
+        &source_path,
+        OpWrite::new()
+            .with_content_length(size as u64)
+            .with_content_type("application/octet-stream"),
+        source_content.clone(),
+    )
+    .await?;
 
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsADirectory);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy a file to self should return an error.
-pub async fn test_copy_self(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&source_path, content).await?;
-
-    let err = op
-        .copy(&source_path, &source_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsSameFile);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy to a nested path, parent path should be created successfully.
-pub async fn test_copy_nested(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (source_content, _) = gen_bytes();
-
-    op.write(&source_path, source_content.clone()).await?;
-
-    let target_path = format!(
-        "{}/{}/{}",
-        uuid::Uuid::new_v4(),
-        uuid::Uuid::new_v4(),
-        uuid::Uuid::new_v4()
-    );
-
-    op.copy(&source_path, &target_path).await?;
+    let target_path = uuid::Uuid::new_v4().to_string();
 
+    op.copy_with(
+        &source_path,
+        &target_path,
+        OpCopy::new()
+            .with_metadata_directive(CopyDirective::Replace)
+            .with_content_type("text/plain"),
+    )
+    .await?;
+
+    let meta = op.stat(&target_path).await?;
+    assert_eq!(meta.content_type(), Some("text/plain"));
     let target_content = op.read(&target_path).await.expect("read must succeed");
     assert_eq!(target_content, source_content);
 
@@ -183,25 +129,29 @@ pub async fn test_copy_nested(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Copy to a exist path should overwrite successfully.
-pub async fn test_copy_overwrite(op: Operator) -> Result<()> {
+/// Copy with mismatched if match should fail.
+pub async fn test_copy_with_if_match(op: Operator) -> Result<()> {
+    if !op.info().capability().copy_with_if_match {
+        return Ok(());
+    }
+
     let source_path = uuid::Uuid::new_v4().to_string();
     let (source_content, _) = gen_bytes();
 
-    op.write(&source_path, source_content.clone()).await?;
+    op.write(&source_path, source_content).await?;
 
     let target_path = uuid::Uuid::new_v4().to_string();
-    let (target_content, _) = gen_bytes();
-    assert_ne!(source_content, target_content);
 
-    op.write(&target_path, target_content).await?;
-
-    op.copy(&source_path, &target_path).await?;
-
-    let target_content = op.read(&target_path).await.expect("read must succeed");
-    assert_eq!(target_content, source_content);
+    let err = op
+        .copy_with(
+            &source_path,
+            &target_path,
+            OpCopy::new().with_if_match("\"invalid_etag\""),
+        )
+        .await
+        .expect_err("copy must fail");
+    assert_eq!(err.kind(), ErrorKind::ConditionNotMatch);
 
     op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
     Ok(())
 }
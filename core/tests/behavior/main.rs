@@ -36,8 +36,6 @@ mod presign;
 #[macro_use]
 mod read_only;
 #[macro_use]
-mod rename;
-#[macro_use]
 mod write;
 
 mod utils;
@@ -73,8 +71,6 @@ macro_rules! behavior_tests {
                     behavior_copy_tests!($service);
                     // can read && can_write && can_blocking && can_copy
                     behavior_blocking_copy_tests!($service);
-                    // can_read && can_write && can_move
-                    behavior_rename_tests!($service);
                     // can_read && can_write && can_blocking && can_move
                     behavior_blocking_rename_tests!($service);
                     // can_read && can_write && can_list
@@ -85,6 +81,15 @@ macro_rules! behavior_tests {
                     behavior_blocking_list_tests!($service);
                     // can_list && !can_write
                     behavior_list_only_tests!($service);
+
+                    // Cases shared with custom services, see `opendal::behavior`.
+                    #[test]
+                    fn behavior_run() -> anyhow::Result<()> {
+                        if let Some(op) = OPERATOR.as_ref() {
+                            RUNTIME.block_on(opendal::behavior::run(op.clone()))?;
+                        }
+                        Ok(())
+                    }
                 }
          )*
         }
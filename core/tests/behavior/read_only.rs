@@ -64,17 +64,14 @@ macro_rules! behavior_read_tests {
                 test_stat,
                 test_stat_special_chars,
                 test_stat_not_cleaned_path,
-                test_stat_not_exist,
                 test_stat_with_if_match,
                 test_stat_with_if_none_match,
-                test_stat_root,
                 test_read_full,
                 test_read_full_with_special_chars,
                 test_read_range,
                 test_reader_range,
                 test_reader_from,
                 test_reader_tail,
-                test_read_not_exist,
                 test_read_with_dir_path,
                 test_read_with_if_match,
                 test_read_with_if_none_match,
@@ -116,17 +113,6 @@ pub async fn test_stat_not_cleaned_path(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Stat not exist file should return NotFound
-pub async fn test_stat_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let meta = op.stat(&path).await;
-    assert!(meta.is_err());
-    assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
 /// Stat with if_match should succeed, else get a ConditionNotMatch error.
 pub async fn test_stat_with_if_match(op: Operator) -> Result<()> {
     if !op.info().capability().stat_with_if_match {
@@ -155,7 +141,7 @@ pub async fn test_stat_with_if_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Stat with if_none_match should succeed, else get a ConditionNotMatch.
+/// Stat with if_none_match should succeed, else get a NotModified error.
 pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
     if !op.info().capability().stat_with_if_none_match {
         return Ok(());
@@ -172,7 +158,7 @@ pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
 
     let res = op.stat_with(path, op_stat).await;
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err().kind(), ErrorKind::ConditionNotMatch);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotModified);
 
     let mut op_stat = OpStat::default();
     op_stat = op_stat.with_if_none_match("invalid_etag");
@@ -184,17 +170,6 @@ pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Root should be able to stat and returns DIR.
-pub async fn test_stat_root(op: Operator) -> Result<()> {
-    let meta = op.stat("").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    let meta = op.stat("/").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    Ok(())
-}
-
 /// Read full content should match.
 pub async fn test_read_full(op: Operator) -> Result<()> {
     let bs = op.read("normal_file").await?;
@@ -285,17 +260,6 @@ pub async fn test_reader_tail(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Read not exist file should return NotFound
-pub async fn test_read_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let bs = op.read(&path).await;
-    assert!(bs.is_err());
-    assert_eq!(bs.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
 /// Read with dir path should return an error.
 pub async fn test_read_with_dir_path(op: Operator) -> Result<()> {
     let path = format!("{}/", uuid::Uuid::new_v4());
@@ -341,7 +305,7 @@ pub async fn test_read_with_if_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Read with if_none_match should match, else get a ConditionNotMatch error.
+/// Read with if_none_match should match, else get a NotModified error.
 pub async fn test_read_with_if_none_match(op: Operator) -> Result<()> {
     if !op.info().capability().read_with_if_none_match {
         return Ok(());
@@ -356,7 +320,7 @@ pub async fn test_read_with_if_none_match(op: Operator) -> Result<()> {
 
     let res = op.read_with(path, op_read).await;
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err().kind(), ErrorKind::ConditionNotMatch);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotModified);
 
     let mut op_read = OpRead::default();
     op_read = op_read.with_if_none_match("invalid_etag");
@@ -19,6 +19,8 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::Result;
+use chrono::TimeZone;
+use chrono::Utc;
 use futures::AsyncReadExt;
 use futures::AsyncSeekExt;
 use futures::StreamExt;
@@ -26,6 +28,7 @@ use http::StatusCode;
 use log::debug;
 use log::warn;
 use opendal::ops::OpRead;
+use opendal::ops::OpSetOwner;
 use opendal::ops::OpStat;
 use opendal::ops::OpWrite;
 use opendal::EntryMode;
@@ -72,103 +75,69 @@ macro_rules! behavior_write_tests {
         $(
             behavior_write_test!(
                 $service,
-
-                test_create_dir,
-                test_create_dir_existing,
-                test_write,
-                test_write_with_dir_path,
                 test_write_with_special_chars,
                 test_write_with_cache_control,
+                test_write_with_expires,
+                test_write_with_if_generation_match,
                 test_write_with_content_type,
-                test_stat,
-                test_stat_dir,
+                test_write_with_unix_permissions,
+                test_write_with_replication,
+                test_write_with_append,
+                test_writer_with_append,
+                test_write_with_append_unsupported,
+                test_truncate,
+                test_truncate_unsupported,
+                test_set_permissions,
+                test_set_owner_unsupported,
+                test_write_if_not_exists,
                 test_stat_with_special_chars,
                 test_stat_not_cleaned_path,
-                test_stat_not_exist,
                 test_stat_with_if_match,
                 test_stat_with_if_none_match,
-                test_stat_root,
-                test_read_full,
-                test_read_range,
                 test_read_large_range,
+                test_read_over_range,
+                test_read_suffix_range,
                 test_reader_range,
                 test_reader_from,
                 test_reader_tail,
-                test_read_not_exist,
                 test_read_with_if_match,
                 test_read_with_if_none_match,
                 test_fuzz_range_reader,
                 test_fuzz_offset_reader,
                 test_fuzz_part_reader,
-                test_read_with_dir_path,
                 test_read_with_special_chars,
                 test_read_with_override_cache_control,
                 test_read_with_override_content_disposition,
-                test_delete,
-                test_delete_empty_dir,
                 test_delete_with_special_chars,
-                test_delete_not_existing,
                 test_delete_stream,
-                test_writer_write,
+                test_delete_iter,
+                test_writer_with_buffer_limit,
                 test_writer_abort,
                 test_writer_futures_copy,
                 test_fuzz_unsized_writer,
+                test_transfer,
+                test_transfer_same_file,
             );
         )*
     };
 }
 
-/// Create dir with dir path should succeed.
-pub async fn test_create_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await?;
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Create dir on existing dir should succeed.
-pub async fn test_create_dir_existing(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await?;
-
-    op.create_dir(&path).await?;
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Write a single file and test with stat.
-pub async fn test_write(op: Operator) -> Result<()> {
+/// Write if not exists should only write the first time.
+pub async fn test_write_if_not_exists(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
     let (content, size) = gen_bytes();
 
-    op.write(&path, content).await?;
+    assert!(op.write_if_not_exists(&path, content.clone()).await?);
 
     let meta = op.stat(&path).await.expect("stat must succeed");
     assert_eq!(meta.content_length(), size as u64);
 
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Write file with dir path should return an error
-pub async fn test_write_with_dir_path(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-    let (content, _) = gen_bytes();
+    assert!(!op.write_if_not_exists(&path, "overwrite").await?);
 
-    let result = op.write(&path, content).await;
-    assert!(result.is_err());
-    assert_eq!(result.unwrap_err().kind(), ErrorKind::IsADirectory);
+    let bs = op.read(&path).await?;
+    assert_eq!(bs, content, "existing content must not be overwritten");
 
+    op.delete(&path).await.expect("delete must succeed");
     Ok(())
 }
 
@@ -220,60 +189,340 @@ pub async fn test_write_with_cache_control(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Write a single file with content type should succeed.
-pub async fn test_write_with_content_type(op: Operator) -> Result<()> {
-    if !op.info().capability().write_with_content_type {
+/// Write a single file with expires should succeed.
+pub async fn test_write_with_expires(op: Operator) -> Result<()> {
+    if !op.info().capability().write_with_expires {
         return Ok(());
     }
 
     let path = uuid::Uuid::new_v4().to_string();
-    let (content, size) = gen_bytes();
+    let (content, _) = gen_bytes();
 
-    let target_content_type = "application/json";
+    // Expires only keeps the precision of seconds.
+    let target_expires = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
 
-    let mut op_write = OpWrite::default();
-    op_write = op_write.with_content_type(target_content_type);
+    op.write_with(
+        &path,
+        OpWrite::default().with_expires(target_expires),
+        content,
+    )
+    .await?;
 
-    op.write_with(&path, op_write, content).await?;
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.mode(), EntryMode::FILE);
+    assert_eq!(meta.expires(), Some(target_expires));
+
+    op.delete(&path).await.expect("delete must succeed");
+
+    Ok(())
+}
+
+/// Write a single file with unix permissions should keep them.
+pub async fn test_write_with_unix_permissions(op: Operator) -> Result<()> {
+    if !op.info().capability().write_with_unix_permissions {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+
+    op.write_with(
+        &path,
+        OpWrite::default().with_unix_permissions(0o755),
+        content.clone(),
+    )
+    .await?;
 
     let meta = op.stat(&path).await.expect("stat must succeed");
     assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(
-        meta.content_type().expect("content type must exist"),
-        target_content_type
-    );
-    assert_eq!(meta.content_length(), size as u64);
+    assert_eq!(meta.unix_permissions(), Some(0o755));
+    // Services track owners either by id or by name.
+    assert!(meta.uid().is_some() || meta.owner().is_some());
+    assert!(meta.gid().is_some() || meta.group().is_some());
+
+    // Overwriting should apply the new permissions.
+    op.write_with(
+        &path,
+        OpWrite::default().with_unix_permissions(0o700),
+        content,
+    )
+    .await?;
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.unix_permissions(), Some(0o700));
+
+    op.delete(&path).await.expect("delete must succeed");
+
+    Ok(())
+}
+
+/// Write a single file with replication and block size should keep them.
+pub async fn test_write_with_replication(op: Operator) -> Result<()> {
+    let cap = op.info().capability();
+    if !cap.write_with_replication || !cap.write_with_block_size {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+
+    op.write_with(
+        &path,
+        OpWrite::default()
+            .with_replication(1)
+            .with_block_size(1024 * 1024),
+        content,
+    )
+    .await?;
+
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.replication(), Some(1));
+    assert_eq!(meta.block_size(), Some(1024 * 1024));
+
+    op.delete(&path).await.expect("delete must succeed");
+
+    Ok(())
+}
+
+/// Write with if generation match should only overwrite the expected generation.
+pub async fn test_write_with_if_generation_match(op: Operator) -> Result<()> {
+    let cap = op.info().capability();
+    if !cap.write_with_if_generation_match || !cap.read_with_generation {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+    let (new_content, _) = gen_bytes();
+
+    // Generation `0` means the path must not exist.
+    op.write_with(
+        &path,
+        OpWrite::default().with_if_generation_match(0),
+        content.clone(),
+    )
+    .await?;
+    let err = op
+        .write_with(
+            &path,
+            OpWrite::default().with_if_generation_match(0),
+            new_content.clone(),
+        )
+        .await
+        .expect_err("write must fail");
+    assert_eq!(err.kind(), ErrorKind::ConditionNotMatch);
+
+    let generation = op
+        .stat(&path)
+        .await?
+        .generation()
+        .expect("generation must be set");
+    op.write_with(
+        &path,
+        OpWrite::default().with_if_generation_match(generation),
+        new_content.clone(),
+    )
+    .await?;
+
+    // The old generation is still readable if object versioning is enabled.
+    match op
+        .read_with(&path, OpRead::new().with_generation(generation))
+        .await
+    {
+        Ok(bs) => assert_eq!(bs, content),
+        Err(err) => assert_eq!(err.kind(), ErrorKind::NotFound),
+    }
+    assert_eq!(op.read(&path).await?, new_content);
+
+    op.delete(&path).await.expect("delete must succeed");
+    Ok(())
+}
+
+/// Write with append should keep the existing content.
+pub async fn test_write_with_append(op: Operator) -> Result<()> {
+    if !op.info().capability().write_with_append {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content_one, size_one) = gen_bytes();
+    let (content_two, size_two) = gen_bytes();
+
+    op.write(&path, content_one.clone()).await?;
+    op.write_with(&path, OpWrite::new().with_append(true), content_two.clone())
+        .await?;
+
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.content_length(), (size_one + size_two) as u64);
+
+    let bs = op.read(&path).await?;
+    assert_eq!(bs[..size_one], content_one);
+    assert_eq!(bs[size_one..], content_two);
 
     op.delete(&path).await.expect("delete must succeed");
 
     Ok(())
 }
 
-/// Stat existing file should return metadata
-pub async fn test_stat(op: Operator) -> Result<()> {
+/// Writer with append should create the file and append every write.
+pub async fn test_writer_with_append(op: Operator) -> Result<()> {
+    if !op.info().capability().write_with_append {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content_one, size_one) = gen_bytes();
+    let (content_two, _) = gen_bytes();
+
+    for content in [content_one.clone(), content_two.clone()] {
+        let mut w = op
+            .writer_with(&path, OpWrite::new().with_append(true))
+            .await?;
+        w.write(content).await?;
+        w.close().await?;
+    }
+
+    let bs = op.read(&path).await?;
+    assert_eq!(bs[..size_one], content_one);
+    assert_eq!(bs[size_one..], content_two);
+
+    op.delete(&path).await.expect("delete must succeed");
+
+    Ok(())
+}
+
+/// Write with append on services that don't support it should fail.
+pub async fn test_write_with_append_unsupported(op: Operator) -> Result<()> {
+    if op.info().capability().write_with_append {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+
+    let err = op
+        .write_with(&path, OpWrite::new().with_append(true), content)
+        .await
+        .expect_err("write with append must fail");
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    Ok(())
+}
+
+/// Truncate should discard content after size, and the file can be
+/// appended right after truncate.
+pub async fn test_truncate(op: Operator) -> Result<()> {
+    if !op.info().capability().truncate {
+        return Ok(());
+    }
+
     let path = uuid::Uuid::new_v4().to_string();
     let (content, size) = gen_bytes();
+    let (content_append, _) = gen_bytes();
+    let (offset, _) = gen_offset_length(size);
 
-    op.write(&path, content).await.expect("write must succeed");
+    op.write(&path, content.clone()).await?;
+    op.truncate(&path, offset).await?;
 
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), size as u64);
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.content_length(), offset);
+    let bs = op.read(&path).await?;
+    assert_eq!(bs, content[..offset as usize]);
+
+    if op.info().capability().write_with_append {
+        op.write_with(
+            &path,
+            OpWrite::new().with_append(true),
+            content_append.clone(),
+        )
+        .await?;
+
+        let bs = op.read(&path).await?;
+        assert_eq!(bs[..offset as usize], content[..offset as usize]);
+        assert_eq!(bs[offset as usize..], content_append);
+    }
 
     op.delete(&path).await.expect("delete must succeed");
+
     Ok(())
 }
 
-/// Stat existing file should return metadata
-pub async fn test_stat_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
+/// Set permissions should be visible via stat.
+pub async fn test_set_permissions(op: Operator) -> Result<()> {
+    if !op.info().capability().set_permissions {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+
+    op.write(&path, content).await?;
+    op.set_permissions(&path, 0o640).await?;
 
-    op.create_dir(&path).await.expect("write must succeed");
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.unix_permissions(), Some(0o640));
 
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
+    op.delete(&path).await.expect("delete must succeed");
+
+    Ok(())
+}
+
+/// Set owner on services that don't support it should fail.
+pub async fn test_set_owner_unsupported(op: Operator) -> Result<()> {
+    if op.info().capability().set_owner {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+
+    let err = op
+        .set_owner(&path, OpSetOwner::new().with_group("nobody"))
+        .await
+        .expect_err("set owner must fail");
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    Ok(())
+}
+
+/// Truncate on services that don't support it should fail.
+pub async fn test_truncate_unsupported(op: Operator) -> Result<()> {
+    if op.info().capability().truncate {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+
+    let err = op.truncate(&path, 0).await.expect_err("truncate must fail");
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    Ok(())
+}
+
+/// Write a single file with content type should succeed.
+pub async fn test_write_with_content_type(op: Operator) -> Result<()> {
+    if !op.info().capability().write_with_content_type {
+        return Ok(());
+    }
+
+    let path = uuid::Uuid::new_v4().to_string();
+    let (content, size) = gen_bytes();
+
+    let target_content_type = "application/json";
+
+    let mut op_write = OpWrite::default();
+    op_write = op_write.with_content_type(target_content_type);
+
+    op.write_with(&path, op_write, content).await?;
+
+    let meta = op.stat(&path).await.expect("stat must succeed");
+    assert_eq!(meta.mode(), EntryMode::FILE);
+    assert_eq!(
+        meta.content_type().expect("content type must exist"),
+        target_content_type
+    );
+    assert_eq!(meta.content_length(), size as u64);
 
     op.delete(&path).await.expect("delete must succeed");
+
     Ok(())
 }
 
@@ -314,17 +563,6 @@ pub async fn test_stat_not_cleaned_path(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Stat not exist file should return NotFound
-pub async fn test_stat_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let meta = op.stat(&path).await;
-    assert!(meta.is_err());
-    assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
 /// Stat with if_match should succeed, else get a ConditionNotMatch error.
 pub async fn test_stat_with_if_match(op: Operator) -> Result<()> {
     if !op.info().capability().stat_with_if_match {
@@ -360,7 +598,7 @@ pub async fn test_stat_with_if_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Stat with if_none_match should succeed, else get a ConditionNotMatch.
+/// Stat with if_none_match should succeed, else get a NotModified error.
 pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
     if !op.info().capability().stat_with_if_none_match {
         return Ok(());
@@ -383,7 +621,7 @@ pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
 
     let res = op.stat_with(&path, op_stat).await;
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err().kind(), ErrorKind::ConditionNotMatch);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotModified);
 
     let mut op_stat = OpStat::default();
     op_stat = op_stat.with_if_none_match("\"invalid_etag\"");
@@ -396,41 +634,39 @@ pub async fn test_stat_with_if_none_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Root should be able to stat and returns DIR.
-pub async fn test_stat_root(op: Operator) -> Result<()> {
-    let meta = op.stat("").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    let meta = op.stat("/").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    Ok(())
-}
+/// Read large range content should match.
+pub async fn test_read_large_range(op: Operator) -> Result<()> {
+    if !op.info().capability().read_with_range {
+        return Ok(());
+    }
 
-/// Read full content should match.
-pub async fn test_read_full(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
     debug!("Generate a random file: {}", &path);
     let (content, size) = gen_bytes();
+    let (offset, _) = gen_offset_length(size);
 
     op.write(&path, content.clone())
         .await
         .expect("write must succeed");
 
-    let bs = op.read(&path).await?;
-    assert_eq!(size, bs.len(), "read size");
+    let bs = op.range_read(&path, offset..u32::MAX as u64).await?;
+    assert_eq!(
+        bs.len() as u64,
+        size as u64 - offset,
+        "read size with large range"
+    );
     assert_eq!(
         format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content)),
-        "read content"
+        format!("{:x}", Sha256::digest(&content[offset as usize..])),
+        "read content with large range"
     );
 
     op.delete(&path).await.expect("delete must succeed");
     Ok(())
 }
 
-/// Read range content should match.
-pub async fn test_read_range(op: Operator) -> Result<()> {
+/// Read range that starts beyond the end should return RangeNotSatisfied.
+pub async fn test_read_over_range(op: Operator) -> Result<()> {
     if !op.info().capability().read_with_range {
         return Ok(());
     }
@@ -438,29 +674,20 @@ pub async fn test_read_range(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
     debug!("Generate a random file: {}", &path);
     let (content, size) = gen_bytes();
-    let (offset, length) = gen_offset_length(size);
 
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
+    op.write(&path, content).await.expect("write must succeed");
 
-    let bs = op.range_read(&path, offset..offset + length).await?;
-    assert_eq!(bs.len() as u64, length, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!(
-            "{:x}",
-            Sha256::digest(&content[offset as usize..(offset + length) as usize])
-        ),
-        "read content"
-    );
+    let offset = size as u64 + 1;
+    let res = op.range_read(&path, offset..offset + 100).await;
+    assert!(res.is_err());
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::RangeNotSatisfied);
 
     op.delete(&path).await.expect("delete must succeed");
     Ok(())
 }
 
-/// Read large range content should match.
-pub async fn test_read_large_range(op: Operator) -> Result<()> {
+/// Read suffix range larger than the content should return the whole content.
+pub async fn test_read_suffix_range(op: Operator) -> Result<()> {
     if !op.info().capability().read_with_range {
         return Ok(());
     }
@@ -468,22 +695,33 @@ pub async fn test_read_large_range(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
     debug!("Generate a random file: {}", &path);
     let (content, size) = gen_bytes();
-    let (offset, _) = gen_offset_length(size);
 
     op.write(&path, content.clone())
         .await
         .expect("write must succeed");
 
-    let bs = op.range_read(&path, offset..u32::MAX as u64).await?;
-    assert_eq!(
-        bs.len() as u64,
-        size as u64 - offset,
-        "read size with large range"
-    );
+    let mut r = match op
+        .reader_with(&path, OpRead::new().with_suffix(size as u64 + 100))
+        .await
+    {
+        Ok(r) => r,
+        // Not all services support range with tail range, let's tolerate this.
+        Err(err) if err.kind() == ErrorKind::Unsupported => {
+            warn!("service doesn't support range with tail");
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if let Some(range) = r.content_range() {
+        assert_eq!(range.range(), Some(0..size as u64), "content range");
+    }
+    let mut bs = Vec::new();
+    r.read_to_end(&mut bs).await?;
+    assert_eq!(bs.len(), size, "read size with large suffix range");
     assert_eq!(
         format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content[offset as usize..])),
-        "read content with large range"
+        format!("{:x}", Sha256::digest(&content)),
+        "read content with large suffix range"
     );
 
     op.delete(&path).await.expect("delete must succeed");
@@ -579,6 +817,16 @@ pub async fn test_reader_tail(op: Operator) -> Result<()> {
         Err(err) => return Err(err.into()),
     };
 
+    // Not all services return content range, but it must be correct if returned.
+    if let Some(range) = r.content_range() {
+        assert_eq!(
+            range.range(),
+            Some(size as u64 - length..size as u64),
+            "content range"
+        );
+        assert_eq!(range.size(), Some(size as u64), "content range size");
+    }
+
     let mut bs = Vec::new();
     r.read_to_end(&mut bs).await?;
 
@@ -593,17 +841,6 @@ pub async fn test_reader_tail(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Read not exist file should return NotFound
-pub async fn test_read_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let bs = op.read(&path).await;
-    assert!(bs.is_err());
-    assert_eq!(bs.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
 /// Read with if_match should match, else get a ConditionNotMatch error.
 pub async fn test_read_with_if_match(op: Operator) -> Result<()> {
     if !op.info().capability().read_with_if_match {
@@ -640,7 +877,7 @@ pub async fn test_read_with_if_match(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Read with if_none_match should match, else get a ConditionNotMatch error.
+/// Read with if_none_match should match, else get a NotModified error.
 pub async fn test_read_with_if_none_match(op: Operator) -> Result<()> {
     if !op.info().capability().read_with_if_none_match {
         return Ok(());
@@ -661,7 +898,7 @@ pub async fn test_read_with_if_none_match(op: Operator) -> Result<()> {
 
     let res = op.read_with(&path, op_read).await;
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err().kind(), ErrorKind::ConditionNotMatch);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotModified);
 
     let mut op_read = OpRead::default();
     op_read = op_read.with_if_none_match("\"invalid_etag\"");
@@ -801,20 +1038,6 @@ pub async fn test_fuzz_part_reader(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Read with dir path should return an error.
-pub async fn test_read_with_dir_path(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await.expect("write must succeed");
-
-    let result = op.read(&path).await;
-    assert!(result.is_err());
-    assert_eq!(result.unwrap_err().kind(), ErrorKind::IsADirectory);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
 /// Read file with special chars should succeed.
 pub async fn test_read_with_special_chars(op: Operator) -> Result<()> {
     // Ignore test for supabase until https://github.com/apache/incubator-opendal/issues/2194 addressed.
@@ -974,32 +1197,6 @@ pub async fn test_writer_abort(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Delete existing file should succeed.
-pub async fn test_delete(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    op.delete(&path).await?;
-
-    // Stat it again to check.
-    assert!(!op.is_exist(&path).await?);
-
-    Ok(())
-}
-
-/// Delete empty dir should succeed.
-pub async fn test_delete_empty_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await.expect("create must succeed");
-
-    op.delete(&path).await?;
-
-    Ok(())
-}
-
 /// Delete file with special chars should succeed.
 pub async fn test_delete_with_special_chars(op: Operator) -> Result<()> {
     // Ignore test for supabase until https://github.com/apache/incubator-opendal/issues/2194 addressed.
@@ -1022,15 +1219,6 @@ pub async fn test_delete_with_special_chars(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Delete not existing file should also succeed.
-pub async fn test_delete_not_existing(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    op.delete(&path).await?;
-
-    Ok(())
-}
-
 /// Delete via stream.
 pub async fn test_delete_stream(op: Operator) -> Result<()> {
     let dir = uuid::Uuid::new_v4().to_string();
@@ -1058,39 +1246,60 @@ pub async fn test_delete_stream(op: Operator) -> Result<()> {
     Ok(())
 }
 
-/// Append data into writer
-pub async fn test_writer_write(op: Operator) -> Result<()> {
+/// Delete via iterator should return results of every path.
+pub async fn test_delete_iter(op: Operator) -> Result<()> {
+    let dir = uuid::Uuid::new_v4().to_string();
+    op.create_dir(&format!("{dir}/"))
+        .await
+        .expect("creat must succeed");
+
+    let expected: Vec<_> = (0..100).map(|v| format!("{dir}/{v}")).collect();
+    for path in expected.iter() {
+        op.write(path, "delete_iter").await?;
+    }
+
+    // Deleting not existing file should also succeed.
+    let mut input = expected.clone();
+    input.push(format!("{dir}/not_existing"));
+
+    let results = op.with_limit(30).delete_iter(input.clone()).await;
+    assert_eq!(
+        results.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>(),
+        input,
+        "results must be in the same order as input"
+    );
+    for (path, result) in results {
+        assert!(result.is_ok(), "{path} should be deleted: {result:?}");
+    }
+
+    for path in expected.iter() {
+        assert!(!op.is_exist(path).await?, "{path} should be removed")
+    }
+
+    op.delete(&format!("{dir}/")).await?;
+    Ok(())
+}
+
+/// Writer with buffer limit should still write all the data.
+pub async fn test_writer_with_buffer_limit(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
     let size = 5 * 1024 * 1024; // write file with 5 MiB
     let content_a = gen_fixed_bytes(size);
     let content_b = gen_fixed_bytes(size);
 
-    let mut w = match op.writer(&path).await {
-        Ok(w) => w,
-        Err(err) if err.kind() == ErrorKind::Unsupported => {
-            warn!("service doesn't support write with append");
-            return Ok(());
-        }
-        Err(err) => return Err(err.into()),
-    };
+    let mut w = op
+        .writer_with(&path, OpWrite::new().with_buffer_limit(size))
+        .await?;
     w.write(content_a.clone()).await?;
     w.write(content_b.clone()).await?;
     w.close().await?;
 
-    let meta = op.stat(&path).await.expect("stat must succeed");
-    assert_eq!(meta.content_length(), (size * 2) as u64);
-
     let bs = op.read(&path).await?;
     assert_eq!(bs.len(), size * 2, "read size");
     assert_eq!(
-        format!("{:x}", Sha256::digest(&bs[..size])),
-        format!("{:x}", Sha256::digest(content_a)),
-        "read content a"
-    );
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs[size..])),
-        format!("{:x}", Sha256::digest(content_b)),
-        "read content b"
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest([content_a, content_b].concat())),
+        "read content"
     );
 
     op.delete(&path).await.expect("delete must succeed");
@@ -1156,3 +1365,47 @@ pub async fn test_fuzz_unsized_writer(op: Operator) -> Result<()> {
     op.delete(&path).await.expect("delete must succeed");
     Ok(())
 }
+
+/// Transfer a file between operators should succeed.
+pub async fn test_transfer(op: Operator) -> Result<()> {
+    let source_path = uuid::Uuid::new_v4().to_string();
+    let target_path = uuid::Uuid::new_v4().to_string();
+    let (content, size) = gen_bytes();
+
+    let mut args = OpWrite::new();
+    if op.info().capability().write_with_content_type {
+        args = args.with_content_type("application/json");
+    }
+    op.write_with(&source_path, args, content.clone()).await?;
+
+    op.transfer(&source_path, &op, &target_path).await?;
+
+    let meta = op.stat(&target_path).await.expect("stat must succeed");
+    assert_eq!(meta.content_length(), size as u64);
+    if op.info().capability().write_with_content_type {
+        assert_eq!(meta.content_type(), Some("application/json"));
+    }
+
+    let bs = op.read(&target_path).await?;
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.delete(&source_path).await.expect("delete must succeed");
+    op.delete(&target_path).await.expect("delete must succeed");
+    Ok(())
+}
+
+/// Transfer a file to itself should return an error.
+pub async fn test_transfer_same_file(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+
+    let err = op
+        .transfer(&path, &op, &path)
+        .await
+        .expect_err("transfer must fail");
+    assert_eq!(err.kind(), ErrorKind::IsSameFile);
+    Ok(())
+}